@@ -0,0 +1,703 @@
+//! Atlassian Document Format (ADF), the JSON documents REST API v3 uses for
+//! descriptions and comments: rendered to plain text for display, and built
+//! from the plain text and markdown people type.
+
+use crate::people;
+use serde_json::{Value, json};
+
+/// Render Atlassian Document Format (ADF) into readable text while preserving links.
+pub fn extract_plain_text_from_adf(adf: &Value) -> String {
+    let mut result = String::new();
+    render_adf_node(adf, &mut result);
+    result
+}
+
+fn render_adf_node(node: &Value, result: &mut String) {
+    let Some(node_type) = node.get("type").and_then(|t| t.as_str()) else {
+        if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
+            for child in content {
+                render_adf_node(child, result);
+            }
+        }
+        return;
+    };
+
+    match node_type {
+        "doc" => render_adf_blocks(node, result),
+        "paragraph" | "heading" | "blockquote" | "codeBlock" => {
+            let block = render_adf_inline_content(node);
+            append_block(result, &block);
+        }
+        "bulletList" => render_adf_list(node, result, false),
+        "orderedList" => render_adf_list(node, result, true),
+        "listItem" => result.push_str(render_adf_list_item(node).trim_end()),
+        "rule" => append_block(result, "---"),
+        "hardBreak" => result.push('\n'),
+        "inlineCard" | "blockCard" | "embedCard" => {
+            if let Some(url) = node
+                .get("attrs")
+                .and_then(|attrs| attrs.get("url"))
+                .and_then(|url| url.as_str())
+            {
+                result.push_str(url);
+            }
+        }
+        "mention" => {
+            if let Some(text) = node
+                .get("attrs")
+                .and_then(|attrs| attrs.get("text"))
+                .and_then(|text| text.as_str())
+            {
+                result.push_str(text);
+            } else if let Some(name) = node["attrs"]["id"].as_str().and_then(people::display_name) {
+                result.push('@');
+                result.push_str(&name);
+            }
+        }
+        "emoji" => {
+            if let Some(text) = node
+                .get("attrs")
+                .and_then(|attrs| attrs.get("text"))
+                .and_then(|text| text.as_str())
+                .or_else(|| {
+                    node.get("attrs")
+                        .and_then(|attrs| attrs.get("shortName"))
+                        .and_then(|text| text.as_str())
+                })
+            {
+                result.push_str(text);
+            }
+        }
+        "status" => {
+            if let Some(text) = node
+                .get("attrs")
+                .and_then(|attrs| attrs.get("text"))
+                .and_then(|text| text.as_str())
+            {
+                result.push_str(text);
+            }
+        }
+        "date" => {
+            if let Some(timestamp) = node
+                .get("attrs")
+                .and_then(|attrs| attrs.get("timestamp"))
+                .and_then(|timestamp| timestamp.as_str())
+            {
+                result.push_str(timestamp);
+            }
+        }
+        "text" => {
+            let text = node
+                .get("text")
+                .and_then(|t| t.as_str())
+                .unwrap_or_default();
+            result.push_str(text);
+
+            if let Some(href) = node
+                .get("marks")
+                .and_then(|marks| marks.as_array())
+                .and_then(|marks| extract_link_href(marks))
+            {
+                let trimmed_text = text.trim();
+                if !href.is_empty() && trimmed_text != href && !trimmed_text.contains(href) {
+                    result.push_str(" (");
+                    result.push_str(href);
+                    result.push(')');
+                }
+            }
+        }
+        _ => {
+            if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
+                for child in content {
+                    render_adf_node(child, result);
+                }
+            }
+        }
+    }
+}
+
+fn render_adf_blocks(node: &Value, result: &mut String) {
+    if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
+        for child in content {
+            render_adf_node(child, result);
+        }
+    }
+}
+
+pub fn render_adf_inline_content(node: &Value) -> String {
+    let mut block = String::new();
+    if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
+        for child in content {
+            render_adf_node(child, &mut block);
+        }
+    }
+    block
+}
+
+fn render_adf_list(node: &Value, result: &mut String, ordered: bool) {
+    if let Some(items) = node.get("content").and_then(|c| c.as_array()) {
+        for (index, item) in items.iter().enumerate() {
+            let rendered_item = render_adf_list_item(item);
+            if rendered_item.is_empty() {
+                continue;
+            }
+
+            let prefix = if ordered {
+                format!("{}. ", index + 1)
+            } else {
+                "- ".to_string()
+            };
+
+            let mut lines = rendered_item.lines();
+            if let Some(first_line) = lines.next() {
+                append_block(result, &format!("{prefix}{first_line}"));
+            }
+
+            for line in lines {
+                append_block(result, line);
+            }
+        }
+    }
+}
+
+fn render_adf_list_item(node: &Value) -> String {
+    let mut item = String::new();
+    if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
+        for child in content {
+            match child.get("type").and_then(|t| t.as_str()) {
+                Some("paragraph") | Some("heading") | Some("blockquote") | Some("codeBlock") => {
+                    let block = render_adf_inline_content(child);
+                    if !block.trim().is_empty() {
+                        if !item.is_empty() && !item.ends_with('\n') {
+                            item.push('\n');
+                        }
+                        item.push_str(block.trim_end());
+                    }
+                }
+                Some("bulletList") | Some("orderedList") => {
+                    let mut nested = String::new();
+                    render_adf_node(child, &mut nested);
+                    if !nested.trim().is_empty() {
+                        if !item.is_empty() && !item.ends_with('\n') {
+                            item.push('\n');
+                        }
+                        item.push_str(nested.trim_end());
+                    }
+                }
+                _ => render_adf_node(child, &mut item),
+            }
+        }
+    }
+    item.trim().to_string()
+}
+
+fn append_block(result: &mut String, block: &str) {
+    let block = block.trim_end();
+    if block.is_empty() {
+        return;
+    }
+
+    if !result.is_empty() && !result.ends_with('\n') {
+        result.push('\n');
+    }
+    result.push_str(block);
+    result.push('\n');
+}
+
+fn extract_link_href<'a>(marks: &'a [Value]) -> Option<&'a str> {
+    marks.iter().find_map(|mark| {
+        (mark.get("type").and_then(|t| t.as_str()) == Some("link"))
+            .then(|| {
+                mark.get("attrs")
+                    .and_then(|attrs| attrs.get("href"))
+                    .and_then(|href| href.as_str())
+            })
+            .flatten()
+    })
+}
+
+pub fn adf_value_to_display_text(value: &Value) -> String {
+    // REST API v2 returns descriptions and comments as plain text.
+    if let Value::String(text) = value {
+        return text.trim().to_string();
+    }
+    let plain_text = extract_plain_text_from_adf(value);
+    if plain_text.trim().is_empty() {
+        serde_json::to_string_pretty(value)
+            .unwrap_or_else(|_| "Cannot display content.".to_string())
+    } else {
+        plain_text.trim().to_string()
+    }
+}
+
+/// Renders ADF as GitHub-flavored Markdown, keeping headings, lists and
+/// task lists, code blocks, quotes, tables, and inline formatting and links.
+pub fn adf_to_markdown(value: &Value) -> String {
+    if let Value::String(text) = value {
+        return text.trim().to_string();
+    }
+    markdown_blocks(value).join("\n\n")
+}
+
+fn adf_children(node: &Value) -> &[Value] {
+    node["content"].as_array().map_or(&[], Vec::as_slice)
+}
+
+fn markdown_blocks(node: &Value) -> Vec<String> {
+    adf_children(node)
+        .iter()
+        .filter_map(markdown_block)
+        .filter(|block| !block.trim().is_empty())
+        .collect()
+}
+
+fn markdown_block(node: &Value) -> Option<String> {
+    Some(match node["type"].as_str()? {
+        "paragraph" => markdown_inline(node),
+        "heading" => {
+            let level = node["attrs"]["level"].as_u64().unwrap_or(1).clamp(1, 6) as usize;
+            format!("{} {}", "#".repeat(level), markdown_inline(node))
+        }
+        "bulletList" => markdown_list(node, |_| "- ".to_string()),
+        "orderedList" => {
+            let start = node["attrs"]["order"].as_u64().unwrap_or(1) as usize;
+            markdown_list(node, |index| format!("{}. ", start + index))
+        }
+        "taskList" => adf_children(node)
+            .iter()
+            .filter_map(|item| match item["type"].as_str() {
+                Some("taskItem") => {
+                    let mark = if item["attrs"]["state"] == "DONE" {
+                        "x"
+                    } else {
+                        " "
+                    };
+                    Some(format!("- [{}] {}", mark, markdown_inline(item)))
+                }
+                // Nested task lists sit beside the items they belong to.
+                _ => markdown_block(item).map(|block| indent_markdown(&block, "  ")),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        "codeBlock" => {
+            let code: String = adf_children(node)
+                .iter()
+                .filter_map(|child| child["text"].as_str())
+                .collect();
+            let language = node["attrs"]["language"].as_str().unwrap_or_default();
+            format!("```{}\n{}\n```", language, code.trim_end_matches('\n'))
+        }
+        "blockquote" | "panel" => markdown_blocks(node)
+            .join("\n\n")
+            .lines()
+            .map(|line| format!("> {}", line).trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        "rule" => "---".to_string(),
+        "table" => markdown_table(node),
+        "mediaSingle" | "mediaGroup" => return None,
+        _ if node["content"].is_array() => markdown_blocks(node).join("\n\n"),
+        _ => markdown_inline_node(node),
+    })
+}
+
+fn markdown_list(node: &Value, marker: impl Fn(usize) -> String) -> String {
+    adf_children(node)
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let marker = marker(index);
+            let body = markdown_blocks(item).join("\n");
+            let body = indent_markdown(&body, &" ".repeat(marker.len()));
+            format!("{}{}", marker, body.trim_start())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `text` with every line but blank ones prefixed by `indent`.
+fn indent_markdown(text: &str, indent: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", indent, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn markdown_table(node: &Value) -> String {
+    let rows: Vec<Vec<String>> = adf_children(node)
+        .iter()
+        .map(|row| {
+            adf_children(row)
+                .iter()
+                .map(|cell| {
+                    markdown_blocks(cell)
+                        .join("<br>")
+                        .replace('|', "\\|")
+                        .replace('\n', "<br>")
+                })
+                .collect()
+        })
+        .collect();
+    let Some(header) = rows.first() else {
+        return String::new();
+    };
+    let mut lines = vec![
+        format!("| {} |", header.join(" | ")),
+        format!("|{}|", vec![" --- "; header.len()].join("|")),
+    ];
+    lines.extend(
+        rows[1..]
+            .iter()
+            .map(|row| format!("| {} |", row.join(" | "))),
+    );
+    lines.join("\n")
+}
+
+fn markdown_inline(node: &Value) -> String {
+    adf_children(node)
+        .iter()
+        .map(markdown_inline_node)
+        .collect()
+}
+
+fn markdown_inline_node(node: &Value) -> String {
+    let attr = |name: &str| node["attrs"][name].as_str().unwrap_or_default().to_string();
+    match node["type"].as_str().unwrap_or_default() {
+        "text" => markdown_text(
+            node["text"].as_str().unwrap_or_default(),
+            node["marks"].as_array().map_or(&[], Vec::as_slice),
+        ),
+        "hardBreak" => "\\\n".to_string(),
+        "mention" => match node["attrs"]["text"].as_str() {
+            Some(text) => text.to_string(),
+            None => node["attrs"]["id"]
+                .as_str()
+                .and_then(people::display_name)
+                .map(|name| format!("@{}", name))
+                .unwrap_or_default(),
+        },
+        "emoji" => node["attrs"]["text"]
+            .as_str()
+            .map_or_else(|| attr("shortName"), str::to_string),
+        "inlineCard" | "blockCard" | "embedCard" => attr("url"),
+        "status" => attr("text"),
+        "date" => attr("timestamp"),
+        _ => markdown_inline(node),
+    }
+}
+
+/// `text` wrapped in the Markdown for its marks, with surrounding whitespace
+/// kept outside so `**bold **` does not break the emphasis.
+fn markdown_text(text: &str, marks: &[Value]) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() || marks.is_empty() {
+        return text.to_string();
+    }
+    let has = |kind: &str| marks.iter().any(|mark| mark["type"] == kind);
+    let mut marked = trimmed.to_string();
+    if has("code") {
+        marked = format!("`{}`", marked);
+    }
+    if has("em") {
+        marked = format!("_{}_", marked);
+    }
+    if has("strong") {
+        marked = format!("**{}**", marked);
+    }
+    if has("strike") {
+        marked = format!("~~{}~~", marked);
+    }
+    if let Some(href) = extract_link_href(marks) {
+        marked = format!("[{}]({})", marked, href);
+    }
+    let start = text.len() - text.trim_start().len();
+    format!(
+        "{}{}{}",
+        &text[..start],
+        marked,
+        &text[start + trimmed.len()..]
+    )
+}
+
+pub fn text_to_adf(text: &str) -> Value {
+    let content: Vec<Value> = text
+        .split("\n\n")
+        .filter_map(|paragraph| {
+            let nodes: Vec<Value> = paragraph
+                .lines()
+                .enumerate()
+                .flat_map(|(index, line)| {
+                    let mut items = Vec::new();
+                    if index > 0 {
+                        items.push(json!({ "type": "hardBreak" }));
+                    }
+                    if !line.is_empty() {
+                        items.push(json!({
+                            "type": "text",
+                            "text": line,
+                        }));
+                    }
+                    items
+                })
+                .collect();
+
+            if nodes.is_empty() {
+                None
+            } else {
+                Some(json!({
+                    "type": "paragraph",
+                    "content": nodes,
+                }))
+            }
+        })
+        .collect();
+
+    json!({
+        "type": "doc",
+        "version": 1,
+        "content": content,
+    })
+}
+
+/// Converts comment text into ADF, keeping the markdown block structure people
+/// type into comments: fenced code blocks, `#` headings, and bullet or numbered
+/// lists. Everything else becomes paragraphs as in [`text_to_adf`].
+pub fn markdown_to_adf(text: &str) -> Value {
+    let mut content: Vec<Value> = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let flush = |content: &mut Vec<Value>, paragraph: &mut Vec<&str>| {
+        if let Some(nodes) = text_to_adf(&paragraph.join("\n"))["content"].as_array() {
+            content.extend(nodes.iter().cloned());
+        }
+        paragraph.clear();
+    };
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        if let Some(language) = trimmed.strip_prefix("```") {
+            flush(&mut content, &mut paragraph);
+            let code: Vec<&str> = lines
+                .by_ref()
+                .take_while(|line| !line.trim_start().starts_with("```"))
+                .collect();
+            let mut block = json!({ "type": "codeBlock", "content": [] });
+            let code = code.join("\n");
+            if !code.is_empty() {
+                block["content"] = json!([{ "type": "text", "text": code }]);
+            }
+            if !language.trim().is_empty() {
+                block["attrs"] = json!({ "language": language.trim() });
+            }
+            content.push(block);
+        } else if let Some((level, heading)) = markdown_heading(trimmed) {
+            flush(&mut content, &mut paragraph);
+            content.push(json!({
+                "type": "heading",
+                "attrs": { "level": level },
+                "content": [{ "type": "text", "text": heading }],
+            }));
+        } else if let Some((ordered, first)) = markdown_list_item(trimmed) {
+            flush(&mut content, &mut paragraph);
+            let mut items = vec![first];
+            while let Some(&next) = lines.peek() {
+                match markdown_list_item(next.trim_start()) {
+                    Some((next_ordered, item)) if next_ordered == ordered => items.push(item),
+                    _ => break,
+                }
+                lines.next();
+            }
+            let items: Vec<Value> = items
+                .iter()
+                .map(|item| {
+                    json!({
+                        "type": "listItem",
+                        "content": [text_to_adf(item)["content"][0].clone()],
+                    })
+                })
+                .collect();
+            let list_type = if ordered { "orderedList" } else { "bulletList" };
+            content.push(json!({ "type": list_type, "content": items }));
+        } else if trimmed.is_empty() {
+            flush(&mut content, &mut paragraph);
+        } else {
+            paragraph.push(line);
+        }
+    }
+    flush(&mut content, &mut paragraph);
+
+    json!({
+        "type": "doc",
+        "version": 1,
+        "content": content,
+    })
+}
+
+fn markdown_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let heading = line[level..].strip_prefix(' ')?.trim();
+    ((1..=6).contains(&level) && !heading.is_empty()).then_some((level, heading))
+}
+
+/// Returns whether a list item is numbered, and its text.
+fn markdown_list_item(line: &str) -> Option<(bool, &str)> {
+    let (ordered, item) =
+        if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            (false, item)
+        } else {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            let rest = &line[digits..];
+            let item = rest
+                .strip_prefix(". ")
+                .or_else(|| rest.strip_prefix(") "))
+                .filter(|_| digits > 0)?;
+            (true, item)
+        };
+    let item = item.trim();
+    (!item.is_empty()).then_some((ordered, item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adf_value_to_display_text_preserves_inline_links() {
+        let value = json!({
+            "type": "doc",
+            "version": 1,
+            "content": [{
+                "type": "paragraph",
+                "content": [
+                    {
+                        "type": "text",
+                        "text": "See docs",
+                        "marks": [{
+                            "type": "link",
+                            "attrs": { "href": "https://example.com/docs" }
+                        }]
+                    }
+                ]
+            }]
+        });
+
+        assert_eq!(
+            adf_value_to_display_text(&value),
+            "See docs (https://example.com/docs)"
+        );
+    }
+
+    #[test]
+    fn adf_value_to_display_text_preserves_smart_links() {
+        let value = json!({
+            "type": "doc",
+            "version": 1,
+            "content": [{
+                "type": "paragraph",
+                "content": [
+                    { "type": "text", "text": "Runbook: " },
+                    {
+                        "type": "inlineCard",
+                        "attrs": { "url": "https://example.com/runbook" }
+                    }
+                ]
+            }]
+        });
+
+        assert_eq!(
+            adf_value_to_display_text(&value),
+            "Runbook: https://example.com/runbook"
+        );
+    }
+
+    #[test]
+    fn adf_to_markdown_keeps_structure_and_inline_marks() {
+        let value = json!({
+            "type": "doc",
+            "version": 1,
+            "content": [
+                {"type": "heading", "attrs": {"level": 2}, "content": [{"type": "text", "text": "Steps"}]},
+                {"type": "paragraph", "content": [
+                    {"type": "text", "text": "Open "},
+                    {"type": "text", "text": "settings ", "marks": [{"type": "strong"}]},
+                    {"type": "text", "text": "the docs", "marks": [{"type": "link", "attrs": {"href": "https://example.com"}}]},
+                    {"type": "hardBreak"},
+                    {"type": "text", "text": "run", "marks": [{"type": "code"}]}
+                ]},
+                {"type": "orderedList", "content": [
+                    {"type": "listItem", "content": [
+                        {"type": "paragraph", "content": [{"type": "text", "text": "One"}]},
+                        {"type": "bulletList", "content": [
+                            {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Nested"}]}]}
+                        ]}
+                    ]},
+                    {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Two"}]}]}
+                ]},
+                {"type": "taskList", "content": [
+                    {"type": "taskItem", "attrs": {"state": "DONE"}, "content": [{"type": "text", "text": "Done"}]},
+                    {"type": "taskItem", "attrs": {"state": "TODO"}, "content": [{"type": "text", "text": "Todo"}]}
+                ]},
+                {"type": "codeBlock", "attrs": {"language": "sh"}, "content": [{"type": "text", "text": "jit RW-1"}]},
+                {"type": "table", "content": [
+                    {"type": "tableRow", "content": [
+                        {"type": "tableHeader", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Env"}]}]},
+                        {"type": "tableHeader", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "Result"}]}]}
+                    ]},
+                    {"type": "tableRow", "content": [
+                        {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "prod"}]}]},
+                        {"type": "tableCell", "content": [{"type": "paragraph", "content": [{"type": "text", "text": "a | b"}]}]}
+                    ]}
+                ]}
+            ]
+        });
+
+        assert_eq!(
+            adf_to_markdown(&value),
+            "## Steps\n\n\
+             Open **settings** [the docs](https://example.com)\\\n`run`\n\n\
+             1. One\n   - Nested\n2. Two\n\n\
+             - [x] Done\n- [ ] Todo\n\n\
+             ```sh\njit RW-1\n```\n\n\
+             | Env | Result |\n| --- | --- |\n| prod | a \\| b |"
+        );
+    }
+
+    #[test]
+    fn text_to_adf_preserves_paragraphs_and_line_breaks() {
+        let adf = text_to_adf("First line\nSecond line\n\nNew paragraph");
+
+        assert_eq!(adf["content"][0]["content"][0]["text"], "First line");
+        assert_eq!(adf["content"][0]["content"][1]["type"], "hardBreak");
+        assert_eq!(adf["content"][0]["content"][2]["text"], "Second line");
+        assert_eq!(adf["content"][1]["content"][0]["text"], "New paragraph");
+    }
+
+    #[test]
+    fn markdown_to_adf_keeps_code_blocks_headings_and_lists() {
+        let adf = markdown_to_adf(
+            "## Findings\nThe job fails on:\n- arm64\n- x86\n\n1. Retry\n2. Report\n```sh\ncargo test\n```",
+        );
+        let content = adf["content"].as_array().expect("content");
+
+        assert_eq!(content[0]["type"], "heading");
+        assert_eq!(content[0]["attrs"]["level"], 2);
+        assert_eq!(content[1]["content"][0]["text"], "The job fails on:");
+        assert_eq!(content[2]["type"], "bulletList");
+        assert_eq!(
+            content[2]["content"][1]["content"][0]["content"][0]["text"],
+            "x86"
+        );
+        assert_eq!(content[3]["type"], "orderedList");
+        assert_eq!(content[3]["content"].as_array().map(Vec::len), Some(2));
+        assert_eq!(content[4]["type"], "codeBlock");
+        assert_eq!(content[4]["attrs"]["language"], "sh");
+        assert_eq!(content[4]["content"][0]["text"], "cargo test");
+        assert_eq!(content.len(), 5);
+    }
+}
@@ -0,0 +1,1391 @@
+//! The Jira REST calls: fetching, searching, and changing issues. Each takes
+//! the HTTP client and the site's base URL; [`crate::JiraClient`] bundles the
+//! two for callers that talk to one site.
+
+use crate::adf::text_to_adf;
+use crate::capabilities::{self, Feature};
+use crate::error::{self, JitError};
+use crate::models::*;
+use crate::site_fields::{self, SPRINT_FIELD, STORY_POINTS_FIELD};
+use crate::{http, people};
+use anyhow::Result;
+use reqwest::blocking::{Client, RequestBuilder};
+use serde_json::{Value, json};
+use std::sync::Mutex;
+
+pub fn fetch_jira_issue(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    include_details: bool,
+    include_description: bool,
+    include_comments: bool,
+) -> Result<JiraIssue> {
+    let fields = issue_fields(include_details, include_description, include_comments);
+    fetch_jira_issue_fields(client, base_url, issue_key, &fields)
+}
+
+/// The fields [`fetch_jira_issue`] asks for: the summary plus the requested
+/// groups, to which callers can add fields of their own.
+pub fn issue_fields(
+    include_details: bool,
+    include_description: bool,
+    include_comments: bool,
+) -> Vec<&'static str> {
+    let mut fields = vec!["summary"];
+
+    if include_details {
+        fields.extend([
+            "status",
+            SPRINT_FIELD,
+            "assignee",
+            "reporter",
+            "priority",
+            "issuetype",
+            "created",
+            "updated",
+            "duedate",
+            STORY_POINTS_FIELD,
+        ]);
+    }
+
+    if include_description {
+        fields.push("description");
+    }
+
+    if include_comments {
+        fields.push("comment");
+    }
+
+    fields
+}
+
+/// Fetches an issue with `fields` filled in, which may name any field, such
+/// as `components` or `customfield_10016`.
+pub fn fetch_jira_issue_fields(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    fields: &[&str],
+) -> Result<JiraIssue> {
+    let site_fields = SiteRequestFields::new(client, base_url, fields);
+    let mut fields = site_fields.fields.clone();
+    fields.sort_unstable();
+    fields.dedup();
+
+    let url = format!(
+        "{}/rest/api/3/issue/{}?fields={}",
+        base_url,
+        issue_key,
+        fields.join(",")
+    );
+
+    let response = http::send(client.get(&url), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    let mut issue: JiraIssue = parse_jira_response(base_url, &response)?;
+    site_fields.restore(&mut issue);
+    Ok(issue)
+}
+
+/// Fields to request with [`SPRINT_FIELD`] and [`STORY_POINTS_FIELD`]
+/// swapped for the site's own, and the site's fields that were swapped in.
+struct SiteRequestFields {
+    fields: Vec<String>,
+    sprint: Option<String>,
+    story_points: Option<String>,
+}
+
+impl SiteRequestFields {
+    fn new(client: &Client, base_url: &str, fields: &[&str]) -> Self {
+        let site_field = |id: &str, resolve: fn(&Client, &str) -> String| {
+            fields
+                .contains(&id)
+                .then(|| resolve(client, base_url))
+                .filter(|field| field != id)
+        };
+        let sprint = site_field(SPRINT_FIELD, site_fields::sprint_field);
+        let story_points = site_field(STORY_POINTS_FIELD, site_fields::story_points_field);
+        let fields = fields
+            .iter()
+            .map(|field| match *field {
+                SPRINT_FIELD => sprint.as_deref().unwrap_or(field),
+                STORY_POINTS_FIELD => story_points.as_deref().unwrap_or(field),
+                field => field,
+            })
+            .map(str::to_string)
+            .collect();
+        SiteRequestFields {
+            fields,
+            sprint,
+            story_points,
+        }
+    }
+
+    /// Moves the swapped-in fields back to where jit reads them.
+    fn restore(&self, issue: &mut JiraIssue) {
+        if let Some(field) = &self.sprint {
+            issue.fields.take_sprint_field(field);
+        }
+        if let Some(field) = &self.story_points {
+            issue.fields.take_story_points_field(field);
+        }
+    }
+}
+
+/// Parses a Jira response, remembering the people it mentions along the way.
+pub fn parse_jira_response<T: serde::de::DeserializeOwned>(
+    base_url: &str,
+    response: &http::ApiResponse,
+) -> Result<T> {
+    let value: Value = response
+        .json()
+        .map_err(|err| JitError::parse("JIRA API", err))?;
+    people::remember(base_url, &value);
+    serde_json::from_value(value).map_err(|err| JitError::parse("JIRA API", err).into())
+}
+
+/// Instances where /search/jql is missing, so later searches skip straight to
+/// the legacy endpoint instead of probing again.
+static LEGACY_SEARCH_INSTANCES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Runs a JQL search, preferring the enhanced /search/jql endpoint and falling
+/// back to the legacy /search endpoint on instances that do not have it yet.
+pub fn search_issues(
+    client: &Client,
+    base_url: &str,
+    jql: &str,
+    fields: &[&str],
+    limit: usize,
+) -> Result<Vec<JiraIssue>> {
+    let mut issues = Vec::new();
+    search_issue_pages(client, base_url, jql, fields, limit, &mut |page| {
+        issues.extend(page);
+        Ok(())
+    })?;
+    Ok(issues)
+}
+
+/// Runs a JQL search like [`search_issues`], handing each page of up to 100
+/// issues to `on_page` as soon as it arrives.
+pub fn search_issue_pages(
+    client: &Client,
+    base_url: &str,
+    jql: &str,
+    fields: &[&str],
+    limit: usize,
+    on_page: &mut dyn FnMut(Vec<JiraIssue>) -> Result<()>,
+) -> Result<()> {
+    let site_fields = SiteRequestFields::new(client, base_url, fields);
+    let fields: Vec<&str> = site_fields.fields.iter().map(String::as_str).collect();
+    let fields = fields.as_slice();
+    let on_page = &mut |mut page: Vec<JiraIssue>| {
+        for issue in &mut page {
+            site_fields.restore(issue);
+        }
+        on_page(page)
+    };
+    if !uses_legacy_search(base_url) {
+        // The probe fails on the first page, before any page is handed out.
+        match search_pages_enhanced(client, base_url, jql, fields, limit, on_page) {
+            Err(err) if enhanced_search_unavailable(&err) => use_legacy_search(base_url),
+            result => return result,
+        }
+    }
+    search_pages_legacy(client, base_url, jql, fields, limit, on_page)
+}
+
+/// The number of issues matching `jql`, without fetching them: from
+/// /search/approximate-count, which can lag just-changed issues slightly, or
+/// the `total` of an empty legacy /search page on instances without it.
+pub fn count_issues(client: &Client, base_url: &str, jql: &str) -> Result<usize> {
+    if !uses_legacy_search(base_url) {
+        let url = format!("{}/rest/api/3/search/approximate-count", base_url);
+        let response = http::send(client.post(&url).json(&json!({ "jql": jql })), "JIRA API")?;
+        if response.status().is_success() {
+            let count: JiraCountResponse = parse_jira_response(base_url, &response)?;
+            return Ok(count.count);
+        }
+        let err = JitError::from_response("JIRA API", &response).into();
+        if !enhanced_search_unavailable(&err) {
+            return Err(err);
+        }
+        use_legacy_search(base_url);
+    }
+
+    let url = format!("{}/rest/api/3/search", base_url);
+    let query = json!({ "jql": jql, "maxResults": 0, "fields": ["summary"] });
+    let response = http::send(client.post(&url).json(&query), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    let page: JiraSearchResponse = parse_jira_response(base_url, &response)?;
+    Ok(page.total.unwrap_or_default())
+}
+
+pub fn uses_legacy_search(base_url: &str) -> bool {
+    LEGACY_SEARCH_INSTANCES
+        .lock()
+        .is_ok_and(|instances| instances.iter().any(|url| url == base_url))
+}
+
+pub fn use_legacy_search(base_url: &str) {
+    if let Ok(mut instances) = LEGACY_SEARCH_INSTANCES.lock() {
+        instances.push(base_url.to_string());
+    }
+}
+
+/// Older instances answer 404 (or 405) for /search/jql; Cloud never does for a
+/// valid search, since bad JQL is reported as 400.
+pub fn enhanced_search_unavailable(err: &anyhow::Error) -> bool {
+    matches!(
+        error::find(err),
+        Some(JitError::NotFound(_)) | Some(JitError::Api { status: 405, .. })
+    )
+}
+
+/// Searches through /search/jql, following `nextPageToken` until `limit`
+/// issues are collected or Jira reports the last page.
+fn search_pages_enhanced(
+    client: &Client,
+    base_url: &str,
+    jql: &str,
+    fields: &[&str],
+    limit: usize,
+    on_page: &mut dyn FnMut(Vec<JiraIssue>) -> Result<()>,
+) -> Result<()> {
+    let mut fetched = 0;
+    let mut next_page_token: Option<String> = None;
+
+    while fetched < limit {
+        let request = search_jql_request(
+            client,
+            base_url,
+            jql,
+            &fields.join(","),
+            (limit - fetched).min(100),
+            next_page_token.as_deref(),
+        )?;
+        let response = http::send(request, "JIRA API")?;
+
+        if !response.status().is_success() {
+            return Err(JitError::from_response("JIRA API", &response).into());
+        }
+
+        let mut page: JiraSearchResponse = parse_jira_response(base_url, &response)?;
+        if page.issues.is_empty() {
+            break;
+        }
+        page.issues.truncate(limit - fetched);
+        fetched += page.issues.len();
+        on_page(page.issues)?;
+
+        match page.next_page_token {
+            Some(token) => next_page_token = Some(token),
+            None => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// A GET of one /search/jql page, so an unchanged page is revalidated with
+/// its `ETag` like any other read.
+fn search_jql_request(
+    client: &Client,
+    base_url: &str,
+    jql: &str,
+    fields: &str,
+    max_results: usize,
+    next_page_token: Option<&str>,
+) -> Result<RequestBuilder> {
+    let max_results = max_results.to_string();
+    let mut query = vec![
+        ("jql", jql),
+        ("fields", fields),
+        ("maxResults", &max_results),
+    ];
+    if let Some(token) = next_page_token {
+        query.push(("nextPageToken", token));
+    }
+    let url =
+        reqwest::Url::parse_with_params(&format!("{}/rest/api/3/search/jql", base_url), query)
+            .map_err(|_| JitError::Config(format!("Invalid Jira base URL: {}", base_url)))?;
+    Ok(client.get(url))
+}
+
+/// Searches through the legacy /search endpoint, paging with `startAt` until
+/// `limit` issues are collected or `total` is reached.
+fn search_pages_legacy(
+    client: &Client,
+    base_url: &str,
+    jql: &str,
+    fields: &[&str],
+    limit: usize,
+    on_page: &mut dyn FnMut(Vec<JiraIssue>) -> Result<()>,
+) -> Result<()> {
+    let url = format!("{}/rest/api/3/search", base_url);
+    let mut fetched = 0;
+
+    while fetched < limit {
+        let query = json!({
+            "jql": jql,
+            "startAt": fetched,
+            "maxResults": (limit - fetched).min(100),
+            "fields": fields,
+        });
+
+        let response = http::send(client.post(&url).json(&query), "JIRA API")?;
+
+        if !response.status().is_success() {
+            return Err(JitError::from_response("JIRA API", &response).into());
+        }
+
+        let mut page: JiraSearchResponse = parse_jira_response(base_url, &response)?;
+        if page.issues.is_empty() {
+            break;
+        }
+        page.issues.truncate(limit - fetched);
+        fetched += page.issues.len();
+        on_page(page.issues)?;
+
+        if page.total.is_some_and(|total| fetched >= total) {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the credentials against `/myself`, returning the user they belong to.
+pub fn validate_jira_authentication(client: &Client, base_url: &str) -> Result<JiraUser> {
+    let url = format!("{}/rest/api/3/myself", base_url);
+    let response = http::send(client.get(&url), "JIRA API")?;
+
+    if response.status().is_success() {
+        // The credentials work even if the profile is oddly shaped.
+        return Ok(response.json().unwrap_or_default());
+    }
+
+    let status = response.status();
+    let body = response.text().unwrap_or_default();
+    if status.as_u16() == 401 || status.as_u16() == 403 {
+        return Err(JitError::Auth(format!(
+            "Jira authentication failed with status: {} - {}. Check your Jira email and API token.",
+            status, body
+        ))
+        .into());
+    }
+
+    Err(JitError::Api {
+        status: status.as_u16(),
+        message: format!(
+            "Jira authentication check failed with status: {} - {}",
+            status, body
+        ),
+    }
+    .into())
+}
+
+/// Creates an issue from a `{"fields": ...}` payload.
+pub fn create_jira_issue_payload(
+    client: &Client,
+    base_url: &str,
+    payload: &Value,
+) -> Result<JiraCreatedIssue> {
+    let url = format!("{}/rest/api/3/issue", base_url);
+
+    let response = http::send(client.post(&url).json(payload), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    response
+        .json()
+        .map_err(|err| JitError::parse("JIRA API", err).into())
+}
+
+pub fn update_jira_issue_payload(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    payload: &Value,
+) -> Result<()> {
+    let url = format!("{}/rest/api/3/issue/{}", base_url, issue_key);
+
+    let response = http::send(client.put(&url).json(payload), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    Ok(())
+}
+
+pub fn fetch_issue_transitions(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+) -> Result<Vec<JiraTransition>> {
+    let url = format!("{}/rest/api/3/issue/{}/transitions", base_url, issue_key);
+    let response = http::send(client.get(&url), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    let transitions: JiraTransitionsResponse = response
+        .json()
+        .map_err(|err| JitError::parse("JIRA API", err))?;
+    Ok(transitions.transitions)
+}
+
+pub fn transition_issue(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    transition_id: &str,
+) -> Result<()> {
+    post_transition(
+        client,
+        base_url,
+        issue_key,
+        &json!({ "transition": { "id": transition_id } }),
+    )
+}
+
+/// Transitions an issue, setting `fields` (such as the resolution) on the
+/// transition screen.
+pub fn transition_issue_with_fields(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    transition_id: &str,
+    fields: &Value,
+) -> Result<()> {
+    post_transition(
+        client,
+        base_url,
+        issue_key,
+        &json!({ "transition": { "id": transition_id }, "fields": fields }),
+    )
+}
+
+fn post_transition(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    payload: &Value,
+) -> Result<()> {
+    let url = format!("{}/rest/api/3/issue/{}/transitions", base_url, issue_key);
+    let response = http::send(client.post(&url).json(payload), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    Ok(())
+}
+
+/// Links two issues with the link type named `link_type`, so that they read
+/// in order, e.g. "RW-2 duplicates RW-1" for the Duplicate type.
+pub fn link_issues(
+    client: &Client,
+    base_url: &str,
+    link_type: &str,
+    inward_key: &str,
+    outward_key: &str,
+) -> Result<()> {
+    let url = format!("{}/rest/api/3/issueLink", base_url);
+    // Jira shows the type's outward description ("duplicates", "blocks") on
+    // the issue it calls inward.
+    let payload = json!({
+        "type": { "name": link_type },
+        "inwardIssue": { "key": inward_key },
+        "outwardIssue": { "key": outward_key },
+    });
+    let response = http::send(client.post(&url).json(&payload), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    Ok(())
+}
+
+pub fn add_issue_comment(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    body: &Value,
+) -> Result<JiraCreatedComment> {
+    let url = format!("{}/rest/api/3/issue/{}/comment", base_url, issue_key);
+    let response = http::send(client.post(&url).json(&json!({ "body": body })), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    response
+        .json()
+        .map_err(|err| JitError::parse("JIRA API", err).into())
+}
+
+/// Every field on the site, system and custom, with its ID and name.
+pub fn fetch_fields(client: &Client, base_url: &str) -> Result<Vec<JiraField>> {
+    let url = format!("{}/rest/api/3/field", base_url);
+    let response = http::send(client.get(&url), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    response
+        .json()
+        .map_err(|err| JitError::parse("JIRA API", err).into())
+}
+
+pub fn fetch_issue_watchers(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+) -> Result<Vec<JiraUser>> {
+    let url = format!("{}/rest/api/3/issue/{}/watchers", base_url, issue_key);
+    let response = http::send(client.get(&url), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    let watchers: JiraWatchersResponse = response
+        .json()
+        .map_err(|err| JitError::parse("JIRA API", err))?;
+    Ok(watchers.watchers)
+}
+
+/// Adds the user with `account_id` to an issue's watchers.
+pub fn add_issue_watcher(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    account_id: &str,
+) -> Result<()> {
+    let url = format!("{}/rest/api/3/issue/{}/watchers", base_url, issue_key);
+    // The body is the account ID as a bare JSON string.
+    let response = http::send(client.post(&url).json(&account_id), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    Ok(())
+}
+
+/// Sets an issue's assignee, or clears it when `account_id` is `None`.
+pub fn assign_issue(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    account_id: Option<&str>,
+) -> Result<()> {
+    let url = format!("{}/rest/api/3/issue/{}/assignee", base_url, issue_key);
+    let payload = json!({ "accountId": account_id });
+    let response = http::send(client.put(&url).json(&payload), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    Ok(())
+}
+
+/// Fetches one field of an issue as Jira returns it, for fields without a
+/// typed model such as checklists and attachments.
+pub fn fetch_issue_field(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    field: &str,
+) -> Result<Value> {
+    let url = format!(
+        "{}/rest/api/3/issue/{}?fields={}",
+        base_url, issue_key, field
+    );
+    let response = http::send(client.get(&url), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    let issue: Value = response
+        .json()
+        .map_err(|err| JitError::parse("JIRA API", err))?;
+    Ok(issue["fields"][field].clone())
+}
+
+/// Downloads an attachment's content from the URL Jira lists for it.
+pub fn fetch_attachment_content(client: &Client, url: &str) -> Result<Vec<u8>> {
+    let response = http::send(client.get(url), "JIRA API")?;
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+    Ok(response.bytes().to_vec())
+}
+
+/// Fetches one page of raw search results with every field: the issues, the
+/// token for the next page, and whether more pages follow. The legacy
+/// endpoint has no tokens and pages from `start_at` instead.
+pub fn search_raw_page(
+    client: &Client,
+    base_url: &str,
+    jql: &str,
+    next_page_token: Option<&str>,
+    start_at: usize,
+    page_size: usize,
+) -> Result<(Vec<Value>, Option<String>, bool)> {
+    if !uses_legacy_search(base_url) {
+        let request =
+            search_jql_request(client, base_url, jql, "*all", page_size, next_page_token)?;
+        let response = http::send(request, "JIRA API")?;
+        if response.status().is_success() {
+            let page: JiraSearchResponse<Value> = parse_jira_response(base_url, &response)?;
+            let more = page.next_page_token.is_some() && !page.issues.is_empty();
+            return Ok((page.issues, page.next_page_token, more));
+        }
+        let err = JitError::from_response("JIRA API", &response).into();
+        if !enhanced_search_unavailable(&err) {
+            return Err(err);
+        }
+        use_legacy_search(base_url);
+    }
+
+    let url = format!("{}/rest/api/3/search", base_url);
+    let query = json!({
+        "jql": jql,
+        "startAt": start_at,
+        "maxResults": page_size,
+        "fields": ["*all"],
+    });
+    let response = http::send(client.post(&url).json(&query), "JIRA API")?;
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+    let page: JiraSearchResponse<Value> = parse_jira_response(base_url, &response)?;
+    let fetched = start_at + page.issues.len();
+    let more = !page.issues.is_empty() && page.total.is_none_or(|total| fetched < total);
+    Ok((page.issues, None, more))
+}
+
+/// Logs `time_spent` (in Jira's `1h 30m` form) on an issue, with an optional comment.
+pub fn add_issue_worklog(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    time_spent: &str,
+    comment: Option<&str>,
+) -> Result<()> {
+    let url = format!("{}/rest/api/3/issue/{}/worklog", base_url, issue_key);
+    let mut payload = json!({ "timeSpent": time_spent });
+    if let Some(comment) = comment {
+        payload["comment"] = text_to_adf(comment);
+    }
+    let response = http::send(client.post(&url).json(&payload), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+    Ok(())
+}
+
+/// Fetches every comment on an issue as returned by Jira, oldest first.
+pub fn fetch_all_comment_values(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+) -> Result<Vec<Value>> {
+    let mut comments = Vec::new();
+    loop {
+        let url = format!(
+            "{}/rest/api/3/issue/{}/comment?orderBy=created&startAt={}&maxResults=100",
+            base_url,
+            issue_key,
+            comments.len()
+        );
+        let response = http::send(client.get(&url), "JIRA API")?;
+        if !response.status().is_success() {
+            return Err(JitError::from_response("JIRA API", &response).into());
+        }
+        let page: Value = parse_jira_response(base_url, &response)?;
+        let batch = page["comments"].as_array().cloned().unwrap_or_default();
+        let total = page["total"].as_u64().map(|total| total as usize);
+        let page_was_empty = batch.is_empty();
+        comments.extend(batch);
+        if page_was_empty || total.is_none_or(|total| comments.len() >= total) {
+            return Ok(comments);
+        }
+    }
+}
+
+/// Fetches up to `limit` of the newest comments on an issue, oldest first,
+/// with how many the issue has in all.
+pub fn fetch_issue_comments(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    limit: usize,
+) -> Result<(Vec<JiraComment>, usize)> {
+    let mut comments = Vec::new();
+    let mut total = 0;
+
+    while comments.len() < limit {
+        let url = format!(
+            "{}/rest/api/3/issue/{}/comment?orderBy=-created&startAt={}&maxResults={}",
+            base_url,
+            issue_key,
+            comments.len(),
+            (limit - comments.len()).min(100)
+        );
+        let response = http::send(client.get(&url), "JIRA API")?;
+
+        if !response.status().is_success() {
+            return Err(JitError::from_response("JIRA API", &response).into());
+        }
+
+        let page: JiraCommentPage = parse_jira_response(base_url, &response)?;
+        let page_was_empty = page.comments.is_empty();
+        comments.extend(page.comments);
+        total = page.total.unwrap_or(comments.len());
+        if page_was_empty || comments.len() >= total {
+            break;
+        }
+    }
+
+    comments.truncate(limit);
+    comments.reverse();
+    Ok((comments, total))
+}
+
+/// The users whose name or email matches `query`.
+pub fn search_users(client: &Client, base_url: &str, query: &str) -> Result<Vec<JiraUser>> {
+    let url = reqwest::Url::parse_with_params(
+        &format!("{}/rest/api/3/user/search", base_url),
+        [("query", query)],
+    )
+    .map_err(|_| JitError::Config(format!("Invalid Jira base URL: {}", base_url)))?;
+    let response = http::send(client.get(url), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    parse_jira_response(base_url, &response)
+}
+
+/// The user the credentials belong to. Unlike
+/// [`validate_jira_authentication`], a malformed profile is an error.
+pub fn fetch_current_user(client: &Client, base_url: &str) -> Result<JiraUser> {
+    let url = format!("{}/rest/api/3/myself", base_url);
+    let response = http::send(client.get(&url), "JIRA API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+
+    parse_jira_response(base_url, &response)
+}
+
+/// Classifies a failed Agile API response, reporting instances without Jira
+/// Software as unsupported instead of "not found".
+fn agile_error(client: &Client, base_url: &str, response: &http::ApiResponse) -> JitError {
+    capabilities::explain_not_found(
+        client,
+        base_url,
+        Feature::Agile,
+        JitError::from_response("Jira Agile API", response),
+    )
+}
+
+/// The project's scrum boards.
+pub fn fetch_scrum_boards_for_project(
+    client: &Client,
+    base_url: &str,
+    project_key: &str,
+) -> Result<Vec<JiraBoard>> {
+    let mut start_at = 0;
+    let mut boards = Vec::new();
+
+    loop {
+        let url = format!(
+            "{}/rest/agile/1.0/board?projectKeyOrId={}&type=scrum&startAt={}&maxResults=50",
+            base_url, project_key, start_at
+        );
+        let response = http::send(client.get(&url), "Jira Agile API")?;
+
+        if !response.status().is_success() {
+            return Err(agile_error(client, base_url, &response).into());
+        }
+
+        let page: JiraBoardPage = response
+            .json()
+            .map_err(|err| JitError::parse("Jira Agile API", err))?;
+        let page_size = page.values.len();
+        boards.extend(page.values);
+
+        if page.is_last || page_size == 0 {
+            break;
+        }
+
+        start_at = page.start_at + page.max_results.max(page_size);
+    }
+
+    Ok(boards)
+}
+
+pub fn fetch_board(client: &Client, base_url: &str, board_id: u64) -> Result<JiraBoard> {
+    let url = format!("{}/rest/agile/1.0/board/{}", base_url, board_id);
+    let response = http::send(client.get(&url), "Jira Agile API")?;
+
+    if !response.status().is_success() {
+        return Err(agile_error(client, base_url, &response).into());
+    }
+
+    response
+        .json()
+        .map_err(|err| JitError::parse("Jira Agile API", err).into())
+}
+
+/// The board's sprints in `state`: `active`, `future`, or `closed`.
+pub fn fetch_board_sprints(
+    client: &Client,
+    base_url: &str,
+    board_id: u64,
+    state: &str,
+) -> Result<Vec<JiraAgileSprint>> {
+    let mut start_at = 0;
+    let mut sprints = Vec::new();
+
+    loop {
+        let url = format!(
+            "{}/rest/agile/1.0/board/{}/sprint?state={}&startAt={}&maxResults=50",
+            base_url, board_id, state, start_at
+        );
+        let response = http::send(client.get(&url), "Jira Agile API")?;
+
+        if !response.status().is_success() {
+            return Err(agile_error(client, base_url, &response).into());
+        }
+
+        let page: JiraSprintPage = response
+            .json()
+            .map_err(|err| JitError::parse("Jira Agile API", err))?;
+        let page_size = page.values.len();
+        sprints.extend(page.values);
+
+        if page.is_last || page_size == 0 {
+            break;
+        }
+
+        start_at = page.start_at + page.max_results.max(page_size);
+    }
+
+    Ok(sprints)
+}
+
+/// Pages through an Agile endpoint that lists issues, such as a sprint's or
+/// a board's backlog, fetching at most `limit` issues.
+pub fn fetch_agile_issues(
+    client: &Client,
+    base_url: &str,
+    path: &str,
+    fields: &[&str],
+    limit: usize,
+) -> Result<Vec<JiraIssue>> {
+    let mut issues = Vec::new();
+
+    while issues.len() < limit {
+        let url = format!(
+            "{}/rest/agile/1.0/{}?fields={}&startAt={}&maxResults={}",
+            base_url,
+            path,
+            fields.join(","),
+            issues.len(),
+            (limit - issues.len()).min(50)
+        );
+        let response = http::send(client.get(&url), "Jira Agile API")?;
+
+        if !response.status().is_success() {
+            return Err(agile_error(client, base_url, &response).into());
+        }
+
+        let page: JiraAgileIssuePage = parse_jira_response(base_url, &response)?;
+        let page_size = page.issues.len();
+        issues.extend(page.issues);
+
+        if page_size == 0 || page.total.is_some_and(|total| issues.len() >= total) {
+            break;
+        }
+    }
+
+    issues.truncate(limit);
+    Ok(issues)
+}
+
+/// Moves issues into a sprint, or to the backlog when `sprint_id` is `None`,
+/// 50 at a time as the Agile API allows.
+pub fn move_issues_to_sprint(
+    client: &Client,
+    base_url: &str,
+    sprint_id: Option<u64>,
+    issue_keys: &[String],
+) -> Result<()> {
+    let url = match sprint_id {
+        Some(sprint_id) => format!("{}/rest/agile/1.0/sprint/{}/issue", base_url, sprint_id),
+        None => format!("{}/rest/agile/1.0/backlog/issue", base_url),
+    };
+    for batch in issue_keys.chunks(50) {
+        let payload = json!({ "issues": batch });
+        let response = http::send(client.post(&url).json(&payload), "Jira Agile API")?;
+
+        if !response.status().is_success() {
+            return Err(agile_error(client, base_url, &response).into());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn add_issue_to_sprint(
+    client: &Client,
+    base_url: &str,
+    sprint_id: u64,
+    issue_key: &str,
+) -> Result<()> {
+    let url = format!("{}/rest/agile/1.0/sprint/{}/issue", base_url, sprint_id);
+    let payload = json!({ "issues": [issue_key] });
+    let response = http::send(client.post(&url).json(&payload), "Jira Agile API")?;
+
+    if !response.status().is_success() {
+        return Err(agile_error(client, base_url, &response).into());
+    }
+
+    Ok(())
+}
+
+/// What Jira's development panel has for an issue, counted per kind.
+pub fn fetch_dev_status_summary(
+    client: &Client,
+    base_url: &str,
+    issue_id: &str,
+) -> Result<JiraDevStatusSummaryResponse> {
+    let url = format!(
+        "{}/rest/dev-status/latest/issue/summary?issueId={}",
+        base_url, issue_id
+    );
+
+    let response = http::send(client.get(&url), "Jira dev-status API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("Jira dev-status", &response).into());
+    }
+
+    response
+        .json()
+        .map_err(|err| JitError::parse("Jira dev-status", err).into())
+}
+
+/// The development panel's `data_type` records (`pullrequest` or `build`)
+/// that `application_type` reported for an issue.
+pub fn fetch_dev_status_detail(
+    client: &Client,
+    base_url: &str,
+    issue_id: &str,
+    application_type: &str,
+    data_type: &str,
+) -> Result<Vec<JiraDevStatusDetail>> {
+    let url = format!(
+        "{}/rest/dev-status/latest/issue/detail?issueId={}&applicationType={}&dataType={}",
+        base_url, issue_id, application_type, data_type
+    );
+
+    let response = http::send(client.get(&url), "Jira dev-status API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("Jira dev-status", &response).into());
+    }
+
+    let dev_status: JiraDevStatusResponse = response
+        .json()
+        .map_err(|err| JitError::parse("Jira dev-status", err))?;
+
+    Ok(dev_status.detail)
+}
+
+/// Creates a branch at `from` in the Bitbucket repository at `repository_url`
+/// (`{api}/repositories/{workspace}/{repo}`).
+pub fn create_bitbucket_branch(
+    client: &Client,
+    repository_url: &str,
+    branch_name: &str,
+    from: &str,
+) -> Result<BitbucketBranch> {
+    let url = format!("{}/refs/branches", repository_url);
+    let payload = json!({ "name": branch_name, "target": { "hash": from } });
+    let response = http::send(client.post(&url).json(&payload), "Bitbucket API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("Bitbucket API", &response).into());
+    }
+
+    response
+        .json()
+        .map_err(|err| JitError::parse("Bitbucket API", err).into())
+}
+
+/// Opens a pull request in the Bitbucket repository at `repository_url`.
+pub fn create_bitbucket_pull_request(
+    client: &Client,
+    repository_url: &str,
+    payload: &Value,
+) -> Result<BitbucketPullRequest> {
+    let url = format!("{}/pullrequests", repository_url);
+    let response = http::send(client.post(&url).json(payload), "Bitbucket API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("Bitbucket API", &response).into());
+    }
+
+    response
+        .json()
+        .map_err(|err| JitError::parse("Bitbucket API", err).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adf::text_to_adf;
+    use crate::client::create_jira_client;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn fetch_jira_issue_requests_expected_detail_fields() {
+        let (base_url, requests, handle) = spawn_test_server(
+            "HTTP/1.1 200 OK",
+            r#"{"id":"10001","key":"RW-123","fields":{"summary":"Implement backlog creation","status":{"name":"In Progress"},"customfield_10020":[{"name":"Sprint 42","state":"active"}],"description":{"type":"doc","version":1,"content":[{"type":"paragraph","content":[{"type":"text","text":"Hello"}]}]},"comment":{"comments":[{"author":{"displayName":"Cesar Ferreira"},"body":{"type":"doc","version":1,"content":[{"type":"paragraph","content":[{"type":"text","text":"Comment body"}]}]},"created":"2026-04-10T09:00:00.000+00:00","updated":"2026-04-10T10:00:00.000+00:00"}]}}}"#,
+        );
+        let client = create_jira_client("user@example.com", "token").expect("client");
+        site_fields::set_sprint_field(&base_url, SPRINT_FIELD);
+        site_fields::set_story_points_field(&base_url, STORY_POINTS_FIELD);
+
+        let issue = fetch_jira_issue(&client, &base_url, "RW-123", true, true, true)
+            .expect("issue fetch should succeed");
+        let request = requests
+            .recv_timeout(Duration::from_secs(2))
+            .expect("request should be captured");
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(issue.key, "RW-123");
+        assert!(request.starts_with("GET /rest/api/3/issue/RW-123?fields="));
+        assert!(request.contains("assignee,comment,created,customfield_10016,customfield_10020,description,duedate,issuetype,priority,reporter,status,summary,updated"));
+    }
+
+    #[test]
+    fn add_issue_comment_posts_adf_body() {
+        let (base_url, requests, handle) =
+            spawn_test_server("HTTP/1.1 201 Created", r#"{"id":"10500"}"#);
+        let client = create_jira_client("user@example.com", "token").expect("client");
+
+        let comment = add_issue_comment(&client, &base_url, "RW-123", &text_to_adf("Hello"))
+            .expect("comment should be created");
+        let request = requests
+            .recv_timeout(Duration::from_secs(2))
+            .expect("request should be captured");
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(comment.id, "10500");
+        assert!(request.starts_with("POST /rest/api/3/issue/RW-123/comment HTTP/1.1"));
+        let body = request
+            .split("\r\n\r\n")
+            .nth(1)
+            .expect("http request should contain a body");
+        let parsed_body: Value =
+            serde_json::from_str(body).expect("request body should be valid json");
+        assert_eq!(parsed_body["body"], text_to_adf("Hello"));
+    }
+
+    #[test]
+    fn search_issues_follows_next_page_token() {
+        let (base_url, requests, handle) = spawn_sequence_server(vec![
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"One"}}],"nextPageToken":"page-2"}"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"issues":[{"id":"2","key":"RW-2","fields":{"summary":"Two"}}],"isLast":true}"#,
+            ),
+        ]);
+        let client = create_jira_client("user@example.com", "token").expect("client");
+
+        let issues = search_issues(&client, &base_url, "project = RW", &["summary"], 10)
+            .expect("search should succeed");
+        let captured = collect_requests(requests, 2);
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(
+            issues
+                .iter()
+                .map(|issue| issue.key.as_str())
+                .collect::<Vec<_>>(),
+            vec!["RW-1", "RW-2"]
+        );
+        let target = captured[1]
+            .split_whitespace()
+            .nth(1)
+            .expect("request should have a target");
+        let url = reqwest::Url::parse(&format!("{}{}", base_url, target))
+            .expect("request target should be a valid path");
+        let second: std::collections::HashMap<_, _> = url.query_pairs().collect();
+        assert_eq!(second["nextPageToken"], "page-2");
+        assert_eq!(second["maxResults"], "9");
+    }
+
+    #[test]
+    fn count_issues_falls_back_to_legacy_total() {
+        let (base_url, requests, handle) =
+            spawn_sequence_server(vec![("HTTP/1.1 200 OK", r#"{"count":42}"#)]);
+        let client = create_jira_client("user@example.com", "token").expect("client");
+        let count = count_issues(&client, &base_url, "project = RW").expect("count");
+        let request = collect_requests(requests, 1).remove(0);
+        handle.join().expect("server thread should finish");
+        assert_eq!(count, 42);
+        assert!(request.starts_with("POST /rest/api/3/search/approximate-count "));
+        assert!(request.contains(r#"{"jql":"project = RW"}"#));
+
+        let (base_url, requests, handle) = spawn_sequence_server(vec![
+            (
+                "HTTP/1.1 404 Not Found",
+                r#"{"errorMessages":["Not found"]}"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"startAt":0,"maxResults":0,"total":7,"issues":[]}"#,
+            ),
+        ]);
+        let count = count_issues(&client, &base_url, "project = RW").expect("legacy count");
+        let requests = collect_requests(requests, 2);
+        handle.join().expect("server thread should finish");
+        assert_eq!(count, 7);
+        assert!(requests[1].starts_with("POST /rest/api/3/search "));
+        assert!(requests[1].contains(r#""maxResults":0"#));
+        assert!(uses_legacy_search(&base_url));
+    }
+
+    #[test]
+    fn search_issues_falls_back_to_legacy_endpoint() {
+        let (base_url, requests, handle) = spawn_sequence_server(vec![
+            (
+                "HTTP/1.1 404 Not Found",
+                r#"{"errorMessages":["null for uri: /rest/api/3/search/jql"]}"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"startAt":0,"maxResults":1,"total":2,"issues":[{"id":"1","key":"RW-1","fields":{"summary":"One"}}]}"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"startAt":1,"maxResults":1,"total":2,"issues":[{"id":"2","key":"RW-2","fields":{"summary":"Two"}}]}"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"startAt":0,"maxResults":1,"total":1,"issues":[{"id":"3","key":"RW-3","fields":{"summary":"Three"}}]}"#,
+            ),
+        ]);
+        let client = create_jira_client("user@example.com", "token").expect("client");
+
+        let issues = search_issues(&client, &base_url, "project = RW", &["summary"], 10)
+            .expect("search should fall back");
+        let again = search_issues(&client, &base_url, "key = RW-3", &["summary"], 10)
+            .expect("search should reuse the legacy endpoint");
+        let captured = collect_requests(requests, 4);
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(again[0].key, "RW-3");
+        assert!(captured[0].starts_with("GET /rest/api/3/search/jql?"));
+        assert!(captured[1].starts_with("POST /rest/api/3/search HTTP/1.1"));
+        assert!(captured[2].contains("\"startAt\":1"));
+        assert!(captured[3].starts_with("POST /rest/api/3/search HTTP/1.1"));
+    }
+
+    #[test]
+    fn fetch_scrum_boards_for_project_reads_all_pages() {
+        let (base_url, requests, handle) = spawn_sequence_server(vec![
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"values":[{"id":10,"name":"Alpha board"}],"isLast":false,"maxResults":1,"startAt":0}"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"values":[{"id":20,"name":"Beta board"}],"isLast":true,"maxResults":1,"startAt":1}"#,
+            ),
+        ]);
+        let client = create_jira_client("user@example.com", "token").expect("client");
+
+        let boards = fetch_scrum_boards_for_project(&client, &base_url, "RW")
+            .expect("board pagination should succeed");
+        let requests = collect_requests(requests, 2);
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0].id, 10);
+        assert_eq!(boards[1].id, 20);
+        assert!(requests[0].contains("startAt=0&maxResults=50"));
+        assert!(requests[1].contains("startAt=1&maxResults=50"));
+    }
+
+    #[test]
+    fn add_issue_to_sprint_posts_issue_key() {
+        let (base_url, requests, handle) = spawn_test_server("HTTP/1.1 204 No Content", "");
+        let client = create_jira_client("user@example.com", "token").expect("client");
+
+        add_issue_to_sprint(&client, &base_url, 200, "RW-123")
+            .expect("adding issue to sprint should succeed");
+        let request = requests
+            .recv_timeout(Duration::from_secs(2))
+            .expect("request should be captured");
+        handle.join().expect("server thread should finish");
+
+        assert!(request.starts_with("POST /rest/agile/1.0/sprint/200/issue HTTP/1.1"));
+        let body = request
+            .split("\r\n\r\n")
+            .nth(1)
+            .expect("http request should contain a body");
+        let parsed_body: Value =
+            serde_json::from_str(body).expect("request body should be valid json");
+        assert_eq!(parsed_body, json!({ "issues": ["RW-123"] }));
+    }
+
+    fn spawn_test_server(
+        status_line: &str,
+        response_body: &'static str,
+    ) -> (String, mpsc::Receiver<String>, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
+        let addr = listener.local_addr().expect("read local addr");
+        let (tx, rx) = mpsc::channel();
+        let status_line = status_line.to_string();
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept connection");
+            stream
+                .set_read_timeout(Some(Duration::from_secs(2)))
+                .expect("set timeout");
+
+            let request = read_http_request(&mut stream);
+            tx.send(request).expect("send request");
+
+            let response = format!(
+                "{status_line}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                response_body.len(),
+                response_body
+            );
+            stream
+                .write_all(response.as_bytes())
+                .expect("write response");
+        });
+
+        (format!("http://{}", addr), rx, handle)
+    }
+
+    fn spawn_sequence_server(
+        responses: Vec<(&'static str, &'static str)>,
+    ) -> (String, mpsc::Receiver<String>, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
+        let addr = listener.local_addr().expect("read local addr");
+        let (tx, rx) = mpsc::channel();
+
+        let handle = thread::spawn(move || {
+            for (status_line, response_body) in responses {
+                let (mut stream, _) = listener.accept().expect("accept connection");
+                stream
+                    .set_read_timeout(Some(Duration::from_secs(2)))
+                    .expect("set timeout");
+
+                let request = read_http_request(&mut stream);
+                tx.send(request).expect("send request");
+
+                let response = format!(
+                    "{status_line}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    response_body.len(),
+                    response_body
+                );
+                stream
+                    .write_all(response.as_bytes())
+                    .expect("write response");
+            }
+        });
+
+        (format!("http://{}", addr), rx, handle)
+    }
+
+    fn collect_requests(receiver: mpsc::Receiver<String>, expected: usize) -> Vec<String> {
+        (0..expected)
+            .map(|_| {
+                receiver
+                    .recv_timeout(Duration::from_secs(2))
+                    .expect("request should be captured")
+            })
+            .collect()
+    }
+
+    fn read_http_request(stream: &mut std::net::TcpStream) -> String {
+        let mut buffer = Vec::new();
+        let mut temp = [0_u8; 4096];
+        let mut header_end = None;
+        let mut content_length = 0_usize;
+
+        loop {
+            let read = stream.read(&mut temp).expect("read request");
+            if read == 0 {
+                break;
+            }
+            buffer.extend_from_slice(&temp[..read]);
+
+            if header_end.is_none() {
+                header_end = buffer
+                    .windows(4)
+                    .position(|window| window == b"\r\n\r\n")
+                    .map(|index| index + 4);
+
+                if let Some(end) = header_end {
+                    let headers = String::from_utf8_lossy(&buffer[..end]).to_lowercase();
+                    content_length = headers
+                        .lines()
+                        .find_map(|line| {
+                            line.strip_prefix("content-length:")
+                                .map(str::trim)
+                                .and_then(|value| value.parse::<usize>().ok())
+                        })
+                        .unwrap_or(0);
+                }
+            }
+
+            if let Some(end) = header_end
+                && buffer.len() >= end + content_length
+            {
+                break;
+            }
+        }
+
+        String::from_utf8(buffer).expect("request should be utf8")
+    }
+}
@@ -0,0 +1,217 @@
+//! Detects what a Jira instance supports (Cloud vs. Server, version, Agile)
+//! from `/serverInfo`, caching the answer in the data directory so the probe
+//! only runs once per instance.
+
+use crate::error::JitError;
+use crate::{http, jit_data_dir};
+use anyhow::Result;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// `Cloud`, `Server`, or `DataCenter`, as reported by the instance.
+    pub deployment_type: String,
+    pub version: String,
+    /// Whether the Jira Software (Agile) REST API is available.
+    pub agile: bool,
+}
+
+impl Capabilities {
+    pub fn is_cloud(&self) -> bool {
+        self.deployment_type.eq_ignore_ascii_case("cloud")
+    }
+
+    /// REST API version the instance serves: 3 on Cloud, 2 on Server and Data Center.
+    pub fn api_version(&self) -> u8 {
+        if self.is_cloud() { 3 } else { 2 }
+    }
+
+    fn describe(&self) -> String {
+        let name = if self.version.is_empty() {
+            format!("Jira {}", self.deployment_type)
+        } else {
+            format!("Jira {} {}", self.deployment_type, self.version)
+        };
+        format!("{}, REST API v{}", name, self.api_version())
+    }
+}
+
+/// Features that only some instances provide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// Boards and sprints from Jira Software.
+    Agile,
+}
+
+impl Feature {
+    fn name(self) -> &'static str {
+        match self {
+            Feature::Agile => "Boards and sprints (Jira Software)",
+        }
+    }
+
+    fn supported_by(self, capabilities: &Capabilities) -> bool {
+        match self {
+            Feature::Agile => capabilities.agile,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerInfo {
+    #[serde(rename = "deploymentType", default)]
+    deployment_type: Option<String>,
+    #[serde(default)]
+    version: String,
+}
+
+/// Capabilities already detected in this process, keyed by base URL.
+static DETECTED: Mutex<BTreeMap<String, Capabilities>> = Mutex::new(BTreeMap::new());
+
+/// The on-disk cache as it was when first needed, so checking it on every
+/// request reads the file only once.
+static CACHED: OnceLock<BTreeMap<String, Capabilities>> = OnceLock::new();
+
+/// The instance's capabilities if this process or the on-disk cache already
+/// knows them, without probing it.
+pub fn known(base_url: &str) -> Option<Capabilities> {
+    DETECTED
+        .lock()
+        .ok()
+        .and_then(|detected| detected.get(base_url).cloned())
+        .or_else(|| CACHED.get_or_init(read_cache).get(base_url).cloned())
+}
+
+/// Returns the instance's capabilities, probing it only when neither this
+/// process nor the on-disk cache has seen it before.
+pub fn detect(client: &Client, base_url: &str) -> Result<Capabilities> {
+    if let Some(capabilities) = DETECTED
+        .lock()
+        .ok()
+        .and_then(|detected| detected.get(base_url).cloned())
+    {
+        return Ok(capabilities);
+    }
+
+    let mut cache = read_cache();
+    let capabilities = match cache.get(base_url) {
+        Some(capabilities) => {
+            http::record_cache_hit();
+            capabilities.clone()
+        }
+        None => {
+            let capabilities = probe(client, base_url)?;
+            cache.insert(base_url.to_string(), capabilities.clone());
+            // The cache only saves a probe next time, so a failed write is not an error.
+            let _ = write_cache(&cache);
+            capabilities
+        }
+    };
+
+    if let Ok(mut detected) = DETECTED.lock() {
+        detected.insert(base_url.to_string(), capabilities.clone());
+    }
+    Ok(capabilities)
+}
+
+fn probe(client: &Client, base_url: &str) -> Result<Capabilities> {
+    // /rest/api/2 is served by Cloud, Server, and Data Center alike.
+    let url = format!("{}/rest/api/2/serverInfo", base_url);
+    let response = http::send(client.get(&url), "JIRA API")?;
+    if !response.status().is_success() {
+        return Err(JitError::from_response("JIRA API", &response).into());
+    }
+    let info: ServerInfo = response
+        .json()
+        .map_err(|err| JitError::parse("Jira server info", err))?;
+
+    let url = format!("{}/rest/agile/1.0/board?maxResults=1", base_url);
+    let agile = http::send(client.get(&url), "Jira Agile API")?
+        .status()
+        .as_u16()
+        != 404;
+
+    Ok(Capabilities {
+        // Older Server releases omit deploymentType entirely.
+        deployment_type: info.deployment_type.unwrap_or_else(|| "Server".to_string()),
+        version: info.version,
+        agile,
+    })
+}
+
+/// Replaces a 404 from `feature`'s endpoint with a clear "not supported" error
+/// when the instance lacks the feature; any other error is returned unchanged.
+pub fn explain_not_found(
+    client: &Client,
+    base_url: &str,
+    feature: Feature,
+    err: JitError,
+) -> JitError {
+    if !matches!(err, JitError::NotFound(_)) {
+        return err;
+    }
+    match detect(client, base_url) {
+        Ok(capabilities) if !feature.supported_by(&capabilities) => JitError::Unsupported(format!(
+            "{} are not supported by your instance ({}).",
+            feature.name(),
+            capabilities.describe()
+        )),
+        _ => err,
+    }
+}
+
+fn cache_path() -> Result<PathBuf> {
+    Ok(jit_data_dir()?.join("capabilities.json"))
+}
+
+fn read_cache() -> BTreeMap<String, Capabilities> {
+    cache_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_cache(cache: &BTreeMap<String, Capabilities>) -> Result<()> {
+    let path = cache_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(cache)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capabilities(deployment_type: &str, agile: bool) -> Capabilities {
+        Capabilities {
+            deployment_type: deployment_type.to_string(),
+            version: "9.12.0".to_string(),
+            agile,
+        }
+    }
+
+    #[test]
+    fn api_version_follows_deployment_type() {
+        assert_eq!(capabilities("Cloud", true).api_version(), 3);
+        assert_eq!(capabilities("Server", true).api_version(), 2);
+        assert_eq!(capabilities("DataCenter", true).api_version(), 2);
+    }
+
+    #[test]
+    fn agile_feature_requires_agile_api() {
+        assert!(Feature::Agile.supported_by(&capabilities("Server", true)));
+        assert!(!Feature::Agile.supported_by(&capabilities("Server", false)));
+        assert_eq!(
+            capabilities("DataCenter", false).describe(),
+            "Jira DataCenter 9.12.0, REST API v2"
+        );
+    }
+}
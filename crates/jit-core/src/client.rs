@@ -0,0 +1,234 @@
+//! Authenticated HTTP clients for Jira, and [`JiraClient`], which pairs one
+//! with the site it talks to.
+
+use crate::api;
+use crate::models::{JiraCreatedComment, JiraCreatedIssue, JiraIssue, JiraTransition, JiraUser};
+use anyhow::Result;
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use reqwest::blocking::{Client, ClientBuilder};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue};
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+
+#[derive(Debug, Deserialize, Default)]
+pub struct HttpConfig {
+    /// Protocol to use: `auto` negotiates HTTP/2 over TLS, `http1` and `http2` force one.
+    #[serde(default)]
+    pub version: HttpVersion,
+    /// Idle connections kept open per host between requests.
+    #[serde(default)]
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Seconds an idle pooled connection is kept before it is closed.
+    #[serde(default)]
+    pub pool_idle_timeout_secs: Option<u64>,
+    /// Interval between TCP keep-alive probes on open connections.
+    #[serde(default)]
+    pub tcp_keepalive_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpVersion {
+    #[default]
+    Auto,
+    Http1,
+    Http2,
+}
+
+impl HttpConfig {
+    pub fn client_builder(&self) -> ClientBuilder {
+        let mut builder = ClientBuilder::new();
+        match self.version {
+            HttpVersion::Auto => {}
+            HttpVersion::Http1 => builder = builder.http1_only(),
+            HttpVersion::Http2 => builder = builder.http2_prior_knowledge(),
+        }
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(seconds) = self.pool_idle_timeout_secs {
+            builder = builder.pool_idle_timeout(Duration::from_secs(seconds));
+        }
+        if let Some(seconds) = self.tcp_keepalive_secs {
+            builder = builder.tcp_keepalive(Duration::from_secs(seconds));
+        }
+        builder
+    }
+}
+
+/// A client with email and API token credentials and default HTTP settings.
+pub fn create_jira_client(email: &str, api_token: &str) -> Result<Client> {
+    create_basic_auth_client(email, api_token, &HttpConfig::default())
+}
+
+pub fn create_basic_auth_client(username: &str, secret: &str, http: &HttpConfig) -> Result<Client> {
+    // Create Basic Auth header
+    let auth = format!("{}:{}", username, secret);
+    let encoded_auth = STANDARD.encode(auth);
+    create_authorized_client(&format!("Basic {}", encoded_auth), http)
+}
+
+pub fn create_authorized_client(auth_header: &str, http: &HttpConfig) -> Result<Client> {
+    // Setup headers
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(auth_header)?);
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+    // Build client
+    let client = http.client_builder().default_headers(headers).build()?;
+
+    Ok(client)
+}
+
+/// A Jira site and a client authenticated against it.
+///
+/// ```no_run
+/// use jit_core::{HttpConfig, JiraApi, JiraClient};
+///
+/// let jira = JiraClient::basic(
+///     "https://your-company.atlassian.net",
+///     "me@example.com",
+///     "api-token",
+///     &HttpConfig::default(),
+/// )?;
+/// let issue = jira.issue("RW-1931", true, true, false)?;
+/// println!("{}: {}", issue.key, issue.fields.summary);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct JiraClient {
+    http: Client,
+    base_url: String,
+}
+
+impl JiraClient {
+    /// Authenticates with an email and API token, as Jira Cloud expects.
+    pub fn basic(base_url: &str, email: &str, api_token: &str, http: &HttpConfig) -> Result<Self> {
+        Ok(Self::new(
+            create_basic_auth_client(email, api_token, http)?,
+            base_url,
+        ))
+    }
+
+    /// Authenticates with a personal access token, for Jira Server and Data Center.
+    pub fn bearer(base_url: &str, token: &str, http: &HttpConfig) -> Result<Self> {
+        Ok(Self::new(
+            create_authorized_client(&format!("Bearer {}", token), http)?,
+            base_url,
+        ))
+    }
+
+    pub fn new(http: Client, base_url: &str) -> Self {
+        JiraClient {
+            http,
+            base_url: base_url.to_string(),
+        }
+    }
+
+    /// The underlying client, for calls without a method here.
+    pub fn http(&self) -> &Client {
+        &self.http
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Creates an issue from a `{"fields": ...}` payload.
+    pub fn create_issue(&self, payload: &Value) -> Result<JiraCreatedIssue> {
+        api::create_jira_issue_payload(&self.http, &self.base_url, payload)
+    }
+
+    /// Updates an issue from a `{"fields": ...}` or `{"update": ...}` payload.
+    pub fn update_issue(&self, key: &str, payload: &Value) -> Result<()> {
+        api::update_jira_issue_payload(&self.http, &self.base_url, key, payload)
+    }
+
+    pub fn transitions(&self, key: &str) -> Result<Vec<JiraTransition>> {
+        api::fetch_issue_transitions(&self.http, &self.base_url, key)
+    }
+
+    pub fn transition(&self, key: &str, transition_id: &str) -> Result<()> {
+        api::transition_issue(&self.http, &self.base_url, key, transition_id)
+    }
+
+    /// Adds a comment whose body is an ADF document.
+    pub fn add_comment(&self, key: &str, body: &Value) -> Result<JiraCreatedComment> {
+        api::add_issue_comment(&self.http, &self.base_url, key, body)
+    }
+
+    /// Sets the assignee, or clears it when `account_id` is `None`.
+    pub fn assign(&self, key: &str, account_id: Option<&str>) -> Result<()> {
+        api::assign_issue(&self.http, &self.base_url, key, account_id)
+    }
+}
+
+/// The reads jit's ticket views are built from, so they can run against
+/// [`JiraClient`] or, in tests, an in-memory `mock::MockJira`.
+pub trait JiraApi {
+    /// Fetches an issue with the summary plus the requested groups of fields.
+    fn issue(
+        &self,
+        key: &str,
+        details: bool,
+        description: bool,
+        comments: bool,
+    ) -> Result<JiraIssue> {
+        self.issue_with_fields(key, &api::issue_fields(details, description, comments))
+    }
+
+    /// Fetches an issue with `fields` filled in, by Jira field ID.
+    fn issue_with_fields(&self, key: &str, fields: &[&str]) -> Result<JiraIssue>;
+
+    /// Up to `limit` issues matching `jql`, with only `fields` filled in.
+    fn search(&self, jql: &str, fields: &[&str], limit: usize) -> Result<Vec<JiraIssue>>;
+
+    /// Like [`JiraApi::search`], but hands each page to `on_page` as it
+    /// arrives, so long results can be shown before the last page is in.
+    fn search_pages(
+        &self,
+        jql: &str,
+        fields: &[&str],
+        limit: usize,
+        on_page: &mut dyn FnMut(Vec<JiraIssue>) -> Result<()>,
+    ) -> Result<()> {
+        on_page(self.search(jql, fields, limit)?)
+    }
+
+    /// How many issues match `jql`. By default this searches for them all;
+    /// [`JiraClient`] asks Jira for the count alone.
+    fn count(&self, jql: &str) -> Result<usize> {
+        Ok(self.search(jql, &["summary"], usize::MAX)?.len())
+    }
+
+    /// The user the credentials belong to, failing when they are rejected.
+    fn myself(&self) -> Result<JiraUser>;
+}
+
+impl JiraApi for JiraClient {
+    fn issue_with_fields(&self, key: &str, fields: &[&str]) -> Result<JiraIssue> {
+        api::fetch_jira_issue_fields(&self.http, &self.base_url, key, fields)
+    }
+
+    fn search(&self, jql: &str, fields: &[&str], limit: usize) -> Result<Vec<JiraIssue>> {
+        api::search_issues(&self.http, &self.base_url, jql, fields, limit)
+    }
+
+    fn search_pages(
+        &self,
+        jql: &str,
+        fields: &[&str],
+        limit: usize,
+        on_page: &mut dyn FnMut(Vec<JiraIssue>) -> Result<()>,
+    ) -> Result<()> {
+        api::search_issue_pages(&self.http, &self.base_url, jql, fields, limit, on_page)
+    }
+
+    fn count(&self, jql: &str) -> Result<usize> {
+        api::count_issues(&self.http, &self.base_url, jql)
+    }
+
+    fn myself(&self) -> Result<JiraUser> {
+        api::validate_jira_authentication(&self.http, &self.base_url)
+    }
+}
@@ -0,0 +1,427 @@
+//! Domain errors for failures that callers need to tell apart, so exit codes,
+//! JSON error output, and retries can branch on the kind of failure.
+
+use crate::http::ApiResponse;
+use reqwest::header::RETRY_AFTER;
+use serde_json::{Value, json};
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum JitError {
+    /// The credentials were rejected (HTTP 401): wrong, expired, or revoked.
+    Auth(String),
+    /// The credentials work but may not do this (HTTP 403).
+    Forbidden(String),
+    /// The issue, board, or other resource does not exist (HTTP 404).
+    NotFound(String),
+    /// The server asked us to slow down (HTTP 429).
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    /// The input was rejected, either locally or by the server (HTTP 400).
+    Validation {
+        message: String,
+        messages: Vec<String>,
+    },
+    /// The request never reached the server or got no response.
+    Network {
+        service: String,
+        source: reqwest::Error,
+    },
+    /// The configuration file is missing, unreadable, or incomplete.
+    Config(String),
+    /// The instance does not provide the requested feature.
+    Unsupported(String),
+    /// A response was not the JSON we expected from `service`.
+    Parse {
+        service: String,
+        source: serde_json::Error,
+    },
+    /// A response parsed, but lacks a value the command needs.
+    FieldMissing(String),
+    /// Any other unsuccessful API response.
+    Api { status: u16, message: String },
+}
+
+impl JitError {
+    pub fn validation(message: impl Into<String>) -> Self {
+        let message = message.into();
+        JitError::Validation {
+            messages: vec![message.clone()],
+            message,
+        }
+    }
+
+    pub fn network(service: &str, source: reqwest::Error) -> Self {
+        JitError::Network {
+            service: service.to_string(),
+            source,
+        }
+    }
+
+    pub fn parse(service: &str, source: serde_json::Error) -> Self {
+        JitError::Parse {
+            service: service.to_string(),
+            source,
+        }
+    }
+
+    /// Classifies an unsuccessful response from `service` by its status code.
+    pub fn from_response(service: &str, response: &ApiResponse) -> Self {
+        let status = response.status().to_string();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs);
+        let code = response.status().as_u16();
+        let body = response.text().unwrap_or_default();
+        match Self::from_status(service, code, &status, retry_after, &body) {
+            // Jira Server answers 403 after too many failed logins, until a
+            // CAPTCHA is solved in the browser; that is a login failure too.
+            JitError::Forbidden(message) if login_denied(response) => JitError::Auth(message),
+            err => err,
+        }
+    }
+
+    fn from_status(
+        service: &str,
+        code: u16,
+        status: &str,
+        retry_after: Option<Duration>,
+        body: &str,
+    ) -> Self {
+        let messages = api_error_messages(body);
+        let message = if messages.is_empty() {
+            format!(
+                "{} request failed with status: {} - {}",
+                service, status, body
+            )
+        } else {
+            let bullets: Vec<String> = messages
+                .iter()
+                .map(|message| format!("  • {}", message))
+                .collect();
+            format!(
+                "{} request failed with status: {}\n{}",
+                service,
+                status,
+                bullets.join("\n")
+            )
+        };
+        match code {
+            400 => JitError::Validation {
+                messages: if messages.is_empty() {
+                    vec![message.clone()]
+                } else {
+                    messages
+                },
+                message,
+            },
+            401 => JitError::Auth(message),
+            403 => JitError::Forbidden(message),
+            404 => JitError::NotFound(message),
+            429 => JitError::RateLimited {
+                message,
+                retry_after,
+            },
+            _ => JitError::Api {
+                status: code,
+                message,
+            },
+        }
+    }
+
+    /// Stable identifier used in JSON error output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            JitError::Auth(_) => "auth",
+            JitError::Forbidden(_) => "forbidden",
+            JitError::NotFound(_) => "not_found",
+            JitError::RateLimited { .. } => "rate_limited",
+            JitError::Validation { .. } => "validation",
+            JitError::Network { .. } => "network",
+            JitError::Config(_) => "config",
+            JitError::Unsupported(_) => "unsupported",
+            JitError::Parse { .. } => "parse",
+            JitError::FieldMissing(_) => "field_missing",
+            JitError::Api { .. } => "api",
+        }
+    }
+
+    /// Process exit code, following the BSD `sysexits.h` conventions.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            JitError::Validation { .. } => 65,
+            JitError::NotFound(_) => 66,
+            JitError::Network { .. } | JitError::Unsupported(_) => 69,
+            JitError::RateLimited { .. } => 75,
+            JitError::Parse { .. } | JitError::FieldMissing(_) => 76,
+            JitError::Auth(_) | JitError::Forbidden(_) => 77,
+            JitError::Config(_) => 78,
+            JitError::Api { .. } => 1,
+        }
+    }
+
+    /// Whether the same request may succeed if tried again later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            JitError::RateLimited { .. } | JitError::Network { .. } => true,
+            JitError::Api { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// What to try next, printed under the error.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            JitError::Auth(_) => Some(
+                "The API token may have expired or been revoked. Check it with `jit auth test`, or save a new one with `jit auth login`.",
+            ),
+            JitError::Forbidden(_) => Some(
+                "Your Jira account is signed in but lacks permission for this; ask a Jira admin for access.",
+            ),
+            JitError::NotFound(_) => {
+                Some("Check the key, and that your Jira account can see the project.")
+            }
+            JitError::RateLimited { .. } => {
+                Some("Jira is limiting requests; wait a minute and try again.")
+            }
+            JitError::Network { .. } => {
+                Some("Check jira.base_url, your network connection, and any proxy settings.")
+            }
+            JitError::Parse { .. } | JitError::FieldMissing(_) => Some(
+                "Check that jira.base_url points at your Jira site rather than a login page or proxy.",
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for JitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JitError::Auth(message)
+            | JitError::Forbidden(message)
+            | JitError::NotFound(message)
+            | JitError::Config(message)
+            | JitError::Unsupported(message)
+            | JitError::FieldMissing(message)
+            | JitError::RateLimited { message, .. }
+            | JitError::Validation { message, .. }
+            | JitError::Api { message, .. } => write!(f, "{}", message),
+            JitError::Network { service, .. } => write!(f, "Failed to send request to {}", service),
+            JitError::Parse { service, .. } => write!(f, "Failed to parse {} response", service),
+        }
+    }
+}
+
+impl std::error::Error for JitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JitError::Network { source, .. } => Some(source),
+            JitError::Parse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a 403 is Jira refusing the login (`X-Seraph-LoginReason`) rather
+/// than the request.
+fn login_denied(response: &ApiResponse) -> bool {
+    response
+        .headers()
+        .get("x-seraph-loginreason")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|reason| {
+            reason.contains("AUTHENTICATION_DENIED") || reason.contains("AUTHENTICATED_FAILED")
+        })
+}
+
+/// Extracts readable messages from a Jira (`errorMessages`/`errors`) or
+/// Bitbucket (`error.message`) error body.
+fn api_error_messages(body: &str) -> Vec<String> {
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+        return Vec::new();
+    };
+
+    let mut messages: Vec<String> = value["errorMessages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect();
+    if let Some(errors) = value["errors"].as_object() {
+        for (field, message) in errors {
+            let Some(message) = message.as_str() else {
+                continue;
+            };
+            if message.contains(&format!("'{}'", field)) {
+                messages.push(message.to_string());
+            } else {
+                messages.push(format!("{}: {}", field, message));
+            }
+        }
+    }
+    if let Some(message) = value["error"]["message"].as_str() {
+        messages.push(message.to_string());
+    }
+    messages
+}
+
+/// Finds the domain error anywhere in an error's context chain.
+pub fn find(err: &anyhow::Error) -> Option<&JitError> {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<JitError>())
+}
+
+pub fn exit_code(err: &anyhow::Error) -> u8 {
+    find(err).map_or(1, JitError::exit_code)
+}
+
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    find(err).is_some_and(JitError::is_retryable)
+}
+
+/// How long the server asked us to wait before retrying, if it said.
+pub fn retry_after(err: &anyhow::Error) -> Option<Duration> {
+    match find(err) {
+        Some(JitError::RateLimited { retry_after, .. }) => *retry_after,
+        _ => None,
+    }
+}
+
+pub fn hint(err: &anyhow::Error) -> Option<&'static str> {
+    find(err).and_then(JitError::hint)
+}
+
+/// The `{"error": {...}}` document printed when JSON output was requested.
+pub fn error_json(err: &anyhow::Error) -> Value {
+    let mut payload = json!({
+        "kind": find(err).map_or("error", JitError::kind),
+        "message": format!("{:#}", err),
+    });
+    if let Some(JitError::Validation { messages, .. }) = find(err) {
+        payload["messages"] = json!(messages);
+    }
+    if let Some(hint) = hint(err) {
+        payload["hint"] = json!(hint);
+    }
+    json!({ "error": payload })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn from_status_classifies_common_failures() {
+        let classify = |code| JitError::from_status("JIRA API", code, "status", None, "").kind();
+
+        assert_eq!(classify(400), "validation");
+        assert_eq!(classify(401), "auth");
+        assert_eq!(classify(403), "forbidden");
+        assert_eq!(classify(404), "not_found");
+        assert_eq!(classify(429), "rate_limited");
+        assert_eq!(classify(502), "api");
+    }
+
+    #[test]
+    fn from_status_lists_jira_error_messages_as_bullets() {
+        let body = r#"{"errorMessages":["Transition is not valid."],"errors":{"resolution":"Field 'resolution' is required","summary":"You must specify a summary."}}"#;
+
+        let err = JitError::from_status("JIRA API", 400, "400 Bad Request", None, body);
+
+        assert_eq!(
+            err.to_string(),
+            "JIRA API request failed with status: 400 Bad Request\n  • Transition is not valid.\n  • Field 'resolution' is required\n  • summary: You must specify a summary."
+        );
+        match err {
+            JitError::Validation { messages, .. } => assert_eq!(messages.len(), 3),
+            other => panic!("expected validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_status_keeps_raw_body_when_it_has_no_messages() {
+        let err = JitError::from_status("JIRA API", 502, "502 Bad Gateway", None, "<html>");
+
+        assert_eq!(
+            err.to_string(),
+            "JIRA API request failed with status: 502 Bad Gateway - <html>"
+        );
+    }
+
+    #[test]
+    fn api_error_messages_reads_bitbucket_errors() {
+        let body = r#"{"type":"error","error":{"message":"Branch already exists"}}"#;
+
+        assert_eq!(api_error_messages(body), vec!["Branch already exists"]);
+    }
+
+    #[test]
+    fn helpers_find_domain_error_behind_context() {
+        let err = Err::<(), _>(JitError::RateLimited {
+            message: "slow down".to_string(),
+            retry_after: Some(Duration::from_secs(30)),
+        })
+        .context("Failed to fetch RW-1")
+        .unwrap_err();
+
+        assert_eq!(exit_code(&err), 75);
+        assert!(is_retryable(&err));
+        assert_eq!(retry_after(&err), Some(Duration::from_secs(30)));
+        assert_eq!(error_json(&err)["error"]["kind"], "rate_limited");
+    }
+
+    #[test]
+    fn untyped_errors_use_generic_kind_and_exit_code() {
+        let err = anyhow::anyhow!("something broke");
+
+        assert_eq!(exit_code(&err), 1);
+        assert!(!is_retryable(&err));
+        assert_eq!(
+            error_json(&err),
+            json!({ "error": { "kind": "error", "message": "something broke" } })
+        );
+    }
+
+    #[test]
+    fn parse_errors_keep_the_serde_error_and_suggest_a_fix() {
+        let source = serde_json::from_str::<Value>("<html>").unwrap_err();
+        let err = anyhow::Error::from(JitError::parse("JIRA API", source));
+
+        assert_eq!(exit_code(&err), 76);
+        assert!(
+            format!("{:#}", err).starts_with("Failed to parse JIRA API response: expected value")
+        );
+        let json = error_json(&err);
+        assert_eq!(json["error"]["kind"], "parse");
+        assert!(
+            json["error"]["hint"]
+                .as_str()
+                .unwrap()
+                .contains("jira.base_url")
+        );
+        assert_eq!(
+            JitError::FieldMissing("RW-1 has no checklist".to_string()).kind(),
+            "field_missing"
+        );
+    }
+
+    #[test]
+    fn validation_errors_list_messages_in_json() {
+        let err = anyhow::Error::from(JitError::validation("Pass a ticket key or --jql"));
+
+        assert_eq!(exit_code(&err), 65);
+        assert_eq!(
+            error_json(&err)["error"]["messages"],
+            json!(["Pass a ticket key or --jql"])
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Bodies of GET responses that came with an `ETag`, kept in `http-cache/` in
+//! the data directory. Repeating the request sends `If-None-Match`, and a 304
+//! answer reuses the stored body instead of downloading it again. Entries
+//! belong to the account set with [`set_account`], and nothing is cached
+//! until one is, so one user's body is never served for another's
+//! credentials.
+
+use crate::jit_data_dir;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Larger bodies are rare for JSON and not worth keeping.
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Entries kept on disk; the least recently stored are removed first.
+const MAX_ENTRIES: usize = 500;
+
+static ACCOUNT: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    url: String,
+    account: String,
+    pub(crate) etag: String,
+    pub(crate) body: String,
+}
+
+/// Scopes the cache to `account`, e.g. the site and credentials in use.
+pub(crate) fn set_account(account: &str) {
+    let mut hasher = DefaultHasher::new();
+    account.hash(&mut hasher);
+    if let Ok(mut current) = ACCOUNT.lock() {
+        *current = Some(format!("{:016x}", hasher.finish()));
+    }
+}
+
+fn account() -> Option<String> {
+    ACCOUNT.lock().ok()?.clone()
+}
+
+/// The stored response for `url`, if any.
+pub(crate) fn get(url: &str) -> Option<Entry> {
+    let account = account()?;
+    let contents = fs::read_to_string(entry_path(url, &account)?).ok()?;
+    let entry: Entry = serde_json::from_str(&contents).ok()?;
+    // File names are hashes, so check the entry is really this one.
+    (entry.url == url && entry.account == account).then_some(entry)
+}
+
+/// Keeps `body` for `url` until it changes. Failing to write only costs a
+/// full download next time, so errors are ignored.
+pub(crate) fn store(url: &str, etag: &str, body: &[u8]) {
+    if body.len() > MAX_BODY_BYTES {
+        return;
+    }
+    let Some(account) = account() else {
+        return;
+    };
+    let (Some(path), Ok(body)) = (entry_path(url, &account), std::str::from_utf8(body)) else {
+        return;
+    };
+    let entry = Entry {
+        url: url.to_string(),
+        account,
+        etag: etag.to_string(),
+        body: body.to_string(),
+    };
+    let Ok(contents) = serde_json::to_string(&entry) else {
+        return;
+    };
+    if let Some(dir) = path.parent()
+        && fs::create_dir_all(dir).is_ok()
+    {
+        // Write then rename, so a concurrent jit never reads half an entry.
+        let partial = path.with_extension(format!("json.{}", std::process::id()));
+        if write_private(&partial, contents.as_bytes()).is_ok()
+            && fs::rename(&partial, &path).is_ok()
+        {
+            prune(dir);
+        }
+    }
+}
+
+/// Writes a file only its owner can read, since bodies may hold private
+/// issue data.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
+
+fn prune(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+    if entries.len() <= MAX_ENTRIES {
+        return;
+    }
+    entries.sort();
+    for (_, path) in &entries[..entries.len() - MAX_ENTRIES] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn entry_path(url: &str, account: &str) -> Option<PathBuf> {
+    // A cache miss is the worst a different hash after a Rust upgrade can cause.
+    let mut hasher = DefaultHasher::new();
+    account.hash(&mut hasher);
+    url.hash(&mut hasher);
+    Some(
+        jit_data_dir()
+            .ok()?
+            .join("http-cache")
+            .join(format!("{:016x}.json", hasher.finish())),
+    )
+}
@@ -0,0 +1,318 @@
+//! Sends API requests and records how long each one took, so `--stats` can
+//! report the API calls, bytes, and time a command spent. Requests for REST
+//! API v3 go to v2 instead on instances that only serve v2 (Jira Server and
+//! Data Center). Once [`set_cache_account`] names the account in use, GET
+//! requests revalidate bodies cached with an `ETag`, so an unchanged response
+//! costs a 304 instead of a full download.
+
+use crate::adf::extract_plain_text_from_adf;
+use crate::capabilities;
+use crate::error::JitError;
+use crate::etags;
+use reqwest::blocking::{Body, Client, Request, RequestBuilder};
+use reqwest::header::{CONTENT_TYPE, ETAG, HeaderMap, HeaderValue, IF_NONE_MATCH};
+use reqwest::{Method, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// A fully read API response.
+pub struct ApiResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl ApiResponse {
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_slice(&self.body)
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    pub fn text(&self) -> Result<String, std::string::FromUtf8Error> {
+        String::from_utf8(self.body.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestRecord {
+    pub method: String,
+    pub path: String,
+    pub status: Option<u16>,
+    pub bytes: usize,
+    pub duration: Duration,
+}
+
+static REQUESTS: Mutex<Vec<RequestRecord>> = Mutex::new(Vec::new());
+static CACHE_HITS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sends a request to `service` and reads the whole body, recording its timing.
+pub fn send(request: RequestBuilder, service: &str) -> Result<ApiResponse, JitError> {
+    let (client, request) = request.build_split();
+    let request = request.map_err(|source| JitError::network(service, source))?;
+    let Some(base_url) = api_v3_base_url(request.url()) else {
+        return execute(&client, request, service);
+    };
+    if capabilities::known(&base_url).is_some_and(|known| known.api_version() == 2) {
+        return execute(&client, downgrade_to_api_v2(request), service);
+    }
+
+    let retry = request.try_clone();
+    let response = execute(&client, request, service)?;
+    // Jira answers a missing issue with `errorMessages`; a v2-only instance
+    // answers an unknown v3 path without them.
+    if response.status() == StatusCode::NOT_FOUND
+        && response
+            .json::<Value>()
+            .map_or(true, |body| body.get("errorMessages").is_none())
+        && let Some(retry) = retry
+        && capabilities::detect(&client, &base_url).is_ok_and(|found| found.api_version() == 2)
+    {
+        return execute(&client, downgrade_to_api_v2(retry), service);
+    }
+    Ok(response)
+}
+
+/// The site a REST API v3 URL belongs to, e.g. `https://jira.example.com`
+/// for `https://jira.example.com/rest/api/3/issue/RW-1`.
+fn api_v3_base_url(url: &Url) -> Option<String> {
+    let url = url.as_str();
+    url.find("/rest/api/3/")
+        .map(|index| url[..index].to_string())
+}
+
+/// Points a v3 request at v2, which takes plain text where v3 takes
+/// Atlassian Document Format, so ADF documents in the body become text.
+fn downgrade_to_api_v2(mut request: Request) -> Request {
+    if let Ok(url) = Url::parse(
+        &request
+            .url()
+            .as_str()
+            .replacen("/rest/api/3/", "/rest/api/2/", 1),
+    ) {
+        *request.url_mut() = url;
+    }
+    let body = request
+        .body()
+        .and_then(Body::as_bytes)
+        .and_then(|bytes| serde_json::from_slice::<Value>(bytes).ok());
+    if let Some(mut body) = body {
+        adf_to_plain_text(&mut body);
+        if let Ok(bytes) = serde_json::to_vec(&body) {
+            *request.body_mut() = Some(Body::from(bytes));
+        }
+    }
+    request
+}
+
+fn adf_to_plain_text(value: &mut Value) {
+    if value.get("type").and_then(Value::as_str) == Some("doc") {
+        *value = Value::String(extract_plain_text_from_adf(value).trim().to_string());
+        return;
+    }
+    match value {
+        Value::Object(fields) => fields.values_mut().for_each(adf_to_plain_text),
+        Value::Array(items) => items.iter_mut().for_each(adf_to_plain_text),
+        _ => {}
+    }
+}
+
+fn execute(client: &Client, mut request: Request, service: &str) -> Result<ApiResponse, JitError> {
+    let method = request.method().to_string();
+    let path = request.url().path().to_string();
+    let url = request.url().to_string();
+    let is_get = request.method() == Method::GET;
+    let cached = is_get.then(|| etags::get(&url)).flatten();
+    if let Some(etag) = cached
+        .as_ref()
+        .and_then(|cached| HeaderValue::from_str(&cached.etag).ok())
+    {
+        request.headers_mut().insert(IF_NONE_MATCH, etag);
+    }
+    let started = Instant::now();
+
+    let result = client.execute(request).and_then(|response| {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes()?.to_vec();
+        Ok(ApiResponse {
+            status,
+            headers,
+            body,
+        })
+    });
+
+    record(RequestRecord {
+        method,
+        path,
+        status: result
+            .as_ref()
+            .ok()
+            .map(|response| response.status.as_u16()),
+        bytes: result.as_ref().map_or(0, |response| response.body.len()),
+        duration: started.elapsed(),
+    });
+    let mut response = result.map_err(|source| JitError::network(service, source))?;
+
+    if response.status == StatusCode::NOT_MODIFIED
+        && let Some(cached) = cached
+    {
+        record_cache_hit();
+        response.status = StatusCode::OK;
+        response.body = cached.body.into_bytes();
+    } else if is_get
+        && response.status.is_success()
+        && is_json(&response.headers)
+        && let Some(etag) = response
+            .headers
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+    {
+        etags::store(&url, etag, &response.body);
+    }
+    Ok(response)
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+fn record(entry: RequestRecord) {
+    if let Ok(mut requests) = REQUESTS.lock() {
+        requests.push(entry);
+    }
+}
+
+/// Caches `ETag` responses for `account`, such as the site and credentials a
+/// client was built with. Entries cached for other accounts are never used.
+pub fn set_cache_account(account: &str) {
+    etags::set_account(account);
+}
+
+/// Counts a lookup that one of jit's caches answered instead of Jira.
+pub fn record_cache_hit() {
+    CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub struct RequestStats {
+    pub requests: Vec<RequestRecord>,
+    pub cache_hits: usize,
+}
+
+impl RequestStats {
+    pub fn bytes(&self) -> usize {
+        self.requests.iter().map(|request| request.bytes).sum()
+    }
+
+    pub fn api_time(&self) -> Duration {
+        self.requests.iter().map(|request| request.duration).sum()
+    }
+
+    /// Writes the `--stats` report to stderr so it never mixes with command output.
+    pub fn print(&self, total: Duration) {
+        eprintln!();
+        eprintln!("API calls:  {}", self.requests.len());
+        eprintln!("Bytes:      {}", self.bytes());
+        eprintln!("Cache hits: {}", self.cache_hits);
+        eprintln!("API time:   {} ms", self.api_time().as_millis());
+        eprintln!("Total time: {} ms", total.as_millis());
+        for request in &self.requests {
+            let status = request
+                .status
+                .map_or_else(|| "---".to_string(), |status| status.to_string());
+            eprintln!(
+                "  {} {} {} ({} bytes, {} ms)",
+                status,
+                request.method,
+                request.path,
+                request.bytes,
+                request.duration.as_millis()
+            );
+        }
+    }
+}
+
+pub fn stats() -> RequestStats {
+    RequestStats {
+        requests: REQUESTS
+            .lock()
+            .map(|requests| requests.clone())
+            .unwrap_or_default(),
+        cache_hits: CACHE_HITS.load(Ordering::Relaxed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(bytes: usize, millis: u64) -> RequestRecord {
+        RequestRecord {
+            method: "GET".to_string(),
+            path: "/rest/api/3/issue/RW-1".to_string(),
+            status: Some(200),
+            bytes,
+            duration: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn downgrade_points_v3_requests_at_v2_with_plain_text_bodies() {
+        let client = Client::new();
+        let request = client
+            .post("https://jira.example.com/rest/api/3/issue/RW-1/comment")
+            .json(&serde_json::json!({
+                "body": {
+                    "type": "doc",
+                    "version": 1,
+                    "content": [{"type": "paragraph", "content": [{"type": "text", "text": "On it"}]}]
+                }
+            }))
+            .build()
+            .unwrap();
+        assert_eq!(
+            api_v3_base_url(request.url()).as_deref(),
+            Some("https://jira.example.com")
+        );
+
+        let request = downgrade_to_api_v2(request);
+        assert_eq!(
+            request.url().as_str(),
+            "https://jira.example.com/rest/api/2/issue/RW-1/comment"
+        );
+        let body: Value =
+            serde_json::from_slice(request.body().and_then(Body::as_bytes).unwrap()).unwrap();
+        assert_eq!(body, serde_json::json!({ "body": "On it" }));
+        assert_eq!(
+            api_v3_base_url(&Url::parse("https://jira.example.com/rest/agile/1.0/board").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn request_stats_sum_bytes_and_api_time() {
+        let stats = RequestStats {
+            requests: vec![request(120, 15), request(30, 5)],
+            cache_hits: 0,
+        };
+
+        assert_eq!(stats.bytes(), 150);
+        assert_eq!(stats.api_time(), Duration::from_millis(20));
+    }
+}
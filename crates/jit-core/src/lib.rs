@@ -0,0 +1,38 @@
+//! The Jira client behind the `jit` CLI, for other tools to reuse: typed
+//! models for issues, comments, and transitions, the REST calls that fetch
+//! and change them, Atlassian Document Format conversion, and the errors
+//! they fail with.
+//!
+//! Start with [`JiraClient`]. The functions in [`api`] take a client and
+//! base URL instead, for callers that already thread both around. Code that
+//! only reads issues can take a [`JiraApi`] and be tested against the
+//! in-memory `mock::MockJira` (the `mock` feature).
+
+pub mod adf;
+pub mod api;
+pub mod capabilities;
+pub mod client;
+pub mod error;
+mod etags;
+pub mod http;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod models;
+pub mod people;
+pub mod site_fields;
+
+pub use client::{HttpConfig, JiraApi, JiraClient};
+pub use error::JitError;
+
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Directory for local state such as snapshots; `JIT_DATA_DIR` overrides `~/.local/share/jit`.
+pub fn jit_data_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("JIT_DATA_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    let home = dirs::home_dir()
+        .ok_or_else(|| JitError::Config("Could not determine home directory".to_string()))?;
+    Ok(home.join(".local").join("share").join("jit"))
+}
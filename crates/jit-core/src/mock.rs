@@ -0,0 +1,93 @@
+//! [`MockJira`], an in-memory [`JiraApi`] for testing code that reads issues
+//! without a Jira server. Enabled by the `mock` feature.
+
+use crate::client::JiraApi;
+use crate::error::JitError;
+use crate::models::{JiraIssue, JiraUser};
+use anyhow::Result;
+use serde_json::Value;
+use std::sync::Mutex;
+
+/// Issues given as Jira's JSON, returned by key from `issue` and in order
+/// from `search`. Searches are recorded so tests can check the JQL sent.
+///
+/// ```
+/// use jit_core::JiraApi;
+/// use jit_core::mock::MockJira;
+/// use serde_json::json;
+///
+/// let jira = MockJira::new().with_issue(json!({
+///     "id": "1",
+///     "key": "RW-1",
+///     "fields": {"summary": "Fix login"}
+/// }));
+/// assert_eq!(jira.issue("RW-1", true, false, false)?.fields.summary, "Fix login");
+/// assert!(jira.issue("RW-2", true, false, false).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct MockJira {
+    issues: Vec<Value>,
+    user: Option<JiraUser>,
+    searches: Mutex<Vec<String>>,
+}
+
+impl MockJira {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an issue in the shape of Jira's `GET /issue/{key}` response.
+    pub fn with_issue(mut self, issue: Value) -> Self {
+        self.issues.push(issue);
+        self
+    }
+
+    /// The user `myself` returns; without one the credentials count as rejected.
+    pub fn with_user(mut self, user: JiraUser) -> Self {
+        self.user = Some(user);
+        self
+    }
+
+    /// The JQL of every search so far, oldest first.
+    pub fn searches(&self) -> Vec<String> {
+        self.searches
+            .lock()
+            .map(|searches| searches.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl JiraApi for MockJira {
+    /// The issue as given, whatever `fields` asks for.
+    fn issue_with_fields(&self, key: &str, _fields: &[&str]) -> Result<JiraIssue> {
+        let issue = self
+            .issues
+            .iter()
+            .find(|issue| issue["key"].as_str() == Some(key))
+            .ok_or_else(|| JitError::NotFound(format!("Issue {} does not exist.", key)))?;
+        serde_json::from_value(issue.clone()).map_err(|err| JitError::parse("mock", err).into())
+    }
+
+    /// Every issue, up to `limit`: the JQL is recorded but not evaluated.
+    fn search(&self, jql: &str, _fields: &[&str], limit: usize) -> Result<Vec<JiraIssue>> {
+        if let Ok(mut searches) = self.searches.lock() {
+            searches.push(jql.to_string());
+        }
+        self.issues
+            .iter()
+            .take(limit)
+            .map(|issue| {
+                serde_json::from_value(issue.clone())
+                    .map_err(|err| JitError::parse("mock", err).into())
+            })
+            .collect()
+    }
+
+    fn myself(&self) -> Result<JiraUser> {
+        self.user.clone().ok_or_else(|| {
+            JitError::Auth("Jira authentication failed with status: 401 Unauthorized".to_string())
+                .into()
+        })
+    }
+}
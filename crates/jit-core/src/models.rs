@@ -0,0 +1,387 @@
+//! Typed Jira REST API responses. Fields jit does not model are either left
+//! out or, on issues, collected in [`JiraIssueFields::extra`].
+
+use crate::site_fields::STORY_POINTS_FIELD;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+pub struct JiraIssue {
+    pub id: String,
+    pub key: String,
+    pub fields: JiraIssueFields,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraIssueFields {
+    pub summary: String,
+    #[serde(default)]
+    pub status: Option<JiraStatus>,
+    #[serde(rename = "customfield_10020", default)]
+    pub sprint: Option<Vec<JiraSprint>>,
+    #[serde(default)]
+    pub description: Option<Value>,
+    #[serde(default)]
+    pub assignee: Option<JiraUser>,
+    #[serde(default)]
+    pub reporter: Option<JiraUser>,
+    #[serde(default)]
+    pub priority: Option<JiraPriority>,
+    #[serde(default)]
+    pub issuetype: Option<JiraIssueType>,
+    #[serde(default)]
+    pub created: Option<String>,
+    #[serde(default)]
+    pub updated: Option<String>,
+    #[serde(rename = "duedate", default)]
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub comment: Option<JiraCommentContainer>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Fields without a dedicated member, such as custom fields requested by ID.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl JiraIssueFields {
+    /// Fills `sprint` from `field`, on sites whose sprint field is not
+    /// `customfield_10020`.
+    pub fn take_sprint_field(&mut self, field: &str) {
+        if let Some(value) = self.extra.remove(field) {
+            self.sprint = serde_json::from_value(value).unwrap_or_default();
+        }
+    }
+
+    /// Files `field` under `customfield_10016`, on sites whose story points
+    /// field is another.
+    pub fn take_story_points_field(&mut self, field: &str) {
+        if let Some(value) = self.extra.remove(field) {
+            self.extra.insert(STORY_POINTS_FIELD.to_string(), value);
+        }
+    }
+
+    /// The issue's story points, when it was fetched with them and is estimated.
+    pub fn story_points(&self) -> Option<f64> {
+        self.extra.get(STORY_POINTS_FIELD).and_then(Value::as_f64)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraIssueLink {
+    #[serde(rename = "type")]
+    pub link_type: JiraIssueLinkType,
+    #[serde(rename = "inwardIssue", default)]
+    pub inward_issue: Option<JiraIssue>,
+    #[serde(rename = "outwardIssue", default)]
+    pub outward_issue: Option<JiraIssue>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraIssueLinkType {
+    /// How the linked issue relates to this one when it is the inward issue, e.g. "is blocked by".
+    pub inward: String,
+    pub outward: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JiraStatus {
+    pub name: String,
+    #[serde(rename = "statusCategory", default)]
+    pub status_category: Option<JiraStatusCategory>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JiraStatusCategory {
+    /// One of `new`, `indeterminate`, or `done`.
+    pub key: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JiraSprint {
+    #[serde(default)]
+    pub id: Option<u64>,
+    pub name: String,
+    pub state: String,
+}
+
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct JiraUser {
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    #[serde(rename = "accountId", default)]
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JiraPriority {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JiraIssueType {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JiraCommentContainer {
+    #[serde(default)]
+    pub comments: Vec<JiraComment>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JiraComment {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub author: Option<JiraUser>,
+    #[serde(default)]
+    pub body: Option<Value>,
+    #[serde(default)]
+    pub created: Option<String>,
+    #[serde(default)]
+    pub updated: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct JiraDevStatusResponse {
+    #[serde(default)]
+    pub detail: Vec<JiraDevStatusDetail>,
+}
+
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct JiraDevStatusDetail {
+    #[serde(rename = "pullRequests", default)]
+    pub pull_requests: Vec<JiraPullRequest>,
+    #[serde(default)]
+    pub builds: Vec<JiraBuild>,
+}
+
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct JiraBuild {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(rename = "displayName", default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub state: JiraBuildState,
+    #[serde(rename = "lastUpdated", default)]
+    pub last_updated: Option<String>,
+}
+
+/// A build's state as Jira's development panel reports it.
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JiraBuildState {
+    Successful,
+    Failed,
+    InProgress,
+    Cancelled,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct JiraPullRequest {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(rename = "lastUpdate", default)]
+    pub last_update: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JiraDevStatusSummaryResponse {
+    #[serde(default)]
+    pub summary: JiraDevStatusSummary,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JiraDevStatusSummary {
+    #[serde(default)]
+    pub build: Option<JiraBuildSummaryContainer>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct JiraBuildSummaryContainer {
+    #[serde(default)]
+    pub overall: JiraBuildSummary,
+    /// Build counts per application type, such as `cloud-providers`; the
+    /// keys are what the detail endpoint lists the builds under.
+    #[serde(rename = "byInstanceType", default)]
+    pub by_instance_type: HashMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct JiraBuildSummary {
+    #[serde(default)]
+    pub count: u64,
+    #[serde(rename = "successfulBuildCount", default)]
+    pub successful: u64,
+    #[serde(rename = "failedBuildCount", default)]
+    pub failed: u64,
+    #[serde(rename = "unknownBuildCount", default)]
+    pub unknown: u64,
+    #[serde(rename = "lastUpdated", default)]
+    pub last_updated: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraSearchResponse<T = JiraIssue> {
+    pub issues: Vec<T>,
+    #[serde(rename = "nextPageToken", default)]
+    pub next_page_token: Option<String>,
+    /// Only returned by the legacy offset-paginated /search endpoint.
+    #[serde(default)]
+    pub total: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraCountResponse {
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraWatchersResponse {
+    #[serde(default)]
+    pub watchers: Vec<JiraUser>,
+}
+
+/// A field from `/rest/api/3/field`, system or custom.
+#[derive(Debug, Deserialize, Clone)]
+pub struct JiraField {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub custom: bool,
+    #[serde(default)]
+    pub schema: Option<JiraFieldSchema>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct JiraFieldSchema {
+    #[serde(rename = "type", default)]
+    pub field_type: Option<String>,
+    /// The element type of an `array` field.
+    #[serde(default)]
+    pub items: Option<String>,
+    /// The type of a custom field, e.g. `com.pyxis.greenhopper.jira:gh-sprint`.
+    #[serde(default)]
+    pub custom: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraTransitionsResponse {
+    #[serde(default)]
+    pub transitions: Vec<JiraTransition>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraTransition {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub to: Option<JiraStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraCreatedComment {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraCreatedIssue {
+    pub id: String,
+    pub key: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraBoardPage {
+    #[serde(default)]
+    pub values: Vec<JiraBoard>,
+    #[serde(rename = "isLast", default)]
+    pub is_last: bool,
+    #[serde(rename = "maxResults", default)]
+    pub max_results: usize,
+    #[serde(rename = "startAt", default)]
+    pub start_at: usize,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct JiraBoard {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraSprintPage {
+    #[serde(default)]
+    pub values: Vec<JiraAgileSprint>,
+    #[serde(rename = "isLast", default)]
+    pub is_last: bool,
+    #[serde(rename = "maxResults", default)]
+    pub max_results: usize,
+    #[serde(rename = "startAt", default)]
+    pub start_at: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraAgileIssuePage {
+    #[serde(default)]
+    pub issues: Vec<JiraIssue>,
+    #[serde(default)]
+    pub total: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct JiraAgileSprint {
+    pub id: u64,
+    pub name: String,
+    #[serde(rename = "startDate", default)]
+    pub start_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JiraCommentPage {
+    #[serde(default)]
+    pub comments: Vec<JiraComment>,
+    #[serde(default)]
+    pub total: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BitbucketBranch {
+    pub name: String,
+    #[serde(default)]
+    pub links: BitbucketLinks,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BitbucketPullRequest {
+    pub id: u64,
+    pub title: String,
+    #[serde(default)]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub links: BitbucketLinks,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct BitbucketLinks {
+    #[serde(default)]
+    pub html: Option<BitbucketLink>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BitbucketLink {
+    pub href: String,
+}
@@ -0,0 +1,246 @@
+//! Remembers who Jira account IDs belong to, learned from the users that show
+//! up in API responses, so mentions and assignees resolve between account IDs,
+//! names, and emails without extra user-search calls.
+
+use crate::{http, jit_data_dir};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Person {
+    pub account_id: String,
+    pub display_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+/// People by account ID, per Jira instance, since each profile may point at a
+/// different site.
+type Directory = BTreeMap<String, BTreeMap<String, Person>>;
+
+struct Cache {
+    directory: Directory,
+    dirty: bool,
+}
+
+/// Loaded on first use and written back once by [`save`] at exit.
+static CACHE: Mutex<Option<Cache>> = Mutex::new(None);
+
+fn with_cache<T>(f: impl FnOnce(&mut Cache) -> T) -> Option<T> {
+    let mut cache = CACHE.lock().ok()?;
+    let cache = cache.get_or_insert_with(|| Cache {
+        directory: read_cache(),
+        dirty: false,
+    });
+    Some(f(cache))
+}
+
+/// Records every user object and mention found anywhere in a `base_url` response.
+pub fn remember(base_url: &str, response: &Value) {
+    with_cache(|cache| {
+        let people = cache.directory.entry(base_url.to_string()).or_default();
+        if learn(people, response) {
+            cache.dirty = true;
+        }
+    });
+}
+
+/// Display name for an account ID on any instance; Atlassian account IDs are
+/// unique across sites.
+pub fn display_name(account_id: &str) -> Option<String> {
+    with_cache(|cache| {
+        cache
+            .directory
+            .values()
+            .find_map(|people| people.get(account_id))
+            .map(|person| person.display_name.clone())
+    })
+    .flatten()
+}
+
+/// Finds the one person on `base_url` whose account ID, email, or display name is `query`.
+pub fn find(base_url: &str, query: &str) -> Option<Person> {
+    with_cache(|cache| {
+        cache
+            .directory
+            .get(base_url)
+            .and_then(|people| lookup(people, query))
+            .cloned()
+    })
+    .flatten()
+    .inspect(|_| http::record_cache_hit())
+}
+
+/// Writes the cache back to disk if this run learned anyone new.
+pub fn save() -> Result<()> {
+    let Some(Some(directory)) =
+        with_cache(|cache| std::mem::take(&mut cache.dirty).then(|| cache.directory.clone()))
+    else {
+        return Ok(());
+    };
+    let path = cache_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Write then rename, so concurrent runs never read a half-written file.
+    let partial = path.with_extension(format!("json.{}", std::process::id()));
+    fs::write(&partial, serde_json::to_string_pretty(&directory)?)?;
+    fs::rename(&partial, &path)?;
+    Ok(())
+}
+
+/// Adds the people in `value` to `people`, returning whether anything changed.
+fn learn(people: &mut BTreeMap<String, Person>, value: &Value) -> bool {
+    let mut changed = false;
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                changed |= learn(people, item);
+            }
+        }
+        Value::Object(object) => {
+            if let (Some(account_id), Some(display_name)) = (
+                object.get("accountId").and_then(Value::as_str),
+                object.get("displayName").and_then(Value::as_str),
+            ) && !display_name.trim().is_empty()
+            {
+                let email = object
+                    .get("emailAddress")
+                    .and_then(Value::as_str)
+                    .filter(|email| !email.is_empty());
+                changed |= insert(people, account_id, display_name.trim(), email);
+            } else if object.get("type").and_then(Value::as_str) == Some("mention")
+                && let (Some(account_id), Some(text)) = (
+                    value["attrs"]["id"].as_str(),
+                    value["attrs"]["text"].as_str(),
+                )
+                && !people.contains_key(account_id)
+            {
+                let name = text.trim().trim_start_matches('@');
+                if !name.is_empty() {
+                    changed |= insert(people, account_id, name, None);
+                }
+            }
+            for child in object.values() {
+                changed |= learn(people, child);
+            }
+        }
+        _ => {}
+    }
+    changed
+}
+
+fn insert(
+    people: &mut BTreeMap<String, Person>,
+    account_id: &str,
+    display_name: &str,
+    email: Option<&str>,
+) -> bool {
+    let person = Person {
+        account_id: account_id.to_string(),
+        display_name: display_name.to_string(),
+        // Email is often hidden by privacy settings; keep one learned earlier.
+        email: email
+            .map(str::to_string)
+            .or_else(|| people.get(account_id).and_then(|known| known.email.clone())),
+    };
+    if people.get(account_id) == Some(&person) {
+        return false;
+    }
+    people.insert(account_id.to_string(), person);
+    true
+}
+
+fn lookup<'a>(people: &'a BTreeMap<String, Person>, query: &str) -> Option<&'a Person> {
+    let query = query.trim().trim_start_matches('@');
+    if let Some(person) = people.get(query) {
+        return Some(person);
+    }
+    let mut matches = people.values().filter(|person| {
+        person.display_name.eq_ignore_ascii_case(query)
+            || person
+                .email
+                .as_deref()
+                .is_some_and(|email| email.eq_ignore_ascii_case(query))
+    });
+    let person = matches.next()?;
+    // Two people sharing a name is ambiguous, so leave it to a user search.
+    matches.next().is_none().then_some(person)
+}
+
+fn cache_path() -> Result<PathBuf> {
+    Ok(jit_data_dir()?.join("people.json"))
+}
+
+fn read_cache() -> Directory {
+    cache_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn learn_collects_users_and_mentions_from_responses() {
+        let mut people = BTreeMap::new();
+        let issue = json!({
+            "fields": {
+                "assignee": {"accountId": "a-1", "displayName": "Ada Lovelace", "emailAddress": "ada@example.com"},
+                "reporter": {"accountId": "a-2", "displayName": "Bo"},
+                "description": {"type": "doc", "content": [
+                    {"type": "mention", "attrs": {"id": "a-3", "text": "@Cy"}},
+                    {"type": "mention", "attrs": {"id": "a-1", "text": "@Ada"}}
+                ]}
+            }
+        });
+
+        assert!(learn(&mut people, &issue));
+        assert!(!learn(&mut people, &issue));
+        assert_eq!(people["a-1"].display_name, "Ada Lovelace");
+        assert_eq!(people["a-3"].display_name, "Cy");
+
+        learn(
+            &mut people,
+            &json!({"accountId": "a-1", "displayName": "Ada King"}),
+        );
+        assert_eq!(people["a-1"].email.as_deref(), Some("ada@example.com"));
+    }
+
+    #[test]
+    fn lookup_matches_account_id_email_or_unique_name() {
+        let mut people = BTreeMap::new();
+        learn(
+            &mut people,
+            &json!([
+                {"accountId": "a-1", "displayName": "Ada", "emailAddress": "ada@example.com"},
+                {"accountId": "a-2", "displayName": "Sam"},
+                {"accountId": "a-3", "displayName": "sam"}
+            ]),
+        );
+
+        assert_eq!(
+            lookup(&people, "a-2").map(|p| p.display_name.as_str()),
+            Some("Sam")
+        );
+        assert_eq!(
+            lookup(&people, "ADA@example.com").map(|p| p.account_id.as_str()),
+            Some("a-1")
+        );
+        assert_eq!(
+            lookup(&people, "@ada").map(|p| p.account_id.as_str()),
+            Some("a-1")
+        );
+        assert_eq!(lookup(&people, "Sam"), None);
+        assert_eq!(lookup(&people, "Nobody"), None);
+    }
+}
@@ -0,0 +1,232 @@
+//! The IDs of custom fields jit gives a meaning to, such as the sprint and
+//! story points fields, which differ from site to site. They are discovered from `/field` the first
+//! time a site needs them and cached in the data directory, like
+//! [`crate::capabilities`].
+
+use crate::api::fetch_fields;
+use crate::models::JiraField;
+use crate::{http, jit_data_dir};
+use anyhow::Result;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Where Jira Cloud keeps the sprint field, and the ID jit requests it by:
+/// requests for it are sent for the site's own sprint field instead.
+pub const SPRINT_FIELD: &str = "customfield_10020";
+
+/// The custom field type of Jira Software's sprint field.
+const SPRINT_FIELD_TYPE: &str = "com.pyxis.greenhopper.jira:gh-sprint";
+
+/// Where Jira Cloud keeps story points, and the ID jit requests them by, the
+/// same way as [`SPRINT_FIELD`].
+pub const STORY_POINTS_FIELD: &str = "customfield_10016";
+
+/// The custom field type of Jira Software's "Story point estimate" field.
+const STORY_POINTS_FIELD_TYPE: &str = "com.pyxis.greenhopper.jira:jsw-story-points";
+
+/// Names story points fields go by when their type does not give them away,
+/// as on Jira Server and Data Center.
+const STORY_POINTS_FIELD_NAMES: [&str; 2] = ["Story point estimate", "Story Points"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SiteFields {
+    /// The sprint field's ID, or `None` when the site has none.
+    #[serde(default)]
+    pub sprint: Option<String>,
+    /// The story points field's ID, or `None` when the site has none.
+    #[serde(default)]
+    pub story_points: Option<String>,
+}
+
+impl SiteFields {
+    fn discover(fields: &[JiraField]) -> Self {
+        let of_type = |custom_type: &str| {
+            fields.iter().find(|field| {
+                field
+                    .schema
+                    .as_ref()
+                    .and_then(|schema| schema.custom.as_deref())
+                    == Some(custom_type)
+            })
+        };
+        let named = |name: &str| {
+            fields
+                .iter()
+                .find(|field| field.custom && field.name.eq_ignore_ascii_case(name))
+        };
+        let sprint = of_type(SPRINT_FIELD_TYPE).or_else(|| named("sprint"));
+        let story_points = of_type(STORY_POINTS_FIELD_TYPE)
+            .or_else(|| STORY_POINTS_FIELD_NAMES.into_iter().find_map(named));
+        SiteFields {
+            sprint: sprint.map(|field| field.id.clone()),
+            story_points: story_points.map(|field| field.id.clone()),
+        }
+    }
+}
+
+/// Fields already resolved in this process, keyed by base URL.
+static RESOLVED: Mutex<BTreeMap<String, SiteFields>> = Mutex::new(BTreeMap::new());
+
+/// Fields named by the configuration, which win over discovered ones.
+static OVERRIDES: Mutex<BTreeMap<String, SiteFields>> = Mutex::new(BTreeMap::new());
+
+/// Uses `field` as the site's sprint field without discovering it, e.g.
+/// because the configuration names it.
+pub fn set_sprint_field(base_url: &str, field: &str) {
+    if let Ok(mut overrides) = OVERRIDES.lock() {
+        overrides.entry(base_url.to_string()).or_default().sprint = Some(field.to_string());
+    }
+}
+
+/// Uses `field` as the site's story points field without discovering it.
+pub fn set_story_points_field(base_url: &str, field: &str) {
+    if let Ok(mut overrides) = OVERRIDES.lock() {
+        overrides
+            .entry(base_url.to_string())
+            .or_default()
+            .story_points = Some(field.to_string());
+    }
+}
+
+/// The site's sprint field ID. Sites that have none, or whose fields cannot
+/// be listed, get [`SPRINT_FIELD`].
+pub fn sprint_field(client: &Client, base_url: &str) -> String {
+    overridden(base_url, |fields| fields.sprint)
+        .or_else(|| resolve(client, base_url).sprint)
+        .unwrap_or_else(|| SPRINT_FIELD.to_string())
+}
+
+/// The site's story points field ID. Sites that have none, or whose fields
+/// cannot be listed, get [`STORY_POINTS_FIELD`].
+pub fn story_points_field(client: &Client, base_url: &str) -> String {
+    overridden(base_url, |fields| fields.story_points)
+        .or_else(|| resolve(client, base_url).story_points)
+        .unwrap_or_else(|| STORY_POINTS_FIELD.to_string())
+}
+
+fn overridden(base_url: &str, field: impl FnOnce(SiteFields) -> Option<String>) -> Option<String> {
+    OVERRIDES
+        .lock()
+        .ok()
+        .and_then(|overrides| overrides.get(base_url).cloned())
+        .and_then(field)
+}
+
+/// The site's fields from this process, the on-disk cache, or else `/field`.
+fn resolve(client: &Client, base_url: &str) -> SiteFields {
+    if let Some(fields) = RESOLVED
+        .lock()
+        .ok()
+        .and_then(|resolved| resolved.get(base_url).cloned())
+    {
+        return fields;
+    }
+
+    let mut cache = read_cache();
+    let fields = match cache.get(base_url) {
+        Some(fields) => {
+            http::record_cache_hit();
+            fields.clone()
+        }
+        None => match fetch_fields(client, base_url) {
+            Ok(found) => {
+                let fields = SiteFields::discover(&found);
+                cache.insert(base_url.to_string(), fields.clone());
+                // The cache only saves a lookup next time, so a failed write is not an error.
+                let _ = write_cache(&cache);
+                fields
+            }
+            // Not cached, so the next run tries again; this one keeps the default.
+            Err(_) => SiteFields::default(),
+        },
+    };
+
+    if let Ok(mut resolved) = RESOLVED.lock() {
+        resolved.insert(base_url.to_string(), fields.clone());
+    }
+    fields
+}
+
+fn cache_path() -> Result<PathBuf> {
+    Ok(jit_data_dir()?.join("fields.json"))
+}
+
+fn read_cache() -> BTreeMap<String, SiteFields> {
+    cache_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_cache(cache: &BTreeMap<String, SiteFields>) -> Result<()> {
+    let path = cache_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(cache)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(value: serde_json::Value) -> JiraField {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn discover_finds_the_sprint_field_by_type_then_name() {
+        let fields = [
+            field(serde_json::json!({"id": "summary", "name": "Summary", "custom": false})),
+            field(serde_json::json!({
+                "id": "customfield_10104",
+                "name": "Iteration",
+                "custom": true,
+                "schema": {"type": "array", "items": "json", "custom": SPRINT_FIELD_TYPE},
+            })),
+        ];
+        assert_eq!(
+            SiteFields::discover(&fields).sprint.as_deref(),
+            Some("customfield_10104")
+        );
+
+        let by_name = [field(
+            serde_json::json!({"id": "customfield_10300", "name": "Sprint", "custom": true}),
+        )];
+        assert_eq!(
+            SiteFields::discover(&by_name).sprint.as_deref(),
+            Some("customfield_10300")
+        );
+        assert_eq!(SiteFields::discover(&fields[..1]).sprint, None);
+    }
+
+    #[test]
+    fn discover_finds_the_story_points_field_by_type_then_name() {
+        let fields = [
+            field(
+                serde_json::json!({"id": "customfield_10028", "name": "Story Points", "custom": true}),
+            ),
+            field(serde_json::json!({
+                "id": "customfield_10016",
+                "name": "Story point estimate",
+                "custom": true,
+                "schema": {"type": "number", "custom": STORY_POINTS_FIELD_TYPE},
+            })),
+        ];
+        assert_eq!(
+            SiteFields::discover(&fields).story_points.as_deref(),
+            Some("customfield_10016")
+        );
+        assert_eq!(
+            SiteFields::discover(&fields[..1]).story_points.as_deref(),
+            Some("customfield_10028")
+        );
+        assert_eq!(SiteFields::discover(&[]).story_points, None);
+    }
+}
@@ -0,0 +1,273 @@
+//! Downloads issue attachments into an archive directory a few at a time,
+//! recording each file's size and SHA-256 in `manifest.json` so an
+//! interrupted run skips what it already has.
+
+use crate::error;
+use anyhow::{Context, Result, anyhow};
+use jit_core::api;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+const ATTEMPTS: u32 = 3;
+
+/// An attachment referenced by an exported issue.
+#[derive(Debug, Clone)]
+pub(crate) struct Attachment {
+    pub(crate) id: String,
+    pub(crate) issue: String,
+    pub(crate) filename: String,
+    pub(crate) size: Option<u64>,
+    pub(crate) url: String,
+}
+
+/// A downloaded attachment, as recorded in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Downloaded {
+    pub(crate) issue: String,
+    pub(crate) filename: String,
+    /// Relative to the archive directory.
+    pub(crate) path: String,
+    pub(crate) size: u64,
+    pub(crate) sha256: String,
+}
+
+/// Downloaded attachments by attachment ID.
+type Manifest = BTreeMap<String, Downloaded>;
+
+#[derive(Debug, Default)]
+pub(crate) struct Summary {
+    pub(crate) downloaded: usize,
+    pub(crate) skipped: usize,
+}
+
+/// Downloads every attachment not already in `dir`'s manifest, running at most
+/// `concurrency` downloads at once. Failed downloads are reported together
+/// after the rest finish, so a rerun only has to fetch those.
+pub(crate) fn download_all(
+    client: &Client,
+    dir: &Path,
+    attachments: &[Attachment],
+    concurrency: usize,
+) -> Result<Summary> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let manifest = read_manifest(dir)?;
+    let (done, pending): (Vec<&Attachment>, Vec<&Attachment>) = attachments
+        .iter()
+        .partition(|attachment| is_intact(dir, &manifest, &attachment.id));
+
+    let manifest = Mutex::new(manifest);
+    let failures = Mutex::new(Vec::new());
+    let next = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, pending.len().max(1)) {
+            scope.spawn(|| {
+                while let Some(attachment) = pending.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let result = download_with_retries(client, dir, attachment).and_then(|entry| {
+                        let mut manifest = manifest
+                            .lock()
+                            .map_err(|_| anyhow!("attachment manifest lock was poisoned"))?;
+                        manifest.insert(attachment.id.clone(), entry);
+                        write_manifest(dir, &manifest)
+                    });
+                    let count = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                    match result {
+                        Ok(()) => eprintln!(
+                            "Downloaded {}/{} ({}/{})",
+                            attachment.issue,
+                            attachment.filename,
+                            count,
+                            pending.len()
+                        ),
+                        Err(err) => {
+                            eprintln!(
+                                "Failed to download {}/{}: {:#}",
+                                attachment.issue, attachment.filename, err
+                            );
+                            if let Ok(mut failures) = failures.lock() {
+                                failures.push(attachment.id.clone());
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    let failures = failures.into_inner().unwrap_or_default();
+    if !failures.is_empty() {
+        return Err(anyhow!(
+            "{} of {} attachment(s) failed to download. Run the export again to retry them.",
+            failures.len(),
+            pending.len()
+        ));
+    }
+    Ok(Summary {
+        downloaded: pending.len(),
+        skipped: done.len(),
+    })
+}
+
+fn download_with_retries(
+    client: &Client,
+    dir: &Path,
+    attachment: &Attachment,
+) -> Result<Downloaded> {
+    let mut attempt = 1;
+    loop {
+        match download(client, dir, attachment) {
+            Err(err) if attempt < ATTEMPTS && error::is_retryable(&err) => {
+                thread::sleep(
+                    error::retry_after(&err).unwrap_or(Duration::from_secs(2 * u64::from(attempt))),
+                );
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn download(client: &Client, dir: &Path, attachment: &Attachment) -> Result<Downloaded> {
+    let bytes = api::fetch_attachment_content(client, &attachment.url)?;
+    if let Some(size) = attachment.size
+        && size != bytes.len() as u64
+    {
+        return Err(anyhow!(
+            "expected {} bytes but received {}",
+            size,
+            bytes.len()
+        ));
+    }
+
+    let relative = attachment_path(attachment);
+    let path = dir.join(&relative);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Write then rename, so an interrupted download never looks complete.
+    let partial = path.with_extension("partial");
+    fs::write(&partial, &bytes)
+        .with_context(|| format!("Failed to write {}", partial.display()))?;
+    fs::rename(&partial, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(Downloaded {
+        issue: attachment.issue.clone(),
+        filename: attachment.filename.clone(),
+        path: relative.to_string_lossy().into_owned(),
+        size: bytes.len() as u64,
+        sha256: sha256_hex(&bytes),
+    })
+}
+
+/// Where an attachment is stored, e.g. `OLD-1/10001-spec.pdf`. The ID prefix
+/// keeps two attachments with the same name apart.
+fn attachment_path(attachment: &Attachment) -> PathBuf {
+    let filename: String = attachment
+        .filename
+        .chars()
+        .map(|c| {
+            if c == '/' || c == '\\' || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    Path::new(&attachment.issue).join(format!("{}-{}", attachment.id, filename))
+}
+
+/// Whether the manifest lists `id` and its file is still on disk unchanged.
+fn is_intact(dir: &Path, manifest: &Manifest, id: &str) -> bool {
+    manifest.get(id).is_some_and(|entry| {
+        fs::read(dir.join(&entry.path)).is_ok_and(|bytes| {
+            bytes.len() as u64 == entry.size && sha256_hex(&bytes) == entry.sha256
+        })
+    })
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn manifest_path(dir: &Path) -> PathBuf {
+    dir.join("manifest.json")
+}
+
+fn read_manifest(dir: &Path) -> Result<Manifest> {
+    let path = manifest_path(dir);
+    match fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display())),
+        Err(_) => Ok(Manifest::new()),
+    }
+}
+
+fn write_manifest(dir: &Path, manifest: &Manifest) -> Result<()> {
+    let path = manifest_path(dir);
+    let partial = path.with_extension("json.partial");
+    fs::write(&partial, serde_json::to_string_pretty(manifest)?)?;
+    fs::rename(&partial, &path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(id: &str, filename: &str) -> Attachment {
+        Attachment {
+            id: id.to_string(),
+            issue: "OLD-1".to_string(),
+            filename: filename.to_string(),
+            size: None,
+            url: String::new(),
+        }
+    }
+
+    #[test]
+    fn attachment_path_prefixes_id_and_strips_separators() {
+        assert_eq!(
+            attachment_path(&attachment("10", "spec.pdf")),
+            Path::new("OLD-1").join("10-spec.pdf")
+        );
+        assert_eq!(
+            attachment_path(&attachment("11", "../etc/passwd")),
+            Path::new("OLD-1").join("11-.._etc_passwd")
+        );
+    }
+
+    #[test]
+    fn is_intact_checks_size_and_checksum() {
+        let dir = std::env::temp_dir().join(format!("jit-attachments-{}", std::process::id()));
+        fs::create_dir_all(dir.join("OLD-1")).unwrap();
+        fs::write(dir.join("OLD-1/10-a.txt"), "hello").unwrap();
+        let mut manifest = Manifest::new();
+        manifest.insert(
+            "10".to_string(),
+            Downloaded {
+                issue: "OLD-1".to_string(),
+                filename: "a.txt".to_string(),
+                path: "OLD-1/10-a.txt".to_string(),
+                size: 5,
+                sha256: sha256_hex(b"hello"),
+            },
+        );
+
+        assert!(is_intact(&dir, &manifest, "10"));
+        assert!(!is_intact(&dir, &manifest, "11"));
+        fs::write(dir.join("OLD-1/10-a.txt"), "jello").unwrap();
+        assert!(!is_intact(&dir, &manifest, "10"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,279 @@
+//! Jira API tokens kept out of the config file: in the OS keyring (macOS
+//! Keychain, Windows Credential Manager, or the Secret Service on Linux), or
+//! in the secret store picked by `auth.source`. A config without `api_token`
+//! reads the token saved by `jit auth login`.
+
+use crate::error::JitError;
+use anyhow::Result;
+use keyring::Entry;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+/// Set to skip the keyring and keep the token in the config file.
+const DISABLE_VAR: &str = "JIT_AUTH_NO_KEYRING";
+
+/// Whether tokens may be stored in the keyring.
+pub(crate) fn keyring_enabled() -> bool {
+    std::env::var_os(DISABLE_VAR).is_none()
+}
+
+/// One entry per site and account, so profiles for different sites coexist.
+fn entry(base_url: &str, user_email: &str) -> keyring::Result<Entry> {
+    Entry::new(
+        &format!("jit {}", base_url.trim_end_matches('/')),
+        user_email,
+    )
+}
+
+/// Saves `token`, failing when no keyring is available.
+pub(crate) fn store_token(base_url: &str, user_email: &str, token: &str) -> Result<()> {
+    entry(base_url, user_email)?.set_password(token)?;
+    Ok(())
+}
+
+/// The saved token, or `None` when there is none or no keyring to ask.
+pub(crate) fn load_token(base_url: &str, user_email: &str) -> Option<String> {
+    if !keyring_enabled() {
+        return None;
+    }
+    entry(base_url, user_email)
+        .and_then(|entry| entry.get_password())
+        .ok()
+}
+
+/// Removes a saved token, e.g. after switching back to a plaintext config.
+pub(crate) fn delete_token(base_url: &str, user_email: &str) {
+    if keyring_enabled()
+        && let Ok(entry) = entry(base_url, user_email)
+    {
+        let _ = entry.delete_credential();
+    }
+}
+
+/// Where `auth.source` says the API token lives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SecretSource {
+    /// `op read <item>`, with `item` a secret reference like `op://Work/Jira/token`.
+    #[serde(rename = "1password")]
+    OnePassword,
+    /// The first line of `pass show <item>`.
+    Pass,
+    /// `vault kv get -field=<field> <item>`.
+    Vault,
+    /// The token `jit auth login` saved in the OS keyring.
+    Keyring,
+    /// An environment variable, `JIRA_API_TOKEN` unless `variable` names another.
+    Env,
+}
+
+impl SecretSource {
+    fn label(self) -> &'static str {
+        match self {
+            SecretSource::OnePassword => "1Password",
+            SecretSource::Pass => "pass",
+            SecretSource::Vault => "Vault",
+            SecretSource::Keyring => "the OS keyring",
+            SecretSource::Env => "the environment",
+        }
+    }
+}
+
+/// The `[auth]` section.
+#[derive(Debug, Deserialize, Default)]
+pub(crate) struct AuthConfig {
+    #[serde(default)]
+    pub(crate) source: Option<SecretSource>,
+    /// 1Password secret reference, pass entry, or Vault secret path.
+    #[serde(default)]
+    pub(crate) item: Option<String>,
+    /// Field of the Vault secret that holds the token (default `token`).
+    #[serde(default)]
+    pub(crate) field: Option<String>,
+    /// Environment variable read by `source = "env"` (default `JIRA_API_TOKEN`).
+    #[serde(default)]
+    pub(crate) variable: Option<String>,
+}
+
+impl AuthConfig {
+    fn field(&self) -> &str {
+        self.field.as_deref().unwrap_or("token")
+    }
+
+    fn variable(&self) -> &str {
+        self.variable.as_deref().unwrap_or("JIRA_API_TOKEN")
+    }
+}
+
+/// Where the API token in use came from, so a rejected one can be replaced
+/// in the same place.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) enum TokenOrigin {
+    /// `key` (such as `jira.api_token`) in the config file at `path`.
+    File { path: PathBuf, key: String },
+    /// The entry `jit auth login` saved in the OS keyring.
+    #[default]
+    Keyring,
+    /// The secret store `auth.source` names, at `auth.item`.
+    Source(SecretSource, String),
+    /// Somewhere jit only reads, such as an environment variable.
+    External(String),
+}
+
+impl fmt::Display for TokenOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenOrigin::File { path, key } => write!(f, "{} in {}", key, path.display()),
+            TokenOrigin::Keyring => write!(f, "the OS keyring"),
+            TokenOrigin::Source(source, item) => write!(f, "{} ({})", source.label(), item),
+            TokenOrigin::External(place) => write!(f, "{}", place),
+        }
+    }
+}
+
+/// Tokens already read from a secret store, so one command asks 1Password or
+/// Vault once no matter how many times the config is loaded.
+static RESOLVED: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// The API token from `auth.source`, read once per process.
+pub(crate) fn resolve_token(
+    auth: &AuthConfig,
+    source: SecretSource,
+    base_url: &str,
+    user_email: &str,
+) -> Result<String> {
+    let key = format!(
+        "{:?} {} {} {} {}",
+        source,
+        auth.item.as_deref().unwrap_or_default(),
+        auth.field(),
+        base_url,
+        user_email
+    );
+    if let Some(token) = RESOLVED
+        .lock()
+        .ok()
+        .and_then(|resolved| resolved.as_ref()?.get(&key).cloned())
+    {
+        return Ok(token);
+    }
+    let token = read_token(auth, source, base_url, user_email)?;
+    if let Ok(mut resolved) = RESOLVED.lock() {
+        resolved
+            .get_or_insert_with(HashMap::new)
+            .insert(key, token.clone());
+    }
+    Ok(token)
+}
+
+fn read_token(
+    auth: &AuthConfig,
+    source: SecretSource,
+    base_url: &str,
+    user_email: &str,
+) -> Result<String> {
+    let item = || {
+        auth.item.as_deref().ok_or_else(|| {
+            JitError::Config(format!(
+                "auth.source = \"{}\" needs auth.item to say which secret holds the Jira API token.",
+                match source {
+                    SecretSource::OnePassword => "1password",
+                    SecretSource::Pass => "pass",
+                    _ => "vault",
+                }
+            ))
+        })
+    };
+    let token = match source {
+        SecretSource::OnePassword => run_secret_command(source, "op", &["read", item()?])?,
+        SecretSource::Pass => run_secret_command(source, "pass", &["show", item()?])?
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        SecretSource::Vault => run_secret_command(
+            source,
+            "vault",
+            &["kv", "get", &format!("-field={}", auth.field()), item()?],
+        )?,
+        SecretSource::Keyring => load_token(base_url, user_email).unwrap_or_default(),
+        SecretSource::Env => std::env::var(auth.variable()).unwrap_or_default(),
+    };
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        let location = match source {
+            SecretSource::Keyring => format!(
+                "for {} at {}. Run `jit auth login` to save one",
+                user_email, base_url
+            ),
+            SecretSource::Env => format!("variable {}", auth.variable()),
+            _ => format!("at {}", auth.item.as_deref().unwrap_or_default()),
+        };
+        return Err(JitError::Config(format!(
+            "No Jira API token in {} {}.",
+            source.label(),
+            location
+        ))
+        .into());
+    }
+    Ok(token)
+}
+
+/// The command's stdout. Its stdin and stderr stay on the terminal so the
+/// secret store can ask to be unlocked.
+fn run_secret_command(source: SecretSource, program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|err| {
+            JitError::Config(format!(
+                "Could not run `{}` to read the Jira API token from {}: {}",
+                program,
+                source.label(),
+                err
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(JitError::Config(format!(
+            "`{} {}` failed with {} while reading the Jira API token from {}.",
+            program,
+            args.join(" "),
+            output.status,
+            source.label()
+        ))
+        .into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_env_token_names_the_variable() {
+        let auth: AuthConfig =
+            toml::from_str("source = \"env\"\nvariable = \"JIT_CREDENTIALS_TEST_UNSET\"").unwrap();
+
+        let err =
+            resolve_token(&auth, SecretSource::Env, "https://a.test", "me@a.test").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No Jira API token in the environment variable JIT_CREDENTIALS_TEST_UNSET."
+        );
+    }
+
+    #[test]
+    fn secret_stores_need_an_item() {
+        let auth: AuthConfig = toml::from_str("source = \"1password\"").unwrap();
+        assert_eq!(auth.source, Some(SecretSource::OnePassword));
+        let err = read_token(&auth, SecretSource::OnePassword, "https://a.test", "").unwrap_err();
+        assert!(err.to_string().contains("needs auth.item"));
+    }
+}
@@ -0,0 +1,129 @@
+//! `--vscode-links`: file references such as `src/foo.rs:42` in descriptions
+//! and comments become `vscode://file/...` links that open the file at that
+//! line. Only files that exist in the current repository are linked, so
+//! `example.com:8080` and the like are left alone.
+
+use anyhow::Result;
+use regex::{Captures, Regex};
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub(crate) struct EditorLinks {
+    root: PathBuf,
+    /// Wrap references in OSC 8 terminal hyperlinks instead of printing the URL.
+    hyperlinks: bool,
+    reference: Regex,
+}
+
+impl EditorLinks {
+    /// Links resolved against the enclosing git work tree, or the current
+    /// directory outside one.
+    pub(crate) fn for_current_dir() -> Result<Self> {
+        let top = Command::new("git")
+            .args(["rev-parse", "--show-toplevel"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .filter(|top| !top.is_empty());
+        let root = match top {
+            Some(top) => PathBuf::from(top),
+            None => std::env::current_dir()?,
+        };
+        let hyperlinks = io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        Ok(Self::new(root, hyperlinks))
+    }
+
+    fn new(root: PathBuf, hyperlinks: bool) -> Self {
+        EditorLinks {
+            root,
+            hyperlinks,
+            reference: Regex::new(
+                r"(^|[\s(\[`'\x22])((?:[\w.-]+/)*[\w.-]*\w\.\w+):(\d+)(?::(\d+))?\b",
+            )
+            .expect("file reference regex is valid"),
+        }
+    }
+
+    /// `text` with every reference to an existing file linked.
+    pub(crate) fn apply(&self, text: &str) -> String {
+        self.reference
+            .replace_all(text, |captures: &Captures| {
+                let whole = &captures[0];
+                let prefix = &captures[1];
+                let reference = &whole[prefix.len()..];
+                match self.url(
+                    &captures[2],
+                    &captures[3],
+                    captures.get(4).map(|m| m.as_str()),
+                ) {
+                    Some(url) if self.hyperlinks => {
+                        format!("{}\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", prefix, url, reference)
+                    }
+                    Some(url) => format!("{}{} ({})", prefix, reference, url),
+                    None => whole.to_string(),
+                }
+            })
+            .into_owned()
+    }
+
+    fn url(&self, path: &str, line: &str, column: Option<&str>) -> Option<String> {
+        let file = self.root.join(path.trim_start_matches("./"));
+        if !file.is_file() {
+            return None;
+        }
+        let file = file.canonicalize().unwrap_or(file);
+        let mut url = format!("vscode://file{}:{}", url_path(&file), line);
+        if let Some(column) = column {
+            url.push_str(&format!(":{}", column));
+        }
+        Some(url)
+    }
+}
+
+/// The path part of a `vscode://file` URL: `/home/me/x.rs`, or `/C:/x.rs` on Windows.
+fn url_path(file: &Path) -> String {
+    let path = file.to_string_lossy().replace('\\', "/");
+    let path = path.strip_prefix("//?/").unwrap_or(&path);
+    if path.starts_with('/') {
+        path.replace(' ', "%20")
+    } else {
+        format!("/{}", path.replace(' ', "%20"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn apply_links_references_to_existing_files_only() {
+        let root = std::env::temp_dir().join(format!("jit-editor-links-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/foo.rs"), "").unwrap();
+        let root = root.canonicalize().unwrap();
+        let links = EditorLinks::new(root.clone(), false);
+
+        let linked =
+            links.apply("Crash in src/foo.rs:42:7, see (src/bar.rs:1) and example.com:8080");
+        assert_eq!(
+            linked,
+            format!(
+                "Crash in src/foo.rs:42:7 (vscode://file{}/src/foo.rs:42:7), see (src/bar.rs:1) and example.com:8080",
+                url_path(&root)
+            )
+        );
+
+        let hyperlinked = EditorLinks::new(root.clone(), true).apply("`src/foo.rs:3`");
+        assert_eq!(
+            hyperlinked,
+            format!(
+                "`\x1b]8;;vscode://file{}/src/foo.rs:3\x1b\\src/foo.rs:3\x1b]8;;\x1b\\`",
+                url_path(&root)
+            )
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,23 +1,69 @@
 // src/main.rs
 use anyhow::{Context, Result, anyhow};
-use base64::{Engine as _, engine::general_purpose::STANDARD};
-use chrono::{DateTime, FixedOffset};
-use clap::{Args, Parser, Subcommand};
+use chrono::{DateTime, FixedOffset, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::*;
 use regex::Regex;
 use reqwest::blocking::{Client, ClientBuilder};
 use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, ExitCode, Stdio};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use unicode_width::UnicodeWidthStr;
+
+use jit_core::adf::{
+    adf_to_markdown, adf_value_to_display_text, markdown_to_adf, render_adf_inline_content,
+    text_to_adf,
+};
+use jit_core::api::{
+    add_issue_comment, add_issue_to_sprint, add_issue_watcher, add_issue_worklog, assign_issue,
+    create_bitbucket_branch, create_bitbucket_pull_request, create_jira_issue_payload,
+    fetch_agile_issues, fetch_all_comment_values, fetch_board, fetch_board_sprints,
+    fetch_current_user, fetch_dev_status_detail, fetch_dev_status_summary, fetch_fields,
+    fetch_issue_comments, fetch_issue_field, fetch_issue_transitions, fetch_issue_watchers,
+    fetch_jira_issue_fields, fetch_scrum_boards_for_project, issue_fields, link_issues,
+    move_issues_to_sprint, search_raw_page, search_users, transition_issue,
+    transition_issue_with_fields, update_jira_issue_payload, validate_jira_authentication,
+};
+use jit_core::client::{
+    HttpConfig, JiraApi, JiraClient, create_basic_auth_client, create_jira_client,
+};
+use jit_core::models::*;
+use jit_core::site_fields::{self, SPRINT_FIELD, STORY_POINTS_FIELD};
+use jit_core::{error, http, jit_data_dir, people};
+
+mod attachments;
+mod credentials;
+mod editor_links;
+mod porcelain;
+mod reminders;
+mod render;
+mod serve;
+mod settings;
+mod stacktrace;
+mod status_cache;
+
+use credentials::TokenOrigin;
+use editor_links::EditorLinks;
+use error::JitError;
+use porcelain::{Porcelain, PorcelainVersion};
+use render::{
+    IssueColumn, IssueGrouping, IssueListView, IssueSections, IssueTableStream, IssueView,
+    LayoutField, OutputFormat, OutputTemplate, RecordOutput, TableOutput, issue_sprint_name,
+    print_box_table, render_formatted, render_output, sparkline,
+};
 
 const DEFAULT_TICKET_LIMIT: u32 = 50;
 
+const API_TOKEN_URL: &str = "https://id.atlassian.com/manage-profile/security/api-tokens";
+
 #[derive(Parser, Debug)]
 #[clap(author, version, about)]
 struct Cli {
@@ -26,3303 +72,14749 @@ struct Cli {
 
     #[command(flatten)]
     query: QueryArgs,
+
+    /// Output format for command results (default: format from the [output] config section, or table)
+    #[clap(long, value_enum, global = true)]
+    output: Option<OutputFormat>,
+
+    /// Print API call count, bytes transferred, cache hits, and timing to stderr
+    #[clap(long, global = true)]
+    stats: bool,
+
+    /// Print tab-separated lines in a versioned layout that stays stable for scripts (jit, jit ISSUE, jit current)
+    #[clap(long, value_enum, global = true, num_args = 0..=1, require_equals = true, default_missing_value = "v1")]
+    porcelain: Option<PorcelainVersion>,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
-    /// Configure Jira credentials interactively
-    Auth,
+    /// Configure Jira credentials interactively, saving the API token in the OS keyring
+    Auth(AuthArgs),
+    /// Set up jit for the first time: Jira credentials, a default project, and a connection check
+    Init,
     /// Create a new Jira issue without sprint assignment so it lands in the backlog on scrum boards
     Create(CreateArgs),
     /// Edit an existing Jira ticket's core fields, including Task issues
     Edit(EditArgs),
     /// Manage the shared jit agent skill (SKILL.md) installed for coding agents like Codex and Claude Code
     Skill(SkillArgs),
+    /// Create ticket branches and pull requests on Bitbucket Cloud
+    Bitbucket(BitbucketArgs),
+    /// Transition the tickets referenced by a pull request based on its lifecycle event (for CI pipelines)
+    SyncPr(SyncPrArgs),
+    /// Block until a ticket reaches a status, exiting non-zero on timeout (for deployment pipelines)
+    Wait(WaitArgs),
+    /// Exit non-zero when more issues match a JQL query than allowed (a release gate for CI pipelines)
+    Gate(GateArgs),
+    /// Record your approval on a ticket using the configured approval field and transition
+    Approve(ApproveArgs),
+    /// Ask approvers to sign off on a ticket with a mention comment and the configured transition
+    RequestApproval(RequestApprovalArgs),
+    /// Show a ticket's checklist, or toggle one of its items
+    Check(CheckArgs),
+    /// Step through issues matching a JQL query with single-key triage actions
+    Triage(TriageArgs),
+    /// Run an estimation session that writes story points to unestimated issues
+    Poker(PokerArgs),
+    /// Report backlog issues that need attention before a grooming session
+    Groom(GroomArgs),
+    /// Check issues against hygiene rules and report violations
+    Lint(LintArgs),
+    /// Validate a ticket against the definition-of-done checklist
+    Dod(DodArgs),
+    /// Move a ticket to done once it passes the definition of done
+    Done(DoneArgs),
+    /// Close a ticket as a duplicate of another: link them, resolve it as Duplicate, and comment
+    Dup(DupArgs),
+    /// Carry a duplicate's comments, attachments, and watchers over to the original, then close it
+    Merge(MergeArgs),
+    /// Split a story into new issues from the checklist items or description sections you pick
+    Split(SplitArgs),
+    /// Show created vs. resolved counts, open bug trend, and top contributors for a project
+    Stats(StatsArgs),
+    /// Record today's open-issue counts per status and assignee for `jit stats --trend`
+    Snapshot(SnapshotArgs),
+    /// Show open issues by status and age as a terminal heatmap
+    Heatmap(HeatmapArgs),
+    /// Print a ticket as markdown for pasting elsewhere, optionally with people and secrets redacted
+    Share(ShareArgs),
+    /// Summarize a ticket's description and comments with the LLM configured in [llm]
+    Summarize(SummarizeArgs),
+    /// Post a comment on a ticket, optionally from a [snippets] template
+    Comment(CommentArgs),
+    /// Apply smart-commit directives (#comment, #time, #<transition>) from a git commit range (for CI pipelines)
+    ProcessCommits(ProcessCommitsArgs),
+    /// Show every ticket in a fix version grouped by component or repository, with PR merge status
+    Train(TrainArgs),
+    /// Check the commits in a git range before a release: tickets not done, tickets missing the fix version, and commits without a ticket
+    VerifyRelease(VerifyReleaseArgs),
+    /// Comment a deployment note on tickets or a whole fix version, and record the environment
+    Deployed(DeployedArgs),
+    /// Invoke a Jira Automation incoming webhook configured in [webhooks]
+    Trigger(TriggerArgs),
+    /// Keep polling Jira and run the [[watch]] rules from the config when their conditions are met
+    Watch(WatchArgs),
+    /// List a ticket's comments with their authors and how long ago they were posted
+    Comments(CommentsArgs),
+    /// Show the open workload of each member of a configured team
+    Team(TeamArgs),
+    /// Move a ticket to another status, or list the transitions available to it
+    #[command(visible_alias = "transition")]
+    Move(MoveArgs),
+    /// Report the issues a project's open tickets are linked to, grouped by owning project and assignee
+    Deps(DepsArgs),
+    /// Assign a ticket to yourself or someone else, or clear its assignee
+    Assign(AssignArgs),
+    /// List the issues matching any JQL query
+    Search(SearchArgs),
+    /// Dump every issue matching a JQL query to a file, resuming where an interrupted export stopped
+    Export(ExportArgs),
+    /// Open a ticket in the browser, detecting it from the current git branch if not given
+    Open(OpenArgs),
+    /// Create and check out a git branch named after a ticket
+    Branch(BranchArgs),
+    /// List local git branches with their tickets' status, or clean up the ones whose tickets are done
+    Branches(BranchesArgs),
+    /// Check out a ticket's branch in its own git worktree, or remove the worktrees of done tickets
+    Worktree(WorktreeArgs),
+    /// Plan the next sprint interactively, moving issues between it and the backlog
+    Plan(PlanArgs),
+    /// Create an issue assigned to you in the active sprint of the default project
+    Quick(QuickArgs),
+    /// Start work on a ticket: assign it to you, move it to In Progress, and create its branch
+    Start(StartArgs),
+    /// Install or remove a git hook that prefixes commit messages with the branch's ticket key
+    Hook(HookArgs),
+    /// Set a local reminder about a ticket, shown by the first jit run after it is due
+    Remind(RemindArgs),
+    /// Mark a ticket as your current focus
+    Focus(FocusArgs),
+    /// Show the ticket you are focused on
+    Current,
+    /// Read and write config.toml settings
+    Config(ConfigArgs),
+    /// Print a one-line ticket summary for tmux or starship, from the local cache only
+    Statusline(StatuslineArgs),
+    /// Print the current branch's ticket status as a colored shell prompt segment
+    Prompt(PromptArgs),
+    /// Serve get issue, search, transition, and comment over JSON-RPC for editor plugins
+    Serve(ServeArgs),
+    /// List the stack trace frames in a ticket that point at files in this repository
+    Trace(TraceArgs),
+    /// List the site's fields with their IDs, names, and whether they are custom
+    Fields(FieldsArgs),
 }
 
 #[derive(Args, Debug)]
-struct SkillArgs {
-    #[command(subcommand)]
-    command: SkillCommands,
-}
-
-#[derive(Subcommand, Debug)]
-enum SkillCommands {
-    /// Download the latest SKILL.md and install it for all known agents (Codex and Claude Code)
-    Install(SkillInstallArgs),
+struct PromptArgs {
+    /// Prompt framework whose color escapes to use
+    #[clap(long, value_enum, default_value_t = PromptFormat::Starship)]
+    format: PromptFormat,
 }
 
 #[derive(Args, Debug)]
-struct SkillInstallArgs {
-    /// Target a specific agent instead of installing for all known agents (codex | claude)
+struct ServeArgs {
+    /// Read requests from stdin and write responses to stdout, one JSON object per line
     #[clap(long)]
-    agent: Option<String>,
+    stdio: bool,
 }
 
 #[derive(Args, Debug)]
-struct QueryArgs {
-    /// JIRA issue key (e.g., RW-1931) or URL (e.g., https://company.atlassian.net/browse/RW-1931)
+struct TraceArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
     ticket: Option<String>,
 
-    /// Output in JSON format
+    /// Open the top frame in $VISUAL / $EDITOR at its line
     #[clap(long)]
-    json: bool,
+    open: bool,
+}
 
-    /// Output as plain text in format "KEY: Summary"
-    #[clap(long)]
-    text: bool,
+#[derive(Args, Debug)]
+struct FieldsArgs {
+    /// Only list fields whose ID or name contains this text (case-insensitive)
+    query: Option<String>,
 
-    /// Display your current tickets in a table (default when no ticket is provided)
+    /// Only list custom fields
     #[clap(long)]
-    my_tickets: bool,
+    custom: bool,
+}
 
-    /// Show detailed information about a ticket in a table format
-    #[clap(long)]
-    show: bool,
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum PromptFormat {
+    /// ANSI escapes, which starship wraps for the shell itself
+    Starship,
+    /// zsh prompt escapes (`%F{color}`), for `p10k segment -e`
+    Powerlevel10k,
+}
 
-    /// Include ticket description in detailed output
-    #[clap(long)]
-    include_description: bool,
+#[derive(Args, Debug)]
+struct StatuslineArgs {
+    /// Fetch a ticket's status into the cache (run in the background by jit itself)
+    #[clap(long, hide = true, value_name = "TICKET")]
+    refresh: Option<String>,
+}
 
-    /// Include ticket comments in detailed output
-    #[clap(long)]
-    include_comments: bool,
+#[derive(Args, Debug)]
+struct ConfigArgs {
+    #[command(subcommand)]
+    command: ConfigCommands,
+}
 
-    /// Include linked GitHub pull requests
-    #[clap(long)]
-    include_prs: bool,
+#[derive(Subcommand, Debug)]
+enum ConfigCommands {
+    /// Print one setting, such as jira.base_url
+    Get(ConfigGetArgs),
+    /// Change one setting in the config file
+    Set(ConfigSetArgs),
+    /// List every setting and where it comes from
+    List,
+}
 
-    /// Include description, comments, pull requests, and metadata in detailed output
-    #[clap(long)]
-    full: bool,
+#[derive(Args, Debug)]
+struct ConfigGetArgs {
+    /// Dotted setting name, such as jira.base_url
+    key: String,
+}
 
-    /// Maximum number of comments to show (default: 5)
-    #[clap(long, default_value = "5")]
-    comments_limit: usize,
+#[derive(Args, Debug)]
+struct ConfigSetArgs {
+    /// Dotted setting name, such as jira.base_url
+    key: String,
 
-    /// Show all comments (overrides --comments-limit)
-    #[clap(long)]
-    all_comments: bool,
+    /// New value; numbers, booleans, and [arrays] are stored as TOML values
+    value: String,
+}
 
-    /// Only include comments created on or after YYYY-MM-DD
-    #[clap(long)]
-    since: Option<String>,
+#[derive(Args, Debug)]
+struct FocusArgs {
+    /// Ticket key or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-    /// Maximum number of tickets to retrieve
-    #[clap(long, default_value_t = DEFAULT_TICKET_LIMIT)]
-    limit: u32,
+    /// Stop focusing on any ticket
+    #[clap(long, conflicts_with = "ticket")]
+    clear: bool,
+}
 
-    /// Path to a custom config.toml file
+#[derive(Args, Debug)]
+struct RemindArgs {
+    /// Ticket key or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
+
+    /// What to be reminded of
+    note: Option<String>,
+
+    /// When the reminder is due, such as 30m, 4h, or 2d
+    #[clap(long = "in", value_name = "DURATION", value_parser = parse_duration)]
+    due_in: Option<Duration>,
+
+    /// List pending reminders
+    #[clap(long, conflicts_with_all = ["note", "due_in", "cancel"])]
+    list: bool,
+
+    /// Delete a pending reminder by its ID
+    #[clap(long, value_name = "ID", conflicts_with_all = ["note", "due_in"])]
+    cancel: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+struct HookArgs {
+    #[command(subcommand)]
+    command: HookCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum HookCommands {
+    /// Install the prepare-commit-msg hook in the current repository
+    Install(HookInstallArgs),
+    /// Remove the prepare-commit-msg hook installed by jit
+    Uninstall,
+    /// Run by the installed hook to prefix a commit message file
+    #[command(hide = true)]
+    PrepareCommitMsg(PrepareCommitMsgArgs),
+}
+
+#[derive(Args, Debug)]
+struct HookInstallArgs {
+    /// Replace an existing prepare-commit-msg hook that jit did not install
     #[clap(long)]
-    config_file: Option<PathBuf>,
+    force: bool,
 }
 
 #[derive(Args, Debug)]
-struct CreateArgs {
-    /// Jira project key (e.g., RW)
+struct PrepareCommitMsgArgs {
+    /// Commit message file
+    file: PathBuf,
+
+    /// Where the message came from: message, template, merge, squash, or commit
+    source: Option<String>,
+
+    /// Commit being amended, if any
+    sha: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct StartArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL
+    ticket: String,
+
+    /// Start the branch from this ref instead of the current HEAD
     #[clap(long)]
-    project: String,
+    from: Option<String>,
+}
 
+#[derive(Args, Debug)]
+struct QuickArgs {
     /// Ticket summary
-    #[clap(long)]
     summary: String,
 
-    /// Plain-text ticket description
-    #[clap(long)]
-    description: Option<String>,
-
-    /// Jira issue type name, such as Task, Bug, or Story (default: Task)
+    /// Jira issue type name, such as Task, Bug, or Story
     #[clap(long = "type", default_value = "Task")]
     issue_type: String,
 
-    /// Assignee account ID, or `me` to assign to the current Jira user (default: me)
-    #[clap(long, default_value = "me")]
-    assignee: String,
-
-    /// Add the created ticket to the current active sprint instead of leaving it in the backlog
+    /// Jira project key (default: project from the [jira] config section)
     #[clap(long)]
-    current_sprint: bool,
+    project: Option<String>,
 
-    /// Jira board ID to use for current sprint selection; otherwise the most recently started active sprint is used
-    #[clap(long, requires = "current_sprint")]
+    /// Jira board ID to take the active sprint from
+    #[clap(long)]
     board: Option<u64>,
+}
 
-    /// Output created issue details in JSON format
+#[derive(Args, Debug)]
+struct OpenArgs {
+    /// Ticket key or URL; defaults to the key in the current git branch name
+    ticket: Option<String>,
+
+    /// Print the URL instead of opening it
     #[clap(long)]
-    json: bool,
+    print: bool,
 }
 
 #[derive(Args, Debug)]
-struct EditArgs {
-    /// JIRA issue key (e.g., RW-1931) or URL (e.g., https://company.atlassian.net/browse/RW-1931)
-    ticket: String,
+struct PlanArgs {
+    /// Scrum board to plan
+    #[clap(long)]
+    board: u64,
 
-    /// Updated ticket summary
+    /// Sprint to plan (default: the board's next future sprint)
     #[clap(long)]
-    summary: Option<String>,
+    sprint: Option<u64>,
 
-    /// Updated plain-text ticket description; pass an empty string to clear it
+    /// Maximum number of backlog issues to show
+    #[clap(long, default_value_t = 100)]
+    limit: usize,
+
+    /// Story points custom field ID (default: [estimation] config, or the site's story points field)
     #[clap(long)]
-    description: Option<String>,
+    field: Option<String>,
+}
 
-    /// Updated Jira issue type name, such as Task, Bug, or Story
-    #[clap(long = "type")]
-    issue_type: Option<String>,
+#[derive(Args, Debug)]
+struct BranchArgs {
+    /// Ticket key or URL
+    ticket: String,
 
-    /// Updated assignee account ID, or `me` / `unassigned`
+    /// Start the branch from this ref instead of the current HEAD
     #[clap(long)]
-    assignee: Option<String>,
+    from: Option<String>,
 
-    /// Output updated issue details in JSON format
+    /// Print the branch name without creating it
     #[clap(long)]
-    json: bool,
+    print: bool,
 }
 
-#[derive(Debug, Deserialize)]
-struct JiraIssue {
-    id: String,
-    key: String,
-    fields: JiraIssueFields,
-}
+#[derive(Args, Debug)]
+struct BranchesArgs {
+    /// Only list branches whose tickets are done, and offer to delete them
+    #[clap(long)]
+    stale: bool,
 
-#[derive(Debug, Deserialize)]
-struct JiraIssueFields {
-    summary: String,
-    #[serde(default)]
-    status: Option<JiraStatus>,
-    #[serde(rename = "customfield_10020", default)]
-    sprint: Option<Vec<JiraSprint>>,
-    #[serde(default)]
-    description: Option<Value>,
-    #[serde(default)]
-    assignee: Option<JiraUser>,
-    #[serde(default)]
-    reporter: Option<JiraUser>,
-    #[serde(default)]
-    priority: Option<JiraPriority>,
-    #[serde(default)]
-    issuetype: Option<JiraIssueType>,
-    #[serde(default)]
-    created: Option<String>,
-    #[serde(default)]
-    updated: Option<String>,
-    #[serde(rename = "duedate", default)]
-    due_date: Option<String>,
-    #[serde(default)]
-    comment: Option<JiraCommentContainer>,
-}
+    /// Delete the stale branches without asking
+    #[clap(long, short = 'y', requires = "stale")]
+    yes: bool,
 
-#[derive(Debug, Deserialize, Default)]
-struct JiraStatus {
-    name: String,
+    /// Delete stale branches even when git says they are not fully merged
+    #[clap(long, requires = "stale")]
+    force: bool,
 }
 
-#[derive(Debug, Deserialize, Default)]
-struct JiraSprint {
-    name: String,
-    state: String,
-}
+#[derive(Args, Debug)]
+struct WorktreeArgs {
+    /// Ticket key or URL
+    #[clap(required_unless_present = "prune", conflicts_with = "prune")]
+    ticket: Option<String>,
 
-#[derive(Debug, Deserialize, Default)]
-struct JiraUser {
-    #[serde(rename = "displayName")]
-    display_name: String,
-    #[serde(rename = "accountId", default)]
-    account_id: Option<String>,
-}
+    /// Start a new branch from this ref instead of the current HEAD
+    #[clap(long)]
+    from: Option<String>,
 
-#[derive(Debug, Deserialize, Default)]
-struct JiraPriority {
-    name: String,
-}
+    /// Print the worktree path without creating it
+    #[clap(long)]
+    print: bool,
 
-#[derive(Debug, Deserialize, Default)]
-struct JiraIssueType {
-    name: String,
-}
+    /// Remove the worktrees of done tickets, after asking
+    #[clap(long)]
+    prune: bool,
 
-#[derive(Debug, Deserialize, Default)]
-struct JiraCommentContainer {
-    #[serde(default)]
-    comments: Vec<JiraComment>,
-}
+    /// Remove the worktrees without asking
+    #[clap(long, short = 'y', requires = "prune")]
+    yes: bool,
 
-#[derive(Debug, Deserialize, Default)]
-struct JiraComment {
-    #[serde(default)]
-    author: Option<JiraUser>,
-    #[serde(default)]
-    body: Option<Value>,
-    #[serde(default)]
-    created: Option<String>,
-    #[serde(default)]
-    updated: Option<String>,
+    /// Remove worktrees even when they have uncommitted changes
+    #[clap(long, requires = "prune")]
+    force: bool,
 }
 
-#[derive(Debug, Deserialize, Default, Clone)]
-struct JiraDevStatusResponse {
-    #[serde(default)]
-    detail: Vec<JiraDevStatusDetail>,
-}
+#[derive(Args, Debug)]
+struct SearchArgs {
+    /// JQL query (e.g., 'project = RW AND status = "In Review" ORDER BY updated DESC')
+    jql: String,
 
-#[derive(Debug, Deserialize, Default, Clone)]
-struct JiraDevStatusDetail {
-    #[serde(rename = "pullRequests", default)]
-    pull_requests: Vec<JiraPullRequest>,
-}
+    /// Maximum number of issues to list, or 0 for all
+    #[clap(long, default_value_t = DEFAULT_TICKET_LIMIT)]
+    limit: u32,
 
-#[derive(Debug, Deserialize, Default, Clone)]
-struct JiraPullRequest {
-    #[serde(default)]
-    id: Option<String>,
-    #[serde(default)]
-    name: Option<String>,
-    #[serde(default)]
-    status: Option<String>,
-    #[serde(default)]
-    url: Option<String>,
-    #[serde(rename = "lastUpdate", default)]
-    last_update: Option<String>,
-}
+    #[command(flatten)]
+    assignees: AssigneeFilterArgs,
 
-#[derive(Debug, Deserialize)]
-struct JiraSearchResponse {
-    issues: Vec<JiraIssue>,
-}
+    /// Output as plain text in format "KEY: Summary"
+    #[clap(long)]
+    text: bool,
 
-#[derive(Debug, Deserialize)]
-struct JiraCreatedIssue {
-    id: String,
-    key: String,
-}
+    /// Output as CSV with a header row
+    #[clap(long)]
+    csv: bool,
 
-#[derive(Debug, Deserialize)]
-struct JiraBoardPage {
-    #[serde(default)]
-    values: Vec<JiraBoard>,
-    #[serde(rename = "isLast", default)]
-    is_last: bool,
-    #[serde(rename = "maxResults", default)]
-    max_results: usize,
-    #[serde(rename = "startAt", default)]
-    start_at: usize,
-}
+    /// Output one JSON object per ticket and line, printed as pages arrive
+    #[clap(long)]
+    ndjson: bool,
 
-#[derive(Debug, Deserialize, Clone)]
-struct JiraBoard {
-    id: u64,
-    name: String,
-}
+    /// Output as GitHub-flavored Markdown: a ticket with its description, or a ticket table
+    #[clap(long)]
+    markdown: bool,
 
-#[derive(Debug, Deserialize)]
-struct JiraSprintPage {
-    #[serde(default)]
-    values: Vec<JiraAgileSprint>,
-    #[serde(rename = "isLast", default)]
-    is_last: bool,
-    #[serde(rename = "maxResults", default)]
-    max_results: usize,
-    #[serde(rename = "startAt", default)]
-    start_at: usize,
-}
+    /// Columns to list, comma-separated (default: key,summary,status)
+    #[clap(long, value_enum, value_delimiter = ',', value_name = "COLUMNS")]
+    columns: Vec<IssueColumn>,
 
-#[derive(Debug, Deserialize, Clone)]
-struct JiraAgileSprint {
-    id: u64,
-    name: String,
-    #[serde(rename = "startDate", default)]
-    start_date: Option<String>,
-}
+    /// Group the tickets into swimlanes, with how many in each are done
+    #[clap(long, value_enum)]
+    group_by: Option<IssueGrouping>,
 
-#[derive(Debug, Deserialize)]
-struct AppConfig {
-    jira: JiraConfig,
-}
+    /// Print each ticket through a Handlebars template, e.g. '{{key}} [{{status}}] {{summary}}', or a template named in [templates]
+    #[clap(long, value_name = "TEMPLATE")]
+    format: Option<String>,
 
-#[derive(Debug, Deserialize)]
-struct JiraConfig {
-    base_url: String,
-    api_token: String,
-    user_email: String,
+    /// Extra Jira fields to fetch and show, comma-separated IDs (e.g. labels,components,customfield_10016)
+    #[clap(long, value_delimiter = ',', value_name = "FIELDS")]
+    fields: Vec<String>,
+
+    /// Print only the number of matching tickets
+    #[clap(long, conflicts_with_all = ["format", "group_by", "fields", "columns"])]
+    count: bool,
 }
 
-fn main() -> Result<()> {
-    let args = Cli::parse();
+#[derive(Args, Debug)]
+struct ExportArgs {
+    /// JQL query selecting the issues to export
+    #[clap(long)]
+    jql: String,
 
-    if let Some(since) = args.query.since.as_deref() {
-        validate_since_date(since)?;
-    }
+    /// File to write the export to
+    #[clap(long)]
+    out: PathBuf,
 
-    if matches!(args.command, Some(Commands::Auth)) {
-        return run_auth_command(args.query.config_file.as_deref());
-    }
+    /// A JSON array, or one JSON document per line
+    #[clap(long, value_enum, default_value = "json")]
+    format: ExportFormat,
 
-    if let Some(Commands::Skill(skill_args)) = args.command.as_ref() {
-        let SkillCommands::Install(install_args) = &skill_args.command;
-        return run_skill_install_command(install_args);
-    }
+    /// Include every comment of each issue
+    #[clap(long)]
+    with_comments: bool,
 
-    let config = load_configuration(&args.query)?;
+    /// Include a list of each issue's attachments with their download URLs
+    #[clap(long)]
+    with_attachments_manifest: bool,
 
-    let client = create_jira_client(&config.user_email, &config.api_token)?;
+    /// Issues to request per page
+    #[clap(long, default_value_t = 100)]
+    page_size: usize,
 
-    match args.command {
-        Some(Commands::Auth) => unreachable!("auth command is handled before loading config"),
-        Some(Commands::Skill(_)) => unreachable!("skill command is handled before loading config"),
-        Some(Commands::Create(create_args)) => {
-            run_create_issue_command(&client, &config.base_url, &create_args)
-        }
-        Some(Commands::Edit(edit_args)) => {
-            run_edit_issue_command(&client, &config.base_url, &edit_args)
-        }
-        None => run_query_mode(&client, &config.base_url, args.query),
-    }
+    /// Download every attachment into this directory, skipping ones a previous run already saved
+    #[clap(long, value_name = "DIR")]
+    download_attachments: Option<PathBuf>,
+
+    /// Attachments to download at the same time
+    #[clap(long, default_value_t = 4, requires = "download_attachments")]
+    download_concurrency: usize,
 }
 
-fn run_query_mode(client: &Client, jira_base_url: &str, args: QueryArgs) -> Result<()> {
-    if args.my_tickets || args.ticket.is_none() {
-        // Fetch and display current tickets
-        let tickets = fetch_my_tickets(client, jira_base_url, args.limit)?;
-        let include_prs = args.include_prs || args.full;
-        let pull_requests_by_key = if include_prs {
-            Some(fetch_pull_requests_for_tickets(
-                client,
-                jira_base_url,
-                &tickets,
-            )?)
-        } else {
-            None
-        };
-        display_tickets_table(&tickets, pull_requests_by_key.as_ref())?;
-    } else if let Some(ticket_input) = args.ticket {
-        // Extract ticket ID from URL if needed
-        let ticket_id = extract_ticket_id(&ticket_input)?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    Json,
+    Ndjson,
+}
 
-        let include_description = args.show || args.full || args.include_description;
-        let include_comments = args.full || args.include_comments;
-        let include_prs = args.full || args.include_prs;
-        let include_details = args.show
-            || args.full
-            || args.include_description
-            || args.include_comments
-            || args.include_prs;
+#[derive(Args, Debug)]
+struct AssignArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-        // Fetch issue details based on requested output mode.
-        let issue = fetch_jira_issue(
-            client,
-            jira_base_url,
-            &ticket_id,
-            include_details,
-            include_description,
-            include_comments,
-        )?;
+    /// Person to assign: display name, email, or account ID
+    #[clap(conflicts_with_all = ["me", "unassign"])]
+    assignee: Option<String>,
 
-        let pull_requests = if include_prs {
-            fetch_issue_pull_requests(client, jira_base_url, &issue.id)?
-        } else {
-            Vec::new()
-        };
+    /// Assign the ticket to yourself
+    #[clap(long, conflicts_with = "unassign")]
+    me: bool,
 
-        // Output the result
-        if args.json {
-            if include_details || include_description || include_comments {
-                let payload = build_issue_json(
-                    &issue,
-                    include_description,
-                    include_comments,
-                    include_prs,
-                    &pull_requests,
-                    args.comments_limit,
-                    args.all_comments,
-                    args.since.as_deref(),
-                );
-                println!("{}", payload);
-            } else {
-                println!(
-                    "{}",
-                    json!({
-                        "ticket": issue.key,
-                        "summary": issue.fields.summary
-                    })
-                );
-            }
-        } else if args.text {
-            println!("{}: {}", issue.key, issue.fields.summary);
-        } else if args.show
-            || args.full
-            || args.include_description
-            || args.include_comments
-            || args.include_prs
-        {
-            display_detailed_ticket(
-                &issue,
-                include_description,
-                include_comments,
-                include_prs,
-                &pull_requests,
-                args.comments_limit,
-                args.all_comments,
-                args.since.as_deref(),
-            )?;
-        } else {
-            println!("Ticket:   {}", issue.key);
-            println!("Summary:  {}", issue.fields.summary);
-        }
-    }
+    /// Clear the ticket's assignee
+    #[clap(long)]
+    unassign: bool,
+}
 
-    Ok(())
+#[derive(Args, Debug)]
+struct DepsArgs {
+    /// Jira project key (e.g., RW)
+    #[clap(long)]
+    project: String,
+
+    /// Only report links to issues in other projects
+    #[clap(long)]
+    external_only: bool,
+
+    /// Maximum number of open issues to inspect
+    #[clap(long, default_value_t = 500)]
+    limit: u32,
 }
 
-/// Attempts to load configuration from multiple locations in order:
-/// 1. Custom config file passed as an argument
-/// 2. Current directory config.toml
-/// 3. User config directory ~/.config/jit/config.toml
-fn load_configuration(args: &QueryArgs) -> Result<JiraConfig> {
-    let config_path = resolve_config_path(args)?;
-    read_config_file(&config_path)
+#[derive(Args, Debug)]
+struct MoveArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
+
+    /// Target status or transition name; partial names work when they match only one transition
+    status: Option<String>,
 }
 
-fn resolve_config_path(args: &QueryArgs) -> Result<PathBuf> {
-    if let Some(config_path) = &args.config_file {
-        if config_path.exists() {
-            return Ok(config_path.clone());
-        }
+#[derive(Args, Debug)]
+struct TeamArgs {
+    /// Team from the [teams] config section (default: the [team] section)
+    name: Option<String>,
 
-        return Err(anyhow!(
-            "Specified config.toml file not found at: {}. Run `jit auth --config-file {}` to create it, or create the file manually.",
-            config_path.display(),
-            config_path.display()
-        ));
-    }
+    /// Only count issues matching this JQL as well, such as a project or sprint
+    #[clap(long)]
+    jql: Option<String>,
 
-    let local_config = PathBuf::from("config.toml");
-    if local_config.exists() {
-        return Ok(local_config);
-    }
+    /// Maximum number of open issues to count
+    #[clap(long, default_value_t = 1000)]
+    limit: u32,
+}
 
-    if let Some(user_config) = default_config_path() {
-        if user_config.exists() {
-            return Ok(user_config);
-        }
+#[derive(Args, Debug)]
+struct CommentsArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-        return Err(anyhow!(
-            "No configuration found. Run `jit auth` to configure Jira credentials, or create `config.toml` in the current directory or at `{}` with:\n[jira]\nbase_url = \"https://your-company.atlassian.net\"\napi_token = \"your_api_token_here\"\nuser_email = \"your_email@example.com\"",
-            user_config.display()
-        ));
-    }
+    /// Maximum number of comments to show, counting back from the newest
+    #[clap(long, default_value_t = 20)]
+    limit: usize,
 
-    Err(anyhow!(
-        "No configuration found. Run `jit auth` to configure Jira credentials, or create a `config.toml` file with:\n[jira]\nbase_url = \"https://your-company.atlassian.net\"\napi_token = \"your_api_token_here\"\nuser_email = \"your_email@example.com\""
-    ))
-}
+    /// Link file:line references in comments to VS Code (vscode://file/...)
+    #[clap(long)]
+    vscode_links: bool,
 
-fn default_config_path() -> Option<PathBuf> {
-    dirs::home_dir().map(|path| config_path_from_home(&path))
+    /// Output the comments as JSON
+    #[clap(long)]
+    json: bool,
 }
 
-fn config_path_from_home(home_dir: &Path) -> PathBuf {
-    home_dir.join(".config").join("jit").join("config.toml")
-}
+#[derive(Args, Debug)]
+struct WatchArgs {
+    /// Seconds to sleep between checks
+    #[clap(long, default_value = "300")]
+    interval: u64,
 
-fn read_config_file(path: &Path) -> Result<JiraConfig> {
-    let contents = fs::read_to_string(path)
-        .with_context(|| format!("Failed to read config file at {}", path.display()))?;
-    let config: AppConfig = toml::from_str(&contents).with_context(|| {
-        format!(
-            "Failed to parse config file at {}. Expected:\n[jira]\nbase_url = \"https://your-company.atlassian.net\"\napi_token = \"your_api_token_here\"\nuser_email = \"your_email@example.com\"",
-            path.display()
-        )
-    })?;
-    Ok(config.jira)
+    /// Check every rule once and exit (for cron)
+    #[clap(long)]
+    once: bool,
 }
 
-fn run_auth_command(config_file: Option<&Path>) -> Result<()> {
-    let config_path = match config_file {
-        Some(path) => path.to_path_buf(),
-        None => default_config_path().context("Could not determine default config path")?,
-    };
-
-    let mut existing_config = None;
-    if config_path.exists() {
-        match read_config_file(&config_path) {
-            Ok(config) => {
-                println!("Existing Jira config found at {}", config_path.display());
-                println!("Base URL: {}", config.base_url);
-                println!("Email:    {}", config.user_email);
-                println!("Token:    {}", mask_token(&config.api_token));
-                existing_config = Some(config);
-            }
-            Err(err) => {
-                println!(
-                    "Existing config at {} could not be read:",
-                    config_path.display()
-                );
-                println!("{}", err);
-            }
-        }
+#[derive(Args, Debug)]
+struct TriggerArgs {
+    /// Name of the webhook in the [webhooks] config section
+    webhook: String,
 
-        if !prompt_yes_no("Overwrite this config? [y/N]: ")? {
-            println!("Leaving existing config unchanged.");
-            return Ok(());
-        }
-    }
+    /// Ticket key or URL the automation should run on (repeatable)
+    #[clap(long = "ticket")]
+    tickets: Vec<String>,
 
-    let base_url_input = if let Some(config) = existing_config.as_ref() {
-        prompt_with_default("Jira company URL", &config.base_url)?
-    } else {
-        prompt_required("Jira company URL: ")?
-    };
-    let base_url = normalize_jira_base_url(&base_url_input)?;
-    let user_email = if let Some(config) = existing_config.as_ref() {
-        prompt_with_default("Jira account email", &config.user_email)?
-    } else {
-        prompt_required("Jira account email: ")?
-    };
+    /// Extra value passed to the rule as {{webhookData.KEY}}, as KEY=VALUE (repeatable)
+    #[clap(long = "data", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    data: Vec<(String, String)>,
+}
 
-    let token_url = "https://id.atlassian.com/manage-profile/security/api-tokens";
-    println!("Open this URL to create an Atlassian API token:");
-    println!("{}", token_url);
-    if let Err(err) = open_url(token_url) {
-        println!("Could not open your browser automatically: {}", err);
-    }
+#[derive(Args, Debug)]
+struct DeployedArgs {
+    /// Ticket keys or URLs to annotate
+    #[clap(required_unless_present = "fix_version")]
+    tickets: Vec<String>,
 
-    let api_token = prompt_required("Paste API token: ")?;
-    let client = create_jira_client(&user_email, &api_token)?;
-    validate_jira_authentication(&client, &base_url)?;
+    /// Annotate every ticket in this fix version
+    #[clap(long)]
+    fix_version: Option<String>,
 
-    if let Some(parent) = config_path.parent() {
-        fs::create_dir_all(parent).with_context(|| {
-            format!("Failed to create config directory at {}", parent.display())
-        })?;
-    }
+    /// Environment the release was deployed to, such as staging or production
+    #[clap(long)]
+    env: String,
 
-    fs::write(
-        &config_path,
-        format_config_toml(&JiraConfig {
-            base_url,
-            api_token,
-            user_email,
-        }),
-    )
-    .with_context(|| format!("Failed to write config file at {}", config_path.display()))?;
+    /// Maximum number of tickets to annotate from the fix version
+    #[clap(long, default_value_t = 500)]
+    limit: u32,
 
-    println!("Saved Jira credentials to {}", config_path.display());
-    Ok(())
+    /// Print the tickets that would be annotated without changing them
+    #[clap(long)]
+    dry_run: bool,
 }
 
-const SKILL_SOURCE_URL: &str =
-    "https://raw.githubusercontent.com/cesarferreira/jit/refs/heads/main/SKILL.md";
-
-struct AgentTarget {
-    name: &'static str,
-    home_subpath: &'static [&'static str],
-}
+#[derive(Args, Debug)]
+struct TrainArgs {
+    /// Fix version (release) name, such as 2.5.0
+    #[clap(long)]
+    fix_version: String,
 
-const AGENT_TARGETS: &[AgentTarget] = &[
-    AgentTarget {
-        name: "codex",
-        home_subpath: &[".codex", "skills", "jit", "SKILL.md"],
-    },
-    AgentTarget {
-        name: "claude",
-        home_subpath: &[".claude", "skills", "jit", "SKILL.md"],
-    },
-];
+    /// Limit the release to one Jira project key (e.g., RW)
+    #[clap(long)]
+    project: Option<String>,
 
-fn run_skill_install_command(args: &SkillInstallArgs) -> Result<()> {
-    let home = dirs::home_dir().context("Could not determine home directory")?;
+    /// Group tickets by Jira component or by the repository of their pull requests
+    #[clap(long, value_enum, default_value = "component")]
+    group_by: TrainGrouping,
 
-    let targets: Vec<&AgentTarget> = match args.agent.as_deref() {
-        Some(agent) => {
-            let agent = agent.to_ascii_lowercase();
-            let target = AGENT_TARGETS
-                .iter()
-                .find(|t| t.name == agent)
-                .ok_or_else(|| {
-                    anyhow!(
-                        "Unknown agent '{}'. Supported: {}",
-                        agent,
-                        AGENT_TARGETS
-                            .iter()
-                            .map(|t| t.name)
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    )
-                })?;
-            vec![target]
-        }
-        None => AGENT_TARGETS.iter().collect(),
-    };
+    #[command(flatten)]
+    assignees: AssigneeFilterArgs,
 
-    println!("Downloading SKILL.md from {}", SKILL_SOURCE_URL);
-    let client = ClientBuilder::new().build()?;
-    let response = client.get(SKILL_SOURCE_URL).send()?;
-    if !response.status().is_success() {
-        return Err(anyhow!(
-            "Failed to download SKILL.md ({}): {}",
-            response.status(),
-            SKILL_SOURCE_URL
-        ));
-    }
-    let skill_contents = response.text()?;
+    /// Maximum number of tickets to include
+    #[clap(long, default_value_t = 500)]
+    limit: u32,
+}
 
-    for target in targets {
-        let mut path = home.clone();
-        for segment in target.home_subpath {
-            path.push(segment);
-        }
+#[derive(Args, Debug)]
+struct VerifyReleaseArgs {
+    /// Git revision range of the release, such as v2.4.0..HEAD
+    range: String,
 
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).with_context(|| {
-                format!("Failed to create skill directory at {}", parent.display())
-            })?;
-        }
+    /// Fix version the tickets should have (default: any fix version)
+    #[clap(long)]
+    fix_version: Option<String>,
 
-        fs::write(&path, &skill_contents)
-            .with_context(|| format!("Failed to write SKILL.md to {}", path.display()))?;
+    /// Only count ticket keys of this Jira project (e.g., RW); repeatable
+    #[clap(long = "project", value_name = "KEY")]
+    projects: Vec<String>,
 
-        println!("Installed {} skill at {}", target.name, path.display());
-    }
+    /// Git repository to read commits from
+    #[clap(long, default_value = ".")]
+    repo: PathBuf,
+}
 
-    Ok(())
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TrainGrouping {
+    Component,
+    Repo,
 }
 
-fn prompt_required(prompt: &str) -> Result<String> {
-    print!("{}", prompt);
-    io::stdout().flush()?;
+#[derive(Args, Debug)]
+struct ProcessCommitsArgs {
+    /// Git revision range to read, such as origin/main..HEAD
+    range: String,
 
-    let mut value = String::new();
-    io::stdin().read_line(&mut value)?;
-    let value = value.trim().to_string();
-    if value.is_empty() {
-        return Err(anyhow!(
-            "{} cannot be empty",
-            prompt.trim().trim_end_matches(':')
-        ));
-    }
-    Ok(value)
-}
+    /// Git repository to read commits from
+    #[clap(long, default_value = ".")]
+    repo: PathBuf,
 
-fn prompt_with_default(prompt: &str, default: &str) -> Result<String> {
-    print!("{} [{}]: ", prompt, default);
-    io::stdout().flush()?;
+    /// Also comment on tickets a commit mentions without any directive
+    #[clap(long)]
+    comment_references: bool,
 
-    let mut value = String::new();
-    io::stdin().read_line(&mut value)?;
-    let value = value.trim();
-    if value.is_empty() {
-        Ok(default.to_string())
-    } else {
-        Ok(value.to_string())
-    }
+    /// Print the actions that would run without changing any tickets
+    #[clap(long)]
+    dry_run: bool,
 }
 
-fn prompt_yes_no(prompt: &str) -> Result<bool> {
-    print!("{}", prompt);
-    io::stdout().flush()?;
-
-    let mut value = String::new();
-    io::stdin().read_line(&mut value)?;
-    Ok(matches!(
-        value.trim().to_ascii_lowercase().as_str(),
-        "y" | "yes"
-    ))
-}
+#[derive(Args, Debug)]
+struct CommentArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-fn normalize_jira_base_url(input: &str) -> Result<String> {
-    let base_url = input.trim().trim_end_matches('/').to_string();
-    if !base_url.starts_with("https://") && !base_url.starts_with("http://") {
-        return Err(anyhow!(
-            "Jira company URL must start with https:// or http://"
-        ));
-    }
-    Ok(base_url)
-}
+    /// Comment text; `-` or no text reads it from stdin
+    message: Option<String>,
 
-fn open_url(url: &str) -> Result<()> {
-    if std::env::var_os("JIT_AUTH_SKIP_OPEN").is_some() {
-        return Ok(());
-    }
+    /// Read the comment text from a file
+    #[clap(long, conflicts_with_all = ["message", "snippet"])]
+    file: Option<PathBuf>,
 
-    let status = if cfg!(target_os = "macos") {
-        Command::new("open").arg(url).status()
-    } else if cfg!(target_os = "windows") {
-        Command::new("cmd").args(["/C", "start", "", url]).status()
-    } else {
-        Command::new("xdg-open").arg(url).status()
-    }
-    .context("failed to launch browser command")?;
+    /// Name of a [snippets] template to post instead of a message
+    #[clap(long, conflicts_with = "message")]
+    snippet: Option<String>,
 
-    if status.success() {
-        Ok(())
-    } else {
-        Err(anyhow!("browser command exited with {}", status))
-    }
+    /// Value for a `{placeholder}` in the snippet, as KEY=VALUE (repeatable)
+    #[clap(long = "var", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    vars: Vec<(String, String)>,
 }
 
-fn format_config_toml(config: &JiraConfig) -> String {
-    format!(
-        "[jira]\nbase_url = {}\napi_token = {}\nuser_email = {}\n",
-        toml_string(&config.base_url),
-        toml_string(&config.api_token),
-        toml_string(&config.user_email)
-    )
-}
+#[derive(Args, Debug)]
+struct SummarizeArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-fn toml_string(value: &str) -> String {
-    serde_json::to_string(value).expect("string serialization should not fail")
+    /// Redact people, emails, and [share] redact_patterns before sending the ticket
+    #[clap(long)]
+    redact: bool,
 }
 
-fn mask_token(token: &str) -> String {
-    if token.is_empty() {
-        return "<empty>".to_string();
-    }
+#[derive(Args, Debug)]
+struct ShareArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-    "*".repeat(token.chars().count().min(8))
+    /// Replace names with placeholders and strip emails, instance URLs, and [share] redact_patterns
+    #[clap(long)]
+    redact: bool,
 }
 
-fn run_create_issue_command(client: &Client, jira_base_url: &str, args: &CreateArgs) -> Result<()> {
-    let resolved_assignee = resolve_create_assignee(client, jira_base_url, &args.assignee)?;
-    let resolved_sprint = resolve_target_sprint(client, jira_base_url, args)?;
-    let created_issue = create_jira_issue(
-        client,
-        jira_base_url,
-        args,
-        resolved_assignee.account_id.as_deref(),
-    )?;
-    if let Some(sprint) = resolved_sprint.as_ref() {
-        add_issue_to_sprint(client, jira_base_url, sprint.id, &created_issue.key).with_context(
-            || {
-                format!(
-                    "Created {} but failed to add it to sprint {}",
-                    created_issue.key, sprint.name
-                )
-            },
-        )?;
-    }
-    let issue_url = format!("{}/browse/{}", jira_base_url, created_issue.key);
+#[derive(Args, Debug)]
+struct HeatmapArgs {
+    /// Jira project key (e.g., RW)
+    #[clap(long)]
+    project: String,
 
-    if args.json {
-        let mut payload = json!({
-            "id": created_issue.id,
-            "ticket": created_issue.key,
-            "project": args.project,
-            "summary": args.summary,
-            "issue_type": args.issue_type,
-            "assignee": resolved_assignee.label,
-            "url": issue_url,
-            "backlog": resolved_sprint.is_none(),
-        });
-        if let Some(sprint) = resolved_sprint {
-            if let Some(obj) = payload.as_object_mut() {
-                obj.insert("sprint".to_string(), json!(sprint.name));
-                obj.insert("board".to_string(), json!(sprint.board_name));
-            }
-        }
-        println!("{}", payload);
-    } else {
-        println!("Created:  {}", created_issue.key);
-        println!("Project:  {}", args.project);
-        println!("Type:     {}", args.issue_type);
-        println!("Assignee: {}", resolved_assignee.label);
-        println!("Summary:  {}", args.summary);
-        if let Some(sprint) = resolved_sprint {
-            println!("Board:    {}", sprint.board_name);
-            println!("Sprint:   {}", sprint.name);
-            println!("Backlog:  No (added to current sprint)");
-        } else {
-            println!("Backlog:  Yes (created without sprint assignment)");
-        }
-        println!("URL:      {}", issue_url);
-    }
+    /// Maximum number of open issues to include
+    #[clap(long, default_value_t = 1000)]
+    limit: u32,
+}
 
-    Ok(())
+#[derive(Args, Debug)]
+struct SnapshotArgs {
+    /// Jira project key (e.g., RW)
+    #[clap(long)]
+    project: String,
+
+    /// Maximum number of open issues to count, or 0 for all
+    #[clap(long, default_value_t = 0)]
+    limit: u32,
 }
 
-fn run_edit_issue_command(client: &Client, jira_base_url: &str, args: &EditArgs) -> Result<()> {
-    if args.summary.is_none()
-        && args.description.is_none()
-        && args.issue_type.is_none()
-        && args.assignee.is_none()
-    {
-        return Err(anyhow!(
-            "No editable fields provided. Pass at least one of --summary, --description, --type, or --assignee."
-        ));
-    }
+#[derive(Args, Debug)]
+struct StatsArgs {
+    /// Jira project key (e.g., RW)
+    #[clap(long)]
+    project: String,
 
-    let ticket_id = extract_ticket_id(&args.ticket)?;
-    let resolved_assignee = args
-        .assignee
-        .as_deref()
-        .map(|assignee| resolve_create_assignee(client, jira_base_url, assignee))
-        .transpose()?;
-    update_jira_issue(
-        client,
-        jira_base_url,
-        &ticket_id,
-        args,
-        resolved_assignee
-            .as_ref()
-            .and_then(|assignee| assignee.account_id.as_deref()),
-    )?;
+    /// How far back to look (e.g., 30d, 12w)
+    #[clap(long, default_value = "90d", value_parser = parse_duration)]
+    since: Duration,
 
-    let issue_url = format!("{}/browse/{}", jira_base_url, ticket_id);
-    let mut updated_fields = Vec::new();
-    if args.summary.is_some() {
-        updated_fields.push("summary");
-    }
-    if args.description.is_some() {
-        updated_fields.push("description");
-    }
-    if args.issue_type.is_some() {
-        updated_fields.push("issue_type");
-    }
-    if args.assignee.is_some() {
-        updated_fields.push("assignee");
-    }
+    /// Width of each row in the trend table (e.g., 1d, 1w)
+    #[clap(long, default_value = "1w", value_parser = parse_duration)]
+    bucket: Duration,
 
-    if args.json {
-        let mut payload = json!({
-            "ticket": ticket_id,
-            "updated_fields": updated_fields,
-            "url": issue_url,
-        });
+    /// Maximum number of issues to analyze, or 0 for all
+    #[clap(long, default_value_t = 0)]
+    limit: u32,
 
-        if let Some(obj) = payload.as_object_mut() {
-            if let Some(summary) = args.summary.as_ref() {
-                obj.insert("summary".to_string(), json!(summary));
-            }
-            if let Some(description) = args.description.as_ref() {
-                obj.insert(
-                    "description".to_string(),
-                    if description.trim().is_empty() {
-                        Value::Null
-                    } else {
-                        json!(description)
-                    },
-                );
-            }
-            if let Some(issue_type) = args.issue_type.as_ref() {
-                obj.insert("issue_type".to_string(), json!(issue_type));
-            }
-            if let Some(assignee) = resolved_assignee.as_ref() {
-                obj.insert("assignee".to_string(), json!(assignee.label));
-            }
-        }
+    /// Show sparkline charts under the trend table
+    #[clap(long)]
+    chart: bool,
 
-        println!("{}", payload);
-    } else {
-        println!("Updated:  {}", ticket_id);
-        println!("Fields:   {}", updated_fields.join(", "));
-        if let Some(summary) = args.summary.as_ref() {
-            println!("Summary:  {}", summary);
-        }
-        if let Some(issue_type) = args.issue_type.as_ref() {
-            println!("Type:     {}", issue_type);
-        }
-        if let Some(assignee) = resolved_assignee.as_ref() {
-            println!("Assignee: {}", assignee.label);
-        }
-        if let Some(description) = args.description.as_ref() {
-            println!(
-                "Description: {}",
-                if description.trim().is_empty() {
-                    "Cleared"
-                } else {
-                    "Updated"
-                }
-            );
-        }
-        println!("URL:      {}", issue_url);
-    }
+    /// Output the statistics as JSON
+    #[clap(long, conflicts_with = "csv")]
+    json: bool,
 
-    Ok(())
-}
+    /// Output the trend table as CSV
+    #[clap(long)]
+    csv: bool,
 
-fn extract_ticket_id(input: &str) -> Result<String> {
-    // If input starts with http/https, it's a URL
-    if input.starts_with("http://") || input.starts_with("https://") {
-        // Use regex to extract the ticket ID from the URL
-        let re = Regex::new(r"/browse/([A-Z]+-\d+)(?:/|$)")?;
-        if let Some(captures) = re.captures(input) {
-            if let Some(ticket_match) = captures.get(1) {
-                return Ok(ticket_match.as_str().to_string());
-            }
-        }
-        Err(anyhow!("Could not extract ticket ID from URL: {}", input))
-    } else {
-        // Input is already a ticket ID
-        Ok(input.to_string())
-    }
+    /// Show open-issue counts per status from stored snapshots instead of querying Jira
+    #[clap(long)]
+    trend: bool,
 }
 
-fn validate_since_date(since: &str) -> Result<()> {
-    let re = Regex::new(r"^\d{4}-\d{2}-\d{2}$")?;
-    if !re.is_match(since) {
-        return Err(anyhow!(
-            "Invalid --since value '{}'. Use YYYY-MM-DD.",
-            since
-        ));
-    }
-    Ok(())
-}
+#[derive(Args, Debug)]
+struct DodArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-fn create_jira_client(email: &str, api_token: &str) -> Result<Client> {
-    // Create Basic Auth header
-    let auth = format!("{}:{}", email, api_token);
-    let encoded_auth = STANDARD.encode(auth);
-    let auth_header = format!("Basic {}", encoded_auth);
+    /// Output check results as JSON
+    #[clap(long)]
+    json: bool,
+}
 
-    // Setup headers
-    let mut headers = HeaderMap::new();
-    headers.insert(AUTHORIZATION, HeaderValue::from_str(&auth_header)?);
-    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+#[derive(Args, Debug)]
+struct DoneArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-    // Build client
-    let client = ClientBuilder::new().default_headers(headers).build()?;
+    /// Move the ticket even when definition-of-done checks fail
+    #[clap(long)]
+    force: bool,
 
-    Ok(client)
+    /// Status to move the ticket to (default: done_status from the [dod] config section, or Done)
+    #[clap(long)]
+    status: Option<String>,
 }
 
-fn fetch_jira_issue(
-    client: &Client,
-    base_url: &str,
-    issue_key: &str,
-    include_details: bool,
-    include_description: bool,
-    include_comments: bool,
-) -> Result<JiraIssue> {
-    let mut fields = vec!["summary"];
-
-    if include_details {
-        fields.extend([
-            "status",
-            "customfield_10020",
-            "assignee",
-            "reporter",
-            "priority",
-            "issuetype",
-            "created",
-            "updated",
-            "duedate",
-        ]);
-    }
+#[derive(Args, Debug)]
+struct DupArgs {
+    /// JIRA issue key (e.g., RW-2044) or URL of the duplicate to close
+    ticket: String,
 
-    if include_description {
-        fields.push("description");
-    }
+    /// Issue key or URL of the original issue it duplicates
+    #[clap(long, value_name = "ORIGINAL")]
+    of: String,
 
-    if include_comments {
-        fields.push("comment");
-    }
+    /// Status to move the duplicate to (default: status from the [duplicate] config section, done_status from [dod], or Done)
+    #[clap(long)]
+    status: Option<String>,
+}
 
-    fields.sort_unstable();
-    fields.dedup();
+#[derive(Args, Debug)]
+struct MergeArgs {
+    /// JIRA issue key (e.g., RW-2044) or URL of the duplicate to merge and close
+    ticket: String,
 
-    let url = format!(
-        "{}/rest/api/3/issue/{}?fields={}",
-        base_url,
-        issue_key,
-        fields.join(",")
-    );
+    /// Issue key or URL of the original issue that keeps the content
+    #[clap(long, value_name = "ORIGINAL")]
+    into: String,
 
-    let response = client
-        .get(&url)
-        .send()
-        .context("Failed to send request to JIRA API")?;
+    /// Status to move the duplicate to (default: status from the [duplicate] config section, done_status from [dod], or Done)
+    #[clap(long)]
+    status: Option<String>,
+}
 
-    if !response.status().is_success() {
-        return Err(anyhow!(
-            "JIRA API request failed with status: {} - {}",
-            response.status(),
-            response.text().unwrap_or_default()
-        ));
-    }
+#[derive(Args, Debug)]
+struct SplitArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL of the story to split
+    ticket: String,
 
-    let issue: JiraIssue = response
-        .json()
-        .context("Failed to parse JIRA API response")?;
+    /// Issue type of the new issues (default: the type of the split story)
+    #[clap(long = "type")]
+    issue_type: Option<String>,
 
-    Ok(issue)
+    /// Add the new issues to the story's open sprint without asking
+    #[clap(long)]
+    same_sprint: bool,
 }
 
-fn fetch_my_tickets(client: &Client, base_url: &str, limit: u32) -> Result<Vec<JiraIssue>> {
-    // Jira removed /rest/api/3/search for JQL queries in favor of /search/jql.
-    let url = format!("{}/rest/api/3/search/jql", base_url);
-
-    // JQL query to find issues assigned to the current user in the active sprint
-    let query = json!({
-        "jql": "assignee = currentUser() AND sprint in openSprints() ORDER BY updated DESC",
-        "maxResults": limit,
-        "fields": ["summary", "status", "customfield_10020"]
-    });
+#[derive(Args, Debug)]
+struct LintArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (e.g., https://company.atlassian.net/browse/RW-1931)
+    #[clap(required_unless_present = "jql", conflicts_with = "jql")]
+    ticket: Option<String>,
 
-    let response = client
-        .post(&url)
-        .json(&query)
-        .send()
-        .context("Failed to send request to JIRA API")?;
+    /// Lint every issue matching this JQL query instead of a single ticket
+    #[clap(long)]
+    jql: Option<String>,
 
-    if !response.status().is_success() {
-        return Err(anyhow!(
-            "JIRA API request failed with status: {} - {}",
-            response.status(),
-            response.text().unwrap_or_default()
-        ));
-    }
+    /// Maximum number of issues to lint with --jql
+    #[clap(long, default_value_t = DEFAULT_TICKET_LIMIT)]
+    limit: u32,
 
-    let search_result: JiraSearchResponse = response
-        .json()
-        .context("Failed to parse JIRA API response")?;
+    #[command(flatten)]
+    assignees: AssigneeFilterArgs,
 
-    if search_result.issues.is_empty() {
-        validate_jira_authentication(client, base_url)?;
-    }
+    /// Rule to check; repeat for several (default: rules from the [lint] config section, or all rules)
+    #[clap(long = "rule")]
+    rules: Vec<String>,
 
-    Ok(search_result.issues)
+    /// Output violations as JSON
+    #[clap(long)]
+    json: bool,
 }
 
-fn validate_jira_authentication(client: &Client, base_url: &str) -> Result<()> {
-    let url = format!("{}/rest/api/3/myself", base_url);
-    let response = client
-        .get(&url)
-        .send()
-        .context("Failed to send request to JIRA API")?;
+#[derive(Args, Debug)]
+struct GroomArgs {
+    /// Jira project key whose backlog should be groomed (e.g., RW)
+    #[clap(long)]
+    project: String,
 
-    if response.status().is_success() {
-        return Ok(());
-    }
+    /// Days without updates before a backlog issue counts as stale
+    #[clap(long, default_value_t = 90)]
+    stale_days: i64,
 
-    let status = response.status();
-    let body = response.text().unwrap_or_default();
-    if status.as_u16() == 401 || status.as_u16() == 403 {
-        return Err(anyhow!(
-            "Jira authentication failed with status: {} - {}. Check your Jira email and API token.",
-            status,
-            body
-        ));
-    }
+    /// Summary similarity (0.0-1.0) at which two issues are flagged as possible duplicates
+    #[clap(long, default_value_t = 0.6)]
+    similarity: f64,
 
-    Err(anyhow!(
-        "Jira authentication check failed with status: {} - {}",
-        status,
-        body
-    ))
+    /// Maximum number of backlog issues to inspect
+    #[clap(long, default_value_t = 200)]
+    limit: u32,
+
+    #[command(flatten)]
+    assignees: AssigneeFilterArgs,
+
+    /// Output the report as JSON
+    #[clap(long)]
+    json: bool,
 }
 
-fn create_jira_issue(
-    client: &Client,
-    base_url: &str,
-    args: &CreateArgs,
-    assignee_id: Option<&str>,
-) -> Result<JiraCreatedIssue> {
-    let url = format!("{}/rest/api/3/issue", base_url);
-    let payload = build_issue_create_payload(args, assignee_id);
+#[derive(Args, Debug)]
+struct PokerArgs {
+    /// JQL query selecting the issues to estimate
+    #[clap(long)]
+    jql: String,
 
-    let response = client
-        .post(&url)
-        .json(&payload)
-        .send()
-        .context("Failed to send request to JIRA API")?;
+    /// Maximum number of issues to fetch
+    #[clap(long, default_value_t = DEFAULT_TICKET_LIMIT)]
+    limit: u32,
 
-    if !response.status().is_success() {
-        return Err(anyhow!(
-            "JIRA API request failed with status: {} - {}",
-            response.status(),
-            response.text().unwrap_or_default()
-        ));
-    }
+    #[command(flatten)]
+    assignees: AssigneeFilterArgs,
+
+    /// Allowed point values, comma-separated (e.g., 1,2,3,5,8,13)
+    #[clap(long, value_delimiter = ',')]
+    scale: Vec<f64>,
 
-    response.json().context("Failed to parse JIRA API response")
+    /// Story points custom field ID (default: [estimation] config, or the site's story points field)
+    #[clap(long)]
+    field: Option<String>,
 }
 
-fn update_jira_issue(
-    client: &Client,
-    base_url: &str,
-    issue_key: &str,
-    args: &EditArgs,
-    assignee_id: Option<&str>,
-) -> Result<()> {
-    let url = format!("{}/rest/api/3/issue/{}", base_url, issue_key);
-    let payload = build_issue_update_payload(args, assignee_id);
+#[derive(Args, Debug)]
+struct TriageArgs {
+    /// JQL query selecting the issues to triage
+    #[clap(long)]
+    jql: String,
 
-    let response = client
-        .put(&url)
-        .json(&payload)
-        .send()
-        .context("Failed to send request to JIRA API")?;
+    /// Maximum number of issues to step through
+    #[clap(long, default_value_t = DEFAULT_TICKET_LIMIT)]
+    limit: u32,
 
-    if !response.status().is_success() {
-        return Err(anyhow!(
-            "JIRA API request failed with status: {} - {}",
-            response.status(),
-            response.text().unwrap_or_default()
-        ));
-    }
+    #[command(flatten)]
+    assignees: AssigneeFilterArgs,
 
-    Ok(())
+    /// Status the close action transitions to
+    #[clap(long, default_value = "Done")]
+    close_status: String,
 }
 
-fn resolve_create_assignee(
-    client: &Client,
-    base_url: &str,
-    requested: &str,
-) -> Result<ResolvedAssignee> {
-    match requested.trim() {
-        "" | "me" | "self" | "current" => fetch_current_user_assignee(client, base_url),
-        "unassigned" => Ok(ResolvedAssignee {
-            account_id: None,
-            label: "Unassigned".to_string(),
-        }),
-        account_id => Ok(ResolvedAssignee {
-            account_id: Some(account_id.to_string()),
-            label: account_id.to_string(),
-        }),
-    }
-}
+#[derive(Args, Debug)]
+struct CheckArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-fn fetch_current_user_assignee(client: &Client, base_url: &str) -> Result<ResolvedAssignee> {
-    let url = format!("{}/rest/api/3/myself", base_url);
-    let response = client
-        .get(&url)
-        .send()
-        .context("Failed to send request to JIRA API")?;
+    /// 1-based checklist item to toggle; omit to list the checklist
+    item: Option<usize>,
 
-    if !response.status().is_success() {
-        return Err(anyhow!(
-            "JIRA API request failed with status: {} - {}",
-            response.status(),
-            response.text().unwrap_or_default()
-        ));
-    }
+    /// Checklist custom field ID (default: field from the [checklist] config section)
+    #[clap(long)]
+    field: Option<String>,
 
-    let current_user: JiraUser = response
-        .json()
-        .context("Failed to parse JIRA API response")?;
-    let account_id = current_user
-        .account_id
-        .context("Current Jira user response did not include accountId")?;
-    let label = if current_user.display_name.trim().is_empty() {
-        "Current user".to_string()
-    } else {
-        current_user.display_name
-    };
+    /// Output checklist as JSON
+    #[clap(long)]
+    json: bool,
+}
 
-    Ok(ResolvedAssignee {
-        account_id: Some(account_id),
-        label,
-    })
+#[derive(Args, Debug)]
+struct ApproveArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
+
+    /// Extra note appended to the approval comment
+    #[clap(long)]
+    message: Option<String>,
 }
 
-fn resolve_target_sprint(
-    client: &Client,
-    base_url: &str,
-    args: &CreateArgs,
-) -> Result<Option<ResolvedSprint>> {
-    if !args.current_sprint {
-        return Ok(None);
-    }
+#[derive(Args, Debug)]
+struct RequestApprovalArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-    let sprint = if let Some(board_id) = args.board {
-        resolve_active_sprint_for_board(client, base_url, board_id)?
-    } else {
-        resolve_latest_active_sprint_for_project(client, base_url, &args.project)?
-    };
+    /// Approver account ID to mention; repeat for several (default: approvers from config)
+    #[clap(long = "approver")]
+    approvers: Vec<String>,
 
-    Ok(Some(sprint))
+    /// Extra note appended to the approval request comment
+    #[clap(long)]
+    message: Option<String>,
 }
 
-fn resolve_latest_active_sprint_for_project(
-    client: &Client,
-    base_url: &str,
-    project_key: &str,
-) -> Result<ResolvedSprint> {
-    let boards = fetch_scrum_boards_for_project(client, base_url, project_key)?;
-    if boards.is_empty() {
-        return Err(anyhow!(
-            "No Scrum boards found for project {}. Pass --board <id> if the sprint lives on a different board.",
-            project_key
-        ));
-    }
+#[derive(Args, Debug)]
+struct WaitArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-    let mut best: Option<ResolvedSprint> = None;
-    for board in boards {
-        let sprint = match fetch_active_sprints_for_board(client, base_url, board.id)? {
-            Some(sprint) => ResolvedSprint {
-                id: sprint.id,
-                name: sprint.name,
-                board_id: board.id,
-                board_name: board.name,
-                start_date: sprint.start_date,
-            },
-            None => continue,
-        };
+    /// Status to wait for; repeat to accept any of several statuses
+    #[clap(long, required = true)]
+    until: Vec<String>,
 
-        if is_better_sprint_candidate(&sprint, best.as_ref()) {
-            best = Some(sprint);
-        }
-    }
+    /// Give up after this long, such as 90s, 30m, 2h, or 1d (default: wait forever)
+    #[clap(long, value_parser = parse_duration)]
+    timeout: Option<Duration>,
 
-    best.ok_or_else(|| {
-        anyhow!(
-            "No active sprint found on accessible Scrum boards for project {}. Create without --current-sprint or pass --board <id>.",
-            project_key
-        )
-    })
+    /// Seconds to sleep between status checks
+    #[clap(long, default_value = "60", value_parser = clap::value_parser!(u64).range(1..))]
+    interval: u64,
 }
 
-fn resolve_active_sprint_for_board(
-    client: &Client,
-    base_url: &str,
-    board_id: u64,
-) -> Result<ResolvedSprint> {
-    let board = fetch_board(client, base_url, board_id)?;
-    let sprint = fetch_active_sprints_for_board(client, base_url, board_id)?.ok_or_else(|| {
-        anyhow!(
-            "No active sprint found on board {} ({}).",
-            board.id,
-            board.name
-        )
-    })?;
+#[derive(Args, Debug)]
+struct GateArgs {
+    /// JQL query for the issues that block the gate (e.g., 'fixVersion = 2.5 AND status != Done')
+    #[clap(long)]
+    jql: String,
 
-    Ok(ResolvedSprint {
-        id: sprint.id,
-        name: sprint.name,
-        board_id: board.id,
-        board_name: board.name,
-        start_date: sprint.start_date,
-    })
+    /// Most matching issues the gate lets through
+    #[clap(long, default_value_t = 0)]
+    max: usize,
+
+    /// How many of the matching issues to list when the gate fails
+    #[clap(long, default_value_t = 20)]
+    show: usize,
 }
 
-fn fetch_scrum_boards_for_project(
-    client: &Client,
-    base_url: &str,
-    project_key: &str,
-) -> Result<Vec<JiraBoard>> {
-    let mut start_at = 0;
-    let mut boards = Vec::new();
+#[derive(Args, Debug)]
+struct SyncPrArgs {
+    /// Pull request event, such as opened, reopened, merged, or closed
+    #[clap(long, env = "JIT_PR_EVENT")]
+    event: String,
 
-    loop {
-        let url = format!(
-            "{}/rest/agile/1.0/board?projectKeyOrId={}&type=scrum&startAt={}&maxResults=50",
-            base_url, project_key, start_at
-        );
-        let response = client
-            .get(&url)
-            .send()
-            .context("Failed to send request to Jira Agile API")?;
+    /// Ticket key to transition; otherwise keys are read from the branch and title
+    #[clap(long, env = "JIT_PR_TICKET")]
+    ticket: Option<String>,
 
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "Jira Agile API request failed with status: {} - {}",
-                response.status(),
-                response.text().unwrap_or_default()
-            ));
-        }
+    /// Pull request source branch used to find ticket keys
+    #[clap(long, env = "JIT_PR_BRANCH")]
+    branch: Option<String>,
 
-        let page: JiraBoardPage = response
-            .json()
-            .context("Failed to parse Jira Agile API response")?;
-        let page_size = page.values.len();
-        boards.extend(page.values);
+    /// Pull request title used to find ticket keys
+    #[clap(long, env = "JIT_PR_TITLE")]
+    title: Option<String>,
 
-        if page.is_last || page_size == 0 {
-            break;
-        }
+    /// Print the transitions that would run without changing any tickets
+    #[clap(long)]
+    dry_run: bool,
+}
 
-        start_at = page.start_at + page.max_results.max(page_size);
-    }
+#[derive(Args, Debug)]
+struct BitbucketArgs {
+    #[command(subcommand)]
+    command: BitbucketCommands,
+}
 
-    Ok(boards)
+#[derive(Subcommand, Debug)]
+enum BitbucketCommands {
+    /// Create a branch named after the ticket on the configured Bitbucket repository
+    Branch(BitbucketBranchArgs),
+    /// Open a pull request for the ticket branch with the ticket summary and description
+    Pr(BitbucketPrArgs),
 }
 
-fn fetch_board(client: &Client, base_url: &str, board_id: u64) -> Result<JiraBoard> {
-    let url = format!("{}/rest/agile/1.0/board/{}", base_url, board_id);
-    let response = client
-        .get(&url)
-        .send()
-        .context("Failed to send request to Jira Agile API")?;
+#[derive(Args, Debug)]
+struct BitbucketBranchArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-    if !response.status().is_success() {
-        return Err(anyhow!(
-            "Jira Agile API request failed with status: {} - {}",
-            response.status(),
-            response.text().unwrap_or_default()
-        ));
-    }
+    /// Branch or commit to create the new branch from (default: the configured default branch)
+    #[clap(long)]
+    from: Option<String>,
 
-    response
-        .json()
-        .context("Failed to parse Jira Agile API response")
+    /// Output created branch details in JSON format
+    #[clap(long)]
+    json: bool,
 }
 
-fn fetch_active_sprints_for_board(
-    client: &Client,
-    base_url: &str,
-    board_id: u64,
-) -> Result<Option<JiraAgileSprint>> {
-    let mut start_at = 0;
-    let mut best: Option<JiraAgileSprint> = None;
+#[derive(Args, Debug)]
+struct BitbucketPrArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-    loop {
-        let url = format!(
-            "{}/rest/agile/1.0/board/{}/sprint?state=active&startAt={}&maxResults=50",
-            base_url, board_id, start_at
-        );
-        let response = client
-            .get(&url)
-            .send()
-            .context("Failed to send request to Jira Agile API")?;
+    /// Source branch for the pull request (default: the ticket branch name)
+    #[clap(long)]
+    source: Option<String>,
 
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "Jira Agile API request failed with status: {} - {}",
-                response.status(),
-                response.text().unwrap_or_default()
-            ));
-        }
+    /// Destination branch for the pull request (default: the configured default branch)
+    #[clap(long)]
+    destination: Option<String>,
 
-        let page: JiraSprintPage = response
-            .json()
-            .context("Failed to parse Jira Agile API response")?;
-        let page_size = page.values.len();
-        for sprint in page.values {
-            if is_better_active_sprint(&sprint, best.as_ref()) {
-                best = Some(sprint);
-            }
-        }
+    /// Output created pull request details in JSON format
+    #[clap(long)]
+    json: bool,
+}
 
-        if page.is_last || page_size == 0 {
-            break;
-        }
+#[derive(Args, Debug)]
+struct AuthArgs {
+    #[command(subcommand)]
+    command: Option<AuthCommands>,
+}
 
-        start_at = page.start_at + page.max_results.max(page_size);
-    }
+#[derive(Subcommand, Debug)]
+enum AuthCommands {
+    /// Ask for Jira credentials and save them (the same as plain `jit auth`)
+    Login(AuthLoginArgs),
+    /// Check the configured credentials and show who they belong to
+    Test,
+}
 
-    Ok(best)
+#[derive(Args, Debug)]
+struct AuthLoginArgs {
+    /// Keep the API token in the config file instead of the OS keyring
+    #[clap(long)]
+    plaintext: bool,
 }
 
-fn add_issue_to_sprint(
-    client: &Client,
-    base_url: &str,
-    sprint_id: u64,
-    issue_key: &str,
-) -> Result<()> {
-    let url = format!("{}/rest/agile/1.0/sprint/{}/issue", base_url, sprint_id);
-    let payload = json!({ "issues": [issue_key] });
-    let response = client
-        .post(&url)
-        .json(&payload)
-        .send()
-        .context("Failed to send request to Jira Agile API")?;
-
-    if !response.status().is_success() {
-        return Err(anyhow!(
-            "Jira Agile API request failed with status: {} - {}",
-            response.status(),
-            response.text().unwrap_or_default()
-        ));
-    }
-
-    Ok(())
-}
-
-fn is_better_sprint_candidate(
-    candidate: &ResolvedSprint,
-    current: Option<&ResolvedSprint>,
-) -> bool {
-    match current {
-        None => true,
-        Some(current) => compare_sprint_identity(
-            candidate.start_date.as_deref(),
-            candidate.id,
-            candidate.board_id,
-            current.start_date.as_deref(),
-            current.id,
-            current.board_id,
-        ),
-    }
+#[derive(Args, Debug)]
+struct SkillArgs {
+    #[command(subcommand)]
+    command: SkillCommands,
 }
 
-fn is_better_active_sprint(candidate: &JiraAgileSprint, current: Option<&JiraAgileSprint>) -> bool {
-    match current {
-        None => true,
-        Some(current) => compare_sprint_identity(
-            candidate.start_date.as_deref(),
-            candidate.id,
-            0,
-            current.start_date.as_deref(),
-            current.id,
-            0,
-        ),
-    }
+#[derive(Subcommand, Debug)]
+enum SkillCommands {
+    /// Download the latest SKILL.md and install it for all known agents (Codex and Claude Code)
+    Install(SkillInstallArgs),
 }
 
-fn compare_sprint_identity(
-    candidate_date: Option<&str>,
-    candidate_sprint_id: u64,
-    candidate_board_id: u64,
-    current_date: Option<&str>,
-    current_sprint_id: u64,
-    current_board_id: u64,
-) -> bool {
-    let candidate_date = candidate_date.and_then(parse_jira_datetime);
-    let current_date = current_date.and_then(parse_jira_datetime);
-
-    match (candidate_date, current_date) {
-        (Some(candidate), Some(current)) => {
-            candidate > current
-                || (candidate == current
-                    && (candidate_sprint_id, candidate_board_id)
-                        > (current_sprint_id, current_board_id))
-        }
-        (Some(_), None) => true,
-        (None, Some(_)) => false,
-        (None, None) => {
-            (candidate_sprint_id, candidate_board_id) > (current_sprint_id, current_board_id)
-        }
-    }
+#[derive(Args, Debug)]
+struct SkillInstallArgs {
+    /// Target a specific agent instead of installing for all known agents (codex | claude)
+    #[clap(long)]
+    agent: Option<String>,
 }
 
-fn parse_jira_datetime(value: &str) -> Option<DateTime<FixedOffset>> {
-    DateTime::parse_from_rfc3339(value).ok()
-}
+#[derive(Args, Debug, Clone)]
+struct QueryArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (e.g., https://company.atlassian.net/browse/RW-1931), or - to read one per line from stdin
+    ticket: Option<String>,
 
-fn build_issue_create_payload(args: &CreateArgs, assignee_id: Option<&str>) -> Value {
-    let mut fields = serde_json::Map::from_iter([
-        ("project".to_string(), json!({ "key": args.project })),
-        ("summary".to_string(), json!(args.summary)),
-        ("issuetype".to_string(), json!({ "name": args.issue_type })),
-    ]);
+    /// Output in JSON format
+    #[clap(long)]
+    json: bool,
 
-    if let Some(assignee_id) = assignee_id {
-        fields.insert("assignee".to_string(), json!({ "id": assignee_id }));
-    }
+    /// Output as plain text in format "KEY: Summary"
+    #[clap(long)]
+    text: bool,
 
-    if let Some(description) = args
-        .description
-        .as_deref()
-        .map(str::trim)
-        .filter(|text| !text.is_empty())
-    {
-        fields.insert("description".to_string(), text_to_adf(description));
-    }
+    /// Output as CSV with a header row
+    #[clap(long)]
+    csv: bool,
 
-    json!({ "fields": fields })
-}
+    /// Output one JSON object per ticket and line, printed as pages arrive
+    #[clap(long)]
+    ndjson: bool,
 
-fn build_issue_update_payload(args: &EditArgs, assignee_id: Option<&str>) -> Value {
-    let mut fields = serde_json::Map::new();
+    /// Output as GitHub-flavored Markdown: a ticket with its description, or a ticket table
+    #[clap(long)]
+    markdown: bool,
 
-    if let Some(summary) = args.summary.as_deref() {
-        fields.insert("summary".to_string(), json!(summary));
-    }
+    /// Columns to list, comma-separated (default: key,summary,status)
+    #[clap(long, value_enum, value_delimiter = ',', value_name = "COLUMNS")]
+    columns: Vec<IssueColumn>,
 
-    if let Some(issue_type) = args.issue_type.as_deref() {
-        fields.insert("issuetype".to_string(), json!({ "name": issue_type }));
-    }
+    /// Group the tickets into swimlanes, with how many in each are done
+    #[clap(long, value_enum)]
+    group_by: Option<IssueGrouping>,
 
-    if args.assignee.is_some() {
-        let assignee_value = assignee_id
-            .map(|account_id| json!({ "accountId": account_id }))
-            .unwrap_or(Value::Null);
-        fields.insert("assignee".to_string(), assignee_value);
-    }
+    /// Print each ticket through a Handlebars template, e.g. '{{key}} [{{status}}] {{summary}}', or a template named in [templates]
+    #[clap(long, value_name = "TEMPLATE")]
+    format: Option<String>,
 
-    if let Some(description) = args.description.as_deref() {
-        fields.insert(
-            "description".to_string(),
-            if description.trim().is_empty() {
-                Value::Null
-            } else {
-                text_to_adf(description)
-            },
-        );
-    }
+    /// Extra Jira fields to fetch and show, comma-separated IDs (e.g. labels,components,customfield_10016)
+    #[clap(long, value_delimiter = ',', value_name = "FIELDS")]
+    fields: Vec<String>,
 
-    json!({ "fields": fields })
-}
+    /// Print only the number of matching tickets
+    #[clap(long, conflicts_with_all = ["ticket", "format", "group_by", "fields", "columns"])]
+    count: bool,
 
-struct ResolvedAssignee {
-    account_id: Option<String>,
-    label: String,
-}
+    /// Display your current tickets in a table (default when no ticket is provided)
+    #[clap(long)]
+    my_tickets: bool,
 
-#[derive(Clone)]
-struct ResolvedSprint {
-    id: u64,
-    name: String,
-    board_id: u64,
-    board_name: String,
-    start_date: Option<String>,
-}
+    /// Show detailed information about a ticket in a table format
+    #[clap(long)]
+    show: bool,
 
-fn fetch_issue_pull_requests(
-    client: &Client,
-    base_url: &str,
-    issue_id: &str,
-) -> Result<Vec<JiraPullRequest>> {
-    let url = format!(
-        "{}/rest/dev-status/latest/issue/detail?issueId={}&applicationType=GitHub&dataType=pullrequest",
-        base_url, issue_id
-    );
+    /// Include ticket description in detailed output
+    #[clap(long)]
+    include_description: bool,
 
-    let response = client
-        .get(&url)
-        .send()
-        .context("Failed to send request to Jira dev-status API")?;
+    /// Include ticket comments in detailed output
+    #[clap(long)]
+    include_comments: bool,
 
-    if !response.status().is_success() {
-        return Err(anyhow!(
-            "Jira dev-status request failed with status: {} - {}",
-            response.status(),
-            response.text().unwrap_or_default()
-        ));
-    }
+    /// Include linked GitHub pull requests
+    #[clap(long)]
+    include_prs: bool,
 
-    let dev_status: JiraDevStatusResponse = response
-        .json()
-        .context("Failed to parse Jira dev-status response")?;
+    /// Include the latest CI/build status reported for the ticket's linked branches and pull requests
+    #[clap(long)]
+    include_ci: bool,
 
-    Ok(dev_status
-        .detail
-        .into_iter()
-        .flat_map(|detail| detail.pull_requests.into_iter())
-        .collect())
-}
+    /// Include description, comments, pull requests, and metadata in detailed output
+    #[clap(long)]
+    full: bool,
 
-fn fetch_pull_requests_for_tickets(
-    client: &Client,
-    base_url: &str,
-    tickets: &[JiraIssue],
-) -> Result<HashMap<String, Vec<JiraPullRequest>>> {
-    let mut by_key = HashMap::new();
+    /// Maximum number of comments to show (default: 5)
+    #[clap(long, default_value = "5")]
+    comments_limit: usize,
 
-    for ticket in tickets {
-        let prs = fetch_issue_pull_requests(client, base_url, &ticket.id)
-            .with_context(|| format!("Failed to fetch pull requests for {}", ticket.key))?;
-        by_key.insert(ticket.key.clone(), prs);
-    }
+    /// Show all comments (overrides --comments-limit)
+    #[clap(long)]
+    all_comments: bool,
 
-    Ok(by_key)
-}
+    /// Only include comments created on or after YYYY-MM-DD
+    #[clap(long)]
+    since: Option<String>,
 
-fn display_tickets_table(
-    tickets: &[JiraIssue],
-    pull_requests_by_key: Option<&HashMap<String, Vec<JiraPullRequest>>>,
-) -> Result<()> {
-    if tickets.is_empty() {
-        println!("No tickets found in the current sprint.");
-        return Ok(());
-    }
+    /// Link file:line references in the description and comments to VS Code (vscode://file/...)
+    #[clap(long)]
+    vscode_links: bool,
 
-    // Get sprint name from the first ticket
-    let sprint_name = tickets[0]
-        .fields
-        .sprint
-        .as_ref()
-        .and_then(|sprints| {
-            sprints
-                .iter()
-                .find(|s| s.state == "active")
-                .or_else(|| sprints.first())
-        })
-        .map_or("Unknown Sprint", |s| &s.name);
+    /// Maximum number of tickets to retrieve, or 0 for all
+    #[clap(long, default_value_t = DEFAULT_TICKET_LIMIT)]
+    limit: u32,
 
-    println!("Current Sprint: {}", sprint_name);
-    println!();
+    #[command(flatten)]
+    assignees: AssigneeFilterArgs,
 
-    // Create a simple table with basic formatting
-    let include_prs_column = pull_requests_by_key.is_some();
-    let mut header_row = vec![
-        "Key".to_string(),
-        "Summary".to_string(),
-        "Status".to_string(),
-    ];
-    if include_prs_column {
-        header_row.push("PRs".to_string());
-    }
-    let mut table = vec![header_row];
+    /// Path to a custom config.toml file
+    #[clap(long)]
+    config_file: Option<PathBuf>,
 
-    // Add the data rows
-    for ticket in tickets {
-        let status_text = ticket.fields.status.as_ref().map_or("Unknown", |s| &s.name);
-        let summary = truncate_with_ellipsis(&ticket.fields.summary, 58);
-        let colored_status = get_colored_status(status_text);
-
-        let mut row = vec![ticket.key.clone(), summary, colored_status];
-        if let Some(pr_map) = pull_requests_by_key {
-            let prs = pr_map
-                .get(&ticket.key)
-                .map(|entries| format_pull_request_summary(entries))
-                .unwrap_or_else(|| "-".to_string());
-            row.push(prs);
-        }
-        table.push(row);
-    }
-
-    // Calculate column widths
-    let mut col_widths = vec![20, 7, 6]; // Set Key column to fixed 20 width
-    if include_prs_column {
-        col_widths.push(5);
-    }
-    for row in &table {
-        for (i, cell) in row.iter().enumerate() {
-            // For status column with color codes, use the length of the plain text
-            let cell_width = if i == 2 && row[0] != "Key" {
-                // This is a status cell, get the original text length
-                let status_text = tickets
-                    [table.iter().position(|r| &r[0] == &row[0]).unwrap_or(0) - 1]
-                    .fields
-                    .status
-                    .as_ref()
-                    .map_or("Unknown", |s| &s.name);
-                status_text.len()
-            } else {
-                cell.len()
-            };
+    /// Use a named [profiles.<name>] section of the config (default: picked from a pasted ticket URL)
+    #[clap(long, env = "JIT_PROFILE")]
+    profile: Option<String>,
 
-            if i < col_widths.len() {
-                col_widths[i] = col_widths[i].max(cell_width + 2);
-            }
-        }
-    }
+    /// List your tickets from the top-level site and every profile at once, with an Instance column
+    #[clap(long, conflicts_with = "ticket")]
+    all_profiles: bool,
+}
 
-    // Print top border
-    print!("┌");
-    for (i, width) in col_widths.iter().enumerate() {
-        print!("{}", "─".repeat(*width));
-        if i < col_widths.len() - 1 {
-            print!("┬");
-        }
-    }
-    println!("┐");
+/// Assignee and quick filters shared by the commands that list or search issues.
+#[derive(Args, Debug, Default, Clone)]
+struct AssigneeFilterArgs {
+    /// Only include issues assigned to you
+    #[clap(long, conflicts_with_all = ["assignee", "team"])]
+    mine: bool,
 
-    // Print header row
-    for (row_idx, row) in table.iter().enumerate() {
-        print!("│");
-        for (col_idx, cell) in row.iter().enumerate() {
-            let cell_text = if row_idx == 0 {
-                format!(" {:<width$}", cell, width = col_widths[col_idx] - 1)
-            } else if col_idx == 2 {
-                // Add proper spacing for colored status
-                let status_text = tickets[row_idx - 1]
-                    .fields
-                    .status
-                    .as_ref()
-                    .map_or("Unknown", |s| &s.name);
-                format!(
-                    " {}{}",
-                    cell,
-                    " ".repeat(col_widths[col_idx] - status_text.len() - 1)
-                )
-            } else {
-                format!(" {:<width$}", cell, width = col_widths[col_idx] - 1)
-            };
+    /// Only include issues assigned to this person: @name, email, account ID, me, unassigned, or team:NAME (repeatable)
+    #[clap(long, value_name = "WHO")]
+    assignee: Vec<String>,
 
-            print!("{}", cell_text);
-            print!("│");
-        }
-        println!();
+    /// Only include issues assigned to a member of the [team] config section
+    #[clap(long)]
+    team: bool,
 
-        // Print row separator
-        if row_idx < table.len() - 1 {
-            print!("├");
-            for (i, width) in col_widths.iter().enumerate() {
-                print!("{}", "─".repeat(*width));
-                if i < col_widths.len() - 1 {
-                    print!("┼");
-                }
-            }
-            println!("┤");
-        }
-    }
+    /// Only include issues matching this quick filter from the [quickfilters] config section (repeatable; all must match)
+    #[clap(short = 'f', long = "filter", value_name = "NAME")]
+    quick_filters: Vec<String>,
+}
 
-    // Print bottom border
-    print!("└");
-    for (i, width) in col_widths.iter().enumerate() {
-        print!("{}", "─".repeat(*width));
-        if i < col_widths.len() - 1 {
-            print!("┴");
-        }
+impl AssigneeFilterArgs {
+    fn is_set(&self) -> bool {
+        self.mine || self.team || !self.assignee.is_empty()
     }
-    println!("┘");
-
-    Ok(())
 }
 
-// Truncate a string to max_len and add ellipsis if needed
-fn truncate_with_ellipsis(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        return s.to_string();
-    }
+#[derive(Args, Debug)]
+struct CreateArgs {
+    /// Jira project key (e.g., RW)
+    #[clap(long)]
+    project: String,
 
-    let mut result = s.chars().take(max_len - 3).collect::<String>();
-    result.push_str("...");
-    result
-}
+    /// Ticket summary
+    #[clap(
+        long,
+        required_unless_present_any = ["draft", "interactive"],
+        default_value = ""
+    )]
+    summary: String,
 
-fn extract_pr_id_from_url(url: &str) -> Option<String> {
-    let marker = "/pull/";
-    let start = url.find(marker)?;
-    let suffix = &url[start + marker.len()..];
-    let number = suffix.split(['/', '?', '#']).next().unwrap_or("").trim();
-    if number.is_empty() {
-        None
-    } else {
-        Some(format!("#{}", number))
-    }
-}
+    /// Plain-text ticket description
+    #[clap(long)]
+    description: Option<String>,
 
-fn pull_request_display_id(pr: &JiraPullRequest) -> String {
-    if let Some(id) = pr.id.as_deref().filter(|id| !id.trim().is_empty()) {
-        return id.to_string();
-    }
-    if let Some(url) = pr.url.as_deref() {
-        if let Some(id) = extract_pr_id_from_url(url) {
-            return id;
-        }
-    }
-    "PR".to_string()
-}
+    /// Jira issue type name, such as Task, Bug, or Story (default: Task)
+    #[clap(long = "type", default_value = "Task")]
+    issue_type: String,
 
-fn format_pull_request_summary(pull_requests: &[JiraPullRequest]) -> String {
-    if pull_requests.is_empty() {
-        return "-".to_string();
-    }
+    /// Assignee account ID, name, or email, or `me` to assign to the current Jira user (default: me)
+    #[clap(long, default_value = "me")]
+    assignee: String,
 
-    let mut ids: Vec<String> = pull_requests.iter().map(pull_request_display_id).collect();
-    if ids.len() > 3 {
-        let remaining = ids.len() - 3;
-        ids.truncate(3);
-        format!("{} +{}", ids.join(", "), remaining)
-    } else {
-        ids.join(", ")
-    }
-}
+    /// Add the created ticket to the current active sprint instead of leaving it in the backlog
+    #[clap(long)]
+    current_sprint: bool,
 
-/// Returns color-coded status text based on the status name
-fn get_colored_status(status: &str) -> String {
-    match status.to_lowercase().as_str() {
-        s if s.contains("done") => status.bright_green().bold().to_string(),
-        s if s.contains("complete") => status.bright_green().bold().to_string(),
-        s if s.contains("resolved") => status.bright_green().bold().to_string(),
+    /// Jira board ID to use for current sprint selection; otherwise the most recently started active sprint is used
+    #[clap(long, requires = "current_sprint")]
+    board: Option<u64>,
 
-        s if s.contains("progress") => status.bright_yellow().bold().to_string(),
-        s if s.contains("review") => status.yellow().bold().to_string(),
-        s if s.contains("implement") => status.bright_yellow().bold().to_string(),
-        s if s.contains("testing") => status.bright_yellow().bold().to_string(),
+    /// Have the configured LLM draft the summary, description, and acceptance criteria from a short idea, then review it in $EDITOR
+    #[clap(long, value_name = "IDEA", conflicts_with = "summary")]
+    draft: Option<String>,
 
-        s if s.contains("todo") => status.bright_blue().to_string(),
-        s if s.contains("backlog") => status.blue().to_string(),
-        s if s.contains("selected") => status.cyan().to_string(),
-        s if s.contains("open") => status.bright_blue().to_string(),
+    /// Start from a template: `story` creates a Story with a user-story description
+    #[clap(long, value_enum, conflicts_with = "issue_type")]
+    template: Option<IssueTemplate>,
 
-        s if s.contains("block") => status.bright_red().bold().to_string(),
-        s if s.contains("impediment") => status.bright_red().bold().to_string(),
-        s if s.contains("cancel") => status.red().bold().to_string(),
-        s if s.contains("won't") => status.red().bold().to_string(),
-        s if s.contains("wont") => status.red().bold().to_string(),
+    /// Acceptance criterion, written to the [acceptance_criteria] field as an unchecked item (repeatable)
+    #[clap(long = "criterion", value_name = "TEXT")]
+    criteria: Vec<String>,
 
-        _ => status.white().to_string(),
-    }
-}
+    /// Prompt for the summary and description; bugs ask for steps to reproduce, expected and actual behavior, and environment
+    #[clap(short = 'i', long, conflicts_with = "draft")]
+    interactive: bool,
 
-/// Format a date string from JIRA's format to a more readable format
-fn format_date(date_str: &str) -> String {
-    if date_str.is_empty() {
-        return "Not set".to_string();
-    }
+    /// Append the OS, app version (from [create] version_command), and git commit to the description
+    #[clap(long)]
+    capture_env: bool,
 
-    // JIRA dates look like "2023-09-15T14:53:37.123+0000"
-    // We'll just extract the date part for simplicity
-    if let Some(date_part) = date_str.split('T').next() {
-        return date_part.to_string();
-    }
+    /// Output created issue details in JSON format
+    #[clap(long)]
+    json: bool,
 
-    date_str.to_string()
+    /// Extra fields for the create request, filled in from the config.
+    #[clap(skip)]
+    fields: serde_json::Map<String, Value>,
 }
 
-/// Render Atlassian Document Format (ADF) into readable text while preserving links.
-fn extract_plain_text_from_adf(adf: &Value) -> String {
-    let mut result = String::new();
-    render_adf_node(adf, &mut result);
-    result
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum IssueTemplate {
+    /// A Story whose description follows "As a ..., I want ..., so that ..."
+    Story,
 }
 
-fn render_adf_node(node: &Value, result: &mut String) {
-    let Some(node_type) = node.get("type").and_then(|t| t.as_str()) else {
-        if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
-            for child in content {
-                render_adf_node(child, result);
-            }
-        }
-        return;
-    };
-
-    match node_type {
-        "doc" => render_adf_blocks(node, result),
-        "paragraph" | "heading" | "blockquote" | "codeBlock" => {
-            let block = render_adf_inline_content(node);
-            append_block(result, &block);
-        }
-        "bulletList" => render_adf_list(node, result, false),
-        "orderedList" => render_adf_list(node, result, true),
-        "listItem" => result.push_str(render_adf_list_item(node).trim_end()),
-        "rule" => append_block(result, "---"),
-        "hardBreak" => result.push('\n'),
-        "inlineCard" | "blockCard" | "embedCard" => {
-            if let Some(url) = node
-                .get("attrs")
-                .and_then(|attrs| attrs.get("url"))
-                .and_then(|url| url.as_str())
-            {
-                result.push_str(url);
-            }
-        }
-        "mention" => {
-            if let Some(text) = node
-                .get("attrs")
-                .and_then(|attrs| attrs.get("text"))
-                .and_then(|text| text.as_str())
-            {
-                result.push_str(text);
-            }
-        }
-        "emoji" => {
-            if let Some(text) = node
-                .get("attrs")
-                .and_then(|attrs| attrs.get("text"))
-                .and_then(|text| text.as_str())
-                .or_else(|| {
-                    node.get("attrs")
-                        .and_then(|attrs| attrs.get("shortName"))
-                        .and_then(|text| text.as_str())
-                })
-            {
-                result.push_str(text);
-            }
-        }
-        "status" => {
-            if let Some(text) = node
-                .get("attrs")
-                .and_then(|attrs| attrs.get("text"))
-                .and_then(|text| text.as_str())
-            {
-                result.push_str(text);
-            }
-        }
-        "date" => {
-            if let Some(timestamp) = node
-                .get("attrs")
-                .and_then(|attrs| attrs.get("timestamp"))
-                .and_then(|timestamp| timestamp.as_str())
-            {
-                result.push_str(timestamp);
-            }
-        }
-        "text" => {
-            let text = node
-                .get("text")
-                .and_then(|t| t.as_str())
-                .unwrap_or_default();
-            result.push_str(text);
+#[derive(Args, Debug)]
+struct EditArgs {
+    /// JIRA issue key (e.g., RW-1931) or URL (default: the key in the current git branch name)
+    ticket: Option<String>,
 
-            if let Some(href) = node
-                .get("marks")
-                .and_then(|marks| marks.as_array())
-                .and_then(|marks| extract_link_href(marks))
-            {
-                let trimmed_text = text.trim();
-                if !href.is_empty() && trimmed_text != href && !trimmed_text.contains(href) {
-                    result.push_str(" (");
-                    result.push_str(href);
-                    result.push(')');
-                }
-            }
-        }
-        _ => {
-            if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
-                for child in content {
-                    render_adf_node(child, result);
-                }
-            }
-        }
-    }
-}
+    /// Updated ticket summary
+    #[clap(long)]
+    summary: Option<String>,
 
-fn render_adf_blocks(node: &Value, result: &mut String) {
-    if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
-        for child in content {
-            render_adf_node(child, result);
-        }
-    }
-}
+    /// Updated plain-text ticket description; pass an empty string to clear it
+    #[clap(long)]
+    description: Option<String>,
 
-fn render_adf_inline_content(node: &Value) -> String {
-    let mut block = String::new();
-    if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
-        for child in content {
-            render_adf_node(child, &mut block);
-        }
-    }
-    block
-}
+    /// Updated Jira issue type name, such as Task, Bug, or Story
+    #[clap(long = "type")]
+    issue_type: Option<String>,
 
-fn render_adf_list(node: &Value, result: &mut String, ordered: bool) {
-    if let Some(items) = node.get("content").and_then(|c| c.as_array()) {
-        for (index, item) in items.iter().enumerate() {
-            let rendered_item = render_adf_list_item(item);
-            if rendered_item.is_empty() {
-                continue;
-            }
+    /// Updated assignee account ID, name, or email, or `me` / `unassigned`
+    #[clap(long)]
+    assignee: Option<String>,
 
-            let prefix = if ordered {
-                format!("{}. ", index + 1)
-            } else {
-                "- ".to_string()
-            };
+    /// Story points, written to the site's story points field ([estimation] config or discovered)
+    #[clap(long, value_name = "POINTS")]
+    estimate: Option<f64>,
 
-            let mut lines = rendered_item.lines();
-            if let Some(first_line) = lines.next() {
-                append_block(result, &format!("{prefix}{first_line}"));
-            }
+    /// Output updated issue details in JSON format
+    #[clap(long)]
+    json: bool,
 
-            for line in lines {
-                append_block(result, line);
-            }
-        }
-    }
+    /// Extra fields for the update request, filled in from the site's fields.
+    #[clap(skip)]
+    fields: serde_json::Map<String, Value>,
 }
 
-fn render_adf_list_item(node: &Value) -> String {
-    let mut item = String::new();
-    if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
-        for child in content {
-            match child.get("type").and_then(|t| t.as_str()) {
-                Some("paragraph") | Some("heading") | Some("blockquote") | Some("codeBlock") => {
-                    let block = render_adf_inline_content(child);
-                    if !block.trim().is_empty() {
-                        if !item.is_empty() && !item.ends_with('\n') {
-                            item.push('\n');
-                        }
-                        item.push_str(block.trim_end());
-                    }
-                }
-                Some("bulletList") | Some("orderedList") => {
-                    let mut nested = String::new();
-                    render_adf_node(child, &mut nested);
-                    if !nested.trim().is_empty() {
-                        if !item.is_empty() && !item.ends_with('\n') {
-                            item.push('\n');
-                        }
-                        item.push_str(nested.trim_end());
-                    }
-                }
-                _ => render_adf_node(child, &mut item),
-            }
-        }
-    }
-    item.trim().to_string()
+#[derive(Debug, Deserialize)]
+struct AppConfig {
+    jira: JiraConfig,
+    /// Where to read the API token when the config has none.
+    #[serde(default)]
+    auth: credentials::AuthConfig,
+    #[serde(default)]
+    bitbucket: Option<BitbucketConfig>,
+    /// Maps pull request events (opened, merged, ...) to target Jira statuses for `jit sync-pr`.
+    #[serde(default)]
+    sync_pr: HashMap<String, String>,
+    #[serde(default)]
+    approval: ApprovalConfig,
+    #[serde(default)]
+    checklist: ChecklistConfig,
+    #[serde(default)]
+    acceptance_criteria: AcceptanceCriteriaConfig,
+    #[serde(default)]
+    estimation: EstimationConfig,
+    #[serde(default)]
+    capacity: CapacityConfig,
+    #[serde(default)]
+    lint: LintConfig,
+    #[serde(default)]
+    team: TeamConfig,
+    /// Named team rosters for `jit team` and `--assignee team:NAME`.
+    #[serde(default)]
+    teams: HashMap<String, TeamConfig>,
+    #[serde(default)]
+    dod: DodConfig,
+    #[serde(default)]
+    duplicate: DuplicateConfig,
+    #[serde(default)]
+    split: SplitConfig,
+    /// Named `--format` templates.
+    #[serde(default)]
+    templates: HashMap<String, String>,
+    /// JQL fragments for `-f NAME`, like a board's quick filters.
+    #[serde(default)]
+    quickfilters: HashMap<String, String>,
+    #[serde(default)]
+    output: OutputConfig,
+    #[serde(default)]
+    http: HttpConfig,
+    #[serde(default)]
+    share: ShareConfig,
+    #[serde(default)]
+    llm: Option<LlmConfig>,
+    /// Reusable comment templates for `jit comment --snippet`, with `{placeholder}` variables.
+    #[serde(default)]
+    snippets: HashMap<String, String>,
+    #[serde(default)]
+    deploy: DeployConfig,
+    /// Jira Automation incoming webhooks for `jit trigger`, by name.
+    #[serde(default)]
+    webhooks: HashMap<String, WebhookConfig>,
+    /// Field-watching rules evaluated by `jit watch`.
+    #[serde(default)]
+    watch: Vec<WatchRule>,
+    #[serde(default)]
+    git: GitConfig,
+    #[serde(default)]
+    start: StartConfig,
+    #[serde(default)]
+    create: CreateConfig,
+    /// `--show` layouts by issue type name, e.g. `[layouts.Bug]`.
+    #[serde(default)]
+    layouts: HashMap<String, LayoutConfig>,
 }
 
-fn append_block(result: &mut String, block: &str) {
-    let block = block.trim_end();
-    if block.is_empty() {
-        return;
-    }
+#[derive(Debug, Clone, Deserialize)]
+struct JiraConfig {
+    base_url: String,
+    /// Empty when `jit auth login` saved the token in the OS keyring.
+    #[serde(default)]
+    api_token: String,
+    /// Not needed with `auth = "pat"`.
+    #[serde(default)]
+    user_email: String,
+    #[serde(default)]
+    auth: JiraAuth,
+    /// Project key `jit quick` creates issues in.
+    #[serde(default)]
+    project: Option<String>,
+    /// Refuse commands that change Jira, e.g. in a profile for a production instance.
+    #[serde(default)]
+    read_only: bool,
+    /// Look a ticket up on the other profiles when this site does not have it.
+    #[serde(default)]
+    fallback_profiles: bool,
+    /// ID of the sprint field, when it should not be discovered from the site's fields.
+    #[serde(default)]
+    sprint_field: Option<String>,
+}
 
-    if !result.is_empty() && !result.ends_with('\n') {
-        result.push('\n');
-    }
-    result.push_str(block);
-    result.push('\n');
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum JiraAuth {
+    /// Email and API token, for Jira Cloud.
+    #[default]
+    Basic,
+    /// A personal access token sent as a bearer token, for Jira Server and Data Center.
+    Pat,
 }
 
-fn extract_link_href<'a>(marks: &'a [Value]) -> Option<&'a str> {
-    marks.iter().find_map(|mark| {
-        (mark.get("type").and_then(|t| t.as_str()) == Some("link"))
-            .then(|| {
-                mark.get("attrs")
-                    .and_then(|attrs| attrs.get("href"))
-                    .and_then(|href| href.as_str())
-            })
-            .flatten()
-    })
+#[derive(Debug, Deserialize)]
+struct BitbucketConfig {
+    workspace: String,
+    repo_slug: String,
+    username: String,
+    app_password: String,
+    #[serde(default = "default_bitbucket_api_url")]
+    api_url: String,
+    #[serde(default = "default_bitbucket_branch")]
+    default_branch: String,
 }
 
-fn adf_value_to_display_text(value: &Value) -> String {
-    let plain_text = extract_plain_text_from_adf(value);
-    if plain_text.trim().is_empty() {
-        serde_json::to_string_pretty(value)
-            .unwrap_or_else(|_| "Cannot display content.".to_string())
-    } else {
-        plain_text.trim().to_string()
+impl BitbucketConfig {
+    fn repository_url(&self) -> String {
+        format!(
+            "{}/repositories/{}/{}",
+            self.api_url, self.workspace, self.repo_slug
+        )
     }
 }
 
-fn text_to_adf(text: &str) -> Value {
-    let content: Vec<Value> = text
-        .split("\n\n")
-        .filter_map(|paragraph| {
-            let nodes: Vec<Value> = paragraph
-                .lines()
-                .enumerate()
-                .flat_map(|(index, line)| {
-                    let mut items = Vec::new();
-                    if index > 0 {
-                        items.push(json!({ "type": "hardBreak" }));
-                    }
-                    if !line.is_empty() {
-                        items.push(json!({
-                            "type": "text",
-                            "text": line,
-                        }));
-                    }
-                    items
-                })
-                .collect();
-
-            if nodes.is_empty() {
-                None
-            } else {
-                Some(json!({
-                    "type": "paragraph",
-                    "content": nodes,
-                }))
-            }
-        })
-        .collect();
+#[derive(Debug, Deserialize, Default)]
+struct ApprovalConfig {
+    /// Multi-user picker field that records who approved the ticket.
+    #[serde(default)]
+    field: Option<String>,
+    /// Status to transition to after `jit approve`.
+    #[serde(default)]
+    approved_status: Option<String>,
+    /// Status to transition to after `jit request-approval`.
+    #[serde(default)]
+    request_status: Option<String>,
+    /// Account IDs mentioned by `jit request-approval` when no --approver is passed.
+    #[serde(default)]
+    approvers: Vec<String>,
+}
 
-    json!({
-        "type": "doc",
-        "version": 1,
-        "content": content,
-    })
+#[derive(Debug, Deserialize, Default)]
+struct ChecklistConfig {
+    /// Custom field that stores the checklist.
+    #[serde(default)]
+    field: Option<String>,
+    /// Storage format: `text`, `items`, or `adf`. Detected from the field value when unset.
+    #[serde(default)]
+    format: Option<ChecklistFormat>,
 }
 
-fn comment_created_date(comment: &JiraComment) -> Option<&str> {
-    comment
-        .created
-        .as_deref()
-        .and_then(|timestamp| timestamp.split('T').next())
+#[derive(Debug, Deserialize, Default)]
+struct AcceptanceCriteriaConfig {
+    /// Custom field that stores acceptance criteria.
+    #[serde(default)]
+    field: Option<String>,
+    /// How `create --criterion` writes the field: `text`, `items`, or `adf` (default `adf`).
+    #[serde(default)]
+    format: Option<ChecklistFormat>,
 }
 
-fn get_filtered_comments<'a>(
-    issue: &'a JiraIssue,
-    since: Option<&str>,
-    comments_limit: usize,
-    all_comments: bool,
-) -> Vec<&'a JiraComment> {
-    let mut comments: Vec<&JiraComment> = issue
-        .fields
-        .comment
-        .as_ref()
-        .map(|container| container.comments.iter().collect())
-        .unwrap_or_default();
+#[derive(Debug, Deserialize, Default)]
+struct EstimationConfig {
+    /// Custom field that stores story points.
+    #[serde(default)]
+    story_points_field: Option<String>,
+}
 
-    if let Some(since_date) = since {
-        comments.retain(|comment| {
-            comment_created_date(comment)
-                .map(|date| date >= since_date)
-                .unwrap_or(false)
-        });
-    }
+#[derive(Debug, Deserialize, Default)]
+struct CapacityConfig {
+    /// Story points one person can take on per sprint, unless listed in `people`.
+    #[serde(default)]
+    default: Option<f64>,
+    /// Per-person sprint capacity in story points, keyed by name, email, or account ID.
+    #[serde(default)]
+    people: HashMap<String, f64>,
+}
 
-    if !all_comments && comments.len() > comments_limit {
-        comments = comments.into_iter().rev().take(comments_limit).collect();
-        comments.reverse();
-    }
+#[derive(Debug, Deserialize, Default)]
+struct TeamConfig {
+    /// Account IDs (or names and emails) that `--team` filters on.
+    #[serde(default)]
+    members: Vec<String>,
+}
 
-    comments
+#[derive(Debug, Deserialize, Default)]
+struct LintConfig {
+    /// Rules `jit lint` checks by default; every rule runs when empty.
+    #[serde(default)]
+    rules: Vec<String>,
 }
 
-fn build_issue_json(
-    issue: &JiraIssue,
-    include_description: bool,
-    include_comments: bool,
-    include_prs: bool,
-    pull_requests: &[JiraPullRequest],
-    comments_limit: usize,
-    all_comments: bool,
-    since: Option<&str>,
-) -> Value {
-    let sprint_name = issue
-        .fields
-        .sprint
-        .as_ref()
-        .and_then(|sprints| sprints.iter().find(|s| s.state == "active"))
-        .or_else(|| {
-            issue
-                .fields
-                .sprint
-                .as_ref()
-                .and_then(|sprints| sprints.first())
-        })
-        .map(|sprint| sprint.name.clone());
+#[derive(Debug, Deserialize, Default)]
+struct DodConfig {
+    /// Checks `jit dod` and `jit done` require. Defaults to `linked-pr` and
+    /// `fix-version`, plus `checklist-complete` when a checklist field is configured.
+    #[serde(default)]
+    checks: Vec<String>,
+    /// Status `jit done` moves tickets to.
+    #[serde(default)]
+    done_status: Option<String>,
+}
 
-    let mut payload = json!({
-        "ticket": issue.key,
-        "summary": issue.fields.summary,
-        "status": issue.fields.status.as_ref().map(|s| s.name.clone()),
-        "issue_type": issue.fields.issuetype.as_ref().map(|t| t.name.clone()),
-        "priority": issue.fields.priority.as_ref().map(|p| p.name.clone()),
-        "assignee": issue.fields.assignee.as_ref().map(|a| a.display_name.clone()),
-        "reporter": issue.fields.reporter.as_ref().map(|r| r.display_name.clone()),
-        "sprint": sprint_name,
-        "created": issue.fields.created.clone(),
-        "updated": issue.fields.updated.clone(),
-        "due_date": issue.fields.due_date.clone()
-    });
+#[derive(Debug, Deserialize, Default)]
+struct DuplicateConfig {
+    /// Status `jit dup` moves duplicates to (default: `[dod] done_status`, or Done).
+    #[serde(default)]
+    status: Option<String>,
+    /// Resolution set on the transition (default: Duplicate).
+    #[serde(default)]
+    resolution: Option<String>,
+    /// Issue link type whose outward description is "duplicates" (default: Duplicate).
+    #[serde(default)]
+    link_type: Option<String>,
+}
 
-    if let Some(obj) = payload.as_object_mut() {
-        if include_description {
-            let description = issue
-                .fields
-                .description
-                .as_ref()
-                .map(|desc| {
-                    if desc.is_null() {
-                        Value::Null
-                    } else {
-                        Value::String(adf_value_to_display_text(desc))
-                    }
-                })
-                .unwrap_or(Value::Null);
-            obj.insert("description".to_string(), description);
-        }
+#[derive(Debug, Deserialize, Default)]
+struct SplitConfig {
+    /// Issue link type whose outward description is "split to" (default: Issue split).
+    #[serde(default)]
+    link_type: Option<String>,
+}
 
-        if include_comments {
-            let comments = get_filtered_comments(issue, since, comments_limit, all_comments);
-            let comments_payload: Vec<Value> = comments
-                .iter()
-                .map(|comment| {
-                    let body_value = comment
-                        .body
-                        .as_ref()
-                        .map(|body| {
-                            if body.is_null() {
-                                Value::Null
-                            } else {
-                                Value::String(adf_value_to_display_text(body))
-                            }
-                        })
-                        .unwrap_or(Value::Null);
+#[derive(Debug, Deserialize, Default)]
+struct OutputConfig {
+    /// Format used when `--output` is not passed.
+    #[serde(default)]
+    format: Option<OutputFormat>,
+}
 
-                    json!({
-                        "author": comment.author.as_ref().map(|a| a.display_name.clone()),
-                        "created": comment.created.clone(),
-                        "updated": comment.updated.clone(),
-                        "body": body_value
-                    })
-                })
-                .collect();
+#[derive(Debug, Deserialize, Default)]
+struct ShareConfig {
+    /// Extra regular expressions `jit share --redact` replaces with `[redacted]`.
+    #[serde(default)]
+    redact_patterns: Vec<String>,
+}
 
-            obj.insert("comments".to_string(), Value::Array(comments_payload));
-            obj.insert("comments_returned".to_string(), json!(comments.len()));
-            if !all_comments {
-                obj.insert("comments_limit".to_string(), json!(comments_limit));
-            }
-            if let Some(since_date) = since {
-                obj.insert("comments_since".to_string(), json!(since_date));
-            }
-        }
+/// Which fields `--show` lists for one issue type, in order, and their labels.
+#[derive(Debug, Deserialize, Default)]
+struct LayoutConfig {
+    /// Names from [`LAYOUT_FIELDS`], or field IDs such as `environment` or `customfield_10050`.
+    fields: Vec<String>,
+    /// Labels by field, replacing the default name or ID.
+    #[serde(default)]
+    labels: HashMap<String, String>,
+}
 
-        if include_prs {
-            let pull_requests_payload: Vec<Value> = pull_requests
-                .iter()
-                .map(|pr| {
-                    json!({
-                        "id": pull_request_display_id(pr),
-                        "title": pr.name,
-                        "status": pr.status,
-                        "url": pr.url,
-                        "last_update": pr.last_update
-                    })
-                })
-                .collect();
+/// Layout fields with a name of their own, and their default labels. All
+/// but `labels` are fetched by `--show` anyway.
+const LAYOUT_FIELDS: &[(&str, &str)] = &[
+    ("type", "Type"),
+    ("issuetype", "Type"),
+    ("status", "Status"),
+    ("priority", "Priority"),
+    ("assignee", "Assignee"),
+    ("reporter", "Reporter"),
+    ("sprint", "Sprint"),
+    ("created", "Created"),
+    ("updated", "Updated"),
+    ("duedate", "Due Date"),
+    ("summary", "Summary"),
+    ("labels", "Labels"),
+];
 
-            obj.insert(
-                "pull_requests".to_string(),
-                Value::Array(pull_requests_payload),
-            );
-            obj.insert(
-                "pull_requests_count".to_string(),
-                json!(pull_requests.len()),
-            );
+#[derive(Debug, Deserialize, Default)]
+struct StartConfig {
+    /// Steps `jit start` runs in order: `assign`, `move:<status>`, `branch`, or
+    /// `comment:<text>` (default: assign, move:In Progress, branch).
+    #[serde(default)]
+    steps: Vec<String>,
+    /// Warn when `jit start` would leave you with more tickets in progress than this.
+    #[serde(default)]
+    wip_limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct CreateConfig {
+    /// Shell command whose first line of output is the app version `create --capture-env` records.
+    #[serde(default)]
+    version_command: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct GitConfig {
+    /// Branch name used by `jit branch` and the Bitbucket commands; `{key}`,
+    /// `{KEY}`, `{summary}`, and `{type}` are filled in.
+    #[serde(default)]
+    branch_template: Option<String>,
+    /// Prefix the `jit hook` commit hook adds to messages; `{KEY}` and `{key}` are filled in.
+    #[serde(default)]
+    commit_template: Option<String>,
+    /// Where `jit worktree` checks out a ticket, relative to the main worktree;
+    /// `{repo}`, `{key}`, `{KEY}`, and `{branch}` are filled in.
+    #[serde(default)]
+    worktree_template: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct DeployConfig {
+    /// Comment posted by `jit deployed`; `{env}`, `{version}`, and `{ticket}` are filled in.
+    #[serde(default)]
+    note: Option<String>,
+    /// Custom field that records where the ticket is deployed, such as "Environment deployed".
+    #[serde(default)]
+    environment_field: Option<String>,
+    /// How the environment field stores values: `text`, `select`, or `multi-select`.
+    #[serde(default)]
+    environment_field_type: EnvironmentFieldType,
+}
+
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum EnvironmentFieldType {
+    #[default]
+    Text,
+    Select,
+    MultiSelect,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookConfig {
+    /// Webhook URL shown in the rule's "Incoming webhook" trigger.
+    url: String,
+    /// Secret sent as `X-Automation-Webhook-Token`, for rules that use one.
+    #[serde(default)]
+    token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchRule {
+    /// Name shown in output and used to remember what the rule has seen.
+    name: String,
+    /// Issues to watch (default: my unresolved tickets).
+    #[serde(default = "default_watch_jql")]
+    jql: String,
+    /// Field to watch, such as `priority`, `status`, `assignee`, or a custom field ID.
+    field: String,
+    /// Fire when the field becomes this value; otherwise fire on every change.
+    #[serde(default)]
+    equals: Option<String>,
+    /// Shell command to run; the ticket is described in `JIT_*` environment variables.
+    #[serde(default)]
+    run: Option<String>,
+    /// Desktop notification text; `{ticket}`, `{summary}`, `{field}`, `{value}`, and `{previous}` are filled in.
+    #[serde(default)]
+    notify: Option<String>,
+}
+
+fn default_watch_jql() -> String {
+    "assignee = currentUser() AND resolution = EMPTY".to_string()
+}
+
+const DEFAULT_DEPLOY_NOTE: &str = "Deployed to {env} in {version}.";
+
+#[derive(Debug, Deserialize)]
+struct LlmConfig {
+    /// Base URL of an OpenAI-compatible API, e.g. `https://api.openai.com/v1`.
+    base_url: String,
+    /// Model name sent with every request.
+    model: String,
+    /// Bearer token; leave unset for local servers that do not need one.
+    #[serde(default)]
+    api_key: Option<String>,
+}
+
+fn default_bitbucket_api_url() -> String {
+    "https://api.bitbucket.org/2.0".to_string()
+}
+
+fn default_bitbucket_branch() -> String {
+    "main".to_string()
+}
+
+fn main() -> ExitCode {
+    let args = Cli::parse();
+    let json_errors = json_errors_requested(&args);
+    let show_stats = args.stats;
+    // Commands that check or replace credentials themselves explain a rejected token their own way.
+    let reauth_query = (!json_errors
+        && !matches!(
+            args.command,
+            Some(
+                Commands::Auth(_)
+                    | Commands::Init
+                    | Commands::Hook(_)
+                    | Commands::Statusline(_)
+                    | Commands::Prompt(_)
+                    | Commands::Serve(_)
+            )
+        ))
+    .then(|| args.query.clone());
+    let started = Instant::now();
+
+    // Skip commands whose stderr nobody reads: the commit hook and status bars.
+    if !matches!(
+        args.command,
+        Some(
+            Commands::Hook(_) | Commands::Statusline(_) | Commands::Prompt(_) | Commands::Serve(_)
+        )
+    ) {
+        show_due_reminders();
+    }
+    let result = run(args);
+    // The people cache only saves lookups next time, so a failed write is not an error.
+    let _ = people::save();
+    if show_stats {
+        http::stats().print(started.elapsed());
+    }
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            if json_errors {
+                eprintln!("{}", error::error_json(&err));
+            } else {
+                eprintln!("Error: {:?}", err);
+                if let (Some(JitError::Auth(_)), Some(query)) =
+                    (error::find(&err), reauth_query.as_ref())
+                {
+                    if let Err(err) = replace_rejected_token(query) {
+                        eprintln!("Error: {:#}", err);
+                    }
+                } else if let Some(hint) = error::hint(&err) {
+                    eprintln!("\nHint: {}", hint);
+                }
+            }
+            ExitCode::from(error::exit_code(&err))
         }
     }
+}
 
-    payload
+/// Errors are printed as JSON when the command was asked for machine-readable output.
+fn json_errors_requested(args: &Cli) -> bool {
+    let command_json = match &args.command {
+        Some(Commands::Create(create_args)) => create_args.json,
+        Some(Commands::Edit(edit_args)) => edit_args.json,
+        Some(Commands::Bitbucket(bitbucket_args)) => match &bitbucket_args.command {
+            BitbucketCommands::Branch(branch_args) => branch_args.json,
+            BitbucketCommands::Pr(pr_args) => pr_args.json,
+        },
+        Some(Commands::Check(check_args)) => check_args.json,
+        Some(Commands::Groom(groom_args)) => groom_args.json,
+        Some(Commands::Lint(lint_args)) => lint_args.json,
+        Some(Commands::Dod(dod_args)) => dod_args.json,
+        Some(Commands::Stats(stats_args)) => stats_args.json,
+        Some(Commands::Comments(comments_args)) => comments_args.json,
+        _ => false,
+    };
+    command_json
+        || args.query.json
+        || matches!(args.output, Some(OutputFormat::Json | OutputFormat::Ndjson))
 }
 
-/// Display detailed information about a JIRA ticket in a table format.
-fn display_detailed_ticket(
-    issue: &JiraIssue,
-    include_description: bool,
-    include_comments: bool,
-    include_prs: bool,
-    pull_requests: &[JiraPullRequest],
-    comments_limit: usize,
-    all_comments: bool,
-    since: Option<&str>,
-) -> Result<()> {
-    println!("{}", "TICKET DETAILS".bold());
-    println!();
+fn run(args: Cli) -> Result<()> {
+    if let Some(since) = args.query.since.as_deref() {
+        validate_since_date(since)?;
+    }
 
-    // Print the ticket key and summary as headers
-    println!("{}: {}", issue.key.bold(), issue.fields.summary.bold());
-    println!();
+    if args.query.all_profiles && (args.command.is_some() || args.porcelain.is_some()) {
+        return Err(JitError::validation(
+            "--all-profiles is supported by jit (your tickets) only.",
+        )
+        .into());
+    }
 
-    // Type and Priority
-    let issue_type = issue
-        .fields
-        .issuetype
-        .as_ref()
-        .map_or("Not set", |t| &t.name);
-    let priority = issue
-        .fields
-        .priority
-        .as_ref()
-        .map_or("Not set", |p| &p.name);
+    if args.porcelain.is_some() && !matches!(args.command, None | Some(Commands::Current)) {
+        return Err(JitError::validation(
+            "--porcelain is supported by jit (your tickets), jit ISSUE-123, and jit current.",
+        )
+        .into());
+    }
 
-    // Status and Sprint
-    let status = issue.fields.status.as_ref().map_or("Not set", |s| &s.name);
-    let sprint = issue
-        .fields
-        .sprint
-        .as_ref()
-        .and_then(|sprints| sprints.iter().find(|s| s.state == "active"))
-        .map_or("Not in sprint", |s| &s.name);
+    if let Some(Commands::Auth(auth_args)) = args.command.as_ref() {
+        let plaintext = match &auth_args.command {
+            Some(AuthCommands::Login(login_args)) => login_args.plaintext,
+            Some(AuthCommands::Test) => {
+                return run_auth_test_command(&args.query, args.output);
+            }
+            None => false,
+        };
+        return run_auth_command(args.query.config_file.as_deref(), false, plaintext);
+    }
 
-    // Assignee and Reporter
-    let assignee = issue
-        .fields
-        .assignee
-        .as_ref()
-        .map_or("Unassigned", |a| &a.display_name);
-    let reporter = issue
-        .fields
-        .reporter
-        .as_ref()
-        .map_or("Unknown", |r| &r.display_name);
+    if matches!(args.command, Some(Commands::Init)) {
+        return run_auth_command(args.query.config_file.as_deref(), true, false);
+    }
 
-    // Created and Updated dates
-    let created = issue.fields.created.as_ref().map_or("Unknown", |d| d);
-    let updated = issue.fields.updated.as_ref().map_or("Unknown", |d| d);
+    if let Some(Commands::Config(config_args)) = args.command.as_ref() {
+        return run_config_command(&args.query, config_args, args.output);
+    }
 
-    // Due Date
-    let due_date = issue.fields.due_date.as_ref().map_or("Not set", |d| d);
+    if let Some(Commands::Statusline(statusline_args)) = args.command.as_ref() {
+        return run_statusline_command(&args.query, statusline_args);
+    }
 
-    // Calculate width needed for label columns
-    let left_col_width = 12; // "Due Date: " width
-    let val_col_width = 18; // Width for value columns
-    // Create a custom-drawn table with perfectly aligned columns
-    println!(
-        "{:<left$} {:<val$} {:<left$} {:<val$}",
-        "Type:".bold(),
-        issue_type,
-        "Priority:".bold(),
-        priority,
-        left = left_col_width,
-        val = val_col_width
-    );
+    if let Some(Commands::Prompt(prompt_args)) = args.command.as_ref() {
+        return run_prompt_command(&args.query, prompt_args);
+    }
 
-    println!(
-        "{:<left$} {:<val$} {:<left$} {:<val$}",
-        "Status:".bold(),
-        get_colored_status(status),
-        "Sprint:".bold(),
-        sprint,
-        left = left_col_width,
-        val = val_col_width
-    );
+    if let Some(Commands::Skill(skill_args)) = args.command.as_ref() {
+        let SkillCommands::Install(install_args) = &skill_args.command;
+        return run_skill_install_command(install_args);
+    }
+
+    if let Some(Commands::Hook(hook_args)) = args.command.as_ref() {
+        return run_hook_command(&args.query, hook_args);
+    }
+
+    if let Some(Commands::Remind(remind_args)) = args.command.as_ref() {
+        // Reminders are local, so a missing or broken config only loses the output default.
+        let output = args
+            .output
+            .or_else(|| {
+                load_configuration(&args.query)
+                    .ok()
+                    .and_then(|config| config.output.format)
+            })
+            .unwrap_or(OutputFormat::Table);
+        return run_remind_command(remind_args, output);
+    }
+
+    let config = load_configuration(&args.query)?;
+    if config.jira.read_only
+        && let Some(command) = args.command.as_ref().and_then(jira_write_command)
+    {
+        return Err(JitError::Config(format!(
+            "jit {} would change Jira, but {} is configured read-only (jira.read_only = true). Use another profile, or remove read_only to allow changes.",
+            command, config.jira.base_url
+        ))
+        .into());
+    }
+
+    let jira = create_jira_api_client(&config.jira, &config.http)?;
+    if let Some(field) = config.estimation.story_points_field.as_deref() {
+        site_fields::set_story_points_field(&config.jira.base_url, field);
+    }
+    let client = jira.http().clone();
+    let output = args
+        .output
+        .or(config.output.format)
+        .unwrap_or(OutputFormat::Table);
+
+    match args.command {
+        Some(Commands::Auth(_)) => unreachable!("auth command is handled before loading config"),
+        Some(Commands::Init) => unreachable!("init command is handled before loading config"),
+        Some(Commands::Config(_)) => {
+            unreachable!("config command is handled before loading config")
+        }
+        Some(Commands::Statusline(_)) => {
+            unreachable!("statusline command is handled before loading config")
+        }
+        Some(Commands::Prompt(_)) => {
+            unreachable!("prompt command is handled before loading config")
+        }
+        Some(Commands::Skill(_)) => unreachable!("skill command is handled before loading config"),
+        Some(Commands::Hook(_)) => unreachable!("hook command is handled before loading config"),
+        Some(Commands::Remind(_)) => {
+            unreachable!("remind command is handled before loading config")
+        }
+        Some(Commands::Create(mut create_args)) => {
+            if let Some(idea) = create_args.draft.take() {
+                apply_issue_draft(&config, &mut create_args, &idea)?;
+            }
+            apply_issue_template(&config, &mut create_args);
+            if create_args.interactive {
+                prompt_issue_description(&mut create_args, &mut io::stdin().lock())?;
+            }
+            if create_args.capture_env {
+                append_captured_environment(&config, &mut create_args)?;
+            }
+            run_create_issue_command(&client, &config.jira.base_url, &create_args, output)
+        }
+        Some(Commands::Edit(mut edit_args)) => {
+            if let Some(points) = edit_args.estimate {
+                edit_args.fields.insert(
+                    site_fields::story_points_field(&client, &config.jira.base_url),
+                    json!(points),
+                );
+            }
+            run_edit_issue_command(&client, &config.jira.base_url, &edit_args, output)
+        }
+        Some(Commands::Bitbucket(bitbucket_args)) => {
+            run_bitbucket_command(&jira, &config, &bitbucket_args, output)
+        }
+        Some(Commands::SyncPr(sync_args)) => {
+            run_sync_pr_command(&jira, &config, &sync_args, output)
+        }
+        Some(Commands::ProcessCommits(process_args)) => {
+            run_process_commits_command(&client, &config, &process_args, output)
+        }
+        Some(Commands::Train(train_args)) => run_train_command(&jira, &config, &train_args, output),
+        Some(Commands::VerifyRelease(verify_args)) => {
+            run_verify_release_command(&jira, &verify_args, output)
+        }
+        Some(Commands::Deployed(deployed_args)) => {
+            run_deployed_command(&jira, &config, &deployed_args, output)
+        }
+        Some(Commands::Trigger(trigger_args)) => {
+            run_trigger_command(&config, &trigger_args, output)
+        }
+        Some(Commands::Watch(watch_args)) => run_watch_command(&jira, &config, &watch_args),
+        Some(Commands::Comments(comments_args)) => {
+            run_comments_command(&client, &config.jira.base_url, &comments_args, output)
+        }
+        Some(Commands::Team(team_args)) => run_team_command(&jira, &config, &team_args, output),
+        Some(Commands::Move(move_args)) => {
+            run_move_command(&client, &config.jira.base_url, &move_args, output)
+        }
+        Some(Commands::Deps(deps_args)) => run_deps_command(&jira, &deps_args, output),
+        Some(Commands::Assign(assign_args)) => {
+            run_assign_command(&client, &config.jira.base_url, &assign_args, output)
+        }
+        Some(Commands::Search(search_args)) => {
+            run_search_command(&jira, &config, &search_args, output)
+        }
+        Some(Commands::Export(export_args)) => {
+            run_export_command(&client, &config.jira.base_url, &export_args, output)
+        }
+        Some(Commands::Open(open_args)) => run_open_command(&config.jira.base_url, &open_args),
+        Some(Commands::Branch(branch_args)) => {
+            run_branch_command(&jira, &config, &branch_args, output)
+        }
+        Some(Commands::Branches(branches_args)) => {
+            run_branches_command(&jira, &branches_args, output)
+        }
+        Some(Commands::Worktree(worktree_args)) if worktree_args.prune => {
+            run_worktree_prune_command(&jira, &worktree_args, output)
+        }
+        Some(Commands::Worktree(worktree_args)) => {
+            run_worktree_command(&jira, &config, &worktree_args, output)
+        }
+        Some(Commands::Plan(plan_args)) => run_plan_command(&client, &config, &plan_args),
+        Some(Commands::Quick(quick_args)) => {
+            run_quick_command(&client, &config, quick_args, output)
+        }
+        Some(Commands::Start(start_args)) => run_start_command(&jira, &config, &start_args, output),
+        Some(Commands::Focus(focus_args)) => {
+            run_focus_command(&jira, &config.jira.base_url, &focus_args, output)
+        }
+        Some(Commands::Current) => run_current_command(&jira, &config, output, args.porcelain),
+        Some(Commands::Wait(wait_args)) => run_wait_command(&jira, &wait_args, output),
+        Some(Commands::Gate(gate_args)) => run_gate_command(&jira, &gate_args, output),
+        Some(Commands::Approve(approve_args)) => {
+            run_approve_command(&client, &config, &approve_args, output)
+        }
+        Some(Commands::RequestApproval(request_args)) => {
+            run_request_approval_command(&client, &config, &request_args, output)
+        }
+        Some(Commands::Check(check_args)) => {
+            run_check_command(&client, &config, &check_args, output)
+        }
+        Some(Commands::Triage(triage_args)) => run_triage_command(&jira, &config, &triage_args),
+        Some(Commands::Poker(poker_args)) => run_poker_command(&jira, &config, &poker_args),
+        Some(Commands::Groom(groom_args)) => run_groom_command(&jira, &config, &groom_args, output),
+        Some(Commands::Lint(lint_args)) => run_lint_command(&jira, &config, &lint_args, output),
+        Some(Commands::Dod(dod_args)) => run_dod_command(&jira, &config, &dod_args, output),
+        Some(Commands::Done(done_args)) => run_done_command(&jira, &config, &done_args, output),
+        Some(Commands::Dup(dup_args)) => run_dup_command(&client, &config, &dup_args, output),
+        Some(Commands::Merge(merge_args)) => {
+            run_merge_command(&client, &config, &merge_args, output)
+        }
+        Some(Commands::Split(split_args)) => {
+            run_split_command(&jira, &config, &split_args, &mut io::stdin().lock(), output)
+        }
+        Some(Commands::Stats(stats_args)) if stats_args.trend => {
+            run_stats_trend(&stats_args, output)
+        }
+        Some(Commands::Stats(stats_args)) => run_stats_command(&jira, &stats_args, output),
+        Some(Commands::Heatmap(heatmap_args)) => run_heatmap_command(&jira, &heatmap_args, output),
+        Some(Commands::Snapshot(snapshot_args)) => {
+            run_snapshot_command(&jira, &snapshot_args, output)
+        }
+        Some(Commands::Share(share_args)) => run_share_command(&jira, &config, &share_args, output),
+        Some(Commands::Summarize(summarize_args)) => {
+            run_summarize_command(&jira, &config, &summarize_args, output)
+        }
+        Some(Commands::Comment(comment_args)) => {
+            run_comment_command(&client, &config, &comment_args, output)
+        }
+        Some(Commands::Serve(serve_args)) => {
+            run_serve_command(&jira, config.jira.read_only, &serve_args)
+        }
+        Some(Commands::Trace(trace_args)) => run_trace_command(&jira, &trace_args, output),
+        Some(Commands::Fields(fields_args)) => {
+            run_fields_command(&client, &config.jira.base_url, &fields_args, output)
+        }
+        None if args.query.all_profiles => run_all_profiles_query(&args.query, output),
+        None => run_query_mode(&jira, &config, args.query, output, args.porcelain),
+    }
+}
+
+/// `jit search`: the issues matching `args.jql`, fetched page by page up to
+/// `--limit`.
+fn run_search_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: &SearchArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let output = if args.text {
+        OutputFormat::Text
+    } else {
+        output
+    }
+    .or_csv(args.csv)
+    .or_ndjson(args.ndjson)
+    .or_markdown(args.markdown);
+    let template = output_template(config, args.format.as_deref())?;
+    let jql = with_assignee_filter(jira.http(), config, &args.jql, &args.assignees)?;
+    if args.count {
+        return render_count(jira.count(&jql)?, output);
+    }
+    let columns = template_columns(template.as_ref(), &args.columns);
+    let fields = column_fields(&["summary", "status"], columns, args.group_by, &args.fields);
+    let list = |issues: &[JiraIssue]| {
+        IssueListView::search_results(issues)
+            .with_columns(columns)
+            .with_grouping(args.group_by)
+            .with_extra_fields(&args.fields)
+    };
+    if template.is_some() || prints_by_page(output) {
+        print_issue_pages(jira, &jql, &fields, args.limit, &mut |page| {
+            render_formatted(output, template.as_ref(), &list(page))
+        })?;
+        return Ok(());
+    }
+    if streams_table(output, args.group_by) {
+        let mut table = IssueTableStream::new(false);
+        print_issue_pages(jira, &jql, &fields, args.limit, &mut |page| {
+            table.print_page(&list(page));
+            Ok(())
+        })?;
+        table.finish();
+        return Ok(());
+    }
+    let issues = jira.search(&jql, &fields, issue_limit(args.limit))?;
+    render_output(output, &list(&issues))
+}
+
+/// `--count`: just the number, or `{"count": n}` as JSON.
+fn render_count(count: usize, output: OutputFormat) -> Result<()> {
+    render_output(
+        output,
+        &RecordOutput::new(json!({ "count": count }))
+            .field("Count", count)
+            .line(count.to_string())
+            .human(|| {
+                println!("{}", count);
+                Ok(())
+            }),
+    )
+}
+
+/// The `--format` template: one named in the [templates] config section, or
+/// `format` itself.
+fn output_template(config: &AppConfig, format: Option<&str>) -> Result<Option<OutputTemplate>> {
+    let Some(format) = format else {
+        return Ok(None);
+    };
+    let template = match config.templates.get(format) {
+        Some(template) => template.as_str(),
+        None if format.contains("{{") => format,
+        None => {
+            return Err(JitError::Config(format!(
+                "No template named '{}' in the [templates] config section. Add it there, or pass a template such as '{{{{key}}}}: {{{{summary}}}}'.",
+                format
+            ))
+            .into());
+        }
+    };
+    OutputTemplate::new(template).map(Some)
+}
+
+/// Every column for a template, which can use any of them, or else `columns`.
+fn template_columns<'a>(
+    template: Option<&OutputTemplate>,
+    columns: &'a [IssueColumn],
+) -> &'a [IssueColumn] {
+    if template.is_some() {
+        IssueColumn::value_variants()
+    } else {
+        columns
+    }
+}
+
+/// `fields` plus the Jira fields `columns` and `group_by` are read from,
+/// and the `extra` fields from `--fields`.
+fn column_fields<'a>(
+    fields: &[&'a str],
+    columns: &[IssueColumn],
+    group_by: Option<IssueGrouping>,
+    extra: &'a [String],
+) -> Vec<&'a str> {
+    let mut fields = fields.to_vec();
+    let needed = columns
+        .iter()
+        .map(|column| column.field())
+        .chain(group_by.map(IssueGrouping::field))
+        .chain(extra.iter().map(String::as_str));
+    for field in needed {
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+    fields
+}
+
+/// Whether `output` prints each issue on its own line, so a list can be
+/// printed a page at a time.
+fn prints_by_page(output: OutputFormat) -> bool {
+    matches!(output, OutputFormat::Text | OutputFormat::Ndjson)
+}
+
+/// Whether a list is printed as a table a page at a time, its columns sized
+/// to the first page. Epic swimlanes need every issue, so they wait for all of them.
+fn streams_table(output: OutputFormat, group_by: Option<IssueGrouping>) -> bool {
+    output == OutputFormat::Table && group_by.is_none()
+}
+
+/// Prints the issues matching `jql` with `print` page by page as Jira
+/// returns them, returning how many there were.
+fn print_issue_pages(
+    jira: &impl JiraApi,
+    jql: &str,
+    fields: &[&str],
+    limit: u32,
+    print: &mut dyn FnMut(&[JiraIssue]) -> Result<()>,
+) -> Result<usize> {
+    let mut printed = 0;
+    jira.search_pages(jql, fields, issue_limit(limit), &mut |page| {
+        printed += page.len();
+        print(&page)?;
+        io::stdout().flush()?;
+        Ok(())
+    })?;
+    Ok(printed)
+}
+
+/// `jit -`: one ticket per line of `input`, as a key or URL. Lines that are
+/// not tickets, or that Jira cannot find, are reported on stderr and skipped;
+/// the run fails at the end if there were any. Text and NDJSON output are
+/// printed as each ticket arrives, so they can feed the next command in a pipeline.
+fn print_tickets_from_lines(
+    jira: &impl JiraApi,
+    input: impl BufRead,
+    output: OutputFormat,
+) -> Result<()> {
+    let key = Regex::new(r"^[A-Z][A-Z0-9_]+-\d+$").expect("ticket key regex is valid");
+    let mut issues = Vec::new();
+    let mut failed = 0;
+    let mut total = 0;
+    for (number, line) in input.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        total += 1;
+        let ticket_id = match extract_ticket_id(line) {
+            Ok(ticket_id) if key.is_match(&ticket_id.to_ascii_uppercase()) => {
+                ticket_id.to_ascii_uppercase()
+            }
+            _ => {
+                eprintln!("line {}: '{}' is not a ticket key or URL", number + 1, line);
+                failed += 1;
+                continue;
+            }
+        };
+        match jira.issue(&ticket_id, true, false, false) {
+            Ok(issue) if output == OutputFormat::Text => {
+                println!("{}: {}", issue.key, issue.fields.summary);
+                io::stdout().flush()?;
+            }
+            Ok(issue) if output == OutputFormat::Ndjson => {
+                render_output(
+                    output,
+                    &IssueListView::search_results(std::slice::from_ref(&issue)),
+                )?;
+                io::stdout().flush()?;
+            }
+            Ok(issue) => issues.push(issue),
+            Err(err) => {
+                eprintln!("{}: {:#}", ticket_id, err);
+                failed += 1;
+            }
+        }
+    }
+    if !prints_by_page(output) {
+        render_output(output, &IssueListView::search_results(&issues))?;
+    }
+    if failed > 0 {
+        return Err(anyhow!(
+            "{} of {} lines could not be resolved",
+            failed,
+            total
+        ));
+    }
+    Ok(())
+}
+
+/// The first profile, other than the current site, where `ticket_id` exists.
+/// Profiles that cannot be loaded or reached are skipped.
+fn find_issue_profile(
+    args: &QueryArgs,
+    current: &AppConfig,
+    ticket_id: &str,
+) -> Result<Option<(String, AppConfig)>> {
+    let table = read_config_table(&resolve_config_path(args)?)?;
+    for profile in settings::profile_names(&table) {
+        let Ok(config) = load_configuration(&QueryArgs {
+            profile: Some(profile.clone()),
+            ..args.clone()
+        }) else {
+            continue;
+        };
+        if config.jira.base_url == current.jira.base_url {
+            continue;
+        }
+        let found = create_jira_api_client(&config.jira, &config.http)
+            .and_then(|jira| jira.issue(ticket_id, false, false, false));
+        if found.is_ok() {
+            return Ok(Some((profile, config)));
+        }
+    }
+    Ok(None)
+}
+
+/// `jit --all-profiles`: your sprint tickets from the top-level site and every
+/// profile, fetched concurrently. A site that fails is reported on stderr and
+/// left out, unless every one fails.
+fn run_all_profiles_query(query: &QueryArgs, output: OutputFormat) -> Result<()> {
+    let table = read_config_table(&resolve_config_path(query)?)?;
+    let names = std::iter::once(None).chain(settings::profile_names(&table).into_iter().map(Some));
+    // Configs load one at a time, as their token commands may prompt.
+    let instances: Vec<(String, Result<AppConfig>)> = names
+        .map(|profile| {
+            let config = load_configuration(&QueryArgs {
+                profile: profile.clone(),
+                ..query.clone()
+            });
+            (profile.unwrap_or_else(|| "default".to_string()), config)
+        })
+        .collect();
+
+    let count = instances.len();
+    let results: Vec<(String, Result<Vec<JiraIssue>>)> = thread::scope(|scope| {
+        let handles: Vec<_> = instances
+            .into_iter()
+            .map(|(name, config)| {
+                let handle = scope.spawn(move || {
+                    let config = config?;
+                    let jira = create_jira_api_client(&config.jira, &config.http)?;
+                    fetch_my_tickets(&jira, query.limit)
+                });
+                (name, handle)
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|(name, handle)| {
+                let tickets = handle
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow!("The request thread panicked")));
+                (name, tickets)
+            })
+            .collect()
+    });
+
+    let mut failures = Vec::new();
+    let mut items = Vec::new();
+    let mut rows = Vec::new();
+    for (instance, tickets) in results {
+        let tickets = match tickets {
+            Ok(tickets) => tickets,
+            Err(err) => {
+                failures.push((instance, err));
+                continue;
+            }
+        };
+        for issue in &tickets {
+            let status = issue
+                .fields
+                .status
+                .as_ref()
+                .map_or("Unknown", |status| &status.name);
+            items.push(json!({
+                "instance": instance,
+                "ticket": issue.key,
+                "summary": issue.fields.summary,
+                "status": status,
+            }));
+            rows.push(vec![
+                instance.clone(),
+                issue.key.clone(),
+                issue.fields.summary.clone(),
+                status.to_string(),
+            ]);
+        }
+    }
+    let all_failed = failures.len() == count;
+    let mut failures = failures.into_iter();
+    if all_failed && let Some((instance, err)) = failures.next() {
+        for (instance, err) in failures {
+            eprintln!("{}: {:#}", instance, err);
+        }
+        return Err(err.context(format!("Could not list your tickets on {}", instance)));
+    }
+    for (instance, err) in failures {
+        eprintln!("{}: {:#}", instance, err);
+    }
+    render_output(
+        output.or_json(query.json),
+        &TableOutput::new(
+            Value::Array(items),
+            &["Instance", "Key", "Summary", "Status"],
+            rows,
+        ),
+    )
+}
+
+/// The name of the command when, as invoked, it would change Jira. Commands
+/// that only read Jira, or only touch git, Bitbucket, or local files, are `None`.
+fn jira_write_command(command: &Commands) -> Option<&'static str> {
+    Some(match command {
+        Commands::Create(_) => "create",
+        Commands::Edit(_) => "edit",
+        Commands::SyncPr(args) if !args.dry_run => "sync-pr",
+        Commands::Approve(_) => "approve",
+        Commands::RequestApproval(_) => "request-approval",
+        Commands::Check(args) if args.item.is_some() => "check",
+        Commands::Triage(_) => "triage",
+        Commands::Poker(_) => "poker",
+        Commands::Done(_) => "done",
+        Commands::Dup(_) => "dup",
+        Commands::Merge(_) => "merge",
+        Commands::Split(_) => "split",
+        Commands::Comment(_) => "comment",
+        Commands::ProcessCommits(args) if !args.dry_run => "process-commits",
+        Commands::Deployed(args) if !args.dry_run => "deployed",
+        Commands::Trigger(_) => "trigger",
+        Commands::Move(args) if args.status.is_some() => "move",
+        Commands::Assign(_) => "assign",
+        Commands::Plan(_) => "plan",
+        Commands::Quick(_) => "quick",
+        Commands::Start(_) => "start",
+        _ => return None,
+    })
+}
+
+fn run_query_mode(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: QueryArgs,
+    output: OutputFormat,
+    porcelain: Option<PorcelainVersion>,
+) -> Result<()> {
+    let client = jira.http();
+    let jira_base_url = config.jira.base_url.as_str();
+    let include_bitbucket = config.bitbucket.is_some();
+    let output = if args.text {
+        OutputFormat::Text
+    } else {
+        output
+    }
+    .or_csv(args.csv)
+    .or_ndjson(args.ndjson)
+    .or_markdown(args.markdown)
+    .or_json(args.json);
+    let wants_ticket = args.show
+        || args.full
+        || args.include_description
+        || args.include_comments
+        || args.include_prs
+        || args.include_ci;
+    // `jit --show` on a ticket branch shows the branch's ticket.
+    let ticket = match args.ticket.clone() {
+        None if wants_ticket && !args.my_tickets => Some(ticket_from_current_branch()?),
+        ticket => ticket,
+    };
+    if ticket.as_deref() == Some("-") {
+        return print_tickets_from_lines(jira, io::stdin().lock(), output);
+    }
+    let template = output_template(config, args.format.as_deref())?;
+    if args.my_tickets || ticket.is_none() {
+        let include_prs = args.include_prs || args.full;
+        let columns = template_columns(template.as_ref(), &args.columns);
+        let fields = column_fields(
+            &["summary", "status", SPRINT_FIELD, STORY_POINTS_FIELD],
+            columns,
+            args.group_by,
+            &args.fields,
+        );
+        let jql = if args.assignees.is_set() {
+            "sprint in openSprints() ORDER BY updated DESC"
+        } else {
+            MY_TICKETS_JQL
+        };
+        let jql = with_assignee_filter(client, config, jql, &args.assignees)?;
+        if args.count {
+            let count = jira.count(&jql)?;
+            if count == 0 && !args.assignees.is_set() {
+                jira.myself()?;
+            }
+            return render_count(count, output);
+        }
+        if porcelain.is_none() && !include_prs && (template.is_some() || prints_by_page(output)) {
+            let printed = print_issue_pages(jira, &jql, &fields, args.limit, &mut |page| {
+                render_formatted(
+                    output,
+                    template.as_ref(),
+                    &IssueListView::search_results(page)
+                        .with_columns(columns)
+                        .with_grouping(args.group_by)
+                        .with_extra_fields(&args.fields),
+                )
+            })?;
+            if printed == 0 && !args.assignees.is_set() {
+                jira.myself()?;
+            }
+            return Ok(());
+        }
+        if porcelain.is_none()
+            && !include_prs
+            && template.is_none()
+            && streams_table(output, args.group_by)
+        {
+            let mut table = IssueTableStream::new(true);
+            let printed = print_issue_pages(jira, &jql, &fields, args.limit, &mut |page| {
+                table.print_page(
+                    &IssueListView::from_issues(page, None)
+                        .with_columns(columns)
+                        .with_extra_fields(&args.fields),
+                );
+                Ok(())
+            })?;
+            if printed == 0 && !args.assignees.is_set() {
+                jira.myself()?;
+            }
+            table.finish();
+            return Ok(());
+        }
+        // Fetch and display current tickets
+        let tickets = jira.search(&jql, &fields, issue_limit(args.limit))?;
+        if tickets.is_empty() && !args.assignees.is_set() {
+            jira.myself()?;
+        }
+        if let Some(version) = porcelain {
+            let porcelain = tickets
+                .iter()
+                .fold(Porcelain::new(version), |porcelain, issue| {
+                    porcelain.issue(issue)
+                });
+            porcelain.print();
+            return Ok(());
+        }
+        let pull_requests_by_key = if include_prs {
+            Some(fetch_pull_requests_for_tickets(
+                client,
+                jira_base_url,
+                &tickets,
+                include_bitbucket,
+            )?)
+        } else {
+            None
+        };
+        render_formatted(
+            output,
+            template.as_ref(),
+            &IssueListView::from_issues(&tickets, pull_requests_by_key.as_ref())
+                .with_columns(columns)
+                .with_grouping(args.group_by)
+                .with_extra_fields(&args.fields),
+        )?;
+    } else if let Some(ticket_input) = ticket {
+        // Extract ticket ID from URL if needed
+        let ticket_id = extract_ticket_id(&ticket_input)?;
+
+        // Markdown is for pasting a ticket elsewhere, so it always has the details.
+        let markdown = output == OutputFormat::Markdown;
+        let include_description = args.show || args.full || args.include_description || markdown;
+        let include_comments = args.full || args.include_comments;
+        let include_prs = args.full || args.include_prs;
+        let include_details = args.show
+            || args.full
+            || markdown
+            || template.is_some()
+            || !args.fields.is_empty()
+            || args.include_description
+            || args.include_comments
+            || args.include_prs
+            || args.include_ci
+            || porcelain.is_some();
+
+        // Fetch issue details based on requested output mode.
+        let mut fields = issue_fields(include_details, include_description, include_comments);
+        fields.extend(args.fields.iter().map(String::as_str));
+        let mut issue = match jira.issue_with_fields(&ticket_id, &fields) {
+            // A bare key with no profile picked may belong to another site.
+            Err(err)
+                if config.jira.fallback_profiles
+                    && args.profile.is_none()
+                    && !ticket_input.contains("://")
+                    && matches!(error::find(&err), Some(JitError::NotFound(_))) =>
+            {
+                let Some((profile, found)) = find_issue_profile(&args, config, &ticket_id)? else {
+                    return Err(err);
+                };
+                eprintln!(
+                    "{} is not on {}; showing it from profile {} ({}).",
+                    ticket_id, config.jira.base_url, profile, found.jira.base_url
+                );
+                let jira = create_jira_api_client(&found.jira, &found.http)?;
+                let args = QueryArgs {
+                    profile: Some(profile),
+                    ..args
+                };
+                return run_query_mode(&jira, &found, args, output, porcelain);
+            }
+            issue => issue?,
+        };
+
+        if let Some(version) = porcelain {
+            let url = format!("{}/browse/{}", jira_base_url, issue.key);
+            Porcelain::new(version).issue_details(&issue, &url).print();
+            return Ok(());
+        }
+
+        let layout = issue_layout(config, &issue).filter(|_| include_details);
+        let criteria_field = config
+            .acceptance_criteria
+            .field
+            .as_deref()
+            .filter(|_| include_details);
+        let mut missing: Vec<&str> = layout
+            .into_iter()
+            .flat_map(|layout| layout.fields.iter().map(String::as_str))
+            .filter(|field| match *field {
+                "labels" => true,
+                field => !LAYOUT_FIELDS.iter().any(|(name, _)| *name == field),
+            })
+            .chain(criteria_field)
+            .filter(|field| !issue.fields.extra.contains_key(*field))
+            .collect();
+        if !missing.is_empty() {
+            missing.insert(0, "summary");
+            let fields = fetch_jira_issue_fields(client, jira_base_url, &issue.key, &missing)?;
+            issue.fields.extra.extend(fields.fields.extra);
+            issue.fields.labels = fields.fields.labels;
+        }
+
+        let pull_requests = if include_prs {
+            fetch_linked_pull_requests(client, jira_base_url, &issue.id, include_bitbucket)?
+        } else {
+            Vec::new()
+        };
+
+        let ci_status = if args.include_ci {
+            Some(fetch_issue_ci_status(client, jira_base_url, &issue.id)?)
+        } else {
+            None
+        };
+
+        // Output the result
+        if include_details {
+            let sections = IssueSections {
+                description: include_description,
+                comments: include_comments,
+                pull_requests: include_prs,
+                comments_limit: args.comments_limit,
+                all_comments: args.all_comments,
+                since: args.since.as_deref(),
+            };
+            let mut view =
+                IssueView::from_issue(&issue, sections, &pull_requests, ci_status.as_ref())
+                    .with_url(format!("{}/browse/{}", jira_base_url, issue.key));
+            if let Some(layout) = layout {
+                view = view.with_layout(layout_fields(&issue, layout));
+            }
+            if !args.fields.is_empty() {
+                let requested = LayoutConfig {
+                    fields: args.fields.clone(),
+                    labels: HashMap::new(),
+                };
+                view = view.with_extra_fields(layout_fields(&issue, &requested));
+            }
+            if let Some(field) = criteria_field {
+                let value = issue.fields.extra.get(field).unwrap_or(&Value::Null);
+                view = view.with_acceptance_criteria(acceptance_criteria_items(value));
+            }
+            if args.vscode_links {
+                view = view.with_editor_links(EditorLinks::for_current_dir()?);
+            }
+            render_formatted(output, template.as_ref(), &view)?;
+        } else {
+            let record = RecordOutput::new(json!({
+                "ticket": issue.key,
+                "summary": issue.fields.summary
+            }))
+            .field("Ticket", &issue.key)
+            .field("Summary", &issue.fields.summary)
+            .line(format!("{}: {}", issue.key, issue.fields.summary));
+            render_output(output, &record)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Attempts to load configuration from multiple locations in order:
+/// 1. Custom config file passed as an argument
+/// 2. Current directory config.toml
+/// 3. User config directory ~/.config/jit/config.toml
+fn load_configuration(args: &QueryArgs) -> Result<AppConfig> {
+    let (config_path, mut table, _) = read_configuration(args)?;
+    settings::run_value_commands(&mut table)?;
+    let mut config = app_config_from_table(&config_path, table)?;
+    if config.jira.api_token.is_empty() {
+        let jira = &config.jira;
+        config.jira.api_token = match config.auth.source {
+            Some(source) => credentials::resolve_token(
+                &config.auth,
+                source,
+                &jira.base_url,
+                &jira.user_email,
+            )?,
+            None => credentials::load_token(&jira.base_url, &jira.user_email).ok_or_else(|| {
+                JitError::Config(format!(
+                    "No api_token in {} and no token in the OS keyring for {} at {}. Run `jit auth login` to save one.",
+                    config_path.display(),
+                    jira.user_email,
+                    jira.base_url
+                ))
+            })?,
+        };
+    }
+    Ok(config)
+}
+
+/// The config file with the profile, `${VAR}` references, and `JIT_*`
+/// overrides applied, before any `<key>_cmd` runs, and where the API token
+/// will come from.
+fn read_configuration(args: &QueryArgs) -> Result<(PathBuf, toml::Table, TokenOrigin)> {
+    let config_path = resolve_config_path(args)?;
+    let mut table = read_config_table(&config_path)?;
+    let profile = apply_selected_profile(&mut table, args.profile.as_deref())?;
+    let file_token = settings::get(&table, "jira.api_token")
+        .and_then(toml::Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    settings::interpolate(&mut table, &|name| std::env::var(name).ok())?;
+    let overridden = settings::apply_env_overrides(&mut table, std::env::vars());
+    let origin = token_origin(
+        &table,
+        &file_token,
+        profile.as_deref(),
+        &overridden,
+        &config_path,
+    );
+    Ok((config_path, table, origin))
+}
+
+/// Where the API token comes from, given the file's own `api_token` value
+/// (before `${VAR}` expansion) and the paths set by environment variables.
+fn token_origin(
+    table: &toml::Table,
+    file_token: &str,
+    profile: Option<&str>,
+    overridden: &[String],
+    config_path: &Path,
+) -> TokenOrigin {
+    if overridden.iter().any(|path| path == "jira.api_token") {
+        return TokenOrigin::External("the JIT_JIRA_API_TOKEN environment variable".to_string());
+    }
+    if file_token.contains("${") {
+        return TokenOrigin::External(format!(
+            "the environment variable jira.api_token refers to ({})",
+            file_token
+        ));
+    }
+    if !file_token.is_empty() {
+        let key = profile
+            .map(|profile| format!("profiles.{}.jira.api_token", profile))
+            .filter(|key| settings::get(table, key).is_some())
+            .unwrap_or_else(|| "jira.api_token".to_string());
+        return TokenOrigin::File {
+            path: config_path.to_path_buf(),
+            key,
+        };
+    }
+    if let Some(key) = ["jira.token_cmd", "jira.api_token_cmd"]
+        .into_iter()
+        .find(|key| settings::get(table, key).is_some())
+    {
+        return TokenOrigin::External(format!("the output of {}", key));
+    }
+    let source = settings::get(table, "auth.source")
+        .and_then(|source| source.clone().try_into::<credentials::SecretSource>().ok());
+    match source {
+        None | Some(credentials::SecretSource::Keyring) => TokenOrigin::Keyring,
+        Some(credentials::SecretSource::Env) => TokenOrigin::External(format!(
+            "the {} environment variable",
+            settings::get(table, "auth.variable")
+                .and_then(toml::Value::as_str)
+                .unwrap_or("JIRA_API_TOKEN")
+        )),
+        Some(source) => TokenOrigin::Source(
+            source,
+            settings::get(table, "auth.item")
+                .and_then(toml::Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        ),
+    }
+}
+
+/// Applies the `--profile`/`JIT_PROFILE` profile or, failing that, the one
+/// whose site matches a ticket URL on the command line, returning its name.
+fn apply_selected_profile(
+    table: &mut toml::Table,
+    profile: Option<&str>,
+) -> Result<Option<String>> {
+    let profile = profile
+        .map(str::to_string)
+        .or_else(|| std::env::args().find_map(|arg| settings::profile_for_url(table, &arg)));
+    if let Some(profile) = &profile {
+        settings::apply_profile(table, profile)?;
+    }
+    Ok(profile)
+}
+
+/// After Jira rejects the API token: says where the token came from and, in
+/// a terminal, offers to paste a new one, checks it, and saves it there.
+fn replace_rejected_token(query: &QueryArgs) -> Result<()> {
+    let (config_path, table, origin) = read_configuration(query)?;
+    let config = app_config_from_table(&config_path, table)?;
+    let jira = &config.jira;
+    eprintln!(
+        "\nJira rejected the token for {}; it has most likely expired or been revoked.",
+        if jira.user_email.is_empty() {
+            &jira.base_url
+        } else {
+            &jira.user_email
+        }
+    );
+    if !matches!(origin, TokenOrigin::File { .. } | TokenOrigin::Keyring) {
+        eprintln!("Replace it in {} and run the command again.", origin);
+        return Ok(());
+    }
+    if !(io::stdin().is_terminal() && io::stdout().is_terminal()) {
+        eprintln!("Run `jit auth login` to save a new one.");
+        return Ok(());
+    }
+
+    if jira.auth == JiraAuth::Basic {
+        println!("Create a new API token at {}", API_TOKEN_URL);
+    }
+    let Some(token) = prompt_line("Paste a new token to save it, or press Enter to skip: ")?
+        .filter(|token| !token.is_empty())
+    else {
+        return Ok(());
+    };
+    let candidate = JiraConfig {
+        api_token: token.clone(),
+        ..jira.clone()
+    };
+    create_jira_api_client(&candidate, &config.http)?
+        .myself()
+        .context("Jira rejected the new token too")?;
+    match &origin {
+        TokenOrigin::File { path, key } => {
+            let contents = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config file at {}", path.display()))?;
+            fs::write(path, settings::set(&contents, key, &token)?)
+                .with_context(|| format!("Failed to write config file at {}", path.display()))?;
+        }
+        _ => credentials::store_token(&jira.base_url, &jira.user_email, &token)?,
+    }
+    println!("Saved the new token to {}. Run the command again.", origin);
+    Ok(())
+}
+
+fn resolve_config_path(args: &QueryArgs) -> Result<PathBuf> {
+    if let Some(config_path) = &args.config_file {
+        if config_path.exists() {
+            return Ok(config_path.clone());
+        }
+
+        return Err(JitError::Config(format!(
+            "Specified config.toml file not found at: {}. Run `jit init --config-file {}` to create it.",
+            config_path.display(),
+            config_path.display()
+        ))
+        .into());
+    }
+
+    let local_config = PathBuf::from("config.toml");
+    if local_config.exists() {
+        return Ok(local_config);
+    }
+
+    if let Some(user_config) = default_config_path() {
+        if user_config.exists() {
+            return Ok(user_config);
+        }
+
+        return Err(JitError::Config(format!(
+            "No configuration found. Run `jit init` to set up your Jira credentials in `{}`.",
+            user_config.display()
+        ))
+        .into());
+    }
+
+    Err(JitError::Config(
+        "No configuration found. Run `jit init --config-file config.toml` to set up your Jira credentials.".to_string(),
+    )
+    .into())
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|path| config_path_from_home(&path))
+}
+
+fn config_path_from_home(home_dir: &Path) -> PathBuf {
+    home_dir.join(".config").join("jit").join("config.toml")
+}
+
+/// The file's own Jira settings, without environment overrides, for the
+/// wizard to offer as defaults.
+fn read_config_file(path: &Path) -> Result<JiraConfig> {
+    let table = read_config_table(path)?;
+    Ok(app_config_from_table(path, table)?.jira)
+}
+
+fn read_config_table(path: &Path) -> Result<toml::Table> {
+    let contents = fs::read_to_string(path).map_err(|err| {
+        JitError::Config(format!(
+            "Failed to read config file at {}: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    contents
+        .parse()
+        .map_err(|err: toml::de::Error| config_parse_error(path, &err).into())
+}
+
+fn app_config_from_table(path: &Path, table: toml::Table) -> Result<AppConfig> {
+    table
+        .try_into()
+        .map_err(|err| config_parse_error(path, &err).into())
+}
+
+fn config_parse_error(path: &Path, err: &toml::de::Error) -> JitError {
+    JitError::Config(format!(
+        "Failed to parse config file at {}: {}\nExpected:\n[jira]\nbase_url = \"https://your-company.atlassian.net\"\napi_token = \"your_api_token_here\"\nuser_email = \"your_email@example.com\"",
+        path.display(),
+        err.message()
+    ))
+}
+
+fn run_config_command(
+    query: &QueryArgs,
+    args: &ConfigArgs,
+    output: Option<OutputFormat>,
+) -> Result<()> {
+    match &args.command {
+        ConfigCommands::Get(get_args) => {
+            let mut table = read_config_table(&resolve_config_path(query)?)?;
+            apply_selected_profile(&mut table, query.profile.as_deref())?;
+            settings::apply_env_overrides(&mut table, std::env::vars());
+            let value = settings::get(&table, &get_args.key)
+                .ok_or_else(|| JitError::validation(format!("{} is not set.", get_args.key)))?;
+            println!("{}", settings::display_value(value));
+            Ok(())
+        }
+        ConfigCommands::Set(set_args) => {
+            // Writing a setting must work before the config is complete, so
+            // fall back to where `jit init` would create it.
+            let path = match resolve_config_path(query) {
+                Ok(path) => path,
+                Err(_) => match &query.config_file {
+                    Some(path) => path.clone(),
+                    None => default_config_path().context("Could not determine home directory")?,
+                },
+            };
+            // With a profile selected, the setting goes into that profile.
+            let key = match &query.profile {
+                Some(profile) => format!("profiles.{}.{}", profile, set_args.key),
+                None => set_args.key.clone(),
+            };
+            let contents = fs::read_to_string(&path).unwrap_or_default();
+            let updated = settings::set(&contents, &key, &set_args.value)?;
+            // Refuse an edit that breaks a config that loaded before it.
+            let was_valid = read_config_table(&path)
+                .and_then(|table| app_config_from_table(&path, table))
+                .is_ok();
+            if was_valid {
+                let table: toml::Table = updated.parse()?;
+                if let Err(err) = app_config_from_table(&path, table) {
+                    return Err(JitError::validation(format!(
+                        "Not saving {} = {}: {:#}",
+                        key, set_args.value, err
+                    ))
+                    .into());
+                }
+            }
+            if let Some(parent) = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+            {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            fs::write(&path, updated)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("Set {} in {}", key, path.display());
+            if let Some(name) = settings::env_var_name(&set_args.key)
+                .filter(|name| std::env::var_os(name).is_some())
+            {
+                println!("Note: {} is set and overrides this value.", name);
+            }
+            Ok(())
+        }
+        ConfigCommands::List => {
+            let path = resolve_config_path(query)?;
+            let mut table = read_config_table(&path)?;
+            apply_selected_profile(&mut table, query.profile.as_deref())?;
+            let overridden = settings::apply_env_overrides(&mut table, std::env::vars());
+            let entries: Vec<(String, String, settings::Source)> = settings::flatten(&table)
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = settings::display_value(value);
+                    let value = if settings::is_secret(&key) {
+                        mask_token(&value)
+                    } else {
+                        value
+                    };
+                    let source = if overridden.contains(&key) {
+                        settings::Source::Env
+                    } else {
+                        settings::Source::File
+                    };
+                    (key, value, source)
+                })
+                .collect();
+
+            let payload: Vec<Value> = entries
+                .iter()
+                .map(|(key, value, source)| {
+                    json!({ "key": key, "value": value, "source": source.label() })
+                })
+                .collect();
+            let rows = entries
+                .iter()
+                .map(|(key, value, source)| {
+                    vec![key.clone(), value.clone(), source.label().to_string()]
+                })
+                .collect();
+            let table =
+                TableOutput::new(json!(payload), &["Key", "Value", "Source"], rows).human(|| {
+                    println!("{}", path.display());
+                    for (key, value, source) in &entries {
+                        match source {
+                            settings::Source::Env => println!("{} = {} (env)", key, value),
+                            settings::Source::File => println!("{} = {}", key, value),
+                        }
+                    }
+                    Ok(())
+                });
+            let output = output.unwrap_or(OutputFormat::Table);
+            render_output(output, &table)
+        }
+    }
+}
+
+/// The credentials wizard behind `jit auth`; `jit init` also asks for a
+/// default project and greets the user it connected as. The token goes to the
+/// OS keyring unless `plaintext` is set or no keyring is available.
+fn run_auth_command(config_file: Option<&Path>, first_run: bool, plaintext: bool) -> Result<()> {
+    let config_path = match config_file {
+        Some(path) => path.to_path_buf(),
+        None => default_config_path().context("Could not determine default config path")?,
+    };
+
+    let mut existing_config = None;
+    if config_path.exists() {
+        match read_config_file(&config_path) {
+            Ok(config) => {
+                println!("Existing Jira config found at {}", config_path.display());
+                println!("Base URL: {}", config.base_url);
+                println!("Email:    {}", config.user_email);
+                if config.api_token.is_empty() {
+                    println!("Token:    (OS keyring)");
+                } else {
+                    println!("Token:    {}", mask_token(&config.api_token));
+                }
+                existing_config = Some(config);
+            }
+            Err(err) => {
+                println!(
+                    "Existing config at {} could not be read:",
+                    config_path.display()
+                );
+                println!("{}", err);
+            }
+        }
+
+        if !prompt_yes_no("Overwrite this config? [y/N]: ")? {
+            println!("Leaving existing config unchanged.");
+            return Ok(());
+        }
+    }
+
+    let base_url_input = if let Some(config) = existing_config.as_ref() {
+        prompt_with_default("Jira company URL", &config.base_url)?
+    } else {
+        prompt_required("Jira company URL: ")?
+    };
+    let base_url = normalize_jira_base_url(&base_url_input)?;
+    let user_email = if let Some(config) = existing_config.as_ref() {
+        prompt_with_default("Jira account email", &config.user_email)?
+    } else {
+        prompt_required("Jira account email: ")?
+    };
+
+    println!("Open this URL to create an Atlassian API token:");
+    println!("{}", API_TOKEN_URL);
+    if let Err(err) = open_url(API_TOKEN_URL) {
+        println!("Could not open your browser automatically: {}", err);
+    }
+
+    let api_token = prompt_required("Paste API token: ")?;
+    let client = create_jira_client(&user_email, &api_token)?;
+    let user = validate_jira_authentication(&client, &base_url)?;
+
+    let mut project = existing_config
+        .as_ref()
+        .and_then(|config| config.project.clone());
+    if first_run {
+        if !user.display_name.is_empty() {
+            println!("Connected as {}.", user.display_name);
+        }
+        let prompt = match project.as_deref() {
+            Some(existing) => format!("Default project key, used by jit quick [{}]: ", existing),
+            None => "Default project key, used by jit quick (optional): ".to_string(),
+        };
+        if let Some(answer) = prompt_line(&prompt)?.filter(|answer| !answer.is_empty()) {
+            project = Some(answer.to_ascii_uppercase());
+        }
+    }
+
+    let in_keyring = if plaintext || !credentials::keyring_enabled() {
+        credentials::delete_token(&base_url, &user_email);
+        false
+    } else {
+        match credentials::store_token(&base_url, &user_email, &api_token) {
+            Ok(()) => true,
+            Err(err) => {
+                println!(
+                    "Could not save the token to the OS keyring ({}); keeping it in the config file.",
+                    err
+                );
+                false
+            }
+        }
+    };
+
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create config directory at {}", parent.display())
+        })?;
+    }
+
+    fs::write(
+        &config_path,
+        format_config_toml(&JiraConfig {
+            base_url,
+            api_token: if in_keyring { String::new() } else { api_token },
+            user_email,
+            auth: JiraAuth::Basic,
+            project,
+            read_only: existing_config
+                .as_ref()
+                .is_some_and(|config| config.read_only),
+            fallback_profiles: existing_config
+                .as_ref()
+                .is_some_and(|config| config.fallback_profiles),
+            sprint_field: existing_config
+                .as_ref()
+                .and_then(|config| config.sprint_field.clone()),
+        }),
+    )
+    .with_context(|| format!("Failed to write config file at {}", config_path.display()))?;
+
+    println!("Saved Jira credentials to {}", config_path.display());
+    if in_keyring {
+        println!("Saved the API token in the OS keyring.");
+    }
+    if first_run {
+        println!("You're all set. Run `jit` to see your tickets in the current sprint.");
+    }
+    Ok(())
+}
+
+/// Calls `/myself` with the configured credentials, explaining a failure as a
+/// rejected token, a URL that is not Jira, or an unreachable site.
+fn run_auth_test_command(query: &QueryArgs, output: Option<OutputFormat>) -> Result<()> {
+    let config = load_configuration(query)?;
+    let jira = &config.jira;
+    let client = create_jira_api_client(jira, &config.http)?;
+    let credential = match jira.auth {
+        JiraAuth::Basic => "API token",
+        JiraAuth::Pat => "personal access token",
+    };
+
+    let user = client.myself().map_err(|err| {
+        let diagnosis = match error::find(&err) {
+            Some(JitError::Auth(_)) => format!(
+                "Jira rejected the {} for {}. It is wrong, expired, or revoked; run `jit auth login` to replace it.",
+                credential,
+                if jira.user_email.is_empty() {
+                    &jira.base_url
+                } else {
+                    &jira.user_email
+                }
+            ),
+            Some(JitError::Network { .. }) => format!(
+                "Could not reach {}. Check jira.base_url and your network connection.",
+                jira.base_url
+            ),
+            Some(JitError::NotFound(_)) => format!(
+                "{} has no Jira REST API. Check that jira.base_url is your Jira site.",
+                jira.base_url
+            ),
+            _ => format!("Could not check the credentials at {}.", jira.base_url),
+        };
+        err.context(diagnosis)
+    })?;
+    // A login page or proxy can answer 200 without being Jira.
+    if user.display_name.is_empty() && user.account_id.is_none() {
+        return Err(JitError::Config(format!(
+            "{} answered, but not with a Jira user. Check that jira.base_url is your Jira site.",
+            jira.base_url
+        ))
+        .into());
+    }
+
+    let account_id = user.account_id.clone().unwrap_or_default();
+    let record = RecordOutput::new(json!({
+        "display_name": user.display_name,
+        "account_id": user.account_id,
+        "site": jira.base_url,
+        "auth": credential,
+    }))
+    .field("User", &user.display_name)
+    .field("Account ID", &account_id)
+    .field("Site", &jira.base_url)
+    .line(match &user.account_id {
+        Some(id) => format!(
+            "Authenticated as {} ({}) on {}",
+            user.display_name, id, jira.base_url
+        ),
+        None => format!(
+            "Authenticated as {} on {}",
+            user.display_name, jira.base_url
+        ),
+    });
+    render_output(
+        output
+            .or(config.output.format)
+            .unwrap_or(OutputFormat::Table),
+        &record,
+    )
+}
+
+const SKILL_SOURCE_URL: &str =
+    "https://raw.githubusercontent.com/cesarferreira/jit/refs/heads/main/SKILL.md";
+
+struct AgentTarget {
+    name: &'static str,
+    home_subpath: &'static [&'static str],
+}
+
+const AGENT_TARGETS: &[AgentTarget] = &[
+    AgentTarget {
+        name: "codex",
+        home_subpath: &[".codex", "skills", "jit", "SKILL.md"],
+    },
+    AgentTarget {
+        name: "claude",
+        home_subpath: &[".claude", "skills", "jit", "SKILL.md"],
+    },
+];
+
+fn run_remind_command(args: &RemindArgs, output: OutputFormat) -> Result<()> {
+    if let Some(id) = args.cancel {
+        if !reminders::cancel(id)? {
+            return Err(
+                JitError::validation(format!("No pending reminder with ID {}.", id)).into(),
+            );
+        }
+        let record = RecordOutput::new(json!({ "cancelled": id }))
+            .field("Cancelled", id)
+            .line(format!("Cancelled reminder {}", id));
+        return render_output(output, &record);
+    }
+    if args.list || (args.ticket.is_none() && args.note.is_none()) {
+        let pending = reminders::list()?;
+        let rows = pending
+            .iter()
+            .map(|reminder| {
+                vec![
+                    reminder.id.to_string(),
+                    reminder.due_label(),
+                    reminder.ticket.clone(),
+                    reminder.note.clone(),
+                ]
+            })
+            .collect();
+        let table =
+            TableOutput::new(json!(pending), &["ID", "Due", "Ticket", "Note"], rows).human(|| {
+                if pending.is_empty() {
+                    println!("No pending reminders.");
+                }
+                for reminder in &pending {
+                    println!(
+                        "{}. {} {}: {}",
+                        reminder.id,
+                        reminder.due_label(),
+                        reminder.ticket,
+                        reminder.note
+                    );
+                }
+                Ok(())
+            });
+        return render_output(output, &table);
+    }
+
+    let (ticket, note) = split_ticket_argument(args.ticket.as_deref(), args.note.as_deref());
+    let ticket = resolve_ticket(ticket)?;
+    let note = note
+        .map(str::trim)
+        .filter(|note| !note.is_empty())
+        .ok_or_else(|| JitError::validation("Give the reminder a note, such as \"ping infra\"."))?;
+    let due_in = args.due_in.ok_or_else(|| {
+        JitError::validation("Say when to be reminded with --in, such as --in 2d.")
+    })?;
+    let due = now_utc() + chrono::Duration::from_std(due_in)?;
+    let reminder = reminders::add(&ticket, note, due)?;
+
+    let record = RecordOutput::new(json!(reminder))
+        .field("ID", reminder.id)
+        .field("Ticket", &reminder.ticket)
+        .field("Note", &reminder.note)
+        .field("Due", reminder.due_label())
+        .line(format!(
+            "Reminder {} set for {}: {} (due {})",
+            reminder.id,
+            reminder.ticket,
+            reminder.note,
+            reminder.due_label()
+        ));
+    render_output(output, &record)
+}
+
+/// Prints reminders that have fallen due to stderr, once each, so they show up
+/// alongside any command without disturbing its output.
+fn show_due_reminders() {
+    let Ok(due) = reminders::take_due(now_utc()) else {
+        return;
+    };
+    for reminder in due {
+        eprintln!(
+            "{} {}: {} (due {})",
+            "Reminder:".yellow().bold(),
+            reminder.ticket,
+            reminder.note,
+            reminder.due_label()
+        );
+    }
+}
+
+const DEFAULT_COMMIT_TEMPLATE: &str = "[{KEY}] ";
+
+/// Marks hooks written by `jit hook install`, so uninstall never removes someone else's.
+const HOOK_MARKER: &str = "# Installed by jit hook install";
+
+fn run_hook_command(query: &QueryArgs, args: &HookArgs) -> Result<()> {
+    match &args.command {
+        HookCommands::Install(install_args) => {
+            let path = prepare_commit_msg_hook_path()?;
+            if let Ok(existing) = fs::read_to_string(&path)
+                && !existing.contains(HOOK_MARKER)
+                && !install_args.force
+            {
+                return Err(JitError::validation(format!(
+                    "{} already exists. Pass --force to replace it.",
+                    path.display()
+                ))
+                .into());
+            }
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            fs::write(&path, prepare_commit_msg_hook_script())
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+                    .with_context(|| format!("Failed to make {} executable", path.display()))?;
+            }
+            println!("Installed {}", path.display());
+            Ok(())
+        }
+        HookCommands::Uninstall => {
+            let path = prepare_commit_msg_hook_path()?;
+            match fs::read_to_string(&path) {
+                Ok(existing) if existing.contains(HOOK_MARKER) => {
+                    fs::remove_file(&path)
+                        .with_context(|| format!("Failed to remove {}", path.display()))?;
+                    println!("Removed {}", path.display());
+                    Ok(())
+                }
+                Ok(_) => Err(JitError::validation(format!(
+                    "{} was not installed by jit; leaving it in place.",
+                    path.display()
+                ))
+                .into()),
+                Err(_) => {
+                    println!("No jit hook installed at {}", path.display());
+                    Ok(())
+                }
+            }
+        }
+        HookCommands::PrepareCommitMsg(hook_args) => {
+            // Merges and squashes already have messages git wrote; leave them alone.
+            if matches!(hook_args.source.as_deref(), Some("merge" | "squash")) {
+                return Ok(());
+            }
+            let Some(key) = current_git_branch()
+                .ok()
+                .and_then(|branch| find_ticket_keys(&branch).into_iter().next())
+            else {
+                return Ok(());
+            };
+            let template = load_configuration(query)
+                .ok()
+                .and_then(|config| config.git.commit_template)
+                .unwrap_or_else(|| DEFAULT_COMMIT_TEMPLATE.to_string());
+            let message = fs::read_to_string(&hook_args.file)
+                .with_context(|| format!("Failed to read {}", hook_args.file.display()))?;
+            if let Some(message) = prefix_commit_message(&message, &key, &template) {
+                fs::write(&hook_args.file, message)
+                    .with_context(|| format!("Failed to write {}", hook_args.file.display()))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Where git looks for the prepare-commit-msg hook, honoring `core.hooksPath`.
+fn prepare_commit_msg_hook_path() -> Result<PathBuf> {
+    let git = |args: &[&str]| -> Option<String> {
+        let output = Command::new("git").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let hooks_dir = match git(&["config", "core.hooksPath"]) {
+        // A relative hooksPath is relative to the top of the working tree.
+        Some(hooks_path) => match git(&["rev-parse", "--show-toplevel"]) {
+            Some(top) => Path::new(&top).join(hooks_path),
+            None => PathBuf::from(hooks_path),
+        },
+        None => PathBuf::from(
+            git(&["rev-parse", "--git-path", "hooks"])
+                .ok_or_else(|| JitError::validation("Not inside a git repository."))?,
+        ),
+    };
+    Ok(hooks_dir.join("prepare-commit-msg"))
+}
+
+fn prepare_commit_msg_hook_script() -> String {
+    format!(
+        "#!/bin/sh\n{}\n# Prefixes commit messages with the ticket key from the branch name.\ncommand -v jit >/dev/null 2>&1 || exit 0\njit hook prepare-commit-msg \"$@\" || true\n",
+        HOOK_MARKER
+    )
+}
+
+/// `message` with the ticket prefix from `template` added, or `None` when the
+/// message already mentions the ticket.
+fn prefix_commit_message(message: &str, key: &str, template: &str) -> Option<String> {
+    if message.contains(key) {
+        return None;
+    }
+    let prefix = template
+        .replace("{KEY}", key)
+        .replace("{key}", &key.to_ascii_lowercase());
+    Some(format!("{}{}", prefix, message))
+}
+
+fn run_skill_install_command(args: &SkillInstallArgs) -> Result<()> {
+    let home = dirs::home_dir().context("Could not determine home directory")?;
+
+    let targets: Vec<&AgentTarget> = match args.agent.as_deref() {
+        Some(agent) => {
+            let agent = agent.to_ascii_lowercase();
+            let target = AGENT_TARGETS
+                .iter()
+                .find(|t| t.name == agent)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Unknown agent '{}'. Supported: {}",
+                        agent,
+                        AGENT_TARGETS
+                            .iter()
+                            .map(|t| t.name)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })?;
+            vec![target]
+        }
+        None => AGENT_TARGETS.iter().collect(),
+    };
+
+    println!("Downloading SKILL.md from {}", SKILL_SOURCE_URL);
+    let client = ClientBuilder::new().build()?;
+    let response = client.get(SKILL_SOURCE_URL).send()?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Failed to download SKILL.md ({}): {}",
+            response.status(),
+            SKILL_SOURCE_URL
+        ));
+    }
+    let skill_contents = response.text()?;
+
+    for target in targets {
+        let mut path = home.clone();
+        for segment in target.home_subpath {
+            path.push(segment);
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create skill directory at {}", parent.display())
+            })?;
+        }
+
+        fs::write(&path, &skill_contents)
+            .with_context(|| format!("Failed to write SKILL.md to {}", path.display()))?;
+
+        println!("Installed {} skill at {}", target.name, path.display());
+    }
+
+    Ok(())
+}
+
+fn prompt_required(prompt: &str) -> Result<String> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+
+    let mut value = String::new();
+    io::stdin().read_line(&mut value)?;
+    let value = value.trim().to_string();
+    if value.is_empty() {
+        return Err(anyhow!(
+            "{} cannot be empty",
+            prompt.trim().trim_end_matches(':')
+        ));
+    }
+    Ok(value)
+}
+
+fn prompt_with_default(prompt: &str, default: &str) -> Result<String> {
+    print!("{} [{}]: ", prompt, default);
+    io::stdout().flush()?;
+
+    let mut value = String::new();
+    io::stdin().read_line(&mut value)?;
+    let value = value.trim();
+    if value.is_empty() {
+        Ok(default.to_string())
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+fn prompt_yes_no(prompt: &str) -> Result<bool> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+
+    let mut value = String::new();
+    io::stdin().read_line(&mut value)?;
+    Ok(matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+fn normalize_jira_base_url(input: &str) -> Result<String> {
+    let base_url = input.trim().trim_end_matches('/').to_string();
+    if !base_url.starts_with("https://") && !base_url.starts_with("http://") {
+        return Err(anyhow!(
+            "Jira company URL must start with https:// or http://"
+        ));
+    }
+    Ok(base_url)
+}
+
+fn open_url(url: &str) -> Result<()> {
+    if std::env::var_os("JIT_AUTH_SKIP_OPEN").is_some() {
+        return Ok(());
+    }
+
+    let status = if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).status()
+    } else if cfg!(target_os = "windows") {
+        Command::new("cmd").args(["/C", "start", "", url]).status()
+    } else {
+        Command::new("xdg-open").arg(url).status()
+    }
+    .context("failed to launch browser command")?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("browser command exited with {}", status))
+    }
+}
+
+fn format_config_toml(config: &JiraConfig) -> String {
+    let mut contents = format!("[jira]\nbase_url = {}\n", toml_string(&config.base_url));
+    if config.auth == JiraAuth::Pat {
+        contents.push_str("auth = \"pat\"\n");
+    }
+    if !config.api_token.is_empty() {
+        contents.push_str(&format!("api_token = {}\n", toml_string(&config.api_token)));
+    }
+    contents.push_str(&format!(
+        "user_email = {}\n",
+        toml_string(&config.user_email)
+    ));
+    if let Some(project) = &config.project {
+        contents.push_str(&format!("project = {}\n", toml_string(project)));
+    }
+    if config.read_only {
+        contents.push_str("read_only = true\n");
+    }
+    if config.fallback_profiles {
+        contents.push_str("fallback_profiles = true\n");
+    }
+    if let Some(field) = &config.sprint_field {
+        contents.push_str(&format!("sprint_field = {}\n", toml_string(field)));
+    }
+    contents
+}
+
+fn toml_string(value: &str) -> String {
+    serde_json::to_string(value).expect("string serialization should not fail")
+}
+
+fn mask_token(token: &str) -> String {
+    if token.is_empty() {
+        return "<empty>".to_string();
+    }
+
+    "*".repeat(token.chars().count().min(8))
+}
+
+fn run_create_issue_command(
+    client: &Client,
+    jira_base_url: &str,
+    args: &CreateArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let resolved_assignee = resolve_create_assignee(client, jira_base_url, &args.assignee)?;
+    let resolved_sprint = resolve_target_sprint(client, jira_base_url, args)?;
+    let created_issue = create_jira_issue(
+        client,
+        jira_base_url,
+        args,
+        resolved_assignee.account_id.as_deref(),
+    )?;
+    if let Some(sprint) = resolved_sprint.as_ref() {
+        add_issue_to_sprint(client, jira_base_url, sprint.id, &created_issue.key).with_context(
+            || {
+                format!(
+                    "Created {} but failed to add it to sprint {}",
+                    created_issue.key, sprint.name
+                )
+            },
+        )?;
+    }
+    let issue_url = format!("{}/browse/{}", jira_base_url, created_issue.key);
+
+    let mut payload = json!({
+        "id": created_issue.id,
+        "ticket": created_issue.key,
+        "project": args.project,
+        "summary": args.summary,
+        "issue_type": args.issue_type,
+        "assignee": resolved_assignee.label,
+        "url": issue_url,
+        "backlog": resolved_sprint.is_none(),
+    });
+    if let Some(sprint) = resolved_sprint.as_ref()
+        && let Some(obj) = payload.as_object_mut()
+    {
+        obj.insert("sprint".to_string(), json!(sprint.name));
+        obj.insert("board".to_string(), json!(sprint.board_name));
+    }
+
+    let mut record = RecordOutput::new(payload)
+        .field("Created", &created_issue.key)
+        .field("Project", &args.project)
+        .field("Type", &args.issue_type)
+        .field("Assignee", &resolved_assignee.label)
+        .field("Summary", &args.summary)
+        .line(format!("{}: {}", created_issue.key, args.summary));
+    if let Some(sprint) = resolved_sprint {
+        record = record
+            .field("Board", sprint.board_name)
+            .field("Sprint", sprint.name)
+            .field("Backlog", "No (added to current sprint)");
+    } else {
+        record = record.field("Backlog", "Yes (created without sprint assignment)");
+    }
+    render_output(output.or_json(args.json), &record.field("URL", issue_url))
+}
+
+const DEFAULT_START_STEPS: [&str; 3] = ["assign", "move:In Progress", "branch"];
+
+#[derive(Debug, PartialEq)]
+enum StartStep {
+    Assign,
+    Move(String),
+    Branch,
+    Comment(String),
+}
+
+fn parse_start_step(step: &str) -> Result<StartStep> {
+    let (name, value) = match step.split_once(':') {
+        Some((name, value)) => (name.trim(), Some(value.trim())),
+        None => (step.trim(), None),
+    };
+    match (name.to_ascii_lowercase().as_str(), value) {
+        ("assign", None) => Ok(StartStep::Assign),
+        ("move" | "transition", Some(status)) if !status.is_empty() => {
+            Ok(StartStep::Move(status.to_string()))
+        }
+        ("branch", None) => Ok(StartStep::Branch),
+        ("comment", Some(text)) if !text.is_empty() => Ok(StartStep::Comment(text.to_string())),
+        _ => Err(JitError::Config(format!(
+            "Unknown step '{}' in [start] steps. Use assign, move:<status>, branch, or comment:<text>.",
+            step
+        ))
+        .into()),
+    }
+}
+
+fn run_start_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: &StartArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let steps = if config.start.steps.is_empty() {
+        DEFAULT_START_STEPS
+            .iter()
+            .map(|step| parse_start_step(step))
+            .collect()
+    } else {
+        config
+            .start
+            .steps
+            .iter()
+            .map(|step| parse_start_step(step))
+            .collect::<Result<Vec<_>>>()
+    }?;
+    let ticket_id = extract_ticket_id(&args.ticket)?;
+    let issue = jira.issue_with_fields(&ticket_id, &["summary", "status", "issuetype"])?;
+    if let Some(limit) = config.start.wip_limit {
+        warn_over_wip_limit(jira, &issue.key, limit)?;
+    }
+
+    let mut payload = json!({ "ticket": issue.key });
+    let mut record_fields = Vec::new();
+    let mut lines = Vec::new();
+    for step in &steps {
+        match step {
+            StartStep::Assign => {
+                let me = resolve_create_assignee(jira.http(), base_url, "me")?;
+                jira.assign(&issue.key, me.account_id.as_deref())?;
+                payload["assignee"] = json!(me.label);
+                record_fields.push(("Assignee", me.label.clone()));
+                lines.push(format!("{}: assigned to {}", issue.key, me.label));
+            }
+            StartStep::Move(target) => {
+                let current = issue
+                    .fields
+                    .status
+                    .as_ref()
+                    .map(|status| status.name.as_str());
+                // Starting a ticket twice should not fail because it already moved.
+                let status = if current.is_some_and(|current| current.eq_ignore_ascii_case(target))
+                {
+                    current.unwrap_or_default().to_string()
+                } else {
+                    let transitions = jira.transitions(&issue.key)?;
+                    let transition = resolve_transition(&transitions, target, &issue.key)?;
+                    jira.transition(&issue.key, &transition.id)?;
+                    target_status(transition)
+                };
+                payload["status"] = json!(status);
+                lines.push(format!("{}: moved to {}", issue.key, status));
+                record_fields.push(("Status", status));
+            }
+            StartStep::Branch => {
+                let branch = configured_branch_name(&config.git, &issue)?;
+                let created = checkout_git_branch(&branch, args.from.as_deref())?;
+                payload["branch"] = json!(branch);
+                lines.push(if created {
+                    format!("Created branch {}", branch)
+                } else {
+                    format!("Switched to branch {}", branch)
+                });
+                record_fields.push(("Branch", branch));
+            }
+            StartStep::Comment(text) => {
+                jira.add_comment(&issue.key, &markdown_to_adf(text))?;
+                payload["comment"] = json!(text);
+                lines.push(format!("{}: commented \"{}\"", issue.key, text));
+                record_fields.push(("Comment", text.clone()));
+            }
+        }
+    }
+
+    let mut record = RecordOutput::new(payload)
+        .field("Started", &issue.key)
+        .field("Summary", &issue.fields.summary);
+    for (label, value) in record_fields {
+        record = record.field(label, value);
+    }
+    render_output(output, &record.line(lines.join("\n")))
+}
+
+/// Warns on stderr when starting `ticket` would put the current user over
+/// `limit` in-progress tickets. The start still goes ahead.
+fn warn_over_wip_limit(jira: &impl JiraApi, ticket: &str, limit: usize) -> Result<()> {
+    let jql = format!(
+        "assignee = currentUser() AND statusCategory = \"In Progress\" AND key != {}",
+        ticket
+    );
+    let in_progress = jira.search(&jql, &["summary"], 50)?;
+    if in_progress.len() >= limit {
+        let keys: Vec<&str> = in_progress.iter().map(|issue| issue.key.as_str()).collect();
+        eprintln!(
+            "{} you already have {} ticket(s) in progress (WIP limit {}): {}",
+            "Warning:".yellow().bold(),
+            in_progress.len(),
+            limit,
+            keys.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// The ticket set by `jit focus`.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Focus {
+    ticket: String,
+    /// RFC 3339 time the focus was set.
+    since: String,
+}
+
+fn focus_store_path() -> Result<PathBuf> {
+    Ok(jit_data_dir()?.join("focus.json"))
+}
+
+fn load_focus(path: &Path) -> Result<Option<Focus>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .map(Some)
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn save_focus(path: &Path, focus: &Focus) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, serde_json::to_string_pretty(focus)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn run_focus_command(
+    jira: &impl JiraApi,
+    base_url: &str,
+    args: &FocusArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let path = focus_store_path()?;
+    if args.clear {
+        let previous = load_focus(&path)?;
+        if previous.is_some() {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        let ticket = previous.map(|focus| focus.ticket);
+        let line = match &ticket {
+            Some(ticket) => format!("No longer focused on {}", ticket),
+            None => "No ticket was in focus".to_string(),
+        };
+        let record = RecordOutput::new(json!({ "cleared": ticket }))
+            .field("Cleared", ticket.as_deref().unwrap_or("-"))
+            .line(line);
+        return render_output(output, &record);
+    }
+
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    // Fetch the ticket so a typo is caught now rather than by `jit current`.
+    let issue = jira.issue_with_fields(&ticket_id, &["summary", "status"])?;
+    let focus = Focus {
+        ticket: issue.key.clone(),
+        since: now_utc().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    };
+    save_focus(&path, &focus)?;
+    // Warm the status line's cache; it only ever reads from there.
+    let _ = cache_issue_status(base_url, &issue.key, &issue);
+
+    let record = RecordOutput::new(json!({
+        "ticket": issue.key,
+        "summary": issue.fields.summary,
+        "since": focus.since,
+    }))
+    .field("Focus", &issue.key)
+    .field("Summary", &issue.fields.summary)
+    .line(format!(
+        "Focused on {}: {}",
+        issue.key, issue.fields.summary
+    ));
+    render_output(output, &record)
+}
+
+fn run_current_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    output: OutputFormat,
+    porcelain: Option<PorcelainVersion>,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let focus = load_focus(&focus_store_path()?)?;
+    if let Some(version) = porcelain {
+        // No focus prints only the header, so scripts never parse a message.
+        let mut lines = Porcelain::new(version);
+        if let Some(focus) = focus {
+            let issue = jira.issue(&focus.ticket, true, false, false)?;
+            let _ = cache_issue_status(base_url, &issue.key, &issue);
+            lines = lines.issue(&issue).field("since", Some(&focus.since));
+        }
+        lines.print();
+        return Ok(());
+    }
+    let Some(focus) = focus else {
+        let record = RecordOutput::new(json!({ "ticket": null }))
+            .field("Focus", "-")
+            .line("No ticket in focus. Set one with jit focus ISSUE-123.");
+        return render_output(output, &record);
+    };
+
+    let issue = jira.issue_with_fields(&focus.ticket, &["summary", "status"])?;
+    let status = issue
+        .fields
+        .status
+        .as_ref()
+        .map(|status| status.name.clone())
+        .unwrap_or_else(|| "-".to_string());
+    let _ = cache_issue_status(base_url, &issue.key, &issue);
+    // Bitbucket links reach Jira's development panel only when the repository is configured.
+    let pull_requests = if config.bitbucket.is_some() {
+        fetch_dev_status_pull_requests(jira.http(), base_url, &issue.id, "bitbucket")?
+    } else {
+        Vec::new()
+    };
+    let pull_request_states: Vec<String> = pull_requests
+        .iter()
+        .map(|pr| {
+            format!(
+                "{} {}",
+                pull_request_display_id(pr),
+                pr.status.as_deref().unwrap_or("UNKNOWN")
+            )
+        })
+        .collect();
+    let mut line = format!("{}: {} [{}]", issue.key, issue.fields.summary, status);
+    if !pull_request_states.is_empty() {
+        line.push_str(&format!(" ({})", pull_request_states.join(", ")));
+    }
+    let mut record = RecordOutput::new(json!({
+        "ticket": issue.key,
+        "summary": issue.fields.summary,
+        "status": status,
+        "since": focus.since,
+        "url": format!("{}/browse/{}", base_url, issue.key),
+        "pull_requests": pull_requests.iter().map(|pr| json!({
+            "id": pull_request_display_id(pr),
+            "title": pr.name,
+            "status": pr.status,
+            "url": pr.url,
+        })).collect::<Vec<_>>(),
+    }))
+    .field("Focus", &issue.key)
+    .field("Summary", &issue.fields.summary)
+    .field("Status", &status)
+    .field("Since", format_date(&focus.since));
+    if config.bitbucket.is_some() {
+        let states = if pull_request_states.is_empty() {
+            "-".to_string()
+        } else {
+            pull_request_states.join(", ")
+        };
+        record = record.field("Pull requests", states);
+    }
+    render_output(output, &record.line(line))
+}
+
+fn run_serve_command(jira: &JiraClient, read_only: bool, args: &ServeArgs) -> Result<()> {
+    if !args.stdio {
+        return Err(JitError::validation(
+            "Pass --stdio; it is the only transport jit serve supports.",
+        )
+        .into());
+    }
+    serve::run(jira, read_only, io::stdin().lock(), io::stdout().lock())
+}
+
+/// `jit fields`: the site's fields sorted by name, for finding the ID behind
+/// a name like "Story Points".
+fn run_fields_command(
+    client: &Client,
+    base_url: &str,
+    args: &FieldsArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let query = args.query.as_deref().map(str::to_lowercase);
+    let mut fields: Vec<JiraField> = fetch_fields(client, base_url)?
+        .into_iter()
+        .filter(|field| !args.custom || field.custom)
+        .filter(|field| {
+            query.as_deref().is_none_or(|query| {
+                field.id.to_lowercase().contains(query) || field.name.to_lowercase().contains(query)
+            })
+        })
+        .collect();
+    fields.sort_by(|a, b| {
+        a.name
+            .to_lowercase()
+            .cmp(&b.name.to_lowercase())
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    let field_type = |field: &JiraField| {
+        let schema = field.schema.as_ref();
+        match (
+            schema.and_then(|schema| schema.field_type.as_deref()),
+            schema.and_then(|schema| schema.items.as_deref()),
+        ) {
+            (Some("array"), Some(items)) => format!("array<{}>", items),
+            (Some(field_type), _) => field_type.to_string(),
+            (None, _) => String::new(),
+        }
+    };
+    let payload = json!(
+        fields
+            .iter()
+            .map(|field| json!({
+                "id": field.id,
+                "name": field.name,
+                "custom": field.custom,
+                "type": Some(field_type(field)).filter(|kind| !kind.is_empty()),
+            }))
+            .collect::<Vec<_>>()
+    );
+    let rows = fields
+        .iter()
+        .map(|field| {
+            vec![
+                field.id.clone(),
+                field.name.clone(),
+                if field.custom { "yes" } else { "no" }.to_string(),
+                field_type(field),
+            ]
+        })
+        .collect();
+    let table = TableOutput::new(payload, &["ID", "Name", "Custom", "Type"], rows).human(|| {
+        if fields.is_empty() {
+            match &args.query {
+                Some(query) => println!("No fields match '{}'.", query),
+                None => println!("No fields found."),
+            }
+            return Ok(());
+        }
+        let width = fields.iter().map(|field| field.id.len()).max().unwrap_or(0);
+        for field in &fields {
+            let kind = match (field.custom, field_type(field)) {
+                (true, kind) if kind.is_empty() => "custom".to_string(),
+                (true, kind) => format!("custom, {}", kind),
+                (false, kind) => kind,
+            };
+            if kind.is_empty() {
+                println!("{:<width$}  {}", field.id, field.name, width = width);
+            } else {
+                println!(
+                    "{:<width$}  {}  {}",
+                    field.id,
+                    field.name,
+                    format!("({})", kind).dimmed(),
+                    width = width
+                );
+            }
+        }
+        Ok(())
+    });
+    render_output(output, &table)
+}
+
+/// Lists the stack trace frames in a ticket's description and comments that
+/// resolve to files tracked in the current repository.
+fn run_trace_command(jira: &impl JiraApi, args: &TraceArgs, output: OutputFormat) -> Result<()> {
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    let repo = stacktrace::RepoFiles::load()?;
+    let issue = jira.issue(&ticket_id, false, true, true)?;
+
+    let mut sources = Vec::new();
+    if let Some(description) = &issue.fields.description {
+        sources.push((
+            "description".to_string(),
+            adf_value_to_display_text(description),
+        ));
+    }
+    for (index, comment) in issue
+        .fields
+        .comment
+        .iter()
+        .flat_map(|container| &container.comments)
+        .enumerate()
+    {
+        if let Some(body) = &comment.body {
+            sources.push((
+                format!("comment {}", index + 1),
+                adf_value_to_display_text(body),
+            ));
+        }
+    }
+
+    let mut unresolved = 0;
+    let mut frames = Vec::new();
+    for (source, text) in &sources {
+        for frame in stacktrace::parse(text) {
+            match repo.resolve(&frame.path) {
+                Some(file) => frames.push((source.as_str(), file.to_string(), frame)),
+                None => unresolved += 1,
+            }
+        }
+    }
+
+    if args.open {
+        let (_, file, frame) = frames.first().ok_or_else(|| {
+            anyhow!(
+                "No stack trace frame in {} points at a file in this repository.",
+                ticket_id
+            )
+        })?;
+        return open_in_editor_at(&repo.root.join(file), frame.line);
+    }
+
+    let location = |file: &str, frame: &stacktrace::Frame| match frame.column {
+        Some(column) => format!("{}:{}:{}", file, frame.line, column),
+        None => format!("{}:{}", file, frame.line),
+    };
+    let payload = json!({
+        "ticket": ticket_id,
+        "root": repo.root.display().to_string(),
+        "unresolved": unresolved,
+        "frames": frames
+            .iter()
+            .map(|(source, file, frame)| {
+                json!({
+                    "file": file,
+                    "line": frame.line,
+                    "column": frame.column,
+                    "frame": frame.text,
+                    "source": source,
+                })
+            })
+            .collect::<Vec<_>>(),
+    });
+    let rows = frames
+        .iter()
+        .enumerate()
+        .map(|(index, (source, file, frame))| {
+            vec![
+                (index + 1).to_string(),
+                location(file, frame),
+                truncate_with_ellipsis(&frame.text, 80),
+                source.to_string(),
+            ]
+        })
+        .collect();
+    let table =
+        TableOutput::new(payload, &["#", "Location", "Frame", "Source"], rows).human(|| {
+            if frames.is_empty() {
+                println!(
+                    "No stack trace frames in {} point at files in this repository.",
+                    ticket_id
+                );
+            }
+            for (index, (source, file, frame)) in frames.iter().enumerate() {
+                println!(
+                    "{:>2}. {}  {}",
+                    index + 1,
+                    location(file, frame).bold(),
+                    format!("({})", source).dimmed()
+                );
+                println!("    {}", frame.text);
+            }
+            if unresolved > 0 {
+                println!();
+                println!(
+                    "{} more frame{} did not match a file in this repository.",
+                    unresolved,
+                    if unresolved == 1 { "" } else { "s" }
+                );
+            }
+            Ok(())
+        });
+    render_output(output, &table)
+}
+
+/// Prints the focused ticket (or the current branch's), its cached status, and
+/// how long it has been in focus. Only local files are read; a stale status is
+/// refreshed by a detached `jit statusline --refresh` for the next redraw.
+fn run_statusline_command(query: &QueryArgs, args: &StatuslineArgs) -> Result<()> {
+    if let Some(ticket) = &args.refresh {
+        return refresh_cached_status(query, ticket);
+    }
+
+    let focus = focus_store_path()
+        .and_then(|path| load_focus(&path))
+        .ok()
+        .flatten();
+    let ticket = match &focus {
+        Some(focus) => focus.ticket.clone(),
+        // Outside a ticket branch the status line is simply empty.
+        None => match ticket_from_current_branch() {
+            Ok(ticket) => ticket,
+            Err(_) => return Ok(()),
+        },
+    };
+
+    let now = now_utc();
+    let base_url = configured_base_url(query);
+    let cached = status_cache::get(&base_url, &ticket);
+    if cached.needs_refresh(now) {
+        start_cached_status_refresh(query, &base_url, &ticket, now);
+    }
+    let focused_secs = focus
+        .filter(|focus| focus.ticket == ticket)
+        .and_then(|focus| DateTime::parse_from_rfc3339(&focus.since).ok())
+        .map(|since| (now - since.with_timezone(&Utc)).num_seconds().max(0));
+    println!(
+        "{}",
+        statusline_text(&ticket, cached.status.as_deref(), focused_secs)
+    );
+    Ok(())
+}
+
+fn statusline_text(ticket: &str, status: Option<&str>, focused_secs: Option<i64>) -> String {
+    let mut text = ticket.to_string();
+    if let Some(status) = status {
+        text.push_str(&format!(" [{}]", status));
+    }
+    if let Some(secs) = focused_secs {
+        text.push(' ');
+        text.push_str(&format_timer(secs));
+    }
+    text
+}
+
+/// A short elapsed time such as `45m`, `1h05m`, or `2d3h`.
+fn format_timer(secs: i64) -> String {
+    let minutes = secs / 60;
+    match minutes {
+        0..60 => format!("{}m", minutes),
+        60..1_440 => format!("{}h{:02}m", minutes / 60, minutes % 60),
+        _ => format!("{}d{}h", minutes / 1_440, minutes % 1_440 / 60),
+    }
+}
+
+/// Prints the current branch's ticket and cached status for a shell prompt.
+/// Like the status line it never waits on Jira, and prints nothing off a ticket branch.
+fn run_prompt_command(query: &QueryArgs, args: &PromptArgs) -> Result<()> {
+    let Ok(ticket) = ticket_from_current_branch() else {
+        return Ok(());
+    };
+    let now = now_utc();
+    let base_url = configured_base_url(query);
+    let cached = status_cache::get(&base_url, &ticket);
+    if cached.needs_refresh(now) {
+        start_cached_status_refresh(query, &base_url, &ticket, now);
+    }
+    println!(
+        "{}",
+        prompt_segment(
+            args.format,
+            &ticket,
+            cached.status.as_deref(),
+            cached.category.as_deref(),
+            std::env::var_os("NO_COLOR").is_none()
+        )
+    );
+    Ok(())
+}
+
+/// `RW-1931 [In Progress]`, colored by status category with the escapes
+/// `format` expects.
+fn prompt_segment(
+    format: PromptFormat,
+    ticket: &str,
+    status: Option<&str>,
+    category: Option<&str>,
+    color: bool,
+) -> String {
+    let text = statusline_text(ticket, status, None);
+    let color = match category {
+        Some("new") => Some(("blue", 34)),
+        Some("indeterminate") => Some(("yellow", 33)),
+        Some("done") => Some(("green", 32)),
+        _ => None,
+    }
+    .filter(|_| color);
+    match format {
+        PromptFormat::Starship => match color {
+            Some((_, code)) => format!("\x1b[{}m{}\x1b[0m", code, text),
+            None => text,
+        },
+        PromptFormat::Powerlevel10k => {
+            // `%` starts a zsh prompt escape, so a literal one is doubled.
+            let text = text.replace('%', "%%");
+            match color {
+                Some((name, _)) => format!("%F{{{}}}{}%f", name, text),
+                None => text,
+            }
+        }
+    }
+}
+
+/// The site whose statuses the status line shows. It is read without
+/// resolving the API token, which may run a command or ask the keyring.
+fn configured_base_url(query: &QueryArgs) -> String {
+    read_configuration(query)
+        .ok()
+        .and_then(|(path, table, _)| app_config_from_table(&path, table).ok())
+        .map(|config| config.jira.base_url)
+        .unwrap_or_default()
+}
+
+fn start_cached_status_refresh(
+    query: &QueryArgs,
+    base_url: &str,
+    ticket: &str,
+    now: DateTime<Utc>,
+) {
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    let mut command = Command::new(exe);
+    if let Some(config_file) = &query.config_file {
+        command.arg("--config-file").arg(config_file);
+    }
+    if let Some(profile) = &query.profile {
+        command.arg("--profile").arg(profile);
+    }
+    let started = command
+        .args(["statusline", "--refresh", ticket])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if started.is_ok() {
+        let _ = status_cache::mark_refresh_started(base_url, ticket, now);
+    }
+}
+
+fn refresh_cached_status(query: &QueryArgs, ticket: &str) -> Result<()> {
+    let config = load_configuration(query)?;
+    let client = create_jira_api_client(&config.jira, &config.http)?;
+    let issue = client.issue_with_fields(ticket, &["summary", "status"])?;
+    cache_issue_status(&config.jira.base_url, ticket, &issue)
+}
+
+/// Stores the issue's status for the status line. An issue fetched without its
+/// status is skipped, so it never replaces a good entry with a blank one.
+fn cache_issue_status(base_url: &str, ticket: &str, issue: &JiraIssue) -> Result<()> {
+    let Some(status) = issue.fields.status.as_ref() else {
+        return Ok(());
+    };
+    status_cache::store(
+        base_url,
+        ticket,
+        &status.name,
+        status
+            .status_category
+            .as_ref()
+            .map(|category| category.key.as_str()),
+        &issue.fields.summary,
+        now_utc(),
+    )
+}
+
+/// Creates an issue through the regular create path, preset for urgent work:
+/// assigned to the current user and added to the active sprint.
+fn run_quick_command(
+    client: &Client,
+    config: &AppConfig,
+    args: QuickArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let project = args
+        .project
+        .or_else(|| config.jira.project.clone())
+        .ok_or_else(|| {
+            JitError::Config(
+                "No project for jit quick. Pass --project or set project in the [jira] config section.".to_string(),
+            )
+        })?;
+    let create_args = CreateArgs {
+        project,
+        summary: args.summary,
+        description: None,
+        issue_type: args.issue_type,
+        assignee: "me".to_string(),
+        current_sprint: true,
+        board: args.board,
+        draft: None,
+        template: None,
+        criteria: Vec::new(),
+        interactive: false,
+        capture_env: false,
+        json: false,
+        fields: Default::default(),
+    };
+    run_create_issue_command(client, &config.jira.base_url, &create_args, output)
+}
+
+fn run_edit_issue_command(
+    client: &Client,
+    jira_base_url: &str,
+    args: &EditArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    if args.summary.is_none()
+        && args.description.is_none()
+        && args.issue_type.is_none()
+        && args.assignee.is_none()
+        && args.estimate.is_none()
+    {
+        return Err(JitError::validation(
+            "No editable fields provided. Pass at least one of --summary, --description, --type, --assignee, or --estimate.",
+        )
+        .into());
+    }
+    if args.estimate.is_some_and(|points| points < 0.0) {
+        return Err(JitError::validation("--estimate cannot be negative.").into());
+    }
+
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    let resolved_assignee = args
+        .assignee
+        .as_deref()
+        .map(|assignee| resolve_create_assignee(client, jira_base_url, assignee))
+        .transpose()?;
+    update_jira_issue(
+        client,
+        jira_base_url,
+        &ticket_id,
+        args,
+        resolved_assignee
+            .as_ref()
+            .and_then(|assignee| assignee.account_id.as_deref()),
+    )?;
+
+    let issue_url = format!("{}/browse/{}", jira_base_url, ticket_id);
+    let mut updated_fields = Vec::new();
+    if args.summary.is_some() {
+        updated_fields.push("summary");
+    }
+    if args.description.is_some() {
+        updated_fields.push("description");
+    }
+    if args.issue_type.is_some() {
+        updated_fields.push("issue_type");
+    }
+    if args.assignee.is_some() {
+        updated_fields.push("assignee");
+    }
+    if args.estimate.is_some() {
+        updated_fields.push("story_points");
+    }
+
+    let mut payload = json!({
+        "ticket": ticket_id,
+        "updated_fields": updated_fields,
+        "url": issue_url,
+    });
+
+    if let Some(obj) = payload.as_object_mut() {
+        if let Some(summary) = args.summary.as_ref() {
+            obj.insert("summary".to_string(), json!(summary));
+        }
+        if let Some(description) = args.description.as_ref() {
+            obj.insert(
+                "description".to_string(),
+                if description.trim().is_empty() {
+                    Value::Null
+                } else {
+                    json!(description)
+                },
+            );
+        }
+        if let Some(issue_type) = args.issue_type.as_ref() {
+            obj.insert("issue_type".to_string(), json!(issue_type));
+        }
+        if let Some(assignee) = resolved_assignee.as_ref() {
+            obj.insert("assignee".to_string(), json!(assignee.label));
+        }
+        if let Some(points) = args.estimate {
+            obj.insert("story_points".to_string(), json!(points));
+        }
+    }
+
+    let mut record = RecordOutput::new(payload)
+        .field("Updated", &ticket_id)
+        .field("Fields", updated_fields.join(", "))
+        .line(format!("{}: {}", ticket_id, updated_fields.join(", ")));
+    if let Some(summary) = args.summary.as_ref() {
+        record = record.field("Summary", summary);
+    }
+    if let Some(issue_type) = args.issue_type.as_ref() {
+        record = record.field("Type", issue_type);
+    }
+    if let Some(assignee) = resolved_assignee.as_ref() {
+        record = record.field("Assignee", &assignee.label);
+    }
+    if let Some(points) = args.estimate {
+        record = record.field("Points", format_points(points));
+    }
+    if let Some(description) = args.description.as_ref() {
+        record = record.field(
+            "Description",
+            if description.trim().is_empty() {
+                "Cleared"
+            } else {
+                "Updated"
+            },
+        );
+    }
+    render_output(output.or_json(args.json), &record.field("URL", issue_url))
+}
+
+fn run_bitbucket_command(
+    jira: &impl JiraApi,
+    config: &AppConfig,
+    args: &BitbucketArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let bitbucket = config.bitbucket.as_ref().ok_or_else(|| {
+        JitError::Config(
+            "Bitbucket is not configured. Add a [bitbucket] section with workspace, repo_slug, username, and app_password to your config.toml.".to_string(),
+        )
+    })?;
+    let bitbucket_client =
+        create_basic_auth_client(&bitbucket.username, &bitbucket.app_password, &config.http)?;
+    let jira_base_url = config.jira.base_url.as_str();
+
+    match &args.command {
+        BitbucketCommands::Branch(branch_args) => {
+            let ticket_id = resolve_ticket(branch_args.ticket.as_deref())?;
+            let issue = jira.issue_with_fields(&ticket_id, &["summary", "issuetype"])?;
+            let branch_name = configured_branch_name(&config.git, &issue)?;
+            let from = branch_args
+                .from
+                .as_deref()
+                .unwrap_or(&bitbucket.default_branch);
+            let branch = create_bitbucket_branch(
+                &bitbucket_client,
+                &bitbucket.repository_url(),
+                &branch_name,
+                from,
+            )?;
+            let url = branch.links.html.map(|link| link.href);
+
+            let mut record = RecordOutput::new(json!({
+                "ticket": issue.key,
+                "branch": branch.name,
+                "from": from,
+                "url": url,
+            }))
+            .field("Branch", &branch.name)
+            .field("From", from)
+            .field("Ticket", &issue.key)
+            .line(branch.name.clone());
+            if let Some(url) = url {
+                record = record.field("URL", url);
+            }
+            render_output(output.or_json(branch_args.json), &record)?;
+        }
+        BitbucketCommands::Pr(pr_args) => {
+            let ticket_id = resolve_ticket(pr_args.ticket.as_deref())?;
+            let issue =
+                jira.issue_with_fields(&ticket_id, &["summary", "description", "issuetype"])?;
+            let source = match pr_args.source.clone() {
+                Some(source) => source,
+                None => configured_branch_name(&config.git, &issue)?,
+            };
+            let destination = pr_args
+                .destination
+                .as_deref()
+                .unwrap_or(&bitbucket.default_branch);
+            let payload = build_bitbucket_pr_payload(&issue, jira_base_url, &source, destination);
+            let pull_request = create_bitbucket_pull_request(
+                &bitbucket_client,
+                &bitbucket.repository_url(),
+                &payload,
+            )?;
+            let url = pull_request.links.html.map(|link| link.href);
+
+            let mut record = RecordOutput::new(json!({
+                "ticket": issue.key,
+                "id": pull_request.id,
+                "title": pull_request.title,
+                "state": pull_request.state,
+                "source": source,
+                "destination": destination,
+                "url": url,
+            }))
+            .label_width(14)
+            .field("Pull request", format!("#{}", pull_request.id))
+            .field("Title", &pull_request.title)
+            .field("Branches", format!("{} -> {}", source, destination))
+            .line(format!("#{}: {}", pull_request.id, pull_request.title));
+            if let Some(url) = url {
+                record = record.field("URL", url);
+            }
+            render_output(output.or_json(pr_args.json), &record)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn run_sync_pr_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: &SyncPrArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let event = args.event.trim().to_ascii_lowercase();
+    let Some(target_status) = sync_pr_target_status(&config.sync_pr, &event) else {
+        let line = format!(
+            "No status mapped for pull request event '{}'; nothing to do.",
+            event
+        );
+        let record = RecordOutput::new(json!({ "event": event, "action": "skipped" }))
+            .field("Event", &event)
+            .field("Action", "skipped")
+            .line(line.clone())
+            .human(|| {
+                println!("{}", line);
+                Ok(())
+            });
+        return render_output(output, &record);
+    };
+
+    let ticket_keys = match args.ticket.as_deref() {
+        Some(ticket) => vec![extract_ticket_id(ticket)?],
+        None => {
+            let text = [args.branch.as_deref(), args.title.as_deref()]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" ");
+            find_ticket_keys(&text)
+        }
+    };
+    if ticket_keys.is_empty() {
+        return Err(JitError::validation(
+            "No ticket key found. Pass --ticket, or --branch/--title containing a key such as RW-123.",
+        )
+        .into());
+    }
+
+    let mut results = Vec::new();
+    for key in ticket_keys {
+        let issue = jira.issue(&key, true, false, false)?;
+        let current_status = issue
+            .fields
+            .status
+            .as_ref()
+            .map_or("Unknown", |s| &s.name)
+            .to_string();
+        let action = if current_status.eq_ignore_ascii_case(&target_status) {
+            "unchanged"
+        } else if args.dry_run {
+            "dry-run"
+        } else {
+            transition_issue_to_status(
+                jira.http(),
+                &config.jira.base_url,
+                &issue.key,
+                &target_status,
+            )?;
+            "transitioned"
+        };
+        results.push((issue.key, current_status, action));
+    }
+
+    let payload = Value::Array(
+        results
+            .iter()
+            .map(|(key, from, action)| {
+                json!({ "ticket": key, "from": from, "to": target_status, "action": action })
+            })
+            .collect(),
+    );
+    let rows = results
+        .iter()
+        .map(|(key, from, action)| {
+            vec![
+                key.clone(),
+                from.clone(),
+                target_status.clone(),
+                action.to_string(),
+            ]
+        })
+        .collect();
+    let table = TableOutput::new(payload, &["Ticket", "From", "To", "Action"], rows).human(|| {
+        for (key, from, action) in &results {
+            match *action {
+                "unchanged" => println!("{}: already {}", key, from),
+                "dry-run" => println!("{}: {} -> {} (dry run)", key, from, target_status),
+                _ => println!("{}: {} -> {}", key, from, target_status),
+            }
+        }
+        Ok(())
+    });
+    render_output(output, &table)
+}
+
+/// A smart-commit command, as in `RW-1 #time 1h 30m Investigated #in-review`.
+#[derive(Debug, Clone, PartialEq)]
+enum CommitDirective {
+    Comment(String),
+    Time {
+        spent: String,
+        comment: Option<String>,
+    },
+    /// Transition name with the hyphens that stand in for spaces already replaced.
+    Transition {
+        name: String,
+        comment: Option<String>,
+    },
+    /// The ticket is mentioned without a directive.
+    Reference,
+}
+
+impl CommitDirective {
+    fn name(&self) -> &'static str {
+        match self {
+            CommitDirective::Comment(_) => "comment",
+            CommitDirective::Time { .. } => "time",
+            CommitDirective::Transition { .. } => "transition",
+            CommitDirective::Reference => "reference",
+        }
+    }
+
+    fn detail(&self) -> String {
+        match self {
+            CommitDirective::Comment(text) => text.clone(),
+            CommitDirective::Time { spent, .. } => spent.clone(),
+            CommitDirective::Transition { name, .. } => name.clone(),
+            CommitDirective::Reference => String::new(),
+        }
+    }
+}
+
+/// Extracts `(ticket, directive)` pairs from a commit message. Directives apply
+/// to the keys that precede them on the same line, following Jira's smart commits.
+fn parse_commit_directives(message: &str) -> Vec<(String, CommitDirective)> {
+    let key_re = Regex::new(r"\b([A-Z][A-Z0-9_]+-\d+)\b").expect("ticket key regex is valid");
+    let command_re =
+        Regex::new(r"(?:^|\s)#([A-Za-z][A-Za-z0-9_-]*)").expect("command regex is valid");
+    let time_re = Regex::new(r"^\d+(?:\.\d+)?[wdhm]$").expect("time regex is valid");
+
+    let mut directives: Vec<(String, CommitDirective)> = Vec::new();
+    for line in message.lines() {
+        let commands: Vec<regex::Captures> = command_re.captures_iter(line).collect();
+        let keys_end = commands.first().map_or(line.len(), |captures| {
+            captures.get(0).map_or(0, |m| m.start())
+        });
+        let keys: Vec<String> = key_re
+            .captures_iter(&line[..keys_end])
+            .map(|captures| captures[1].to_string())
+            .collect();
+        if keys.is_empty() {
+            continue;
+        }
+
+        let mut line_directives = Vec::new();
+        for (index, captures) in commands.iter().enumerate() {
+            let start = captures.get(0).map_or(0, |m| m.end());
+            let end = commands
+                .get(index + 1)
+                .and_then(|next| next.get(0))
+                .map_or(line.len(), |m| m.start());
+            let argument = line[start..end].trim();
+            let optional = |text: &str| (!text.is_empty()).then(|| text.to_string());
+
+            let directive = match captures[1].to_ascii_lowercase().as_str() {
+                "comment" if argument.is_empty() => continue,
+                "comment" => CommitDirective::Comment(argument.to_string()),
+                "time" => {
+                    let words: Vec<&str> = argument.split_whitespace().collect();
+                    let spent_len = words
+                        .iter()
+                        .take_while(|word| time_re.is_match(word))
+                        .count();
+                    if spent_len == 0 {
+                        continue;
+                    }
+                    CommitDirective::Time {
+                        spent: words[..spent_len].join(" "),
+                        comment: optional(&words[spent_len..].join(" ")),
+                    }
+                }
+                _ => CommitDirective::Transition {
+                    name: captures[1].replace('-', " "),
+                    comment: optional(argument),
+                },
+            };
+            line_directives.push(directive);
+        }
+        if line_directives.is_empty() {
+            line_directives.push(CommitDirective::Reference);
+        }
+
+        for key in keys {
+            for directive in &line_directives {
+                let entry = (key.clone(), directive.clone());
+                if !directives.contains(&entry) {
+                    directives.push(entry);
+                }
+            }
+        }
+    }
+
+    // A ticket with real directives does not also need a reference comment.
+    let with_directives: Vec<String> = directives
+        .iter()
+        .filter(|(_, directive)| *directive != CommitDirective::Reference)
+        .map(|(key, _)| key.clone())
+        .collect();
+    directives.retain(|(key, directive)| {
+        *directive != CommitDirective::Reference || !with_directives.contains(key)
+    });
+    directives
+}
+
+struct GitCommit {
+    hash: String,
+    message: String,
+}
+
+/// Reads commits in `range` oldest first, so directives apply in the order they were made.
+fn read_git_commits(repo: &Path, range: &str) -> Result<Vec<GitCommit>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["log", "--reverse", "--format=%h%x1f%B%x1e", range])
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git log {} failed: {}",
+            range,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\x1e')
+        .filter_map(|record| {
+            let (hash, message) = record.trim_start_matches('\n').split_once('\x1f')?;
+            Some(GitCommit {
+                hash: hash.to_string(),
+                message: message.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+fn current_git_branch() -> Result<String> {
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--short", "HEAD"])
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Could not read the current git branch: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The names of the local branches, in git's order.
+fn local_git_branches() -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(["for-each-ref", "--format=%(refname:short)", "refs/heads"])
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Could not list the local git branches: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Deletes a local branch; without `force`, git refuses unless it is merged.
+fn delete_git_branch(name: &str, force: bool) -> Result<()> {
+    let output = Command::new("git")
+        .args(["branch", if force { "-D" } else { "-d" }, name])
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// A git worktree: where it is checked out, and its branch unless detached.
+struct GitWorktree {
+    path: PathBuf,
+    branch: Option<String>,
+}
+
+/// The repository's worktrees, the main one first.
+fn git_worktrees() -> Result<Vec<GitWorktree>> {
+    let output = Command::new("git")
+        .args(["worktree", "list", "--porcelain"])
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Could not list the git worktrees: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let mut worktrees: Vec<GitWorktree> = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            worktrees.push(GitWorktree {
+                path: PathBuf::from(path),
+                branch: None,
+            });
+        } else if let Some(branch) = line.strip_prefix("branch ")
+            && let Some(worktree) = worktrees.last_mut()
+        {
+            worktree.branch = Some(branch.trim_start_matches("refs/heads/").to_string());
+        }
+    }
+    Ok(worktrees)
+}
+
+/// Checks `branch` out at `path`, creating the branch from `from` (or HEAD)
+/// when it does not exist yet. Returns whether the branch was created.
+fn add_git_worktree(path: &Path, branch: &str, from: Option<&str>) -> Result<bool> {
+    let created = !git_branch_exists(branch);
+    let mut command = Command::new("git");
+    command.args(["worktree", "add"]);
+    if created {
+        command.args(["-b", branch]).arg(path).args(from);
+    } else {
+        command.arg(path).arg(branch);
+    }
+    let output = command.output().context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git worktree add {} failed: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(created)
+}
+
+fn remove_git_worktree(path: &Path, force: bool) -> Result<()> {
+    let mut command = Command::new("git");
+    command.args(["worktree", "remove"]);
+    if force {
+        command.arg("--force");
+    }
+    let output = command.arg(path).output().context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn git_branch_exists(name: &str) -> bool {
+    Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("refs/heads/{}", name))
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Checks out `name`, creating it from `from` (or HEAD) when it does not exist yet.
+/// Returns whether the branch was created.
+fn checkout_git_branch(name: &str, from: Option<&str>) -> Result<bool> {
+    let created = !git_branch_exists(name);
+    let mut command = Command::new("git");
+    command.arg("checkout");
+    if created {
+        command.args(["-b", name]);
+        command.args(from);
+    } else {
+        command.arg(name);
+    }
+    let output = command.output().context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git checkout {} failed: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(created)
+}
+
+/// The ticket named by the current git branch, such as `RW-1931` for
+/// `feature/RW-1931-fix-login` or `rw-1931-fix-login`.
+fn ticket_from_current_branch() -> Result<String> {
+    let branch = current_git_branch().map_err(|err| {
+        JitError::validation(format!(
+            "No ticket given, and the current git branch could not be read ({:#}). Pass a ticket key such as RW-1931.",
+            err
+        ))
+    })?;
+    find_ticket_keys(&branch).into_iter().next().ok_or_else(|| {
+        JitError::validation(format!(
+            "No ticket given, and branch '{}' has no ticket key in its name. Pass a ticket key such as RW-1931.",
+            branch
+        ))
+        .into()
+    })
+}
+
+/// The ticket a command should act on: `input` when given, otherwise the key
+/// in the current git branch name.
+fn resolve_ticket(input: Option<&str>) -> Result<String> {
+    match input {
+        Some(input) => extract_ticket_id(input),
+        None => ticket_from_current_branch(),
+    }
+}
+
+/// Whether `input` is a ticket key or URL rather than another kind of argument.
+fn looks_like_ticket(input: &str) -> bool {
+    input.starts_with("http://")
+        || input.starts_with("https://")
+        || Regex::new(r"^[A-Za-z][A-Za-z0-9_]*-\d+$")
+            .expect("ticket key regex is valid")
+            .is_match(input)
+}
+
+/// Sorts out a command's optional ticket and the positional argument after it.
+/// A lone argument that is not a ticket, as in `jit move "In Review"`, is the
+/// second argument, leaving the ticket to come from the git branch.
+fn split_ticket_argument<'a>(
+    ticket: Option<&'a str>,
+    other: Option<&'a str>,
+) -> (Option<&'a str>, Option<&'a str>) {
+    match (ticket, other) {
+        (Some(input), None) if !looks_like_ticket(input) => (None, Some(input)),
+        arguments => arguments,
+    }
+}
+
+fn apply_commit_directive(
+    client: &Client,
+    base_url: &str,
+    commit: &GitCommit,
+    ticket: &str,
+    directive: &CommitDirective,
+) -> Result<()> {
+    let subject = commit.message.lines().next().unwrap_or_default();
+    match directive {
+        CommitDirective::Comment(text) => {
+            add_issue_comment(client, base_url, ticket, &text_to_adf(text))?;
+        }
+        CommitDirective::Time { spent, comment } => {
+            add_issue_worklog(client, base_url, ticket, spent, comment.as_deref())?;
+        }
+        CommitDirective::Transition { name, comment } => {
+            transition_issue_to_status(client, base_url, ticket, name)?;
+            if let Some(comment) = comment {
+                add_issue_comment(client, base_url, ticket, &text_to_adf(comment))?;
+            }
+        }
+        CommitDirective::Reference => {
+            let note = format!("Referenced in commit {}: {}", commit.hash, subject);
+            add_issue_comment(client, base_url, ticket, &text_to_adf(&note))?;
+        }
+    }
+    Ok(())
+}
+
+fn run_process_commits_command(
+    client: &Client,
+    config: &AppConfig,
+    args: &ProcessCommitsArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let commits = read_git_commits(&args.repo, &args.range)?;
+
+    // (commit, ticket, directive, result)
+    let mut results: Vec<(String, String, CommitDirective, String)> = Vec::new();
+    for commit in &commits {
+        for (ticket, directive) in parse_commit_directives(&commit.message) {
+            let result = if directive == CommitDirective::Reference && !args.comment_references {
+                "skipped".to_string()
+            } else if args.dry_run {
+                "dry-run".to_string()
+            } else {
+                match apply_commit_directive(client, base_url, commit, &ticket, &directive) {
+                    Ok(()) => "applied".to_string(),
+                    Err(err) => format!("failed: {:#}", err),
+                }
+            };
+            results.push((commit.hash.clone(), ticket, directive, result));
+        }
+    }
+
+    let failed = results
+        .iter()
+        .filter(|(_, _, _, result)| result.starts_with("failed"))
+        .count();
+    let payload = Value::Array(
+        results
+            .iter()
+            .map(|(commit, ticket, directive, result)| {
+                json!({
+                    "commit": commit,
+                    "ticket": ticket,
+                    "action": directive.name(),
+                    "detail": directive.detail(),
+                    "result": result,
+                })
+            })
+            .collect(),
+    );
+    let rows = results
+        .iter()
+        .map(|(commit, ticket, directive, result)| {
+            vec![
+                commit.clone(),
+                ticket.clone(),
+                directive.name().to_string(),
+                directive.detail(),
+                result.clone(),
+            ]
+        })
+        .collect();
+    let table = TableOutput::new(
+        payload,
+        &["Commit", "Ticket", "Action", "Detail", "Result"],
+        rows,
+    )
+    .human(|| {
+        if results.is_empty() {
+            println!("No ticket directives found in {} commit(s).", commits.len());
+        }
+        for (commit, ticket, directive, result) in &results {
+            let detail = directive.detail();
+            if detail.is_empty() {
+                println!("{} {}: {} ({})", commit, ticket, directive.name(), result);
+            } else {
+                println!(
+                    "{} {}: {} {} ({})",
+                    commit,
+                    ticket,
+                    directive.name(),
+                    detail,
+                    result
+                );
+            }
+        }
+        Ok(())
+    });
+    render_output(output, &table)?;
+
+    if failed > 0 {
+        return Err(anyhow!(
+            "{} of {} commit action(s) failed",
+            failed,
+            results.len()
+        ));
+    }
+    Ok(())
+}
+
+fn run_approve_command(
+    client: &Client,
+    config: &AppConfig,
+    args: &ApproveArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    let approver = fetch_current_user_assignee(client, base_url)?;
+    let account_id = approver
+        .account_id
+        .as_deref()
+        .context("Current Jira user response did not include accountId")?;
+
+    if let Some(field) = config.approval.field.as_deref() {
+        let payload = json!({ "update": { field: [{ "add": { "accountId": account_id } }] } });
+        update_jira_issue_payload(client, base_url, &ticket_id, &payload)?;
+    }
+
+    let mut note = format!("Approved by {}.", approver.label);
+    if let Some(message) = args.message.as_deref().filter(|m| !m.trim().is_empty()) {
+        note.push_str("\n\n");
+        note.push_str(message);
+    }
+    let comment = add_issue_comment(client, base_url, &ticket_id, &text_to_adf(&note))?;
+
+    if let Some(status) = config.approval.approved_status.as_deref() {
+        transition_issue_to_status(client, base_url, &ticket_id, status)?;
+    }
+
+    let status = config.approval.approved_status.as_deref();
+    let mut record = RecordOutput::new(json!({
+        "ticket": ticket_id,
+        "approver": approver.label,
+        "comment": comment.id,
+        "status": status,
+    }))
+    .field("Approved", &ticket_id)
+    .field("Approver", &approver.label)
+    .field("Comment", &comment.id)
+    .line(format!("{}: approved by {}", ticket_id, approver.label));
+    if let Some(status) = status {
+        record = record.field("Status", status);
+    }
+    render_output(output, &record)
+}
+
+fn run_request_approval_command(
+    client: &Client,
+    config: &AppConfig,
+    args: &RequestApprovalArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    let approvers = if args.approvers.is_empty() {
+        &config.approval.approvers
+    } else {
+        &args.approvers
+    };
+    if approvers.is_empty() {
+        return Err(JitError::validation(
+            "No approvers provided. Pass --approver <account-id> or set approvers in the [approval] config section.",
+        )
+        .into());
+    }
+
+    let body = build_approval_request_adf(approvers, args.message.as_deref());
+    let comment = add_issue_comment(client, base_url, &ticket_id, &body)?;
+
+    if let Some(status) = config.approval.request_status.as_deref() {
+        transition_issue_to_status(client, base_url, &ticket_id, status)?;
+    }
+
+    let status = config.approval.request_status.as_deref();
+    let mut record = RecordOutput::new(json!({
+        "ticket": ticket_id,
+        "approvers": approvers,
+        "comment": comment.id,
+        "status": status,
+    }))
+    .label_width(20)
+    .field("Requested approval", &ticket_id)
+    .field("Approvers", approvers.join(", "))
+    .field("Comment", &comment.id)
+    .line(format!(
+        "{}: approval requested from {}",
+        ticket_id,
+        approvers.join(", ")
+    ));
+    if let Some(status) = status {
+        record = record.field("Status", status);
+    }
+    render_output(output, &record)
+}
+
+/// Builds an ADF comment that @-mentions each approver so Jira notifies them.
+fn build_approval_request_adf(approvers: &[String], message: Option<&str>) -> Value {
+    let mut content = vec![json!({ "type": "text", "text": "Approval requested from " })];
+    for (index, account_id) in approvers.iter().enumerate() {
+        if index > 0 {
+            content.push(json!({ "type": "text", "text": ", " }));
+        }
+        content.push(json!({ "type": "mention", "attrs": { "id": account_id } }));
+    }
+    content.push(json!({ "type": "text", "text": "." }));
+
+    let mut paragraphs = vec![json!({ "type": "paragraph", "content": content })];
+    if let Some(message) = message.filter(|m| !m.trim().is_empty())
+        && let Some(extra) = text_to_adf(message)["content"].as_array()
+    {
+        paragraphs.extend(extra.iter().cloned());
+    }
+
+    json!({ "type": "doc", "version": 1, "content": paragraphs })
+}
+
+/// Storage formats used by common Jira checklist apps.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum ChecklistFormat {
+    /// Plain or markdown text with one `[ ]` / `[x]` item per line.
+    Text,
+    /// JSON array of item objects with a name and a checked flag.
+    Items,
+    /// Rich text field holding ADF `taskList` nodes.
+    Adf,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ChecklistItem {
+    text: String,
+    checked: bool,
+}
+
+fn run_check_command(
+    client: &Client,
+    config: &AppConfig,
+    args: &CheckArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    // `jit check 3` on a ticket branch toggles item 3 of the branch's ticket.
+    let (ticket, item) = match (args.ticket.as_deref(), args.item) {
+        (Some(input), None) if !looks_like_ticket(input) => match input.parse() {
+            Ok(item) => (None, Some(item)),
+            Err(_) => (Some(input), None),
+        },
+        (ticket, item) => (ticket, item),
+    };
+    let ticket_id = resolve_ticket(ticket)?;
+    let field = args
+        .field
+        .as_deref()
+        .or(config.checklist.field.as_deref())
+        .ok_or_else(|| {
+            JitError::Config(
+                "No checklist field configured. Pass --field <customfield_id> or set field in the [checklist] config section.".to_string(),
+            )
+        })?;
+
+    let value = fetch_issue_field(client, base_url, &ticket_id, field)?;
+    let format = match config.checklist.format {
+        Some(format) => format,
+        None => detect_checklist_format(&value).ok_or_else(|| {
+            JitError::FieldMissing(format!("{} has no checklist in field {}", ticket_id, field))
+        })?,
+    };
+
+    let mut items = parse_checklist(&value, format);
+    let mut toggled = None;
+    if let Some(item) = item {
+        if item == 0 || item > items.len() {
+            return Err(JitError::validation(format!(
+                "Checklist item {} does not exist on {} (it has {} items)",
+                item,
+                ticket_id,
+                items.len()
+            ))
+            .into());
+        }
+        let updated = toggle_checklist_item(&value, format, item - 1);
+        let payload = json!({ "fields": { field: updated } });
+        update_jira_issue_payload(client, base_url, &ticket_id, &payload)?;
+        items = parse_checklist(&updated, format);
+        toggled = Some(item);
+    }
+
+    let payload = json!({
+        "key": ticket_id,
+        "field": field,
+        "toggled": toggled,
+        "items": items
+            .iter()
+            .map(|item| json!({ "text": item.text, "checked": item.checked }))
+            .collect::<Vec<_>>(),
+    });
+    let rows = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            vec![
+                (index + 1).to_string(),
+                item.checked.to_string(),
+                item.text.clone(),
+            ]
+        })
+        .collect();
+    let table = TableOutput::new(payload, &["#", "Checked", "Item"], rows)
+        .human(|| print_checklist(&ticket_id, &items, toggled));
+    render_output(output.or_json(args.json), &table)
+}
+
+fn print_checklist(ticket_id: &str, items: &[ChecklistItem], toggled: Option<usize>) -> Result<()> {
+    if let Some(index) = toggled {
+        let item = &items[index - 1];
+        let state = if item.checked { "Checked" } else { "Unchecked" };
+        println!("{}: {}. {}", state, index, item.text);
+        return Ok(());
+    }
+
+    let done = items.iter().filter(|item| item.checked).count();
+    println!("{} checklist ({}/{} done)", ticket_id, done, items.len());
+    for (index, item) in items.iter().enumerate() {
+        let marker = if item.checked {
+            "[x]".green()
+        } else {
+            "[ ]".normal()
+        };
+        println!("  {}. {} {}", index + 1, marker, item.text);
+    }
+    Ok(())
+}
+
+fn checklist_line_regex() -> Regex {
+    Regex::new(r"^(\s*(?:[-*+]\s+)?)\[([ xX])\]\s*(.*)$").expect("checklist regex is valid")
+}
+
+fn detect_checklist_format(value: &Value) -> Option<ChecklistFormat> {
+    match value {
+        Value::String(_) => Some(ChecklistFormat::Text),
+        Value::Array(_) => Some(ChecklistFormat::Items),
+        Value::Object(map) if map.get("type").and_then(Value::as_str) == Some("doc") => {
+            Some(ChecklistFormat::Adf)
+        }
+        _ => None,
+    }
+}
+
+fn parse_checklist(value: &Value, format: ChecklistFormat) -> Vec<ChecklistItem> {
+    match format {
+        ChecklistFormat::Text => {
+            let re = checklist_line_regex();
+            value
+                .as_str()
+                .unwrap_or_default()
+                .lines()
+                .filter_map(|line| re.captures(line))
+                .map(|caps| ChecklistItem {
+                    text: caps[3].trim().to_string(),
+                    checked: !caps[2].trim().is_empty(),
+                })
+                .collect()
+        }
+        ChecklistFormat::Items => value
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| ChecklistItem {
+                        text: ["name", "text", "title"]
+                            .iter()
+                            .find_map(|key| item[*key].as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                        checked: checklist_item_checked(item),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ChecklistFormat::Adf => {
+            let mut items = Vec::new();
+            collect_adf_task_items(value, &mut items);
+            items
+        }
+    }
+}
+
+fn checklist_item_checked(item: &Value) -> bool {
+    ["checked", "done", "isChecked"]
+        .iter()
+        .find_map(|key| item[*key].as_bool())
+        .unwrap_or_else(|| {
+            item["status"]
+                .as_str()
+                .is_some_and(|status| status.eq_ignore_ascii_case("done"))
+        })
+}
+
+/// A new checklist of unchecked `items`, stored in `format`.
+fn checklist_value(items: &[String], format: ChecklistFormat) -> Value {
+    let items = items.iter().map(|item| item.trim());
+    match format {
+        ChecklistFormat::Text => Value::String(
+            items
+                .map(|item| format!("- [ ] {}", item))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        ChecklistFormat::Items => items
+            .map(|item| json!({ "name": item, "checked": false }))
+            .collect(),
+        ChecklistFormat::Adf => json!({
+            "type": "doc",
+            "version": 1,
+            "content": [{
+                "type": "taskList",
+                "attrs": { "localId": "acceptance-criteria" },
+                "content": items.enumerate().map(|(index, item)| json!({
+                    "type": "taskItem",
+                    "attrs": { "localId": format!("criterion-{}", index + 1), "state": "TODO" },
+                    "content": [{ "type": "text", "text": item }],
+                })).collect::<Vec<_>>(),
+            }],
+        }),
+    }
+}
+
+/// Acceptance criteria as checklist items: the field's checkboxes or task
+/// list when it has them, otherwise one unchecked item per line or bullet.
+fn acceptance_criteria_items(value: &Value) -> Vec<ChecklistItem> {
+    let items = detect_checklist_format(value)
+        .map(|format| parse_checklist(value, format))
+        .unwrap_or_default();
+    if !items.is_empty() {
+        return items;
+    }
+    let text = match value {
+        Value::String(text) => text.clone(),
+        Value::Object(_) if value["type"] == "doc" => adf_value_to_display_text(value),
+        _ => String::new(),
+    };
+    let bullet = Regex::new(r"^\s*(?:[-*+•]|\d+[.)])\s+").expect("bullet regex is valid");
+    text.lines()
+        .map(|line| bullet.replace(line, "").trim().to_string())
+        .filter(|line| !line.is_empty())
+        .map(|text| ChecklistItem {
+            text,
+            checked: false,
+        })
+        .collect()
+}
+
+fn collect_adf_task_items(node: &Value, items: &mut Vec<ChecklistItem>) {
+    if node["type"] == "taskItem" {
+        items.push(ChecklistItem {
+            text: render_adf_inline_content(node).trim().to_string(),
+            checked: node["attrs"]["state"] == "DONE",
+        });
+        return;
+    }
+    if let Some(children) = node["content"].as_array() {
+        for child in children {
+            collect_adf_task_items(child, items);
+        }
+    }
+}
+
+/// Returns a copy of the checklist value with the item at `index` toggled,
+/// leaving everything else the checklist app stored untouched.
+fn toggle_checklist_item(value: &Value, format: ChecklistFormat, index: usize) -> Value {
+    let mut updated = value.clone();
+    match format {
+        ChecklistFormat::Text => {
+            let re = checklist_line_regex();
+            let mut position = 0;
+            let lines: Vec<String> = value
+                .as_str()
+                .unwrap_or_default()
+                .lines()
+                .map(|line| match re.captures(line) {
+                    Some(caps) => {
+                        position += 1;
+                        if position - 1 == index {
+                            let marker = if caps[2].trim().is_empty() { "x" } else { " " };
+                            format!("{}[{}] {}", &caps[1], marker, &caps[3])
+                        } else {
+                            line.to_string()
+                        }
+                    }
+                    None => line.to_string(),
+                })
+                .collect();
+            updated = Value::String(lines.join("\n"));
+        }
+        ChecklistFormat::Items => {
+            if let Some(item) = updated.get_mut(index) {
+                let checked = checklist_item_checked(item);
+                let key = ["checked", "done", "isChecked"]
+                    .into_iter()
+                    .find(|key| item[*key].is_boolean());
+                match (key, item.get("status").is_some()) {
+                    (None, true) => {
+                        item["status"] = json!(if checked { "open" } else { "done" });
+                    }
+                    (key, _) => item[key.unwrap_or("checked")] = json!(!checked),
+                }
+            }
+        }
+        ChecklistFormat::Adf => {
+            let mut position = 0;
+            toggle_adf_task_item(&mut updated, index, &mut position);
+        }
+    }
+    updated
+}
+
+fn toggle_adf_task_item(node: &mut Value, index: usize, position: &mut usize) -> bool {
+    if node["type"] == "taskItem" {
+        let found = *position == index;
+        *position += 1;
+        if found {
+            let state = if node["attrs"]["state"] == "DONE" {
+                "TODO"
+            } else {
+                "DONE"
+            };
+            node["attrs"]["state"] = json!(state);
+        }
+        return found;
+    }
+    if let Some(children) = node.get_mut("content").and_then(Value::as_array_mut) {
+        for child in children {
+            if toggle_adf_task_item(child, index, position) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum TriageAction {
+    Assign,
+    Label,
+    Priority,
+    Close,
+    Skip,
+    Quit,
+}
+
+fn parse_triage_action(input: &str) -> Option<TriageAction> {
+    match input.trim().to_ascii_lowercase().as_str() {
+        "a" | "assign" => Some(TriageAction::Assign),
+        "l" | "label" => Some(TriageAction::Label),
+        "p" | "priority" => Some(TriageAction::Priority),
+        "c" | "close" => Some(TriageAction::Close),
+        "" | "s" | "skip" | "n" | "next" => Some(TriageAction::Skip),
+        "q" | "quit" => Some(TriageAction::Quit),
+        _ => None,
+    }
+}
+
+fn run_triage_command(jira: &JiraClient, config: &AppConfig, args: &TriageArgs) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let fields = [
+        "summary",
+        "status",
+        "issuetype",
+        "priority",
+        "assignee",
+        "reporter",
+        "labels",
+        "created",
+        "description",
+    ];
+    let jql = with_assignee_filter(jira.http(), config, &args.jql, &args.assignees)?;
+    let issues = jira.search(&jql, &fields, args.limit as usize)?;
+    if issues.is_empty() {
+        println!("No issues match the triage query.");
+        return Ok(());
+    }
+
+    let total = issues.len();
+    let mut updated = 0;
+    let mut closed = 0;
+    let mut skipped = 0;
+
+    // A rejected change, such as a misspelled priority, is reported and the
+    // same issue asked about again rather than ending the session.
+    let failed = |key: &str, result: Result<()>| match result {
+        Ok(()) => false,
+        Err(err) => {
+            eprintln!("{}: {:#}", key, err);
+            true
+        }
+    };
+
+    'issues: for (index, issue) in issues.iter().enumerate() {
+        display_triage_issue(issue, index + 1, total);
+        let mut touched = false;
+
+        loop {
+            let Some(input) = prompt_line("[a]ssign [l]abel [p]riority [c]lose [s]kip [q]uit: ")?
+            else {
+                break 'issues;
+            };
+            let Some(action) = parse_triage_action(&input) else {
+                println!("Unknown action '{}'", input.trim());
+                continue;
+            };
+
+            match action {
+                TriageAction::Assign => {
+                    let Some(requested) =
+                        prompt_line("Assignee (me, name, email, account ID, or unassigned): ")?
+                    else {
+                        break 'issues;
+                    };
+                    let assignee = match resolve_create_assignee(jira.http(), base_url, &requested)
+                    {
+                        Ok(assignee) => assignee,
+                        Err(err) => {
+                            failed(&issue.key, Err(err));
+                            continue;
+                        }
+                    };
+                    let value = assignee
+                        .account_id
+                        .as_deref()
+                        .map(|account_id| json!({ "accountId": account_id }))
+                        .unwrap_or(Value::Null);
+                    let payload = json!({ "fields": { "assignee": value } });
+                    if failed(&issue.key, jira.update_issue(&issue.key, &payload)) {
+                        continue;
+                    }
+                    println!("Assigned {} to {}", issue.key, assignee.label);
+                    touched = true;
+                }
+                TriageAction::Label => {
+                    let Some(input) = prompt_line("Labels to add (comma-separated): ")? else {
+                        break 'issues;
+                    };
+                    let labels: Vec<Value> = input
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|label| !label.is_empty())
+                        .map(|label| json!({ "add": label }))
+                        .collect();
+                    if labels.is_empty() {
+                        continue;
+                    }
+                    let payload = json!({ "update": { "labels": labels } });
+                    if failed(&issue.key, jira.update_issue(&issue.key, &payload)) {
+                        continue;
+                    }
+                    println!("Labeled {}", issue.key);
+                    touched = true;
+                }
+                TriageAction::Priority => {
+                    let Some(priority) = prompt_line("Priority: ")? else {
+                        break 'issues;
+                    };
+                    if priority.trim().is_empty() {
+                        continue;
+                    }
+                    let payload = json!({ "fields": { "priority": { "name": priority.trim() } } });
+                    if failed(&issue.key, jira.update_issue(&issue.key, &payload)) {
+                        continue;
+                    }
+                    println!("Set {} priority to {}", issue.key, priority.trim());
+                    touched = true;
+                }
+                TriageAction::Close => {
+                    let result = transition_issue_to_status(
+                        jira.http(),
+                        base_url,
+                        &issue.key,
+                        &args.close_status,
+                    );
+                    if failed(&issue.key, result) {
+                        continue;
+                    }
+                    println!("Moved {} to {}", issue.key, args.close_status);
+                    closed += 1;
+                    continue 'issues;
+                }
+                TriageAction::Skip => {
+                    if touched {
+                        updated += 1;
+                    } else {
+                        skipped += 1;
+                    }
+                    continue 'issues;
+                }
+                TriageAction::Quit => {
+                    if touched {
+                        updated += 1;
+                    }
+                    break 'issues;
+                }
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "Triage complete: {} updated, {} closed, {} skipped",
+        updated, closed, skipped
+    );
+    Ok(())
+}
+
+fn display_triage_issue(issue: &JiraIssue, position: usize, total: usize) {
+    let fields = &issue.fields;
+    let name_or_none = |user: &Option<JiraUser>| {
+        user.as_ref()
+            .map(|user| user.display_name.clone())
+            .unwrap_or_else(|| "Unassigned".to_string())
+    };
+
+    println!();
+    println!(
+        "[{}/{}] {}: {}",
+        position,
+        total,
+        issue.key.bold(),
+        fields.summary.bold()
+    );
+    println!(
+        "Type:     {}",
+        fields
+            .issuetype
+            .as_ref()
+            .map_or("Unknown", |t| t.name.as_str())
+    );
+    println!(
+        "Priority: {}",
+        fields.priority.as_ref().map_or("None", |p| p.name.as_str())
+    );
+    println!(
+        "Status:   {}",
+        fields
+            .status
+            .as_ref()
+            .map_or("Unknown", |s| s.name.as_str())
+    );
+    println!("Assignee: {}", name_or_none(&fields.assignee));
+    println!("Reporter: {}", name_or_none(&fields.reporter));
+    if !fields.labels.is_empty() {
+        println!("Labels:   {}", fields.labels.join(", "));
+    }
+    if let Some(created) = fields.created.as_deref() {
+        println!("Created:  {}", format_date(created));
+    }
+    if let Some(description) = fields.description.as_ref() {
+        let text = adf_value_to_display_text(description);
+        for line in text.lines().filter(|line| !line.trim().is_empty()).take(5) {
+            println!("  {}", line);
+        }
+    }
+    println!();
+}
+
+fn run_poker_command(jira: &JiraClient, config: &AppConfig, args: &PokerArgs) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let field = args
+        .field
+        .clone()
+        .unwrap_or_else(|| site_fields::story_points_field(jira.http(), base_url));
+    let field = field.as_str();
+    let fields = [
+        "summary",
+        "status",
+        "issuetype",
+        "priority",
+        "assignee",
+        "reporter",
+        "labels",
+        "created",
+        "description",
+        field,
+    ];
+    let jql = with_assignee_filter(jira.http(), config, &args.jql, &args.assignees)?;
+    // Filter in the query, so estimated issues do not use up `--limit`.
+    let jql = and_jql(&jql, &format!("{} is EMPTY", jql_field_name(field)));
+    let issues: Vec<JiraIssue> = jira
+        .search(&jql, &fields, args.limit as usize)?
+        .into_iter()
+        .filter(|issue| is_unestimated(issue, field))
+        .collect();
+    if issues.is_empty() {
+        println!("No unestimated issues match the query.");
+        return Ok(());
+    }
+
+    let total = issues.len();
+    let mut estimated = 0;
+    let mut points_total = 0.0;
+    let mut skipped = 0;
+
+    'issues: for (index, issue) in issues.iter().enumerate() {
+        display_triage_issue(issue, index + 1, total);
+
+        loop {
+            let Some(input) = prompt_line("Points ([s]kip, [q]uit): ")? else {
+                break 'issues;
+            };
+            match input.to_ascii_lowercase().as_str() {
+                "" | "s" | "skip" | "?" => {
+                    skipped += 1;
+                    continue 'issues;
+                }
+                "q" | "quit" => break 'issues,
+                _ => {}
+            }
+
+            let points = match parse_story_points(&input, &args.scale) {
+                Ok(points) => points,
+                Err(err) => {
+                    println!("{}", err);
+                    continue;
+                }
+            };
+            let payload = json!({ "fields": { field: points } });
+            jira.update_issue(&issue.key, &payload)?;
+            println!(
+                "Estimated {} at {} points",
+                issue.key,
+                format_points(points)
+            );
+            estimated += 1;
+            points_total += points;
+            continue 'issues;
+        }
+    }
+
+    println!();
+    println!(
+        "Estimation complete: {} estimated ({} points), {} skipped",
+        estimated,
+        format_points(points_total),
+        skipped
+    );
+    Ok(())
+}
+
+/// How JQL names a field ID: `cf[10016]` for `customfield_10016`.
+fn jql_field_name(field: &str) -> String {
+    match field.strip_prefix("customfield_") {
+        Some(id) => format!("cf[{}]", id),
+        None => field.to_string(),
+    }
+}
+
+fn is_unestimated(issue: &JiraIssue, field: &str) -> bool {
+    issue
+        .fields
+        .extra
+        .get(field)
+        .and_then(Value::as_f64)
+        .is_none()
+}
+
+fn parse_story_points(input: &str, scale: &[f64]) -> Result<f64> {
+    let points: f64 = input
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("Invalid estimate '{}'. Enter a number.", input.trim()))?;
+    if !points.is_finite() || points < 0.0 {
+        return Err(anyhow!(
+            "Invalid estimate '{}'. Enter a number.",
+            input.trim()
+        ));
+    }
+    if !scale.is_empty() && !scale.contains(&points) {
+        let allowed: Vec<String> = scale.iter().map(|value| format_points(*value)).collect();
+        return Err(anyhow!(
+            "Estimate {} is not on the scale: {}",
+            format_points(points),
+            allowed.join(", ")
+        ));
+    }
+    Ok(points)
+}
+
+fn format_points(points: f64) -> String {
+    if points.fract() == 0.0 {
+        format!("{}", points as i64)
+    } else {
+        format!("{}", points)
+    }
+}
+
+/// An issue on the planning board, with its original placement and estimate
+/// kept so only the changes are applied.
+#[derive(Debug, Clone)]
+struct PlanItem {
+    key: String,
+    summary: String,
+    assignee: Option<JiraUser>,
+    points: Option<f64>,
+    original_points: Option<f64>,
+    in_sprint: bool,
+    originally_in_sprint: bool,
+}
+
+impl PlanItem {
+    fn new(issue: &JiraIssue, field: &str, in_sprint: bool) -> Self {
+        let points = issue.fields.extra.get(field).and_then(Value::as_f64);
+        PlanItem {
+            key: issue.key.clone(),
+            summary: issue.fields.summary.clone(),
+            assignee: issue.fields.assignee.clone(),
+            points,
+            original_points: points,
+            in_sprint,
+            originally_in_sprint: in_sprint,
+        }
+    }
+
+    fn is_changed(&self) -> bool {
+        self.in_sprint != self.originally_in_sprint || self.points != self.original_points
+    }
+}
+
+/// Someone whose committed sprint points exceed their capacity.
+#[derive(Debug, PartialEq)]
+struct Overallocation {
+    name: String,
+    committed: f64,
+    capacity: f64,
+}
+
+impl Overallocation {
+    fn describe(&self) -> String {
+        format!(
+            "{} has {} pts committed, over their capacity of {} by {}",
+            self.name,
+            format_points(self.committed),
+            format_points(self.capacity),
+            format_points(self.committed - self.capacity)
+        )
+    }
+}
+
+/// The configured capacity for `user`, matching `[capacity.people]` entries by
+/// account ID, display name, or (through the people cache) email.
+fn person_capacity(config: &CapacityConfig, base_url: &str, user: &JiraUser) -> Option<f64> {
+    config
+        .people
+        .iter()
+        .find(|(person, _)| {
+            user.account_id.as_deref() == Some(person.as_str())
+                || user.display_name.eq_ignore_ascii_case(person)
+                || people::find(base_url, person).is_some_and(|found| {
+                    user.account_id.as_deref() == Some(found.account_id.as_str())
+                })
+        })
+        .map(|(_, capacity)| *capacity)
+        .or(config.default)
+}
+
+/// Assignees whose estimated sprint issues add up to more than `capacity` allows,
+/// most over-allocated first.
+fn over_allocated(
+    items: &[PlanItem],
+    capacity: impl Fn(&JiraUser) -> Option<f64>,
+) -> Vec<Overallocation> {
+    let mut committed: BTreeMap<String, (&JiraUser, f64)> = BTreeMap::new();
+    for item in items.iter().filter(|item| item.in_sprint) {
+        if let Some(assignee) = &item.assignee {
+            let id = assignee
+                .account_id
+                .clone()
+                .unwrap_or_else(|| assignee.display_name.clone());
+            committed.entry(id).or_insert((assignee, 0.0)).1 += item.points.unwrap_or(0.0);
+        }
+    }
+
+    let mut over: Vec<Overallocation> = committed
+        .into_values()
+        .filter_map(|(assignee, committed)| {
+            let capacity = capacity(assignee)?;
+            (committed > capacity).then(|| Overallocation {
+                name: assignee.display_name.clone(),
+                committed,
+                capacity,
+            })
+        })
+        .collect();
+    over.sort_by(|a, b| {
+        (b.committed - b.capacity)
+            .total_cmp(&(a.committed - a.capacity))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    over
+}
+
+#[derive(Debug, PartialEq)]
+enum PlanAction {
+    Move(Vec<usize>),
+    Estimate(usize, f64),
+    Reset(usize),
+    Apply,
+    Quit,
+    Help,
+}
+
+/// Parses a planning command; item numbers are as shown on the board.
+fn parse_plan_action(input: &str) -> Result<PlanAction> {
+    let mut words = input.split_whitespace();
+    let command = words.next().unwrap_or("?").to_ascii_lowercase();
+    let numbers = |words: std::str::SplitWhitespace| -> Result<Vec<usize>> {
+        words
+            .map(|word| {
+                word.parse::<usize>()
+                    .map_err(|_| anyhow!("'{}' is not an item number", word))
+            })
+            .collect()
+    };
+    match command.as_str() {
+        "m" | "move" => {
+            let items = numbers(words)?;
+            if items.is_empty() {
+                return Err(anyhow!("Usage: m ITEM [ITEM...]"));
+            }
+            Ok(PlanAction::Move(items))
+        }
+        "e" | "estimate" => match (words.next(), words.next()) {
+            (Some(item), Some(points)) => Ok(PlanAction::Estimate(
+                item.parse()
+                    .map_err(|_| anyhow!("'{}' is not an item number", item))?,
+                parse_story_points(points, &[])?,
+            )),
+            _ => Err(anyhow!("Usage: e ITEM POINTS")),
+        },
+        "r" | "reset" => match numbers(words)?.as_slice() {
+            [item] => Ok(PlanAction::Reset(*item)),
+            _ => Err(anyhow!("Usage: r ITEM")),
+        },
+        "a" | "apply" => Ok(PlanAction::Apply),
+        "q" | "quit" => Ok(PlanAction::Quit),
+        _ => Ok(PlanAction::Help),
+    }
+}
+
+fn plan_points(items: &[&PlanItem]) -> f64 {
+    items.iter().filter_map(|item| item.points).sum()
+}
+
+/// Prints the backlog and the sprint side by side, numbering every item.
+/// Changed items are marked with `*`.
+fn print_plan_board(items: &[PlanItem], sprint_name: &str) {
+    let width = term_size::dimensions()
+        .map_or(120, |(width, _)| width)
+        .max(60);
+    let column = (width - 3) / 2;
+    let side = |in_sprint: bool| -> Vec<(usize, &PlanItem)> {
+        items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.in_sprint == in_sprint)
+            .map(|(index, item)| (index + 1, item))
+            .collect()
+    };
+    let backlog = side(false);
+    let sprint = side(true);
+    let heading = |name: &str, side: &[(usize, &PlanItem)]| {
+        let items: Vec<&PlanItem> = side.iter().map(|(_, item)| *item).collect();
+        format!(
+            "{} ({} issues, {} pts)",
+            name,
+            items.len(),
+            format_points(plan_points(&items))
+        )
+    };
+    let cell = |entry: Option<&(usize, &PlanItem)>| {
+        let text = entry.map_or(String::new(), |(number, item)| {
+            format!(
+                "{}{:>3} {} {:>3} {}",
+                if item.is_changed() { "*" } else { " " },
+                number,
+                item.key,
+                item.points.map_or("-".to_string(), format_points),
+                item.summary
+            )
+        });
+        cell_text(&text, column)
+    };
+
+    println!();
+    println!(
+        "{} | {}",
+        cell_text(&heading("Backlog", &backlog), column).bold(),
+        heading(sprint_name, &sprint).bold()
+    );
+    println!("{}-+-{}", "-".repeat(column), "-".repeat(column));
+    for row in 0..backlog.len().max(sprint.len()) {
+        println!(
+            "{} | {}",
+            cell(backlog.get(row)),
+            cell(sprint.get(row)).trim_end()
+        );
+    }
+    println!();
+}
+
+/// `text` cut or padded to exactly `width` columns.
+fn cell_text(text: &str, width: usize) -> String {
+    let text = truncate_with_ellipsis(text, width);
+    let padding = width.saturating_sub(UnicodeWidthStr::width(text.as_str()));
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+fn run_plan_command(client: &Client, config: &AppConfig, args: &PlanArgs) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let field = args
+        .field
+        .clone()
+        .unwrap_or_else(|| site_fields::story_points_field(client, base_url));
+    let field = field.as_str();
+    let (sprint_id, sprint_name) = match args.sprint {
+        Some(sprint_id) => (sprint_id, format!("Sprint {}", sprint_id)),
+        None => {
+            let sprint = fetch_next_future_sprint(client, base_url, args.board)?.ok_or_else(|| {
+                JitError::validation(format!(
+                    "Board {} has no future sprint to plan. Create one in Jira or pass --sprint.",
+                    args.board
+                ))
+            })?;
+            (sprint.id, sprint.name)
+        }
+    };
+
+    let fields = ["summary", "assignee", field];
+    let sprint_issues = fetch_agile_issues(
+        client,
+        base_url,
+        &format!("sprint/{}/issue", sprint_id),
+        &fields,
+        1000,
+    )?;
+    let backlog_issues = fetch_agile_issues(
+        client,
+        base_url,
+        &format!("board/{}/backlog", args.board),
+        &fields,
+        args.limit,
+    )?;
+    let mut items: Vec<PlanItem> = backlog_issues
+        .iter()
+        .map(|issue| PlanItem::new(issue, field, false))
+        .chain(
+            sprint_issues
+                .iter()
+                .map(|issue| PlanItem::new(issue, field, true)),
+        )
+        .collect();
+
+    let show_board = |items: &[PlanItem]| {
+        print_plan_board(items, &sprint_name);
+        let over = over_allocated(items, |user| {
+            person_capacity(&config.capacity, base_url, user)
+        });
+        for overallocation in &over {
+            println!("{} {}", "Warning:".yellow(), overallocation.describe());
+        }
+        if !over.is_empty() {
+            println!();
+        }
+    };
+
+    let help = "Commands: m ITEM... (move between backlog and sprint), e ITEM POINTS (estimate), r ITEM (undo changes), a (apply), q (quit)";
+    println!("{}", help);
+    show_board(&items);
+    loop {
+        let Some(input) = prompt_line("plan> ")? else {
+            println!("Nothing applied.");
+            return Ok(());
+        };
+        let action = match parse_plan_action(&input) {
+            Ok(action) => action,
+            Err(err) => {
+                println!("{}", err);
+                continue;
+            }
+        };
+        let count = items.len();
+        let item_at = |number: usize| {
+            number
+                .checked_sub(1)
+                .filter(|index| *index < count)
+                .ok_or_else(|| anyhow!("There is no item {}", number))
+        };
+        let result = match action {
+            PlanAction::Move(numbers) => numbers.iter().try_for_each(|number| {
+                let index = item_at(*number)?;
+                items[index].in_sprint = !items[index].in_sprint;
+                Ok(())
+            }),
+            PlanAction::Estimate(number, points) => item_at(number).map(|index| {
+                items[index].points = Some(points);
+            }),
+            PlanAction::Reset(number) => item_at(number).map(|index| {
+                let item = &mut items[index];
+                item.in_sprint = item.originally_in_sprint;
+                item.points = item.original_points;
+            }),
+            PlanAction::Help => {
+                println!("{}", help);
+                continue;
+            }
+            PlanAction::Quit => {
+                let changed = items.iter().filter(|item| item.is_changed()).count();
+                if changed == 0
+                    || prompt_yes_no(&format!("Discard {} change(s)? [y/N]: ", changed))?
+                {
+                    println!("Nothing applied.");
+                    return Ok(());
+                }
+                continue;
+            }
+            PlanAction::Apply => break,
+        };
+        match result {
+            Ok(()) => show_board(&items),
+            Err(err) => println!("{}", err),
+        }
+    }
+
+    let changes = PlanChanges::from_items(&items);
+    if changes.is_empty() {
+        println!("No changes to apply.");
+        return Ok(());
+    }
+    println!("Changes:");
+    for line in changes.describe(&sprint_name) {
+        println!("  {}", line);
+    }
+    for overallocation in over_allocated(&items, |user| {
+        person_capacity(&config.capacity, base_url, user)
+    }) {
+        println!("{} {}", "Warning:".yellow(), overallocation.describe());
+    }
+    if !prompt_yes_no("Apply these changes? [y/N]: ")? {
+        println!("Nothing applied.");
+        return Ok(());
+    }
+    move_issues_to_sprint(client, base_url, Some(sprint_id), &changes.to_sprint)?;
+    move_issues_to_sprint(client, base_url, None, &changes.to_backlog)?;
+    for (key, points) in &changes.estimates {
+        let payload = json!({ "fields": { field: points } });
+        update_jira_issue_payload(client, base_url, key, &payload)?;
+    }
+    println!(
+        "Applied: {} moved to {}, {} moved to the backlog, {} re-estimated",
+        changes.to_sprint.len(),
+        sprint_name,
+        changes.to_backlog.len(),
+        changes.estimates.len()
+    );
+    Ok(())
+}
+
+/// What a planning session changed, grouped into the batches it is applied in.
+#[derive(Debug, Default, PartialEq)]
+struct PlanChanges {
+    to_sprint: Vec<String>,
+    to_backlog: Vec<String>,
+    estimates: Vec<(String, f64)>,
+}
+
+impl PlanChanges {
+    fn from_items(items: &[PlanItem]) -> Self {
+        let mut changes = PlanChanges::default();
+        for item in items {
+            if item.in_sprint != item.originally_in_sprint {
+                if item.in_sprint {
+                    changes.to_sprint.push(item.key.clone());
+                } else {
+                    changes.to_backlog.push(item.key.clone());
+                }
+            }
+            if let Some(points) = item.points
+                && item.points != item.original_points
+            {
+                changes.estimates.push((item.key.clone(), points));
+            }
+        }
+        changes
+    }
+
+    fn is_empty(&self) -> bool {
+        self.to_sprint.is_empty() && self.to_backlog.is_empty() && self.estimates.is_empty()
+    }
+
+    fn describe(&self, sprint_name: &str) -> Vec<String> {
+        self.to_sprint
+            .iter()
+            .map(|key| format!("{} -> {}", key, sprint_name))
+            .chain(
+                self.to_backlog
+                    .iter()
+                    .map(|key| format!("{} -> Backlog", key)),
+            )
+            .chain(
+                self.estimates
+                    .iter()
+                    .map(|(key, points)| format!("{} estimate -> {}", key, format_points(*points))),
+            )
+            .collect()
+    }
+}
+
+#[derive(Debug, Default)]
+struct GroomingReport {
+    unestimated: Vec<GroomingFinding>,
+    missing_components: Vec<GroomingFinding>,
+    missing_epic: Vec<GroomingFinding>,
+    stale: Vec<GroomingFinding>,
+    duplicates: Vec<GroomingDuplicate>,
+}
+
+#[derive(Debug)]
+struct GroomingFinding {
+    key: String,
+    summary: String,
+    detail: Option<String>,
+}
+
+#[derive(Debug)]
+struct GroomingDuplicate {
+    first: String,
+    second: String,
+    first_summary: String,
+    second_summary: String,
+    similarity: f64,
+}
+
+fn run_groom_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: &GroomArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let points_field = site_fields::story_points_field(jira.http(), base_url);
+    let points_field = points_field.as_str();
+    let jql = format!(
+        "project = \"{}\" AND statusCategory != Done AND (sprint is EMPTY OR sprint not in openSprints()) ORDER BY created ASC",
+        args.project
+    );
+    let jql = with_assignee_filter(jira.http(), config, &jql, &args.assignees)?;
+    let fields = [
+        "summary",
+        "issuetype",
+        "updated",
+        "components",
+        "parent",
+        points_field,
+    ];
+    let issues = jira.search(&jql, &fields, args.limit as usize)?;
+    let report = build_grooming_report(
+        &issues,
+        points_field,
+        args.stale_days,
+        args.similarity,
+        now_utc(),
+    );
+
+    let findings = |items: &[GroomingFinding]| {
+        items
+            .iter()
+            .map(|item| json!({ "key": item.key, "summary": item.summary, "detail": item.detail }))
+            .collect::<Vec<_>>()
+    };
+    let payload = json!({
+        "project": args.project,
+        "issues": issues.len(),
+        "unestimated": findings(&report.unestimated),
+        "missingComponents": findings(&report.missing_components),
+        "missingEpic": findings(&report.missing_epic),
+        "stale": findings(&report.stale),
+        "duplicates": report.duplicates.iter().map(|pair| json!({
+            "keys": [pair.first, pair.second],
+            "summaries": [pair.first_summary, pair.second_summary],
+            "similarity": pair.similarity,
+        })).collect::<Vec<_>>(),
+    });
+
+    let sections = [
+        ("unestimated", &report.unestimated),
+        ("missing-components", &report.missing_components),
+        ("missing-epic", &report.missing_epic),
+        ("stale", &report.stale),
+    ];
+    let mut rows = Vec::new();
+    for (category, items) in sections {
+        for item in items {
+            rows.push(vec![
+                category.to_string(),
+                item.key.clone(),
+                item.summary.clone(),
+                item.detail.clone().unwrap_or_default(),
+            ]);
+        }
+    }
+    for pair in &report.duplicates {
+        rows.push(vec![
+            "duplicate".to_string(),
+            format!("{} / {}", pair.first, pair.second),
+            format!("{} / {}", pair.first_summary, pair.second_summary),
+            format!("{:.0}% similar", pair.similarity * 100.0),
+        ]);
+    }
+    let table = TableOutput::new(payload, &["Category", "Key", "Summary", "Detail"], rows)
+        .human(|| print_grooming_report(&args.project, issues.len(), args.stale_days, &report));
+    render_output(output.or_json(args.json), &table)
+}
+
+fn print_grooming_report(
+    project: &str,
+    issue_count: usize,
+    stale_days: i64,
+    report: &GroomingReport,
+) -> Result<()> {
+    println!(
+        "{}",
+        format!(
+            "Backlog grooming report for {} ({} issues)",
+            project, issue_count
+        )
+        .bold()
+    );
+    print_grooming_section("Unestimated", &report.unestimated);
+    print_grooming_section("Without components", &report.missing_components);
+    print_grooming_section("Without an epic", &report.missing_epic);
+    print_grooming_section(
+        &format!("Stale (no updates in {}+ days)", stale_days),
+        &report.stale,
+    );
+
+    println!();
+    println!(
+        "{}",
+        format!("Possible duplicates ({})", report.duplicates.len()).bold()
+    );
+    if report.duplicates.is_empty() {
+        println!("  None");
+    }
+    for pair in &report.duplicates {
+        println!(
+            "  [ ] {} / {}  {:.0}% similar",
+            pair.first,
+            pair.second,
+            pair.similarity * 100.0
+        );
+        println!("        {}", pair.first_summary);
+        println!("        {}", pair.second_summary);
+    }
+    Ok(())
+}
+
+fn print_grooming_section(title: &str, findings: &[GroomingFinding]) {
+    println!();
+    println!("{}", format!("{} ({})", title, findings.len()).bold());
+    if findings.is_empty() {
+        println!("  None");
+    }
+    for finding in findings {
+        match finding.detail.as_deref() {
+            Some(detail) => println!("  [ ] {}  {} ({})", finding.key, finding.summary, detail),
+            None => println!("  [ ] {}  {}", finding.key, finding.summary),
+        }
+    }
+}
+
+fn build_grooming_report(
+    issues: &[JiraIssue],
+    points_field: &str,
+    stale_days: i64,
+    similarity_threshold: f64,
+    now: DateTime<Utc>,
+) -> GroomingReport {
+    let mut report = GroomingReport::default();
+    let finding = |issue: &JiraIssue, detail: Option<String>| GroomingFinding {
+        key: issue.key.clone(),
+        summary: issue.fields.summary.clone(),
+        detail,
+    };
+
+    for issue in issues {
+        let issue_type = issue
+            .fields
+            .issuetype
+            .as_ref()
+            .map(|issue_type| issue_type.name.to_ascii_lowercase())
+            .unwrap_or_default();
+        let is_epic = issue_type == "epic";
+
+        if !is_epic && is_unestimated(issue, points_field) {
+            report.unestimated.push(finding(issue, None));
+        }
+        let has_components = issue
+            .fields
+            .extra
+            .get("components")
+            .and_then(Value::as_array)
+            .is_some_and(|components| !components.is_empty());
+        if !has_components {
+            report.missing_components.push(finding(issue, None));
+        }
+        let has_parent = issue
+            .fields
+            .extra
+            .get("parent")
+            .is_some_and(|parent| !parent.is_null());
+        if !is_epic && !has_parent {
+            report.missing_epic.push(finding(issue, None));
+        }
+        if let Some(updated) = issue
+            .fields
+            .updated
+            .as_deref()
+            .and_then(parse_jira_datetime)
+        {
+            let idle_days = (now - updated.with_timezone(&Utc)).num_days();
+            if idle_days >= stale_days {
+                report.stale.push(finding(
+                    issue,
+                    Some(format!("not updated in {} days", idle_days)),
+                ));
+            }
+        }
+    }
+
+    let words: Vec<Vec<String>> = issues
+        .iter()
+        .map(|issue| summary_words(&issue.fields.summary))
+        .collect();
+    for (i, first) in issues.iter().enumerate() {
+        for (j, second) in issues.iter().enumerate().skip(i + 1) {
+            let similarity = word_similarity(&words[i], &words[j]);
+            if similarity >= similarity_threshold {
+                report.duplicates.push(GroomingDuplicate {
+                    first: first.key.clone(),
+                    second: second.key.clone(),
+                    first_summary: first.fields.summary.clone(),
+                    second_summary: second.fields.summary.clone(),
+                    similarity,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+fn summary_words(summary: &str) -> Vec<String> {
+    const STOP_WORDS: &[&str] = &[
+        "a", "an", "and", "for", "in", "is", "of", "on", "or", "the", "to", "with",
+    ];
+    let mut words: Vec<String> = summary
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !STOP_WORDS.contains(word))
+        .map(str::to_string)
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// Jaccard similarity of two sorted, deduplicated word lists.
+fn word_similarity(first: &[String], second: &[String]) -> f64 {
+    if first.is_empty() || second.is_empty() {
+        return 0.0;
+    }
+    let shared = first.iter().filter(|word| second.contains(word)).count();
+    let total = first.len() + second.len() - shared;
+    shared as f64 / total as f64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LintRule {
+    FixVersionOnDone,
+    AssigneeInProgress,
+    EpicLink,
+    Labels,
+}
+
+impl LintRule {
+    const ALL: [LintRule; 4] = [
+        LintRule::FixVersionOnDone,
+        LintRule::AssigneeInProgress,
+        LintRule::EpicLink,
+        LintRule::Labels,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            LintRule::FixVersionOnDone => "fix-version-on-done",
+            LintRule::AssigneeInProgress => "assignee-in-progress",
+            LintRule::EpicLink => "epic-link",
+            LintRule::Labels => "labels",
+        }
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            LintRule::FixVersionOnDone => "done issue has no fix version",
+            LintRule::AssigneeInProgress => "in-progress issue has no assignee",
+            LintRule::EpicLink => "issue is not linked to an epic",
+            LintRule::Labels => "issue has no labels",
+        }
+    }
+
+    fn parse(name: &str) -> Result<LintRule> {
+        LintRule::ALL
+            .into_iter()
+            .find(|rule| rule.name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown lint rule '{}'. Available rules: {}",
+                    name,
+                    LintRule::ALL.map(LintRule::name).join(", ")
+                )
+            })
+    }
+
+    fn is_violated_by(self, issue: &JiraIssue) -> bool {
+        let fields = &issue.fields;
+        let category = fields
+            .status
+            .as_ref()
+            .and_then(|status| status.status_category.as_ref())
+            .map(|category| category.key.as_str());
+        let is_epic = fields
+            .issuetype
+            .as_ref()
+            .is_some_and(|issue_type| issue_type.name.eq_ignore_ascii_case("epic"));
+
+        match self {
+            LintRule::FixVersionOnDone => {
+                category == Some("done")
+                    && fields
+                        .extra
+                        .get("fixVersions")
+                        .and_then(Value::as_array)
+                        .is_none_or(|versions| versions.is_empty())
+            }
+            LintRule::AssigneeInProgress => {
+                category == Some("indeterminate") && fields.assignee.is_none()
+            }
+            LintRule::EpicLink => {
+                !is_epic
+                    && fields
+                        .extra
+                        .get("parent")
+                        .is_none_or(|parent| parent.is_null())
+            }
+            LintRule::Labels => fields.labels.is_empty(),
+        }
+    }
+}
+
+fn run_lint_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: &LintArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let rule_names = if args.rules.is_empty() {
+        &config.lint.rules
+    } else {
+        &args.rules
+    };
+    let rules = if rule_names.is_empty() {
+        LintRule::ALL.to_vec()
+    } else {
+        rule_names
+            .iter()
+            .map(|name| LintRule::parse(name))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let fields = [
+        "summary",
+        "status",
+        "issuetype",
+        "assignee",
+        "labels",
+        "parent",
+        "fixVersions",
+    ];
+    let issues = match (&args.ticket, &args.jql) {
+        (Some(ticket), _) => {
+            let ticket_id = extract_ticket_id(ticket)?;
+            vec![jira.issue_with_fields(&ticket_id, &fields)?]
+        }
+        (None, Some(jql)) => {
+            let jql = with_assignee_filter(jira.http(), config, jql, &args.assignees)?;
+            jira.search(&jql, &fields, args.limit as usize)?
+        }
+        (None, None) => return Err(JitError::validation("Pass a ticket key or --jql").into()),
+    };
+
+    let violations: Vec<(&JiraIssue, Vec<LintRule>)> = issues
+        .iter()
+        .map(|issue| {
+            let broken = rules
+                .iter()
+                .copied()
+                .filter(|rule| rule.is_violated_by(issue))
+                .collect::<Vec<_>>();
+            (issue, broken)
+        })
+        .filter(|(_, broken)| !broken.is_empty())
+        .collect();
+    let problem_count: usize = violations.iter().map(|(_, broken)| broken.len()).sum();
+
+    let payload = json!({
+        "checked": issues.len(),
+        "violations": violations.iter().map(|(issue, broken)| json!({
+            "key": issue.key,
+            "summary": issue.fields.summary,
+            "rules": broken.iter().map(|rule| rule.name()).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+    });
+    let rows = violations
+        .iter()
+        .flat_map(|(issue, broken)| {
+            broken.iter().map(|rule| {
+                vec![
+                    issue.key.clone(),
+                    rule.name().to_string(),
+                    rule.message().to_string(),
+                ]
+            })
+        })
+        .collect();
+    let table = TableOutput::new(payload, &["Key", "Rule", "Message"], rows).human(|| {
+        for (issue, broken) in &violations {
+            println!("{}: {}", issue.key.bold(), issue.fields.summary);
+            for rule in broken {
+                println!("  {}  {}", rule.name().red(), rule.message());
+            }
+        }
+        if violations.is_empty() {
+            println!("{} issue(s) passed {} rule(s)", issues.len(), rules.len());
+        }
+        Ok(())
+    });
+    render_output(output.or_json(args.json), &table)?;
+
+    if problem_count > 0 {
+        return Err(anyhow!(
+            "Found {} problem(s) in {} of {} issue(s)",
+            problem_count,
+            violations.len(),
+            issues.len()
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DodCheck {
+    LinkedPr,
+    ChecklistComplete,
+    FixVersion,
+}
+
+#[derive(Debug)]
+struct DodResult {
+    check: DodCheck,
+    passed: bool,
+    detail: String,
+}
+
+impl DodCheck {
+    const ALL: [DodCheck; 3] = [
+        DodCheck::LinkedPr,
+        DodCheck::ChecklistComplete,
+        DodCheck::FixVersion,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            DodCheck::LinkedPr => "linked-pr",
+            DodCheck::ChecklistComplete => "checklist-complete",
+            DodCheck::FixVersion => "fix-version",
+        }
+    }
+
+    fn parse(name: &str) -> Result<DodCheck> {
+        DodCheck::ALL
+            .into_iter()
+            .find(|check| check.name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown definition-of-done check '{}'. Available checks: {}",
+                    name,
+                    DodCheck::ALL.map(DodCheck::name).join(", ")
+                )
+            })
+    }
+}
+
+fn configured_dod_checks(config: &AppConfig) -> Result<Vec<DodCheck>> {
+    if !config.dod.checks.is_empty() {
+        return config
+            .dod
+            .checks
+            .iter()
+            .map(|name| DodCheck::parse(name))
+            .collect();
+    }
+
+    let mut checks = vec![DodCheck::LinkedPr, DodCheck::FixVersion];
+    if config.checklist.field.is_some() {
+        checks.insert(1, DodCheck::ChecklistComplete);
+    }
+    Ok(checks)
+}
+
+/// The result of each configured check, and warnings that do not fail the
+/// definition of done, such as missing acceptance criteria.
+fn evaluate_definition_of_done(
+    jira: &JiraClient,
+    config: &AppConfig,
+    ticket_id: &str,
+) -> Result<(Vec<DodResult>, Vec<String>)> {
+    let base_url = config.jira.base_url.as_str();
+    let checks = configured_dod_checks(config)?;
+    let mut fields = vec!["summary", "fixVersions"];
+    if let Some(field) = config.checklist.field.as_deref() {
+        fields.push(field);
+    }
+    let criteria_field = config.acceptance_criteria.field.as_deref();
+    fields.extend(criteria_field);
+    let issue = jira.issue_with_fields(ticket_id, &fields)?;
+
+    let mut warnings = Vec::new();
+    if let Some(field) = criteria_field {
+        let value = issue.fields.extra.get(field).unwrap_or(&Value::Null);
+        if acceptance_criteria_items(value).is_empty() {
+            warnings.push(format!(
+                "{} has no acceptance criteria ({} is empty)",
+                ticket_id, field
+            ));
+        }
+    }
+
+    let results = checks
+        .into_iter()
+        .map(|check| {
+            let (passed, detail) = match check {
+                DodCheck::LinkedPr => {
+                    let pull_requests = fetch_linked_pull_requests(
+                        jira.http(),
+                        base_url,
+                        &issue.id,
+                        config.bitbucket.is_some(),
+                    )?;
+                    (
+                        !pull_requests.is_empty(),
+                        format!("{} linked pull request(s)", pull_requests.len()),
+                    )
+                }
+                DodCheck::ChecklistComplete => checklist_completion(config, &issue),
+                DodCheck::FixVersion => fix_version_status(&issue),
+            };
+            Ok(DodResult {
+                check,
+                passed,
+                detail,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok((results, warnings))
+}
+
+fn checklist_completion(config: &AppConfig, issue: &JiraIssue) -> (bool, String) {
+    let Some(field) = config.checklist.field.as_deref() else {
+        return (false, "no checklist field configured".to_string());
+    };
+    let value = issue
+        .fields
+        .extra
+        .get(field)
+        .cloned()
+        .unwrap_or(Value::Null);
+    let format = config
+        .checklist
+        .format
+        .or_else(|| detect_checklist_format(&value));
+    let items = format
+        .map(|format| parse_checklist(&value, format))
+        .unwrap_or_default();
+    if items.is_empty() {
+        return (false, "checklist is empty".to_string());
+    }
+    let done = items.iter().filter(|item| item.checked).count();
+    (
+        done == items.len(),
+        format!("{}/{} items done", done, items.len()),
+    )
+}
+
+fn fix_version_names(issue: &JiraIssue) -> Vec<&str> {
+    issue
+        .fields
+        .extra
+        .get("fixVersions")
+        .and_then(Value::as_array)
+        .map(|versions| {
+            versions
+                .iter()
+                .filter_map(|version| version["name"].as_str())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn fix_version_status(issue: &JiraIssue) -> (bool, String) {
+    let versions = fix_version_names(issue);
+    if versions.is_empty() {
+        (false, "no fix version set".to_string())
+    } else {
+        (true, versions.join(", "))
+    }
+}
+
+fn print_dod_results(ticket_id: &str, results: &[DodResult]) {
+    println!("Definition of done for {}", ticket_id.bold());
+    for result in results {
+        let state = if result.passed {
+            "PASS".green()
+        } else {
+            "FAIL".red()
+        };
+        println!("  {}  {:<20} {}", state, result.check.name(), result.detail);
+    }
+}
+
+fn run_dod_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: &DodArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    let (results, warnings) = evaluate_definition_of_done(jira, config, &ticket_id)?;
+    let passed = results.iter().all(|result| result.passed);
+
+    let payload = json!({
+        "key": ticket_id,
+        "passed": passed,
+        "warnings": warnings,
+        "checks": results.iter().map(|result| json!({
+            "name": result.check.name(),
+            "passed": result.passed,
+            "detail": result.detail,
+        })).collect::<Vec<_>>(),
+    });
+    let rows = results
+        .iter()
+        .map(|result| {
+            vec![
+                result.check.name().to_string(),
+                result.passed.to_string(),
+                result.detail.clone(),
+            ]
+        })
+        .collect();
+    let table = TableOutput::new(payload, &["Check", "Passed", "Detail"], rows).human(|| {
+        print_dod_results(&ticket_id, &results);
+        Ok(())
+    });
+    render_output(output.or_json(args.json), &table)?;
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+
+    if !passed {
+        return Err(anyhow!(
+            "{} does not meet the definition of done",
+            ticket_id
+        ));
+    }
+    Ok(())
+}
+
+fn run_done_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: &DoneArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    let (results, _) = evaluate_definition_of_done(jira, config, &ticket_id)?;
+    if !results.iter().all(|result| result.passed) {
+        print_dod_results(&ticket_id, &results);
+        if !args.force {
+            return Err(anyhow!(
+                "{} does not meet the definition of done. Fix the failing checks or pass --force.",
+                ticket_id
+            ));
+        }
+        eprintln!("Continuing because --force was passed");
+    }
+
+    let status = args
+        .status
+        .as_deref()
+        .or(config.dod.done_status.as_deref())
+        .unwrap_or("Done");
+    transition_issue_to_status(jira.http(), jira.base_url(), &ticket_id, status)?;
+    let line = format!("{}: moved to {}", ticket_id, status);
+    let record = RecordOutput::new(json!({ "key": ticket_id, "status": status }))
+        .field("Ticket", &ticket_id)
+        .field("Status", status)
+        .line(line.clone())
+        .human(|| {
+            println!("{}", line);
+            Ok(())
+        });
+    render_output(output, &record)
+}
+
+/// `jit dup`: links the duplicate to the original, moves it to the closed
+/// status with the duplicate resolution, and comments with a link to the original.
+fn run_dup_command(
+    client: &Client,
+    config: &AppConfig,
+    args: &DupArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let (duplicate, original) = duplicate_pair(&args.ticket, &args.of)?;
+    let (status, resolution) = close_as_duplicate(
+        client,
+        config,
+        &duplicate,
+        &original,
+        args.status.as_deref(),
+    )?;
+
+    let line = format!(
+        "{}: closed as a duplicate of {} ({})",
+        duplicate, original, status
+    );
+    let record = RecordOutput::new(json!({
+        "key": duplicate,
+        "duplicate_of": original,
+        "status": status,
+        "resolution": resolution,
+    }))
+    .field("Ticket", &duplicate)
+    .field("Duplicate of", &original)
+    .field("Status", status)
+    .field("Resolution", resolution)
+    .line(line.clone())
+    .human(|| {
+        println!("{}", line);
+        Ok(())
+    });
+    render_output(output, &record)
+}
+
+/// The duplicate and original ticket keys, which must differ.
+fn duplicate_pair(duplicate: &str, original: &str) -> Result<(String, String)> {
+    let duplicate = extract_ticket_id(duplicate)?;
+    let original = extract_ticket_id(original)?;
+    if duplicate.eq_ignore_ascii_case(&original) {
+        return Err(anyhow!("{} cannot be a duplicate of itself", duplicate));
+    }
+    Ok((duplicate, original))
+}
+
+/// Links `duplicate` to `original`, moves it to the [duplicate] status with
+/// the duplicate resolution, and comments with a link to the original.
+/// Returns the status and resolution set.
+fn close_as_duplicate<'a>(
+    client: &Client,
+    config: &'a AppConfig,
+    duplicate: &str,
+    original: &str,
+    status: Option<&'a str>,
+) -> Result<(&'a str, &'a str)> {
+    let base_url = config.jira.base_url.as_str();
+    let settings = &config.duplicate;
+    let status = status
+        .or(settings.status.as_deref())
+        .or(config.dod.done_status.as_deref())
+        .unwrap_or("Done");
+    let resolution = settings.resolution.as_deref().unwrap_or("Duplicate");
+
+    link_issues(
+        client,
+        base_url,
+        settings.link_type.as_deref().unwrap_or("Duplicate"),
+        duplicate,
+        original,
+    )?;
+    let transition_id = status_transition_id(client, base_url, duplicate, status)?;
+    transition_issue_with_fields(
+        client,
+        base_url,
+        duplicate,
+        &transition_id,
+        &json!({ "resolution": { "name": resolution } }),
+    )?;
+    let comment = json!({
+        "type": "doc",
+        "version": 1,
+        "content": [{
+            "type": "paragraph",
+            "content": [
+                { "type": "text", "text": "Closed as a duplicate of " },
+                {
+                    "type": "text",
+                    "text": original,
+                    "marks": [{
+                        "type": "link",
+                        "attrs": { "href": format!("{}/browse/{}", base_url, original) },
+                    }],
+                },
+                { "type": "text", "text": "." },
+            ],
+        }],
+    });
+    add_issue_comment(client, base_url, duplicate, &comment)?;
+    Ok((status, resolution))
+}
+
+/// `jit merge`: posts a digest of the duplicate's comments and attachments on
+/// the original, adds the duplicate's watchers to it, then closes the
+/// duplicate as `jit dup` does. A watcher Jira refuses is reported and skipped.
+fn run_merge_command(
+    client: &Client,
+    config: &AppConfig,
+    args: &MergeArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let (duplicate, original) = duplicate_pair(&args.ticket, &args.into)?;
+    let comments = fetch_all_comment_values(client, base_url, &duplicate)?;
+    let attachments = fetch_issue_field(client, base_url, &duplicate, "attachment")?;
+    let attachments = attachments.as_array().map_or(&[][..], Vec::as_slice);
+    let watchers = fetch_issue_watchers(client, base_url, &duplicate)?;
+
+    let digest = merge_digest(base_url, &duplicate, &comments, attachments);
+    add_issue_comment(client, base_url, &original, &digest)?;
+    let mut added = Vec::new();
+    for watcher in &watchers {
+        let Some(account_id) = watcher.account_id.as_deref() else {
+            continue;
+        };
+        match add_issue_watcher(client, base_url, &original, account_id) {
+            Ok(()) => added.push(watcher.display_name.clone()),
+            Err(err) => eprintln!(
+                "Could not add {} as a watcher of {}: {:#}",
+                watcher.display_name, original, err
+            ),
+        }
+    }
+    let (status, resolution) = close_as_duplicate(
+        client,
+        config,
+        &duplicate,
+        &original,
+        args.status.as_deref(),
+    )?;
+
+    let line = format!(
+        "{}: merged into {} ({} comments, {} attachments, {} watchers) and closed ({})",
+        duplicate,
+        original,
+        comments.len(),
+        attachments.len(),
+        added.len(),
+        status
+    );
+    let record = RecordOutput::new(json!({
+        "key": duplicate,
+        "merged_into": original,
+        "comments": comments.len(),
+        "attachments": attachments.len(),
+        "watchers_added": added,
+        "status": status,
+        "resolution": resolution,
+    }))
+    .field("Ticket", &duplicate)
+    .field("Merged into", &original)
+    .field("Comments", comments.len().to_string())
+    .field("Attachments", attachments.len().to_string())
+    .field("Watchers added", added.join(", "))
+    .field("Status", status)
+    .line(line.clone())
+    .human(|| {
+        println!("{}", line);
+        Ok(())
+    });
+    render_output(output, &record)
+}
+
+/// A piece of a story `jit split` can turn into its own issue.
+#[derive(Debug, PartialEq)]
+struct SplitCandidate {
+    summary: String,
+    /// Markdown for the new issue's description.
+    description: Option<String>,
+}
+
+/// `jit split`: lists the story's checklist items and description sections,
+/// creates an issue for each one picked, links it as split from the story,
+/// and adds it to the story's open sprint when asked to.
+fn run_split_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: &SplitArgs,
+    input: &mut impl BufRead,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let ticket = extract_ticket_id(&args.ticket)?;
+    let mut fields = vec!["summary", "issuetype", "description", SPRINT_FIELD];
+    fields.extend(config.checklist.field.as_deref());
+    let story = jira.issue_with_fields(&ticket, &fields)?;
+
+    let mut candidates = Vec::new();
+    if let Some(field) = config.checklist.field.as_deref()
+        && let Some(value) = story.fields.extra.get(field)
+        && let Some(format) = config
+            .checklist
+            .format
+            .or_else(|| detect_checklist_format(value))
+    {
+        candidates.extend(
+            parse_checklist(value, format)
+                .into_iter()
+                .map(|item| SplitCandidate {
+                    summary: item.text,
+                    description: None,
+                }),
+        );
+    }
+    let checklist_items = candidates.len();
+    if let Some(description) = story.fields.description.as_ref() {
+        candidates.extend(description_sections(&adf_to_markdown(description)));
+    }
+    if candidates.is_empty() {
+        return Err(JitError::FieldMissing(format!(
+            "{} has no checklist items or description sections to split",
+            ticket
+        ))
+        .into());
+    }
+
+    eprintln!("{}: {}", ticket, story.fields.summary);
+    for (index, candidate) in candidates.iter().enumerate() {
+        if index == 0 && checklist_items > 0 {
+            eprintln!("Checklist:");
+        } else if index == checklist_items {
+            eprintln!("Description sections:");
+        }
+        eprintln!("  {}. {}", index + 1, candidate.summary);
+    }
+    eprint!("Split out which items? (e.g. 1,3-4 or all): ");
+    let mut selection = String::new();
+    input.read_line(&mut selection)?;
+    let selected = parse_item_selection(&selection, candidates.len())?;
+
+    let sprint = story
+        .fields
+        .sprint
+        .iter()
+        .flatten()
+        .filter(|sprint| sprint.id.is_some())
+        .min_by_key(|sprint| match sprint.state.as_str() {
+            "active" => 0,
+            "future" => 1,
+            _ => 2,
+        })
+        .filter(|sprint| sprint.state != "closed");
+    let sprint = match sprint {
+        Some(_) if args.same_sprint => sprint,
+        Some(sprint) => {
+            eprint!("Add them to {} too? [y/N]: ", sprint.name);
+            let mut answer = String::new();
+            input.read_line(&mut answer)?;
+            matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes").then_some(sprint)
+        }
+        None if args.same_sprint => {
+            return Err(anyhow!("{} is not in an open sprint", ticket));
+        }
+        None => None,
+    };
+
+    let project = ticket.split('-').next().unwrap_or_default();
+    let issue_type = args
+        .issue_type
+        .as_deref()
+        .or(story
+            .fields
+            .issuetype
+            .as_ref()
+            .map(|issue_type| issue_type.name.as_str()))
+        .unwrap_or("Task");
+    let link_type = config.split.link_type.as_deref().unwrap_or("Issue split");
+    let mut created = Vec::new();
+    for index in selected {
+        let candidate = &candidates[index];
+        let mut fields = json!({
+            "project": { "key": project },
+            "summary": candidate.summary,
+            "issuetype": { "name": issue_type },
+        });
+        if let Some(description) = candidate.description.as_deref() {
+            fields["description"] = markdown_to_adf(description);
+        }
+        let issue = jira.create_issue(&json!({ "fields": fields }))?;
+        link_issues(jira.http(), base_url, link_type, &ticket, &issue.key).with_context(|| {
+            format!("Created {} but failed to link it to {}", issue.key, ticket)
+        })?;
+        created.push((issue.key, candidate.summary.clone()));
+    }
+    if let Some(sprint) = sprint {
+        let keys: Vec<String> = created.iter().map(|(key, _)| key.clone()).collect();
+        move_issues_to_sprint(jira.http(), base_url, sprint.id, &keys).with_context(|| {
+            format!(
+                "Created {} but failed to add them to {}",
+                keys.join(", "),
+                sprint.name
+            )
+        })?;
+    }
+
+    let sprint_name = sprint.map(|sprint| sprint.name.as_str());
+    let record = RecordOutput::new(json!({
+        "key": ticket,
+        "split_into": created
+            .iter()
+            .map(|(key, summary)| json!({ "ticket": key, "summary": summary }))
+            .collect::<Vec<_>>(),
+        "sprint": sprint_name,
+    }))
+    .field("Ticket", &ticket)
+    .field(
+        "Split into",
+        created
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+    .field("Sprint", sprint_name.unwrap_or("-"))
+    .line(format!("{}: split into {} issues", ticket, created.len()))
+    .human(|| {
+        for (key, summary) in &created {
+            println!("{}: {}", key, summary);
+        }
+        match sprint_name {
+            Some(name) => println!("Split from {} and added to {}", ticket, name),
+            None => println!("Split from {}", ticket),
+        }
+        Ok(())
+    });
+    render_output(output, &record)
+}
+
+/// The sections of a markdown description, split at its top-level headings,
+/// each with its heading as the summary. Text before the first heading stays
+/// with the story.
+fn description_sections(markdown: &str) -> Vec<SplitCandidate> {
+    let heading = Regex::new(r"^(#{1,6})\s+(.+?)\s*#*\s*$").expect("heading regex is valid");
+    let Some(level) = markdown
+        .lines()
+        .filter_map(|line| heading.captures(line))
+        .map(|captures| captures[1].len())
+        .min()
+    else {
+        return Vec::new();
+    };
+
+    let mut sections: Vec<(String, Vec<&str>)> = Vec::new();
+    for line in markdown.lines() {
+        match heading.captures(line) {
+            Some(captures) if captures[1].len() == level => {
+                sections.push((captures[2].to_string(), Vec::new()));
+            }
+            _ => {
+                if let Some((_, body)) = sections.last_mut() {
+                    body.push(line);
+                }
+            }
+        }
+    }
+    sections
+        .into_iter()
+        .map(|(summary, body)| {
+            let body = body.join("\n").trim().to_string();
+            SplitCandidate {
+                summary,
+                description: (!body.is_empty()).then_some(body),
+            }
+        })
+        .collect()
+}
+
+/// Zero-based indexes for a selection like `1,3-4` or `all` among `count` items.
+fn parse_item_selection(selection: &str, count: usize) -> Result<Vec<usize>> {
+    let selection = selection.trim();
+    if selection.eq_ignore_ascii_case("all") {
+        return Ok((0..count).collect());
+    }
+    let number = |text: &str| -> Result<usize> {
+        match text.trim().parse::<usize>() {
+            Ok(number) if (1..=count).contains(&number) => Ok(number - 1),
+            _ => Err(anyhow!(
+                "'{}' is not an item number between 1 and {}",
+                text.trim(),
+                count
+            )),
+        }
+    };
+    let mut selected = Vec::new();
+    for part in selection
+        .split([',', ' '])
+        .filter(|part| !part.trim().is_empty())
+    {
+        let range = match part.split_once('-') {
+            Some((start, end)) => number(start)?..=number(end)?,
+            None => number(part)?..=number(part)?,
+        };
+        for index in range {
+            if !selected.contains(&index) {
+                selected.push(index);
+            }
+        }
+    }
+    if selected.is_empty() {
+        return Err(anyhow!("No items selected"));
+    }
+    Ok(selected)
+}
+
+/// The comment `jit merge` leaves on the original: who said what on the
+/// duplicate, one line per comment, and links to its attachments.
+fn merge_digest(
+    base_url: &str,
+    duplicate: &str,
+    comments: &[Value],
+    attachments: &[Value],
+) -> Value {
+    let text = |text: &str| json!({ "type": "text", "text": text });
+    let link = |text: &str, href: &str| json!({ "type": "text", "text": text, "marks": [{ "type": "link", "attrs": { "href": href } }] });
+    let paragraph = |content: Vec<Value>| json!({ "type": "paragraph", "content": content });
+    let list = |items: Vec<Vec<Value>>| {
+        json!({
+            "type": "bulletList",
+            "content": items
+                .into_iter()
+                .map(|item| json!({ "type": "listItem", "content": [paragraph(item)] }))
+                .collect::<Vec<_>>(),
+        })
+    };
+
+    let mut content = vec![paragraph(vec![
+        text("Merged from "),
+        link(duplicate, &format!("{}/browse/{}", base_url, duplicate)),
+        text("."),
+    ])];
+    if !comments.is_empty() {
+        content.push(paragraph(vec![text(&format!(
+            "Comments ({}):",
+            comments.len()
+        ))]));
+        content.push(list(
+            comments
+                .iter()
+                .map(|comment| {
+                    let author = comment["author"]["displayName"]
+                        .as_str()
+                        .unwrap_or("Unknown");
+                    let created = comment["created"]
+                        .as_str()
+                        .map(format_date)
+                        .unwrap_or_default();
+                    let body = adf_value_to_display_text(&comment["body"]);
+                    let first_line = body
+                        .lines()
+                        .find(|line| !line.trim().is_empty())
+                        .unwrap_or_default();
+                    vec![text(&format!(
+                        "{} ({}): {}",
+                        author,
+                        created,
+                        truncate_with_ellipsis(first_line.trim(), 200)
+                    ))]
+                })
+                .collect(),
+        ));
+    }
+    if !attachments.is_empty() {
+        content.push(paragraph(vec![text(&format!(
+            "Attachments ({}):",
+            attachments.len()
+        ))]));
+        content.push(list(
+            attachments
+                .iter()
+                .map(|attachment| {
+                    let name = attachment["filename"].as_str().unwrap_or("attachment");
+                    match attachment["content"].as_str() {
+                        Some(url) => vec![link(name, url)],
+                        None => vec![text(name)],
+                    }
+                })
+                .collect(),
+        ));
+    }
+    json!({ "type": "doc", "version": 1, "content": content })
+}
+
+#[derive(Debug, Default)]
+struct ProjectStats {
+    buckets: Vec<StatsBucket>,
+    top_assignees: Vec<(String, usize)>,
+    top_components: Vec<(String, usize)>,
+}
+
+#[derive(Debug)]
+struct StatsBucket {
+    start: DateTime<Utc>,
+    created: usize,
+    resolved: usize,
+    open_bugs: usize,
+}
+
+const STATS_TOP_COUNT: usize = 5;
+
+fn run_stats_command(jira: &impl JiraApi, args: &StatsArgs, output: OutputFormat) -> Result<()> {
+    let days = (args.since.as_secs() / 86_400).max(1);
+    let jql = format!(
+        "project = \"{}\" AND (created >= -{}d OR resolved >= -{}d OR (issuetype = Bug AND resolution = EMPTY))",
+        args.project, days, days
+    );
+    let fields = [
+        "summary",
+        "created",
+        "resolutiondate",
+        "issuetype",
+        "assignee",
+        "components",
+    ];
+    let issues = jira.search(
+        &format!("{} ORDER BY created ASC", jql),
+        &fields,
+        issue_limit(args.limit),
+    )?;
+    truncated_total(jira, &jql, issues.len(), args.limit)?;
+    let now = now_utc();
+    let since = now - chrono::Duration::days(days as i64);
+    let stats = build_project_stats(&issues, since, now, args.bucket);
+
+    let top = |entries: &[(String, usize)]| {
+        entries
+            .iter()
+            .map(|(name, count)| json!({ "name": name, "count": count }))
+            .collect::<Vec<_>>()
+    };
+    let payload = json!({
+        "project": args.project,
+        "since": since.to_rfc3339(),
+        "issues": issues.len(),
+        "buckets": stats.buckets.iter().map(|bucket| json!({
+            "start": bucket.start.format("%Y-%m-%d").to_string(),
+            "created": bucket.created,
+            "resolved": bucket.resolved,
+            "openBugs": bucket.open_bugs,
+        })).collect::<Vec<_>>(),
+        "topAssignees": top(&stats.top_assignees),
+        "topComponents": top(&stats.top_components),
+    });
+    let rows: Vec<Vec<String>> = stats
+        .buckets
+        .iter()
+        .map(|bucket| {
+            vec![
+                bucket.start.format("%Y-%m-%d").to_string(),
+                bucket.created.to_string(),
+                bucket.resolved.to_string(),
+                bucket.open_bugs.to_string(),
+            ]
+        })
+        .collect();
+    let table = TableOutput::new(
+        payload,
+        &["period_start", "created", "resolved", "open_bugs"],
+        rows.clone(),
+    )
+    .human(|| {
+        print_project_stats(args, days, issues.len(), &stats, &rows);
+        Ok(())
+    });
+    render_output(output.or_csv(args.csv).or_json(args.json), &table)
+}
+
+fn print_project_stats(
+    args: &StatsArgs,
+    days: u64,
+    issue_count: usize,
+    stats: &ProjectStats,
+    bucket_rows: &[Vec<String>],
+) {
+    println!(
+        "{}",
+        format!(
+            "Project stats for {} (last {} days, {} issues)",
+            args.project, days, issue_count
+        )
+        .bold()
+    );
+    println!();
+
+    let header = vec![
+        "Period starting".to_string(),
+        "Created".to_string(),
+        "Resolved".to_string(),
+        "Open bugs".to_string(),
+    ];
+    print_box_table(&[vec![header], bucket_rows.to_vec()].concat());
+
+    if args.chart {
+        let series = |value: fn(&StatsBucket) -> usize| {
+            sparkline(&stats.buckets.iter().map(value).collect::<Vec<_>>())
+        };
+        println!();
+        println!("Created    {}", series(|bucket| bucket.created));
+        println!("Resolved   {}", series(|bucket| bucket.resolved));
+        println!("Open bugs  {}", series(|bucket| bucket.open_bugs));
+    }
+
+    for (title, header, entries) in [
+        ("Top assignees", "Resolved", &stats.top_assignees),
+        ("Top components", "Created", &stats.top_components),
+    ] {
+        println!();
+        println!("{}", title.bold());
+        if entries.is_empty() {
+            println!("  None");
+            continue;
+        }
+        let mut rows = vec![vec!["Name".to_string(), header.to_string()]];
+        rows.extend(
+            entries
+                .iter()
+                .map(|(name, count)| vec![name.clone(), count.to_string()]),
+        );
+        print_box_table(&rows);
+    }
+}
+
+fn build_project_stats(
+    issues: &[JiraIssue],
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+    bucket: Duration,
+) -> ProjectStats {
+    let bucket = chrono::Duration::from_std(bucket)
+        .ok()
+        .filter(|bucket| *bucket > chrono::Duration::zero())
+        .unwrap_or_else(|| chrono::Duration::days(7));
+    let parse = |value: Option<&str>| {
+        value
+            .and_then(parse_jira_datetime)
+            .map(|date| date.with_timezone(&Utc))
+    };
+    type DatedIssue<'a> = (&'a JiraIssue, Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+    let dated: Vec<DatedIssue> = issues
+        .iter()
+        .map(|issue| {
+            let created = parse(issue.fields.created.as_deref());
+            let resolved = parse(
+                issue
+                    .fields
+                    .extra
+                    .get("resolutiondate")
+                    .and_then(Value::as_str),
+            );
+            (issue, created, resolved)
+        })
+        .collect();
+
+    let mut stats = ProjectStats::default();
+    let mut start = since;
+    while start < now {
+        let end = (start + bucket).min(now);
+        let in_bucket = |date: Option<DateTime<Utc>>| date.is_some_and(|d| d >= start && d < end);
+        stats.buckets.push(StatsBucket {
+            start,
+            created: dated.iter().filter(|(_, c, _)| in_bucket(*c)).count(),
+            resolved: dated.iter().filter(|(_, _, r)| in_bucket(*r)).count(),
+            open_bugs: dated
+                .iter()
+                .filter(|(issue, created, resolved)| {
+                    is_bug(issue)
+                        && created.is_some_and(|created| created < end)
+                        && resolved.is_none_or(|resolved| resolved >= end)
+                })
+                .count(),
+        });
+        start = end;
+    }
+
+    let mut assignees: HashMap<String, usize> = HashMap::new();
+    let mut components: HashMap<String, usize> = HashMap::new();
+    for (issue, created, resolved) in &dated {
+        if resolved.is_some_and(|resolved| resolved >= since)
+            && let Some(assignee) = issue.fields.assignee.as_ref()
+        {
+            *assignees.entry(assignee.display_name.clone()).or_default() += 1;
+        }
+        if created.is_some_and(|created| created >= since) {
+            for component in issue
+                .fields
+                .extra
+                .get("components")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|component| component["name"].as_str())
+            {
+                *components.entry(component.to_string()).or_default() += 1;
+            }
+        }
+    }
+    stats.top_assignees = top_counts(assignees, STATS_TOP_COUNT);
+    stats.top_components = top_counts(components, STATS_TOP_COUNT);
+    stats
+}
+
+fn is_bug(issue: &JiraIssue) -> bool {
+    issue
+        .fields
+        .issuetype
+        .as_ref()
+        .is_some_and(|issue_type| issue_type.name.eq_ignore_ascii_case("bug"))
+}
+
+/// Sorts counts descending (ties by name) and keeps the first `limit` entries.
+fn top_counts(counts: HashMap<String, usize>, limit: usize) -> Vec<(String, usize)> {
+    let mut entries: Vec<(String, usize)> = counts.into_iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entries.truncate(limit);
+    entries
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Snapshot {
+    date: String,
+    project: String,
+    total: usize,
+    statuses: BTreeMap<String, usize>,
+    assignees: BTreeMap<String, usize>,
+}
+
+fn snapshot_store_path() -> Result<PathBuf> {
+    Ok(jit_data_dir()?.join("snapshots.json"))
+}
+
+fn load_snapshots(path: &Path) -> Result<Vec<Snapshot>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read snapshots from {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse snapshots in {}", path.display()))
+}
+
+fn save_snapshots(path: &Path, snapshots: &[Snapshot]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let contents = serde_json::to_string_pretty(snapshots)?;
+    fs::write(path, contents)
+        .with_context(|| format!("Failed to write snapshots to {}", path.display()))
+}
+
+/// Adds a snapshot, replacing any earlier one for the same project and day.
+fn upsert_snapshot(snapshots: &mut Vec<Snapshot>, snapshot: Snapshot) {
+    snapshots.retain(|existing| {
+        !(existing.project == snapshot.project && existing.date == snapshot.date)
+    });
+    snapshots.push(snapshot);
+    snapshots.sort_by(|a, b| a.project.cmp(&b.project).then_with(|| a.date.cmp(&b.date)));
+}
+
+fn build_snapshot(project: &str, date: &str, issues: &[JiraIssue]) -> Snapshot {
+    let mut statuses = BTreeMap::new();
+    let mut assignees = BTreeMap::new();
+    for issue in issues {
+        let status = issue
+            .fields
+            .status
+            .as_ref()
+            .map_or("Unknown", |status| status.name.as_str());
+        *statuses.entry(status.to_string()).or_default() += 1;
+        let assignee = issue
+            .fields
+            .assignee
+            .as_ref()
+            .map_or("Unassigned", |user| user.display_name.as_str());
+        *assignees.entry(assignee.to_string()).or_default() += 1;
+    }
+    Snapshot {
+        date: date.to_string(),
+        project: project.to_string(),
+        total: issues.len(),
+        statuses,
+        assignees,
+    }
+}
+
+fn run_snapshot_command(
+    jira: &impl JiraApi,
+    args: &SnapshotArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let jql = format!("project = \"{}\" AND statusCategory != Done", args.project);
+    let issues = jira.search(
+        &jql,
+        &["summary", "status", "assignee"],
+        issue_limit(args.limit),
+    )?;
+    let date = now_utc().format("%Y-%m-%d").to_string();
+    let mut snapshot = build_snapshot(&args.project, &date, &issues);
+    if let Some(total) = truncated_total(jira, &jql, issues.len(), args.limit)? {
+        snapshot.total = total;
+    }
+    let total = snapshot.total;
+
+    let path = snapshot_store_path()?;
+    let mut snapshots = load_snapshots(&path)?;
+    let payload = serde_json::to_value(&snapshot)?;
+    upsert_snapshot(&mut snapshots, snapshot);
+    save_snapshots(&path, &snapshots)?;
+
+    let line = format!(
+        "Recorded {} open issues for {} on {} in {}",
+        total,
+        args.project,
+        date,
+        path.display()
+    );
+    let record = RecordOutput::new(payload)
+        .field("Project", &args.project)
+        .field("Date", &date)
+        .field("Open", total)
+        .field("Store", path.display())
+        .line(line.clone())
+        .human(|| {
+            println!("{}", line);
+            Ok(())
+        });
+    render_output(output, &record)
+}
+
+fn run_stats_trend(args: &StatsArgs, output: OutputFormat) -> Result<()> {
+    let path = snapshot_store_path()?;
+    let since = (now_utc() - chrono::Duration::from_std(args.since)?)
+        .format("%Y-%m-%d")
+        .to_string();
+    let snapshots: Vec<Snapshot> = load_snapshots(&path)?
+        .into_iter()
+        .filter(|snapshot| snapshot.project == args.project && snapshot.date >= since)
+        .collect();
+    if snapshots.is_empty() {
+        return Err(anyhow!(
+            "No snapshots for {} since {}. Run `jit snapshot --project {}` daily (e.g., from cron) to build the trend.",
+            args.project,
+            since,
+            args.project
+        ));
+    }
+
+    let statuses: Vec<&String> = snapshots
+        .iter()
+        .flat_map(|snapshot| snapshot.statuses.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let headers: Vec<String> = ["Date".to_string(), "Open".to_string()]
+        .into_iter()
+        .chain(statuses.iter().map(|status| status.to_string()))
+        .collect();
+    let mut rows = Vec::new();
+    for snapshot in &snapshots {
+        rows.push(
+            [snapshot.date.clone(), snapshot.total.to_string()]
+                .into_iter()
+                .chain(statuses.iter().map(|status| {
+                    snapshot
+                        .statuses
+                        .get(*status)
+                        .copied()
+                        .unwrap_or(0)
+                        .to_string()
+                }))
+                .collect(),
+        );
+    }
+
+    let header_refs: Vec<&str> = headers.iter().map(String::as_str).collect();
+    let table = TableOutput::new(
+        serde_json::to_value(&snapshots)?,
+        &header_refs,
+        rows.clone(),
+    )
+    .human(|| {
+        println!(
+            "{}",
+            format!("Open issue trend for {} since {}", args.project, since).bold()
+        );
+        println!();
+        print_box_table(&[vec![headers.clone()], rows.clone()].concat());
+        if args.chart {
+            let totals: Vec<usize> = snapshots.iter().map(|snapshot| snapshot.total).collect();
+            println!();
+            println!("Open  {}", sparkline(&totals));
+        }
+        Ok(())
+    });
+    render_output(output.or_csv(args.csv).or_json(args.json), &table)
+}
+
+/// Age buckets for the heatmap columns: label and upper bound in days.
+const AGE_BUCKETS: [(&str, i64); 6] = [
+    ("<1w", 7),
+    ("1-4w", 28),
+    ("1-3m", 90),
+    ("3-6m", 180),
+    ("6-12m", 365),
+    (">1y", i64::MAX),
+];
+
+/// Issues at least this many days old count as ancient and are highlighted.
+const ANCIENT_AGE_DAYS: i64 = 180;
+
+#[derive(Debug, Default)]
+struct AgeHeatmap {
+    rows: Vec<(String, [usize; AGE_BUCKETS.len()])>,
+    oldest: Vec<(String, String, i64)>,
+}
+
+fn age_bucket_index(age_days: i64) -> usize {
+    AGE_BUCKETS
+        .iter()
+        .position(|(_, upper)| age_days < *upper)
+        .unwrap_or(AGE_BUCKETS.len() - 1)
+}
+
+fn build_age_heatmap(issues: &[JiraIssue], now: DateTime<Utc>) -> AgeHeatmap {
+    let mut rows: BTreeMap<String, [usize; AGE_BUCKETS.len()]> = BTreeMap::new();
+    let mut aged = Vec::new();
+    for issue in issues {
+        let Some(created) = issue
+            .fields
+            .created
+            .as_deref()
+            .and_then(parse_jira_datetime)
+        else {
+            continue;
+        };
+        let age_days = (now - created.with_timezone(&Utc)).num_days().max(0);
+        let status = issue
+            .fields
+            .status
+            .as_ref()
+            .map_or("Unknown", |status| status.name.as_str());
+        rows.entry(status.to_string()).or_default()[age_bucket_index(age_days)] += 1;
+        if age_days >= ANCIENT_AGE_DAYS {
+            aged.push((issue.key.clone(), issue.fields.summary.clone(), age_days));
+        }
+    }
+    aged.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+    aged.truncate(5);
+
+    AgeHeatmap {
+        rows: rows.into_iter().collect(),
+        oldest: aged,
+    }
+}
+
+#[derive(Debug, Default)]
+struct MemberWorkload {
+    account_id: String,
+    name: String,
+    to_do: usize,
+    in_progress: usize,
+}
+
+impl MemberWorkload {
+    fn total(&self) -> usize {
+        self.to_do + self.in_progress
+    }
+}
+
+/// Counts each member's open issues by status category, keeping the roster order.
+fn build_team_workload(members: &[(String, String)], issues: &[JiraIssue]) -> Vec<MemberWorkload> {
+    let mut workload: Vec<MemberWorkload> = members
+        .iter()
+        .map(|(account_id, name)| MemberWorkload {
+            account_id: account_id.clone(),
+            name: name.clone(),
+            ..MemberWorkload::default()
+        })
+        .collect();
+    for issue in issues {
+        let Some(assignee) = issue.fields.assignee.as_ref() else {
+            continue;
+        };
+        let Some(member) = workload
+            .iter_mut()
+            .find(|member| assignee.account_id.as_deref() == Some(member.account_id.as_str()))
+        else {
+            continue;
+        };
+        let category = issue
+            .fields
+            .status
+            .as_ref()
+            .and_then(|status| status.status_category.as_ref())
+            .map(|category| category.key.as_str());
+        match category {
+            Some("indeterminate") => member.in_progress += 1,
+            Some("done") => {}
+            _ => member.to_do += 1,
+        }
+    }
+    workload
+}
+
+fn run_team_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: &TeamArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let mut members = Vec::new();
+    for member in team_members(config, args.name.as_deref())? {
+        let resolved = resolve_create_assignee(jira.http(), base_url, member)?;
+        if let Some(account_id) = resolved.account_id {
+            let name = people::display_name(&account_id).unwrap_or(resolved.label);
+            members.push((account_id, name));
+        }
+    }
+    if members.is_empty() {
+        return Err(
+            JitError::Config("The team has no members with a Jira account".to_string()).into(),
+        );
+    }
+
+    let accounts: Vec<String> = members
+        .iter()
+        .map(|(account_id, _)| format!("\"{}\"", account_id.replace('"', "\\\"")))
+        .collect();
+    let mut jql = format!(
+        "assignee in ({}) AND statusCategory != Done",
+        accounts.join(", ")
+    );
+    if let Some(extra) = args.jql.as_deref() {
+        jql = and_jql(extra, &jql);
+    }
+    let issues = jira.search(
+        &jql,
+        &["summary", "assignee", "status"],
+        args.limit as usize,
+    )?;
+    let workload = build_team_workload(&members, &issues);
+
+    let team = args.name.as_deref().unwrap_or("team");
+    let payload = json!({ "team": team, "members": workload
+        .iter()
+        .map(|member| json!({
+            "account_id": member.account_id,
+            "name": member.name,
+            "to_do": member.to_do,
+            "in_progress": member.in_progress,
+            "total": member.total(),
+        }))
+        .collect::<Vec<_>>() });
+    let rows = workload
+        .iter()
+        .map(|member| {
+            vec![
+                member.name.clone(),
+                member.to_do.to_string(),
+                member.in_progress.to_string(),
+                member.total().to_string(),
+            ]
+        })
+        .collect();
+    let table = TableOutput::new(payload, &["Member", "To Do", "In Progress", "Open"], rows);
+    render_output(output, &table)
+}
+
+#[derive(Debug, PartialEq)]
+struct Dependency {
+    key: String,
+    summary: String,
+    status: String,
+    project: String,
+    assignee: Option<String>,
+    /// How our tickets relate to this issue, e.g. "is blocked by".
+    relation: String,
+    tickets: Vec<String>,
+}
+
+fn issue_project_key(key: &str) -> &str {
+    key.rsplit_once('-').map_or(key, |(project, _)| project)
+}
+
+/// Collects the unresolved issues that `project`'s tickets link to, merging
+/// tickets that share the same link, ordered by project and issue key.
+fn collect_dependencies(
+    project: &str,
+    issues: &[JiraIssue],
+    external_only: bool,
+) -> Vec<Dependency> {
+    let mut dependencies: Vec<Dependency> = Vec::new();
+    for issue in issues {
+        let links: Vec<JiraIssueLink> = issue
+            .fields
+            .extra
+            .get("issuelinks")
+            .and_then(|links| serde_json::from_value(links.clone()).ok())
+            .unwrap_or_default();
+        for link in links {
+            let (linked, relation) = match (link.inward_issue, link.outward_issue) {
+                (Some(linked), _) => (linked, link.link_type.inward),
+                (None, Some(linked)) => (linked, link.link_type.outward),
+                (None, None) => continue,
+            };
+            let linked_project = issue_project_key(&linked.key);
+            if external_only && linked_project.eq_ignore_ascii_case(project) {
+                continue;
+            }
+            let status = linked.fields.status.as_ref();
+            if status
+                .and_then(|status| status.status_category.as_ref())
+                .is_some_and(|category| category.key == "done")
+            {
+                continue;
+            }
+            match dependencies
+                .iter_mut()
+                .find(|dependency| dependency.key == linked.key && dependency.relation == relation)
+            {
+                Some(dependency) => dependency.tickets.push(issue.key.clone()),
+                None => dependencies.push(Dependency {
+                    project: linked_project.to_string(),
+                    status: status.map_or("Unknown", |status| &status.name).to_string(),
+                    summary: linked.fields.summary.clone(),
+                    key: linked.key,
+                    assignee: None,
+                    relation,
+                    tickets: vec![issue.key.clone()],
+                }),
+            }
+        }
+    }
+    dependencies.sort_by(|first, second| {
+        (&first.project, issue_number(&first.key))
+            .cmp(&(&second.project, issue_number(&second.key)))
+    });
+    dependencies
+}
+
+fn issue_number(key: &str) -> u64 {
+    key.rsplit_once('-')
+        .and_then(|(_, number)| number.parse().ok())
+        .unwrap_or_default()
+}
+
+fn run_deps_command(jira: &impl JiraApi, args: &DepsArgs, output: OutputFormat) -> Result<()> {
+    let jql = format!(
+        "project = \"{}\" AND statusCategory != Done ORDER BY key ASC",
+        args.project
+    );
+    let issues = jira.search(&jql, &["summary", "issuelinks"], args.limit as usize)?;
+    let mut dependencies = collect_dependencies(&args.project, &issues, args.external_only);
+
+    // Links only carry the linked issue's summary and status, so look up assignees separately.
+    let mut keys: Vec<&str> = dependencies
+        .iter()
+        .map(|dependency| dependency.key.as_str())
+        .collect();
+    keys.dedup();
+    let mut assignees = HashMap::new();
+    for chunk in keys.chunks(100) {
+        let jql = format!("key in ({})", chunk.join(", "));
+        for issue in jira.search(&jql, &["summary", "assignee"], chunk.len())? {
+            if let Some(assignee) = issue.fields.assignee {
+                assignees.insert(issue.key, assignee.display_name);
+            }
+        }
+    }
+    for dependency in &mut dependencies {
+        dependency.assignee = assignees.get(&dependency.key).cloned();
+    }
+    dependencies.sort_by(|first, second| {
+        (&first.project, &first.assignee).cmp(&(&second.project, &second.assignee))
+    });
+
+    let payload = json!({
+        "project": args.project,
+        "external_only": args.external_only,
+        "dependencies": dependencies
+            .iter()
+            .map(|dependency| json!({
+                "key": dependency.key,
+                "summary": dependency.summary,
+                "status": dependency.status,
+                "project": dependency.project,
+                "assignee": dependency.assignee,
+                "relation": dependency.relation,
+                "tickets": dependency.tickets,
+            }))
+            .collect::<Vec<_>>(),
+    });
+    let rows = dependencies
+        .iter()
+        .map(|dependency| {
+            vec![
+                dependency.project.clone(),
+                dependency
+                    .assignee
+                    .clone()
+                    .unwrap_or_else(|| "Unassigned".to_string()),
+                dependency.key.clone(),
+                dependency.status.clone(),
+                dependency.relation.clone(),
+                dependency.tickets.join(", "),
+            ]
+        })
+        .collect();
+    let table = TableOutput::new(
+        payload,
+        &[
+            "Project",
+            "Assignee",
+            "Issue",
+            "Status",
+            "Relation",
+            "Our tickets",
+        ],
+        rows,
+    )
+    .human(|| {
+        if dependencies.is_empty() {
+            println!("No open linked issues found for {}.", args.project);
+            return Ok(());
+        }
+        let mut current: Option<(&str, Option<&str>)> = None;
+        for dependency in &dependencies {
+            let project = dependency.project.as_str();
+            let assignee = dependency.assignee.as_deref();
+            if current.map(|(current_project, _)| current_project) != Some(project) {
+                let count = dependencies
+                    .iter()
+                    .filter(|other| other.project == project)
+                    .count();
+                if current.is_some() {
+                    println!();
+                }
+                println!("{} ({})", project.bold(), count);
+                current = None;
+            }
+            if current != Some((project, assignee)) {
+                println!("  {}", assignee.unwrap_or("Unassigned"));
+                current = Some((project, assignee));
+            }
+            println!(
+                "    {} [{}] {}",
+                dependency.key, dependency.status, dependency.summary
+            );
+            println!(
+                "      {} {} {}",
+                dependency.tickets.join(", "),
+                dependency.relation,
+                dependency.key
+            );
+        }
+        Ok(())
+    });
+    render_output(output, &table)
+}
+
+/// Progress of an export, saved after every page so an interrupted run can resume.
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportCheckpoint {
+    jql: String,
+    exported: usize,
+    #[serde(default)]
+    next_page_token: Option<String>,
+    /// Every page has been written; only attachments and the final file remain.
+    #[serde(default)]
+    finished: bool,
+}
+
+/// The attachments of a raw issue, reduced to what a migration needs to fetch them.
+fn attachment_manifest(issue: &Value) -> Vec<Value> {
+    issue["fields"]["attachment"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|attachment| {
+            json!({
+                "id": attachment["id"],
+                "filename": attachment["filename"],
+                "size": attachment["size"],
+                "mime_type": attachment["mimeType"],
+                "created": attachment["created"],
+                "author": attachment["author"]["displayName"],
+                "url": attachment["content"],
+            })
+        })
+        .collect()
+}
+
+fn export_paths(out: &Path) -> (PathBuf, PathBuf) {
+    let with_suffix = |suffix: &str| {
+        let mut name = out.as_os_str().to_owned();
+        name.push(suffix);
+        PathBuf::from(name)
+    };
+    (with_suffix(".partial"), with_suffix(".checkpoint"))
+}
+
+fn run_export_command(
+    client: &Client,
+    base_url: &str,
+    args: &ExportArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let (partial_path, checkpoint_path) = export_paths(&args.out);
+    let mut checkpoint = match fs::read_to_string(&checkpoint_path) {
+        Ok(contents) => {
+            let checkpoint: ExportCheckpoint = serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse {}", checkpoint_path.display()))?;
+            if checkpoint.jql != args.jql {
+                return Err(JitError::validation(format!(
+                    "{} belongs to an export of a different query. Delete it to start over.",
+                    checkpoint_path.display()
+                ))
+                .into());
+            }
+            eprintln!("Resuming export after {} issue(s)", checkpoint.exported);
+            checkpoint
+        }
+        Err(_) => {
+            fs::write(&partial_path, "")
+                .with_context(|| format!("Failed to write {}", partial_path.display()))?;
+            ExportCheckpoint {
+                jql: args.jql.clone(),
+                exported: 0,
+                next_page_token: None,
+                finished: false,
+            }
+        }
+    };
+
+    let mut partial = fs::OpenOptions::new()
+        .append(true)
+        .open(&partial_path)
+        .with_context(|| format!("Failed to open {}", partial_path.display()))?;
+    while !checkpoint.finished {
+        let (issues, next_page_token, more) = search_raw_page(
+            client,
+            base_url,
+            &checkpoint.jql,
+            checkpoint.next_page_token.as_deref(),
+            checkpoint.exported,
+            args.page_size.clamp(1, 100),
+        )?;
+        let mut lines = String::new();
+        for issue in &issues {
+            let key = issue["key"].as_str().unwrap_or_default();
+            let mut record = json!({ "key": key, "issue": issue });
+            if args.with_comments {
+                record["comments"] = json!(fetch_all_comment_values(client, base_url, key)?);
+            }
+            if args.with_attachments_manifest {
+                record["attachments"] = json!(attachment_manifest(issue));
+            }
+            lines.push_str(&serde_json::to_string(&record)?);
+            lines.push('\n');
+        }
+        // The page is written before the checkpoint moves past it, so a crash
+        // at worst repeats one page.
+        partial
+            .write_all(lines.as_bytes())
+            .with_context(|| format!("Failed to write {}", partial_path.display()))?;
+        checkpoint.exported += issues.len();
+        checkpoint.next_page_token = next_page_token;
+        checkpoint.finished = !more;
+        fs::write(&checkpoint_path, serde_json::to_string(&checkpoint)?)
+            .with_context(|| format!("Failed to write {}", checkpoint_path.display()))?;
+        eprintln!("Exported {} issue(s)", checkpoint.exported);
+    }
+    drop(partial);
+
+    let read_records = || -> Result<Vec<Value>> {
+        fs::read_to_string(&partial_path)
+            .with_context(|| format!("Failed to read {}", partial_path.display()))?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<Vec<Value>>>()
+            .with_context(|| format!("Failed to parse {}", partial_path.display()))
+    };
+
+    // Attachments are fetched before the export is finalized, so a failed
+    // download leaves the checkpoint in place for the rerun.
+    let downloads = match &args.download_attachments {
+        Some(dir) => {
+            let attachments: Vec<attachments::Attachment> = read_records()?
+                .iter()
+                .flat_map(|record| attachments_to_download(&record["issue"]))
+                .collect();
+            Some(attachments::download_all(
+                client,
+                dir,
+                &attachments,
+                args.download_concurrency,
+            )?)
+        }
+        None => None,
+    };
+
+    match args.format {
+        ExportFormat::Ndjson => fs::rename(&partial_path, &args.out)
+            .with_context(|| format!("Failed to write {}", args.out.display()))?,
+        ExportFormat::Json => {
+            let records = read_records()?;
+            fs::write(&args.out, serde_json::to_string_pretty(&records)?)
+                .with_context(|| format!("Failed to write {}", args.out.display()))?;
+            fs::remove_file(&partial_path)
+                .with_context(|| format!("Failed to remove {}", partial_path.display()))?;
+        }
+    }
+    fs::remove_file(&checkpoint_path)
+        .with_context(|| format!("Failed to remove {}", checkpoint_path.display()))?;
+
+    let file = args.out.display().to_string();
+    let mut payload = json!({
+        "file": file,
+        "issues": checkpoint.exported,
+    });
+    let mut line = format!("Exported {} issue(s) to {}", checkpoint.exported, file);
+    if let (Some(dir), Some(downloads)) = (&args.download_attachments, &downloads) {
+        payload["attachments"] = json!({
+            "dir": dir.display().to_string(),
+            "downloaded": downloads.downloaded,
+            "skipped": downloads.skipped,
+        });
+        line.push_str(&format!(
+            "; downloaded {} attachment(s) to {} ({} already present)",
+            downloads.downloaded,
+            dir.display(),
+            downloads.skipped
+        ));
+    }
+    let mut record = RecordOutput::new(payload)
+        .field("File", &file)
+        .field("Issues", checkpoint.exported.to_string());
+    if let (Some(dir), Some(downloads)) = (&args.download_attachments, &downloads) {
+        record = record.field(
+            "Attachments",
+            format!(
+                "{} downloaded, {} already present in {}",
+                downloads.downloaded,
+                downloads.skipped,
+                dir.display()
+            ),
+        );
+    }
+    render_output(output, &record.line(line))
+}
+
+/// The attachments of a raw issue in the form the downloader takes.
+fn attachments_to_download(issue: &Value) -> Vec<attachments::Attachment> {
+    let key = issue["key"].as_str().unwrap_or_default();
+    attachment_manifest(issue)
+        .into_iter()
+        .filter_map(|attachment| {
+            Some(attachments::Attachment {
+                id: attachment["id"].as_str()?.to_string(),
+                issue: key.to_string(),
+                filename: attachment["filename"].as_str()?.to_string(),
+                size: attachment["size"].as_u64(),
+                url: attachment["url"].as_str()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn run_branch_command(
+    jira: &impl JiraApi,
+    config: &AppConfig,
+    args: &BranchArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let ticket_id = extract_ticket_id(&args.ticket)?;
+    let issue = jira.issue_with_fields(&ticket_id, &["summary", "issuetype"])?;
+    let branch = configured_branch_name(&config.git, &issue)?;
+    if args.print {
+        println!("{}", branch);
+        return Ok(());
+    }
+
+    let created = checkout_git_branch(&branch, args.from.as_deref())?;
+    let record = RecordOutput::new(json!({
+        "ticket": issue.key,
+        "branch": branch,
+        "created": created,
+    }))
+    .field("Branch", &branch)
+    .field("Ticket", &issue.key)
+    .field(
+        "Created",
+        if created { "yes" } else { "no, switched to it" },
+    )
+    .line(branch.clone());
+    render_output(output, &record)
+}
+
+/// A local branch named after a ticket.
+struct TicketBranch {
+    name: String,
+    ticket: String,
+    /// The ticket's status, or `None` when Jira does not know the key.
+    status: Option<String>,
+    done: bool,
+    deleted: bool,
+}
+
+/// `jit branches`: the local ticket branches and their tickets' status. With
+/// `--stale`, only the branches of done tickets, which it offers to delete.
+fn run_branches_command(
+    jira: &impl JiraApi,
+    args: &BranchesArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let current = current_git_branch().ok();
+    let mut issues: HashMap<String, Option<JiraIssue>> = HashMap::new();
+    let mut branches = Vec::new();
+    for name in local_git_branches()? {
+        let Some(ticket) = find_ticket_keys(&name).into_iter().next() else {
+            continue;
+        };
+        if !issues.contains_key(&ticket) {
+            let issue = match jira.issue_with_fields(&ticket, &["summary", "status"]) {
+                Ok(issue) => Some(issue),
+                Err(err) if matches!(error::find(&err), Some(JitError::NotFound(_))) => None,
+                Err(err) => return Err(err),
+            };
+            issues.insert(ticket.clone(), issue);
+        }
+        let status = issues[&ticket]
+            .as_ref()
+            .and_then(|issue| issue.fields.status.as_ref());
+        branches.push(TicketBranch {
+            status: status.map(|status| status.name.clone()),
+            done: status
+                .and_then(|status| status.status_category.as_ref())
+                .is_some_and(|category| category.key == "done"),
+            deleted: false,
+            ticket,
+            name,
+        });
+    }
+    if !args.stale {
+        return render_branches(&branches, false, output);
+    }
+
+    // The checked-out branch cannot be deleted, so it is never stale.
+    branches.retain(|branch| branch.done && current.as_deref() != Some(&branch.name));
+    prune(
+        &mut branches,
+        args.yes,
+        output,
+        &PruneWording {
+            items: "branch(es)",
+            verb: "Delete",
+            done: "Deleted",
+            hint: "pass --force to delete unmerged branches",
+        },
+        |branches| render_branches(branches, true, output),
+        |branch| branch.name.clone(),
+        |branch| {
+            delete_git_branch(&branch.name, args.force)?;
+            branch.deleted = true;
+            Ok(())
+        },
+    )
+}
+
+/// How `prune` words what it removes in its prompt and report.
+struct PruneWording {
+    items: &'static str,
+    verb: &'static str,
+    done: &'static str,
+    hint: &'static str,
+}
+
+/// Lists `stale` and asks before removing each one, or removes them straight
+/// away with `--yes`. JSON output never waits for an answer; it removes only
+/// with `--yes`. A removal that fails is reported, and the rest still run.
+fn prune<T>(
+    stale: &mut [T],
+    yes: bool,
+    output: OutputFormat,
+    wording: &PruneWording,
+    render: impl Fn(&[T]) -> Result<()>,
+    name: impl Fn(&T) -> String,
+    mut remove: impl FnMut(&mut T) -> Result<()>,
+) -> Result<()> {
+    let asks = !yes && output != OutputFormat::Json && !stale.is_empty();
+    if asks {
+        render(stale)?;
+        println!();
+        let question = format!(
+            "{} {} {}? [y/N]: ",
+            wording.verb,
+            stale.len(),
+            wording.items
+        );
+        if !prompt_yes_no(&question)? {
+            return Ok(());
+        }
+    }
+    let mut removed = Vec::new();
+    let mut failures = Vec::new();
+    if yes || asks {
+        for item in stale.iter_mut() {
+            match remove(item) {
+                Ok(()) => removed.push(name(item)),
+                Err(err) => failures.push(format!("{}: {:#}", name(item), err)),
+            }
+        }
+    }
+    if asks {
+        for name in &removed {
+            println!("{} {}", wording.done, name);
+        }
+    } else {
+        render(stale)?;
+    }
+
+    if !failures.is_empty() {
+        for failure in &failures {
+            eprintln!("{} {}", "Kept".yellow(), failure);
+        }
+        return Err(anyhow!(
+            "{} {} could not be {}; {}",
+            failures.len(),
+            wording.items,
+            wording.done.to_lowercase(),
+            wording.hint
+        ));
+    }
+    Ok(())
+}
+
+fn render_branches(branches: &[TicketBranch], stale: bool, output: OutputFormat) -> Result<()> {
+    let payload = Value::Array(
+        branches
+            .iter()
+            .map(|branch| {
+                json!({
+                    "branch": branch.name,
+                    "ticket": branch.ticket,
+                    "status": branch.status,
+                    "done": branch.done,
+                    "deleted": branch.deleted,
+                })
+            })
+            .collect(),
+    );
+    let rows = branches
+        .iter()
+        .map(|branch| {
+            vec![
+                branch.name.clone(),
+                branch.ticket.clone(),
+                branch
+                    .status
+                    .clone()
+                    .unwrap_or_else(|| "Not found".to_string()),
+            ]
+        })
+        .collect();
+    let table = TableOutput::new(payload, &["Branch", "Ticket", "Status"], rows).human(|| {
+        if branches.is_empty() {
+            if stale {
+                println!("No branches of done tickets.");
+            } else {
+                println!("No local branches named after a ticket.");
+            }
+            return Ok(());
+        }
+        for branch in branches {
+            let status = branch.status.as_deref().unwrap_or("Not found");
+            println!(
+                "{}  {}  {}{}",
+                branch.name.bold(),
+                branch.ticket,
+                if branch.done {
+                    status.green()
+                } else {
+                    status.normal()
+                },
+                if branch.deleted { "  (deleted)" } else { "" }
+            );
+        }
+        Ok(())
+    });
+    render_output(output, &table)
+}
+
+const DEFAULT_WORKTREE_TEMPLATE: &str = "../{repo}-{key}";
+
+/// Where `jit worktree` checks out `branch` for `key`: `[git] worktree_template`
+/// under the main worktree `root`.
+fn worktree_path(config: &GitConfig, root: &Path, key: &str, branch: &str) -> Result<PathBuf> {
+    let template = config
+        .worktree_template
+        .as_deref()
+        .unwrap_or(DEFAULT_WORKTREE_TEMPLATE);
+    let repo = root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let vars = [
+        ("repo".to_string(), repo),
+        ("key".to_string(), key.to_ascii_lowercase()),
+        ("KEY".to_string(), key.to_string()),
+        ("branch".to_string(), branch.to_string()),
+    ];
+    let path = render_snippet("git.worktree_template", template, &vars)?;
+    // Resolve `..` so the default sibling directory reads as a plain path.
+    let mut resolved = PathBuf::new();
+    for component in root.join(path).components() {
+        match component {
+            std::path::Component::ParentDir => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+    Ok(resolved)
+}
+
+/// `jit worktree`: checks the ticket's branch out in a worktree of its own,
+/// or reports the one it already has.
+fn run_worktree_command(
+    jira: &impl JiraApi,
+    config: &AppConfig,
+    args: &WorktreeArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    let issue = jira.issue_with_fields(&ticket_id, &["summary", "issuetype"])?;
+    let branch = configured_branch_name(&config.git, &issue)?;
+    let worktrees = git_worktrees()?;
+    let root = worktrees
+        .first()
+        .map(|worktree| worktree.path.clone())
+        .ok_or_else(|| anyhow!("Not inside a git repository."))?;
+    let existing = worktrees
+        .iter()
+        .find(|worktree| worktree.branch.as_deref() == Some(branch.as_str()));
+    let path = match existing {
+        Some(worktree) => worktree.path.clone(),
+        None => worktree_path(&config.git, &root, &issue.key, &branch)?,
+    };
+    if args.print {
+        println!("{}", path.display());
+        return Ok(());
+    }
+
+    let (added, created) = match existing {
+        Some(_) => (false, false),
+        None => (
+            true,
+            add_git_worktree(&path, &branch, args.from.as_deref())?,
+        ),
+    };
+    let record = RecordOutput::new(json!({
+        "ticket": issue.key,
+        "branch": branch,
+        "path": path,
+        "added": added,
+        "created_branch": created,
+    }))
+    .field("Worktree", path.display())
+    .field("Branch", &branch)
+    .field("Ticket", &issue.key)
+    .field(
+        "Added",
+        if added {
+            "yes"
+        } else {
+            "no, it already exists"
+        },
+    )
+    .line(path.display().to_string());
+    render_output(output, &record)
+}
+
+/// `jit worktree --prune`: removes the worktrees whose branches belong to
+/// done tickets, after asking.
+fn run_worktree_prune_command(
+    jira: &impl JiraApi,
+    args: &WorktreeArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let current = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()));
+    let mut stale = Vec::new();
+    // The main worktree holds the repository, so it is never removed.
+    for worktree in git_worktrees()?.into_iter().skip(1) {
+        if current.as_ref() == Some(&worktree.path) {
+            continue;
+        }
+        let Some(branch) = worktree.branch.as_deref() else {
+            continue;
+        };
+        let Some(ticket) = find_ticket_keys(branch).into_iter().next() else {
+            continue;
+        };
+        let issue = match jira.issue_with_fields(&ticket, &["summary", "status"]) {
+            Ok(issue) => issue,
+            Err(err) if matches!(error::find(&err), Some(JitError::NotFound(_))) => continue,
+            Err(err) => return Err(err),
+        };
+        let status = issue.fields.status.as_ref();
+        if status
+            .and_then(|status| status.status_category.as_ref())
+            .is_some_and(|category| category.key == "done")
+        {
+            let status = status.map_or("Unknown", |status| &status.name).to_string();
+            stale.push((worktree, ticket, status, false));
+        }
+    }
+
+    let render = |stale: &[(GitWorktree, String, String, bool)]| {
+        let payload = Value::Array(
+            stale
+                .iter()
+                .map(|(worktree, ticket, status, removed)| {
+                    json!({
+                        "path": worktree.path,
+                        "branch": worktree.branch,
+                        "ticket": ticket,
+                        "status": status,
+                        "removed": removed,
+                    })
+                })
+                .collect(),
+        );
+        let rows = stale
+            .iter()
+            .map(|(worktree, ticket, status, _)| {
+                vec![
+                    worktree.path.display().to_string(),
+                    ticket.clone(),
+                    status.clone(),
+                ]
+            })
+            .collect();
+        let table =
+            TableOutput::new(payload, &["Worktree", "Ticket", "Status"], rows).human(|| {
+                if stale.is_empty() {
+                    println!("No worktrees of done tickets.");
+                }
+                for (worktree, ticket, status, removed) in stale {
+                    println!(
+                        "{}  {}  {}{}",
+                        worktree.path.display().to_string().bold(),
+                        ticket,
+                        status.green(),
+                        if *removed { "  (removed)" } else { "" }
+                    );
+                }
+                Ok(())
+            });
+        render_output(output, &table)
+    };
+
+    prune(
+        &mut stale,
+        args.yes,
+        output,
+        &PruneWording {
+            items: "worktree(s)",
+            verb: "Remove",
+            done: "Removed",
+            hint: "pass --force to remove ones with changes",
+        },
+        render,
+        |(worktree, ..)| worktree.path.display().to_string(),
+        |(worktree, _, _, removed)| {
+            remove_git_worktree(&worktree.path, args.force)?;
+            *removed = true;
+            Ok(())
+        },
+    )
+}
+
+fn run_open_command(base_url: &str, args: &OpenArgs) -> Result<()> {
+    let ticket = resolve_ticket(args.ticket.as_deref())?;
+    let url = format!("{}/browse/{}", base_url, ticket);
+    if args.print {
+        println!("{}", url);
+        return Ok(());
+    }
+    open_url(&url).with_context(|| format!("Failed to open {}", url))?;
+    println!("Opened {}", url);
+    Ok(())
+}
+
+fn run_heatmap_command(
+    jira: &impl JiraApi,
+    args: &HeatmapArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let jql = format!(
+        "project = \"{}\" AND statusCategory != Done ORDER BY created ASC",
+        args.project
+    );
+    let issues = jira.search(&jql, &["summary", "status", "created"], args.limit as usize)?;
+    let heatmap = build_age_heatmap(&issues, now_utc());
+
+    let payload = json!({
+        "project": args.project,
+        "issues": issues.len(),
+        "buckets": AGE_BUCKETS.iter().map(|(label, _)| label).collect::<Vec<_>>(),
+        "statuses": heatmap.rows.iter().map(|(status, counts)| json!({
+            "status": status,
+            "counts": counts,
+        })).collect::<Vec<_>>(),
+        "oldest": heatmap.oldest.iter().map(|(key, summary, age_days)| json!({
+            "key": key,
+            "summary": summary,
+            "ageDays": age_days,
+        })).collect::<Vec<_>>(),
+    });
+    let headers: Vec<&str> = std::iter::once("Status")
+        .chain(AGE_BUCKETS.iter().map(|(label, _)| *label))
+        .collect();
+    let rows = heatmap
+        .rows
+        .iter()
+        .map(|(status, counts)| {
+            std::iter::once(status.clone())
+                .chain(counts.iter().map(|count| count.to_string()))
+                .collect()
+        })
+        .collect();
+    let table = TableOutput::new(payload, &headers, rows)
+        .human(|| print_age_heatmap(&args.project, issues.len(), &heatmap));
+    render_output(output, &table)
+}
+
+fn print_age_heatmap(project: &str, issue_count: usize, heatmap: &AgeHeatmap) -> Result<()> {
+    if heatmap.rows.is_empty() {
+        println!("No open issues found in {}.", project);
+        return Ok(());
+    }
+
+    let status_width = heatmap
+        .rows
+        .iter()
+        .map(|(status, _)| UnicodeWidthStr::width(status.as_str()))
+        .max()
+        .unwrap_or(0)
+        .max("Status".len());
+    let max_count = heatmap
+        .rows
+        .iter()
+        .flat_map(|(_, counts)| counts.iter().copied())
+        .max()
+        .unwrap_or(0);
+
+    println!(
+        "{}",
+        format!(
+            "Open issue age heatmap for {} ({} issues)",
+            project, issue_count
+        )
+        .bold()
+    );
+    println!();
+    print!("{:<width$}", "Status", width = status_width);
+    for (label, _) in AGE_BUCKETS {
+        print!(" {:>6}", label);
+    }
+    println!();
+
+    for (status, counts) in &heatmap.rows {
+        print!(
+            "{}{}",
+            status,
+            " ".repeat(status_width - UnicodeWidthStr::width(status.as_str()))
+        );
+        for (index, count) in counts.iter().enumerate() {
+            print!(" {}", heatmap_cell(*count, max_count, index));
+        }
+        println!();
+    }
+
+    if !heatmap.oldest.is_empty() {
+        println!();
+        println!("{}", "Oldest open issues".bold());
+        for (key, summary, age_days) in &heatmap.oldest {
+            println!(
+                "  {}  {} ({} days old)",
+                key.red(),
+                truncate_with_ellipsis(summary, 60),
+                age_days
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Formats a heatmap count, shading by its share of the busiest cell and
+/// highlighting ancient columns.
+fn heatmap_cell(count: usize, max_count: usize, bucket_index: usize) -> ColoredString {
+    let text = if count == 0 {
+        format!("{:>6}", "·")
+    } else {
+        format!("{:>6}", count)
+    };
+    if count == 0 {
+        return text.dimmed();
+    }
+    if AGE_BUCKETS[bucket_index].1 > ANCIENT_AGE_DAYS {
+        return text.red().bold();
+    }
+    if count * 2 >= max_count {
+        text.yellow().bold()
+    } else {
+        text.normal()
+    }
+}
+
+/// Prompts for a line of input, returning `None` once stdin is closed.
+#[derive(Debug)]
+struct TrainTicket {
+    key: String,
+    summary: String,
+    status: String,
+    done: bool,
+    pull_requests: Vec<JiraPullRequest>,
+}
+
+impl TrainTicket {
+    /// Done in Jira with no pull request still open.
+    fn is_ready(&self) -> bool {
+        self.done
+            && !self.pull_requests.iter().any(|pull_request| {
+                pull_request
+                    .status
+                    .as_deref()
+                    .is_some_and(|status| status.eq_ignore_ascii_case("open"))
+            })
+    }
+
+    fn pull_request_summary(&self) -> String {
+        if self.pull_requests.is_empty() {
+            return "no PRs".to_string();
+        }
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for pull_request in &self.pull_requests {
+            let status = pull_request
+                .status
+                .as_deref()
+                .unwrap_or("unknown")
+                .to_ascii_lowercase();
+            *counts.entry(status).or_default() += 1;
+        }
+        counts
+            .iter()
+            .map(|(status, count)| format!("{} {}", count, status))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Reads `org/repo` from a GitHub or Bitbucket pull request URL.
+fn pull_request_repository(url: &str) -> Option<String> {
+    let path = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let mut segments = path
+        .split('/')
+        .skip(1)
+        .filter(|segment| !segment.is_empty());
+    let owner = segments.next()?;
+    let repo = segments.next()?;
+    Some(format!("{}/{}", owner, repo))
+}
+
+/// Groups release tickets by component or PR repository; a ticket appears in
+/// every group it belongs to.
+fn build_release_train(
+    issues: &[JiraIssue],
+    pull_requests: &HashMap<String, Vec<JiraPullRequest>>,
+    grouping: TrainGrouping,
+) -> BTreeMap<String, Vec<TrainTicket>> {
+    let mut groups: BTreeMap<String, Vec<TrainTicket>> = BTreeMap::new();
+    for issue in issues {
+        let prs = pull_requests.get(&issue.key).cloned().unwrap_or_default();
+        let mut names: Vec<String> = match grouping {
+            TrainGrouping::Component => issue
+                .fields
+                .extra
+                .get("components")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|component| component["name"].as_str())
+                .map(str::to_string)
+                .collect(),
+            TrainGrouping::Repo => prs
+                .iter()
+                .filter_map(|pull_request| pull_request.url.as_deref())
+                .filter_map(pull_request_repository)
+                .collect(),
+        };
+        names.sort_unstable();
+        names.dedup();
+        if names.is_empty() {
+            names.push(match grouping {
+                TrainGrouping::Component => "No component".to_string(),
+                TrainGrouping::Repo => "No pull requests".to_string(),
+            });
+        }
+
+        let status = issue.fields.status.as_ref();
+        for name in names {
+            groups.entry(name).or_default().push(TrainTicket {
+                key: issue.key.clone(),
+                summary: issue.fields.summary.clone(),
+                status: status.map_or("Unknown", |status| &status.name).to_string(),
+                done: status
+                    .and_then(|status| status.status_category.as_ref())
+                    .is_some_and(|category| category.key == "done"),
+                pull_requests: prs.clone(),
+            });
+        }
+    }
+    groups
+}
+
+fn run_train_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: &TrainArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let mut jql = format!("fixVersion = \"{}\"", args.fix_version.replace('"', "\\\""));
+    if let Some(project) = args.project.as_deref() {
+        jql = format!("project = \"{}\" AND {}", project, jql);
+    }
+    jql.push_str(" ORDER BY key ASC");
+    let jql = with_assignee_filter(jira.http(), config, &jql, &args.assignees)?;
+    let issues = jira.search(
+        &jql,
+        &["summary", "status", "components"],
+        args.limit as usize,
+    )?;
+    let pull_requests = fetch_pull_requests_for_tickets(
+        jira.http(),
+        base_url,
+        &issues,
+        config.bitbucket.is_some(),
+    )?;
+    let groups = build_release_train(&issues, &pull_requests, args.group_by);
+
+    let ready_keys: BTreeSet<&str> = groups
+        .values()
+        .flatten()
+        .filter(|ticket| ticket.is_ready())
+        .map(|ticket| ticket.key.as_str())
+        .collect();
+    let ready = ready_keys.len() == issues.len();
+
+    let payload = json!({
+        "fix_version": args.fix_version,
+        "ready": ready,
+        "tickets": issues.len(),
+        "ready_tickets": ready_keys.len(),
+        "groups": groups.iter().map(|(name, tickets)| json!({
+            "name": name,
+            "tickets": tickets.iter().map(|ticket| json!({
+                "ticket": ticket.key,
+                "summary": ticket.summary,
+                "status": ticket.status,
+                "ready": ticket.is_ready(),
+                "pull_requests": ticket.pull_requests.iter().map(|pull_request| json!({
+                    "name": pull_request.name,
+                    "status": pull_request.status,
+                    "url": pull_request.url,
+                })).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+    });
+    let rows = groups
+        .iter()
+        .flat_map(|(name, tickets)| {
+            tickets.iter().map(move |ticket| {
+                vec![
+                    name.clone(),
+                    ticket.key.clone(),
+                    ticket.status.clone(),
+                    ticket.pull_request_summary(),
+                    ticket.is_ready().to_string(),
+                ]
+            })
+        })
+        .collect();
+    let table = TableOutput::new(
+        payload,
+        &["Group", "Ticket", "Status", "Pull requests", "Ready"],
+        rows,
+    )
+    .human(|| {
+        println!(
+            "{} {}: {}/{} tickets ready",
+            "Release".bold(),
+            args.fix_version.bold(),
+            ready_keys.len(),
+            issues.len()
+        );
+        for (name, tickets) in &groups {
+            println!();
+            println!("{} ({})", name.bold(), tickets.len());
+            for ticket in tickets {
+                let mark = if ticket.is_ready() {
+                    "✓".bright_green()
+                } else {
+                    "✗".bright_red()
+                };
+                println!(
+                    "  {} {:<10} {:<14} {:<20} {}",
+                    mark,
+                    ticket.key,
+                    ticket.status,
+                    ticket.pull_request_summary(),
+                    ticket.summary
+                );
+            }
+        }
+        println!();
+        if issues.is_empty() {
+            println!("No tickets found for fix version {}.", args.fix_version);
+        } else if ready {
+            println!("{}", "Ready to cut the release.".bright_green().bold());
+        } else {
+            println!(
+                "{}",
+                format!(
+                    "Not ready: {} ticket(s) still open or waiting on pull requests.",
+                    issues.len() - ready_keys.len()
+                )
+                .bright_red()
+                .bold()
+            );
+        }
+        Ok(())
+    });
+    render_output(output, &table)
+}
+
+/// Builds the issue update that records `env` in the configured environment field.
+fn environment_field_update(field: &str, kind: EnvironmentFieldType, env: &str) -> Value {
+    match kind {
+        EnvironmentFieldType::Text => json!({ "fields": { field: env } }),
+        EnvironmentFieldType::Select => json!({ "fields": { field: { "value": env } } }),
+        EnvironmentFieldType::MultiSelect => {
+            json!({ "update": { field: [{ "add": { "value": env } }] } })
+        }
+    }
+}
+
+fn run_deployed_command(
+    jira: &JiraClient,
+    config: &AppConfig,
+    args: &DeployedArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let mut tickets = args
+        .tickets
+        .iter()
+        .map(|ticket| extract_ticket_id(ticket))
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(version) = args.fix_version.as_deref() {
+        let jql = format!(
+            "fixVersion = \"{}\" ORDER BY key ASC",
+            version.replace('"', "\\\"")
+        );
+        for issue in jira.search(&jql, &["summary"], args.limit as usize)? {
+            if !tickets.contains(&issue.key) {
+                tickets.push(issue.key);
+            }
+        }
+    }
+    if tickets.is_empty() {
+        return Err(JitError::validation("No tickets to annotate").into());
+    }
+
+    let template = config.deploy.note.as_deref().unwrap_or(DEFAULT_DEPLOY_NOTE);
+    let version = args.fix_version.as_deref().unwrap_or("this release");
+    let field = config.deploy.environment_field.as_deref();
+
+    // (ticket, result)
+    let mut results: Vec<(String, String)> = Vec::new();
+    for ticket in &tickets {
+        let vars = [
+            ("env".to_string(), args.env.clone()),
+            ("version".to_string(), version.to_string()),
+            ("ticket".to_string(), ticket.clone()),
+        ];
+        let note = render_snippet("deploy.note", template, &vars)?;
+        let result = if args.dry_run {
+            "dry-run".to_string()
+        } else {
+            let annotate = || -> Result<()> {
+                jira.add_comment(ticket, &text_to_adf(&note))?;
+                if let Some(field) = field {
+                    let payload = environment_field_update(
+                        field,
+                        config.deploy.environment_field_type,
+                        &args.env,
+                    );
+                    jira.update_issue(ticket, &payload)?;
+                }
+                Ok(())
+            };
+            match annotate() {
+                Ok(()) => "annotated".to_string(),
+                Err(err) => format!("failed: {:#}", err),
+            }
+        };
+        results.push((ticket.clone(), result));
+    }
+
+    let failed = results
+        .iter()
+        .filter(|(_, result)| result.starts_with("failed"))
+        .count();
+    let payload = json!({
+        "env": args.env,
+        "fix_version": args.fix_version,
+        "field": field,
+        "tickets": results.iter().map(|(ticket, result)| json!({
+            "ticket": ticket,
+            "result": result,
+        })).collect::<Vec<_>>(),
+    });
+    let rows = results
+        .iter()
+        .map(|(ticket, result)| vec![ticket.clone(), args.env.clone(), result.clone()])
+        .collect();
+    let table = TableOutput::new(payload, &["Ticket", "Environment", "Result"], rows).human(|| {
+        for (ticket, result) in &results {
+            println!("{}: deployed to {} ({})", ticket, args.env, result);
+        }
+        Ok(())
+    });
+    render_output(output, &table)?;
+
+    if failed > 0 {
+        return Err(anyhow!(
+            "{} of {} ticket(s) could not be annotated",
+            failed,
+            results.len()
+        ));
+    }
+    Ok(())
+}
+
+fn build_webhook_payload(tickets: &[String], data: &[(String, String)]) -> Value {
+    let mut payload = json!({});
+    if !tickets.is_empty() {
+        payload["issues"] = json!(tickets);
+    }
+    if !data.is_empty() {
+        let data: serde_json::Map<String, Value> = data
+            .iter()
+            .map(|(key, value)| (key.clone(), json!(value)))
+            .collect();
+        payload["data"] = Value::Object(data);
+    }
+    payload
+}
+
+fn run_trigger_command(config: &AppConfig, args: &TriggerArgs, output: OutputFormat) -> Result<()> {
+    let webhook = config.webhooks.get(&args.webhook).ok_or_else(|| {
+        let mut names: Vec<&str> = config.webhooks.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        JitError::Config(if names.is_empty() {
+            format!(
+                "Unknown webhook '{}'. Add it to the [webhooks] section of your config.toml.",
+                args.webhook
+            )
+        } else {
+            format!(
+                "Unknown webhook '{}'. Available webhooks: {}",
+                args.webhook,
+                names.join(", ")
+            )
+        })
+    })?;
+    let tickets = args
+        .tickets
+        .iter()
+        .map(|ticket| extract_ticket_id(ticket))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    if let Some(token) = webhook.token.as_deref() {
+        headers.insert("X-Automation-Webhook-Token", HeaderValue::from_str(token)?);
+    }
+    let client = config
+        .http
+        .client_builder()
+        .default_headers(headers)
+        .build()?;
+    let payload = build_webhook_payload(&tickets, &args.data);
+    let response = http::send(
+        client.post(&webhook.url).json(&payload),
+        "Jira Automation webhook",
+    )?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("Jira Automation webhook", &response).into());
+    }
+
+    let line = if tickets.is_empty() {
+        format!("Triggered {}", args.webhook)
+    } else {
+        format!("Triggered {} for {}", args.webhook, tickets.join(", "))
+    };
+    let record = RecordOutput::new(json!({
+        "webhook": args.webhook,
+        "tickets": tickets,
+        "status": response.status().as_u16(),
+    }))
+    .field("Webhook", &args.webhook)
+    .field("Tickets", tickets.join(", "))
+    .field("Status", response.status().to_string())
+    .line(line.clone())
+    .human(|| {
+        println!("{}", line);
+        Ok(())
+    });
+    render_output(output, &record)
+}
+
+/// Last value each watch rule saw per ticket, keyed by rule name then ticket key.
+type WatchState = BTreeMap<String, BTreeMap<String, String>>;
+
+#[derive(Debug, PartialEq)]
+struct WatchFiring {
+    ticket: String,
+    summary: String,
+    value: String,
+    previous: Option<String>,
+}
+
+/// The `[layouts]` entry for the issue's type, matched case-insensitively.
+fn issue_layout<'a>(config: &'a AppConfig, issue: &JiraIssue) -> Option<&'a LayoutConfig> {
+    let issue_type = &issue.fields.issuetype.as_ref()?.name;
+    config
+        .layouts
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(issue_type))
+        .map(|(_, layout)| layout)
+}
+
+/// `layout` filled in from `issue`, whose fields must include the layout's.
+fn layout_fields(issue: &JiraIssue, layout: &LayoutConfig) -> Vec<LayoutField> {
+    layout
+        .fields
+        .iter()
+        .map(|field| {
+            let value = match field.as_str() {
+                "type" => issue_field_text(issue, "issuetype"),
+                "sprint" => issue_sprint_name(issue).unwrap_or_default(),
+                "created" | "updated" | "duedate" => {
+                    let date = issue_field_text(issue, field);
+                    if date.is_empty() {
+                        date
+                    } else {
+                        format_date(&date)
+                    }
+                }
+                _ => issue_field_text(issue, field),
+            };
+            let label = layout.labels.get(field).cloned().unwrap_or_else(|| {
+                LAYOUT_FIELDS
+                    .iter()
+                    .find(|(name, _)| name == field)
+                    .map_or_else(|| field.clone(), |(_, label)| label.to_string())
+            });
+            LayoutField {
+                field: field.clone(),
+                label,
+                value: (!value.is_empty()).then_some(value),
+            }
+        })
+        .collect()
+}
+
+/// Display text of an issue field, for the fields watch rules usually target.
+fn issue_field_text(issue: &JiraIssue, field: &str) -> String {
+    let fields = &issue.fields;
+    let name = |value: Option<&str>| value.unwrap_or_default().to_string();
+    match field {
+        "summary" => fields.summary.clone(),
+        "status" => name(fields.status.as_ref().map(|status| status.name.as_str())),
+        "priority" => name(
+            fields
+                .priority
+                .as_ref()
+                .map(|priority| priority.name.as_str()),
+        ),
+        "issuetype" => name(fields.issuetype.as_ref().map(|kind| kind.name.as_str())),
+        "assignee" => name(
+            fields
+                .assignee
+                .as_ref()
+                .map(|user| user.display_name.as_str()),
+        ),
+        "reporter" => name(
+            fields
+                .reporter
+                .as_ref()
+                .map(|user| user.display_name.as_str()),
+        ),
+        "created" => name(fields.created.as_deref()),
+        "updated" => name(fields.updated.as_deref()),
+        "duedate" => name(fields.due_date.as_deref()),
+        "labels" => fields.labels.join(", "),
+        _ => match fields.extra.get(field) {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(text)) => text.clone(),
+            // Rich text fields, such as steps to reproduce.
+            Some(value) if value["type"] == "doc" => adf_value_to_display_text(value),
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|item| {
+                    ["value", "name", "displayName"]
+                        .iter()
+                        .find_map(|key| item[*key].as_str())
+                        .map_or_else(|| scalar_json_text(item), str::to_string)
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            Some(value) => ["value", "name", "displayName"]
+                .iter()
+                .find_map(|key| value[*key].as_str())
+                .map_or_else(|| scalar_json_text(value), str::to_string),
+        },
+    }
+}
+
+fn scalar_json_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Compares the issues a rule currently matches with what it saw last time.
+/// The first check of a rule only records a baseline, so existing tickets do
+/// not all fire at once.
+fn evaluate_watch_rule(
+    rule: &WatchRule,
+    issues: &[JiraIssue],
+    previous: Option<&BTreeMap<String, String>>,
+) -> (Vec<WatchFiring>, BTreeMap<String, String>) {
+    let mut seen = BTreeMap::new();
+    let mut firings = Vec::new();
+    for issue in issues {
+        let value = issue_field_text(issue, &rule.field);
+        let before = previous.and_then(|previous| previous.get(&issue.key));
+        let changed = before != Some(&value);
+        let fires = previous.is_some()
+            && match rule.equals.as_deref() {
+                Some(target) => {
+                    let matches = |text: &str| text.eq_ignore_ascii_case(target.trim());
+                    matches(&value) && !before.is_some_and(|before| matches(before))
+                }
+                None => before.is_some() && changed,
+            };
+        if fires {
+            firings.push(WatchFiring {
+                ticket: issue.key.clone(),
+                summary: issue.fields.summary.clone(),
+                value: value.clone(),
+                previous: before.cloned(),
+            });
+        }
+        seen.insert(issue.key.clone(), value);
+    }
+    (firings, seen)
+}
+
+fn watch_state_path() -> Result<PathBuf> {
+    Ok(jit_data_dir()?.join("watch.json"))
+}
+
+fn load_watch_state(path: &Path) -> Result<WatchState> {
+    if !path.exists() {
+        return Ok(WatchState::new());
+    }
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn save_watch_state(path: &Path, state: &WatchState) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, serde_json::to_string_pretty(state)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn send_desktop_notification(title: &str, message: &str) -> Result<()> {
+    let status = if cfg!(target_os = "macos") {
+        let quote = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+        Command::new("osascript")
+            .arg("-e")
+            .arg(format!(
+                "display notification \"{}\" with title \"{}\"",
+                quote(message),
+                quote(title)
+            ))
+            .status()
+    } else {
+        Command::new("notify-send").arg(title).arg(message).status()
+    }
+    .context("failed to launch notification command")?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("notification command exited with {}", status))
+    }
+}
+
+fn run_watch_actions(rule: &WatchRule, firing: &WatchFiring) -> Result<()> {
+    let previous = firing.previous.clone().unwrap_or_default();
+    if let Some(template) = rule.notify.as_deref() {
+        let vars = [
+            ("ticket".to_string(), firing.ticket.clone()),
+            ("summary".to_string(), firing.summary.clone()),
+            ("field".to_string(), rule.field.clone()),
+            ("value".to_string(), firing.value.clone()),
+            ("previous".to_string(), previous.clone()),
+        ];
+        let message = render_snippet(&rule.name, template, &vars)?;
+        send_desktop_notification(&format!("jit: {}", rule.name), &message)?;
+    }
+    if let Some(command) = rule.run.as_deref() {
+        // Values go through the environment rather than the command line, so
+        // summaries cannot inject shell syntax.
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("JIT_WATCH", &rule.name)
+            .env("JIT_TICKET", &firing.ticket)
+            .env("JIT_SUMMARY", &firing.summary)
+            .env("JIT_FIELD", &rule.field)
+            .env("JIT_VALUE", &firing.value)
+            .env("JIT_PREVIOUS", &previous)
+            .status()
+            .with_context(|| format!("Failed to run '{}'", command))?;
+        if !status.success() {
+            return Err(anyhow!("'{}' exited with {}", command, status));
+        }
+    }
+    Ok(())
+}
+
+fn run_watch_pass(jira: &impl JiraApi, rules: &[WatchRule]) -> Result<()> {
+    let path = watch_state_path()?;
+    let mut state = load_watch_state(&path)?;
+    for rule in rules {
+        let fields = ["summary", rule.field.as_str()];
+        let issues = match jira.search(&rule.jql, &fields, 1000) {
+            Ok(issues) => issues,
+            Err(err) => {
+                eprintln!("{}: {:#}", rule.name, err);
+                continue;
+            }
+        };
+        let (firings, seen) = evaluate_watch_rule(rule, &issues, state.get(&rule.name));
+        for firing in &firings {
+            println!(
+                "{}: {} {} is now {}",
+                rule.name, firing.ticket, rule.field, firing.value
+            );
+            if let Err(err) = run_watch_actions(rule, firing) {
+                eprintln!("{}: {}: {:#}", rule.name, firing.ticket, err);
+            }
+        }
+        state.insert(rule.name.clone(), seen);
+    }
+    save_watch_state(&path, &state)
+}
+
+fn run_watch_command(jira: &impl JiraApi, config: &AppConfig, args: &WatchArgs) -> Result<()> {
+    if config.watch.is_empty() {
+        return Err(JitError::Config(
+            "No watch rules configured. Add [[watch]] entries with a name, field, and run or notify action to your config.toml.".to_string(),
+        )
+        .into());
+    }
+    if let Some(rule) = config
+        .watch
+        .iter()
+        .find(|rule| rule.run.is_none() && rule.notify.is_none())
+    {
+        return Err(JitError::Config(format!(
+            "Watch rule '{}' needs a run or notify action",
+            rule.name
+        ))
+        .into());
+    }
+
+    loop {
+        run_watch_pass(jira, &config.watch)?;
+        show_due_reminders();
+        if args.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(args.interval));
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SharedIssue {
+    ticket: String,
+    summary: String,
+    issue_type: Option<String>,
+    status: Option<String>,
+    priority: Option<String>,
+    assignee: Option<String>,
+    reporter: Option<String>,
+    description: Option<String>,
+    comments: Vec<SharedComment>,
+}
+
+#[derive(Debug, Serialize)]
+struct SharedComment {
+    author: Option<String>,
+    created: Option<String>,
+    body: String,
+}
+
+/// Replaces people with stable `Person N` placeholders and strips emails, the
+/// Jira instance URL, and configured patterns from shared text.
+struct Redactor {
+    people: Vec<String>,
+    patterns: Vec<(Regex, &'static str)>,
+}
+
+impl Redactor {
+    fn new(base_url: &str, extra_patterns: &[String]) -> Result<Self> {
+        let mut patterns = vec![
+            (
+                Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}")?,
+                "[email]",
+            ),
+            (
+                Regex::new(&regex::escape(base_url.trim_end_matches('/')))?,
+                "[jira]",
+            ),
+        ];
+        for pattern in extra_patterns {
+            let regex = Regex::new(pattern)
+                .with_context(|| format!("Invalid [share] redact pattern '{}'", pattern))?;
+            patterns.push((regex, "[redacted]"));
+        }
+        Ok(Self {
+            people: Vec::new(),
+            patterns,
+        })
+    }
+
+    /// Registers a name so later text mentioning it is redacted too.
+    fn person(&mut self, name: &str) -> String {
+        let position = match self.people.iter().position(|known| known == name) {
+            Some(position) => position,
+            None => {
+                self.people.push(name.to_string());
+                self.people.len() - 1
+            }
+        };
+        format!("Person {}", position + 1)
+    }
+
+    fn text(&self, text: &str) -> String {
+        let mut redacted = text.to_string();
+        for (pattern, replacement) in &self.patterns {
+            redacted = pattern.replace_all(&redacted, *replacement).into_owned();
+        }
+        // Longest names first, so "Ana Maria" is not left as "Person 2 Maria".
+        let mut people: Vec<(usize, &String)> = self.people.iter().enumerate().collect();
+        people.sort_by_key(|(_, name)| std::cmp::Reverse(name.len()));
+        for (index, name) in people {
+            redacted = redacted.replace(name.as_str(), &format!("Person {}", index + 1));
+        }
+        redacted
+    }
+}
+
+/// Collects the display names of users mentioned in an ADF document.
+fn collect_adf_mentions(node: &Value, names: &mut Vec<String>) {
+    if node["type"] == "mention" {
+        if let Some(text) = node["attrs"]["text"].as_str() {
+            names.push(text.trim_start_matches('@').to_string());
+        } else if let Some(name) = node["attrs"]["id"].as_str().and_then(people::display_name) {
+            names.push(name);
+        }
+    }
+    if let Some(content) = node["content"].as_array() {
+        for child in content {
+            collect_adf_mentions(child, names);
+        }
+    }
+}
+
+fn build_shared_issue(issue: &JiraIssue, mut redactor: Option<Redactor>) -> SharedIssue {
+    let fields = &issue.fields;
+    let comments: &[JiraComment] = fields
+        .comment
+        .as_ref()
+        .map_or(&[], |container| container.comments.as_slice());
+
+    // Register every person up front so names are numbered by role, then by
+    // first appearance, and so mentions inside text are caught as well.
+    let mut person = |user: Option<&JiraUser>| {
+        user.map(|user| match redactor.as_mut() {
+            Some(redactor) => redactor.person(&user.display_name),
+            None => user.display_name.clone(),
+        })
+    };
+    let assignee = person(fields.assignee.as_ref());
+    let reporter = person(fields.reporter.as_ref());
+    let authors: Vec<Option<String>> = comments
+        .iter()
+        .map(|comment| person(comment.author.as_ref()))
+        .collect();
+    if let Some(redactor) = redactor.as_mut() {
+        let mut mentions = Vec::new();
+        for body in fields
+            .description
+            .iter()
+            .chain(comments.iter().filter_map(|comment| comment.body.as_ref()))
+        {
+            collect_adf_mentions(body, &mut mentions);
+        }
+        for name in mentions {
+            redactor.person(&name);
+        }
+    }
+
+    let text = |value: String| match redactor.as_ref() {
+        Some(redactor) => redactor.text(&value),
+        None => value,
+    };
+    SharedIssue {
+        ticket: issue.key.clone(),
+        summary: text(fields.summary.clone()),
+        issue_type: fields.issuetype.as_ref().map(|kind| kind.name.clone()),
+        status: fields.status.as_ref().map(|status| status.name.clone()),
+        priority: fields
+            .priority
+            .as_ref()
+            .map(|priority| priority.name.clone()),
+        assignee,
+        reporter,
+        description: fields
+            .description
+            .as_ref()
+            .map(|description| text(adf_value_to_display_text(description))),
+        comments: comments
+            .iter()
+            .zip(authors)
+            .map(|(comment, author)| SharedComment {
+                author,
+                created: comment_created_date(comment).map(str::to_string),
+                body: text(
+                    comment
+                        .body
+                        .as_ref()
+                        .map(adf_value_to_display_text)
+                        .unwrap_or_default(),
+                ),
+            })
+            .collect(),
+    }
+}
+
+fn format_shared_issue_markdown(issue: &SharedIssue) -> String {
+    let mut markdown = format!("# {}: {}\n", issue.ticket, issue.summary);
+
+    let details: Vec<String> = [
+        ("Type", &issue.issue_type),
+        ("Status", &issue.status),
+        ("Priority", &issue.priority),
+        ("Assignee", &issue.assignee),
+        ("Reporter", &issue.reporter),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| {
+        value
+            .as_deref()
+            .map(|value| format!("- **{}:** {}", label, value))
+    })
+    .collect();
+    if !details.is_empty() {
+        markdown.push_str(&format!("\n{}\n", details.join("\n")));
+    }
+
+    if let Some(description) = issue.description.as_deref() {
+        markdown.push_str(&format!("\n## Description\n\n{}\n", description));
+    }
+
+    if !issue.comments.is_empty() {
+        markdown.push_str("\n## Comments\n");
+        for comment in &issue.comments {
+            let author = comment.author.as_deref().unwrap_or("Unknown");
+            match comment.created.as_deref() {
+                Some(created) => markdown.push_str(&format!("\n**{}** ({})\n\n", author, created)),
+                None => markdown.push_str(&format!("\n**{}**\n\n", author)),
+            }
+            markdown.push_str(&format!("{}\n", comment.body));
+        }
+    }
+
+    markdown
+}
+
+fn run_share_command(
+    jira: &impl JiraApi,
+    config: &AppConfig,
+    args: &ShareArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    let issue = jira.issue(&ticket_id, true, true, true)?;
+    let redactor = if args.redact {
+        Some(Redactor::new(base_url, &config.share.redact_patterns)?)
+    } else {
+        None
+    };
+    let shared = build_shared_issue(&issue, redactor);
+
+    match output {
+        OutputFormat::Table | OutputFormat::Text | OutputFormat::Markdown => {
+            print!("{}", format_shared_issue_markdown(&shared));
+            Ok(())
+        }
+        format => render_output(format, &RecordOutput::new(serde_json::to_value(&shared)?)),
+    }
+}
+
+fn parse_key_value(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got '{}'", value)),
+    }
+}
+
+/// Fills `{placeholder}`s in a snippet, failing when any are left without a value.
+fn render_snippet(name: &str, template: &str, vars: &[(String, String)]) -> Result<String> {
+    let placeholder = Regex::new(r"\{([A-Za-z0-9_-]+)\}")?;
+    let mut missing = BTreeSet::new();
+    let rendered = placeholder.replace_all(template, |captures: &regex::Captures| {
+        let key = &captures[1];
+        match vars.iter().rev().find(|(name, _)| name == key) {
+            Some((_, value)) => value.clone(),
+            None => {
+                missing.insert(key.to_string());
+                captures[0].to_string()
+            }
+        }
+    });
+    if !missing.is_empty() {
+        let flags: Vec<String> = missing
+            .iter()
+            .map(|key| format!("--var {}=...", key))
+            .collect();
+        return Err(
+            JitError::validation(format!("Snippet '{}' needs {}", name, flags.join(" "))).into(),
+        );
+    }
+    Ok(rendered.into_owned())
+}
+
+fn run_comment_command(
+    client: &Client,
+    config: &AppConfig,
+    args: &CommentArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let base_url = config.jira.base_url.as_str();
+    let (ticket, message) = split_ticket_argument(args.ticket.as_deref(), args.message.as_deref());
+    let ticket_id = resolve_ticket(ticket)?;
+    let body = match args.snippet.as_deref() {
+        Some(name) => {
+            let template = config.snippets.get(name).ok_or_else(|| {
+                let mut names: Vec<&str> = config.snippets.keys().map(String::as_str).collect();
+                names.sort_unstable();
+                JitError::Config(if names.is_empty() {
+                    format!(
+                        "Unknown snippet '{}'. Add it to the [snippets] section of your config.toml.",
+                        name
+                    )
+                } else {
+                    format!(
+                        "Unknown snippet '{}'. Available snippets: {}",
+                        name,
+                        names.join(", ")
+                    )
+                })
+            })?;
+            render_snippet(name, template, &args.vars)?
+        }
+        None => match (args.file.as_deref(), message) {
+            (Some(path), _) => fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?,
+            (None, Some(message)) if message != "-" => message.to_string(),
+            (None, message) => {
+                if message.is_none() && io::stdin().is_terminal() {
+                    return Err(JitError::validation(
+                        "Pass the comment text, --file, --snippet, or pipe it on stdin",
+                    )
+                    .into());
+                }
+                io::read_to_string(io::stdin()).context("Failed to read comment from stdin")?
+            }
+        },
+    };
+    if body.trim().is_empty() {
+        return Err(JitError::validation("Comment text is empty").into());
+    }
+
+    let comment = add_issue_comment(client, base_url, &ticket_id, &markdown_to_adf(&body))?;
+    let url = format!(
+        "{}/browse/{}?focusedCommentId={}",
+        base_url, ticket_id, comment.id
+    );
+    let record = RecordOutput::new(json!({
+        "ticket": ticket_id,
+        "comment": comment.id,
+        "url": url,
+        "body": body,
+    }))
+    .field("Commented", &ticket_id)
+    .field("Comment", &comment.id)
+    .field("URL", &url)
+    .field("Body", &body)
+    .line(url.clone());
+    render_output(output, &record)
+}
+
+/// Describes how long before `now` a Jira timestamp was, falling back to the
+/// date once it is more than a month old.
+fn format_relative_time(timestamp: &str, now: DateTime<Utc>) -> String {
+    let Some(time) = parse_jira_datetime(timestamp) else {
+        return timestamp.to_string();
+    };
+    let seconds = (now - time.with_timezone(&Utc)).num_seconds().max(0);
+    let ago = |count: i64, unit: &str| {
+        format!(
+            "{} {}{} ago",
+            count,
+            unit,
+            if count == 1 { "" } else { "s" }
+        )
+    };
+    match seconds {
+        0..60 => "just now".to_string(),
+        60..3_600 => ago(seconds / 60, "minute"),
+        3_600..86_400 => ago(seconds / 3_600, "hour"),
+        86_400..2_592_000 => ago(seconds / 86_400, "day"),
+        _ => time.format("%Y-%m-%d").to_string(),
+    }
+}
+
+fn run_comments_command(
+    client: &Client,
+    base_url: &str,
+    args: &CommentsArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    let (comments, total) = fetch_issue_comments(client, base_url, &ticket_id, args.limit)?;
+    let now = now_utc();
+    let entries: Vec<(String, String, String)> = comments
+        .iter()
+        .map(|comment| {
+            (
+                comment
+                    .author
+                    .as_ref()
+                    .map_or("Unknown", |author| author.display_name.as_str())
+                    .to_string(),
+                comment.created.as_deref().map_or_else(
+                    || "Unknown".to_string(),
+                    |created| format_relative_time(created, now),
+                ),
+                comment
+                    .body
+                    .as_ref()
+                    .map(adf_value_to_display_text)
+                    .unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    let payload = json!({
+        "ticket": ticket_id,
+        "total": total,
+        "comments": comments
+            .iter()
+            .zip(&entries)
+            .map(|(comment, (author, _, body))| {
+                json!({
+                    "id": comment.id,
+                    "author": author,
+                    "created": comment.created,
+                    "updated": comment.updated,
+                    "body": body,
+                })
+            })
+            .collect::<Vec<_>>(),
+    });
+    let links = if args.vscode_links {
+        Some(EditorLinks::for_current_dir()?)
+    } else {
+        None
+    };
+    let rows = entries
+        .iter()
+        .enumerate()
+        .map(|(index, (author, created, body))| {
+            vec![
+                (index + 1).to_string(),
+                author.clone(),
+                created.clone(),
+                truncate_with_ellipsis(&body.split_whitespace().collect::<Vec<_>>().join(" "), 80),
+            ]
+        })
+        .collect();
+    let table =
+        TableOutput::new(payload, &["#", "Author", "Posted", "Comment"], rows).human(|| {
+            if entries.is_empty() {
+                println!("No comments on {}.", ticket_id);
+            }
+            for (index, (author, created, body)) in entries.iter().enumerate() {
+                if index > 0 {
+                    println!();
+                }
+                println!("{} | {}", author.bold(), created);
+                match &links {
+                    Some(links) => println!("{}", links.apply(body.trim_end())),
+                    None => println!("{}", body.trim_end()),
+                }
+            }
+            if total > entries.len() {
+                println!();
+                println!(
+                    "Showing the newest {} of {} comments. Use --limit to see more.",
+                    entries.len(),
+                    total
+                );
+            }
+            Ok(())
+        });
+    render_output(output.or_json(args.json), &table)
+}
+
+const SUMMARIZE_SYSTEM_PROMPT: &str = "You summarize Jira tickets for the engineers working on them. \
+Reply in markdown with a concise summary of at most three sentences, followed by a \
+\"Next actions\" heading and a short bulleted list of suggested next steps.";
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    #[serde(default)]
+    choices: Vec<ChatCompletionChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChoice {
+    message: ChatCompletionMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// Sends `prompt` to the configured OpenAI-compatible chat completions endpoint.
+fn request_llm_completion(
+    config: &AppConfig,
+    llm: &LlmConfig,
+    system_prompt: &str,
+    prompt: &str,
+) -> Result<String> {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    if let Some(api_key) = llm.api_key.as_deref() {
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", api_key))?,
+        );
+    }
+    let client = config
+        .http
+        .client_builder()
+        .default_headers(headers)
+        .build()?;
+
+    let url = format!("{}/chat/completions", llm.base_url.trim_end_matches('/'));
+    let payload = json!({
+        "model": llm.model,
+        "messages": [
+            { "role": "system", "content": system_prompt },
+            { "role": "user", "content": prompt },
+        ],
+    });
+    let response = http::send(client.post(&url).json(&payload), "LLM API")?;
+
+    if !response.status().is_success() {
+        return Err(JitError::from_response("LLM API", &response).into());
+    }
+
+    let completion: ChatCompletionResponse = response
+        .json()
+        .map_err(|err| JitError::parse("LLM API", err))?;
+    completion
+        .choices
+        .into_iter()
+        .find_map(|choice| choice.message.content)
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+        .ok_or_else(|| {
+            JitError::FieldMissing("LLM API returned an empty completion".to_string()).into()
+        })
+}
+
+fn configured_llm(config: &AppConfig) -> Result<&LlmConfig> {
+    Ok(config.llm.as_ref().ok_or_else(|| {
+        JitError::Config(
+            "No LLM configured. Add an [llm] section with base_url and model (and api_key if needed) to your config.toml.".to_string(),
+        )
+    })?)
+}
+
+fn run_summarize_command(
+    jira: &impl JiraApi,
+    config: &AppConfig,
+    args: &SummarizeArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let llm = configured_llm(config)?;
+    let base_url = config.jira.base_url.as_str();
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    let issue = jira.issue(&ticket_id, true, true, true)?;
+    let redactor = if args.redact {
+        Some(Redactor::new(base_url, &config.share.redact_patterns)?)
+    } else {
+        None
+    };
+    let prompt = format_shared_issue_markdown(&build_shared_issue(&issue, redactor));
+    let summary = request_llm_completion(config, llm, SUMMARIZE_SYSTEM_PROMPT, &prompt)?;
+
+    let record = RecordOutput::new(json!({ "ticket": ticket_id, "summary": summary }))
+        .field("Ticket", &ticket_id)
+        .field("Summary", &summary)
+        .line(summary.clone())
+        .human(|| {
+            println!("{}", summary);
+            Ok(())
+        });
+    render_output(output, &record)
+}
+
+const DRAFT_SYSTEM_PROMPT: &str = "You turn short problem statements into Jira tickets. \
+Reply with only a JSON object with the keys \"summary\" (one line, under 100 characters), \
+\"description\" (plain text explaining the problem and context), and \
+\"acceptance_criteria\" (an array of short, testable statements).";
+
+const DRAFT_EDITOR_HELP: &str = "# The first line is the summary; everything after the blank line is the description.\n\
+# Lines starting with '#' are ignored. Clear the summary to cancel.\n";
+
+#[derive(Debug, Deserialize)]
+struct IssueDraft {
+    summary: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    acceptance_criteria: Vec<String>,
+}
+
+/// Parses the LLM's draft, tolerating a markdown code fence around the JSON.
+fn parse_issue_draft(completion: &str) -> Result<IssueDraft> {
+    let trimmed = completion.trim();
+    let json = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.trim_end().strip_suffix("```"))
+        .unwrap_or(trimmed);
+    serde_json::from_str(json.trim()).context("LLM API did not return a ticket draft as JSON")
+}
+
+/// Lays a draft out for editing: summary, blank line, then the description.
+fn format_issue_draft(draft: &IssueDraft) -> String {
+    let mut text = format!("{}\n\n{}", draft.summary.trim(), draft.description.trim());
+    if !draft.acceptance_criteria.is_empty() {
+        text.push_str("\n\nAcceptance criteria:");
+        for criterion in &draft.acceptance_criteria {
+            text.push_str(&format!("\n- {}", criterion.trim()));
+        }
+    }
+    format!("{}\n\n{}", text.trim(), DRAFT_EDITOR_HELP)
+}
+
+/// Reads the edited draft back as `(summary, description)`.
+fn parse_edited_draft(text: &str) -> Result<(String, Option<String>)> {
+    let content: Vec<&str> = text.lines().filter(|line| !line.starts_with('#')).collect();
+    let content = content.join("\n");
+    let content = content.trim();
+    let (summary, description) = content.split_once('\n').unwrap_or((content, ""));
+    let summary = summary.trim();
+    if summary.is_empty() {
+        return Err(
+            JitError::validation("Draft has an empty summary; not creating the ticket").into(),
+        );
+    }
+    let description = description.trim();
+    Ok((
+        summary.to_string(),
+        (!description.is_empty()).then(|| description.to_string()),
+    ))
+}
+
+/// Opens `text` in `$VISUAL` / `$EDITOR` (default `vi`) and returns the saved contents.
+fn edit_in_editor(text: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let path = std::env::temp_dir().join(format!("jit-draft-{}.txt", std::process::id()));
+    fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    // Run through the shell so editors configured with arguments ("code --wait") work.
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to launch editor '{}'", editor));
+    let edited = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+
+    let status = status?;
+    if !status.success() {
+        return Err(anyhow!("Editor '{}' exited with {}", editor, status));
+    }
+    edited.context("Failed to read the edited draft")
+}
+
+/// Opens `path` at `line` in `$VISUAL` / `$EDITOR` (default `vi`), using the
+/// line syntax of the editors that do not take vi's `+line`.
+fn open_in_editor_at(path: &Path, line: u32) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let program = editor
+        .split_whitespace()
+        .next()
+        .and_then(|program| Path::new(program).file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let target = match program.as_str() {
+        "code" | "code-insiders" | "codium" | "cursor" => format!("--goto \"$1:{}\"", line),
+        "subl" | "zed" => format!("\"$1:{}\"", line),
+        _ => format!("+{} \"$1\"", line),
+    };
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} {}", editor, target))
+        .arg("sh")
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to launch editor '{}'", editor))?;
+    if !status.success() {
+        return Err(anyhow!("Editor '{}' exited with {}", editor, status));
+    }
+    Ok(())
+}
+
+const STORY_DESCRIPTION: &str = "As a <type of user>, I want <goal>, so that <reason>.";
+
+/// Fills in what `--template` and `--criterion` add to a new issue. Criteria
+/// go to the [acceptance_criteria] field, or under the description without one.
+fn apply_issue_template(config: &AppConfig, args: &mut CreateArgs) {
+    if args.template == Some(IssueTemplate::Story) {
+        args.issue_type = "Story".to_string();
+        if args.description.is_none() {
+            args.description = Some(STORY_DESCRIPTION.to_string());
+        }
+    }
+    if args.criteria.is_empty() {
+        return;
+    }
+    match config.acceptance_criteria.field.as_deref() {
+        Some(field) => {
+            let format = config
+                .acceptance_criteria
+                .format
+                .unwrap_or(ChecklistFormat::Adf);
+            args.fields
+                .insert(field.to_string(), checklist_value(&args.criteria, format));
+        }
+        None => {
+            let mut description = args.description.take().unwrap_or_default();
+            description.push_str("\n\nAcceptance criteria:");
+            for criterion in &args.criteria {
+                description.push_str(&format!("\n- [ ] {}", criterion.trim()));
+            }
+            args.description = Some(description.trim().to_string());
+        }
+    }
+}
+
+/// The sections `create -i` asks for on a bug, each a heading in the description.
+const BUG_REPORT_SECTIONS: [&str; 4] = [
+    "Steps to reproduce",
+    "Expected behavior",
+    "Actual behavior",
+    "Environment",
+];
+
+/// `create -i`: asks on stderr for a one-line summary when it was not given,
+/// then for the description, a block of lines ended by an empty one. Bugs get
+/// one block per [`BUG_REPORT_SECTIONS`] entry, with the steps numbered, and
+/// any `--description` text above them.
+fn prompt_issue_description(args: &mut CreateArgs, input: &mut impl BufRead) -> Result<()> {
+    if args.summary.trim().is_empty() {
+        eprint!("Summary: ");
+        let mut summary = String::new();
+        input.read_line(&mut summary)?;
+        if summary.trim().is_empty() {
+            return Err(anyhow!("Summary cannot be empty"));
+        }
+        args.summary = summary.trim().to_string();
+    }
+    if !args.issue_type.eq_ignore_ascii_case("bug") {
+        eprintln!("Description (end with an empty line):");
+        let description = read_prompt_block(input)?.join("\n");
+        if !description.is_empty() {
+            let mut text = args.description.take().unwrap_or_default();
+            text.push_str("\n\n");
+            text.push_str(&description);
+            args.description = Some(text.trim().to_string());
+        }
+        return Ok(());
+    }
+
+    let numbering = Regex::new(r"^(?:[-*+•]|\d+[.)])\s+").expect("numbering regex is valid");
+    let mut markdown = args.description.take().unwrap_or_default();
+    for (index, section) in BUG_REPORT_SECTIONS.iter().enumerate() {
+        eprintln!("{} (end with an empty line):", section);
+        let lines = read_prompt_block(input)?;
+        if lines.is_empty() {
+            continue;
+        }
+        markdown.push_str(&format!("\n\n## {}\n", section));
+        if index == 0 {
+            for (number, step) in lines.iter().enumerate() {
+                markdown.push_str(&format!(
+                    "{}. {}\n",
+                    number + 1,
+                    numbering.replace(step, "")
+                ));
+            }
+        } else {
+            markdown.push_str(&lines.join("\n"));
+        }
+    }
+    if !markdown.trim().is_empty() {
+        args.fields
+            .insert("description".to_string(), markdown_to_adf(markdown.trim()));
+    }
+    Ok(())
+}
+
+/// `create --capture-env`: appends a "Captured environment" section listing
+/// the OS, the app version when [create] version_command is set, and the git
+/// commit of the current directory when it is in a repository.
+fn append_captured_environment(config: &AppConfig, args: &mut CreateArgs) -> Result<()> {
+    let mut block = "## Captured environment\n".to_string();
+    block.push_str(&format!("- OS: {}\n", operating_system()));
+    if let Some(command) = config.create.version_command.as_deref() {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .output()
+            .with_context(|| format!("Failed to run '{}'", command))?;
+        if !output.status.success() {
+            return Err(anyhow!("'{}' exited with {}", command, output.status));
+        }
+        let version = String::from_utf8_lossy(&output.stdout);
+        let version = version.lines().next().unwrap_or_default().trim();
+        if !version.is_empty() {
+            block.push_str(&format!("- App version: {}\n", version));
+        }
+    }
+    if let Some(commit) = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    {
+        block.push_str(&format!("- Git commit: {}\n", commit));
+    }
+
+    // The description may already be ADF, from `-i`.
+    let mut description = args
+        .fields
+        .remove("description")
+        .unwrap_or_else(|| text_to_adf(args.description.take().unwrap_or_default().trim()));
+    if let (Some(content), Value::Array(section)) = (
+        description["content"].as_array_mut(),
+        markdown_to_adf(&block)["content"].take(),
+    ) {
+        content.extend(section);
+    }
+    args.fields.insert("description".to_string(), description);
+    Ok(())
+}
+
+/// The kernel name and release from `uname`, or the OS jit was built for.
+fn operating_system() -> String {
+    Command::new("uname")
+        .args(["-sr"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| format!("{} {}", std::env::consts::OS, std::env::consts::ARCH))
+}
+
+/// Trimmed lines from `input` up to the first empty one or the end.
+fn read_prompt_block(input: &mut impl BufRead) -> Result<Vec<String>> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            return Ok(lines);
+        }
+        lines.push(line.trim().to_string());
+    }
+}
+
+/// Fills in the summary and description of `args` from an LLM draft of `idea`
+/// after the user has reviewed it in their editor.
+fn apply_issue_draft(config: &AppConfig, args: &mut CreateArgs, idea: &str) -> Result<()> {
+    let llm = configured_llm(config)?;
+    let prompt = format!(
+        "Project: {}\nIssue type: {}\n\n{}",
+        args.project, args.issue_type, idea
+    );
+    let completion = request_llm_completion(config, llm, DRAFT_SYSTEM_PROMPT, &prompt)?;
+    let draft = parse_issue_draft(&completion)?;
+    let edited = edit_in_editor(&format_issue_draft(&draft))?;
+    let (summary, description) = parse_edited_draft(&edited)?;
+    args.summary = summary;
+    args.description = description;
+    Ok(())
+}
+
+fn prompt_line(prompt: &str) -> Result<Option<String>> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+
+    let mut value = String::new();
+    if io::stdin().read_line(&mut value)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(value.trim().to_string()))
+}
+
+/// `jit verify-release`: the tickets the commits in a range mention that are
+/// not done or lack the fix version, and the commits that mention none.
+/// Exits non-zero when there are any.
+fn run_verify_release_command(
+    jira: &impl JiraApi,
+    args: &VerifyReleaseArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let commits = read_git_commits(&args.repo, &args.range)?;
+    let mut keys: Vec<String> = Vec::new();
+    let mut unreferenced: Vec<&GitCommit> = Vec::new();
+    for commit in &commits {
+        let found: Vec<String> = find_ticket_keys(&commit.message)
+            .into_iter()
+            .filter(|key| {
+                args.projects.is_empty()
+                    || args
+                        .projects
+                        .iter()
+                        .any(|project| issue_project_key(key).eq_ignore_ascii_case(project))
+            })
+            .collect();
+        if found.is_empty() {
+            unreferenced.push(commit);
+        }
+        for key in found {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+
+    let mut tickets = Vec::new();
+    let mut not_found = Vec::new();
+    for key in &keys {
+        match jira.issue_with_fields(key, &["summary", "status", "fixVersions"]) {
+            Ok(issue) => tickets.push(issue),
+            Err(err) if matches!(error::find(&err), Some(JitError::NotFound(_))) => {
+                not_found.push(key.clone())
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    let status = |issue: &JiraIssue| {
+        issue
+            .fields
+            .status
+            .as_ref()
+            .map_or("Unknown", |status| &status.name)
+            .to_string()
+    };
+    let not_done: Vec<&JiraIssue> = tickets
+        .iter()
+        .filter(|issue| {
+            issue
+                .fields
+                .status
+                .as_ref()
+                .and_then(|status| status.status_category.as_ref())
+                .is_none_or(|category| category.key != "done")
+        })
+        .collect();
+    let missing_fix_version: Vec<&JiraIssue> = tickets
+        .iter()
+        .filter(|issue| {
+            let versions = fix_version_names(issue);
+            match args.fix_version.as_deref() {
+                Some(wanted) => !versions.contains(&wanted),
+                None => versions.is_empty(),
+            }
+        })
+        .collect();
+    let problems =
+        not_done.len() + missing_fix_version.len() + unreferenced.len() + not_found.len();
+
+    let ticket_json = |issue: &&JiraIssue| {
+        json!({
+            "ticket": issue.key,
+            "summary": issue.fields.summary,
+            "status": status(issue),
+            "fix_versions": fix_version_names(issue),
+        })
+    };
+    let subject = |commit: &GitCommit| {
+        commit
+            .message
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    };
+    let payload = json!({
+        "range": args.range,
+        "fix_version": args.fix_version,
+        "commits": commits.len(),
+        "tickets": keys,
+        "passed": problems == 0,
+        "not_done": not_done.iter().map(ticket_json).collect::<Vec<_>>(),
+        "missing_fix_version": missing_fix_version.iter().map(ticket_json).collect::<Vec<_>>(),
+        "not_found": not_found,
+        "unreferenced_commits": unreferenced
+            .iter()
+            .map(|commit| json!({ "commit": commit.hash, "subject": subject(commit) }))
+            .collect::<Vec<_>>(),
+    });
+    let missing_label = match args.fix_version.as_deref() {
+        Some(version) => format!("Missing fix version {}", version),
+        None => "Missing a fix version".to_string(),
+    };
+    let record = RecordOutput::new(payload)
+        .field("Commits", commits.len())
+        .field("Tickets", keys.len())
+        .field("Not done", not_done.len())
+        .field(&missing_label, missing_fix_version.len())
+        .field("Not found", not_found.len())
+        .field("Without ticket", unreferenced.len())
+        .human(|| {
+            let verdict = if problems == 0 {
+                "Release ready:".green().bold()
+            } else {
+                "Release not ready:".red().bold()
+            };
+            println!(
+                "{} {} commit(s) in {} mention {} ticket(s).",
+                verdict,
+                commits.len(),
+                args.range,
+                keys.len()
+            );
+            let section = |title: &str, lines: Vec<String>| {
+                if lines.is_empty() {
+                    return;
+                }
+                println!();
+                println!("{} ({}):", title.bold(), lines.len());
+                for line in lines {
+                    println!("  {}", line);
+                }
+            };
+            let ticket_line = |issue: &&JiraIssue| {
+                format!(
+                    "{}  {}  {}",
+                    issue.key.bold(),
+                    issue.fields.summary,
+                    format!("({})", status(issue)).dimmed()
+                )
+            };
+            section("Not done", not_done.iter().map(ticket_line).collect());
+            section(
+                &missing_label,
+                missing_fix_version.iter().map(ticket_line).collect(),
+            );
+            section("Not found in Jira", not_found.clone());
+            section(
+                "Commits without a ticket",
+                unreferenced
+                    .iter()
+                    .map(|commit| format!("{}  {}", commit.hash.yellow(), subject(commit)))
+                    .collect(),
+            );
+            Ok(())
+        });
+    render_output(output, &record)?;
+
+    if problems > 0 {
+        return Err(anyhow!("{} problem(s) found in {}", problems, args.range));
+    }
+    Ok(())
+}
+
+/// `jit gate`: counts the issues matching `--jql` and fails when there are
+/// more than `--max`, listing the first few so the CI log shows what blocks.
+fn run_gate_command(jira: &impl JiraApi, args: &GateArgs, output: OutputFormat) -> Result<()> {
+    let count = jira.count(&args.jql)?;
+    let passed = count <= args.max;
+    let issues = if passed || args.show == 0 {
+        Vec::new()
+    } else {
+        jira.search(&args.jql, &["summary", "status"], args.show)?
+    };
+
+    let status = |issue: &JiraIssue| {
+        issue
+            .fields
+            .status
+            .as_ref()
+            .map_or("Unknown", |status| &status.name)
+            .to_string()
+    };
+    let payload = json!({
+        "jql": args.jql,
+        "count": count,
+        "max": args.max,
+        "passed": passed,
+        "issues": issues
+            .iter()
+            .map(|issue| json!({
+                "ticket": issue.key,
+                "summary": issue.fields.summary,
+                "status": status(issue),
+            }))
+            .collect::<Vec<_>>(),
+    });
+    let record = RecordOutput::new(payload)
+        .field("Count", count)
+        .field("Max", args.max)
+        .field("Passed", passed)
+        .human(|| {
+            if passed {
+                println!(
+                    "{} {} issue(s) match, {} allowed.",
+                    "Gate passed:".green().bold(),
+                    count,
+                    args.max
+                );
+                return Ok(());
+            }
+            println!(
+                "{} {} issue(s) match, {} allowed.",
+                "Gate failed:".red().bold(),
+                count,
+                args.max
+            );
+            for issue in &issues {
+                println!(
+                    "  {}  {}  {}",
+                    issue.key.bold(),
+                    issue.fields.summary,
+                    format!("({})", status(issue)).dimmed()
+                );
+            }
+            if count > issues.len() && !issues.is_empty() {
+                println!("  ... and {} more", count - issues.len());
+            }
+            Ok(())
+        });
+    render_output(output, &record)?;
+
+    if !passed {
+        return Err(anyhow!(
+            "{} issue(s) match the gate query, more than the {} allowed",
+            count,
+            args.max
+        ));
+    }
+    Ok(())
+}
+
+fn run_wait_command(jira: &impl JiraApi, args: &WaitArgs, output: OutputFormat) -> Result<()> {
+    let ticket_id = resolve_ticket(args.ticket.as_deref())?;
+    let started = Instant::now();
+    let interval = Duration::from_secs(args.interval);
+    let mut last_status: Option<String> = None;
+
+    loop {
+        let issue = match jira.issue(&ticket_id, true, false, false) {
+            Ok(issue) => issue,
+            Err(err) if error::is_retryable(&err) => {
+                let elapsed = started.elapsed();
+                if args.timeout.is_some_and(|timeout| elapsed >= timeout) {
+                    return Err(err);
+                }
+                eprintln!("{}: {:#} (retrying)", ticket_id, err);
+                thread::sleep(error::retry_after(&err).unwrap_or(interval));
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        let status = issue
+            .fields
+            .status
+            .as_ref()
+            .map_or("Unknown", |s| &s.name)
+            .to_string();
+
+        if args
+            .until
+            .iter()
+            .any(|target| target.trim().eq_ignore_ascii_case(&status))
+        {
+            let line = format!("{}: {}", issue.key, status);
+            let record = RecordOutput::new(json!({ "key": issue.key, "status": status }))
+                .field("Ticket", &issue.key)
+                .field("Status", &status)
+                .line(line.clone())
+                .human(|| {
+                    println!("{}", line);
+                    Ok(())
+                });
+            return render_output(output, &record);
+        }
+
+        if last_status.as_deref() != Some(status.as_str()) {
+            eprintln!(
+                "{}: {} (waiting for {})",
+                issue.key,
+                status,
+                args.until.join(" or ")
+            );
+            last_status = Some(status.clone());
+        }
+
+        let elapsed = started.elapsed();
+        let sleep_for = match args.timeout {
+            Some(timeout) if elapsed >= timeout => {
+                return Err(anyhow!(
+                    "Timed out after {}s waiting for {} to reach {} (current status: {})",
+                    elapsed.as_secs(),
+                    issue.key,
+                    args.until.join(" or "),
+                    status
+                ));
+            }
+            Some(timeout) => interval.min(timeout - elapsed),
+            None => interval,
+        };
+        thread::sleep(sleep_for);
+    }
+}
+
+/// Parses durations such as `90s`, `30m`, `2h`, `1d`, or `1w`; bare numbers are seconds.
+fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let invalid = || {
+        JitError::validation(format!(
+            "Invalid duration '{}'. Use a number followed by s, m, h, d, or w (e.g. 2h).",
+            value
+        ))
+    };
+    let split_at = value
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split_at);
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    let multiplier = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(invalid().into()),
+    };
+    let seconds = number
+        .checked_mul(multiplier)
+        .ok_or_else(|| JitError::validation(format!("Duration '{}' is too long.", value)))?;
+
+    Ok(Duration::from_secs(seconds))
+}
+
+fn sync_pr_target_status(mappings: &HashMap<String, String>, event: &str) -> Option<String> {
+    if let Some(status) = mappings.get(event) {
+        return Some(status.clone()).filter(|status| !status.trim().is_empty());
+    }
+
+    match event {
+        "opened" | "reopened" | "ready_for_review" => Some("In Review".to_string()),
+        "merged" => Some("Done".to_string()),
+        _ => None,
+    }
+}
+
+/// Names of standards and encodings that look like ticket keys, as in `UTF-8`
+/// or `ISO-8601`, and never are.
+const NON_TICKET_PREFIXES: &[&str] = &[
+    "ASCII", "CVE", "ECMA", "GPL", "HTTP", "IEC", "IEEE", "ISO", "LGPL", "MD", "PEP", "RFC", "SHA",
+    "SSL", "TLS", "UCS", "UTF", "WCAG",
+];
+
+/// Whether `key` (e.g. `RW-12`) can be a ticket key: its project part has at
+/// least two letters and is not a standard such as `UTF` or `ISO`.
+fn is_ticket_key(key: &str) -> bool {
+    let Some((project, _)) = key.rsplit_once('-') else {
+        return false;
+    };
+    project.chars().filter(char::is_ascii_alphabetic).count() >= 2
+        && !NON_TICKET_PREFIXES.contains(&project)
+}
+
+/// Finds Jira ticket keys in free text such as branch names or commit titles.
+/// Uppercase keys win; otherwise the first lowercase key (e.g. `rw-123-fix`) is used.
+fn find_ticket_keys(text: &str) -> Vec<String> {
+    let upper = Regex::new(r"\b([A-Z][A-Z0-9_]+-\d+)\b").expect("ticket key regex is valid");
+    let mut keys: Vec<String> = Vec::new();
+    for captures in upper.captures_iter(text) {
+        let key = captures[1].to_string();
+        if is_ticket_key(&key) && !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    if keys.is_empty() {
+        let lower =
+            Regex::new(r"(?:^|[/_\s])([a-z][a-z0-9_]+-\d+)").expect("ticket key regex is valid");
+        if let Some(key) = lower
+            .captures_iter(text)
+            .map(|captures| captures[1].to_ascii_uppercase())
+            .find(|key| is_ticket_key(key))
+        {
+            keys.push(key);
+        }
+    }
+
+    keys
+}
+
+/// Builds a git branch name such as `rw-1931-fix-login-crash` from a ticket key and summary.
+fn ticket_branch_name(issue_key: &str, summary: &str) -> String {
+    let key = issue_key.to_ascii_lowercase();
+    let slug = slugify(summary, 50);
+    if slug.is_empty() {
+        key
+    } else {
+        format!("{}-{}", key, slug)
+    }
+}
+
+/// The branch name for `issue`, from `[git] branch_template` when one is configured.
+fn configured_branch_name(config: &GitConfig, issue: &JiraIssue) -> Result<String> {
+    let Some(template) = config.branch_template.as_deref() else {
+        return Ok(ticket_branch_name(&issue.key, &issue.fields.summary));
+    };
+    let issue_type = issue
+        .fields
+        .issuetype
+        .as_ref()
+        .map_or(String::new(), |issue_type| slugify(&issue_type.name, 30));
+    let vars = [
+        ("key".to_string(), issue.key.to_ascii_lowercase()),
+        ("KEY".to_string(), issue.key.clone()),
+        ("summary".to_string(), slugify(&issue.fields.summary, 50)),
+        ("type".to_string(), issue_type),
+    ];
+    let name = render_snippet("git.branch_template", template, &vars)?;
+    // An empty summary or type must not leave dangling separators behind.
+    Ok(name
+        .trim_matches(['-', '/', '_'])
+        .replace("/-", "/")
+        .replace("-/", "/"))
+}
+
+fn slugify(text: &str, max_len: usize) -> String {
+    let mut slug = String::new();
+    for ch in text.to_lowercase().chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    if slug.len() > max_len {
+        slug.truncate(max_len);
+        if let Some(index) = slug.rfind('-') {
+            slug.truncate(index);
+        }
+    }
+
+    slug.trim_matches('-').to_string()
+}
+
+fn extract_ticket_id(input: &str) -> Result<String> {
+    // If input starts with http/https, it's a URL
+    if input.starts_with("http://") || input.starts_with("https://") {
+        // Use regex to extract the ticket ID from the URL
+        let re = Regex::new(r"/browse/([A-Z]+-\d+)(?:/|$)")?;
+        if let Some(captures) = re.captures(input) {
+            if let Some(ticket_match) = captures.get(1) {
+                return Ok(ticket_match.as_str().to_string());
+            }
+        }
+        Err(JitError::validation(format!("Could not extract ticket ID from URL: {}", input)).into())
+    } else {
+        // Input is already a ticket ID
+        Ok(input.to_string())
+    }
+}
+
+fn validate_since_date(since: &str) -> Result<()> {
+    let re = Regex::new(r"^\d{4}-\d{2}-\d{2}$")?;
+    if !re.is_match(since) {
+        return Err(JitError::validation(format!(
+            "Invalid --since value '{}'. Use YYYY-MM-DD.",
+            since
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// A client for the configured Jira site: email and API token by default, or
+/// a personal access token with `auth = "pat"`.
+fn create_jira_api_client(jira: &JiraConfig, http: &HttpConfig) -> Result<JiraClient> {
+    if let Some(field) = jira.sprint_field.as_deref() {
+        site_fields::set_sprint_field(&jira.base_url, field);
+    }
+    http::set_cache_account(&format!(
+        "{} {} {}",
+        jira.base_url, jira.user_email, jira.api_token
+    ));
+    match jira.auth {
+        JiraAuth::Basic if jira.user_email.is_empty() => Err(JitError::Config(
+            "jira.user_email is missing. Set it, or set jira.auth = \"pat\" to use a personal access token.".to_string(),
+        )
+        .into()),
+        JiraAuth::Basic => {
+            JiraClient::basic(&jira.base_url, &jira.user_email, &jira.api_token, http)
+        }
+        JiraAuth::Pat => JiraClient::bearer(&jira.base_url, &jira.api_token, http),
+    }
+}
+
+/// Issues assigned to the current user in the open sprints.
+const MY_TICKETS_JQL: &str =
+    "assignee = currentUser() AND sprint in openSprints() ORDER BY updated DESC";
+
+/// How many issues match `jql` when `--limit` cut the `fetched` ones short,
+/// after warning that what was fetched is incomplete.
+fn truncated_total(
+    jira: &impl JiraApi,
+    jql: &str,
+    fetched: usize,
+    limit: u32,
+) -> Result<Option<usize>> {
+    if limit == 0 || fetched < limit as usize {
+        return Ok(None);
+    }
+    let total = jira.count(jql)?;
+    if total <= fetched {
+        return Ok(None);
+    }
+    eprintln!(
+        "{} only {} of {} matching issues were fetched; pass --limit 0 to include them all",
+        "Warning:".yellow().bold(),
+        fetched,
+        total
+    );
+    Ok(Some(total))
+}
+
+/// `--limit` as a number of issues, where 0 means all of them.
+fn issue_limit(limit: u32) -> usize {
+    match limit {
+        0 => usize::MAX,
+        limit => limit as usize,
+    }
+}
+
+fn fetch_my_tickets(jira: &impl JiraApi, limit: u32) -> Result<Vec<JiraIssue>> {
+    let issues = jira.search(
+        MY_TICKETS_JQL,
+        &["summary", "status", SPRINT_FIELD],
+        issue_limit(limit),
+    )?;
+
+    if issues.is_empty() {
+        jira.myself()?;
+    }
+
+    Ok(issues)
+}
+
+/// Narrows `jql` to the assignees picked by the filter flags, keeping any
+/// ORDER BY clause at the end.
+fn with_assignee_filter(
+    client: &Client,
+    config: &AppConfig,
+    jql: &str,
+    filter: &AssigneeFilterArgs,
+) -> Result<String> {
+    let mut jql = match assignee_filter_clause(client, config, filter)? {
+        Some(clause) => and_jql(jql, &clause),
+        None => jql.to_string(),
+    };
+    for name in &filter.quick_filters {
+        jql = and_jql(&jql, &format!("({})", quick_filter(config, name)?));
+    }
+    Ok(jql)
+}
+
+/// The JQL of the quick filter `name` from the [quickfilters] config section.
+fn quick_filter<'a>(config: &'a AppConfig, name: &str) -> Result<&'a str> {
+    if let Some(jql) = config.quickfilters.get(name.trim()) {
+        return Ok(jql);
+    }
+    let mut names: Vec<&str> = config.quickfilters.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    let known = if names.is_empty() {
+        "none are configured".to_string()
+    } else {
+        format!("configured: {}", names.join(", "))
+    };
+    Err(JitError::Config(format!(
+        "Unknown quick filter '{}'. Define it in the [quickfilters] config section ({}).",
+        name, known
+    ))
+    .into())
+}
+
+fn assignee_filter_clause(
+    client: &Client,
+    config: &AppConfig,
+    filter: &AssigneeFilterArgs,
+) -> Result<Option<String>> {
+    if filter.mine {
+        return Ok(Some("assignee = currentUser()".to_string()));
+    }
+    let mut requested: Vec<&str> = Vec::new();
+    for who in &filter.assignee {
+        match who.trim().strip_prefix("team:") {
+            Some(team) => {
+                requested.extend(team_members(config, Some(team))?.iter().map(String::as_str))
+            }
+            None => requested.push(who),
+        }
+    }
+    if filter.team {
+        requested.extend(team_members(config, None)?.iter().map(String::as_str));
+    }
+    if requested.is_empty() {
+        return Ok(None);
+    }
+
+    let base_url = config.jira.base_url.as_str();
+    let mut assignees = Vec::new();
+    let mut unassigned = false;
+    for who in requested {
+        match who.trim() {
+            "me" | "self" | "current" => assignees.push("currentUser()".to_string()),
+            "unassigned" => unassigned = true,
+            who => {
+                let resolved = resolve_create_assignee(client, base_url, who)?;
+                if let Some(account_id) = resolved.account_id {
+                    assignees.push(format!("\"{}\"", account_id.replace('"', "\\\"")));
+                }
+            }
+        }
+    }
+    assignees.dedup();
+
+    let clause = match (assignees.is_empty(), unassigned) {
+        (true, _) => "assignee is EMPTY".to_string(),
+        (false, false) => format!("assignee in ({})", assignees.join(", ")),
+        (false, true) => format!(
+            "(assignee in ({}) OR assignee is EMPTY)",
+            assignees.join(", ")
+        ),
+    };
+    Ok(Some(clause))
+}
+
+/// Members of the `[teams.NAME]` roster, or of the `[team]` section when no name is given.
+fn team_members<'a>(config: &'a AppConfig, name: Option<&str>) -> Result<&'a [String]> {
+    let Some(name) = name else {
+        if config.team.members.is_empty() {
+            return Err(JitError::Config(
+                "No team configured. Add account IDs to `members` in the [team] section of your config.toml.".to_string(),
+            )
+            .into());
+        }
+        return Ok(&config.team.members);
+    };
+    match config.teams.get(name) {
+        Some(team) if !team.members.is_empty() => Ok(&team.members),
+        Some(_) => Err(JitError::Config(format!("Team '{}' has no members", name)).into()),
+        None => {
+            let mut names: Vec<&str> = config.teams.keys().map(String::as_str).collect();
+            names.sort_unstable();
+            Err(JitError::Config(if names.is_empty() {
+                format!(
+                    "Unknown team '{}'. Add a [teams.{}] section with `members` to your config.toml.",
+                    name, name
+                )
+            } else {
+                format!("Unknown team '{}'. Available teams: {}", name, names.join(", "))
+            })
+            .into())
+        }
+    }
+}
+
+/// Adds `clause` to a JQL query with AND, keeping any ORDER BY clause last.
+fn and_jql(jql: &str, clause: &str) -> String {
+    let (query, order_by) = match jql.to_ascii_uppercase().rfind("ORDER BY") {
+        Some(index) => (&jql[..index], Some(jql[index..].trim())),
+        None => (jql, None),
+    };
+    let query = query.trim();
+    let combined = if query.is_empty() {
+        clause.to_string()
+    } else {
+        format!("({}) AND {}", query, clause)
+    };
+    match order_by {
+        Some(order_by) => format!("{} {}", combined, order_by),
+        None => combined,
+    }
+}
+
+fn create_jira_issue(
+    client: &Client,
+    base_url: &str,
+    args: &CreateArgs,
+    assignee_id: Option<&str>,
+) -> Result<JiraCreatedIssue> {
+    let payload = build_issue_create_payload(args, assignee_id);
+    create_jira_issue_payload(client, base_url, &payload)
+}
+
+fn update_jira_issue(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    args: &EditArgs,
+    assignee_id: Option<&str>,
+) -> Result<()> {
+    let payload = build_issue_update_payload(args, assignee_id);
+    update_jira_issue_payload(client, base_url, issue_key, &payload)
+}
+
+fn transition_issue_to_status(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    target_status: &str,
+) -> Result<()> {
+    let transition_id = status_transition_id(client, base_url, issue_key, target_status)?;
+    transition_issue(client, base_url, issue_key, &transition_id)
+}
+
+/// The ID of the transition that moves `issue_key` to `target_status`,
+/// failing with the available ones when there is none.
+fn status_transition_id(
+    client: &Client,
+    base_url: &str,
+    issue_key: &str,
+    target_status: &str,
+) -> Result<String> {
+    let transitions = fetch_issue_transitions(client, base_url, issue_key)?;
+    find_transition(&transitions, target_status)
+        .map(|transition| transition.id.clone())
+        .ok_or_else(|| {
+            anyhow!(
+                "No transition to '{}' is available for {}. Available: {}",
+                target_status,
+                issue_key,
+                transition_names(&transitions)
+            )
+        })
+}
+
+/// Finds a transition by its name or target status name, ignoring case.
+fn find_transition<'a>(
+    transitions: &'a [JiraTransition],
+    target: &str,
+) -> Option<&'a JiraTransition> {
+    let target = target.trim();
+    transitions
+        .iter()
+        .find(|transition| {
+            transition
+                .to
+                .as_ref()
+                .is_some_and(|to| to.name.eq_ignore_ascii_case(target))
+        })
+        .or_else(|| {
+            transitions
+                .iter()
+                .find(|transition| transition.name.eq_ignore_ascii_case(target))
+        })
+}
+
+fn run_assign_command(
+    client: &Client,
+    base_url: &str,
+    args: &AssignArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let (ticket, assignee) =
+        split_ticket_argument(args.ticket.as_deref(), args.assignee.as_deref());
+    let ticket_id = resolve_ticket(ticket)?;
+    let requested = match (args.me, args.unassign, assignee) {
+        (true, _, Some(_)) | (_, true, Some(_)) => {
+            return Err(JitError::validation(
+                "Pass either an assignee or --me/--unassign, not both",
+            )
+            .into());
+        }
+        (true, _, _) => "me",
+        (_, true, _) => "unassigned",
+        (_, _, Some(assignee)) => assignee,
+        (false, false, None) => {
+            return Err(JitError::validation("Pass an assignee, --me, or --unassign").into());
+        }
+    };
+    let assignee = resolve_create_assignee(client, base_url, requested)?;
+    assign_issue(client, base_url, &ticket_id, assignee.account_id.as_deref())?;
+
+    let line = match assignee.account_id {
+        Some(_) => format!("{}: assigned to {}", ticket_id, assignee.label),
+        None => format!("{}: unassigned", ticket_id),
+    };
+    let record = RecordOutput::new(json!({
+        "ticket": ticket_id,
+        "account_id": assignee.account_id,
+        "assignee": assignee.label,
+    }))
+    .field("Ticket", &ticket_id)
+    .field("Assignee", &assignee.label)
+    .line(line);
+    render_output(output, &record)
+}
+
+/// Finds the transition a user means: an exact name first, then the only
+/// transition whose target status or name contains what they typed.
+fn match_transition<'a>(
+    transitions: &'a [JiraTransition],
+    target: &str,
+) -> std::result::Result<&'a JiraTransition, Vec<&'a JiraTransition>> {
+    if let Some(transition) = find_transition(transitions, target) {
+        return Ok(transition);
+    }
+    let normalize = |text: &str| -> String {
+        text.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let target = normalize(target);
+    let candidates: Vec<&JiraTransition> = transitions
+        .iter()
+        .filter(|transition| {
+            !target.is_empty()
+                && (normalize(&transition.name).contains(&target)
+                    || transition
+                        .to
+                        .as_ref()
+                        .is_some_and(|to| normalize(&to.name).contains(&target)))
+        })
+        .collect();
+    match candidates.as_slice() {
+        [transition] => Ok(transition),
+        _ => Err(candidates),
+    }
+}
+
+fn run_move_command(
+    client: &Client,
+    base_url: &str,
+    args: &MoveArgs,
+    output: OutputFormat,
+) -> Result<()> {
+    let (ticket, status) = split_ticket_argument(args.ticket.as_deref(), args.status.as_deref());
+    let ticket_id = resolve_ticket(ticket)?;
+    let transitions = fetch_issue_transitions(client, base_url, &ticket_id)?;
+
+    let Some(target) = status else {
+        let payload = json!({
+            "ticket": ticket_id,
+            "transitions": transitions
+                .iter()
+                .map(|transition| json!({
+                    "id": transition.id,
+                    "name": transition.name,
+                    "to": target_status(transition),
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let rows = transitions
+            .iter()
+            .map(|transition| vec![transition.name.clone(), target_status(transition)])
+            .collect();
+        let table = TableOutput::new(payload, &["Transition", "To"], rows).human(|| {
+            if transitions.is_empty() {
+                println!("No transitions are available for {}.", ticket_id);
+                return Ok(());
+            }
+            println!("Transitions available for {}:", ticket_id);
+            for transition in &transitions {
+                println!("  {} -> {}", transition.name, target_status(transition));
+            }
+            println!();
+            println!("Run `jit move {} \"<status>\"` to move it.", ticket_id);
+            Ok(())
+        });
+        return render_output(output, &table);
+    };
+
+    let transition = resolve_transition(&transitions, target, &ticket_id)?;
+    transition_issue(client, base_url, &ticket_id, &transition.id)?;
+
+    let status = target_status(transition);
+    let record = RecordOutput::new(json!({
+        "ticket": ticket_id,
+        "transition": transition.name,
+        "status": status,
+    }))
+    .field("Moved", &ticket_id)
+    .field("Transition", &transition.name)
+    .field("Status", &status)
+    .line(format!("{}: moved to {}", ticket_id, status));
+    render_output(output, &record)
+}
+
+/// The status a transition leads to, or its own name when Jira does not say.
+fn target_status(transition: &JiraTransition) -> String {
+    transition
+        .to
+        .as_ref()
+        .map_or_else(|| transition.name.clone(), |to| to.name.clone())
+}
+
+/// [`match_transition`], with an error listing what is available when the
+/// target matches no transition or several.
+fn resolve_transition<'a>(
+    transitions: &'a [JiraTransition],
+    target: &str,
+    ticket_id: &str,
+) -> Result<&'a JiraTransition> {
+    match_transition(transitions, target).map_err(|candidates| {
+        let message = if candidates.is_empty() {
+            format!(
+                "No transition to '{}' is available for {}. Available: {}",
+                target,
+                ticket_id,
+                transition_names(transitions)
+            )
+        } else {
+            let names: Vec<&str> = candidates
+                .iter()
+                .map(|transition| transition.name.as_str())
+                .collect();
+            format!(
+                "'{}' matches several transitions for {}: {}",
+                target,
+                ticket_id,
+                names.join(", ")
+            )
+        };
+        JitError::validation(message).into()
+    })
+}
+
+fn transition_names(transitions: &[JiraTransition]) -> String {
+    if transitions.is_empty() {
+        return "none".to_string();
+    }
+
+    transitions
+        .iter()
+        .map(|transition| transition.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn resolve_create_assignee(
+    client: &Client,
+    base_url: &str,
+    requested: &str,
+) -> Result<ResolvedAssignee> {
+    match requested.trim() {
+        "" | "me" | "self" | "current" => fetch_current_user_assignee(client, base_url),
+        "unassigned" => Ok(ResolvedAssignee {
+            account_id: None,
+            label: "Unassigned".to_string(),
+        }),
+        requested => {
+            if let Some(person) = people::find(base_url, requested) {
+                return Ok(ResolvedAssignee {
+                    account_id: Some(person.account_id),
+                    label: person.display_name,
+                });
+            }
+            // Account IDs never contain spaces or `@`, so anything else is a name or email.
+            if requested.contains([' ', '@']) {
+                return search_assignee(client, base_url, requested);
+            }
+            Ok(ResolvedAssignee {
+                account_id: Some(requested.to_string()),
+                label: requested.to_string(),
+            })
+        }
+    }
+}
+
+/// Looks up a user by name or email, for people jit has not seen yet.
+fn search_assignee(client: &Client, base_url: &str, query: &str) -> Result<ResolvedAssignee> {
+    let query = query.trim_start_matches('@');
+    let users = search_users(client, base_url, query)?;
+    let exact: Vec<&JiraUser> = users
+        .iter()
+        .filter(|user| user.display_name.eq_ignore_ascii_case(query))
+        .collect();
+    let user = match (users.as_slice(), exact.as_slice()) {
+        ([user], _) | (_, &[user]) => user,
+        ([], _) => {
+            return Err(JitError::validation(format!("No Jira user matches '{}'", query)).into());
+        }
+        _ => {
+            let names: Vec<&str> = users
+                .iter()
+                .map(|user| user.display_name.as_str())
+                .collect();
+            return Err(JitError::validation(format!(
+                "'{}' matches several Jira users: {}",
+                query,
+                names.join(", ")
+            ))
+            .into());
+        }
+    };
+    let account_id = user
+        .account_id
+        .clone()
+        .context("Jira user search response did not include accountId")?;
+
+    Ok(ResolvedAssignee {
+        account_id: Some(account_id),
+        label: user.display_name.clone(),
+    })
+}
+
+fn fetch_current_user_assignee(client: &Client, base_url: &str) -> Result<ResolvedAssignee> {
+    let current_user = fetch_current_user(client, base_url)?;
+    let account_id = current_user
+        .account_id
+        .context("Current Jira user response did not include accountId")?;
+    let label = if current_user.display_name.trim().is_empty() {
+        "Current user".to_string()
+    } else {
+        current_user.display_name
+    };
+
+    Ok(ResolvedAssignee {
+        account_id: Some(account_id),
+        label,
+    })
+}
+
+fn resolve_target_sprint(
+    client: &Client,
+    base_url: &str,
+    args: &CreateArgs,
+) -> Result<Option<ResolvedSprint>> {
+    if !args.current_sprint {
+        return Ok(None);
+    }
+
+    let sprint = if let Some(board_id) = args.board {
+        resolve_active_sprint_for_board(client, base_url, board_id)?
+    } else {
+        resolve_latest_active_sprint_for_project(client, base_url, &args.project)?
+    };
+
+    Ok(Some(sprint))
+}
+
+fn resolve_latest_active_sprint_for_project(
+    client: &Client,
+    base_url: &str,
+    project_key: &str,
+) -> Result<ResolvedSprint> {
+    let boards = fetch_scrum_boards_for_project(client, base_url, project_key)?;
+    if boards.is_empty() {
+        return Err(anyhow!(
+            "No Scrum boards found for project {}. Pass --board <id> if the sprint lives on a different board.",
+            project_key
+        ));
+    }
+
+    let mut best: Option<ResolvedSprint> = None;
+    for board in boards {
+        let sprint = match fetch_active_sprints_for_board(client, base_url, board.id)? {
+            Some(sprint) => ResolvedSprint {
+                id: sprint.id,
+                name: sprint.name,
+                board_id: board.id,
+                board_name: board.name,
+                start_date: sprint.start_date,
+            },
+            None => continue,
+        };
+
+        if is_better_sprint_candidate(&sprint, best.as_ref()) {
+            best = Some(sprint);
+        }
+    }
+
+    best.ok_or_else(|| {
+        anyhow!(
+            "No active sprint found on accessible Scrum boards for project {}. Create without --current-sprint or pass --board <id>.",
+            project_key
+        )
+    })
+}
+
+fn resolve_active_sprint_for_board(
+    client: &Client,
+    base_url: &str,
+    board_id: u64,
+) -> Result<ResolvedSprint> {
+    let board = fetch_board(client, base_url, board_id)?;
+    let sprint = fetch_active_sprints_for_board(client, base_url, board_id)?.ok_or_else(|| {
+        anyhow!(
+            "No active sprint found on board {} ({}).",
+            board.id,
+            board.name
+        )
+    })?;
+
+    Ok(ResolvedSprint {
+        id: sprint.id,
+        name: sprint.name,
+        board_id: board.id,
+        board_name: board.name,
+        start_date: sprint.start_date,
+    })
+}
+
+fn fetch_active_sprints_for_board(
+    client: &Client,
+    base_url: &str,
+    board_id: u64,
+) -> Result<Option<JiraAgileSprint>> {
+    let mut best: Option<JiraAgileSprint> = None;
+    for sprint in fetch_board_sprints(client, base_url, board_id, "active")? {
+        if is_better_active_sprint(&sprint, best.as_ref()) {
+            best = Some(sprint);
+        }
+    }
+    Ok(best)
+}
+
+/// The board's next future sprint: the earliest-starting one, or the oldest
+/// when none has a start date yet.
+fn fetch_next_future_sprint(
+    client: &Client,
+    base_url: &str,
+    board_id: u64,
+) -> Result<Option<JiraAgileSprint>> {
+    let sprints = fetch_board_sprints(client, base_url, board_id, "future")?;
+    Ok(sprints.into_iter().min_by(|a, b| {
+        let a_date = a.start_date.as_deref().unwrap_or("\u{10ffff}");
+        let b_date = b.start_date.as_deref().unwrap_or("\u{10ffff}");
+        a_date.cmp(b_date).then(a.id.cmp(&b.id))
+    }))
+}
+
+fn is_better_sprint_candidate(
+    candidate: &ResolvedSprint,
+    current: Option<&ResolvedSprint>,
+) -> bool {
+    match current {
+        None => true,
+        Some(current) => compare_sprint_identity(
+            candidate.start_date.as_deref(),
+            candidate.id,
+            candidate.board_id,
+            current.start_date.as_deref(),
+            current.id,
+            current.board_id,
+        ),
+    }
+}
+
+fn is_better_active_sprint(candidate: &JiraAgileSprint, current: Option<&JiraAgileSprint>) -> bool {
+    match current {
+        None => true,
+        Some(current) => compare_sprint_identity(
+            candidate.start_date.as_deref(),
+            candidate.id,
+            0,
+            current.start_date.as_deref(),
+            current.id,
+            0,
+        ),
+    }
+}
+
+fn compare_sprint_identity(
+    candidate_date: Option<&str>,
+    candidate_sprint_id: u64,
+    candidate_board_id: u64,
+    current_date: Option<&str>,
+    current_sprint_id: u64,
+    current_board_id: u64,
+) -> bool {
+    let candidate_date = candidate_date.and_then(parse_jira_datetime);
+    let current_date = current_date.and_then(parse_jira_datetime);
+
+    match (candidate_date, current_date) {
+        (Some(candidate), Some(current)) => {
+            candidate > current
+                || (candidate == current
+                    && (candidate_sprint_id, candidate_board_id)
+                        > (current_sprint_id, current_board_id))
+        }
+        (Some(_), None) => true,
+        (None, Some(_)) => false,
+        (None, None) => {
+            (candidate_sprint_id, candidate_board_id) > (current_sprint_id, current_board_id)
+        }
+    }
+}
+
+fn parse_jira_datetime(value: &str) -> Option<DateTime<FixedOffset>> {
+    // Jira Cloud returns offsets without a colon (e.g., "+0000"), which RFC 3339 rejects.
+    DateTime::parse_from_rfc3339(value)
+        .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z"))
+        .ok()
+}
+
+fn now_utc() -> DateTime<Utc> {
+    DateTime::from(SystemTime::now())
+}
+
+fn build_issue_create_payload(args: &CreateArgs, assignee_id: Option<&str>) -> Value {
+    let mut fields = serde_json::Map::from_iter([
+        ("project".to_string(), json!({ "key": args.project })),
+        ("summary".to_string(), json!(args.summary)),
+        ("issuetype".to_string(), json!({ "name": args.issue_type })),
+    ]);
+
+    if let Some(assignee_id) = assignee_id {
+        fields.insert("assignee".to_string(), json!({ "id": assignee_id }));
+    }
+
+    if let Some(description) = args
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+    {
+        fields.insert("description".to_string(), text_to_adf(description));
+    }
+
+    fields.extend(args.fields.clone());
+    json!({ "fields": fields })
+}
+
+fn build_issue_update_payload(args: &EditArgs, assignee_id: Option<&str>) -> Value {
+    let mut fields = serde_json::Map::new();
+
+    if let Some(summary) = args.summary.as_deref() {
+        fields.insert("summary".to_string(), json!(summary));
+    }
+
+    if let Some(issue_type) = args.issue_type.as_deref() {
+        fields.insert("issuetype".to_string(), json!({ "name": issue_type }));
+    }
+
+    if args.assignee.is_some() {
+        let assignee_value = assignee_id
+            .map(|account_id| json!({ "accountId": account_id }))
+            .unwrap_or(Value::Null);
+        fields.insert("assignee".to_string(), assignee_value);
+    }
+
+    if let Some(description) = args.description.as_deref() {
+        fields.insert(
+            "description".to_string(),
+            if description.trim().is_empty() {
+                Value::Null
+            } else {
+                text_to_adf(description)
+            },
+        );
+    }
+
+    fields.extend(args.fields.clone());
+    json!({ "fields": fields })
+}
+
+struct ResolvedAssignee {
+    account_id: Option<String>,
+    label: String,
+}
+
+#[derive(Clone)]
+struct ResolvedSprint {
+    id: u64,
+    name: String,
+    board_id: u64,
+    board_name: String,
+    start_date: Option<String>,
+}
+
+fn fetch_issue_pull_requests(
+    client: &Client,
+    base_url: &str,
+    issue_id: &str,
+) -> Result<Vec<JiraPullRequest>> {
+    fetch_dev_status_pull_requests(client, base_url, issue_id, "GitHub")
+}
+
+/// Fetches GitHub pull requests, plus Bitbucket ones when a Bitbucket repository is configured.
+fn fetch_linked_pull_requests(
+    client: &Client,
+    base_url: &str,
+    issue_id: &str,
+    include_bitbucket: bool,
+) -> Result<Vec<JiraPullRequest>> {
+    let mut pull_requests = fetch_issue_pull_requests(client, base_url, issue_id)?;
+    if include_bitbucket {
+        pull_requests.extend(fetch_dev_status_pull_requests(
+            client,
+            base_url,
+            issue_id,
+            "bitbucket",
+        )?);
+    }
+    Ok(pull_requests)
+}
+
+/// A ticket's CI status: its most recent build, and the counts over all of them.
+#[derive(Debug, Clone, Default)]
+struct CiStatus {
+    latest: Option<JiraBuild>,
+    summary: JiraBuildSummary,
+}
+
+/// The CI state shown for a ticket, taken from its most recent build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BuildState {
+    None,
+    Passing,
+    Failing,
+    Running,
+    Cancelled,
+    Unknown,
+}
+
+impl BuildState {
+    fn as_str(self) -> &'static str {
+        match self {
+            BuildState::None => "none",
+            BuildState::Passing => "passing",
+            BuildState::Failing => "failing",
+            BuildState::Running => "running",
+            BuildState::Cancelled => "cancelled",
+            BuildState::Unknown => "unknown",
+        }
+    }
+}
+
+impl CiStatus {
+    fn state(&self) -> BuildState {
+        match self.latest.as_ref().map(|build| build.state) {
+            Some(JiraBuildState::Successful) => BuildState::Passing,
+            Some(JiraBuildState::Failed) => BuildState::Failing,
+            Some(JiraBuildState::InProgress) => BuildState::Running,
+            Some(JiraBuildState::Cancelled) => BuildState::Cancelled,
+            Some(JiraBuildState::Unknown) => BuildState::Unknown,
+            // Builds were counted, but none could be listed.
+            None if self.summary.count > 0 => BuildState::Unknown,
+            None => BuildState::None,
+        }
+    }
+
+    fn latest_name(&self) -> Option<&str> {
+        self.latest
+            .as_ref()
+            .and_then(|build| build.display_name.as_deref().or(build.name.as_deref()))
+    }
+}
+
+/// Fetches the builds Jira's development panel has for an issue: the counts
+/// from the summary, then the builds of each provider to find the latest.
+fn fetch_issue_ci_status(client: &Client, base_url: &str, issue_id: &str) -> Result<CiStatus> {
+    let summary = fetch_dev_status_summary(client, base_url, issue_id)?;
+    let build = summary.summary.build.unwrap_or_default();
+
+    let built_at = |build: &JiraBuild| build.last_updated.as_deref().and_then(parse_jira_datetime);
+    let mut latest: Option<JiraBuild> = None;
+    for application_type in build.by_instance_type.keys() {
+        let details =
+            fetch_dev_status_detail(client, base_url, issue_id, application_type, "build")?;
+        for candidate in details.into_iter().flat_map(|detail| detail.builds) {
+            if latest
+                .as_ref()
+                .is_none_or(|latest| built_at(&candidate) > built_at(latest))
+            {
+                latest = Some(candidate);
+            }
+        }
+    }
+
+    Ok(CiStatus {
+        latest,
+        summary: build.overall,
+    })
+}
+
+fn format_ci_status(ci: &CiStatus) -> String {
+    let state = ci.state();
+    if state == BuildState::None {
+        return "No builds reported".to_string();
+    }
+    let counts = format!(
+        "{} passed, {} failed, {} pending",
+        ci.summary.successful, ci.summary.failed, ci.summary.unknown
+    );
+    match ci.latest_name() {
+        Some(name) => format!("{}: {} ({})", state.as_str().to_uppercase(), name, counts),
+        None => format!("{} ({})", state.as_str().to_uppercase(), counts),
+    }
+}
+
+fn get_colored_ci_status(ci: &CiStatus) -> String {
+    let text = format_ci_status(ci);
+    match ci.state() {
+        BuildState::Failing => text.bright_red().bold().to_string(),
+        BuildState::Running => text.bright_yellow().bold().to_string(),
+        BuildState::Passing => text.bright_green().bold().to_string(),
+        _ => text,
+    }
+}
+
+fn fetch_dev_status_pull_requests(
+    client: &Client,
+    base_url: &str,
+    issue_id: &str,
+    application_type: &str,
+) -> Result<Vec<JiraPullRequest>> {
+    Ok(
+        fetch_dev_status_detail(client, base_url, issue_id, application_type, "pullrequest")?
+            .into_iter()
+            .flat_map(|detail| detail.pull_requests)
+            .collect(),
+    )
+}
+
+fn fetch_pull_requests_for_tickets(
+    client: &Client,
+    base_url: &str,
+    tickets: &[JiraIssue],
+    include_bitbucket: bool,
+) -> Result<HashMap<String, Vec<JiraPullRequest>>> {
+    let mut by_key = HashMap::new();
+
+    for ticket in tickets {
+        let prs = fetch_linked_pull_requests(client, base_url, &ticket.id, include_bitbucket)
+            .with_context(|| format!("Failed to fetch pull requests for {}", ticket.key))?;
+        by_key.insert(ticket.key.clone(), prs);
+    }
+
+    Ok(by_key)
+}
+
+fn build_bitbucket_pr_payload(
+    issue: &JiraIssue,
+    jira_base_url: &str,
+    source: &str,
+    destination: &str,
+) -> Value {
+    let mut description = format!("Jira: {}/browse/{}", jira_base_url, issue.key);
+    if let Some(text) = issue
+        .fields
+        .description
+        .as_ref()
+        .filter(|desc| !desc.is_null())
+        .map(adf_value_to_display_text)
+    {
+        description.push_str("\n\n");
+        description.push_str(&text);
+    }
+
+    json!({
+        "title": format!("{}: {}", issue.key, issue.fields.summary),
+        "description": description,
+        "source": { "branch": { "name": source } },
+        "destination": { "branch": { "name": destination } },
+    })
+}
+
+// Truncate a string to max_len and add ellipsis if needed
+fn truncate_with_ellipsis(s: &str, max_len: usize) -> String {
+    if s.len() <= max_len {
+        return s.to_string();
+    }
+
+    let mut result = s.chars().take(max_len - 3).collect::<String>();
+    result.push_str("...");
+    result
+}
+
+fn extract_pr_id_from_url(url: &str) -> Option<String> {
+    let (start, marker) = ["/pull/", "/pull-requests/"]
+        .into_iter()
+        .find_map(|marker| url.find(marker).map(|start| (start, marker)))?;
+    let suffix = &url[start + marker.len()..];
+    let number = suffix.split(['/', '?', '#']).next().unwrap_or("").trim();
+    if number.is_empty() {
+        None
+    } else {
+        Some(format!("#{}", number))
+    }
+}
+
+fn pull_request_display_id(pr: &JiraPullRequest) -> String {
+    if let Some(id) = pr.id.as_deref().filter(|id| !id.trim().is_empty()) {
+        return id.to_string();
+    }
+    if let Some(url) = pr.url.as_deref() {
+        if let Some(id) = extract_pr_id_from_url(url) {
+            return id;
+        }
+    }
+    "PR".to_string()
+}
+
+fn format_pull_request_summary(pull_requests: &[JiraPullRequest]) -> String {
+    if pull_requests.is_empty() {
+        return "-".to_string();
+    }
+
+    let mut ids: Vec<String> = pull_requests.iter().map(pull_request_display_id).collect();
+    if ids.len() > 3 {
+        let remaining = ids.len() - 3;
+        ids.truncate(3);
+        format!("{} +{}", ids.join(", "), remaining)
+    } else {
+        ids.join(", ")
+    }
+}
+
+/// Returns color-coded status text based on the status name
+fn get_colored_status(status: &str) -> String {
+    match status.to_lowercase().as_str() {
+        s if s.contains("done") => status.bright_green().bold().to_string(),
+        s if s.contains("complete") => status.bright_green().bold().to_string(),
+        s if s.contains("resolved") => status.bright_green().bold().to_string(),
+
+        s if s.contains("progress") => status.bright_yellow().bold().to_string(),
+        s if s.contains("review") => status.yellow().bold().to_string(),
+        s if s.contains("implement") => status.bright_yellow().bold().to_string(),
+        s if s.contains("testing") => status.bright_yellow().bold().to_string(),
+
+        s if s.contains("todo") => status.bright_blue().to_string(),
+        s if s.contains("backlog") => status.blue().to_string(),
+        s if s.contains("selected") => status.cyan().to_string(),
+        s if s.contains("open") => status.bright_blue().to_string(),
+
+        s if s.contains("block") => status.bright_red().bold().to_string(),
+        s if s.contains("impediment") => status.bright_red().bold().to_string(),
+        s if s.contains("cancel") => status.red().bold().to_string(),
+        s if s.contains("won't") => status.red().bold().to_string(),
+        s if s.contains("wont") => status.red().bold().to_string(),
+
+        _ => status.white().to_string(),
+    }
+}
+
+/// Format a date string from JIRA's format to a more readable format
+fn format_date(date_str: &str) -> String {
+    if date_str.is_empty() {
+        return "Not set".to_string();
+    }
+
+    // JIRA dates look like "2023-09-15T14:53:37.123+0000"
+    // We'll just extract the date part for simplicity
+    if let Some(date_part) = date_str.split('T').next() {
+        return date_part.to_string();
+    }
+
+    date_str.to_string()
+}
+
+fn comment_created_date(comment: &JiraComment) -> Option<&str> {
+    comment
+        .created
+        .as_deref()
+        .and_then(|timestamp| timestamp.split('T').next())
+}
+
+fn get_filtered_comments<'a>(
+    issue: &'a JiraIssue,
+    since: Option<&str>,
+    comments_limit: usize,
+    all_comments: bool,
+) -> Vec<&'a JiraComment> {
+    let mut comments: Vec<&JiraComment> = issue
+        .fields
+        .comment
+        .as_ref()
+        .map(|container| container.comments.iter().collect())
+        .unwrap_or_default();
+
+    if let Some(since_date) = since {
+        comments.retain(|comment| {
+            comment_created_date(comment)
+                .map(|date| date >= since_date)
+                .unwrap_or(false)
+        });
+    }
+
+    if !all_comments && comments.len() > comments_limit {
+        comments = comments.into_iter().rev().take(comments_limit).collect();
+        comments.reverse();
+    }
+
+    comments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::CommandOutput;
+    use jit_core::adf::extract_plain_text_from_adf;
+    use jit_core::client::HttpVersion;
+    use jit_core::mock::MockJira;
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn cli_parses_create_command() {
+        let cli = Cli::try_parse_from([
+            "jit",
+            "create",
+            "--project",
+            "RW",
+            "--summary",
+            "Implement backlog creation",
+            "--description",
+            "First line\nSecond line",
+            "--type",
+            "Story",
+            "--assignee",
+            "account-id-123",
+            "--current-sprint",
+            "--board",
+            "456",
+            "--json",
+        ])
+        .expect("create command should parse");
+
+        match cli.command {
+            Some(Commands::Create(args)) => {
+                assert_eq!(args.project, "RW");
+                assert_eq!(args.summary, "Implement backlog creation");
+                assert_eq!(args.description.as_deref(), Some("First line\nSecond line"));
+                assert_eq!(args.issue_type, "Story");
+                assert_eq!(args.assignee, "account-id-123");
+                assert!(args.current_sprint);
+                assert_eq!(args.board, Some(456));
+                assert!(args.json);
+            }
+            _ => panic!("expected create command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_edit_command() {
+        let cli = Cli::try_parse_from([
+            "jit",
+            "edit",
+            "RW-123",
+            "--summary",
+            "Updated summary",
+            "--description",
+            "First line\nSecond line",
+            "--type",
+            "Bug",
+            "--assignee",
+            "unassigned",
+            "--json",
+        ])
+        .expect("edit command should parse");
+
+        match cli.command {
+            Some(Commands::Edit(args)) => {
+                assert_eq!(args.ticket.as_deref(), Some("RW-123"));
+                assert_eq!(args.summary.as_deref(), Some("Updated summary"));
+                assert_eq!(args.description.as_deref(), Some("First line\nSecond line"));
+                assert_eq!(args.issue_type.as_deref(), Some("Bug"));
+                assert_eq!(args.assignee.as_deref(), Some("unassigned"));
+                assert!(args.json);
+            }
+            _ => panic!("expected edit command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_skill_install_command() {
+        let cli = Cli::try_parse_from(["jit", "skill", "install"])
+            .expect("skill install command should parse");
+
+        match cli.command {
+            Some(Commands::Skill(args)) => match args.command {
+                SkillCommands::Install(install_args) => {
+                    assert!(install_args.agent.is_none());
+                }
+            },
+            _ => panic!("expected skill install command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_skill_install_with_agent_filter() {
+        let cli = Cli::try_parse_from(["jit", "skill", "install", "--agent", "codex"])
+            .expect("skill install --agent should parse");
+
+        match cli.command {
+            Some(Commands::Skill(args)) => match args.command {
+                SkillCommands::Install(install_args) => {
+                    assert_eq!(install_args.agent.as_deref(), Some("codex"));
+                }
+            },
+            _ => panic!("expected skill install command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_config_file_flag() {
+        let cli = Cli::try_parse_from(["jit", "--config-file", "/tmp/jit-config.toml", "RW-123"])
+            .expect("config file flag should parse");
+
+        assert_eq!(
+            cli.query.config_file,
+            Some(PathBuf::from("/tmp/jit-config.toml"))
+        );
+        assert_eq!(cli.query.ticket.as_deref(), Some("RW-123"));
+    }
+
+    #[test]
+    fn cli_defaults_my_tickets_limit_to_full_sprint_view() {
+        let cli = Cli::try_parse_from(["jit", "--my-tickets"])
+            .expect("my tickets flag should parse");
+
+        assert_eq!(cli.query.limit, 50);
+    }
+
+    #[test]
+    fn read_config_file_loads_jira_credentials_from_toml() {
+        let unique_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock should be after epoch")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("jit-config-{unique_id}.toml"));
+
+        fs::write(
+            &path,
+            r#"[jira]
+base_url = "https://example.atlassian.net"
+api_token = "token-123"
+user_email = "user@example.com"
+"#,
+        )
+        .expect("temp config should be written");
+
+        let config = read_config_file(&path).expect("config should parse");
+        fs::remove_file(&path).expect("temp config should be removed");
+
+        assert_eq!(config.base_url, "https://example.atlassian.net");
+        assert_eq!(config.api_token, "token-123");
+        assert_eq!(config.user_email, "user@example.com");
+    }
+
+    #[test]
+    fn config_path_from_home_uses_dot_config_jit_directory() {
+        let path = config_path_from_home(Path::new("/Users/example"));
+
+        assert_eq!(
+            path,
+            PathBuf::from("/Users/example/.config/jit/config.toml")
+        );
+    }
+
+    #[test]
+    fn extract_ticket_id_accepts_plain_ticket_keys() {
+        let ticket = extract_ticket_id("RW-123").expect("plain ticket keys should be accepted");
+
+        assert_eq!(ticket, "RW-123");
+    }
+
+    #[test]
+    fn extract_ticket_id_extracts_ticket_from_browse_url() {
+        let ticket = extract_ticket_id("https://example.atlassian.net/browse/RW-123/")
+            .expect("browse URLs should parse");
+
+        assert_eq!(ticket, "RW-123");
+    }
+
+    #[test]
+    fn extract_ticket_id_rejects_invalid_jira_urls() {
+        let error = extract_ticket_id("https://example.atlassian.net/issues/RW-123")
+            .expect_err("non-browse URLs should be rejected");
+
+        assert!(
+            error
+                .to_string()
+                .contains("Could not extract ticket ID from URL")
+        );
+    }
+
+    #[test]
+    fn validate_since_date_accepts_yyyy_mm_dd_dates() {
+        validate_since_date("2026-04-22").expect("valid dates should pass");
+    }
+
+    #[test]
+    fn validate_since_date_rejects_invalid_date_format() {
+        let error =
+            validate_since_date("22-04-2026").expect_err("invalid date formats should fail");
+
+        assert!(error.to_string().contains("Use YYYY-MM-DD"));
+    }
+
+    #[test]
+    fn resolve_config_path_prefers_explicit_config_file() {
+        let unique_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock should be after epoch")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("jit-config-path-{unique_id}.toml"));
+        fs::write(&path, "[jira]\nbase_url = \"https://example.atlassian.net\"\napi_token = \"token\"\nuser_email = \"user@example.com\"\n")
+            .expect("temp config should be written");
+
+        let args = QueryArgs {
+            ticket: None,
+            json: false,
+            text: false,
+            csv: false,
+            ndjson: false,
+            markdown: false,
+            columns: Vec::new(),
+            group_by: None,
+            format: None,
+            fields: Vec::new(),
+            count: false,
+            my_tickets: false,
+            show: false,
+            include_description: false,
+            include_comments: false,
+            include_prs: false,
+            include_ci: false,
+            full: false,
+            comments_limit: 5,
+            all_comments: false,
+            since: None,
+            vscode_links: false,
+            limit: 10,
+            assignees: AssigneeFilterArgs::default(),
+            config_file: Some(path.clone()),
+            profile: None,
+            all_profiles: false,
+        };
+
+        let resolved = resolve_config_path(&args).expect("explicit config file should resolve");
+        fs::remove_file(&path).expect("temp config should be removed");
+
+        assert_eq!(resolved, path);
+    }
+
+    #[test]
+    fn resolve_config_path_errors_for_missing_explicit_config_file() {
+        let args = QueryArgs {
+            ticket: None,
+            json: false,
+            text: false,
+            csv: false,
+            ndjson: false,
+            markdown: false,
+            columns: Vec::new(),
+            group_by: None,
+            format: None,
+            fields: Vec::new(),
+            count: false,
+            my_tickets: false,
+            show: false,
+            include_description: false,
+            include_comments: false,
+            include_prs: false,
+            include_ci: false,
+            full: false,
+            comments_limit: 5,
+            all_comments: false,
+            since: None,
+            vscode_links: false,
+            limit: 10,
+            assignees: AssigneeFilterArgs::default(),
+            config_file: Some(PathBuf::from("/tmp/definitely-missing-jit-config.toml")),
+            profile: None,
+            all_profiles: false,
+        };
+
+        let error =
+            resolve_config_path(&args).expect_err("missing explicit config should return an error");
+
+        assert!(
+            error
+                .to_string()
+                .contains("Specified config.toml file not found")
+        );
+    }
+
+    #[test]
+    fn build_issue_create_payload_uses_adf_for_description() {
+        let args = CreateArgs {
+            project: "RW".to_string(),
+            summary: "Implement backlog creation".to_string(),
+            description: Some("First line\nSecond line\n\nNew paragraph".to_string()),
+            issue_type: "Task".to_string(),
+            assignee: "me".to_string(),
+            current_sprint: false,
+            board: None,
+            draft: None,
+            template: None,
+            criteria: Vec::new(),
+            interactive: false,
+            capture_env: false,
+            json: false,
+            fields: Default::default(),
+        };
+
+        let payload = build_issue_create_payload(&args, Some("account-id-123"));
+
+        assert_eq!(payload["fields"]["project"]["key"], "RW");
+        assert_eq!(payload["fields"]["summary"], "Implement backlog creation");
+        assert_eq!(payload["fields"]["issuetype"]["name"], "Task");
+        assert_eq!(payload["fields"]["assignee"]["id"], "account-id-123");
+        assert_eq!(payload["fields"]["description"]["type"], "doc");
+        assert_eq!(payload["fields"]["description"]["version"], 1);
+        assert_eq!(
+            payload["fields"]["description"]["content"][0]["type"],
+            "paragraph"
+        );
+        assert_eq!(
+            payload["fields"]["description"]["content"][0]["content"][0]["text"],
+            "First line"
+        );
+        assert_eq!(
+            payload["fields"]["description"]["content"][0]["content"][1]["type"],
+            "hardBreak"
+        );
+        assert_eq!(
+            payload["fields"]["description"]["content"][0]["content"][2]["text"],
+            "Second line"
+        );
+        assert_eq!(
+            payload["fields"]["description"]["content"][1]["content"][0]["text"],
+            "New paragraph"
+        );
+    }
+
+    #[test]
+    fn build_issue_update_payload_supports_core_editable_fields() {
+        let args = EditArgs {
+            ticket: Some("RW-123".to_string()),
+            summary: Some("Updated summary".to_string()),
+            description: Some("First line\nSecond line".to_string()),
+            issue_type: Some("Bug".to_string()),
+            assignee: Some("account-id-123".to_string()),
+            estimate: None,
+            json: false,
+            fields: Default::default(),
+        };
+
+        let payload = build_issue_update_payload(&args, Some("account-id-123"));
+
+        assert_eq!(payload["fields"]["summary"], "Updated summary");
+        assert_eq!(payload["fields"]["issuetype"]["name"], "Bug");
+        assert_eq!(payload["fields"]["assignee"]["accountId"], "account-id-123");
+        assert_eq!(payload["fields"]["description"]["type"], "doc");
+        assert_eq!(payload["fields"]["description"]["version"], 1);
+        assert_eq!(
+            payload["fields"]["description"]["content"][0]["content"][0]["text"],
+            "First line"
+        );
+        assert_eq!(
+            payload["fields"]["description"]["content"][0]["content"][1]["type"],
+            "hardBreak"
+        );
+        assert_eq!(
+            payload["fields"]["description"]["content"][0]["content"][2]["text"],
+            "Second line"
+        );
+    }
+
+    #[test]
+    fn build_issue_create_payload_skips_blank_description_and_unassigned_assignee() {
+        let args = CreateArgs {
+            project: "RW".to_string(),
+            summary: "Implement backlog creation".to_string(),
+            description: Some("   ".to_string()),
+            issue_type: "Task".to_string(),
+            assignee: "unassigned".to_string(),
+            current_sprint: false,
+            board: None,
+            draft: None,
+            template: None,
+            criteria: Vec::new(),
+            interactive: false,
+            capture_env: false,
+            json: false,
+            fields: Default::default(),
+        };
+
+        let payload = build_issue_create_payload(&args, None);
+
+        assert!(payload["fields"].get("description").is_none());
+        assert!(payload["fields"].get("assignee").is_none());
+    }
+
+    #[test]
+    fn build_issue_update_payload_clears_description_and_assignee() {
+        let args = EditArgs {
+            ticket: Some("RW-123".to_string()),
+            summary: None,
+            description: Some(String::new()),
+            issue_type: None,
+            assignee: Some("unassigned".to_string()),
+            estimate: None,
+            json: false,
+            fields: Default::default(),
+        };
+
+        let payload = build_issue_update_payload(&args, None);
+
+        assert_eq!(payload["fields"]["description"], Value::Null);
+        assert_eq!(payload["fields"]["assignee"], Value::Null);
+    }
+
+    #[test]
+    fn create_jira_issue_posts_expected_request_for_explicit_assignee() {
+        let args = CreateArgs {
+            project: "RW".to_string(),
+            summary: "Implement backlog creation".to_string(),
+            description: Some("Description text".to_string()),
+            issue_type: "Task".to_string(),
+            assignee: "account-id-123".to_string(),
+            current_sprint: false,
+            board: None,
+            draft: None,
+            template: None,
+            criteria: Vec::new(),
+            interactive: false,
+            capture_env: false,
+            json: false,
+            fields: Default::default(),
+        };
+        let expected_payload = build_issue_create_payload(&args, Some("account-id-123"));
+        let (base_url, requests, handle) =
+            spawn_test_server("HTTP/1.1 201 Created", r#"{"id":"10001","key":"RW-123"}"#);
+        let client = create_jira_client("user@example.com", "token").expect("client");
+
+        let created_issue = create_jira_issue(&client, &base_url, &args, Some("account-id-123"))
+            .expect("issue creation should succeed");
+        let request = requests
+            .recv_timeout(Duration::from_secs(2))
+            .expect("request should be captured");
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(created_issue.id, "10001");
+        assert_eq!(created_issue.key, "RW-123");
+        assert!(request.starts_with("POST /rest/api/3/issue HTTP/1.1"));
+        assert!(
+            request.contains("\r\ncontent-type: application/json\r\n")
+                || request.contains("\r\nContent-Type: application/json\r\n")
+        );
+
+        let body = request
+            .split("\r\n\r\n")
+            .nth(1)
+            .expect("http request should contain a body");
+        let parsed_body: Value =
+            serde_json::from_str(body).expect("request body should be valid json");
+        assert_eq!(parsed_body, expected_payload);
+    }
+
+    #[test]
+    fn update_jira_issue_puts_expected_request() {
+        let args = EditArgs {
+            ticket: Some("RW-123".to_string()),
+            summary: Some("Updated summary".to_string()),
+            description: Some("Description text".to_string()),
+            issue_type: Some("Story".to_string()),
+            assignee: Some("account-id-123".to_string()),
+            estimate: None,
+            json: false,
+            fields: Default::default(),
+        };
+        let expected_payload = build_issue_update_payload(&args, Some("account-id-123"));
+        let (base_url, requests, handle) = spawn_test_server("HTTP/1.1 204 No Content", "");
+        let client = create_jira_client("user@example.com", "token").expect("client");
+
+        update_jira_issue(&client, &base_url, "RW-123", &args, Some("account-id-123"))
+            .expect("issue update should succeed");
+        let request = requests
+            .recv_timeout(Duration::from_secs(2))
+            .expect("request should be captured");
+        handle.join().expect("server thread should finish");
+
+        assert!(request.starts_with("PUT /rest/api/3/issue/RW-123 HTTP/1.1"));
+        let body = request
+            .split("\r\n\r\n")
+            .nth(1)
+            .expect("http request should contain a body");
+        let parsed_body: Value =
+            serde_json::from_str(body).expect("request body should be valid json");
+        assert_eq!(parsed_body, expected_payload);
+    }
+
+    #[test]
+    fn resolve_create_assignee_uses_current_user_by_default() {
+        let (base_url, requests, handle) = spawn_test_server(
+            "HTTP/1.1 200 OK",
+            r#"{"accountId":"account-id-999","displayName":"Cesar Ferreira"}"#,
+        );
+        let client = create_jira_client("user@example.com", "token").expect("client");
+
+        let assignee = resolve_create_assignee(&client, &base_url, "me")
+            .expect("default assignee should resolve");
+        let request = requests
+            .recv_timeout(Duration::from_secs(2))
+            .expect("request should be captured");
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(assignee.account_id.as_deref(), Some("account-id-999"));
+        assert_eq!(assignee.label, "Cesar Ferreira");
+        assert!(request.starts_with("GET /rest/api/3/myself HTTP/1.1"));
+    }
+
+    #[test]
+    fn resolve_create_assignee_accepts_unassigned_without_network_call() {
+        let assignee = resolve_create_assignee(
+            &create_jira_client("user@example.com", "token").expect("client"),
+            "http://127.0.0.1:9",
+            "unassigned",
+        )
+        .expect("unassigned should not require network");
+
+        assert_eq!(assignee.account_id, None);
+        assert_eq!(assignee.label, "Unassigned");
+    }
+
+    #[test]
+    fn resolve_create_assignee_keeps_explicit_account_id() {
+        let assignee = resolve_create_assignee(
+            &create_jira_client("user@example.com", "token").expect("client"),
+            "http://127.0.0.1:9",
+            "account-id-123",
+        )
+        .expect("explicit account ids should be returned as-is");
+
+        assert_eq!(assignee.account_id.as_deref(), Some("account-id-123"));
+        assert_eq!(assignee.label, "account-id-123");
+    }
+
+    #[test]
+    fn resolve_create_assignee_reuses_people_seen_in_earlier_responses() {
+        let (base_url, rx, handle) = spawn_sequence_server(vec![
+            (
+                "HTTP/1.1 200 OK",
+                r#"[{"accountId":"account-id-777","displayName":"Grace Hopper","emailAddress":"grace@example.com"}]"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"id":"1","key":"RW-1","fields":{"summary":"","description":{"type":"doc","content":[{"type":"paragraph","content":[{"type":"mention","attrs":{"id":"account-id-777"}}]}]}}}"#,
+            ),
+        ]);
+        let client = create_jira_client("user@example.com", "token").expect("client");
+
+        let searched = resolve_create_assignee(&client, &base_url, "grace@example.com")
+            .expect("email should resolve through user search");
+        let cached = resolve_create_assignee(&client, &base_url, "Grace Hopper")
+            .expect("name should resolve from the cache");
+        let issue = jit_core::api::fetch_jira_issue(&client, &base_url, "RW-1", false, true, false)
+            .expect("issue should be fetched");
+        let requests = collect_requests(rx, 2);
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(searched.account_id.as_deref(), Some("account-id-777"));
+        assert_eq!(cached.account_id.as_deref(), Some("account-id-777"));
+        assert_eq!(cached.label, "Grace Hopper");
+        assert!(requests[0].starts_with("GET /rest/api/3/user/search?query=grace%40example.com "));
+        assert!(requests[1].starts_with("GET /rest/api/3/issue/RW-1?"));
+        assert_eq!(
+            extract_plain_text_from_adf(issue.fields.description.as_ref().expect("description"))
+                .trim_end(),
+            "@Grace Hopper"
+        );
+    }
+
+    #[test]
+    fn fetch_current_user_assignee_uses_fallback_label_when_display_name_missing() {
+        let (base_url, requests, handle) = spawn_test_server(
+            "HTTP/1.1 200 OK",
+            r#"{"accountId":"account-id-999","displayName":""}"#,
+        );
+        let client = create_jira_client("user@example.com", "token").expect("client");
+
+        let assignee =
+            fetch_current_user_assignee(&client, &base_url).expect("current user should resolve");
+        let request = requests
+            .recv_timeout(Duration::from_secs(2))
+            .expect("request should be captured");
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(assignee.account_id.as_deref(), Some("account-id-999"));
+        assert_eq!(assignee.label, "Current user");
+        assert!(request.starts_with("GET /rest/api/3/myself HTTP/1.1"));
+    }
+
+    #[test]
+    fn resolve_target_sprint_uses_latest_active_sprint_across_project_boards() {
+        let (base_url, requests, handle) = spawn_sequence_server(vec![
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"values":[{"id":10,"name":"Alpha board"},{"id":20,"name":"Beta board"}],"isLast":true,"maxResults":50,"startAt":0}"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"values":[{"id":100,"name":"Alpha Sprint","startDate":"2026-03-01T09:00:00.000+00:00"}],"isLast":true,"maxResults":50,"startAt":0}"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"values":[{"id":200,"name":"Beta Sprint","startDate":"2026-03-05T09:00:00.000+00:00"}],"isLast":true,"maxResults":50,"startAt":0}"#,
+            ),
+        ]);
+        let client = create_jira_client("user@example.com", "token").expect("client");
+        let args = CreateArgs {
+            project: "RW".to_string(),
+            summary: "Implement backlog creation".to_string(),
+            description: None,
+            issue_type: "Task".to_string(),
+            assignee: "me".to_string(),
+            current_sprint: true,
+            board: None,
+            draft: None,
+            template: None,
+            criteria: Vec::new(),
+            interactive: false,
+            capture_env: false,
+            json: false,
+            fields: Default::default(),
+        };
+
+        let sprint = resolve_target_sprint(&client, &base_url, &args)
+            .expect("current sprint should resolve")
+            .expect("current sprint should be present");
+        let requests = collect_requests(requests, 3);
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(sprint.id, 200);
+        assert_eq!(sprint.name, "Beta Sprint");
+        assert_eq!(sprint.board_id, 20);
+        assert_eq!(sprint.board_name, "Beta board");
+        assert!(requests[0].starts_with("GET /rest/agile/1.0/board?projectKeyOrId=RW&type=scrum"));
+        assert!(requests[1].starts_with("GET /rest/agile/1.0/board/10/sprint?state=active"));
+        assert!(requests[2].starts_with("GET /rest/agile/1.0/board/20/sprint?state=active"));
+    }
+
+    #[test]
+    fn resolve_target_sprint_returns_none_when_current_sprint_not_requested() {
+        let client = create_jira_client("user@example.com", "token").expect("client");
+        let args = CreateArgs {
+            project: "RW".to_string(),
+            summary: "Implement backlog creation".to_string(),
+            description: None,
+            issue_type: "Task".to_string(),
+            assignee: "me".to_string(),
+            current_sprint: false,
+            board: None,
+            draft: None,
+            template: None,
+            criteria: Vec::new(),
+            interactive: false,
+            capture_env: false,
+            json: false,
+            fields: Default::default(),
+        };
+
+        let sprint = resolve_target_sprint(&client, "http://127.0.0.1:9", &args)
+            .expect("sprint lookup should be skipped");
+
+        assert!(sprint.is_none());
+    }
+
+    #[test]
+    fn resolve_target_sprint_uses_explicit_board_when_provided() {
+        let (base_url, requests, handle) = spawn_sequence_server(vec![
+            ("HTTP/1.1 200 OK", r#"{"id":42,"name":"Explicit board"}"#),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"values":[{"id":300,"name":"Board Sprint","startDate":"2026-04-01T09:00:00+00:00"}],"isLast":true,"maxResults":50,"startAt":0}"#,
+            ),
+        ]);
+        let client = create_jira_client("user@example.com", "token").expect("client");
+        let args = CreateArgs {
+            project: "RW".to_string(),
+            summary: "Implement backlog creation".to_string(),
+            description: None,
+            issue_type: "Task".to_string(),
+            assignee: "me".to_string(),
+            current_sprint: true,
+            board: Some(42),
+            draft: None,
+            template: None,
+            criteria: Vec::new(),
+            interactive: false,
+            capture_env: false,
+            json: false,
+            fields: Default::default(),
+        };
+
+        let sprint = resolve_target_sprint(&client, &base_url, &args)
+            .expect("sprint should resolve")
+            .expect("sprint should be present");
+        let requests = collect_requests(requests, 2);
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(sprint.id, 300);
+        assert_eq!(sprint.board_id, 42);
+        assert_eq!(sprint.board_name, "Explicit board");
+        assert!(requests[0].starts_with("GET /rest/agile/1.0/board/42 HTTP/1.1"));
+        assert!(requests[1].starts_with("GET /rest/agile/1.0/board/42/sprint?state=active"));
+    }
+
+    #[test]
+    fn fetch_active_sprints_for_board_prefers_latest_active_sprint_across_pages() {
+        let (base_url, requests, handle) = spawn_sequence_server(vec![
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"values":[{"id":100,"name":"Alpha Sprint","startDate":"2026-03-01T09:00:00+00:00"}],"isLast":false,"maxResults":1,"startAt":0}"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"values":[{"id":200,"name":"Beta Sprint","startDate":"2026-03-05T09:00:00+00:00"}],"isLast":true,"maxResults":1,"startAt":1}"#,
+            ),
+        ]);
+        let client = create_jira_client("user@example.com", "token").expect("client");
+
+        let sprint = fetch_active_sprints_for_board(&client, &base_url, 42)
+            .expect("sprint pagination should succeed")
+            .expect("a sprint should be returned");
+        let requests = collect_requests(requests, 2);
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(sprint.id, 200);
+        assert_eq!(sprint.name, "Beta Sprint");
+        assert!(requests[0].contains("/board/42/sprint?state=active&startAt=0"));
+        assert!(requests[1].contains("/board/42/sprint?state=active&startAt=1"));
+    }
+
+    #[test]
+    fn compare_sprint_identity_prefers_newer_dates_then_ids() {
+        assert!(compare_sprint_identity(
+            Some("2026-04-10T09:00:00+00:00"),
+            10,
+            1,
+            Some("2026-04-09T09:00:00+00:00"),
+            99,
+            2,
+        ));
+        assert!(compare_sprint_identity(
+            Some("2026-04-10T09:00:00+00:00"),
+            10,
+            2,
+            Some("2026-04-10T09:00:00+00:00"),
+            10,
+            1,
+        ));
+        assert!(!compare_sprint_identity(
+            None,
+            10,
+            1,
+            Some("2026-04-10T09:00:00+00:00"),
+            5,
+            1,
+        ));
+    }
+
+    #[test]
+    fn parse_jira_datetime_accepts_rfc3339_values() {
+        let parsed = parse_jira_datetime("2026-04-10T09:00:00+00:00")
+            .expect("valid RFC3339 values should parse");
+
+        assert_eq!(parsed.to_rfc3339(), "2026-04-10T09:00:00+00:00");
+    }
+
+    #[test]
+    fn fetch_my_tickets_searches_active_sprint_jql() {
+        let (base_url, requests, handle) = spawn_test_server(
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"10001","key":"RW-123","fields":{"summary":"Implement backlog creation","status":{"name":"In Progress"},"customfield_10020":[{"name":"Sprint 42","state":"active"}]}}]}"#,
+        );
+        let client = create_jira_client("user@example.com", "token").expect("client");
+        site_fields::set_sprint_field(&base_url, SPRINT_FIELD);
+
+        let jira = JiraClient::new(client, &base_url);
+        let issues = fetch_my_tickets(&jira, 7).expect("ticket fetch should succeed");
+        let request = requests
+            .recv_timeout(Duration::from_secs(2))
+            .expect("request should be captured");
+        handle.join().expect("server thread should finish");
+
+        let target = request
+            .split_whitespace()
+            .nth(1)
+            .expect("http request should have a target");
+        let url = reqwest::Url::parse(&format!("{}{}", base_url, target))
+            .expect("request target should be a valid path");
+        let query: HashMap<_, _> = url.query_pairs().collect();
+
+        assert_eq!(issues.len(), 1);
+        assert!(request.starts_with("GET /rest/api/3/search/jql?"));
+        assert_eq!(
+            query["jql"],
+            "assignee = currentUser() AND sprint in openSprints() ORDER BY updated DESC"
+        );
+        assert_eq!(query["maxResults"], "7");
+    }
+
+    #[test]
+    fn token_origin_names_where_the_token_was_read_from() {
+        let path = Path::new("/home/me/.config/jit/config.toml");
+        let table: toml::Table = "[jira]\napi_token = \"a\"\n\n[profiles.work.jira]\napi_token = \"b\"\n\n[auth]\nsource = \"pass\"\nitem = \"work/jira\"\n"
+            .parse()
+            .unwrap();
+        let origin = |file_token: &str, profile: Option<&str>, overridden: &[String]| {
+            token_origin(&table, file_token, profile, overridden, path).to_string()
+        };
+
+        assert_eq!(
+            origin("a", None, &[]),
+            "jira.api_token in /home/me/.config/jit/config.toml"
+        );
+        assert_eq!(
+            origin("b", Some("work"), &[]),
+            "profiles.work.jira.api_token in /home/me/.config/jit/config.toml"
+        );
+        assert_eq!(
+            origin("a", None, &["jira.api_token".to_string()]),
+            "the JIT_JIRA_API_TOKEN environment variable"
+        );
+        assert_eq!(
+            origin("${JIRA_TOKEN}", None, &[]),
+            "the environment variable jira.api_token refers to (${JIRA_TOKEN})"
+        );
+        assert_eq!(origin("", None, &[]), "pass (work/jira)");
+        assert_eq!(
+            token_origin(&toml::Table::new(), "", None, &[], path),
+            TokenOrigin::Keyring
+        );
+    }
+
+    #[test]
+    fn fetch_my_tickets_checks_credentials_only_when_the_sprint_is_empty() {
+        let empty = MockJira::new();
+        let err = fetch_my_tickets(&empty, 5).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<JitError>(),
+            Some(JitError::Auth(_))
+        ));
+        assert_eq!(
+            empty.searches(),
+            ["assignee = currentUser() AND sprint in openSprints() ORDER BY updated DESC"]
+        );
+
+        let signed_in = MockJira::new().with_user(JiraUser::default());
+        assert!(fetch_my_tickets(&signed_in, 5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn my_tickets_render_from_the_fetched_issues() {
+        let jira = MockJira::new()
+            .with_issue(json!({
+                "id": "1",
+                "key": "RW-1",
+                "fields": {
+                    "summary": "Fix login",
+                    "status": {"name": "In Progress"},
+                    "customfield_10020": [{"name": "Sprint 42", "state": "active"}]
+                }
+            }))
+            .with_issue(json!({"id": "2", "key": "RW-2", "fields": {"summary": "Add SSO"}}))
+            .with_issue(json!({"id": "3", "key": "RW-3", "fields": {"summary": "Not shown"}}));
 
-    println!(
-        "{:<left$} {:<val$} {:<left$} {:<val$}",
-        "Assignee:".bold(),
-        assignee,
-        "Reporter:".bold(),
-        reporter,
-        left = left_col_width,
-        val = val_col_width
-    );
+        let tickets = fetch_my_tickets(&jira, 2).unwrap();
+        let view = IssueListView::from_issues(&tickets, None);
 
-    println!(
-        "{:<left$} {:<val$} {:<left$} {:<val$}",
-        "Created:".bold(),
-        format_date(created),
-        "Updated:".bold(),
-        format_date(updated),
-        left = left_col_width,
-        val = val_col_width
-    );
+        assert_eq!(
+            view.to_json(),
+            json!([
+                {"ticket": "RW-1", "summary": "Fix login", "status": "In Progress"},
+                {"ticket": "RW-2", "summary": "Add SSO", "status": "Unknown"},
+            ])
+        );
+        let (headers, rows) = view.to_rows();
+        assert_eq!(headers, ["Key", "Summary", "Status"]);
+        assert_eq!(rows[1], ["RW-2", "Add SSO", "Unknown"]);
+    }
 
-    println!(
-        "{:<left$} {:<val$}",
-        "Due Date:".bold(),
-        format_date(due_date),
-        left = left_col_width,
-        val = val_col_width
-    );
+    #[test]
+    fn fetch_issue_pull_requests_flattens_dev_status_response() {
+        let (base_url, requests, handle) = spawn_test_server(
+            "HTTP/1.1 200 OK",
+            r#"{"detail":[{"pullRequests":[{"id":"1","name":"First PR","status":"OPEN","url":"https://github.com/org/repo/pull/1","lastUpdate":"2026-04-10T09:00:00.000+00:00"}]},{"pullRequests":[{"id":"2","name":"Second PR","status":"MERGED","url":"https://github.com/org/repo/pull/2","lastUpdate":"2026-04-10T10:00:00.000+00:00"}]}]}"#,
+        );
+        let client = create_jira_client("user@example.com", "token").expect("client");
 
-    if include_description {
-        println!();
-        println!("{}", "DESCRIPTION".bold());
-        println!();
+        let pull_requests = fetch_issue_pull_requests(&client, &base_url, "10001")
+            .expect("PR fetch should succeed");
+        let request = requests
+            .recv_timeout(Duration::from_secs(2))
+            .expect("request should be captured");
+        handle.join().expect("server thread should finish");
 
-        // Print the description (if available)
-        match &issue.fields.description {
-            Some(desc) => {
-                if desc.is_null() {
-                    println!("No description provided.");
-                } else {
-                    println!("{}", adf_value_to_display_text(desc));
-                }
-            }
-            None => println!("No description provided."),
-        }
+        assert_eq!(pull_requests.len(), 2);
+        assert_eq!(pull_requests[0].name.as_deref(), Some("First PR"));
+        assert_eq!(pull_requests[1].name.as_deref(), Some("Second PR"));
+        assert!(request.starts_with("GET /rest/dev-status/latest/issue/detail?issueId=10001"));
     }
 
-    if include_comments {
-        println!();
-        println!("{}", "COMMENTS".bold());
-        println!();
+    #[test]
+    fn fetch_pull_requests_for_tickets_maps_results_by_ticket_key() {
+        let (base_url, requests, handle) = spawn_sequence_server(vec![
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"detail":[{"pullRequests":[{"id":"1","name":"First PR","status":"OPEN","url":"https://github.com/org/repo/pull/1","lastUpdate":"2026-04-10T09:00:00.000+00:00"}]}]}"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"detail":[{"pullRequests":[{"id":"2","name":"Second PR","status":"MERGED","url":"https://github.com/org/repo/pull/2","lastUpdate":"2026-04-10T10:00:00.000+00:00"}]}]}"#,
+            ),
+        ]);
+        let client = create_jira_client("user@example.com", "token").expect("client");
+        let tickets = vec![
+            sample_issue_with_summary("10001", "RW-123", "First issue"),
+            sample_issue_with_summary("10002", "RW-124", "Second issue"),
+        ];
 
-        let comments = get_filtered_comments(issue, since, comments_limit, all_comments);
-        if comments.is_empty() {
-            if since.is_some() {
-                println!("No comments found for the provided filters.");
-            } else {
-                println!("No comments found.");
-            }
-        } else {
-            for (index, comment) in comments.iter().enumerate() {
-                let author = comment
-                    .author
-                    .as_ref()
-                    .map_or("Unknown", |a| a.display_name.as_str());
-                let created = comment.created.as_deref().unwrap_or("Unknown");
-                let updated = comment.updated.as_deref().unwrap_or("Unknown");
+        let mapped = fetch_pull_requests_for_tickets(&client, &base_url, &tickets, false)
+            .expect("PR mapping should succeed");
+        let requests = collect_requests(requests, 2);
+        handle.join().expect("server thread should finish");
 
-                println!(
-                    "#{} {} | created: {} | updated: {}",
-                    index + 1,
-                    author.bold(),
-                    created,
-                    updated
-                );
+        assert_eq!(mapped["RW-123"].len(), 1);
+        assert_eq!(mapped["RW-124"].len(), 1);
+        assert!(requests[0].contains("issueId=10001"));
+        assert!(requests[1].contains("issueId=10002"));
+    }
 
-                match &comment.body {
-                    Some(body) if !body.is_null() => {
-                        println!("{}", adf_value_to_display_text(body))
-                    }
-                    _ => println!("(No comment body)"),
-                }
+    #[test]
+    fn fetch_issue_ci_status_reports_the_latest_build() {
+        let (base_url, requests, handle) = spawn_sequence_server(vec![
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"summary":{"build":{"overall":{"count":3,"successfulBuildCount":2,"failedBuildCount":1,"unknownBuildCount":0,"lastUpdated":"2026-04-10T11:00:00.000+00:00"},"byInstanceType":{"bamboo":{}}}}}"#,
+            ),
+            (
+                "HTTP/1.1 200 OK",
+                r#"{"detail":[{"builds":[{"name":"nightly","state":"FAILED","lastUpdated":"2026-04-09T08:00:00.000+00:00"},{"name":"main","state":"SUCCESSFUL","url":"https://ci.example.com/42","lastUpdated":"2026-04-10T11:00:00.000+00:00"}]}]}"#,
+            ),
+        ]);
+        let client = create_jira_client("user@example.com", "token").expect("client");
 
-                if index < comments.len() - 1 {
-                    println!();
-                }
-            }
-        }
+        let ci = fetch_issue_ci_status(&client, &base_url, "10001")
+            .expect("CI status fetch should succeed");
+        let requests = collect_requests(requests, 2);
+        handle.join().expect("server thread should finish");
+
+        assert_eq!(ci.summary.count, 3);
+        assert_eq!(ci.state(), BuildState::Passing);
+        assert_eq!(
+            format_ci_status(&ci),
+            "PASSING: main (2 passed, 1 failed, 0 pending)"
+        );
+        assert!(requests[0].starts_with("GET /rest/dev-status/latest/issue/summary?issueId=10001"));
+        assert!(requests[1].starts_with(
+            "GET /rest/dev-status/latest/issue/detail?issueId=10001&applicationType=bamboo&dataType=build"
+        ));
     }
 
-    if include_prs {
-        println!();
-        println!("{}", "PULL REQUESTS".bold());
-        println!();
+    #[test]
+    fn ci_status_state_follows_the_latest_build() {
+        let mut ci = CiStatus::default();
+        assert_eq!(ci.state(), BuildState::None);
+        assert_eq!(format_ci_status(&ci), "No builds reported");
 
-        if pull_requests.is_empty() {
-            println!("No pull requests found.");
-        } else {
-            for (index, pr) in pull_requests.iter().enumerate() {
-                let pr_id = pull_request_display_id(pr);
-                let title = pr.name.as_deref().unwrap_or("Untitled PR");
-                let status = pr.status.as_deref().unwrap_or("Unknown");
-                let last_update = pr.last_update.as_deref().unwrap_or("Unknown");
-                let url = pr.url.as_deref().unwrap_or("No URL");
+        ci.summary.count = 1;
+        assert_eq!(ci.state(), BuildState::Unknown);
 
-                println!(
-                    "#{} {} [{}] | updated: {}",
-                    index + 1,
-                    pr_id.bold(),
-                    status,
-                    last_update
-                );
-                println!("{}", title);
-                println!("{}", url);
+        ci.latest = Some(JiraBuild {
+            state: JiraBuildState::InProgress,
+            ..JiraBuild::default()
+        });
+        assert_eq!(ci.state(), BuildState::Running);
 
-                if index < pull_requests.len() - 1 {
-                    println!();
-                }
-            }
-        }
+        ci.latest = Some(JiraBuild {
+            state: JiraBuildState::Failed,
+            ..JiraBuild::default()
+        });
+        assert_eq!(ci.state(), BuildState::Failing);
     }
 
-    Ok(())
-}
+    #[test]
+    fn truncate_with_ellipsis_shortens_long_strings() {
+        let truncated = truncate_with_ellipsis("abcdefghijklmnopqrstuvwxyz", 10);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use std::io::{Read, Write};
-    use std::net::TcpListener;
-    use std::sync::mpsc;
-    use std::thread;
-    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+        assert_eq!(truncated, "abcdefg...");
+    }
 
     #[test]
-    fn cli_parses_create_command() {
-        let cli = Cli::try_parse_from([
-            "jit",
-            "create",
-            "--project",
-            "RW",
-            "--summary",
-            "Implement backlog creation",
-            "--description",
-            "First line\nSecond line",
-            "--type",
-            "Story",
-            "--assignee",
-            "account-id-123",
-            "--current-sprint",
-            "--board",
-            "456",
-            "--json",
-        ])
-        .expect("create command should parse");
+    fn extract_pr_id_from_url_returns_pull_number() {
+        let pr_id = extract_pr_id_from_url("https://github.com/org/repo/pull/123/files");
 
-        match cli.command {
-            Some(Commands::Create(args)) => {
-                assert_eq!(args.project, "RW");
-                assert_eq!(args.summary, "Implement backlog creation");
-                assert_eq!(args.description.as_deref(), Some("First line\nSecond line"));
-                assert_eq!(args.issue_type, "Story");
-                assert_eq!(args.assignee, "account-id-123");
-                assert!(args.current_sprint);
-                assert_eq!(args.board, Some(456));
-                assert!(args.json);
-            }
-            _ => panic!("expected create command"),
-        }
+        assert_eq!(pr_id.as_deref(), Some("#123"));
     }
 
     #[test]
-    fn cli_parses_edit_command() {
-        let cli = Cli::try_parse_from([
-            "jit",
-            "edit",
-            "RW-123",
-            "--summary",
-            "Updated summary",
-            "--description",
-            "First line\nSecond line",
-            "--type",
-            "Bug",
-            "--assignee",
-            "unassigned",
-            "--json",
-        ])
-        .expect("edit command should parse");
+    fn extract_pr_id_from_url_supports_bitbucket_pull_requests() {
+        let pr_id = extract_pr_id_from_url("https://bitbucket.org/acme/web/pull-requests/7/diff");
 
-        match cli.command {
-            Some(Commands::Edit(args)) => {
-                assert_eq!(args.ticket, "RW-123");
-                assert_eq!(args.summary.as_deref(), Some("Updated summary"));
-                assert_eq!(args.description.as_deref(), Some("First line\nSecond line"));
-                assert_eq!(args.issue_type.as_deref(), Some("Bug"));
-                assert_eq!(args.assignee.as_deref(), Some("unassigned"));
-                assert!(args.json);
-            }
-            _ => panic!("expected edit command"),
-        }
+        assert_eq!(pr_id.as_deref(), Some("#7"));
     }
 
     #[test]
-    fn cli_parses_skill_install_command() {
-        let cli = Cli::try_parse_from(["jit", "skill", "install"])
-            .expect("skill install command should parse");
+    fn build_approval_request_adf_mentions_each_approver() {
+        let adf = build_approval_request_adf(
+            &["account-1".to_string(), "account-2".to_string()],
+            Some("Release 2.5 is ready"),
+        );
 
-        match cli.command {
-            Some(Commands::Skill(args)) => match args.command {
-                SkillCommands::Install(install_args) => {
-                    assert!(install_args.agent.is_none());
-                }
-            },
-            _ => panic!("expected skill install command"),
-        }
+        let content = &adf["content"][0]["content"];
+        assert_eq!(content[0]["text"], "Approval requested from ");
+        assert_eq!(content[1]["type"], "mention");
+        assert_eq!(content[1]["attrs"]["id"], "account-1");
+        assert_eq!(content[2]["text"], ", ");
+        assert_eq!(content[3]["attrs"]["id"], "account-2");
+        assert_eq!(
+            adf["content"][1]["content"][0]["text"],
+            "Release 2.5 is ready"
+        );
     }
 
     #[test]
-    fn cli_parses_skill_install_with_agent_filter() {
-        let cli = Cli::try_parse_from(["jit", "skill", "install", "--agent", "codex"])
-            .expect("skill install --agent should parse");
+    fn parse_checklist_detects_text_items_and_adf_formats() {
+        let text = json!("Release steps\n- [x] Tag build\n- [ ] Update changelog\n* [X] Notify QA");
+        assert_eq!(detect_checklist_format(&text), Some(ChecklistFormat::Text));
+        assert_eq!(
+            parse_checklist(&text, ChecklistFormat::Text),
+            vec![
+                ChecklistItem {
+                    text: "Tag build".to_string(),
+                    checked: true
+                },
+                ChecklistItem {
+                    text: "Update changelog".to_string(),
+                    checked: false
+                },
+                ChecklistItem {
+                    text: "Notify QA".to_string(),
+                    checked: true
+                },
+            ]
+        );
 
-        match cli.command {
-            Some(Commands::Skill(args)) => match args.command {
-                SkillCommands::Install(install_args) => {
-                    assert_eq!(install_args.agent.as_deref(), Some("codex"));
-                }
-            },
-            _ => panic!("expected skill install command"),
+        let items = json!([
+            {"id": 1, "name": "Tag build", "checked": true},
+            {"id": 2, "name": "Update changelog", "status": "open"}
+        ]);
+        assert_eq!(
+            detect_checklist_format(&items),
+            Some(ChecklistFormat::Items)
+        );
+        let parsed = parse_checklist(&items, ChecklistFormat::Items);
+        assert!(parsed[0].checked);
+        assert!(!parsed[1].checked);
+
+        let adf = json!({"type": "doc", "version": 1, "content": [{"type": "taskList", "content": [
+            {"type": "taskItem", "attrs": {"localId": "a", "state": "DONE"}, "content": [{"type": "text", "text": "Tag build"}]},
+            {"type": "taskItem", "attrs": {"localId": "b", "state": "TODO"}, "content": [{"type": "text", "text": "Update changelog"}]}
+        ]}]});
+        assert_eq!(detect_checklist_format(&adf), Some(ChecklistFormat::Adf));
+        let parsed = parse_checklist(&adf, ChecklistFormat::Adf);
+        assert_eq!(parsed[1].text, "Update changelog");
+        assert!(!parsed[1].checked);
+    }
+
+    #[test]
+    fn acceptance_criteria_read_checklists_or_one_item_per_line() {
+        let criteria = ["Can log in".to_string(), "Sees the dashboard".to_string()];
+        for format in [
+            ChecklistFormat::Text,
+            ChecklistFormat::Items,
+            ChecklistFormat::Adf,
+        ] {
+            let value = checklist_value(&criteria, format);
+            let items: Vec<String> = acceptance_criteria_items(&value)
+                .into_iter()
+                .map(|item| item.text)
+                .collect();
+            assert_eq!(items, criteria, "{:?}", format);
         }
+
+        let bullets = json!("Given a user\n- Can log in\n  2. Sees the dashboard\n");
+        let items = acceptance_criteria_items(&bullets);
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[2].text, "Sees the dashboard");
+        assert!(items.iter().all(|item| !item.checked));
+        assert!(acceptance_criteria_items(&Value::Null).is_empty());
     }
 
     #[test]
-    fn cli_parses_config_file_flag() {
-        let cli = Cli::try_parse_from(["jit", "--config-file", "/tmp/jit-config.toml", "RW-123"])
-            .expect("config file flag should parse");
+    fn toggle_checklist_item_preserves_surrounding_content() {
+        let text = json!("Release steps\n- [x] Tag build\n- [ ] Update changelog");
+        assert_eq!(
+            toggle_checklist_item(&text, ChecklistFormat::Text, 1),
+            json!("Release steps\n- [x] Tag build\n- [x] Update changelog")
+        );
 
+        let items = json!([
+            {"id": 1, "name": "Tag build", "checked": true, "mandatory": true},
+            {"id": 2, "name": "Update changelog", "status": "open"}
+        ]);
+        let toggled = toggle_checklist_item(&items, ChecklistFormat::Items, 0);
+        assert_eq!(toggled[0]["checked"], false);
+        assert_eq!(toggled[0]["mandatory"], true);
+        let toggled = toggle_checklist_item(&items, ChecklistFormat::Items, 1);
+        assert_eq!(toggled[1]["status"], "done");
+
+        let adf = json!({"type": "doc", "version": 1, "content": [{"type": "taskList", "content": [
+            {"type": "taskItem", "attrs": {"localId": "a", "state": "DONE"}, "content": []},
+            {"type": "taskItem", "attrs": {"localId": "b", "state": "TODO"}, "content": []}
+        ]}]});
+        let toggled = toggle_checklist_item(&adf, ChecklistFormat::Adf, 1);
         assert_eq!(
-            cli.query.config_file,
-            Some(PathBuf::from("/tmp/jit-config.toml"))
+            toggled["content"][0]["content"][0]["attrs"]["state"],
+            "DONE"
         );
-        assert_eq!(cli.query.ticket.as_deref(), Some("RW-123"));
+        assert_eq!(
+            toggled["content"][0]["content"][1]["attrs"]["state"],
+            "DONE"
+        );
+        assert_eq!(toggled["content"][0]["content"][1]["attrs"]["localId"], "b");
     }
 
     #[test]
-    fn cli_defaults_my_tickets_limit_to_full_sprint_view() {
-        let cli = Cli::try_parse_from(["jit", "--my-tickets"])
-            .expect("my tickets flag should parse");
+    fn build_shared_issue_redacts_people_emails_and_patterns() {
+        let issue: JiraIssue = serde_json::from_value(json!({
+            "id": "1",
+            "key": "RW-1",
+            "fields": {
+                "summary": "Login fails for ana@example.com",
+                "assignee": {"displayName": "Ana Maria"},
+                "reporter": {"displayName": "Bo"},
+                "description": {"type": "doc", "content": [{"type": "paragraph", "content": [
+                    {"type": "mention", "attrs": {"id": "a-3", "text": "@Cy"}},
+                    {"type": "text", "text": " see https://example.atlassian.net/browse/RW-2 token sk-12345"}
+                ]}]},
+                "comment": {"comments": [{
+                    "author": {"displayName": "Bo"},
+                    "created": "2026-03-01T09:00:00.000+0000",
+                    "body": {"type": "doc", "content": [{"type": "paragraph", "content": [
+                        {"type": "text", "text": "Ana Maria, can you check?"}
+                    ]}]}
+                }]}
+            }
+        }))
+        .expect("issue should parse");
+        let redactor = Redactor::new("https://example.atlassian.net/", &[r"sk-\d+".to_string()])
+            .expect("redactor");
 
-        assert_eq!(cli.query.limit, 50);
+        let shared = build_shared_issue(&issue, Some(redactor));
+
+        assert_eq!(shared.summary, "Login fails for [email]");
+        assert_eq!(shared.assignee.as_deref(), Some("Person 1"));
+        assert_eq!(shared.reporter.as_deref(), Some("Person 2"));
+        assert_eq!(
+            shared.description.as_deref(),
+            Some("@Person 3 see [jira]/browse/RW-2 token [redacted]")
+        );
+        assert_eq!(shared.comments[0].author.as_deref(), Some("Person 2"));
+        assert_eq!(shared.comments[0].body, "Person 1, can you check?");
+
+        let markdown = format_shared_issue_markdown(&shared);
+        assert!(markdown.starts_with("# RW-1: Login fails for [email]\n"));
+        assert!(markdown.contains("- **Assignee:** Person 1"));
+        assert!(markdown.contains("\n**Person 2** (2026-03-01)\n\nPerson 1, can you check?\n"));
     }
 
     #[test]
-    fn read_config_file_loads_jira_credentials_from_toml() {
-        let unique_id = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("system clock should be after epoch")
-            .as_nanos();
-        let path = std::env::temp_dir().join(format!("jit-config-{unique_id}.toml"));
-
-        fs::write(
-            &path,
-            r#"[jira]
-base_url = "https://example.atlassian.net"
-api_token = "token-123"
-user_email = "user@example.com"
-"#,
+    fn issue_draft_round_trips_through_the_editor_format() {
+        let draft = parse_issue_draft(
+            "```json\n{\"summary\":\"Checkout times out\",\"description\":\"Users report timeouts.\",\"acceptance_criteria\":[\"Checkout completes in 2s\"]}\n```",
         )
-        .expect("temp config should be written");
+        .expect("draft should parse");
+
+        let text = format_issue_draft(&draft);
+        let (summary, description) = parse_edited_draft(&text).expect("draft should read back");
+
+        assert_eq!(summary, "Checkout times out");
+        assert_eq!(
+            description.as_deref(),
+            Some("Users report timeouts.\n\nAcceptance criteria:\n- Checkout completes in 2s")
+        );
+        assert!(parse_edited_draft("# only comments\n\n").is_err());
+    }
 
-        let config = read_config_file(&path).expect("config should parse");
-        fs::remove_file(&path).expect("temp config should be removed");
+    #[test]
+    fn render_snippet_fills_variables_and_reports_missing_ones() {
+        let vars = vec![
+            ("env".to_string(), "staging".to_string()),
+            ("build".to_string(), "1042".to_string()),
+        ];
 
-        assert_eq!(config.base_url, "https://example.atlassian.net");
-        assert_eq!(config.api_token, "token-123");
-        assert_eq!(config.user_email, "user@example.com");
+        assert_eq!(
+            render_snippet("deployed", "Deployed to {env} in build {build}", &vars)
+                .expect("snippet should render"),
+            "Deployed to staging in build 1042"
+        );
+        let err = render_snippet("deployed", "Deployed to {env} by {owner}", &vars[..1])
+            .expect_err("owner is missing");
+        assert_eq!(err.to_string(), "Snippet 'deployed' needs --var owner=...");
+        assert_eq!(
+            parse_key_value("note=a=b"),
+            Ok(("note".to_string(), "a=b".to_string()))
+        );
+        assert!(parse_key_value("missing-equals").is_err());
     }
 
     #[test]
-    fn config_path_from_home_uses_dot_config_jit_directory() {
-        let path = config_path_from_home(Path::new("/Users/example"));
+    fn parse_commit_directives_reads_smart_commit_commands() {
+        let directives = parse_commit_directives(
+            "RW-1 RW-2 #comment Fixed the crash #time 1h 30m Investigated #in-review\n\nAlso touches RW-3\nSee #123 for context",
+        );
 
         assert_eq!(
-            path,
-            PathBuf::from("/Users/example/.config/jit/config.toml")
+            directives,
+            vec![
+                (
+                    "RW-1".to_string(),
+                    CommitDirective::Comment("Fixed the crash".to_string())
+                ),
+                (
+                    "RW-1".to_string(),
+                    CommitDirective::Time {
+                        spent: "1h 30m".to_string(),
+                        comment: Some("Investigated".to_string())
+                    }
+                ),
+                (
+                    "RW-1".to_string(),
+                    CommitDirective::Transition {
+                        name: "in review".to_string(),
+                        comment: None
+                    }
+                ),
+                (
+                    "RW-2".to_string(),
+                    CommitDirective::Comment("Fixed the crash".to_string())
+                ),
+                (
+                    "RW-2".to_string(),
+                    CommitDirective::Time {
+                        spent: "1h 30m".to_string(),
+                        comment: Some("Investigated".to_string())
+                    }
+                ),
+                (
+                    "RW-2".to_string(),
+                    CommitDirective::Transition {
+                        name: "in review".to_string(),
+                        comment: None
+                    }
+                ),
+                ("RW-3".to_string(), CommitDirective::Reference),
+            ]
         );
     }
 
     #[test]
-    fn extract_ticket_id_accepts_plain_ticket_keys() {
-        let ticket = extract_ticket_id("RW-123").expect("plain ticket keys should be accepted");
+    fn parse_commit_directives_skips_references_for_tickets_with_commands() {
+        let directives = parse_commit_directives("RW-1: fix login\n\nRW-1 #done");
 
-        assert_eq!(ticket, "RW-123");
+        assert_eq!(
+            directives,
+            vec![(
+                "RW-1".to_string(),
+                CommitDirective::Transition {
+                    name: "done".to_string(),
+                    comment: None
+                }
+            )]
+        );
     }
 
     #[test]
-    fn extract_ticket_id_extracts_ticket_from_browse_url() {
-        let ticket = extract_ticket_id("https://example.atlassian.net/browse/RW-123/")
-            .expect("browse URLs should parse");
+    fn build_release_train_groups_tickets_and_checks_readiness() {
+        let issues: Vec<JiraIssue> = serde_json::from_value(json!([
+            {"id": "1", "key": "RW-1", "fields": {
+                "summary": "Merged work",
+                "status": {"name": "Done", "statusCategory": {"key": "done"}},
+                "components": [{"name": "Payments"}, {"name": "API"}]
+            }},
+            {"id": "2", "key": "RW-2", "fields": {
+                "summary": "Open PR",
+                "status": {"name": "Done", "statusCategory": {"key": "done"}}
+            }}
+        ]))
+        .expect("issues should parse");
+        let pull_request = |status: &str, url: &str| JiraPullRequest {
+            status: Some(status.to_string()),
+            url: Some(url.to_string()),
+            ..JiraPullRequest::default()
+        };
+        let pull_requests = HashMap::from([
+            (
+                "RW-1".to_string(),
+                vec![pull_request("MERGED", "https://github.com/org/api/pull/1")],
+            ),
+            (
+                "RW-2".to_string(),
+                vec![
+                    pull_request("MERGED", "https://github.com/org/web/pull/2"),
+                    pull_request("OPEN", "https://github.com/org/api/pull/3"),
+                ],
+            ),
+        ]);
 
-        assert_eq!(ticket, "RW-123");
+        let by_component = build_release_train(&issues, &pull_requests, TrainGrouping::Component);
+        assert_eq!(
+            by_component.keys().collect::<Vec<_>>(),
+            vec!["API", "No component", "Payments"]
+        );
+        assert!(by_component["Payments"][0].is_ready());
+        assert!(!by_component["No component"][0].is_ready());
+        assert_eq!(
+            by_component["No component"][0].pull_request_summary(),
+            "1 merged, 1 open"
+        );
+
+        let by_repo = build_release_train(&issues, &pull_requests, TrainGrouping::Repo);
+        assert_eq!(
+            by_repo.keys().collect::<Vec<_>>(),
+            vec!["org/api", "org/web"]
+        );
+        assert_eq!(by_repo["org/api"].len(), 2);
     }
 
     #[test]
-    fn extract_ticket_id_rejects_invalid_jira_urls() {
-        let error = extract_ticket_id("https://example.atlassian.net/issues/RW-123")
-            .expect_err("non-browse URLs should be rejected");
+    fn environment_field_update_matches_field_type() {
+        assert_eq!(
+            environment_field_update("customfield_1", EnvironmentFieldType::Text, "prod"),
+            json!({ "fields": { "customfield_1": "prod" } })
+        );
+        assert_eq!(
+            environment_field_update("customfield_1", EnvironmentFieldType::Select, "prod"),
+            json!({ "fields": { "customfield_1": { "value": "prod" } } })
+        );
+        assert_eq!(
+            environment_field_update("customfield_1", EnvironmentFieldType::MultiSelect, "prod"),
+            json!({ "update": { "customfield_1": [{ "add": { "value": "prod" } }] } })
+        );
+    }
 
-        assert!(
-            error
-                .to_string()
-                .contains("Could not extract ticket ID from URL")
+    #[test]
+    fn build_webhook_payload_includes_issues_and_data() {
+        assert_eq!(build_webhook_payload(&[], &[]), json!({}));
+        assert_eq!(
+            build_webhook_payload(
+                &["RW-1".to_string()],
+                &[("env".to_string(), "prod".to_string())]
+            ),
+            json!({ "issues": ["RW-1"], "data": { "env": "prod" } })
         );
     }
 
     #[test]
-    fn validate_since_date_accepts_yyyy_mm_dd_dates() {
-        validate_since_date("2026-04-22").expect("valid dates should pass");
+    fn evaluate_watch_rule_fires_when_field_becomes_target_value() {
+        let rule: WatchRule = toml::from_str(
+            "name = \"urgent\"\nfield = \"priority\"\nequals = \"Highest\"\nrun = \"true\"\n",
+        )
+        .expect("rule should parse");
+        let issues = |priority: &str| -> Vec<JiraIssue> {
+            serde_json::from_value(json!([
+                {"id": "1", "key": "RW-1", "fields": {"summary": "One", "priority": {"name": priority}}},
+                {"id": "2", "key": "RW-2", "fields": {"summary": "Two", "priority": {"name": "Highest"}}}
+            ]))
+            .expect("issues should parse")
+        };
+
+        let (firings, baseline) = evaluate_watch_rule(&rule, &issues("Medium"), None);
+        assert!(firings.is_empty());
+
+        let (firings, seen) = evaluate_watch_rule(&rule, &issues("Highest"), Some(&baseline));
+        assert_eq!(
+            firings,
+            vec![WatchFiring {
+                ticket: "RW-1".to_string(),
+                summary: "One".to_string(),
+                value: "Highest".to_string(),
+                previous: Some("Medium".to_string()),
+            }]
+        );
+
+        let (firings, _) = evaluate_watch_rule(&rule, &issues("Highest"), Some(&seen));
+        assert!(firings.is_empty());
     }
 
     #[test]
-    fn validate_since_date_rejects_invalid_date_format() {
-        let error =
-            validate_since_date("22-04-2026").expect_err("invalid date formats should fail");
+    fn issue_field_text_reads_custom_select_fields() {
+        let issue: JiraIssue = serde_json::from_value(json!({
+            "id": "1",
+            "key": "RW-1",
+            "fields": {
+                "summary": "One",
+                "customfield_1": {"value": "Production"},
+                "customfield_2": [{"value": "iOS"}, {"value": "Android"}],
+                "customfield_3": 5
+            }
+        }))
+        .expect("issue should parse");
 
-        assert!(error.to_string().contains("Use YYYY-MM-DD"));
+        assert_eq!(issue_field_text(&issue, "customfield_1"), "Production");
+        assert_eq!(issue_field_text(&issue, "customfield_2"), "iOS, Android");
+        assert_eq!(issue_field_text(&issue, "customfield_3"), "5");
+        assert_eq!(issue_field_text(&issue, "priority"), "");
     }
 
     #[test]
-    fn resolve_config_path_prefers_explicit_config_file() {
-        let unique_id = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("system clock should be after epoch")
-            .as_nanos();
-        let path = std::env::temp_dir().join(format!("jit-config-path-{unique_id}.toml"));
-        fs::write(&path, "[jira]\nbase_url = \"https://example.atlassian.net\"\napi_token = \"token\"\nuser_email = \"user@example.com\"\n")
-            .expect("temp config should be written");
+    fn and_jql_keeps_order_by_last() {
+        assert_eq!(
+            and_jql(
+                "project = RW OR labels = x ORDER BY created ASC",
+                "assignee = currentUser()"
+            ),
+            "(project = RW OR labels = x) AND assignee = currentUser() ORDER BY created ASC"
+        );
+        assert_eq!(
+            and_jql("order by key", "assignee is EMPTY"),
+            "assignee is EMPTY order by key"
+        );
+    }
 
-        let args = QueryArgs {
-            ticket: None,
-            json: false,
-            text: false,
-            my_tickets: false,
-            show: false,
-            include_description: false,
-            include_comments: false,
-            include_prs: false,
-            full: false,
-            comments_limit: 5,
-            all_comments: false,
-            since: None,
-            limit: 10,
-            config_file: Some(path.clone()),
+    #[test]
+    fn assignee_filter_clause_resolves_team_members_and_unassigned() {
+        let config: AppConfig = toml::from_str(
+            "[jira]\nbase_url = \"http://127.0.0.1:9\"\napi_token = \"t\"\nuser_email = \"u@example.com\"\n\n[team]\nmembers = [\"account-1\", \"account-2\"]\n",
+        )
+        .expect("config should parse");
+        let client = create_jira_client("user@example.com", "token").expect("client");
+        let filter = AssigneeFilterArgs {
+            assignee: vec!["unassigned".to_string()],
+            team: true,
+            ..AssigneeFilterArgs::default()
         };
 
-        let resolved = resolve_config_path(&args).expect("explicit config file should resolve");
-        fs::remove_file(&path).expect("temp config should be removed");
+        let clause = assignee_filter_clause(&client, &config, &filter)
+            .expect("account ids should resolve without network calls");
 
-        assert_eq!(resolved, path);
+        assert_eq!(
+            clause.as_deref(),
+            Some("(assignee in (\"account-1\", \"account-2\") OR assignee is EMPTY)")
+        );
     }
 
     #[test]
-    fn resolve_config_path_errors_for_missing_explicit_config_file() {
-        let args = QueryArgs {
-            ticket: None,
-            json: false,
-            text: false,
-            my_tickets: false,
-            show: false,
-            include_description: false,
-            include_comments: false,
-            include_prs: false,
-            full: false,
-            comments_limit: 5,
-            all_comments: false,
-            since: None,
-            limit: 10,
-            config_file: Some(PathBuf::from("/tmp/definitely-missing-jit-config.toml")),
+    fn quick_filters_are_anded_onto_the_query() {
+        let config: AppConfig = toml::from_str(
+            "[jira]\nbase_url = \"http://127.0.0.1:9\"\napi_token = \"t\"\nuser_email = \"u@example.com\"\n\n[quickfilters]\nfrontend = \"component = Web\"\nbugs = \"type = Bug OR labels = bug\"\n",
+        )
+        .expect("config should parse");
+        let client = create_jira_client("user@example.com", "token").expect("client");
+        let filter = AssigneeFilterArgs {
+            mine: true,
+            quick_filters: vec!["frontend".to_string(), "bugs".to_string()],
+            ..AssigneeFilterArgs::default()
         };
 
-        let error =
-            resolve_config_path(&args).expect_err("missing explicit config should return an error");
+        let jql = with_assignee_filter(&client, &config, "project = RW ORDER BY rank", &filter)
+            .expect("quick filters should resolve");
+        assert_eq!(
+            jql,
+            "(((project = RW) AND assignee = currentUser()) AND (component = Web)) AND (type = Bug OR labels = bug) ORDER BY rank"
+        );
 
-        assert!(
-            error
-                .to_string()
-                .contains("Specified config.toml file not found")
+        let unknown = AssigneeFilterArgs {
+            quick_filters: vec!["backend".to_string()],
+            ..AssigneeFilterArgs::default()
+        };
+        let err = with_assignee_filter(&client, &config, "project = RW", &unknown).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown quick filter 'backend'. Define it in the [quickfilters] config section (configured: bugs, frontend)."
         );
     }
 
     #[test]
-    fn build_issue_create_payload_uses_adf_for_description() {
-        let args = CreateArgs {
-            project: "RW".to_string(),
-            summary: "Implement backlog creation".to_string(),
-            description: Some("First line\nSecond line\n\nNew paragraph".to_string()),
-            issue_type: "Task".to_string(),
-            assignee: "me".to_string(),
-            current_sprint: false,
-            board: None,
-            json: false,
-        };
+    fn format_relative_time_counts_back_from_now() {
+        let now: DateTime<Utc> = "2026-04-10T12:00:00Z".parse().expect("time should parse");
 
-        let payload = build_issue_create_payload(&args, Some("account-id-123"));
-
-        assert_eq!(payload["fields"]["project"]["key"], "RW");
-        assert_eq!(payload["fields"]["summary"], "Implement backlog creation");
-        assert_eq!(payload["fields"]["issuetype"]["name"], "Task");
-        assert_eq!(payload["fields"]["assignee"]["id"], "account-id-123");
-        assert_eq!(payload["fields"]["description"]["type"], "doc");
-        assert_eq!(payload["fields"]["description"]["version"], 1);
         assert_eq!(
-            payload["fields"]["description"]["content"][0]["type"],
-            "paragraph"
+            format_relative_time("2026-04-10T11:59:30.000+0000", now),
+            "just now"
         );
         assert_eq!(
-            payload["fields"]["description"]["content"][0]["content"][0]["text"],
-            "First line"
+            format_relative_time("2026-04-10T11:15:00.000+0000", now),
+            "45 minutes ago"
         );
         assert_eq!(
-            payload["fields"]["description"]["content"][0]["content"][1]["type"],
-            "hardBreak"
+            format_relative_time("2026-04-10T13:00:00.000+0200", now),
+            "1 hour ago"
         );
         assert_eq!(
-            payload["fields"]["description"]["content"][0]["content"][2]["text"],
-            "Second line"
+            format_relative_time("2026-04-07T12:00:00.000+0000", now),
+            "3 days ago"
         );
         assert_eq!(
-            payload["fields"]["description"]["content"][1]["content"][0]["text"],
-            "New paragraph"
+            format_relative_time("2026-01-02T12:00:00.000+0000", now),
+            "2026-01-02"
         );
     }
 
     #[test]
-    fn build_issue_update_payload_supports_core_editable_fields() {
-        let args = EditArgs {
-            ticket: "RW-123".to_string(),
-            summary: Some("Updated summary".to_string()),
-            description: Some("First line\nSecond line".to_string()),
-            issue_type: Some("Bug".to_string()),
-            assignee: Some("account-id-123".to_string()),
-            json: false,
+    fn build_team_workload_counts_open_issues_per_member() {
+        let issues: Vec<JiraIssue> = serde_json::from_value(json!([
+            {"id": "1", "key": "RW-1", "fields": {"summary": "", "assignee": {"displayName": "Ada", "accountId": "a-1"}, "status": {"name": "In Progress", "statusCategory": {"key": "indeterminate"}}}},
+            {"id": "2", "key": "RW-2", "fields": {"summary": "", "assignee": {"displayName": "Ada", "accountId": "a-1"}, "status": {"name": "To Do", "statusCategory": {"key": "new"}}}},
+            {"id": "3", "key": "RW-3", "fields": {"summary": "", "assignee": {"displayName": "Zed", "accountId": "a-9"}, "status": {"name": "To Do", "statusCategory": {"key": "new"}}}}
+        ]))
+        .expect("issues should parse");
+        let members = [
+            ("a-1".to_string(), "Ada".to_string()),
+            ("a-2".to_string(), "Bo".to_string()),
+        ];
+
+        let workload = build_team_workload(&members, &issues);
+
+        assert_eq!(workload.len(), 2);
+        assert_eq!((workload[0].to_do, workload[0].in_progress), (1, 1));
+        assert_eq!(workload[0].total(), 2);
+        assert_eq!(workload[1].total(), 0);
+    }
+
+    #[test]
+    fn match_transition_accepts_unique_partial_names() {
+        let transition = |id: &str, name: &str, to: &str| JiraTransition {
+            id: id.to_string(),
+            name: name.to_string(),
+            to: Some(JiraStatus {
+                name: to.to_string(),
+                status_category: None,
+            }),
         };
+        let transitions = vec![
+            transition("11", "Start review", "In Review"),
+            transition("21", "Start progress", "In Progress"),
+            transition("31", "Done", "Done"),
+        ];
 
-        let payload = build_issue_update_payload(&args, Some("account-id-123"));
+        let matched = |target: &str| {
+            match_transition(&transitions, target)
+                .map(|transition| transition.id.as_str())
+                .map_err(|candidates| candidates.len())
+        };
+        assert_eq!(matched("in review"), Ok("11"));
+        assert_eq!(matched("review"), Ok("11"));
+        assert_eq!(matched("in-prog"), Ok("21"));
+        assert_eq!(matched("start"), Err(2));
+        assert_eq!(matched("blocked"), Err(0));
+    }
 
-        assert_eq!(payload["fields"]["summary"], "Updated summary");
-        assert_eq!(payload["fields"]["issuetype"]["name"], "Bug");
-        assert_eq!(payload["fields"]["assignee"]["accountId"], "account-id-123");
-        assert_eq!(payload["fields"]["description"]["type"], "doc");
-        assert_eq!(payload["fields"]["description"]["version"], 1);
+    #[test]
+    fn collect_dependencies_merges_external_links_and_skips_done_issues() {
+        let blocked_by = |key: &str, category: &str| {
+            json!({
+                "type": {"name": "Blocks", "inward": "is blocked by", "outward": "blocks"},
+                "inwardIssue": {"id": "9", "key": key, "fields": {"summary": "Provision DB", "status": {"name": "Open", "statusCategory": {"key": category}}}}
+            })
+        };
+        let issues: Vec<JiraIssue> = serde_json::from_value(json!([
+            {"id": "1", "key": "RW-1", "fields": {"summary": "", "issuelinks": [
+                blocked_by("OPS-12", "new"),
+                blocked_by("RW-2", "new"),
+                blocked_by("OPS-3", "done")
+            ]}},
+            {"id": "2", "key": "RW-4", "fields": {"summary": "", "issuelinks": [
+                blocked_by("OPS-12", "new"),
+                {"type": {"name": "Blocks", "inward": "is blocked by", "outward": "blocks"},
+                 "outwardIssue": {"id": "8", "key": "WEB-7", "fields": {"summary": "Landing page", "status": {"name": "To Do"}}}}
+            ]}}
+        ]))
+        .expect("issues should parse");
+
+        let dependencies = collect_dependencies("RW", &issues, true);
+
+        assert_eq!(dependencies.len(), 2);
+        assert_eq!(dependencies[0].key, "OPS-12");
+        assert_eq!(dependencies[0].relation, "is blocked by");
+        assert_eq!(dependencies[0].tickets, vec!["RW-1", "RW-4"]);
+        assert_eq!(dependencies[1].key, "WEB-7");
+        assert_eq!(dependencies[1].relation, "blocks");
+        assert_eq!(collect_dependencies("RW", &issues, false).len(), 3);
+    }
+
+    #[test]
+    fn attachment_manifest_keeps_download_details() {
+        let issue = json!({
+            "key": "OLD-1",
+            "fields": {"attachment": [{
+                "id": "10",
+                "filename": "spec.pdf",
+                "size": 2048,
+                "mimeType": "application/pdf",
+                "created": "2024-01-02T03:04:05.000+0000",
+                "author": {"displayName": "Ada"},
+                "content": "https://example.atlassian.net/rest/api/3/attachment/content/10"
+            }]}
+        });
+
+        let manifest = attachment_manifest(&issue);
+        assert_eq!(manifest.len(), 1);
+        assert_eq!(manifest[0]["filename"], "spec.pdf");
+        assert_eq!(manifest[0]["mime_type"], "application/pdf");
+        assert_eq!(manifest[0]["author"], "Ada");
         assert_eq!(
-            payload["fields"]["description"]["content"][0]["content"][0]["text"],
-            "First line"
+            manifest[0]["url"],
+            "https://example.atlassian.net/rest/api/3/attachment/content/10"
         );
+        assert!(attachment_manifest(&json!({"fields": {}})).is_empty());
+
+        let (partial, checkpoint) = export_paths(Path::new("dump/old.json"));
+        assert_eq!(partial, PathBuf::from("dump/old.json.partial"));
+        assert_eq!(checkpoint, PathBuf::from("dump/old.json.checkpoint"));
+    }
+
+    #[test]
+    fn parse_plan_action_reads_commands_and_item_numbers() {
         assert_eq!(
-            payload["fields"]["description"]["content"][0]["content"][1]["type"],
-            "hardBreak"
+            parse_plan_action("m 1 3").unwrap(),
+            PlanAction::Move(vec![1, 3])
         );
         assert_eq!(
-            payload["fields"]["description"]["content"][0]["content"][2]["text"],
-            "Second line"
+            parse_plan_action("estimate 2 5").unwrap(),
+            PlanAction::Estimate(2, 5.0)
         );
+        assert_eq!(parse_plan_action("r 4").unwrap(), PlanAction::Reset(4));
+        assert_eq!(parse_plan_action("A").unwrap(), PlanAction::Apply);
+        assert_eq!(parse_plan_action("").unwrap(), PlanAction::Help);
+        assert!(parse_plan_action("m").is_err());
+        assert!(parse_plan_action("m one").is_err());
+        assert!(parse_plan_action("e 2 -1").is_err());
     }
 
     #[test]
-    fn build_issue_create_payload_skips_blank_description_and_unassigned_assignee() {
-        let args = CreateArgs {
-            project: "RW".to_string(),
-            summary: "Implement backlog creation".to_string(),
-            description: Some("   ".to_string()),
-            issue_type: "Task".to_string(),
-            assignee: "unassigned".to_string(),
-            current_sprint: false,
-            board: None,
-            json: false,
-        };
-
-        let payload = build_issue_create_payload(&args, None);
+    fn split_reads_item_selections_and_top_level_sections() {
+        assert_eq!(parse_item_selection("3, 1-2,2", 4).unwrap(), [2, 0, 1]);
+        assert_eq!(parse_item_selection(" all\n", 3).unwrap(), [0, 1, 2]);
+        assert!(parse_item_selection("5", 4).is_err());
+        assert!(parse_item_selection("", 4).is_err());
+
+        let sections = description_sections(
+            "Intro stays.\n\n## Login\n\nOAuth flow.\n\n### Errors\n\nShow them.\n\n## Logout\n",
+        );
+        assert_eq!(
+            sections,
+            [
+                SplitCandidate {
+                    summary: "Login".to_string(),
+                    description: Some("OAuth flow.\n\n### Errors\n\nShow them.".to_string()),
+                },
+                SplitCandidate {
+                    summary: "Logout".to_string(),
+                    description: None,
+                },
+            ]
+        );
+        assert!(description_sections("No headings here").is_empty());
+    }
 
-        assert!(payload["fields"].get("description").is_none());
-        assert!(payload["fields"].get("assignee").is_none());
+    #[test]
+    fn plan_changes_only_include_what_moved_or_was_re_estimated() {
+        let mut issue = sample_issue_with_summary("1", "RW-1", "First");
+        issue
+            .fields
+            .extra
+            .insert(STORY_POINTS_FIELD.to_string(), json!(3.0));
+        let mut items = vec![
+            PlanItem::new(&issue, STORY_POINTS_FIELD, false),
+            PlanItem::new(
+                &sample_issue_with_summary("2", "RW-2", "Second"),
+                STORY_POINTS_FIELD,
+                true,
+            ),
+            PlanItem::new(
+                &sample_issue_with_summary("3", "RW-3", "Third"),
+                STORY_POINTS_FIELD,
+                true,
+            ),
+        ];
+        assert_eq!(items[0].points, Some(3.0));
+        items[0].in_sprint = true;
+        items[1].in_sprint = false;
+        items[2].points = Some(5.0);
+        items[2].in_sprint = false;
+        items[2].in_sprint = true;
+
+        let changes = PlanChanges::from_items(&items);
+        assert_eq!(changes.to_sprint, vec!["RW-1".to_string()]);
+        assert_eq!(changes.to_backlog, vec!["RW-2".to_string()]);
+        assert_eq!(changes.estimates, vec![("RW-3".to_string(), 5.0)]);
+        assert_eq!(
+            changes.describe("Sprint 43"),
+            vec!["RW-1 -> Sprint 43", "RW-2 -> Backlog", "RW-3 estimate -> 5"]
+        );
     }
 
     #[test]
-    fn build_issue_update_payload_clears_description_and_assignee() {
-        let args = EditArgs {
-            ticket: "RW-123".to_string(),
-            summary: None,
-            description: Some(String::new()),
-            issue_type: None,
-            assignee: Some("unassigned".to_string()),
-            json: false,
+    fn over_allocated_compares_sprint_points_to_capacity() {
+        let assigned = |key: &str, name: &str, points: f64, in_sprint: bool| {
+            let mut issue = sample_issue_with_summary("1", key, "Work");
+            issue.fields.assignee = Some(JiraUser {
+                display_name: name.to_string(),
+                account_id: Some(format!("id-{}", name)),
+            });
+            issue
+                .fields
+                .extra
+                .insert(STORY_POINTS_FIELD.to_string(), json!(points));
+            PlanItem::new(&issue, STORY_POINTS_FIELD, in_sprint)
+        };
+        let items = vec![
+            assigned("RW-1", "Jane", 8.0, true),
+            assigned("RW-2", "Jane", 8.0, true),
+            assigned("RW-3", "Sam", 5.0, true),
+            assigned("RW-4", "Sam", 13.0, false),
+            assigned("RW-5", "Kim", 21.0, true),
+        ];
+        let config = CapacityConfig {
+            default: Some(10.0),
+            people: HashMap::from([("jane".to_string(), 13.0), ("id-Kim".to_string(), 20.0)]),
         };
 
-        let payload = build_issue_update_payload(&args, None);
-
-        assert_eq!(payload["fields"]["description"], Value::Null);
-        assert_eq!(payload["fields"]["assignee"], Value::Null);
+        let over = over_allocated(&items, |user| {
+            person_capacity(&config, "https://example.atlassian.net", user)
+        });
+        assert_eq!(
+            over,
+            vec![
+                Overallocation {
+                    name: "Jane".to_string(),
+                    committed: 16.0,
+                    capacity: 13.0,
+                },
+                Overallocation {
+                    name: "Kim".to_string(),
+                    committed: 21.0,
+                    capacity: 20.0,
+                },
+            ]
+        );
+        assert_eq!(
+            over[0].describe(),
+            "Jane has 16 pts committed, over their capacity of 13 by 3"
+        );
+        assert!(over_allocated(&items, |_| None).is_empty());
     }
 
     #[test]
-    fn adf_value_to_display_text_preserves_inline_links() {
-        let value = json!({
-            "type": "doc",
-            "version": 1,
-            "content": [{
-                "type": "paragraph",
-                "content": [
-                    {
-                        "type": "text",
-                        "text": "See docs",
-                        "marks": [{
-                            "type": "link",
-                            "attrs": { "href": "https://example.com/docs" }
-                        }]
-                    }
-                ]
-            }]
-        });
+    fn split_ticket_argument_treats_lone_non_ticket_as_second_argument() {
+        assert!(looks_like_ticket("RW-1931"));
+        assert!(looks_like_ticket("rw-1931"));
+        assert!(looks_like_ticket(
+            "https://example.atlassian.net/browse/RW-1931"
+        ));
+        assert!(!looks_like_ticket("In Review"));
+        assert!(!looks_like_ticket("3"));
 
         assert_eq!(
-            adf_value_to_display_text(&value),
-            "See docs (https://example.com/docs)"
+            split_ticket_argument(Some("In Review"), None),
+            (None, Some("In Review"))
+        );
+        assert_eq!(
+            split_ticket_argument(Some("RW-1"), None),
+            (Some("RW-1"), None)
+        );
+        assert_eq!(
+            split_ticket_argument(Some("RW-1"), Some("Done")),
+            (Some("RW-1"), Some("Done"))
+        );
+        assert_eq!(split_ticket_argument(None, None), (None, None));
+        assert_eq!(
+            resolve_ticket(Some("https://example.atlassian.net/browse/RW-7")).unwrap(),
+            "RW-7"
         );
     }
 
     #[test]
-    fn adf_value_to_display_text_preserves_smart_links() {
-        let value = json!({
-            "type": "doc",
-            "version": 1,
-            "content": [{
-                "type": "paragraph",
-                "content": [
-                    { "type": "text", "text": "Runbook: " },
-                    {
-                        "type": "inlineCard",
-                        "attrs": { "url": "https://example.com/runbook" }
-                    }
-                ]
-            }]
-        });
-
+    fn parse_start_step_reads_configured_steps() {
+        assert_eq!(parse_start_step("assign").unwrap(), StartStep::Assign);
+        assert_eq!(
+            parse_start_step("move: In Progress").unwrap(),
+            StartStep::Move("In Progress".to_string())
+        );
+        assert_eq!(
+            parse_start_step("transition:Doing").unwrap(),
+            StartStep::Move("Doing".to_string())
+        );
+        assert_eq!(parse_start_step("Branch").unwrap(), StartStep::Branch);
         assert_eq!(
-            adf_value_to_display_text(&value),
-            "Runbook: https://example.com/runbook"
+            parse_start_step("comment:Picked up").unwrap(),
+            StartStep::Comment("Picked up".to_string())
         );
+        assert!(parse_start_step("move").is_err());
+        assert!(parse_start_step("deploy").is_err());
     }
 
     #[test]
-    fn create_jira_issue_posts_expected_request_for_explicit_assignee() {
-        let args = CreateArgs {
-            project: "RW".to_string(),
-            summary: "Implement backlog creation".to_string(),
-            description: Some("Description text".to_string()),
-            issue_type: "Task".to_string(),
-            assignee: "account-id-123".to_string(),
-            current_sprint: false,
-            board: None,
-            json: false,
-        };
-        let expected_payload = build_issue_create_payload(&args, Some("account-id-123"));
-        let (base_url, requests, handle) =
-            spawn_test_server("HTTP/1.1 201 Created", r#"{"id":"10001","key":"RW-123"}"#);
-        let client = create_jira_client("user@example.com", "token").expect("client");
-
-        let created_issue = create_jira_issue(&client, &base_url, &args, Some("account-id-123"))
-            .expect("issue creation should succeed");
-        let request = requests
-            .recv_timeout(Duration::from_secs(2))
-            .expect("request should be captured");
-        handle.join().expect("server thread should finish");
-
-        assert_eq!(created_issue.id, "10001");
-        assert_eq!(created_issue.key, "RW-123");
-        assert!(request.starts_with("POST /rest/api/3/issue HTTP/1.1"));
-        assert!(
-            request.contains("\r\ncontent-type: application/json\r\n")
-                || request.contains("\r\nContent-Type: application/json\r\n")
+    fn prefix_commit_message_adds_ticket_once() {
+        assert_eq!(
+            prefix_commit_message("Fix login crash\n", "RW-1931", DEFAULT_COMMIT_TEMPLATE)
+                .as_deref(),
+            Some("[RW-1931] Fix login crash\n")
+        );
+        assert_eq!(
+            prefix_commit_message("Fix login crash", "RW-1931", "{key}: ").as_deref(),
+            Some("rw-1931: Fix login crash")
+        );
+        assert_eq!(
+            prefix_commit_message("[RW-1931] Fix login crash", "RW-1931", "[{KEY}] "),
+            None
         );
-
-        let body = request
-            .split("\r\n\r\n")
-            .nth(1)
-            .expect("http request should contain a body");
-        let parsed_body: Value =
-            serde_json::from_str(body).expect("request body should be valid json");
-        assert_eq!(parsed_body, expected_payload);
     }
 
     #[test]
-    fn update_jira_issue_puts_expected_request() {
-        let args = EditArgs {
-            ticket: "RW-123".to_string(),
-            summary: Some("Updated summary".to_string()),
-            description: Some("Description text".to_string()),
-            issue_type: Some("Story".to_string()),
-            assignee: Some("account-id-123".to_string()),
-            json: false,
-        };
-        let expected_payload = build_issue_update_payload(&args, Some("account-id-123"));
-        let (base_url, requests, handle) = spawn_test_server("HTTP/1.1 204 No Content", "");
-        let client = create_jira_client("user@example.com", "token").expect("client");
-
-        update_jira_issue(&client, &base_url, "RW-123", &args, Some("account-id-123"))
-            .expect("issue update should succeed");
-        let request = requests
-            .recv_timeout(Duration::from_secs(2))
-            .expect("request should be captured");
-        handle.join().expect("server thread should finish");
-
-        assert!(request.starts_with("PUT /rest/api/3/issue/RW-123 HTTP/1.1"));
-        let body = request
-            .split("\r\n\r\n")
-            .nth(1)
-            .expect("http request should contain a body");
-        let parsed_body: Value =
-            serde_json::from_str(body).expect("request body should be valid json");
-        assert_eq!(parsed_body, expected_payload);
+    fn statusline_text_shows_status_and_focus_timer() {
+        assert_eq!(statusline_text("RW-1931", None, None), "RW-1931");
+        assert_eq!(
+            statusline_text("RW-1931", Some("In Progress"), Some(3_900)),
+            "RW-1931 [In Progress] 1h05m"
+        );
+        assert_eq!(format_timer(59), "0m");
+        assert_eq!(format_timer(45 * 60), "45m");
+        assert_eq!(format_timer(2 * 86_400 + 3 * 3_600 + 59), "2d3h");
     }
 
     #[test]
-    fn resolve_create_assignee_uses_current_user_by_default() {
-        let (base_url, requests, handle) = spawn_test_server(
-            "HTTP/1.1 200 OK",
-            r#"{"accountId":"account-id-999","displayName":"Cesar Ferreira"}"#,
+    fn prompt_segment_uses_each_frameworks_color_escapes() {
+        assert_eq!(
+            prompt_segment(
+                PromptFormat::Starship,
+                "RW-1931",
+                Some("In Progress"),
+                Some("indeterminate"),
+                true
+            ),
+            "\x1b[33mRW-1931 [In Progress]\x1b[0m"
+        );
+        assert_eq!(
+            prompt_segment(
+                PromptFormat::Powerlevel10k,
+                "RW-1931",
+                Some("100% done"),
+                Some("done"),
+                true
+            ),
+            "%F{green}RW-1931 [100%% done]%f"
+        );
+        assert_eq!(
+            prompt_segment(
+                PromptFormat::Starship,
+                "RW-1931",
+                Some("To Do"),
+                Some("new"),
+                false
+            ),
+            "RW-1931 [To Do]"
         );
-        let client = create_jira_client("user@example.com", "token").expect("client");
-
-        let assignee = resolve_create_assignee(&client, &base_url, "me")
-            .expect("default assignee should resolve");
-        let request = requests
-            .recv_timeout(Duration::from_secs(2))
-            .expect("request should be captured");
-        handle.join().expect("server thread should finish");
-
-        assert_eq!(assignee.account_id.as_deref(), Some("account-id-999"));
-        assert_eq!(assignee.label, "Cesar Ferreira");
-        assert!(request.starts_with("GET /rest/api/3/myself HTTP/1.1"));
     }
 
     #[test]
-    fn resolve_create_assignee_accepts_unassigned_without_network_call() {
-        let assignee = resolve_create_assignee(
-            &create_jira_client("user@example.com", "token").expect("client"),
-            "http://127.0.0.1:9",
-            "unassigned",
-        )
-        .expect("unassigned should not require network");
-
-        assert_eq!(assignee.account_id, None);
-        assert_eq!(assignee.label, "Unassigned");
+    fn parse_triage_action_accepts_single_keys_and_words() {
+        assert_eq!(parse_triage_action("a"), Some(TriageAction::Assign));
+        assert_eq!(parse_triage_action("Label"), Some(TriageAction::Label));
+        assert_eq!(parse_triage_action(" p "), Some(TriageAction::Priority));
+        assert_eq!(parse_triage_action("c"), Some(TriageAction::Close));
+        assert_eq!(parse_triage_action(""), Some(TriageAction::Skip));
+        assert_eq!(parse_triage_action("q"), Some(TriageAction::Quit));
+        assert_eq!(parse_triage_action("x"), None);
     }
 
     #[test]
-    fn resolve_create_assignee_keeps_explicit_account_id() {
-        let assignee = resolve_create_assignee(
-            &create_jira_client("user@example.com", "token").expect("client"),
-            "http://127.0.0.1:9",
-            "account-id-123",
-        )
-        .expect("explicit account ids should be returned as-is");
-
-        assert_eq!(assignee.account_id.as_deref(), Some("account-id-123"));
-        assert_eq!(assignee.label, "account-id-123");
+    fn parse_story_points_enforces_optional_scale() {
+        assert_eq!(parse_story_points("3", &[]).expect("valid"), 3.0);
+        assert_eq!(parse_story_points(" 0.5 ", &[]).expect("valid"), 0.5);
+        assert!(parse_story_points("-1", &[]).is_err());
+        assert!(parse_story_points("big", &[]).is_err());
+
+        let scale = [1.0, 2.0, 3.0, 5.0, 8.0];
+        assert_eq!(parse_story_points("5", &scale).expect("valid"), 5.0);
+        let err = parse_story_points("4", &scale).expect_err("4 is off the scale");
+        assert_eq!(
+            err.to_string(),
+            "Estimate 4 is not on the scale: 1, 2, 3, 5, 8"
+        );
     }
 
     #[test]
-    fn fetch_current_user_assignee_uses_fallback_label_when_display_name_missing() {
-        let (base_url, requests, handle) = spawn_test_server(
-            "HTTP/1.1 200 OK",
-            r#"{"accountId":"account-id-999","displayName":""}"#,
+    fn is_unestimated_reads_story_points_from_extra_fields() {
+        let issue: JiraIssue = serde_json::from_value(json!({
+            "id": "1",
+            "key": "RW-1",
+            "fields": {"summary": "Estimated", "customfield_10016": 3.0}
+        }))
+        .expect("issue should parse");
+        assert!(!is_unestimated(&issue, "customfield_10016"));
+        assert!(is_unestimated(&issue, "customfield_10026"));
+        assert_eq!(jql_field_name("customfield_10016"), "cf[10016]");
+        assert_eq!(
+            jql_field_name("timeoriginalestimate"),
+            "timeoriginalestimate"
         );
-        let client = create_jira_client("user@example.com", "token").expect("client");
+    }
 
-        let assignee =
-            fetch_current_user_assignee(&client, &base_url).expect("current user should resolve");
-        let request = requests
-            .recv_timeout(Duration::from_secs(2))
-            .expect("request should be captured");
-        handle.join().expect("server thread should finish");
+    #[test]
+    fn parse_jira_datetime_accepts_offsets_without_colon() {
+        let parsed = parse_jira_datetime("2026-04-10T09:00:00.000+0000")
+            .expect("Jira Cloud timestamps should parse");
 
-        assert_eq!(assignee.account_id.as_deref(), Some("account-id-999"));
-        assert_eq!(assignee.label, "Current user");
-        assert!(request.starts_with("GET /rest/api/3/myself HTTP/1.1"));
+        assert_eq!(parsed.to_rfc3339(), "2026-04-10T09:00:00+00:00");
     }
 
     #[test]
-    fn resolve_target_sprint_uses_latest_active_sprint_across_project_boards() {
-        let (base_url, requests, handle) = spawn_sequence_server(vec![
-            (
-                "HTTP/1.1 200 OK",
-                r#"{"values":[{"id":10,"name":"Alpha board"},{"id":20,"name":"Beta board"}],"isLast":true,"maxResults":50,"startAt":0}"#,
-            ),
-            (
-                "HTTP/1.1 200 OK",
-                r#"{"values":[{"id":100,"name":"Alpha Sprint","startDate":"2026-03-01T09:00:00.000+00:00"}],"isLast":true,"maxResults":50,"startAt":0}"#,
-            ),
-            (
-                "HTTP/1.1 200 OK",
-                r#"{"values":[{"id":200,"name":"Beta Sprint","startDate":"2026-03-05T09:00:00.000+00:00"}],"isLast":true,"maxResults":50,"startAt":0}"#,
-            ),
-        ]);
-        let client = create_jira_client("user@example.com", "token").expect("client");
-        let args = CreateArgs {
-            project: "RW".to_string(),
-            summary: "Implement backlog creation".to_string(),
-            description: None,
-            issue_type: "Task".to_string(),
-            assignee: "me".to_string(),
-            current_sprint: true,
-            board: None,
-            json: false,
+    fn build_grooming_report_flags_backlog_hygiene_issues() {
+        let issues: Vec<JiraIssue> = serde_json::from_value(json!([
+            {"id": "1", "key": "RW-1", "fields": {
+                "summary": "Fix login crash on Android",
+                "issuetype": {"name": "Bug"},
+                "updated": "2026-01-01T09:00:00.000+0000",
+                "components": [],
+                "customfield_10016": null
+            }},
+            {"id": "2", "key": "RW-2", "fields": {
+                "summary": "Login crash on Android fix",
+                "issuetype": {"name": "Bug"},
+                "updated": "2026-04-01T09:00:00.000+0000",
+                "components": [{"name": "Mobile"}],
+                "parent": {"key": "RW-100"},
+                "customfield_10016": 3
+            }},
+            {"id": "3", "key": "RW-3", "fields": {
+                "summary": "Payments epic",
+                "issuetype": {"name": "Epic"},
+                "updated": "2026-04-01T09:00:00.000+0000",
+                "components": [{"name": "Payments"}]
+            }}
+        ]))
+        .expect("issues should parse");
+        let now = parse_jira_datetime("2026-04-10T09:00:00+00:00")
+            .expect("valid date")
+            .with_timezone(&Utc);
+
+        let report = build_grooming_report(&issues, "customfield_10016", 90, 0.6, now);
+        let keys = |items: &[GroomingFinding]| {
+            items
+                .iter()
+                .map(|item| item.key.clone())
+                .collect::<Vec<_>>()
         };
 
-        let sprint = resolve_target_sprint(&client, &base_url, &args)
-            .expect("current sprint should resolve")
-            .expect("current sprint should be present");
-        let requests = collect_requests(requests, 3);
-        handle.join().expect("server thread should finish");
-
-        assert_eq!(sprint.id, 200);
-        assert_eq!(sprint.name, "Beta Sprint");
-        assert_eq!(sprint.board_id, 20);
-        assert_eq!(sprint.board_name, "Beta board");
-        assert!(requests[0].starts_with("GET /rest/agile/1.0/board?projectKeyOrId=RW&type=scrum"));
-        assert!(requests[1].starts_with("GET /rest/agile/1.0/board/10/sprint?state=active"));
-        assert!(requests[2].starts_with("GET /rest/agile/1.0/board/20/sprint?state=active"));
+        assert_eq!(keys(&report.unestimated), vec!["RW-1"]);
+        assert_eq!(keys(&report.missing_components), vec!["RW-1"]);
+        assert_eq!(keys(&report.missing_epic), vec!["RW-1"]);
+        assert_eq!(keys(&report.stale), vec!["RW-1"]);
+        assert_eq!(
+            report.stale[0].detail.as_deref(),
+            Some("not updated in 99 days")
+        );
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(report.duplicates[0].first, "RW-1");
+        assert_eq!(report.duplicates[0].second, "RW-2");
+        assert_eq!(report.duplicates[0].similarity, 1.0);
     }
 
     #[test]
-    fn resolve_target_sprint_returns_none_when_current_sprint_not_requested() {
-        let client = create_jira_client("user@example.com", "token").expect("client");
-        let args = CreateArgs {
-            project: "RW".to_string(),
-            summary: "Implement backlog creation".to_string(),
-            description: None,
-            issue_type: "Task".to_string(),
-            assignee: "me".to_string(),
-            current_sprint: false,
-            board: None,
-            json: false,
-        };
+    fn lint_rules_detect_hygiene_violations() {
+        let issue: JiraIssue = serde_json::from_value(json!({
+            "id": "1",
+            "key": "RW-1",
+            "fields": {
+                "summary": "Ship export",
+                "status": {"name": "Done", "statusCategory": {"key": "done"}},
+                "issuetype": {"name": "Story"},
+                "labels": ["export"],
+                "fixVersions": []
+            }
+        }))
+        .expect("issue should parse");
+
+        assert!(LintRule::FixVersionOnDone.is_violated_by(&issue));
+        assert!(!LintRule::AssigneeInProgress.is_violated_by(&issue));
+        assert!(LintRule::EpicLink.is_violated_by(&issue));
+        assert!(!LintRule::Labels.is_violated_by(&issue));
+
+        let in_progress: JiraIssue = serde_json::from_value(json!({
+            "id": "2",
+            "key": "RW-2",
+            "fields": {
+                "summary": "Build export",
+                "status": {"name": "In Progress", "statusCategory": {"key": "indeterminate"}},
+                "parent": {"key": "RW-100"}
+            }
+        }))
+        .expect("issue should parse");
 
-        let sprint = resolve_target_sprint(&client, "http://127.0.0.1:9", &args)
-            .expect("sprint lookup should be skipped");
+        assert!(LintRule::AssigneeInProgress.is_violated_by(&in_progress));
+        assert!(!LintRule::EpicLink.is_violated_by(&in_progress));
+        assert!(LintRule::Labels.is_violated_by(&in_progress));
+    }
 
-        assert!(sprint.is_none());
+    #[test]
+    fn lint_rule_parse_lists_available_rules_on_typo() {
+        assert_eq!(
+            LintRule::parse("Epic-Link").expect("rule should parse"),
+            LintRule::EpicLink
+        );
+        let err = LintRule::parse("epic").expect_err("unknown rule");
+        assert_eq!(
+            err.to_string(),
+            "Unknown lint rule 'epic'. Available rules: fix-version-on-done, assignee-in-progress, epic-link, labels"
+        );
     }
 
     #[test]
-    fn resolve_target_sprint_uses_explicit_board_when_provided() {
-        let (base_url, requests, handle) = spawn_sequence_server(vec![
-            ("HTTP/1.1 200 OK", r#"{"id":42,"name":"Explicit board"}"#),
-            (
-                "HTTP/1.1 200 OK",
-                r#"{"values":[{"id":300,"name":"Board Sprint","startDate":"2026-04-01T09:00:00+00:00"}],"isLast":true,"maxResults":50,"startAt":0}"#,
-            ),
-        ]);
-        let client = create_jira_client("user@example.com", "token").expect("client");
-        let args = CreateArgs {
-            project: "RW".to_string(),
-            summary: "Implement backlog creation".to_string(),
-            description: None,
-            issue_type: "Task".to_string(),
-            assignee: "me".to_string(),
-            current_sprint: true,
-            board: Some(42),
-            json: false,
-        };
+    fn configured_dod_checks_default_to_checklist_when_field_is_set() {
+        let mut config: AppConfig = toml::from_str(
+            "[jira]\nbase_url = \"https://example.atlassian.net\"\napi_token = \"t\"\nuser_email = \"u@example.com\"\n",
+        )
+        .expect("config should parse");
+        assert_eq!(
+            configured_dod_checks(&config).expect("checks"),
+            vec![DodCheck::LinkedPr, DodCheck::FixVersion]
+        );
 
-        let sprint = resolve_target_sprint(&client, &base_url, &args)
-            .expect("sprint should resolve")
-            .expect("sprint should be present");
-        let requests = collect_requests(requests, 2);
-        handle.join().expect("server thread should finish");
+        config.checklist.field = Some("customfield_10300".to_string());
+        assert_eq!(
+            configured_dod_checks(&config).expect("checks"),
+            vec![
+                DodCheck::LinkedPr,
+                DodCheck::ChecklistComplete,
+                DodCheck::FixVersion
+            ]
+        );
 
-        assert_eq!(sprint.id, 300);
-        assert_eq!(sprint.board_id, 42);
-        assert_eq!(sprint.board_name, "Explicit board");
-        assert!(requests[0].starts_with("GET /rest/agile/1.0/board/42 HTTP/1.1"));
-        assert!(requests[1].starts_with("GET /rest/agile/1.0/board/42/sprint?state=active"));
+        config.dod.checks = vec!["fix-version".to_string(), "qa".to_string()];
+        assert!(configured_dod_checks(&config).is_err());
     }
 
     #[test]
-    fn fetch_scrum_boards_for_project_reads_all_pages() {
-        let (base_url, requests, handle) = spawn_sequence_server(vec![
-            (
-                "HTTP/1.1 200 OK",
-                r#"{"values":[{"id":10,"name":"Alpha board"}],"isLast":false,"maxResults":1,"startAt":0}"#,
-            ),
-            (
-                "HTTP/1.1 200 OK",
-                r#"{"values":[{"id":20,"name":"Beta board"}],"isLast":true,"maxResults":1,"startAt":1}"#,
-            ),
-        ]);
-        let client = create_jira_client("user@example.com", "token").expect("client");
+    fn http_config_parses_pool_and_protocol_settings() {
+        let config: AppConfig = toml::from_str(
+            "[jira]\nbase_url = \"https://example.atlassian.net\"\napi_token = \"t\"\nuser_email = \"u@example.com\"\n[http]\nversion = \"http2\"\npool_max_idle_per_host = 4\npool_idle_timeout_secs = 300\ntcp_keepalive_secs = 60\n",
+        )
+        .expect("config should parse");
 
-        let boards = fetch_scrum_boards_for_project(&client, &base_url, "RW")
-            .expect("board pagination should succeed");
-        let requests = collect_requests(requests, 2);
-        handle.join().expect("server thread should finish");
+        assert_eq!(config.http.version, HttpVersion::Http2);
+        assert_eq!(config.http.pool_max_idle_per_host, Some(4));
+        assert_eq!(config.http.pool_idle_timeout_secs, Some(300));
+        assert_eq!(config.http.tcp_keepalive_secs, Some(60));
+        assert!(config.http.client_builder().build().is_ok());
+    }
 
-        assert_eq!(boards.len(), 2);
-        assert_eq!(boards[0].id, 10);
-        assert_eq!(boards[1].id, 20);
-        assert!(requests[0].contains("startAt=0&maxResults=50"));
-        assert!(requests[1].contains("startAt=1&maxResults=50"));
+    #[test]
+    fn checklist_completion_requires_every_item_checked() {
+        let config: AppConfig = toml::from_str(
+            "[jira]\nbase_url = \"https://example.atlassian.net\"\napi_token = \"t\"\nuser_email = \"u@example.com\"\n[checklist]\nfield = \"customfield_10300\"\n",
+        )
+        .expect("config should parse");
+        let issue: JiraIssue = serde_json::from_value(json!({
+            "id": "1",
+            "key": "RW-1",
+            "fields": {"summary": "QA", "customfield_10300": "- [x] Smoke test\n- [ ] Regression"}
+        }))
+        .expect("issue should parse");
+
+        assert_eq!(
+            checklist_completion(&config, &issue),
+            (false, "1/2 items done".to_string())
+        );
     }
 
     #[test]
-    fn fetch_active_sprints_for_board_prefers_latest_active_sprint_across_pages() {
-        let (base_url, requests, handle) = spawn_sequence_server(vec![
-            (
-                "HTTP/1.1 200 OK",
-                r#"{"values":[{"id":100,"name":"Alpha Sprint","startDate":"2026-03-01T09:00:00+00:00"}],"isLast":false,"maxResults":1,"startAt":0}"#,
-            ),
-            (
-                "HTTP/1.1 200 OK",
-                r#"{"values":[{"id":200,"name":"Beta Sprint","startDate":"2026-03-05T09:00:00+00:00"}],"isLast":true,"maxResults":1,"startAt":1}"#,
-            ),
-        ]);
-        let client = create_jira_client("user@example.com", "token").expect("client");
+    fn build_project_stats_buckets_created_resolved_and_open_bugs() {
+        let issues: Vec<JiraIssue> = serde_json::from_value(json!([
+            {"id": "1", "key": "RW-1", "fields": {
+                "summary": "Old bug",
+                "issuetype": {"name": "Bug"},
+                "created": "2026-03-01T09:00:00.000+0000",
+                "resolutiondate": "2026-04-09T09:00:00.000+0000",
+                "assignee": {"displayName": "Ada"}
+            }},
+            {"id": "2", "key": "RW-2", "fields": {
+                "summary": "New bug",
+                "issuetype": {"name": "Bug"},
+                "created": "2026-04-02T09:00:00.000+0000",
+                "components": [{"name": "Mobile"}]
+            }},
+            {"id": "3", "key": "RW-3", "fields": {
+                "summary": "Story",
+                "issuetype": {"name": "Story"},
+                "created": "2026-04-03T09:00:00.000+0000",
+                "resolutiondate": "2026-04-04T09:00:00.000+0000",
+                "assignee": {"displayName": "Ada"},
+                "components": [{"name": "Mobile"}, {"name": "API"}]
+            }}
+        ]))
+        .expect("issues should parse");
+        let date = |value: &str| {
+            parse_jira_datetime(value)
+                .expect("valid date")
+                .with_timezone(&Utc)
+        };
 
-        let sprint = fetch_active_sprints_for_board(&client, &base_url, 42)
-            .expect("sprint pagination should succeed")
-            .expect("a sprint should be returned");
-        let requests = collect_requests(requests, 2);
-        handle.join().expect("server thread should finish");
+        let stats = build_project_stats(
+            &issues,
+            date("2026-03-27T00:00:00+00:00"),
+            date("2026-04-10T00:00:00+00:00"),
+            Duration::from_secs(7 * 86_400),
+        );
 
-        assert_eq!(sprint.id, 200);
-        assert_eq!(sprint.name, "Beta Sprint");
-        assert!(requests[0].contains("/board/42/sprint?state=active&startAt=0"));
-        assert!(requests[1].contains("/board/42/sprint?state=active&startAt=1"));
+        assert_eq!(stats.buckets.len(), 2);
+        assert_eq!(stats.buckets[0].created, 1);
+        assert_eq!(stats.buckets[0].resolved, 0);
+        assert_eq!(stats.buckets[0].open_bugs, 2);
+        assert_eq!(stats.buckets[1].created, 1);
+        assert_eq!(stats.buckets[1].resolved, 2);
+        assert_eq!(stats.buckets[1].open_bugs, 1);
+        assert_eq!(stats.top_assignees, vec![("Ada".to_string(), 2)]);
+        assert_eq!(
+            stats.top_components,
+            vec![("Mobile".to_string(), 2), ("API".to_string(), 1)]
+        );
     }
 
     #[test]
-    fn compare_sprint_identity_prefers_newer_dates_then_ids() {
-        assert!(compare_sprint_identity(
-            Some("2026-04-10T09:00:00+00:00"),
-            10,
-            1,
-            Some("2026-04-09T09:00:00+00:00"),
-            99,
-            2,
-        ));
-        assert!(compare_sprint_identity(
-            Some("2026-04-10T09:00:00+00:00"),
-            10,
-            2,
-            Some("2026-04-10T09:00:00+00:00"),
-            10,
-            1,
-        ));
-        assert!(!compare_sprint_identity(
-            None,
-            10,
-            1,
-            Some("2026-04-10T09:00:00+00:00"),
-            5,
-            1,
-        ));
+    fn sparkline_scales_to_largest_value() {
+        assert_eq!(sparkline(&[0, 2, 4, 8]), "▁▃▅█");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
     }
 
     #[test]
-    fn parse_jira_datetime_accepts_rfc3339_values() {
-        let parsed = parse_jira_datetime("2026-04-10T09:00:00+00:00")
-            .expect("valid RFC3339 values should parse");
+    fn build_snapshot_counts_statuses_and_assignees() {
+        let issues: Vec<JiraIssue> = serde_json::from_value(json!([
+            {"id": "1", "key": "RW-1", "fields": {"summary": "", "status": {"name": "To Do"}}},
+            {"id": "2", "key": "RW-2", "fields": {"summary": "", "status": {"name": "To Do"}, "assignee": {"displayName": "Ada"}}},
+            {"id": "3", "key": "RW-3", "fields": {"summary": "", "status": {"name": "In Progress"}, "assignee": {"displayName": "Ada"}}}
+        ]))
+        .expect("issues should parse");
 
-        assert_eq!(parsed.to_rfc3339(), "2026-04-10T09:00:00+00:00");
+        let snapshot = build_snapshot("RW", "2026-04-10", &issues);
+
+        assert_eq!(snapshot.total, 3);
+        assert_eq!(snapshot.statuses.get("To Do"), Some(&2));
+        assert_eq!(snapshot.statuses.get("In Progress"), Some(&1));
+        assert_eq!(snapshot.assignees.get("Ada"), Some(&2));
+        assert_eq!(snapshot.assignees.get("Unassigned"), Some(&1));
     }
 
     #[test]
-    fn fetch_jira_issue_requests_expected_detail_fields() {
-        let (base_url, requests, handle) = spawn_test_server(
-            "HTTP/1.1 200 OK",
-            r#"{"id":"10001","key":"RW-123","fields":{"summary":"Implement backlog creation","status":{"name":"In Progress"},"customfield_10020":[{"name":"Sprint 42","state":"active"}],"description":{"type":"doc","version":1,"content":[{"type":"paragraph","content":[{"type":"text","text":"Hello"}]}]},"comment":{"comments":[{"author":{"displayName":"Cesar Ferreira"},"body":{"type":"doc","version":1,"content":[{"type":"paragraph","content":[{"type":"text","text":"Comment body"}]}]},"created":"2026-04-10T09:00:00.000+00:00","updated":"2026-04-10T10:00:00.000+00:00"}]}}}"#,
-        );
-        let client = create_jira_client("user@example.com", "token").expect("client");
+    fn upsert_snapshot_replaces_same_day_and_keeps_order() {
+        let snapshot = |project: &str, date: &str, total: usize| Snapshot {
+            date: date.to_string(),
+            project: project.to_string(),
+            total,
+            statuses: BTreeMap::new(),
+            assignees: BTreeMap::new(),
+        };
+        let mut snapshots = vec![
+            snapshot("RW", "2026-04-09", 5),
+            snapshot("RW", "2026-04-10", 6),
+        ];
 
-        let issue = fetch_jira_issue(&client, &base_url, "RW-123", true, true, true)
-            .expect("issue fetch should succeed");
-        let request = requests
-            .recv_timeout(Duration::from_secs(2))
-            .expect("request should be captured");
-        handle.join().expect("server thread should finish");
+        upsert_snapshot(&mut snapshots, snapshot("RW", "2026-04-10", 7));
+        upsert_snapshot(&mut snapshots, snapshot("AB", "2026-04-10", 1));
 
-        assert_eq!(issue.key, "RW-123");
-        assert!(request.starts_with("GET /rest/api/3/issue/RW-123?fields="));
-        assert!(request.contains("assignee,comment,created,customfield_10020,description,duedate,issuetype,priority,reporter,status,summary,updated"));
+        assert_eq!(
+            snapshots
+                .iter()
+                .map(|s| (s.project.as_str(), s.date.as_str(), s.total))
+                .collect::<Vec<_>>(),
+            vec![
+                ("AB", "2026-04-10", 1),
+                ("RW", "2026-04-09", 5),
+                ("RW", "2026-04-10", 7)
+            ]
+        );
     }
 
     #[test]
-    fn fetch_my_tickets_posts_active_sprint_jql() {
-        let (base_url, requests, handle) = spawn_test_server(
-            "HTTP/1.1 200 OK",
-            r#"{"issues":[{"id":"10001","key":"RW-123","fields":{"summary":"Implement backlog creation","status":{"name":"In Progress"},"customfield_10020":[{"name":"Sprint 42","state":"active"}]}}]}"#,
+    fn build_age_heatmap_groups_open_issues_by_status_and_age() {
+        let issues: Vec<JiraIssue> = serde_json::from_value(json!([
+            {"id": "1", "key": "RW-1", "fields": {"summary": "Fresh", "status": {"name": "To Do"}, "created": "2026-04-08T09:00:00.000+0000"}},
+            {"id": "2", "key": "RW-2", "fields": {"summary": "Month old", "status": {"name": "To Do"}, "created": "2026-03-01T09:00:00.000+0000"}},
+            {"id": "3", "key": "RW-3", "fields": {"summary": "Ancient", "status": {"name": "In Progress"}, "created": "2024-01-01T09:00:00.000+0000"}},
+            {"id": "4", "key": "RW-4", "fields": {"summary": "Old", "status": {"name": "To Do"}, "created": "2025-08-01T09:00:00.000+0000"}}
+        ]))
+        .expect("issues should parse");
+        let now = parse_jira_datetime("2026-04-10T09:00:00+00:00")
+            .expect("valid date")
+            .with_timezone(&Utc);
+
+        let heatmap = build_age_heatmap(&issues, now);
+
+        assert_eq!(
+            heatmap.rows,
+            vec![
+                ("In Progress".to_string(), [0, 0, 0, 0, 0, 1]),
+                ("To Do".to_string(), [1, 0, 1, 0, 1, 0]),
+            ]
         );
-        let client = create_jira_client("user@example.com", "token").expect("client");
+        assert_eq!(
+            heatmap
+                .oldest
+                .iter()
+                .map(|(key, _, _)| key.as_str())
+                .collect::<Vec<_>>(),
+            vec!["RW-3", "RW-4"]
+        );
+    }
 
-        let issues = fetch_my_tickets(&client, &base_url, 7).expect("ticket fetch should succeed");
-        let request = requests
-            .recv_timeout(Duration::from_secs(2))
-            .expect("request should be captured");
-        handle.join().expect("server thread should finish");
+    #[test]
+    fn cli_parses_global_output_flag_after_subcommand() {
+        let cli = Cli::parse_from(["jit", "stats", "--project", "RW", "--output", "markdown"]);
+        assert_eq!(cli.output, Some(OutputFormat::Markdown));
+    }
 
-        let body = request
-            .split("\r\n\r\n")
-            .nth(1)
-            .expect("http request should contain a body");
-        let parsed_body: Value =
-            serde_json::from_str(body).expect("request body should be valid json");
+    #[test]
+    fn parse_duration_accepts_common_units() {
+        assert_eq!(
+            parse_duration("90").expect("seconds"),
+            Duration::from_secs(90)
+        );
+        assert_eq!(
+            parse_duration("30m").expect("minutes"),
+            Duration::from_secs(1800)
+        );
+        assert_eq!(
+            parse_duration("2h").expect("hours"),
+            Duration::from_secs(7200)
+        );
+        assert_eq!(
+            parse_duration("1d").expect("days"),
+            Duration::from_secs(86400)
+        );
+        assert!(parse_duration("2 hours").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("99999999999999999w").is_err());
+    }
 
-        assert_eq!(issues.len(), 1);
-        assert!(request.starts_with("POST /rest/api/3/search/jql HTTP/1.1"));
+    #[test]
+    fn find_ticket_keys_prefers_uppercase_keys_and_dedupes() {
         assert_eq!(
-            parsed_body["jql"],
-            "assignee = currentUser() AND sprint in openSprints() ORDER BY updated DESC"
+            find_ticket_keys("RW-12: Fix login (also RW-13, RW-12)"),
+            vec!["RW-12".to_string(), "RW-13".to_string()]
         );
-        assert_eq!(parsed_body["maxResults"], 7);
+        assert_eq!(
+            find_ticket_keys("feature/rw-1931-fix-login-2"),
+            vec!["RW-1931".to_string()]
+        );
+        assert!(find_ticket_keys("main").is_empty());
     }
 
     #[test]
-    fn fetch_issue_pull_requests_flattens_dev_status_response() {
-        let (base_url, requests, handle) = spawn_test_server(
-            "HTTP/1.1 200 OK",
-            r#"{"detail":[{"pullRequests":[{"id":"1","name":"First PR","status":"OPEN","url":"https://github.com/org/repo/pull/1","lastUpdate":"2026-04-10T09:00:00.000+00:00"}]},{"pullRequests":[{"id":"2","name":"Second PR","status":"MERGED","url":"https://github.com/org/repo/pull/2","lastUpdate":"2026-04-10T10:00:00.000+00:00"}]}]}"#,
+    fn find_ticket_keys_skips_standards_that_look_like_keys() {
+        assert_eq!(
+            find_ticket_keys("RW-7: Parse ISO-8601 dates as UTF-8 (see RFC-3339)"),
+            vec!["RW-7".to_string()]
         );
-        let client = create_jira_client("user@example.com", "token").expect("client");
+        assert_eq!(
+            find_ticket_keys("utf-8/rw-44-fix-decoding"),
+            vec!["RW-44".to_string()]
+        );
+        assert!(find_ticket_keys("Bump SHA-256 and X1-2").is_empty());
+    }
 
-        let pull_requests = fetch_issue_pull_requests(&client, &base_url, "10001")
-            .expect("PR fetch should succeed");
-        let request = requests
-            .recv_timeout(Duration::from_secs(2))
-            .expect("request should be captured");
-        handle.join().expect("server thread should finish");
+    #[test]
+    fn sync_pr_target_status_uses_config_then_defaults() {
+        let mut mappings = HashMap::new();
+        mappings.insert("merged".to_string(), "Ready for QA".to_string());
+        mappings.insert("opened".to_string(), String::new());
 
-        assert_eq!(pull_requests.len(), 2);
-        assert_eq!(pull_requests[0].name.as_deref(), Some("First PR"));
-        assert_eq!(pull_requests[1].name.as_deref(), Some("Second PR"));
-        assert!(request.starts_with("GET /rest/dev-status/latest/issue/detail?issueId=10001"));
+        assert_eq!(
+            sync_pr_target_status(&mappings, "merged").as_deref(),
+            Some("Ready for QA")
+        );
+        assert_eq!(sync_pr_target_status(&mappings, "opened"), None);
+        assert_eq!(
+            sync_pr_target_status(&HashMap::new(), "reopened").as_deref(),
+            Some("In Review")
+        );
+        assert_eq!(sync_pr_target_status(&HashMap::new(), "closed"), None);
     }
 
     #[test]
-    fn fetch_pull_requests_for_tickets_maps_results_by_ticket_key() {
-        let (base_url, requests, handle) = spawn_sequence_server(vec![
-            (
-                "HTTP/1.1 200 OK",
-                r#"{"detail":[{"pullRequests":[{"id":"1","name":"First PR","status":"OPEN","url":"https://github.com/org/repo/pull/1","lastUpdate":"2026-04-10T09:00:00.000+00:00"}]}]}"#,
-            ),
-            (
-                "HTTP/1.1 200 OK",
-                r#"{"detail":[{"pullRequests":[{"id":"2","name":"Second PR","status":"MERGED","url":"https://github.com/org/repo/pull/2","lastUpdate":"2026-04-10T10:00:00.000+00:00"}]}]}"#,
-            ),
-        ]);
-        let client = create_jira_client("user@example.com", "token").expect("client");
-        let tickets = vec![
-            sample_issue_with_summary("10001", "RW-123", "First issue"),
-            sample_issue_with_summary("10002", "RW-124", "Second issue"),
+    fn find_transition_matches_target_status_or_transition_name() {
+        let transitions = vec![
+            JiraTransition {
+                id: "11".to_string(),
+                name: "Start review".to_string(),
+                to: Some(JiraStatus {
+                    name: "In Review".to_string(),
+                    status_category: None,
+                }),
+            },
+            JiraTransition {
+                id: "31".to_string(),
+                name: "Done".to_string(),
+                to: None,
+            },
         ];
 
-        let mapped = fetch_pull_requests_for_tickets(&client, &base_url, &tickets)
-            .expect("PR mapping should succeed");
-        let requests = collect_requests(requests, 2);
-        handle.join().expect("server thread should finish");
+        assert_eq!(
+            find_transition(&transitions, "in review").map(|t| t.id.as_str()),
+            Some("11")
+        );
+        assert_eq!(
+            find_transition(&transitions, "DONE").map(|t| t.id.as_str()),
+            Some("31")
+        );
+        assert!(find_transition(&transitions, "Blocked").is_none());
+    }
 
-        assert_eq!(mapped["RW-123"].len(), 1);
-        assert_eq!(mapped["RW-124"].len(), 1);
-        assert!(requests[0].contains("issueId=10001"));
-        assert!(requests[1].contains("issueId=10002"));
+    #[test]
+    fn ticket_branch_name_slugifies_summary() {
+        assert_eq!(
+            ticket_branch_name("RW-1931", "Fix login crash (Safari) — again!"),
+            "rw-1931-fix-login-crash-safari-again"
+        );
+        assert_eq!(ticket_branch_name("RW-1", "  ***  "), "rw-1");
+        assert_eq!(
+            ticket_branch_name(
+                "RW-2",
+                "Improve the onboarding experience for enterprise administrators everywhere"
+            ),
+            "rw-2-improve-the-onboarding-experience-for-enterprise"
+        );
     }
 
     #[test]
-    fn truncate_with_ellipsis_shortens_long_strings() {
-        let truncated = truncate_with_ellipsis("abcdefghijklmnopqrstuvwxyz", 10);
+    fn configured_branch_name_fills_template() {
+        let mut issue = sample_issue_with_summary("10001", "RW-1931", "Fix login crash");
+        let default = GitConfig::default();
+        assert_eq!(
+            configured_branch_name(&default, &issue).unwrap(),
+            "rw-1931-fix-login-crash"
+        );
 
-        assert_eq!(truncated, "abcdefg...");
+        let config = GitConfig {
+            branch_template: Some("{type}/{KEY}-{summary}".to_string()),
+            ..GitConfig::default()
+        };
+        assert_eq!(
+            configured_branch_name(&config, &issue).unwrap(),
+            "RW-1931-fix-login-crash"
+        );
+        issue.fields.issuetype = Some(JiraIssueType {
+            name: "Bug Fix".to_string(),
+        });
+        assert_eq!(
+            configured_branch_name(&config, &issue).unwrap(),
+            "bug-fix/RW-1931-fix-login-crash"
+        );
+
+        let unknown = GitConfig {
+            branch_template: Some("{key}-{owner}".to_string()),
+            ..GitConfig::default()
+        };
+        assert!(configured_branch_name(&unknown, &issue).is_err());
     }
 
     #[test]
-    fn extract_pr_id_from_url_returns_pull_number() {
-        let pr_id = extract_pr_id_from_url("https://github.com/org/repo/pull/123/files");
+    fn worktree_path_fills_template_under_the_main_worktree() {
+        let root = Path::new("/src/app");
+        assert_eq!(
+            worktree_path(&GitConfig::default(), root, "RW-1931", "rw-1931-fix").unwrap(),
+            Path::new("/src/app-rw-1931")
+        );
 
-        assert_eq!(pr_id.as_deref(), Some("#123"));
+        let config = GitConfig {
+            worktree_template: Some(".worktrees/{KEY}".to_string()),
+            ..GitConfig::default()
+        };
+        assert_eq!(
+            worktree_path(&config, root, "RW-1931", "rw-1931-fix").unwrap(),
+            Path::new("/src/app/.worktrees/RW-1931")
+        );
+    }
+
+    #[test]
+    fn build_bitbucket_pr_payload_includes_ticket_link_and_description() {
+        let mut issue = sample_issue_with_summary("10001", "RW-123", "Fix login crash");
+        issue.fields.description = Some(text_to_adf("Steps to reproduce"));
+
+        let payload = build_bitbucket_pr_payload(
+            &issue,
+            "https://example.atlassian.net",
+            "rw-123-fix-login-crash",
+            "main",
+        );
+
+        assert_eq!(payload["title"], "RW-123: Fix login crash");
+        assert_eq!(
+            payload["description"],
+            "Jira: https://example.atlassian.net/browse/RW-123\n\nSteps to reproduce"
+        );
+        assert_eq!(
+            payload["source"]["branch"]["name"],
+            "rw-123-fix-login-crash"
+        );
+        assert_eq!(payload["destination"]["branch"]["name"], "main");
     }
 
     #[test]
@@ -3388,16 +14880,6 @@ user_email = "user@example.com"
         assert_eq!(format_date(""), "Not set");
     }
 
-    #[test]
-    fn text_to_adf_preserves_paragraphs_and_line_breaks() {
-        let adf = text_to_adf("First line\nSecond line\n\nNew paragraph");
-
-        assert_eq!(adf["content"][0]["content"][0]["text"], "First line");
-        assert_eq!(adf["content"][0]["content"][1]["type"], "hardBreak");
-        assert_eq!(adf["content"][0]["content"][2]["text"], "Second line");
-        assert_eq!(adf["content"][1]["content"][0]["text"], "New paragraph");
-    }
-
     #[test]
     fn get_filtered_comments_applies_since_and_limit_filters() {
         let issue = sample_issue_with_comments(vec![
@@ -3419,7 +14901,7 @@ user_email = "user@example.com"
     }
 
     #[test]
-    fn build_issue_json_includes_description_comments_and_prs() {
+    fn issue_view_json_includes_description_comments_and_prs() {
         let issue = JiraIssue {
             id: "10001".to_string(),
             key: "RW-123".to_string(),
@@ -3427,8 +14909,10 @@ user_email = "user@example.com"
                 summary: "Implement backlog creation".to_string(),
                 status: Some(JiraStatus {
                     name: "In Progress".to_string(),
+                    status_category: None,
                 }),
                 sprint: Some(vec![JiraSprint {
+                    id: None,
                     name: "Sprint 42".to_string(),
                     state: "active".to_string(),
                 }]),
@@ -3456,6 +14940,8 @@ user_email = "user@example.com"
                         sample_comment("Grace", "2026-04-12T09:00:00.000+00:00", "Keep me"),
                     ],
                 }),
+                labels: Vec::new(),
+                extra: HashMap::new(),
             },
         };
         let pull_requests = vec![JiraPullRequest {
@@ -3466,16 +14952,15 @@ user_email = "user@example.com"
             last_update: Some("2026-04-10T11:00:00.000+00:00".to_string()),
         }];
 
-        let payload = build_issue_json(
-            &issue,
-            true,
-            true,
-            true,
-            &pull_requests,
-            5,
-            false,
-            Some("2026-04-05"),
-        );
+        let sections = IssueSections {
+            description: true,
+            comments: true,
+            pull_requests: true,
+            comments_limit: 5,
+            all_comments: false,
+            since: Some("2026-04-05"),
+        };
+        let payload = IssueView::from_issue(&issue, sections, &pull_requests, None).to_json();
 
         assert_eq!(payload["ticket"], "RW-123");
         assert_eq!(payload["description"], "Hello\nWorld");
@@ -3487,28 +14972,6 @@ user_email = "user@example.com"
         assert_eq!(payload["pull_requests"][0]["id"], "#42");
     }
 
-    #[test]
-    fn add_issue_to_sprint_posts_issue_key() {
-        let (base_url, requests, handle) = spawn_test_server("HTTP/1.1 204 No Content", "");
-        let client = create_jira_client("user@example.com", "token").expect("client");
-
-        add_issue_to_sprint(&client, &base_url, 200, "RW-123")
-            .expect("adding issue to sprint should succeed");
-        let request = requests
-            .recv_timeout(Duration::from_secs(2))
-            .expect("request should be captured");
-        handle.join().expect("server thread should finish");
-
-        assert!(request.starts_with("POST /rest/agile/1.0/sprint/200/issue HTTP/1.1"));
-        let body = request
-            .split("\r\n\r\n")
-            .nth(1)
-            .expect("http request should contain a body");
-        let parsed_body: Value =
-            serde_json::from_str(body).expect("request body should be valid json");
-        assert_eq!(parsed_body, json!({ "issues": ["RW-123"] }));
-    }
-
     fn spawn_test_server(
         status_line: &str,
         response_body: &'static str,
@@ -3589,8 +15052,10 @@ user_email = "user@example.com"
                 summary: summary.to_string(),
                 status: Some(JiraStatus {
                     name: "In Progress".to_string(),
+                    status_category: None,
                 }),
                 sprint: Some(vec![JiraSprint {
+                    id: None,
                     name: "Sprint 42".to_string(),
                     state: "active".to_string(),
                 }]),
@@ -3603,12 +15068,15 @@ user_email = "user@example.com"
                 updated: None,
                 due_date: None,
                 comment: None,
+                labels: Vec::new(),
+                extra: HashMap::new(),
             },
         }
     }
 
     fn sample_comment(author: &str, created: &str, body: &str) -> JiraComment {
         JiraComment {
+            id: None,
             author: Some(JiraUser {
                 display_name: author.to_string(),
                 account_id: None,
@@ -3627,6 +15095,7 @@ user_email = "user@example.com"
                 summary: "Implement backlog creation".to_string(),
                 status: Some(JiraStatus {
                     name: "In Progress".to_string(),
+                    status_category: None,
                 }),
                 sprint: None,
                 description: None,
@@ -3638,6 +15107,8 @@ user_email = "user@example.com"
                 updated: None,
                 due_date: None,
                 comment: Some(JiraCommentContainer { comments }),
+                labels: Vec::new(),
+                extra: HashMap::new(),
             },
         }
     }
@@ -3674,10 +15145,10 @@ user_email = "user@example.com"
                 }
             }
 
-            if let Some(end) = header_end {
-                if buffer.len() >= end + content_length {
-                    break;
-                }
+            if let Some(end) = header_end
+                && buffer.len() >= end + content_length
+            {
+                break;
             }
         }
 
@@ -0,0 +1,172 @@
+//! `--porcelain` output: tab-separated lines with a layout that only changes
+//! with a new version, for scripts and editor plugins to parse while the human
+//! output keeps evolving.
+//!
+//! Version 1 starts with a `# jit porcelain v1` line, followed by:
+//!
+//! ```text
+//! issue<TAB>key<TAB>status<TAB>status category<TAB>summary
+//! field<TAB>name<TAB>value
+//! comment<TAB>author<TAB>created<TAB>body
+//! ```
+//!
+//! Missing values are empty. Backslashes, tabs, and newlines inside values are
+//! written as `\\`, `\t`, and `\n`. Later v1 releases may add line kinds or
+//! field names, so parsers should skip the ones they do not know.
+
+use crate::{JiraComment, JiraIssue, adf_value_to_display_text};
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum PorcelainVersion {
+    V1,
+}
+
+pub(crate) struct Porcelain {
+    lines: Vec<String>,
+}
+
+impl Porcelain {
+    pub(crate) fn new(version: PorcelainVersion) -> Self {
+        let version = match version {
+            PorcelainVersion::V1 => "v1",
+        };
+        Porcelain {
+            lines: vec![format!("# jit porcelain {}", version)],
+        }
+    }
+
+    pub(crate) fn issue(self, issue: &JiraIssue) -> Self {
+        let status = issue.fields.status.as_ref();
+        self.line(&[
+            "issue",
+            &issue.key,
+            status.map_or("", |status| status.name.as_str()),
+            status
+                .and_then(|status| status.status_category.as_ref())
+                .map_or("", |category| category.key.as_str()),
+            &issue.fields.summary,
+        ])
+    }
+
+    /// The issue line followed by a field line for each detail that was fetched.
+    pub(crate) fn issue_details(self, issue: &JiraIssue, url: &str) -> Self {
+        let fields = &issue.fields;
+        let mut porcelain = self
+            .issue(issue)
+            .field(
+                "type",
+                fields.issuetype.as_ref().map(|kind| kind.name.as_str()),
+            )
+            .field(
+                "priority",
+                fields
+                    .priority
+                    .as_ref()
+                    .map(|priority| priority.name.as_str()),
+            )
+            .field(
+                "assignee",
+                fields
+                    .assignee
+                    .as_ref()
+                    .map(|user| user.display_name.as_str()),
+            )
+            .field(
+                "reporter",
+                fields
+                    .reporter
+                    .as_ref()
+                    .map(|user| user.display_name.as_str()),
+            )
+            .field("created", fields.created.as_deref())
+            .field("updated", fields.updated.as_deref())
+            .field("due", fields.due_date.as_deref())
+            .field("labels", Some(fields.labels.join(",").as_str()))
+            .field("url", Some(url));
+        if let Some(description) = &fields.description {
+            let description = adf_value_to_display_text(description);
+            porcelain = porcelain.field("description", Some(description.trim()));
+        }
+        for comment in fields
+            .comment
+            .iter()
+            .flat_map(|container| &container.comments)
+        {
+            porcelain = porcelain.comment(comment);
+        }
+        porcelain
+    }
+
+    pub(crate) fn field(self, name: &str, value: Option<&str>) -> Self {
+        self.line(&["field", name, value.unwrap_or_default()])
+    }
+
+    fn comment(self, comment: &JiraComment) -> Self {
+        let body = comment
+            .body
+            .as_ref()
+            .map(adf_value_to_display_text)
+            .unwrap_or_default();
+        self.line(&[
+            "comment",
+            comment
+                .author
+                .as_ref()
+                .map_or("", |author| author.display_name.as_str()),
+            comment.created.as_deref().unwrap_or_default(),
+            body.trim(),
+        ])
+    }
+
+    fn line(mut self, values: &[&str]) -> Self {
+        let escaped: Vec<String> = values.iter().map(|value| escape(value)).collect();
+        self.lines.push(escaped.join("\t"));
+        self
+    }
+
+    pub(crate) fn print(&self) {
+        println!("{}", self.lines.join("\n"));
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_tab_separated_with_escaped_values() {
+        let issue: JiraIssue = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "key": "RW-1",
+            "fields": {
+                "summary": "Fix\tlogin\ncrash",
+                "status": {"name": "In Progress", "statusCategory": {"key": "indeterminate"}}
+            }
+        }))
+        .unwrap();
+
+        let porcelain = Porcelain::new(PorcelainVersion::V1)
+            .issue(&issue)
+            .field("path", Some("C:\\jit"))
+            .field("due", None);
+
+        assert_eq!(
+            porcelain.lines,
+            [
+                "# jit porcelain v1",
+                "issue\tRW-1\tIn Progress\tindeterminate\tFix\\tlogin\\ncrash",
+                "field\tpath\tC:\\\\jit",
+                "field\tdue\t",
+            ]
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! Personal reminders about tickets, kept in `reminders.json` in the data
+//! directory. They have nothing to do with Jira due dates: a reminder is shown
+//! once by the first jit run after it falls due, then dropped.
+
+use crate::jit_data_dir;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Reminder {
+    pub(crate) id: u64,
+    pub(crate) ticket: String,
+    pub(crate) note: String,
+    /// RFC 3339, in UTC.
+    pub(crate) due: String,
+}
+
+impl Reminder {
+    /// The due time for display, e.g. `2026-05-03 09:00 UTC`.
+    pub(crate) fn due_label(&self) -> String {
+        self.due_at()
+            .map(|due| due.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| self.due.clone())
+    }
+
+    fn due_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.due)
+            .ok()
+            .map(|due| due.with_timezone(&Utc))
+    }
+}
+
+/// Stores a new reminder and returns it.
+pub(crate) fn add(ticket: &str, note: &str, due: DateTime<Utc>) -> Result<Reminder> {
+    let mut reminders = load()?;
+    let reminder = Reminder {
+        id: reminders
+            .iter()
+            .map(|reminder| reminder.id)
+            .max()
+            .unwrap_or(0)
+            + 1,
+        ticket: ticket.to_string(),
+        note: note.to_string(),
+        due: due.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    };
+    reminders.push(reminder.clone());
+    store(&reminders)?;
+    Ok(reminder)
+}
+
+/// Pending reminders, soonest first.
+pub(crate) fn list() -> Result<Vec<Reminder>> {
+    let mut reminders = load()?;
+    reminders.sort_by_key(|reminder| reminder.due_at());
+    Ok(reminders)
+}
+
+/// Deletes reminder `id`, returning whether it existed.
+pub(crate) fn cancel(id: u64) -> Result<bool> {
+    let mut reminders = load()?;
+    let before = reminders.len();
+    reminders.retain(|reminder| reminder.id != id);
+    if reminders.len() == before {
+        return Ok(false);
+    }
+    store(&reminders)?;
+    Ok(true)
+}
+
+/// Removes and returns the reminders that are due at `now`.
+pub(crate) fn take_due(now: DateTime<Utc>) -> Result<Vec<Reminder>> {
+    let reminders = load()?;
+    let (due, pending) = split_due(reminders, now);
+    if !due.is_empty() {
+        store(&pending)?;
+    }
+    Ok(due)
+}
+
+/// Splits `reminders` into those due at `now` and those still pending. A
+/// reminder whose due time cannot be read counts as due, so it is not kept forever.
+fn split_due(reminders: Vec<Reminder>, now: DateTime<Utc>) -> (Vec<Reminder>, Vec<Reminder>) {
+    reminders
+        .into_iter()
+        .partition(|reminder| reminder.due_at().is_none_or(|due| due <= now))
+}
+
+fn reminders_path() -> Result<PathBuf> {
+    Ok(jit_data_dir()?.join("reminders.json"))
+}
+
+fn load() -> Result<Vec<Reminder>> {
+    let path = reminders_path()?;
+    match fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display())),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+fn store(reminders: &[Reminder]) -> Result<()> {
+    let path = reminders_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Write then rename, so concurrent runs never read a half-written file.
+    let partial = path.with_extension(format!("json.{}", std::process::id()));
+    fs::write(&partial, serde_json::to_string_pretty(reminders)?)?;
+    fs::rename(&partial, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reminder(id: u64, due: &str) -> Reminder {
+        Reminder {
+            id,
+            ticket: "RW-1931".to_string(),
+            note: "ping infra".to_string(),
+            due: due.to_string(),
+        }
+    }
+
+    #[test]
+    fn split_due_separates_due_and_pending_reminders() {
+        let now = DateTime::parse_from_rfc3339("2026-05-02T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let (due, pending) = split_due(
+            vec![
+                reminder(1, "2026-05-01T09:00:00Z"),
+                reminder(2, "2026-05-03T09:00:00Z"),
+                reminder(3, "2026-05-02T12:00:00Z"),
+                reminder(4, "tomorrow"),
+            ],
+            now,
+        );
+
+        assert_eq!(
+            due.iter().map(|reminder| reminder.id).collect::<Vec<_>>(),
+            vec![1, 3, 4]
+        );
+        assert_eq!(
+            pending
+                .iter()
+                .map(|reminder| reminder.id)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+}
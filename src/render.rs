@@ -0,0 +1,1789 @@
+//! Output rendering: the formats accepted by `--output`, a renderer per format,
+//! and the view models commands hand to them.
+
+use crate::editor_links::EditorLinks;
+use crate::error::JitError;
+use crate::{
+    ChecklistItem, CiStatus, JiraIssue, JiraPullRequest, SPRINT_FIELD, STORY_POINTS_FIELD,
+    adf_to_markdown, adf_value_to_display_text, format_ci_status, format_date, format_points,
+    format_pull_request_summary, get_colored_ci_status, get_colored_status, get_filtered_comments,
+    issue_field_text, pull_request_display_id, truncate_with_ellipsis,
+};
+use anyhow::Result;
+use clap::ValueEnum;
+use colored::*;
+use handlebars::Handlebars;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Output formats accepted by `--output` and the `[output]` config section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum OutputFormat {
+    /// Human-readable tables and labels
+    Table,
+    /// Plain text, one record per line
+    Text,
+    /// A single JSON document
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// Comma-separated values with a header row
+    Csv,
+    /// YAML document
+    Yaml,
+    /// GitHub-flavored Markdown table
+    Markdown,
+}
+
+impl OutputFormat {
+    /// Applies a command's legacy `--json` flag on top of the resolved format.
+    pub(crate) fn or_json(self, json: bool) -> OutputFormat {
+        if json { OutputFormat::Json } else { self }
+    }
+
+    /// Applies a command's legacy `--csv` flag on top of the resolved format.
+    pub(crate) fn or_csv(self, csv: bool) -> OutputFormat {
+        if csv { OutputFormat::Csv } else { self }
+    }
+
+    /// Applies a list command's `--ndjson` flag on top of the resolved format.
+    pub(crate) fn or_ndjson(self, ndjson: bool) -> OutputFormat {
+        if ndjson { OutputFormat::Ndjson } else { self }
+    }
+
+    /// Applies a command's `--markdown` flag on top of the resolved format.
+    pub(crate) fn or_markdown(self, markdown: bool) -> OutputFormat {
+        if markdown {
+            OutputFormat::Markdown
+        } else {
+            self
+        }
+    }
+
+    fn renderer(self) -> Box<dyn Renderer> {
+        match self {
+            OutputFormat::Table => Box::new(TableRenderer),
+            OutputFormat::Text => Box::new(PlainRenderer),
+            OutputFormat::Json => Box::new(JsonRenderer),
+            OutputFormat::Ndjson => Box::new(NdjsonRenderer),
+            OutputFormat::Csv => Box::new(CsvRenderer),
+            OutputFormat::Yaml => Box::new(YamlRenderer),
+            OutputFormat::Markdown => Box::new(MarkdownRenderer),
+        }
+    }
+}
+
+/// A command result that can be rendered in every `OutputFormat`.
+pub(crate) trait CommandOutput {
+    /// Structured payload used for JSON and YAML output.
+    fn to_json(&self) -> Value;
+
+    /// Column headers and rows used for CSV, Markdown, and text output.
+    fn to_rows(&self) -> (Vec<String>, Vec<Vec<String>>);
+
+    /// Values written one per line for NDJSON output.
+    fn to_records(&self) -> Vec<Value> {
+        let (headers, rows) = self.to_rows();
+        rows_to_records(&headers, &rows)
+    }
+
+    /// Human-readable output; a box table of `to_rows` unless the command has its own layout.
+    fn print_table(&self) -> Result<()> {
+        let (headers, rows) = self.to_rows();
+        print_box_table(&[vec![headers], rows].concat());
+        Ok(())
+    }
+
+    /// Plain text output; tab-separated rows without a header by default.
+    fn print_text(&self) -> Result<()> {
+        let (_, rows) = self.to_rows();
+        for row in rows {
+            println!("{}", row.join("\t"));
+        }
+        Ok(())
+    }
+
+    /// GitHub-flavored Markdown; a table of `to_rows` unless the command has its own layout.
+    fn print_markdown(&self) -> Result<()> {
+        let (headers, rows) = self.to_rows();
+        print_markdown_table(&headers, &rows);
+        Ok(())
+    }
+}
+
+/// Writes a `CommandOutput` to stdout in one particular format.
+pub(crate) trait Renderer {
+    fn render(&self, output: &dyn CommandOutput) -> Result<()>;
+}
+
+pub(crate) struct TableRenderer;
+
+impl Renderer for TableRenderer {
+    fn render(&self, output: &dyn CommandOutput) -> Result<()> {
+        output.print_table()
+    }
+}
+
+pub(crate) struct PlainRenderer;
+
+impl Renderer for PlainRenderer {
+    fn render(&self, output: &dyn CommandOutput) -> Result<()> {
+        output.print_text()
+    }
+}
+
+pub(crate) struct JsonRenderer;
+
+impl Renderer for JsonRenderer {
+    fn render(&self, output: &dyn CommandOutput) -> Result<()> {
+        println!("{}", output.to_json());
+        Ok(())
+    }
+}
+
+pub(crate) struct NdjsonRenderer;
+
+impl Renderer for NdjsonRenderer {
+    fn render(&self, output: &dyn CommandOutput) -> Result<()> {
+        for record in output.to_records() {
+            println!("{}", record);
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct YamlRenderer;
+
+impl Renderer for YamlRenderer {
+    fn render(&self, output: &dyn CommandOutput) -> Result<()> {
+        print!("{}", serde_yaml::to_string(&output.to_json())?);
+        Ok(())
+    }
+}
+
+pub(crate) struct CsvRenderer;
+
+impl Renderer for CsvRenderer {
+    fn render(&self, output: &dyn CommandOutput) -> Result<()> {
+        let (headers, rows) = output.to_rows();
+        for row in std::iter::once(headers).chain(rows) {
+            let cells: Vec<String> = row.iter().map(|cell| csv_field(cell)).collect();
+            println!("{}", cells.join(","));
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct MarkdownRenderer;
+
+impl Renderer for MarkdownRenderer {
+    fn render(&self, output: &dyn CommandOutput) -> Result<()> {
+        output.print_markdown()
+    }
+}
+
+fn print_markdown_table(headers: &[String], rows: &[Vec<String>]) {
+    let cells = |row: &[String]| {
+        row.iter()
+            .map(|cell| markdown_cell(cell))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+    println!("| {} |", cells(headers));
+    println!("|{}|", vec![" --- "; headers.len()].join("|"));
+    for row in rows {
+        println!("| {} |", cells(row));
+    }
+}
+
+pub(crate) fn render_output(format: OutputFormat, output: &dyn CommandOutput) -> Result<()> {
+    format.renderer().render(output)
+}
+
+/// Renders through the `--format` template when there is one, otherwise in `format`.
+pub(crate) fn render_formatted(
+    format: OutputFormat,
+    template: Option<&OutputTemplate>,
+    output: &dyn CommandOutput,
+) -> Result<()> {
+    match template {
+        Some(template) => template.render(output),
+        None => render_output(format, output),
+    }
+}
+
+/// A `--format` Handlebars template, printed once per record of a command's
+/// JSON output: once per ticket of a list, or once for a single ticket.
+pub(crate) struct OutputTemplate {
+    registry: Handlebars<'static>,
+}
+
+impl OutputTemplate {
+    const NAME: &'static str = "format";
+
+    pub(crate) fn new(template: &str) -> Result<Self> {
+        let mut registry = Handlebars::new();
+        // Output goes to a terminal or a pipe, not a web page.
+        registry.register_escape_fn(handlebars::no_escape);
+        registry
+            .register_template_string(Self::NAME, template)
+            .map_err(|err| JitError::Config(format!("Invalid --format template: {}", err)))?;
+        Ok(OutputTemplate { registry })
+    }
+
+    fn render(&self, output: &dyn CommandOutput) -> Result<()> {
+        let records = match output.to_json() {
+            Value::Array(records) => records,
+            record => vec![record],
+        };
+        for mut record in records {
+            // Tickets are `ticket` in JSON output; templates can call it `key` too.
+            if record.get("key").is_none()
+                && let Some(ticket) = record.get("ticket").cloned()
+            {
+                record["key"] = ticket;
+            }
+            println!("{}", self.registry.render(Self::NAME, &record)?);
+        }
+        Ok(())
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn rows_to_records(headers: &[String], rows: &[Vec<String>]) -> Vec<Value> {
+    rows.iter()
+        .map(|row| {
+            Value::Object(
+                headers
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().cloned().map(Value::String))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Renders a JSON scalar as plain text; objects and arrays return `None`.
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        Value::Null => Some(String::new()),
+        Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+type HumanPrinter<'a> = Box<dyn Fn() -> Result<()> + 'a>;
+
+/// Label/value output for commands that report a single result.
+pub(crate) struct RecordOutput<'a> {
+    json: Value,
+    fields: Vec<(String, String)>,
+    label_width: usize,
+    line: Option<String>,
+    human: Option<HumanPrinter<'a>>,
+}
+
+impl<'a> RecordOutput<'a> {
+    pub(crate) fn new(json: Value) -> Self {
+        RecordOutput {
+            json,
+            fields: Vec::new(),
+            label_width: 10,
+            line: None,
+            human: None,
+        }
+    }
+
+    pub(crate) fn field(mut self, label: &str, value: impl std::fmt::Display) -> Self {
+        self.fields.push((label.to_string(), value.to_string()));
+        self
+    }
+
+    pub(crate) fn label_width(mut self, width: usize) -> Self {
+        self.label_width = width;
+        self
+    }
+
+    /// One-line summary used for text output, and for table output when no other layout is set.
+    pub(crate) fn line(mut self, line: impl Into<String>) -> Self {
+        self.line = Some(line.into());
+        self
+    }
+
+    pub(crate) fn human(mut self, printer: impl Fn() -> Result<()> + 'a) -> Self {
+        self.human = Some(Box::new(printer));
+        self
+    }
+}
+
+impl CommandOutput for RecordOutput<'_> {
+    fn to_json(&self) -> Value {
+        self.json.clone()
+    }
+
+    fn to_rows(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let headers = self.fields.iter().map(|(label, _)| label.clone()).collect();
+        let values = self.fields.iter().map(|(_, value)| value.clone()).collect();
+        (headers, vec![values])
+    }
+
+    fn to_records(&self) -> Vec<Value> {
+        vec![self.json.clone()]
+    }
+
+    fn print_table(&self) -> Result<()> {
+        if let Some(human) = self.human.as_ref() {
+            return human();
+        }
+        if self.fields.is_empty()
+            && let Some(line) = self.line.as_deref()
+        {
+            println!("{}", line);
+            return Ok(());
+        }
+        for (label, value) in &self.fields {
+            let label = format!("{}:", label);
+            let width = self.label_width.max(label.len() + 1);
+            println!("{:<width$}{}", label, value, width = width);
+        }
+        Ok(())
+    }
+
+    fn print_text(&self) -> Result<()> {
+        match self.line.as_deref() {
+            Some(line) => println!("{}", line),
+            None => {
+                let (_, rows) = self.to_rows();
+                println!("{}", rows[0].join("\t"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Row-based output for commands that report several results.
+pub(crate) struct TableOutput<'a> {
+    json: Value,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    human: Option<HumanPrinter<'a>>,
+}
+
+impl<'a> TableOutput<'a> {
+    pub(crate) fn new(json: Value, headers: &[&str], rows: Vec<Vec<String>>) -> Self {
+        TableOutput {
+            json,
+            headers: headers.iter().map(|header| header.to_string()).collect(),
+            rows,
+            human: None,
+        }
+    }
+
+    pub(crate) fn human(mut self, printer: impl Fn() -> Result<()> + 'a) -> Self {
+        self.human = Some(Box::new(printer));
+        self
+    }
+}
+
+impl CommandOutput for TableOutput<'_> {
+    fn to_json(&self) -> Value {
+        self.json.clone()
+    }
+
+    fn to_rows(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        (self.headers.clone(), self.rows.clone())
+    }
+
+    fn to_records(&self) -> Vec<Value> {
+        match &self.json {
+            Value::Array(items) => items.clone(),
+            _ => rows_to_records(&self.headers, &self.rows),
+        }
+    }
+
+    fn print_table(&self) -> Result<()> {
+        match self.human.as_ref() {
+            Some(human) => human(),
+            None => {
+                print_box_table(&[vec![self.headers.clone()], self.rows.clone()].concat());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Sections of an issue to include beyond its core fields.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct IssueSections<'a> {
+    pub(crate) description: bool,
+    pub(crate) comments: bool,
+    pub(crate) pull_requests: bool,
+    pub(crate) comments_limit: usize,
+    pub(crate) all_comments: bool,
+    pub(crate) since: Option<&'a str>,
+}
+
+struct CommentView {
+    author: Option<String>,
+    created: Option<String>,
+    updated: Option<String>,
+    body: Option<String>,
+}
+
+struct CommentsView {
+    comments: Vec<CommentView>,
+    limit: Option<usize>,
+    since: Option<String>,
+}
+
+/// One row of a `[layouts.<type>]` layout, with its value already as text.
+pub(crate) struct LayoutField {
+    pub(crate) field: String,
+    pub(crate) label: String,
+    pub(crate) value: Option<String>,
+}
+
+/// A single issue as shown by `--show` and the detailed JSON output.
+pub(crate) struct IssueView {
+    key: String,
+    summary: String,
+    status: Option<String>,
+    issue_type: Option<String>,
+    priority: Option<String>,
+    assignee: Option<String>,
+    reporter: Option<String>,
+    sprint: Option<String>,
+    created: Option<String>,
+    updated: Option<String>,
+    due_date: Option<String>,
+    story_points: Option<f64>,
+    description: Option<Option<String>>,
+    /// The description converted to Markdown, for Markdown output.
+    description_markdown: Option<String>,
+    comments: Option<CommentsView>,
+    pull_requests: Option<Vec<JiraPullRequest>>,
+    ci_status: Option<CiStatus>,
+    /// Replaces the standard field grid in table output when the issue type has a layout.
+    layout: Option<Vec<LayoutField>>,
+    /// Fields asked for with `--fields`, shown after the standard ones.
+    extra_fields: Vec<LayoutField>,
+    acceptance_criteria: Option<Vec<ChecklistItem>>,
+    /// Applied to the description and comments in table output only.
+    editor_links: Option<EditorLinks>,
+    /// Browse URL the key links to in Markdown output.
+    url: Option<String>,
+}
+
+impl IssueView {
+    pub(crate) fn from_issue(
+        issue: &JiraIssue,
+        sections: IssueSections,
+        pull_requests: &[JiraPullRequest],
+        ci_status: Option<&CiStatus>,
+    ) -> Self {
+        let adf_text = |value: Option<&Value>| {
+            value
+                .filter(|value| !value.is_null())
+                .map(adf_value_to_display_text)
+        };
+        let fields = &issue.fields;
+
+        let comments = sections.comments.then(|| CommentsView {
+            comments: get_filtered_comments(
+                issue,
+                sections.since,
+                sections.comments_limit,
+                sections.all_comments,
+            )
+            .into_iter()
+            .map(|comment| CommentView {
+                author: comment.author.as_ref().map(|a| a.display_name.clone()),
+                created: comment.created.clone(),
+                updated: comment.updated.clone(),
+                body: adf_text(comment.body.as_ref()),
+            })
+            .collect(),
+            limit: (!sections.all_comments).then_some(sections.comments_limit),
+            since: sections.since.map(str::to_string),
+        });
+
+        IssueView {
+            key: issue.key.clone(),
+            summary: fields.summary.clone(),
+            status: fields.status.as_ref().map(|s| s.name.clone()),
+            issue_type: fields.issuetype.as_ref().map(|t| t.name.clone()),
+            priority: fields.priority.as_ref().map(|p| p.name.clone()),
+            assignee: fields.assignee.as_ref().map(|a| a.display_name.clone()),
+            reporter: fields.reporter.as_ref().map(|r| r.display_name.clone()),
+            sprint: issue_sprint_name(issue),
+            created: fields.created.clone(),
+            updated: fields.updated.clone(),
+            due_date: fields.due_date.clone(),
+            story_points: fields.story_points(),
+            description: sections
+                .description
+                .then(|| adf_text(fields.description.as_ref())),
+            description_markdown: fields
+                .description
+                .as_ref()
+                .filter(|value| sections.description && !value.is_null())
+                .map(adf_to_markdown)
+                .filter(|markdown| !markdown.is_empty()),
+            comments,
+            pull_requests: sections.pull_requests.then(|| pull_requests.to_vec()),
+            ci_status: ci_status.cloned(),
+            layout: None,
+            extra_fields: Vec::new(),
+            acceptance_criteria: None,
+            editor_links: None,
+            url: None,
+        }
+    }
+
+    pub(crate) fn with_url(mut self, url: String) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    pub(crate) fn with_layout(mut self, layout: Vec<LayoutField>) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
+    pub(crate) fn with_extra_fields(mut self, fields: Vec<LayoutField>) -> Self {
+        self.extra_fields = fields;
+        self
+    }
+
+    pub(crate) fn with_acceptance_criteria(mut self, criteria: Vec<ChecklistItem>) -> Self {
+        self.acceptance_criteria = Some(criteria);
+        self
+    }
+
+    pub(crate) fn with_editor_links(mut self, links: EditorLinks) -> Self {
+        self.editor_links = Some(links);
+        self
+    }
+
+    fn print_field_grid(&self) {
+        let or = |value: &Option<String>, fallback: &str| {
+            value.clone().unwrap_or_else(|| fallback.to_string())
+        };
+        let left_col_width = 12; // "Due Date: " width
+        let val_col_width = 18; // Width for value columns
+        let pairs = [
+            (
+                ("Type:", or(&self.issue_type, "Not set")),
+                Some(("Priority:", or(&self.priority, "Not set"))),
+            ),
+            (
+                ("Status:", get_colored_status(&or(&self.status, "Not set"))),
+                Some(("Sprint:", or(&self.sprint, "Not in sprint"))),
+            ),
+            (
+                ("Assignee:", or(&self.assignee, "Unassigned")),
+                Some(("Reporter:", or(&self.reporter, "Unknown"))),
+            ),
+            (
+                ("Created:", format_date(&or(&self.created, "Unknown"))),
+                Some(("Updated:", format_date(&or(&self.updated, "Unknown")))),
+            ),
+            (
+                ("Due Date:", format_date(&or(&self.due_date, "Not set"))),
+                self.story_points
+                    .map(|points| ("Points:", format_points(points))),
+            ),
+        ];
+        for ((left_label, left_value), right) in pairs {
+            let mut line = format!(
+                "{:<left$} {:<val$}",
+                left_label.bold(),
+                left_value,
+                left = left_col_width,
+                val = val_col_width
+            );
+            if let Some((right_label, right_value)) = right {
+                line.push_str(&format!(
+                    " {:<left$} {:<val$}",
+                    right_label.bold(),
+                    right_value,
+                    left = left_col_width,
+                    val = val_col_width
+                ));
+            }
+            println!("{}", line);
+        }
+    }
+
+    /// One `Label: value` line per layout field, in the configured order.
+    fn print_layout(&self, layout: &[LayoutField]) {
+        let width = layout
+            .iter()
+            .map(|row| row.label.width() + 1)
+            .max()
+            .unwrap_or_default();
+        for row in layout {
+            let value = match row.value.as_deref() {
+                Some(value) if row.field == "status" => get_colored_status(value),
+                Some(value) => self.linked(value),
+                None => "Not set".to_string(),
+            };
+            let label = format!("{}:", row.label);
+            println!("{:<width$} {}", label.bold(), value, width = width);
+        }
+    }
+
+    fn linked(&self, text: &str) -> String {
+        match &self.editor_links {
+            Some(links) => links.apply(text),
+            None => text.to_string(),
+        }
+    }
+}
+
+impl CommandOutput for IssueView {
+    fn to_json(&self) -> Value {
+        let mut payload = json!({
+            "ticket": self.key,
+            "summary": self.summary,
+            "status": self.status,
+            "issue_type": self.issue_type,
+            "priority": self.priority,
+            "assignee": self.assignee,
+            "reporter": self.reporter,
+            "sprint": self.sprint,
+            "created": self.created,
+            "updated": self.updated,
+            "due_date": self.due_date,
+            "story_points": self.story_points,
+        });
+
+        if let Some(obj) = payload.as_object_mut() {
+            if let Some(description) = &self.description {
+                obj.insert("description".to_string(), json!(description));
+            }
+
+            if let Some(criteria) = &self.acceptance_criteria {
+                let items: Vec<Value> = criteria
+                    .iter()
+                    .map(|item| json!({ "text": item.text, "checked": item.checked }))
+                    .collect();
+                obj.insert("acceptance_criteria".to_string(), Value::Array(items));
+            }
+
+            if let Some(view) = &self.comments {
+                let comments: Vec<Value> = view
+                    .comments
+                    .iter()
+                    .map(|comment| {
+                        json!({
+                            "author": comment.author,
+                            "created": comment.created,
+                            "updated": comment.updated,
+                            "body": comment.body,
+                        })
+                    })
+                    .collect();
+                obj.insert("comments_returned".to_string(), json!(comments.len()));
+                obj.insert("comments".to_string(), Value::Array(comments));
+                if let Some(limit) = view.limit {
+                    obj.insert("comments_limit".to_string(), json!(limit));
+                }
+                if let Some(since) = &view.since {
+                    obj.insert("comments_since".to_string(), json!(since));
+                }
+            }
+
+            if let Some(pull_requests) = &self.pull_requests {
+                let payload: Vec<Value> = pull_requests
+                    .iter()
+                    .map(|pr| {
+                        json!({
+                            "id": pull_request_display_id(pr),
+                            "title": pr.name,
+                            "status": pr.status,
+                            "url": pr.url,
+                            "last_update": pr.last_update
+                        })
+                    })
+                    .collect();
+                obj.insert("pull_requests".to_string(), Value::Array(payload));
+                obj.insert(
+                    "pull_requests_count".to_string(),
+                    json!(pull_requests.len()),
+                );
+            }
+
+            if let Some(layout) = &self.layout {
+                let fields: Vec<Value> = layout
+                    .iter()
+                    .map(
+                        |row| json!({ "field": row.field, "label": row.label, "value": row.value }),
+                    )
+                    .collect();
+                obj.insert("layout".to_string(), Value::Array(fields));
+            }
+
+            if !self.extra_fields.is_empty() {
+                let fields: serde_json::Map<String, Value> = self
+                    .extra_fields
+                    .iter()
+                    .map(|row| (row.field.clone(), json!(row.value)))
+                    .collect();
+                obj.insert("fields".to_string(), Value::Object(fields));
+            }
+
+            if let Some(ci) = &self.ci_status {
+                let summary = &ci.summary;
+                obj.insert(
+                    "ci".to_string(),
+                    json!({
+                        "state": ci.state().as_str(),
+                        "latest": ci.latest.as_ref().map(|build| json!({
+                            "name": ci.latest_name(),
+                            "url": build.url,
+                            "last_updated": build.last_updated,
+                        })),
+                        "total": summary.count,
+                        "successful": summary.successful,
+                        "failed": summary.failed,
+                        "pending": summary.unknown,
+                        "last_updated": summary.last_updated,
+                    }),
+                );
+            }
+        }
+
+        payload
+    }
+
+    fn to_rows(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let mut headers = Vec::new();
+        let mut values = Vec::new();
+        if let Value::Object(fields) = self.to_json() {
+            for (label, value) in &fields {
+                if let Some(text) = scalar_to_string(value) {
+                    headers.push(label.clone());
+                    values.push(text);
+                }
+            }
+        }
+        (headers, vec![values])
+    }
+
+    fn to_records(&self) -> Vec<Value> {
+        vec![self.to_json()]
+    }
+
+    fn print_table(&self) -> Result<()> {
+        println!("{}", "TICKET DETAILS".bold());
+        println!();
+
+        // Print the ticket key and summary as headers
+        println!("{}: {}", self.key.bold(), self.summary.bold());
+        println!();
+
+        if let Some(layout) = &self.layout {
+            self.print_layout(layout);
+        } else {
+            self.print_field_grid();
+        }
+        if !self.extra_fields.is_empty() {
+            println!();
+            self.print_layout(&self.extra_fields);
+        }
+
+        if let Some(description) = &self.description {
+            println!();
+            println!("{}", "DESCRIPTION".bold());
+            println!();
+            match description {
+                Some(description) => println!("{}", self.linked(description)),
+                None => println!("No description provided."),
+            }
+        }
+
+        if let Some(criteria) = &self.acceptance_criteria {
+            println!();
+            println!("{}", "ACCEPTANCE CRITERIA".bold());
+            println!();
+            if criteria.is_empty() {
+                println!("No acceptance criteria.");
+            }
+            for item in criteria {
+                let marker = if item.checked {
+                    "[x]".green()
+                } else {
+                    "[ ]".normal()
+                };
+                println!("{} {}", marker, self.linked(&item.text));
+            }
+        }
+
+        if let Some(view) = &self.comments {
+            println!();
+            println!("{}", "COMMENTS".bold());
+            println!();
+
+            if view.comments.is_empty() {
+                if view.since.is_some() {
+                    println!("No comments found for the provided filters.");
+                } else {
+                    println!("No comments found.");
+                }
+            }
+            for (index, comment) in view.comments.iter().enumerate() {
+                println!(
+                    "#{} {} | created: {} | updated: {}",
+                    index + 1,
+                    comment.author.as_deref().unwrap_or("Unknown").bold(),
+                    comment.created.as_deref().unwrap_or("Unknown"),
+                    comment.updated.as_deref().unwrap_or("Unknown")
+                );
+                match &comment.body {
+                    Some(body) => println!("{}", self.linked(body)),
+                    None => println!("(No comment body)"),
+                }
+                if index < view.comments.len() - 1 {
+                    println!();
+                }
+            }
+        }
+
+        if let Some(pull_requests) = &self.pull_requests {
+            println!();
+            println!("{}", "PULL REQUESTS".bold());
+            println!();
+
+            if pull_requests.is_empty() {
+                println!("No pull requests found.");
+            }
+            for (index, pr) in pull_requests.iter().enumerate() {
+                println!(
+                    "#{} {} [{}] | updated: {}",
+                    index + 1,
+                    pull_request_display_id(pr).bold(),
+                    pr.status.as_deref().unwrap_or("Unknown"),
+                    pr.last_update.as_deref().unwrap_or("Unknown")
+                );
+                println!("{}", pr.name.as_deref().unwrap_or("Untitled PR"));
+                println!("{}", pr.url.as_deref().unwrap_or("No URL"));
+                if index < pull_requests.len() - 1 {
+                    println!();
+                }
+            }
+        }
+
+        if let Some(ci) = &self.ci_status {
+            println!();
+            println!("{}", "CI STATUS".bold());
+            println!();
+            println!("{}", get_colored_ci_status(ci));
+            if let Some(url) = ci.latest.as_ref().and_then(|build| build.url.as_deref()) {
+                println!("Build: {}", url.underline());
+            }
+            let last_updated = ci
+                .latest
+                .as_ref()
+                .and_then(|build| build.last_updated.as_deref())
+                .or(ci.summary.last_updated.as_deref());
+            if let Some(last_updated) = last_updated {
+                println!("Last build: {}", last_updated);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn print_text(&self) -> Result<()> {
+        println!("{}: {}", self.key, self.summary);
+        Ok(())
+    }
+
+    /// A heading, a table of the set fields, and a section per included part,
+    /// for pasting into pull requests and documents.
+    fn print_markdown(&self) -> Result<()> {
+        let key = match &self.url {
+            Some(url) => format!("[{}]({})", self.key, url),
+            None => self.key.clone(),
+        };
+        println!("# {}: {}", key, self.summary);
+        println!();
+
+        let layout_rows = |layout: &[LayoutField]| {
+            layout
+                .iter()
+                .map(|row| {
+                    let value = row.value.as_deref().unwrap_or("Not set");
+                    vec![row.label.clone(), value.to_string()]
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut rows: Vec<Vec<String>> = match &self.layout {
+            Some(layout) => layout_rows(layout),
+            None => [
+                ("Type", self.issue_type.clone()),
+                ("Status", self.status.clone()),
+                ("Priority", self.priority.clone()),
+                ("Assignee", self.assignee.clone()),
+                ("Reporter", self.reporter.clone()),
+                ("Sprint", self.sprint.clone()),
+                ("Created", self.created.as_deref().map(format_date)),
+                ("Updated", self.updated.as_deref().map(format_date)),
+                ("Due Date", self.due_date.as_deref().map(format_date)),
+                ("Points", self.story_points.map(format_points)),
+            ]
+            .into_iter()
+            .filter_map(|(label, value)| Some(vec![label.to_string(), value?]))
+            .collect(),
+        };
+        rows.extend(layout_rows(&self.extra_fields));
+        print_markdown_table(&["Field".to_string(), "Value".to_string()], &rows);
+
+        if let Some(description) = &self.description {
+            println!();
+            println!("## Description");
+            println!();
+            match (&self.description_markdown, description) {
+                (Some(markdown), _) => println!("{}", markdown),
+                (None, Some(text)) => println!("{}", text),
+                (None, None) => println!("No description provided."),
+            }
+        }
+
+        if let Some(criteria) = &self.acceptance_criteria {
+            println!();
+            println!("## Acceptance Criteria");
+            println!();
+            if criteria.is_empty() {
+                println!("No acceptance criteria.");
+            }
+            for item in criteria {
+                let mark = if item.checked { "x" } else { " " };
+                println!("- [{}] {}", mark, item.text);
+            }
+        }
+
+        if let Some(view) = &self.comments {
+            println!();
+            println!("## Comments");
+            if view.comments.is_empty() {
+                println!();
+                println!("No comments found.");
+            }
+            for comment in &view.comments {
+                println!();
+                println!(
+                    "**{}** ({})",
+                    comment.author.as_deref().unwrap_or("Unknown"),
+                    comment
+                        .created
+                        .as_deref()
+                        .map_or("Unknown".to_string(), format_date)
+                );
+                println!();
+                println!("{}", comment.body.as_deref().unwrap_or("(No comment body)"));
+            }
+        }
+
+        if let Some(pull_requests) = &self.pull_requests {
+            println!();
+            println!("## Pull Requests");
+            println!();
+            if pull_requests.is_empty() {
+                println!("No pull requests found.");
+            }
+            for pr in pull_requests {
+                let title = format!(
+                    "{} {}",
+                    pull_request_display_id(pr),
+                    pr.name.as_deref().unwrap_or("Untitled PR")
+                );
+                let title = match pr.url.as_deref() {
+                    Some(url) => format!("[{}]({})", title, url),
+                    None => title,
+                };
+                println!(
+                    "- {} ({})",
+                    title,
+                    pr.status.as_deref().unwrap_or("Unknown")
+                );
+            }
+        }
+
+        if let Some(ci) = &self.ci_status {
+            println!();
+            println!("## CI Status");
+            println!();
+            println!("{}", format_ci_status(ci));
+        }
+
+        Ok(())
+    }
+}
+
+/// A column of a ticket list, picked with `--columns`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub(crate) enum IssueColumn {
+    Key,
+    Summary,
+    Status,
+    Assignee,
+    Reporter,
+    Priority,
+    Type,
+    Sprint,
+    Created,
+    Updated,
+    Labels,
+    Points,
+}
+
+impl IssueColumn {
+    /// The columns shown when `--columns` is not passed.
+    pub(crate) const DEFAULT: [IssueColumn; 3] =
+        [IssueColumn::Key, IssueColumn::Summary, IssueColumn::Status];
+
+    fn header(self) -> &'static str {
+        match self {
+            IssueColumn::Key => "Key",
+            IssueColumn::Summary => "Summary",
+            IssueColumn::Status => "Status",
+            IssueColumn::Assignee => "Assignee",
+            IssueColumn::Reporter => "Reporter",
+            IssueColumn::Priority => "Priority",
+            IssueColumn::Type => "Type",
+            IssueColumn::Sprint => "Sprint",
+            IssueColumn::Created => "Created",
+            IssueColumn::Updated => "Updated",
+            IssueColumn::Labels => "Labels",
+            IssueColumn::Points => "Points",
+        }
+    }
+
+    /// Key of the column in JSON output; `ticket` for the key, as elsewhere.
+    fn json_key(self) -> &'static str {
+        match self {
+            IssueColumn::Key => "ticket",
+            IssueColumn::Type => "issue_type",
+            IssueColumn::Sprint => "sprint",
+            IssueColumn::Points => "points",
+            column => column.field(),
+        }
+    }
+
+    /// The Jira field a search has to return for the column to be filled in.
+    pub(crate) fn field(self) -> &'static str {
+        match self {
+            IssueColumn::Key | IssueColumn::Summary => "summary",
+            IssueColumn::Status => "status",
+            IssueColumn::Assignee => "assignee",
+            IssueColumn::Reporter => "reporter",
+            IssueColumn::Priority => "priority",
+            IssueColumn::Type => "issuetype",
+            IssueColumn::Sprint => SPRINT_FIELD,
+            IssueColumn::Created => "created",
+            IssueColumn::Updated => "updated",
+            IssueColumn::Labels => "labels",
+            IssueColumn::Points => STORY_POINTS_FIELD,
+        }
+    }
+
+    fn value(self, issue: &JiraIssue) -> String {
+        match self {
+            IssueColumn::Key => issue.key.clone(),
+            IssueColumn::Status => issue
+                .fields
+                .status
+                .as_ref()
+                .map_or("Unknown", |s| &s.name)
+                .to_string(),
+            IssueColumn::Sprint => issue_sprint_name(issue).unwrap_or_default(),
+            IssueColumn::Points => issue
+                .fields
+                .story_points()
+                .map(format_points)
+                .unwrap_or_default(),
+            IssueColumn::Created | IssueColumn::Updated => {
+                let date = issue_field_text(issue, self.field());
+                if date.is_empty() {
+                    date
+                } else {
+                    format_date(&date)
+                }
+            }
+            column => issue_field_text(issue, column.field()),
+        }
+    }
+}
+
+/// How `--group-by` splits a ticket list into swimlanes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum IssueGrouping {
+    /// Under each ticket's epic, as on a board with epic swimlanes
+    Epic,
+}
+
+impl IssueGrouping {
+    /// The Jira field a search has to return to group by it.
+    pub(crate) fn field(self) -> &'static str {
+        match self {
+            IssueGrouping::Epic => "parent",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IssueEpic {
+    key: String,
+    summary: String,
+}
+
+impl IssueEpic {
+    /// The issue's parent when it is an epic.
+    fn of(issue: &JiraIssue) -> Option<Self> {
+        let parent = issue.fields.extra.get("parent")?;
+        let issue_type = &parent["fields"]["issuetype"];
+        let is_epic = issue_type["name"]
+            .as_str()
+            .is_some_and(|name| name.eq_ignore_ascii_case("epic"))
+            || issue_type["hierarchyLevel"] == 1;
+        is_epic.then(|| IssueEpic {
+            key: parent["key"].as_str().unwrap_or_default().to_string(),
+            summary: parent["fields"]["summary"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        })
+    }
+}
+
+struct IssueRow {
+    values: HashMap<IssueColumn, String>,
+    /// Text of every field the search returned, by field ID, for `--fields`.
+    field_values: HashMap<String, String>,
+    pull_requests: Option<Vec<JiraPullRequest>>,
+    epic: Option<IssueEpic>,
+    done: bool,
+    points: Option<f64>,
+}
+
+/// Fields with a place of their own in `JiraIssueFields`, which `--fields`
+/// can still name.
+const STANDARD_FIELDS: [&str; 9] = [
+    "status",
+    "priority",
+    "issuetype",
+    "assignee",
+    "reporter",
+    "created",
+    "updated",
+    "duedate",
+    "labels",
+];
+
+impl IssueRow {
+    fn value(&self, column: IssueColumn) -> &str {
+        self.values.get(&column).map_or("", String::as_str)
+    }
+
+    fn field_value(&self, field: &str) -> &str {
+        self.field_values.get(field).map_or("", String::as_str)
+    }
+}
+
+/// The current sprint ticket list shown by `--my-tickets`, or the results of
+/// a search.
+pub(crate) struct IssueListView {
+    sprint: Option<String>,
+    issues: Vec<IssueRow>,
+    columns: Vec<IssueColumn>,
+    include_pull_requests: bool,
+    /// Field IDs from `--fields`, listed after the columns.
+    extra_fields: Vec<String>,
+    /// Whether the issues are the current sprint's, which the table heads with its name.
+    current_sprint: bool,
+    group_by: Option<IssueGrouping>,
+}
+
+impl IssueListView {
+    pub(crate) fn from_issues(
+        issues: &[JiraIssue],
+        pull_requests_by_key: Option<&HashMap<String, Vec<JiraPullRequest>>>,
+    ) -> Self {
+        IssueListView {
+            sprint: issues.first().and_then(issue_sprint_name),
+            issues: issues
+                .iter()
+                .map(|issue| IssueRow {
+                    values: IssueColumn::value_variants()
+                        .iter()
+                        .map(|column| (*column, column.value(issue)))
+                        .collect(),
+                    field_values: STANDARD_FIELDS
+                        .iter()
+                        .copied()
+                        .chain(issue.fields.extra.keys().map(String::as_str))
+                        .map(|field| (field.to_string(), issue_field_text(issue, field)))
+                        .collect(),
+                    pull_requests: pull_requests_by_key
+                        .map(|map| map.get(&issue.key).cloned().unwrap_or_default()),
+                    epic: IssueEpic::of(issue),
+                    done: issue
+                        .fields
+                        .status
+                        .as_ref()
+                        .and_then(|status| status.status_category.as_ref())
+                        .is_some_and(|category| category.key == "done"),
+                    points: issue.fields.story_points(),
+                })
+                .collect(),
+            // Points are worth a column once the sprint has been estimated.
+            columns: if issues
+                .iter()
+                .any(|issue| issue.fields.story_points().is_some())
+            {
+                [IssueColumn::DEFAULT.as_slice(), &[IssueColumn::Points]].concat()
+            } else {
+                IssueColumn::DEFAULT.to_vec()
+            },
+            include_pull_requests: pull_requests_by_key.is_some(),
+            extra_fields: Vec::new(),
+            current_sprint: true,
+            group_by: None,
+        }
+    }
+
+    /// Issues from an arbitrary query, shown without a sprint heading.
+    pub(crate) fn search_results(issues: &[JiraIssue]) -> Self {
+        IssueListView {
+            current_sprint: false,
+            columns: IssueColumn::DEFAULT.to_vec(),
+            ..IssueListView::from_issues(issues, None)
+        }
+    }
+
+    /// Shows `columns` instead of the default key, summary, and status.
+    pub(crate) fn with_columns(mut self, columns: &[IssueColumn]) -> Self {
+        if !columns.is_empty() {
+            self.columns = columns.to_vec();
+        }
+        self
+    }
+
+    /// Lists the `--fields` fields after the columns, headed by their IDs.
+    pub(crate) fn with_extra_fields(mut self, fields: &[String]) -> Self {
+        self.extra_fields = fields.to_vec();
+        self
+    }
+
+    /// Shows the issues in swimlanes; CSV, text, and JSON get an epic column instead.
+    pub(crate) fn with_grouping(mut self, group_by: Option<IssueGrouping>) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    fn headers(&self) -> Vec<String> {
+        let mut headers: Vec<&str> = self.columns.iter().map(|column| column.header()).collect();
+        headers.extend(self.extra_fields.iter().map(String::as_str));
+        if self.include_pull_requests {
+            headers.push("PRs");
+        }
+        headers.into_iter().map(str::to_string).collect()
+    }
+
+    fn row(&self, issue: &IssueRow) -> Vec<String> {
+        let mut row: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| issue.value(*column).to_string())
+            .chain(
+                self.extra_fields
+                    .iter()
+                    .map(|field| issue.field_value(field).to_string()),
+            )
+            .collect();
+        if let Some(pull_requests) = &issue.pull_requests {
+            row.push(format_pull_request_summary(pull_requests));
+        }
+        row
+    }
+
+    /// The issues under each epic, in the order the epics first appear, then
+    /// the issues without one, as the board orders its swimlanes.
+    fn swimlanes(&self) -> Vec<(Option<&IssueEpic>, Vec<&IssueRow>)> {
+        let mut lanes: Vec<(Option<&IssueEpic>, Vec<&IssueRow>)> = Vec::new();
+        for issue in &self.issues {
+            match lanes
+                .iter_mut()
+                .find(|(epic, _)| *epic == issue.epic.as_ref())
+            {
+                Some((_, issues)) => issues.push(issue),
+                None => lanes.push((issue.epic.as_ref(), vec![issue])),
+            }
+        }
+        lanes.sort_by_key(|(epic, _)| epic.is_none());
+        lanes
+    }
+
+    /// The sprint heading, then one table per swimlane headed by its epic and
+    /// how many of its issues are done.
+    fn print_swimlanes(&self, heading: &str, print_lane: impl Fn(&[Vec<String>])) {
+        for (index, (epic, issues)) in self.swimlanes().into_iter().enumerate() {
+            if index > 0 {
+                println!();
+            }
+            let done = issues.iter().filter(|issue| issue.done).count();
+            let name = epic.map_or("Issues without epic".to_string(), |epic| {
+                format!("{} ({})", epic.summary, epic.key)
+            });
+            println!(
+                "{}{}: {}/{} done ({}%)",
+                heading,
+                name,
+                done,
+                issues.len(),
+                done * 100 / issues.len()
+            );
+            println!();
+            let rows: Vec<Vec<String>> = issues.iter().map(|issue| self.row(issue)).collect();
+            print_lane(&rows);
+        }
+    }
+
+    /// A table row, with the summary cut short and the status colored.
+    fn styled_row(&self, mut row: Vec<String>) -> Vec<String> {
+        for (cell, column) in row.iter_mut().zip(&self.columns) {
+            match column {
+                IssueColumn::Summary => *cell = truncate_with_ellipsis(cell, 58),
+                IssueColumn::Status => *cell = get_colored_status(cell),
+                _ => {}
+            }
+        }
+        row
+    }
+
+    fn print_sprint_heading(&self, prefix: &str, points_summary: Option<String>) {
+        if self.current_sprint {
+            println!(
+                "{}Current Sprint: {}{}",
+                prefix,
+                self.sprint.as_deref().unwrap_or("Unknown Sprint"),
+                points_summary
+                    .map(|summary| format!(" ({})", summary))
+                    .unwrap_or_default()
+            );
+            println!();
+        }
+    }
+
+    /// The points and whether they are done, for each estimated issue.
+    fn estimated(&self) -> impl Iterator<Item = (f64, bool)> + '_ {
+        self.issues
+            .iter()
+            .filter_map(|issue| Some((issue.points?, issue.done)))
+    }
+
+    fn points_summary(&self) -> Option<String> {
+        points_summary(&self.estimated().collect::<Vec<_>>())
+    }
+}
+
+/// `5 of 13 points done`, when any of the issues are estimated.
+fn points_summary(estimated: &[(f64, bool)]) -> Option<String> {
+    if estimated.is_empty() {
+        return None;
+    }
+    let total: f64 = estimated.iter().map(|(points, _)| points).sum();
+    let done: f64 = estimated
+        .iter()
+        .filter(|(_, done)| *done)
+        .map(|(points, _)| points)
+        .sum();
+    Some(format!(
+        "{} of {} points done",
+        format_points(done),
+        format_points(total)
+    ))
+}
+
+fn empty_list_message(current_sprint: bool) -> &'static str {
+    if current_sprint {
+        "No tickets found in the current sprint."
+    } else {
+        "No issues found."
+    }
+}
+
+/// An issue list printed as a table a page at a time, as a search returns
+/// them. The sprint heading comes with the first page and the points done
+/// after the table, since their total needs every issue.
+pub(crate) struct IssueTableStream {
+    table: BoxTableStream,
+    current_sprint: bool,
+    estimated: Vec<(f64, bool)>,
+}
+
+impl IssueTableStream {
+    pub(crate) fn new(current_sprint: bool) -> Self {
+        IssueTableStream {
+            table: BoxTableStream::default(),
+            current_sprint,
+            estimated: Vec::new(),
+        }
+    }
+
+    pub(crate) fn print_page(&mut self, page: &IssueListView) {
+        if page.issues.is_empty() {
+            return;
+        }
+        if !self.table.is_started() {
+            page.print_sprint_heading("", None);
+        }
+        let rows: Vec<Vec<String>> = page
+            .issues
+            .iter()
+            .map(|issue| page.styled_row(page.row(issue)))
+            .collect();
+        self.table.print_rows(&page.headers(), &rows);
+        self.estimated.extend(page.estimated());
+    }
+
+    /// Closes the table, or says there were no issues.
+    pub(crate) fn finish(&self) {
+        if !self.table.is_started() {
+            println!("{}", empty_list_message(self.current_sprint));
+            return;
+        }
+        self.table.finish();
+        if let Some(summary) = points_summary(&self.estimated).filter(|_| self.current_sprint) {
+            println!();
+            println!("{}", summary);
+        }
+    }
+}
+
+impl CommandOutput for IssueListView {
+    fn to_json(&self) -> Value {
+        let items = self
+            .issues
+            .iter()
+            .map(|issue| {
+                let mut item: Value = self
+                    .columns
+                    .iter()
+                    .map(|column| (column.json_key().to_string(), json!(issue.value(*column))))
+                    .chain(
+                        self.extra_fields
+                            .iter()
+                            .map(|field| (field.clone(), json!(issue.field_value(field)))),
+                    )
+                    .collect::<serde_json::Map<_, _>>()
+                    .into();
+                if self.group_by == Some(IssueGrouping::Epic) {
+                    item["epic"] = issue.epic.as_ref().map_or(
+                        Value::Null,
+                        |epic| json!({ "key": epic.key, "summary": epic.summary }),
+                    );
+                }
+                if let Some(pull_requests) = &issue.pull_requests {
+                    item["pull_requests"] = pull_requests
+                        .iter()
+                        .map(|pr| json!({ "name": pr.name, "status": pr.status, "url": pr.url }))
+                        .collect();
+                }
+                item
+            })
+            .collect();
+        Value::Array(items)
+    }
+
+    fn to_rows(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let mut headers = self.headers();
+        let mut rows: Vec<Vec<String>> = self.issues.iter().map(|issue| self.row(issue)).collect();
+        if self.group_by == Some(IssueGrouping::Epic) {
+            headers.insert(0, "Epic".to_string());
+            for (row, issue) in rows.iter_mut().zip(&self.issues) {
+                row.insert(
+                    0,
+                    issue
+                        .epic
+                        .as_ref()
+                        .map_or(String::new(), |epic| epic.summary.clone()),
+                );
+            }
+        }
+        (headers, rows)
+    }
+
+    fn to_records(&self) -> Vec<Value> {
+        match self.to_json() {
+            Value::Array(items) => items,
+            _ => Vec::new(),
+        }
+    }
+
+    fn print_table(&self) -> Result<()> {
+        if self.issues.is_empty() {
+            println!("{}", empty_list_message(self.current_sprint));
+            return Ok(());
+        }
+
+        self.print_sprint_heading("", self.points_summary());
+
+        let print_table = |rows: &[Vec<String>]| {
+            let rows = rows.iter().cloned().map(|row| self.styled_row(row));
+            print_box_table(
+                &std::iter::once(self.headers())
+                    .chain(rows)
+                    .collect::<Vec<_>>(),
+            );
+        };
+        match self.group_by {
+            Some(IssueGrouping::Epic) => self.print_swimlanes("", print_table),
+            None => print_table(
+                &self
+                    .issues
+                    .iter()
+                    .map(|issue| self.row(issue))
+                    .collect::<Vec<_>>(),
+            ),
+        }
+        Ok(())
+    }
+
+    fn print_markdown(&self) -> Result<()> {
+        self.print_sprint_heading("## ", self.points_summary());
+        match self.group_by {
+            Some(IssueGrouping::Epic) => {
+                self.print_swimlanes("### ", |rows| print_markdown_table(&self.headers(), rows))
+            }
+            None => {
+                let (headers, rows) = self.to_rows();
+                print_markdown_table(&headers, &rows);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The issue's active sprint, or its first sprint when none is active.
+pub(crate) fn issue_sprint_name(issue: &JiraIssue) -> Option<String> {
+    let sprints = issue.fields.sprint.as_ref()?;
+    sprints
+        .iter()
+        .find(|s| s.state == "active")
+        .or_else(|| sprints.first())
+        .map(|sprint| sprint.name.clone())
+}
+
+/// A table cell without its ANSI color codes.
+fn strip_ansi(cell: &str) -> String {
+    let mut plain = String::with_capacity(cell.len());
+    let mut chars = cell.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            for c in chars.by_ref() {
+                if c == 'm' {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+/// Display width of a table cell, ignoring ANSI color codes.
+fn display_width(cell: &str) -> usize {
+    UnicodeWidthStr::width(strip_ansi(cell).as_str())
+}
+
+/// `cell` cut short with an ellipsis to fit `width` columns, uncolored if cut.
+fn fit_cell(cell: &str, width: usize) -> String {
+    if display_width(cell) <= width {
+        return cell.to_string();
+    }
+    let mut fitted = String::new();
+    let mut used = 0;
+    for c in strip_ansi(cell).chars() {
+        let char_width = UnicodeWidthChar::width(c).unwrap_or(0);
+        if used + char_width + 1 > width {
+            break;
+        }
+        used += char_width;
+        fitted.push(c);
+    }
+    fitted.push('…');
+    fitted
+}
+
+/// Prints rows in the same box-drawing style as the sprint table. The first row is the header.
+pub(crate) fn print_box_table(rows: &[Vec<String>]) {
+    let Some((header, rows)) = rows.split_first() else {
+        return;
+    };
+    let mut table = BoxTableStream::default();
+    table.print_rows(header, rows);
+    table.finish();
+}
+
+/// A box table printed a few rows at a time. The header and the first rows
+/// fix the column widths; wider cells after them are cut to fit.
+#[derive(Default)]
+pub(crate) struct BoxTableStream {
+    widths: Option<Vec<usize>>,
+}
+
+impl BoxTableStream {
+    /// Prints `rows`, after the top border and `header` the first time.
+    pub(crate) fn print_rows(&mut self, header: &[String], rows: &[Vec<String>]) {
+        if self.widths.is_none() {
+            let mut widths = vec![0; header.len()];
+            for row in std::iter::once(header).chain(rows.iter().map(Vec::as_slice)) {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(display_width(cell) + 2);
+                }
+            }
+            self.widths = Some(widths);
+            self.border("┌", "┬", "┐");
+            self.print_row(header);
+        }
+        for row in rows {
+            self.border("├", "┼", "┤");
+            self.print_row(row);
+        }
+    }
+
+    pub(crate) fn is_started(&self) -> bool {
+        self.widths.is_some()
+    }
+
+    /// Prints the bottom border, once any rows have been printed.
+    pub(crate) fn finish(&self) {
+        if self.is_started() {
+            self.border("└", "┴", "┘");
+        }
+    }
+
+    fn widths(&self) -> &[usize] {
+        self.widths.as_deref().unwrap_or_default()
+    }
+
+    fn border(&self, left: &str, middle: &str, right: &str) {
+        let segments: Vec<String> = self
+            .widths()
+            .iter()
+            .map(|width| "─".repeat(*width))
+            .collect();
+        println!("{}{}{}", left, segments.join(middle), right);
+    }
+
+    fn print_row(&self, row: &[String]) {
+        print!("│");
+        for (cell, width) in row.iter().zip(self.widths()) {
+            let cell = fit_cell(cell, width - 2);
+            let padding = width - 1 - display_width(&cell);
+            print!(" {}{}│", cell, " ".repeat(padding));
+        }
+        println!();
+    }
+}
+
+/// Renders values as a one-line sparkline scaled to the largest value.
+pub(crate) fn sparkline(values: &[usize]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|value| {
+            (value * (BARS.len() - 1) + max / 2)
+                .checked_div(max)
+                .map_or(BARS[0], |index| BARS[index])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_field_quotes_values_with_separators() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(markdown_cell("a|b\nc"), "a\\|b c");
+    }
+
+    #[test]
+    fn record_output_exposes_fields_as_single_row() {
+        let record = RecordOutput::new(json!({ "ticket": "RW-1" }))
+            .field("Ticket", "RW-1")
+            .field("Status", "Done");
+
+        let (headers, rows) = record.to_rows();
+
+        assert_eq!(headers, vec!["Ticket", "Status"]);
+        assert_eq!(rows, vec![vec!["RW-1".to_string(), "Done".to_string()]]);
+        assert_eq!(record.to_records(), vec![json!({ "ticket": "RW-1" })]);
+    }
+
+    #[test]
+    fn table_output_records_fall_back_to_rows() {
+        let table = TableOutput::new(
+            json!({ "project": "RW" }),
+            &["Key", "Status"],
+            vec![vec!["RW-1".to_string(), "Done".to_string()]],
+        );
+
+        assert_eq!(
+            table.to_records(),
+            vec![json!({ "Key": "RW-1", "Status": "Done" })]
+        );
+    }
+
+    #[test]
+    fn legacy_format_flags_override_output_format() {
+        assert_eq!(OutputFormat::Yaml.or_json(true), OutputFormat::Json);
+        assert_eq!(OutputFormat::Yaml.or_json(false), OutputFormat::Yaml);
+        assert_eq!(
+            OutputFormat::Table.or_csv(true).or_json(true),
+            OutputFormat::Json
+        );
+    }
+
+    #[test]
+    fn display_width_ignores_ansi_color_codes() {
+        assert_eq!(display_width("\u{1b}[1;92mDone\u{1b}[0m"), 4);
+        assert_eq!(display_width("Done"), 4);
+    }
+
+    #[test]
+    fn fit_cell_cuts_wide_cells_to_the_column() {
+        assert_eq!(
+            fit_cell("\u{1b}[1;92mDone\u{1b}[0m", 4),
+            "\u{1b}[1;92mDone\u{1b}[0m"
+        );
+        assert_eq!(fit_cell("\u{1b}[1;92mIn Progress\u{1b}[0m", 6), "In Pr…");
+        assert_eq!(display_width(&fit_cell("修正ログイン", 7)), 7);
+    }
+}
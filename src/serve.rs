@@ -0,0 +1,261 @@
+//! `jit serve --stdio`: a JSON-RPC 2.0 server for editor plugins, which keep
+//! one jit process (and its connection pool) instead of spawning one per call.
+//!
+//! Each request is one JSON object per line on stdin; each response is one
+//! line on stdout. Methods:
+//!
+//! - `getIssue` `{key, description?, comments?}`: the `jit --show --json` document
+//! - `searchIssues` `{jql, limit?}`: the `jit --json` ticket list, where a
+//!   `limit` of 0 means every matching issue, as with `--limit 0`
+//! - `transitionIssue` `{key, status}`: `{ticket, transition, status}`
+//! - `addComment` `{key, body}`: `{ticket, id}`, with `body` in markdown
+//!
+//! Jira failures are returned as error code -32000 with the same
+//! `{kind, message}` as jit's JSON errors in `data`. With `jira.read_only`,
+//! `transitionIssue` and `addComment` fail the same way without calling Jira.
+
+use crate::render::{CommandOutput, IssueListView, IssueSections, IssueView};
+use crate::{
+    DEFAULT_TICKET_LIMIT, error, extract_ticket_id, issue_limit, markdown_to_adf,
+    resolve_transition, target_status,
+};
+use anyhow::Result;
+use jit_core::JitError;
+use jit_core::site_fields::SPRINT_FIELD;
+use jit_core::{JiraApi, JiraClient};
+use serde_json::{Value, json};
+use std::io::{BufRead, Write};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const JIRA_ERROR: i64 = -32000;
+
+/// Answers requests from `input` until it closes.
+pub(crate) fn run(
+    jira: &JiraClient,
+    read_only: bool,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = respond(jira, read_only, &line) {
+            writeln!(output, "{}", response)?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// The response to one request line, or `None` for a notification.
+fn respond(jira: &JiraClient, read_only: bool, line: &str) -> Option<Value> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(err) => return Some(failure(Value::Null, PARSE_ERROR, err.to_string(), None)),
+    };
+    let id = request.get("id").cloned();
+    let Some(method) = request["method"].as_str() else {
+        return Some(failure(
+            id.unwrap_or(Value::Null),
+            INVALID_REQUEST,
+            "Request has no method".to_string(),
+            None,
+        ));
+    };
+    let result = if read_only && matches!(method, "transitionIssue" | "addComment") {
+        Err(Failure::Jira(
+            JitError::Config(format!(
+                "{} would change Jira, but jira.read_only is set.",
+                method
+            ))
+            .into(),
+        ))
+    } else {
+        call(jira, method, &request["params"])
+    };
+    // Notifications (requests without an id) get no response, even on failure.
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(Failure::Protocol(code, message)) => failure(id, code, message, None),
+        Err(Failure::Jira(err)) => {
+            let data = error::error_json(&err)["error"].take();
+            failure(id, JIRA_ERROR, format!("{:#}", err), Some(data))
+        }
+    })
+}
+
+enum Failure {
+    Protocol(i64, String),
+    Jira(anyhow::Error),
+}
+
+impl From<anyhow::Error> for Failure {
+    fn from(err: anyhow::Error) -> Self {
+        Failure::Jira(err)
+    }
+}
+
+fn call(jira: &JiraClient, method: &str, params: &Value) -> std::result::Result<Value, Failure> {
+    match method {
+        "getIssue" => {
+            let key = issue_key(params)?;
+            let description = params["description"].as_bool().unwrap_or(true);
+            let comments = params["comments"].as_bool().unwrap_or(false);
+            let issue = jira.issue(&key, true, description, comments)?;
+            let sections = IssueSections {
+                description,
+                comments,
+                all_comments: true,
+                ..IssueSections::default()
+            };
+            Ok(IssueView::from_issue(&issue, sections, &[], None).to_json())
+        }
+        "searchIssues" => {
+            let jql = string_param(params, "jql")?;
+            let limit = match &params["limit"] {
+                Value::Null => DEFAULT_TICKET_LIMIT,
+                limit => limit
+                    .as_u64()
+                    .and_then(|limit| u32::try_from(limit).ok())
+                    .ok_or_else(|| {
+                        invalid_params("limit must be a non-negative integer".to_string())
+                    })?,
+            };
+            let issues = jira.search(
+                jql,
+                &["summary", "status", SPRINT_FIELD],
+                issue_limit(limit),
+            )?;
+            Ok(IssueListView::from_issues(&issues, None).to_json())
+        }
+        "transitionIssue" => {
+            let key = issue_key(params)?;
+            let status = string_param(params, "status")?;
+            let transitions = jira.transitions(&key)?;
+            let transition = resolve_transition(&transitions, status, &key)?;
+            jira.transition(&key, &transition.id)?;
+            Ok(json!({
+                "ticket": key,
+                "transition": transition.name,
+                "status": target_status(transition),
+            }))
+        }
+        "addComment" => {
+            let key = issue_key(params)?;
+            let body = string_param(params, "body")?;
+            let comment = jira.add_comment(&key, &markdown_to_adf(body))?;
+            Ok(json!({ "ticket": key, "id": comment.id }))
+        }
+        _ => Err(Failure::Protocol(
+            METHOD_NOT_FOUND,
+            format!("Unknown method '{}'", method),
+        )),
+    }
+}
+
+fn issue_key(params: &Value) -> std::result::Result<String, Failure> {
+    let key = string_param(params, "key")?;
+    extract_ticket_id(key).map_err(|err| invalid_params(format!("{:#}", err)))
+}
+
+fn string_param<'a>(params: &'a Value, name: &str) -> std::result::Result<&'a str, Failure> {
+    params[name]
+        .as_str()
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| invalid_params(format!("Missing string parameter '{}'", name)))
+}
+
+fn invalid_params(message: String) -> Failure {
+    Failure::Protocol(INVALID_PARAMS, message)
+}
+
+fn failure(id: Value, code: i64, message: String, data: Option<Value>) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jit_core::HttpConfig;
+
+    #[test]
+    fn protocol_errors_are_reported_without_calling_jira() {
+        let jira = JiraClient::basic(
+            "http://127.0.0.1:9",
+            "user@example.com",
+            "token",
+            &HttpConfig::default(),
+        )
+        .unwrap();
+        let input = concat!(
+            "not json\n",
+            "\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"deleteEverything\"}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"getIssue\",\"params\":{}}\n",
+            "{\"jsonrpc\":\"2.0\",\"method\":\"getIssue\",\"params\":{}}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":3}\n",
+        );
+        let mut output = Vec::new();
+        run(&jira, false, input.as_bytes(), &mut output).unwrap();
+
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let codes: Vec<(Value, i64)> = responses
+            .iter()
+            .map(|response| {
+                (
+                    response["id"].clone(),
+                    response["error"]["code"].as_i64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                (Value::Null, PARSE_ERROR),
+                (json!(1), METHOD_NOT_FOUND),
+                (json!(2), INVALID_PARAMS),
+                (json!(3), INVALID_REQUEST),
+            ]
+        );
+        assert_eq!(
+            responses[2]["error"]["message"],
+            "Missing string parameter 'key'"
+        );
+    }
+
+    #[test]
+    fn read_only_refuses_changes_without_calling_jira() {
+        let jira = JiraClient::basic(
+            "http://127.0.0.1:9",
+            "user@example.com",
+            "token",
+            &HttpConfig::default(),
+        )
+        .unwrap();
+        let input = "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"addComment\",\"params\":{\"key\":\"RW-1\",\"body\":\"hi\"}}\n";
+        let mut output = Vec::new();
+        run(&jira, true, input.as_bytes(), &mut output).unwrap();
+
+        let response: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(response["error"]["code"], JIRA_ERROR);
+        assert_eq!(response["error"]["data"]["kind"], "config");
+        assert_eq!(
+            response["error"]["message"],
+            "addComment would change Jira, but jira.read_only is set."
+        );
+    }
+}
@@ -0,0 +1,582 @@
+//! Config values addressed by dotted paths such as `jira.base_url`: named
+//! `[profiles.<name>]` layered over the rest of the file, the `${VAR}`
+//! references, `JIT_*` environment overrides, and `<key>_cmd` commands
+//! resolved when the config loads, and the edits behind `jit config set`,
+//! which keep the file's comments and layout.
+
+use crate::error::JitError;
+use anyhow::Result;
+use std::process::{Command, Stdio};
+use toml::{Table, Value};
+
+/// Sections whose keys can be overridden from the environment, e.g.
+/// `JIT_JIRA_API_TOKEN` for `jira.api_token`.
+const ENV_SECTIONS: &[&str] = &[
+    "jira",
+    "auth",
+    "bitbucket",
+    "approval",
+    "checklist",
+    "acceptance_criteria",
+    "estimation",
+    "capacity",
+    "lint",
+    "team",
+    "dod",
+    "duplicate",
+    "split",
+    "templates",
+    "quickfilters",
+    "output",
+    "http",
+    "share",
+    "llm",
+    "deploy",
+    "git",
+    "start",
+    "create",
+    "sync_pr",
+];
+
+const ENV_PREFIX: &str = "JIT_";
+
+/// Where a listed value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Source {
+    File,
+    Env,
+}
+
+impl Source {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Source::File => "file",
+            Source::Env => "env",
+        }
+    }
+}
+
+/// The `section.key` path an environment variable overrides, if any.
+fn env_path(name: &str) -> Option<String> {
+    let rest = name.strip_prefix(ENV_PREFIX)?.to_ascii_lowercase();
+    ENV_SECTIONS.iter().find_map(|section| {
+        let key = rest.strip_prefix(section)?.strip_prefix('_')?;
+        (!key.is_empty()).then(|| format!("{}.{}", section, key))
+    })
+}
+
+/// Names of the `[profiles.<name>]` tables, in file order.
+pub(crate) fn profile_names(table: &Table) -> Vec<String> {
+    table
+        .get("profiles")
+        .and_then(Value::as_table)
+        .map(|profiles| profiles.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Layers profile `name` over the top-level settings, so a profile only has
+/// to list what differs, usually its `[profiles.<name>.jira]` credentials.
+pub(crate) fn apply_profile(table: &mut Table, name: &str) -> Result<()> {
+    let profile = table
+        .get("profiles")
+        .and_then(Value::as_table)
+        .and_then(|profiles| profiles.get(name))
+        .and_then(Value::as_table)
+        .cloned()
+        .ok_or_else(|| {
+            let names = profile_names(table);
+            JitError::Config(if names.is_empty() {
+                format!(
+                    "Unknown profile '{}'. No [profiles.<name>] sections are configured.",
+                    name
+                )
+            } else {
+                format!(
+                    "Unknown profile '{}'. Configured profiles: {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+        })?;
+    merge(table, profile);
+    Ok(())
+}
+
+fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => merge(base, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The profile whose `jira.base_url` is on the same site as `url`. A URL for
+/// the top-level site picks no profile.
+pub(crate) fn profile_for_url(table: &Table, url: &str) -> Option<String> {
+    let site = url_host(url)?;
+    let on_site = |config: &Table| {
+        get(config, "jira.base_url")
+            .and_then(Value::as_str)
+            .and_then(url_host)
+            .is_some_and(|host| host == site)
+    };
+    if on_site(table) {
+        return None;
+    }
+    let profiles = table.get("profiles")?.as_table()?;
+    profiles.iter().find_map(|(name, profile)| {
+        profile
+            .as_table()
+            .is_some_and(&on_site)
+            .then(|| name.clone())
+    })
+}
+
+fn url_host(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let host = rest.split(['/', '?', '#']).next()?;
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// The environment variable that overrides `path`, if it can be overridden.
+pub(crate) fn env_var_name(path: &str) -> Option<String> {
+    let name = format!(
+        "{}{}",
+        ENV_PREFIX,
+        path.replace('.', "_").to_ascii_uppercase()
+    );
+    (env_path(&name).as_deref() == Some(path)).then_some(name)
+}
+
+/// Applies `JIT_<SECTION>_<KEY>` variables on top of the file's values,
+/// returning the paths that were overridden.
+pub(crate) fn apply_env_overrides(
+    table: &mut Table,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Vec<String> {
+    let mut overridden = Vec::new();
+    for (name, raw) in vars {
+        let Some(path) = env_path(&name) else {
+            continue;
+        };
+        let (section, key) = path.split_once('.').expect("env paths have a section");
+        let section = table
+            .entry(section)
+            .or_insert_with(|| Value::Table(Table::new()));
+        let Value::Table(section) = section else {
+            continue;
+        };
+        let value = parse_value(&raw, section.get(key));
+        section.insert(key.to_string(), value);
+        overridden.push(path);
+    }
+    overridden
+}
+
+/// Replaces `${NAME}` in every string value with `lookup(NAME)`, so secrets
+/// can stay in the environment. `$${` is a literal `${`.
+pub(crate) fn interpolate(
+    table: &mut Table,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<()> {
+    interpolate_table(table, "", lookup)
+}
+
+fn interpolate_table(
+    table: &mut Table,
+    prefix: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<()> {
+    for (key, value) in table.iter_mut() {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        interpolate_value(value, &path, lookup)?;
+    }
+    Ok(())
+}
+
+fn interpolate_value(
+    value: &mut Value,
+    path: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<()> {
+    match value {
+        Value::String(text) if text.contains("${") => *text = expand(text, path, lookup)?,
+        Value::Array(items) => {
+            for item in items {
+                interpolate_value(item, path, lookup)?;
+            }
+        }
+        Value::Table(child) => interpolate_table(child, path, lookup)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn expand(text: &str, path: &str, lookup: &impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("$${") {
+            expanded.push_str("${");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("${") {
+            let end = after.find('}').ok_or_else(|| {
+                JitError::Config(format!("{} has an unclosed ${{ in {:?}.", path, text))
+            })?;
+            let name = &after[..end];
+            let value = lookup(name).ok_or_else(|| {
+                JitError::Config(format!(
+                    "{} refers to ${{{}}}, which is not set in the environment.",
+                    path, name
+                ))
+            })?;
+            expanded.push_str(&value);
+            rest = &after[end + 1..];
+        } else {
+            expanded.push('$');
+            rest = &rest[1..];
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Sets each `<key>` that is unset or empty from the output of its
+/// `<key>_cmd` shell command, such as `api_token_cmd = "op read ..."`, so
+/// secrets can come from a password manager. `jira.token_cmd` is short for
+/// `jira.api_token_cmd`.
+pub(crate) fn run_value_commands(table: &mut Table) -> Result<()> {
+    run_commands_in(table, "")
+}
+
+fn run_commands_in(table: &mut Table, prefix: &str) -> Result<()> {
+    let commands: Vec<(String, String)> = table
+        .iter()
+        .filter_map(|(key, value)| {
+            let target = key.strip_suffix("_cmd")?;
+            let target = match (prefix, target) {
+                ("jira", "token") => "api_token",
+                _ => target,
+            };
+            Some((target.to_string(), value.as_str()?.to_string()))
+        })
+        .collect();
+    for (target, command) in commands {
+        let is_set = table
+            .get(&target)
+            .and_then(Value::as_str)
+            .is_some_and(|value| !value.is_empty());
+        if !is_set {
+            let path = if prefix.is_empty() {
+                target.clone()
+            } else {
+                format!("{}.{}", prefix, target)
+            };
+            let value = command_output(&command, &path)?;
+            table.insert(target, Value::String(value));
+        }
+    }
+    for (key, value) in table.iter_mut() {
+        if let Value::Table(child) = value {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            run_commands_in(child, &path)?;
+        }
+    }
+    Ok(())
+}
+
+/// The command's trimmed stdout. Its stderr and stdin stay on the terminal
+/// so password managers can prompt for an unlock.
+fn command_output(command: &str, path: &str) -> Result<String> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|err| {
+            JitError::Config(format!("Failed to run the command for {}: {}", path, err))
+        })?;
+    if !output.status.success() {
+        return Err(JitError::Config(format!(
+            "The command for {} ({}) exited with {}.",
+            path, command, output.status
+        ))
+        .into());
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if value.is_empty() {
+        return Err(JitError::Config(format!(
+            "The command for {} ({}) printed nothing.",
+            path, command
+        ))
+        .into());
+    }
+    Ok(value)
+}
+
+/// Reads `raw` as a TOML value (number, boolean, array), falling back to a
+/// string. A value replacing a string stays a string, so a numeric-looking
+/// token is not turned into an integer.
+fn parse_value(raw: &str, existing: Option<&Value>) -> Value {
+    if matches!(existing, Some(Value::String(_))) {
+        return Value::String(raw.to_string());
+    }
+    format!("value = {}", raw)
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+/// The value at a dotted `path`.
+pub(crate) fn get<'a>(table: &'a Table, path: &str) -> Option<&'a Value> {
+    let mut segments = path.split('.');
+    let mut value = table.get(segments.next()?)?;
+    for segment in segments {
+        value = value.as_table()?.get(segment)?;
+    }
+    Some(value)
+}
+
+/// Every scalar and array value in `table` as `(path, value)`, in file order.
+pub(crate) fn flatten(table: &Table) -> Vec<(String, &Value)> {
+    let mut entries = Vec::new();
+    flatten_into(table, "", &mut entries);
+    entries
+}
+
+fn flatten_into<'a>(table: &'a Table, prefix: &str, entries: &mut Vec<(String, &'a Value)>) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Table(child) => flatten_into(child, &path, entries),
+            _ => entries.push((path, value)),
+        }
+    }
+}
+
+/// A value as `jit config get` prints it: strings bare, everything else as TOML.
+pub(crate) fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Whether a path holds a credential that `jit config list` should mask.
+pub(crate) fn is_secret(path: &str) -> bool {
+    let key = path.rsplit('.').next().unwrap_or(path);
+    ["token", "password", "secret"]
+        .iter()
+        .any(|word| key.contains(word))
+}
+
+/// Sets `path` to `raw` in a config file's contents, creating missing
+/// sections and leaving comments and formatting alone.
+pub(crate) fn set(contents: &str, path: &str, raw: &str) -> Result<String> {
+    let mut document: toml_edit::DocumentMut = contents
+        .parse()
+        .map_err(|err: toml_edit::TomlError| JitError::Config(err.to_string()))?;
+    let segments: Vec<&str> = path.split('.').collect();
+    if segments.iter().any(|segment| segment.trim().is_empty()) {
+        return Err(JitError::validation(format!(
+            "Invalid config key '{}'. Use a dotted path such as jira.base_url.",
+            path
+        ))
+        .into());
+    }
+    let (key, sections) = segments.split_last().expect("split yields a segment");
+
+    let mut table = document.as_table_mut();
+    for section in sections {
+        let item = table
+            .entry(section)
+            .or_insert_with(|| toml_edit::Item::Table(toml_edit::Table::new()));
+        table = item.as_table_mut().ok_or_else(|| {
+            JitError::validation(format!("Config key '{}' is not a section.", section))
+        })?;
+    }
+    let existing = table.get(key).and_then(toml_edit::Item::as_value);
+    let mut value = if existing.is_some_and(toml_edit::Value::is_str) {
+        toml_edit::Value::from(raw)
+    } else {
+        raw.parse::<toml_edit::Value>()
+            .unwrap_or_else(|_| toml_edit::Value::from(raw))
+    };
+    // Keep a trailing comment on the line being replaced.
+    if let Some(existing) = existing {
+        *value.decor_mut() = existing.decor().clone();
+    } else {
+        value.decor_mut().clear();
+    }
+    table.insert(key, toml_edit::value(value));
+    Ok(document.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_overrides_replace_file_values_by_section() {
+        let mut table: Table = "[jira]\nbase_url = \"https://file\"\napi_token = \"a\"\n\n[http]\npool_idle_timeout_secs = 30\n"
+            .parse()
+            .unwrap();
+        let overridden = apply_env_overrides(
+            &mut table,
+            [
+                ("JIT_JIRA_BASE_URL", "https://env"),
+                ("JIT_JIRA_API_TOKEN", "12345"),
+                ("JIT_HTTP_POOL_IDLE_TIMEOUT_SECS", "5"),
+                ("JIT_SYNC_PR_MERGED", "Done"),
+                ("JIT_DATA_DIR", "/tmp/jit"),
+            ]
+            .map(|(name, value)| (name.to_string(), value.to_string())),
+        );
+
+        assert_eq!(
+            overridden,
+            [
+                "jira.base_url",
+                "jira.api_token",
+                "http.pool_idle_timeout_secs",
+                "sync_pr.merged"
+            ]
+        );
+        assert_eq!(
+            get(&table, "jira.base_url").unwrap().as_str(),
+            Some("https://env")
+        );
+        assert_eq!(
+            get(&table, "jira.api_token").unwrap().as_str(),
+            Some("12345")
+        );
+        assert_eq!(
+            get(&table, "http.pool_idle_timeout_secs")
+                .unwrap()
+                .as_integer(),
+            Some(5)
+        );
+        assert!(get(&table, "data.dir").is_none());
+        assert_eq!(
+            env_var_name("jira.api_token").as_deref(),
+            Some("JIT_JIRA_API_TOKEN")
+        );
+        assert_eq!(env_var_name("teams.web.members"), None);
+    }
+
+    #[test]
+    fn interpolation_and_commands_fill_in_config_values() {
+        let mut table: Table = "[jira]\nbase_url = \"https://${SITE}.atlassian.net\"\nuser_email = \"$${literal}\"\ntoken_cmd = \"printf 'from-cmd\\\\n'\"\n\n[bitbucket]\napp_password = \"kept\"\napp_password_cmd = \"exit 1\"\n\n[team]\nmembers = [\"${MEMBER}\"]\n"
+            .parse()
+            .unwrap();
+        let lookup = |name: &str| match name {
+            "SITE" => Some("acme".to_string()),
+            "MEMBER" => Some("account-1".to_string()),
+            _ => None,
+        };
+        interpolate(&mut table, &lookup).unwrap();
+        run_value_commands(&mut table).unwrap();
+
+        assert_eq!(
+            get(&table, "jira.base_url").unwrap().as_str(),
+            Some("https://acme.atlassian.net")
+        );
+        assert_eq!(
+            get(&table, "jira.user_email").unwrap().as_str(),
+            Some("${literal}")
+        );
+        assert_eq!(
+            get(&table, "jira.api_token").unwrap().as_str(),
+            Some("from-cmd")
+        );
+        // A value that is already set wins over its command.
+        assert_eq!(
+            get(&table, "bitbucket.app_password").unwrap().as_str(),
+            Some("kept")
+        );
+        assert_eq!(
+            get(&table, "team.members").unwrap().as_array().unwrap()[0].as_str(),
+            Some("account-1")
+        );
+
+        let mut missing: Table = "[jira]\napi_token = \"${NOPE}\"\n".parse().unwrap();
+        let err = interpolate(&mut missing, &lookup).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "jira.api_token refers to ${NOPE}, which is not set in the environment."
+        );
+        let mut failing: Table = "[jira]\ntoken_cmd = \"exit 3\"\n".parse().unwrap();
+        assert!(run_value_commands(&mut failing).is_err());
+    }
+
+    #[test]
+    fn profiles_layer_over_top_level_settings_and_match_urls() {
+        let contents = "[jira]\nbase_url = \"https://work.atlassian.net\"\nuser_email = \"me@work.com\"\n\n[output]\nformat = \"text\"\n\n[profiles.client.jira]\nbase_url = \"https://client.atlassian.net/\"\napi_token = \"client-token\"\n";
+        let table: Table = contents.parse().unwrap();
+
+        assert_eq!(profile_names(&table), ["client"]);
+        assert_eq!(
+            profile_for_url(&table, "https://CLIENT.atlassian.net/browse/CL-7").as_deref(),
+            Some("client")
+        );
+        assert_eq!(
+            profile_for_url(&table, "https://work.atlassian.net/browse/RW-1"),
+            None
+        );
+        assert_eq!(profile_for_url(&table, "RW-1"), None);
+
+        let mut merged = table.clone();
+        apply_profile(&mut merged, "client").unwrap();
+        assert_eq!(
+            get(&merged, "jira.base_url").unwrap().as_str(),
+            Some("https://client.atlassian.net/")
+        );
+        assert_eq!(
+            get(&merged, "jira.user_email").unwrap().as_str(),
+            Some("me@work.com")
+        );
+        assert_eq!(
+            get(&merged, "output.format").unwrap().as_str(),
+            Some("text")
+        );
+
+        let err = apply_profile(&mut table.clone(), "home").unwrap_err();
+        assert!(err.to_string().contains("Configured profiles: client"));
+    }
+
+    #[test]
+    fn set_keeps_comments_and_adds_missing_sections() {
+        let contents = "# my jira\n[jira]\nbase_url = \"https://old\" # work site\n";
+
+        let updated = set(contents, "jira.base_url", "https://new").unwrap();
+        assert_eq!(
+            updated,
+            "# my jira\n[jira]\nbase_url = \"https://new\" # work site\n"
+        );
+
+        let updated = set(&updated, "http.pool_idle_timeout_secs", "10").unwrap();
+        assert!(updated.ends_with("[http]\npool_idle_timeout_secs = 10\n"));
+        assert!(set(&updated, "jira.base_url.host", "x").is_err());
+    }
+}
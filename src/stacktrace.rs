@@ -0,0 +1,214 @@
+//! Stack trace frames found in ticket text, and the files in the current git
+//! repository they point at, for `jit trace`.
+//!
+//! Frames are recognized in the common formats: Java (`at a.b.C.m(C.java:42)`),
+//! Python (`File "x.py", line 42`), and the `path:line[:column]` used by Rust,
+//! Go, JavaScript, and most other runtimes.
+
+use anyhow::{Result, anyhow};
+use regex::Regex;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Frame {
+    /// The line the frame was found on, trimmed.
+    pub(crate) text: String,
+    /// The path as written in the trace, e.g. `/build/app/src/main.rs`.
+    pub(crate) path: String,
+    pub(crate) line: u32,
+    pub(crate) column: Option<u32>,
+}
+
+/// Every frame in `text`, in the order they appear, except that Python
+/// tracebacks (`most recent call last`) are reversed so the innermost frame,
+/// where the error was raised, comes first as it does in other languages.
+pub(crate) fn parse(text: &str) -> Vec<Frame> {
+    let java = Regex::new(r"\bat\s+([\w$.<>]+)\(([\w$-]+\.\w+):(\d+)\)")
+        .expect("java frame regex is valid");
+    let python = Regex::new(r#"File "([^"]+)", line (\d+)"#).expect("python frame regex is valid");
+    let generic = Regex::new(r"((?:[A-Za-z]:)?[\w./\\@~+-]*\w\.[A-Za-z]\w*):(\d+)(?::(\d+))?")
+        .expect("file:line regex is valid");
+
+    let mut frames = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let text = |captures: &regex::Captures<'_>, group: usize| {
+            captures.get(group).map_or("", |m| m.as_str()).to_string()
+        };
+        let frame = if let Some(captures) = java.captures(trimmed) {
+            Some((
+                java_path(&captures[1], &captures[2]),
+                text(&captures, 3),
+                None,
+            ))
+        } else if let Some(captures) = python.captures(trimmed) {
+            Some((text(&captures, 1), text(&captures, 2), None))
+        } else {
+            generic.captures(trimmed).map(|captures| {
+                (
+                    text(&captures, 1),
+                    text(&captures, 2),
+                    captures.get(3).map(|m| m.as_str().to_string()),
+                )
+            })
+        };
+        let Some((path, line_number, column)) = frame else {
+            continue;
+        };
+        let Ok(line_number) = line_number.parse() else {
+            continue;
+        };
+        frames.push(Frame {
+            text: trimmed.to_string(),
+            path,
+            line: line_number,
+            column: column.and_then(|column| column.parse().ok()),
+        });
+    }
+    if text.contains("most recent call last") {
+        frames.reverse();
+    }
+    frames
+}
+
+/// `com/acme/Login.java` for method `com.acme.Login.check` in `Login.java`.
+fn java_path(method: &str, file: &str) -> String {
+    let mut parts: Vec<&str> = method.split('.').collect();
+    // Drop the method and class names, leaving the package.
+    parts.truncate(parts.len().saturating_sub(2));
+    parts.push(file);
+    parts.join("/")
+}
+
+/// The files tracked in the git repository containing the current directory.
+pub(crate) struct RepoFiles {
+    pub(crate) root: PathBuf,
+    files: Vec<String>,
+}
+
+impl RepoFiles {
+    pub(crate) fn load() -> Result<Self> {
+        let git = |args: &[&str]| -> Option<String> {
+            let output = Command::new("git").args(args).output().ok()?;
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+        };
+        let root = git(&["rev-parse", "--show-toplevel"])
+            .map(|top| top.trim().to_string())
+            .filter(|top| !top.is_empty())
+            .ok_or_else(|| anyhow!("Not inside a git repository. Run jit trace from the repository the trace came from."))?;
+        let listing = git(&["-C", &root, "ls-files", "-z"]).unwrap_or_default();
+        let files = listing
+            .split('\0')
+            .filter(|file| !file.is_empty())
+            .map(str::to_string)
+            .collect();
+        Ok(RepoFiles {
+            root: PathBuf::from(root),
+            files,
+        })
+    }
+
+    /// The repository file `path` refers to: the one sharing the longest
+    /// trailing run of path components with it, so build-machine prefixes
+    /// like `/home/ci/app/` and Java source roots do not matter. A bare file
+    /// name only matches when a single file has it.
+    pub(crate) fn resolve(&self, path: &str) -> Option<&str> {
+        let wanted: Vec<&str> = path
+            .split(['/', '\\'])
+            .filter(|part| !part.is_empty() && *part != ".")
+            .collect();
+        let mut best: Option<(usize, Vec<&str>)> = None;
+        for file in &self.files {
+            let shared = file
+                .rsplit('/')
+                .zip(wanted.iter().rev())
+                .take_while(|(have, want)| have == *want)
+                .count();
+            if shared == 0 {
+                continue;
+            }
+            match &mut best {
+                Some((length, matches)) if *length == shared => matches.push(file),
+                Some((length, _)) if *length > shared => {}
+                _ => best = Some((shared, vec![file])),
+            }
+        }
+        let (shared, matches) = best?;
+        match matches.as_slice() {
+            [file] => Some(file),
+            // Several files share the same ending; only a path that narrows
+            // it down beyond the file name is trusted.
+            [first, ..] if shared > 1 => Some(first),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_java_python_and_file_line_frames() {
+        let frames = parse(
+            "java.lang.IllegalStateException: boom\n\
+             \tat com.acme.auth.Login.check(Login.java:42)\n\
+             \tat com.acme.Main.main(Main.java:7)\n\
+             Also panicked at src/cache.rs:88:13 and see https://example.com for more",
+        );
+        let locations: Vec<(&str, u32, Option<u32>)> = frames
+            .iter()
+            .map(|frame| (frame.path.as_str(), frame.line, frame.column))
+            .collect();
+        assert_eq!(
+            locations,
+            [
+                ("com/acme/auth/Login.java", 42, None),
+                ("com/acme/Main.java", 7, None),
+                ("src/cache.rs", 88, Some(13)),
+            ]
+        );
+        assert_eq!(
+            frames[0].text,
+            "at com.acme.auth.Login.check(Login.java:42)"
+        );
+
+        let python = parse(
+            "Traceback (most recent call last):\n  File \"/srv/app/jobs/run.py\", line 10, in <module>\n    main()\n  File \"/srv/app/jobs/sync.py\", line 55, in main\nValueError: bad",
+        );
+        assert_eq!(python[0].path, "/srv/app/jobs/sync.py");
+        assert_eq!(python[0].line, 55);
+        assert_eq!(python[1].path, "/srv/app/jobs/run.py");
+    }
+
+    #[test]
+    fn resolve_matches_the_longest_shared_path_ending() {
+        let repo = RepoFiles {
+            root: PathBuf::from("/repo"),
+            files: [
+                "src/main/java/com/acme/auth/Login.java",
+                "jobs/sync.py",
+                "web/src/index.js",
+                "api/src/index.js",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+        };
+
+        assert_eq!(
+            repo.resolve("com/acme/auth/Login.java"),
+            Some("src/main/java/com/acme/auth/Login.java")
+        );
+        assert_eq!(repo.resolve("/srv/app/jobs/sync.py"), Some("jobs/sync.py"));
+        assert_eq!(
+            repo.resolve("/build/web/src/index.js"),
+            Some("web/src/index.js")
+        );
+        assert_eq!(repo.resolve("index.js"), None);
+        assert_eq!(repo.resolve("lib/other.rs"), None);
+    }
+}
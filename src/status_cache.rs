@@ -0,0 +1,161 @@
+//! Last known status of tickets, kept in `status.json` in the data directory
+//! so `jit statusline` and `jit prompt` can print without waiting on Jira. Entries are
+//! kept per site, since two sites or profiles can have the same ticket key, and are
+//! refreshed in the background once they go stale.
+
+use crate::{http, jit_data_dir};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+/// How long a cached status is shown before a refresh is started.
+const MAX_AGE_SECS: i64 = 5 * 60;
+
+/// How long to wait for a started refresh before starting another one, so a
+/// status bar redrawing every second does not pile up requests.
+const REFRESH_GRACE_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct CachedStatus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) status: Option<String>,
+    /// Jira status category key: `new`, `indeterminate`, or `done`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) summary: Option<String>,
+    /// RFC 3339 time the status was fetched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) fetched_at: Option<String>,
+    /// RFC 3339 time a background refresh was last started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) refresh_started_at: Option<String>,
+}
+
+impl CachedStatus {
+    /// Whether a background refresh should be started at `now`.
+    pub(crate) fn needs_refresh(&self, now: DateTime<Utc>) -> bool {
+        let older_than = |time: &Option<String>, secs: i64| {
+            time.as_deref()
+                .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+                .is_none_or(|time| (now - time.with_timezone(&Utc)).num_seconds() >= secs)
+        };
+        older_than(&self.fetched_at, MAX_AGE_SECS)
+            && older_than(&self.refresh_started_at, REFRESH_GRACE_SECS)
+    }
+}
+
+/// Entries by `entry_key`.
+type Cache = BTreeMap<String, CachedStatus>;
+
+/// The cached entry for `ticket` on `base_url`, or an empty one.
+pub(crate) fn get(base_url: &str, ticket: &str) -> CachedStatus {
+    let cached = read_cache()
+        .remove(&entry_key(base_url, ticket))
+        .unwrap_or_default();
+    if cached.status.is_some() {
+        http::record_cache_hit();
+    }
+    cached
+}
+
+/// Records a freshly fetched status.
+pub(crate) fn store(
+    base_url: &str,
+    ticket: &str,
+    status: &str,
+    category: Option<&str>,
+    summary: &str,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    update(base_url, ticket, |entry| {
+        *entry = CachedStatus {
+            status: Some(status.to_string()),
+            category: category.map(str::to_string),
+            summary: Some(summary.to_string()),
+            fetched_at: Some(timestamp(now)),
+            refresh_started_at: None,
+        };
+    })
+}
+
+/// Notes that a background refresh of `ticket` has started.
+pub(crate) fn mark_refresh_started(base_url: &str, ticket: &str, now: DateTime<Utc>) -> Result<()> {
+    update(base_url, ticket, |entry| {
+        entry.refresh_started_at = Some(timestamp(now))
+    })
+}
+
+fn update(base_url: &str, ticket: &str, change: impl FnOnce(&mut CachedStatus)) -> Result<()> {
+    let path = cache_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Background refreshes of different tickets can finish together; holding
+    // the lock from read to rename keeps one from dropping the other's entry.
+    let lock = File::create(path.with_extension("lock"))?;
+    lock.lock()?;
+    let mut cache = read_cache();
+    change(cache.entry(entry_key(base_url, ticket)).or_default());
+    // Write then rename, so a status bar never reads a half-written file.
+    let partial = path.with_extension(format!("json.{}", std::process::id()));
+    fs::write(&partial, serde_json::to_string_pretty(&cache)?)?;
+    fs::rename(&partial, &path)?;
+    Ok(())
+}
+
+fn entry_key(base_url: &str, ticket: &str) -> String {
+    format!("{} {}", base_url.trim_end_matches('/'), ticket)
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+fn cache_path() -> Result<PathBuf> {
+    Ok(jit_data_dir()?.join("status.json"))
+}
+
+fn read_cache() -> Cache {
+    cache_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn needs_refresh_waits_for_stale_entries_and_running_refreshes() {
+        let now = at("2026-05-02T12:00:00Z");
+        assert!(CachedStatus::default().needs_refresh(now));
+
+        let fresh = CachedStatus {
+            fetched_at: Some("2026-05-02T11:58:00Z".to_string()),
+            ..CachedStatus::default()
+        };
+        assert!(!fresh.needs_refresh(now));
+
+        let mut stale = CachedStatus {
+            fetched_at: Some("2026-05-02T11:50:00Z".to_string()),
+            ..CachedStatus::default()
+        };
+        assert!(stale.needs_refresh(now));
+        stale.refresh_started_at = Some("2026-05-02T11:59:30Z".to_string());
+        assert!(!stale.needs_refresh(now));
+        stale.refresh_started_at = Some("2026-05-02T11:55:00Z".to_string());
+        assert!(stale.needs_refresh(now));
+    }
+}
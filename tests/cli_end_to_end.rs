@@ -11,6 +11,12 @@ use std::time::Duration;
 
 static TEMP_CONFIG_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
+/// Pins the sprint field so commands do not ask the test server for `/field` first.
+const SPRINT_FIELD_LINE: &str = "sprint_field = \"customfield_10020\"\n";
+
+/// Pins the story points field the same way; it goes before the first table.
+const STORY_POINTS_FIELD_LINE: &str = "estimation.story_points_field = \"customfield_10016\"\n";
+
 #[test]
 fn default_query_without_ticket_lists_current_sprint_table() {
     let (server, requests) = spawn_sequence_server(vec![(
@@ -30,9 +36,188 @@ fn default_query_without_ticket_lists_current_sprint_table() {
     let captured = requests
         .recv_timeout(Duration::from_secs(2))
         .expect("search request should be captured");
-    assert!(captured.starts_with("POST /rest/api/3/search/jql HTTP/1.1"));
+    assert!(captured.starts_with("GET /rest/api/3/search/jql?"));
+
+    server.join();
+}
+
+#[test]
+fn sprint_list_follows_next_page_token() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Login","status":{"name":"To Do"},"customfield_10020":[{"name":"Sprint 42","state":"active"}]}}],"nextPageToken":"page-2"}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"2","key":"RW-2","fields":{"summary":"Signup","status":{"name":"To Do"}}}],"isLast":true}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit(["--config-file", config.path_str()]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("RW-1"));
+    assert!(stdout.contains("RW-2"));
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[0].starts_with("GET /rest/api/3/search/jql?"));
+    let second: Value = search_params(&captured[1]);
+    assert_eq!(second["nextPageToken"], "page-2");
+    server.join();
+}
+
+#[test]
+fn sprint_list_falls_back_to_legacy_search_when_search_jql_is_missing() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 404 Not Found",
+            r#"{"errorMessages":["null for uri: /rest/api/3/search/jql"]}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"startAt":0,"maxResults":100,"total":1,"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Login","status":{"name":"To Do"},"customfield_10020":[{"name":"Sprint 42","state":"active"}]}}]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit(["--config-file", config.path_str()]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("RW-1"));
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[0].starts_with("GET /rest/api/3/search/jql?"));
+    assert!(captured[1].starts_with("POST /rest/api/3/search HTTP/1.1"));
+    server.join();
+}
+
+#[test]
+fn story_points_are_listed_for_the_sprint_and_set_with_estimate() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[
+                {"id":"1","key":"RW-1","fields":{"summary":"Login","status":{"name":"Done","statusCategory":{"key":"done"}},"customfield_10020":[{"name":"Sprint 42","state":"active"}],"customfield_10016":3.0}},
+                {"id":"2","key":"RW-2","fields":{"summary":"Signup","status":{"name":"To Do","statusCategory":{"key":"new"}},"customfield_10016":5.0}},
+                {"id":"3","key":"RW-3","fields":{"summary":"Spike","status":{"name":"To Do","statusCategory":{"key":"new"}}}}
+            ],"isLast":true}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"2","key":"RW-2","fields":{"summary":"Signup","status":{"name":"To Do"},"customfield_10016":5.0}}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let list = run_jit(["--config-file", config.path_str(), "--markdown"]);
+    let show = run_jit([
+        "--config-file",
+        config.path_str(),
+        "RW-2",
+        "--show",
+        "--markdown",
+    ]);
+    let edit = run_jit([
+        "--config-file",
+        config.path_str(),
+        "edit",
+        "RW-3",
+        "--estimate",
+        "2",
+        "--json",
+    ]);
+
+    assert!(list.status.success(), "stderr: {}", stderr(&list));
+    assert_eq!(
+        stdout(&list),
+        "## Current Sprint: Sprint 42 (3 of 8 points done)\n\n\
+         | Key | Summary | Status | Points |\n\
+         | --- | --- | --- | --- |\n\
+         | RW-1 | Login | Done | 3 |\n\
+         | RW-2 | Signup | To Do | 5 |\n\
+         | RW-3 | Spike | To Do |  |\n"
+    );
+    assert!(show.status.success(), "stderr: {}", stderr(&show));
+    assert!(
+        stdout(&show).contains("| Points | 5 |"),
+        "stdout: {}",
+        stdout(&show)
+    );
+    assert!(edit.status.success(), "stderr: {}", stderr(&edit));
+    let payload: Value = serde_json::from_str(&stdout(&edit)).expect("edit json");
+    assert_eq!(payload["story_points"], 2.0);
+    assert_eq!(
+        payload["updated_fields"],
+        serde_json::json!(["story_points"])
+    );
+
+    let requests = collect_requests(&requests, 3);
+    server.join();
+    let body: Value = search_params(&requests[0]);
+    assert_eq!(
+        body["fields"],
+        serde_json::json!([
+            "summary",
+            "status",
+            "customfield_10020",
+            "customfield_10016"
+        ])
+    );
+    assert!(requests[1].contains("customfield_10016"));
+    assert!(requests[2].starts_with("PUT /rest/api/3/issue/RW-3 HTTP/1.1"));
+    let body: Value = serde_json::from_str(request_body(&requests[2])).expect("update body");
+    assert_eq!(
+        body,
+        serde_json::json!({"fields": {"customfield_10016": 2.0}})
+    );
+}
+
+#[test]
+fn group_by_epic_lists_sprint_tickets_in_epic_swimlanes() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"issues":[
+            {"id":"1","key":"RW-1","fields":{"summary":"Loose end","status":{"name":"To Do","statusCategory":{"key":"new"}},"customfield_10020":[{"name":"Sprint 42","state":"active"}]}},
+            {"id":"2","key":"RW-2","fields":{"summary":"Card form","status":{"name":"Done","statusCategory":{"key":"done"}},"parent":{"key":"RW-100","fields":{"summary":"Payments","issuetype":{"name":"Epic","hierarchyLevel":1}}}}},
+            {"id":"3","key":"RW-3","fields":{"summary":"Refunds","status":{"name":"In Progress","statusCategory":{"key":"indeterminate"}},"parent":{"key":"RW-100","fields":{"summary":"Payments","issuetype":{"name":"Epic","hierarchyLevel":1}}}}}
+        ],"isLast":true}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "--group-by",
+        "epic",
+        "--markdown",
+    ]);
 
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "## Current Sprint: Sprint 42\n\n\
+         ### Payments (RW-100): 1/2 done (50%)\n\n\
+         | Key | Summary | Status |\n\
+         | --- | --- | --- |\n\
+         | RW-2 | Card form | Done |\n\
+         | RW-3 | Refunds | In Progress |\n\
+         \n\
+         ### Issues without epic: 0/1 done (0%)\n\n\
+         | Key | Summary | Status |\n\
+         | --- | --- | --- |\n\
+         | RW-1 | Loose end | To Do |\n"
+    );
+    let captured = collect_requests(&requests, 1);
     server.join();
+    let body: Value = search_params(&captured[0]);
+    assert!(
+        body["fields"]
+            .as_array()
+            .expect("fields should be a list")
+            .contains(&Value::from("parent"))
+    );
 }
 
 #[test]
@@ -61,7 +246,7 @@ fn my_tickets_reports_auth_error_when_empty_search_masks_expired_token() {
     );
 
     let captured = collect_requests(&requests, 2);
-    assert!(captured[0].starts_with("POST /rest/api/3/search/jql HTTP/1.1"));
+    assert!(captured[0].starts_with("GET /rest/api/3/search/jql?"));
     assert!(captured[1].starts_with("GET /rest/api/3/myself HTTP/1.1"));
 
     server.join();
@@ -99,6 +284,30 @@ fn auth_wizard_writes_config_after_validating_credentials() {
     server.join();
 }
 
+#[test]
+fn init_wizard_validates_credentials_and_saves_default_project() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"accountId":"account-id-123","displayName":"Cesar Ferreira"}"#,
+    )]);
+    let config = TempConfig::empty();
+
+    let output = run_jit_with_stdin(
+        ["--config-file", config.path_str(), "init"],
+        &format!("{}\nuser@example.com\ntoken-123\nrw\n", server.base_url),
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Connected as Cesar Ferreira."));
+    let contents = fs::read_to_string(&config.path).expect("config should be written");
+    assert!(contents.contains("api_token = \"token-123\""));
+    assert!(contents.contains("project = \"RW\""));
+
+    let captured = collect_requests(&requests, 1);
+    assert!(captured[0].starts_with("GET /rest/api/3/myself HTTP/1.1"));
+    server.join();
+}
+
 #[test]
 fn auth_wizard_rejects_invalid_token_without_writing_config() {
     let (server, requests) = spawn_sequence_server(vec![(
@@ -170,14 +379,14 @@ fn auth_wizard_uses_existing_url_and_email_when_prompts_are_empty() {
 }
 
 #[test]
-fn missing_explicit_config_suggests_auth_wizard() {
+fn missing_explicit_config_suggests_init_wizard() {
     let config = TempConfig::empty();
 
     let output = run_jit(["--config-file", config.path_str(), "--my-tickets"]);
 
     assert!(!output.status.success());
     assert!(
-        stderr(&output).contains("Run `jit auth --config-file"),
+        stderr(&output).contains("Run `jit init --config-file"),
         "stderr was: {}",
         stderr(&output)
     );
@@ -227,6 +436,86 @@ fn text_ticket_query_prints_one_line_summary() {
     server.join();
 }
 
+#[test]
+fn show_uses_the_layout_configured_for_the_issue_type() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"BUG-1","fields":{"summary":"Crash on login","status":{"name":"Open"},"priority":{"name":"High"},"issuetype":{"name":"Bug"},"created":"2026-04-10T09:00:00.000+00:00"}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"BUG-1","fields":{"summary":"Crash on login","environment":"iOS 19","customfield_10050":{"type":"doc","version":1,"content":[{"type":"paragraph","content":[{"type":"text","text":"Tap Login twice"}]}]}}}"#,
+        ),
+    ]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "\n[layouts.bug]\nfields = [\"status\", \"environment\", \"customfield_10050\", \"created\", \"duedate\"]\nlabels = { environment = \"Environment\", customfield_10050 = \"Steps to reproduce\" }\n",
+    );
+
+    let output = run_jit(["--config-file", config.path_str(), "--show", "BUG-1"]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(
+        stdout.contains(concat!(
+            "Status:             Open\n",
+            "Environment:        iOS 19\n",
+            "Steps to reproduce: Tap Login twice\n",
+            "Created:            2026-04-10\n",
+            "Due Date:           Not set\n",
+        )),
+        "stdout: {}",
+        stdout
+    );
+    assert!(!stdout.contains("Priority:"));
+
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[1].starts_with(
+        "GET /rest/api/3/issue/BUG-1?fields=customfield_10050,environment,summary HTTP/1.1"
+    ));
+    server.join();
+}
+
+#[test]
+fn include_ci_reports_the_latest_build() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"10001","key":"RW-123","fields":{"summary":"Fix login crash","status":{"name":"In Review"}}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"summary":{"build":{"overall":{"count":2,"successfulBuildCount":1,"failedBuildCount":1,"unknownBuildCount":0},"byInstanceType":{"bamboo":{}}}}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"detail":[{"builds":[{"name":"main","state":"SUCCESSFUL","lastUpdated":"2026-04-09T08:00:00.000+00:00"},{"name":"main","state":"FAILED","url":"https://ci.example.com/43","lastUpdated":"2026-04-10T11:00:00.000+00:00"}]}]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "--json",
+        "--include-ci",
+        "RW-123",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value = serde_json::from_str(&stdout(&output)).expect("issue json");
+    assert_eq!(payload["ci"]["state"], "failing");
+    assert_eq!(payload["ci"]["latest"]["url"], "https://ci.example.com/43");
+    assert_eq!(payload["ci"]["total"], 2);
+    let captured = collect_requests(&requests, 3);
+    assert!(captured[1].starts_with("GET /rest/dev-status/latest/issue/summary?issueId=10001 "));
+    assert!(captured[2].starts_with(
+        "GET /rest/dev-status/latest/issue/detail?issueId=10001&applicationType=bamboo&dataType=build "
+    ));
+    server.join();
+}
+
 #[test]
 fn show_full_ticket_query_prints_details_description_comments_and_prs() {
     let (server, requests) = spawn_sequence_server(vec![
@@ -408,71 +697,352 @@ fn invalid_since_fails_before_loading_configuration() {
     );
 }
 
-struct TempConfig {
-    dir: PathBuf,
-    path: PathBuf,
-}
+#[test]
+fn bitbucket_pr_opens_pull_request_for_ticket_branch() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"10001","key":"RW-123","fields":{"summary":"Fix login crash","issuetype":{"name":"Bug"},"description":{"type":"doc","version":1,"content":[{"type":"paragraph","content":[{"type":"text","text":"Crashes on Safari"}]}]}}}"#,
+        ),
+        (
+            "HTTP/1.1 201 Created",
+            r#"{"id":7,"title":"RW-123: Fix login crash","state":"OPEN","links":{"html":{"href":"https://bitbucket.org/acme/web/pull-requests/7"}}}"#,
+        ),
+    ]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        &format!(
+            "[git]\nbranch_template = \"{{type}}/{{key}}-{{summary}}\"\n\n[bitbucket]\nworkspace = \"acme\"\nrepo_slug = \"web\"\nusername = \"bot\"\napp_password = \"secret\"\napi_url = \"{}\"\n",
+            server.base_url
+        ),
+    );
 
-impl TempConfig {
-    fn empty() -> Self {
-        let unique = TEMP_CONFIG_COUNTER.fetch_add(1, Ordering::Relaxed);
-        let dir = std::env::temp_dir().join(format!("jit-cli-e2e-{}-{unique}", std::process::id()));
-        fs::create_dir_all(&dir).expect("temp config directory should be created");
-        let path = dir.join("config.toml");
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "bitbucket",
+        "pr",
+        "RW-123",
+    ]);
 
-        Self { dir, path }
-    }
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Pull request: #7"));
+    assert!(stdout.contains("Branches:     bug/rw-123-fix-login-crash -> main"));
+    assert!(stdout.contains("URL:          https://bitbucket.org/acme/web/pull-requests/7"));
 
-    fn new(base_url: &str) -> Self {
-        let config = Self::empty();
-        fs::write(
-            &config.path,
-            format!(
-                "[jira]\nbase_url = \"{base_url}\"\napi_token = \"token-123\"\nuser_email = \"user@example.com\"\n"
-            ),
+    let captured = collect_requests(&requests, 2);
+    assert!(
+        captured[0].starts_with(
+            "GET /rest/api/3/issue/RW-123?fields=description,issuetype,summary HTTP/1.1"
         )
-        .expect("temp config file should be written");
-
-        config
-    }
+    );
+    assert!(captured[1].starts_with("POST /repositories/acme/web/pullrequests HTTP/1.1"));
+    let body: Value =
+        serde_json::from_str(request_body(&captured[1])).expect("PR request body should be json");
+    assert_eq!(body["title"], "RW-123: Fix login crash");
+    assert_eq!(
+        body["source"]["branch"]["name"],
+        "bug/rw-123-fix-login-crash"
+    );
 
-    fn path_str(&self) -> &str {
-        self.path
-            .to_str()
-            .expect("temp config path should be valid utf-8")
-    }
+    server.join();
 }
 
-impl Drop for TempConfig {
-    fn drop(&mut self) {
-        let _ = fs::remove_dir_all(&self.dir);
-    }
-}
+#[test]
+fn current_shows_bitbucket_pull_request_state() {
+    let issue = r#"{"id":"10001","key":"RW-123","fields":{"summary":"Fix login crash","status":{"name":"In Review"}}}"#;
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 200 OK", issue),
+        ("HTTP/1.1 200 OK", issue),
+        (
+            "HTTP/1.1 200 OK",
+            r##"{"detail":[{"pullRequests":[{"id":"#7","name":"RW-123: Fix login crash","status":"OPEN","url":"https://bitbucket.org/acme/web/pull-requests/7"}]}]}"##,
+        ),
+    ]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "[bitbucket]\nworkspace = \"acme\"\nrepo_slug = \"web\"\nusername = \"bot\"\napp_password = \"secret\"\n",
+    );
+    let data_dir = config.data_dir();
+    let env = [("JIT_DATA_DIR", data_dir.as_str())];
 
-struct TestServer {
-    base_url: String,
-    handle: thread::JoinHandle<()>,
-}
+    let focus = run_jit_with_env(
+        ["--config-file", config.path_str(), "focus", "RW-123"],
+        &env,
+    );
+    let current = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "current",
+            "--output",
+            "text",
+        ],
+        &env,
+    );
 
-impl TestServer {
-    fn join(self) {
-        self.handle.join().expect("server thread should finish");
-    }
+    assert!(focus.status.success(), "stderr: {}", stderr(&focus));
+    assert!(current.status.success(), "stderr: {}", stderr(&current));
+    assert_eq!(
+        stdout(&current),
+        "RW-123: Fix login crash [In Review] (#7 OPEN)\n"
+    );
+    let captured = collect_requests(&requests, 3);
+    assert!(captured[1].starts_with("GET /rest/api/3/issue/RW-123?fields=status,summary HTTP/1.1"));
+    assert!(captured[2].starts_with(
+        "GET /rest/dev-status/latest/issue/detail?issueId=10001&applicationType=bitbucket&dataType=pullrequest "
+    ));
+    server.join();
 }
 
-fn run_jit<'a>(args: impl IntoIterator<Item = &'a str>) -> Output {
-    Command::new(env!("CARGO_BIN_EXE_jit"))
-        .args(args)
-        .env("NO_COLOR", "1")
-        .output()
-        .expect("jit command should run")
-}
+#[test]
+fn sync_pr_transitions_ticket_from_pull_request_title() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"10001","key":"RW-123","fields":{"summary":"Fix login crash","status":{"name":"In Review"}}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"transitions":[{"id":"21","name":"Start work","to":{"name":"In Progress"}},{"id":"31","name":"Resolve","to":{"name":"Done"}}]}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
 
-fn run_jit_with_stdin<'a>(args: impl IntoIterator<Item = &'a str>, stdin: &str) -> Output {
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "sync-pr",
+        "--event",
+        "merged",
+        "--title",
+        "RW-123: Fix login crash",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("RW-123: In Review -> Done"));
+
+    let captured = collect_requests(&requests, 3);
+    assert!(captured[0].starts_with("GET /rest/api/3/issue/RW-123?fields="));
+    assert!(captured[1].starts_with("GET /rest/api/3/issue/RW-123/transitions HTTP/1.1"));
+    assert!(captured[2].starts_with("POST /rest/api/3/issue/RW-123/transitions HTTP/1.1"));
+    let body: Value =
+        serde_json::from_str(request_body(&captured[2])).expect("transition body should be json");
+    assert_eq!(body["transition"]["id"], "31");
+
+    server.join();
+}
+
+#[test]
+fn sync_pr_reports_an_unmapped_event_as_skipped_in_json() {
+    let config = TempConfig::new("http://127.0.0.1:9");
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "--output",
+        "json",
+        "sync-pr",
+        "--event",
+        "closed",
+        "--ticket",
+        "RW-123",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value = serde_json::from_str(&stdout(&output)).expect("stdout should be json");
+    assert_eq!(payload["event"], "closed");
+    assert_eq!(payload["action"], "skipped");
+}
+
+#[test]
+fn wait_polls_until_ticket_reaches_status() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"10001","key":"RW-123","fields":{"summary":"Release","status":{"name":"Awaiting Approval"}}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"10001","key":"RW-123","fields":{"summary":"Release","status":{"name":"Approved"}}}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "wait",
+        "RW-123",
+        "--until",
+        "approved",
+        "--interval",
+        "1",
+        "--timeout",
+        "1m",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), "RW-123: Approved");
+    assert!(stderr(&output).contains("RW-123: Awaiting Approval (waiting for approved)"));
+    collect_requests(&requests, 2);
+
+    server.join();
+}
+
+#[test]
+fn wait_exits_non_zero_on_timeout() {
+    let (server, _requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"10001","key":"RW-123","fields":{"summary":"Release","status":{"name":"In Progress"}}}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "wait",
+        "RW-123",
+        "--until",
+        "Done",
+        "--timeout",
+        "0s",
+    ]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Timed out after 0s waiting for RW-123 to reach Done"),
+        "stderr was: {}",
+        stderr(&output)
+    );
+
+    server.join();
+}
+
+#[test]
+fn wait_rejects_zero_interval() {
+    let config = TempConfig::new("http://127.0.0.1:9");
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "wait",
+        "RW-123",
+        "--until",
+        "Done",
+        "--interval",
+        "0",
+    ]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--interval"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+struct TempConfig {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl TempConfig {
+    fn empty() -> Self {
+        let unique = TEMP_CONFIG_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("jit-cli-e2e-{}-{unique}", std::process::id()));
+        fs::create_dir_all(&dir).expect("temp config directory should be created");
+        let path = dir.join("config.toml");
+
+        Self { dir, path }
+    }
+
+    fn new(base_url: &str) -> Self {
+        let config = Self::empty();
+        fs::write(
+            &config.path,
+            format!(
+                "{STORY_POINTS_FIELD_LINE}[jira]\nbase_url = \"{base_url}\"\napi_token = \"token-123\"\nuser_email = \"user@example.com\"\n{SPRINT_FIELD_LINE}"
+            ),
+        )
+        .expect("temp config file should be written");
+
+        config
+    }
+
+    fn with_extra(base_url: &str, extra: &str) -> Self {
+        let config = Self::new(base_url);
+        let mut contents =
+            fs::read_to_string(&config.path).expect("temp config file should be readable");
+        contents.push_str(extra);
+        fs::write(&config.path, contents).expect("temp config file should be written");
+
+        config
+    }
+
+    fn data_dir(&self) -> String {
+        self.dir.join("data").to_string_lossy().into_owned()
+    }
+
+    fn path_str(&self) -> &str {
+        self.path
+            .to_str()
+            .expect("temp config path should be valid utf-8")
+    }
+}
+
+impl Drop for TempConfig {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+struct TestServer {
+    base_url: String,
+    handle: thread::JoinHandle<()>,
+}
+
+impl TestServer {
+    fn join(self) {
+        self.handle.join().expect("server thread should finish");
+    }
+}
+
+/// Default data directory, so runs that do not set their own never touch the
+/// real home directory.
+fn shared_data_dir() -> PathBuf {
+    std::env::temp_dir().join("jit-cli-e2e-data")
+}
+
+fn run_jit<'a>(args: impl IntoIterator<Item = &'a str>) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_jit"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .env("JIT_DATA_DIR", shared_data_dir())
+        .output()
+        .expect("jit command should run")
+}
+
+fn run_jit_with_env<'a>(args: impl IntoIterator<Item = &'a str>, envs: &[(&str, &str)]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_jit"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .env("JIT_DATA_DIR", shared_data_dir())
+        .envs(envs.iter().copied())
+        .output()
+        .expect("jit command should run")
+}
+
+fn run_jit_with_stdin<'a>(args: impl IntoIterator<Item = &'a str>, stdin: &str) -> Output {
     let mut child = Command::new(env!("CARGO_BIN_EXE_jit"))
         .args(args)
         .env("NO_COLOR", "1")
+        .env("JIT_DATA_DIR", shared_data_dir())
         .env("JIT_AUTH_SKIP_OPEN", "1")
+        // Keep test tokens out of the developer's real keyring.
+        .env("JIT_AUTH_NO_KEYRING", "1")
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -504,6 +1074,29 @@ fn request_body(request: &str) -> &str {
         .expect("http request should contain a body")
 }
 
+/// The query of a GET /search/jql request as JSON, with `fields` as a list
+/// and `maxResults` as a number.
+fn search_params(request: &str) -> Value {
+    let target = request
+        .split_whitespace()
+        .nth(1)
+        .expect("http request should have a target");
+    let url = reqwest::Url::parse(&format!("http://localhost{}", target))
+        .expect("request target should be a valid path");
+    let mut params = serde_json::Map::new();
+    for (key, value) in url.query_pairs() {
+        let value = match key.as_ref() {
+            "fields" => Value::from(value.split(',').collect::<Vec<_>>()),
+            "maxResults" => {
+                Value::from(value.parse::<u64>().expect("maxResults should be a number"))
+            }
+            _ => Value::from(value.as_ref()),
+        };
+        params.insert(key.into_owned(), value);
+    }
+    Value::Object(params)
+}
+
 fn collect_requests(receiver: &mpsc::Receiver<String>, expected: usize) -> Vec<String> {
     (0..expected)
         .map(|_| {
@@ -611,3 +1204,4332 @@ fn parse_content_length(headers: &[u8]) -> usize {
         })
         .unwrap_or(0)
 }
+
+#[test]
+fn approve_records_approver_comments_and_transitions() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"accountId":"account-42","displayName":"Release Manager"}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+        ("HTTP/1.1 201 Created", r#"{"id":"10500"}"#),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"transitions":[{"id":"41","name":"Approve","to":{"name":"Approved"}}]}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "[approval]\nfield = \"customfield_10200\"\napproved_status = \"Approved\"\n",
+    );
+
+    let output = run_jit(["--config-file", config.path_str(), "approve", "RW-123"]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Approved: RW-123"));
+    assert!(stdout(&output).contains("Approver: Release Manager"));
+
+    let captured = collect_requests(&requests, 5);
+    assert!(captured[0].starts_with("GET /rest/api/3/myself HTTP/1.1"));
+    assert!(captured[1].starts_with("PUT /rest/api/3/issue/RW-123 HTTP/1.1"));
+    let update: Value =
+        serde_json::from_str(request_body(&captured[1])).expect("update body should be json");
+    assert_eq!(
+        update["update"]["customfield_10200"][0]["add"]["accountId"],
+        "account-42"
+    );
+    assert!(captured[2].starts_with("POST /rest/api/3/issue/RW-123/comment HTTP/1.1"));
+    assert!(request_body(&captured[2]).contains("Approved by Release Manager."));
+    assert!(captured[4].starts_with("POST /rest/api/3/issue/RW-123/transitions HTTP/1.1"));
+
+    server.join();
+}
+
+#[test]
+fn check_toggles_checklist_item_in_text_field() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"10001","key":"RW-123","fields":{"customfield_10300":"- [x] Tag build\n- [ ] Update changelog"}}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "[checklist]\nfield = \"customfield_10300\"\n",
+    );
+
+    let output = run_jit(["--config-file", config.path_str(), "check", "RW-123", "2"]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), "Checked: 2. Update changelog");
+
+    let captured = collect_requests(&requests, 2);
+    assert!(
+        captured[0].starts_with("GET /rest/api/3/issue/RW-123?fields=customfield_10300 HTTP/1.1")
+    );
+    assert!(captured[1].starts_with("PUT /rest/api/3/issue/RW-123 HTTP/1.1"));
+    let body: Value =
+        serde_json::from_str(request_body(&captured[1])).expect("update body should be json");
+    assert_eq!(
+        body["fields"]["customfield_10300"],
+        "- [x] Tag build\n- [x] Update changelog"
+    );
+
+    server.join();
+}
+
+#[test]
+fn check_rejects_a_missing_checklist_item_as_invalid_input() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"10001","key":"RW-123","fields":{"customfield_10300":"- [x] Tag build"}}"#,
+    )]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "[checklist]\nfield = \"customfield_10300\"\n",
+    );
+
+    let output = run_jit(["--config-file", config.path_str(), "check", "RW-123", "3"]);
+
+    assert_eq!(output.status.code(), Some(65));
+    assert!(
+        stderr(&output).contains("Checklist item 3 does not exist on RW-123 (it has 1 items)"),
+        "stderr was: {}",
+        stderr(&output)
+    );
+    collect_requests(&requests, 1);
+    server.join();
+}
+
+#[test]
+fn triage_applies_single_key_actions_to_matching_issues() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Crash on login","status":{"name":"Open"}}},{"id":"2","key":"RW-2","fields":{"summary":"Duplicate report","status":{"name":"Open"}}}],"isLast":true}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"transitions":[{"id":"31","name":"Close","to":{"name":"Done"}}]}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit_with_stdin(
+        [
+            "--config-file",
+            config.path_str(),
+            "triage",
+            "--jql",
+            "project = RW AND type = Bug",
+        ],
+        "l\nbug, triaged\ns\nc\n",
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("[1/2] RW-1: Crash on login"));
+    assert!(stdout.contains("Moved RW-2 to Done"));
+    assert!(stdout.contains("Triage complete: 1 updated, 1 closed, 0 skipped"));
+
+    let captured = collect_requests(&requests, 4);
+    assert!(captured[0].starts_with("GET /rest/api/3/search/jql?"));
+    assert!(captured[1].starts_with("PUT /rest/api/3/issue/RW-1 HTTP/1.1"));
+    let body: Value =
+        serde_json::from_str(request_body(&captured[1])).expect("update body should be json");
+    assert_eq!(body["update"]["labels"][1]["add"], "triaged");
+    assert!(captured[3].starts_with("POST /rest/api/3/issue/RW-2/transitions HTTP/1.1"));
+
+    server.join();
+}
+
+#[test]
+fn triage_reports_a_rejected_change_and_asks_again() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Crash on login","status":{"name":"Open"}}}],"isLast":true}"#,
+        ),
+        (
+            "HTTP/1.1 400 Bad Request",
+            r#"{"errorMessages":[],"errors":{"priority":"Specify a valid priority"}}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit_with_stdin(
+        [
+            "--config-file",
+            config.path_str(),
+            "triage",
+            "--jql",
+            "project = RW AND type = Bug",
+        ],
+        "p\nHihg\np\nHigh\ns\n",
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stderr(&output).contains("Specify a valid priority"));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Set RW-1 priority to High"));
+    assert!(stdout.contains("Triage complete: 1 updated, 0 closed, 0 skipped"));
+
+    let captured = collect_requests(&requests, 3);
+    let body: Value =
+        serde_json::from_str(request_body(&captured[2])).expect("update body should be json");
+    assert_eq!(body["fields"]["priority"]["name"], "High");
+
+    server.join();
+}
+
+#[test]
+fn poker_writes_story_points_for_unestimated_issues() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Already sized","customfield_10016":5.0}},{"id":"2","key":"RW-2","fields":{"summary":"Add export","customfield_10016":null}}],"isLast":true}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit_with_stdin(
+        [
+            "--config-file",
+            config.path_str(),
+            "poker",
+            "--jql",
+            "sprint in futureSprints()",
+            "--scale",
+            "1,2,3,5,8",
+        ],
+        "4\n3\n",
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(!stdout.contains("RW-1: Already sized"));
+    assert!(stdout.contains("[1/1] RW-2: Add export"));
+    assert!(stdout.contains("Estimate 4 is not on the scale: 1, 2, 3, 5, 8"));
+    assert!(stdout.contains("Estimation complete: 1 estimated (3 points), 0 skipped"));
+
+    let captured = collect_requests(&requests, 2);
+    let search: Value = search_params(&captured[0]);
+    assert_eq!(
+        search["jql"],
+        "(sprint in futureSprints()) AND cf[10016] is EMPTY"
+    );
+    assert!(captured[1].starts_with("PUT /rest/api/3/issue/RW-2 HTTP/1.1"));
+    let body: Value =
+        serde_json::from_str(request_body(&captured[1])).expect("update body should be json");
+    assert_eq!(body["fields"]["customfield_10016"], 3.0);
+
+    server.join();
+}
+
+#[test]
+fn groom_reports_backlog_findings_as_json() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Export invoices","issuetype":{"name":"Story"},"components":[],"customfield_10016":null}}],"isLast":true}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "groom",
+        "--project",
+        "RW",
+        "--json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value = serde_json::from_str(stdout(&output).trim()).expect("stdout is json");
+    assert_eq!(payload["issues"], 1);
+    assert_eq!(payload["unestimated"][0]["key"], "RW-1");
+    assert_eq!(payload["missingComponents"][0]["key"], "RW-1");
+    assert_eq!(payload["missingEpic"][0]["key"], "RW-1");
+
+    let captured = collect_requests(&requests, 1);
+    let body: Value = search_params(&captured[0]);
+    assert!(
+        body["jql"]
+            .as_str()
+            .expect("jql should be a string")
+            .starts_with("project = \"RW\" AND statusCategory != Done")
+    );
+
+    server.join();
+}
+
+#[test]
+fn lint_reports_rule_violations_and_exits_non_zero() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"1","key":"RW-123","fields":{"summary":"Ship export","status":{"name":"Done","statusCategory":{"key":"done"}},"labels":[],"fixVersions":[],"parent":{"key":"RW-100"}}}"#,
+    )]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "[lint]\nrules = [\"fix-version-on-done\", \"epic-link\"]\n",
+    );
+
+    let output = run_jit(["--config-file", config.path_str(), "lint", "RW-123"]);
+
+    assert!(!output.status.success());
+    let stdout = stdout(&output);
+    assert!(stdout.contains("RW-123: Ship export"));
+    assert!(stdout.contains("fix-version-on-done  done issue has no fix version"));
+    assert!(!stdout.contains("epic-link"));
+    assert!(!stdout.contains("labels"));
+    assert!(
+        stderr(&output).contains("Found 1 problem(s) in 1 of 1 issue(s)"),
+        "stderr was: {}",
+        stderr(&output)
+    );
+
+    let captured = collect_requests(&requests, 1);
+    assert!(captured[0].starts_with(
+        "GET /rest/api/3/issue/RW-123?fields=assignee,fixVersions,issuetype,labels,parent,status,summary HTTP/1.1"
+    ));
+
+    server.join();
+}
+
+#[test]
+fn done_refuses_ticket_failing_definition_of_done_without_force() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"10001","key":"RW-123","fields":{"summary":"Ship export","fixVersions":[]}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"detail":[{"pullRequests":[{"id":"1","name":"Ship export","status":"MERGED","url":"https://github.com/org/repo/pull/1"}]}]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit(["--config-file", config.path_str(), "done", "RW-123"]);
+
+    assert!(!output.status.success());
+    let stdout = stdout(&output);
+    assert!(stdout.contains("PASS  linked-pr"));
+    assert!(stdout.contains("FAIL  fix-version          no fix version set"));
+    assert!(
+        stderr(&output).contains("RW-123 does not meet the definition of done"),
+        "stderr was: {}",
+        stderr(&output)
+    );
+
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[0].starts_with("GET /rest/api/3/issue/RW-123?fields=fixVersions,summary"));
+    assert!(captured[1].contains("/rest/dev-status/latest/issue/detail?issueId=10001"));
+
+    server.join();
+}
+
+#[test]
+fn done_with_force_transitions_despite_failing_checks() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"10001","key":"RW-123","fields":{"summary":"Ship export","fixVersions":[{"name":"2.5.0"}]}}"#,
+        ),
+        ("HTTP/1.1 200 OK", r#"{"detail":[]}"#),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"transitions":[{"id":"31","name":"Resolve","to":{"name":"Done"}}]}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "done",
+        "RW-123",
+        "--force",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("RW-123: moved to Done"));
+    assert!(stderr(&output).contains("Continuing because --force was passed"));
+
+    let captured = collect_requests(&requests, 4);
+    assert!(captured[3].starts_with("POST /rest/api/3/issue/RW-123/transitions HTTP/1.1"));
+
+    server.join();
+}
+
+#[test]
+fn dup_links_resolves_and_comments_on_the_duplicate() {
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 201 Created", ""),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"transitions":[{"id":"21","name":"Start","to":{"name":"In Progress"}},{"id":"41","name":"Close","to":{"name":"Closed"}}]}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+        ("HTTP/1.1 201 Created", r#"{"id":"9001"}"#),
+    ]);
+    let config = TempConfig::with_extra(&server.base_url, "\n[duplicate]\nstatus = \"Closed\"\n");
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "dup",
+        "RW-2044",
+        "--of",
+        "RW-1931",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "RW-2044: closed as a duplicate of RW-1931 (Closed)\n"
+    );
+    let captured = collect_requests(&requests, 4);
+    server.join();
+    assert!(captured[0].starts_with("POST /rest/api/3/issueLink HTTP/1.1"));
+    let link: Value = serde_json::from_str(request_body(&captured[0])).expect("link body");
+    assert_eq!(link["type"]["name"], "Duplicate");
+    assert_eq!(link["inwardIssue"]["key"], "RW-2044");
+    assert_eq!(link["outwardIssue"]["key"], "RW-1931");
+    assert!(captured[2].starts_with("POST /rest/api/3/issue/RW-2044/transitions HTTP/1.1"));
+    let transition: Value =
+        serde_json::from_str(request_body(&captured[2])).expect("transition body");
+    assert_eq!(transition["transition"]["id"], "41");
+    assert_eq!(transition["fields"]["resolution"]["name"], "Duplicate");
+    assert!(captured[3].starts_with("POST /rest/api/3/issue/RW-2044/comment HTTP/1.1"));
+    assert!(request_body(&captured[3]).contains("/browse/RW-1931"));
+}
+
+#[test]
+fn merge_digests_the_duplicate_onto_the_original_and_closes_it() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"comments":[{"id":"1","author":{"displayName":"Ada"},"created":"2024-03-01T09:00:00.000+0000","body":{"type":"doc","version":1,"content":[{"type":"paragraph","content":[{"type":"text","text":"Also crashes on Android"}]}]}}],"total":1}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"fields":{"attachment":[{"filename":"crash.log","content":"https://jira.test/attachment/10/crash.log"}]}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"watchers":[{"accountId":"acc-1","displayName":"Ada"},{"accountId":"acc-2","displayName":"Grace"}]}"#,
+        ),
+        ("HTTP/1.1 201 Created", r#"{"id":"9002"}"#),
+        ("HTTP/1.1 204 No Content", ""),
+        (
+            "HTTP/1.1 403 Forbidden",
+            r#"{"errorMessages":["No permission"]}"#,
+        ),
+        ("HTTP/1.1 201 Created", ""),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"transitions":[{"id":"41","name":"Done","to":{"name":"Done"}}]}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+        ("HTTP/1.1 201 Created", r#"{"id":"9003"}"#),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "merge",
+        "RW-2044",
+        "--into",
+        "RW-1931",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "RW-2044: merged into RW-1931 (1 comments, 1 attachments, 1 watchers) and closed (Done)\n"
+    );
+    assert!(stderr(&output).contains("Could not add Grace as a watcher of RW-1931"));
+    let captured = collect_requests(&requests, 10);
+    server.join();
+    assert!(captured[3].starts_with("POST /rest/api/3/issue/RW-1931/comment HTTP/1.1"));
+    let digest = request_body(&captured[3]);
+    assert!(digest.contains("/browse/RW-2044"));
+    assert!(digest.contains("Ada (2024-03-01): Also crashes on Android"));
+    assert!(digest.contains("https://jira.test/attachment/10/crash.log"));
+    assert!(captured[4].starts_with("POST /rest/api/3/issue/RW-1931/watchers HTTP/1.1"));
+    assert_eq!(request_body(&captured[4]), r#""acc-1""#);
+    assert!(captured[6].starts_with("POST /rest/api/3/issueLink HTTP/1.1"));
+    assert!(captured[8].starts_with("POST /rest/api/3/issue/RW-2044/transitions HTTP/1.1"));
+}
+
+#[test]
+fn split_creates_linked_issues_from_picked_items_in_the_same_sprint() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"10","key":"RW-1931","fields":{"summary":"Big story","issuetype":{"name":"Story"},"customfield_10300":"- [ ] Add API\n- [x] Add UI","customfield_10020":[{"id":7,"name":"Sprint 42","state":"active"}],"description":{"type":"doc","version":1,"content":[{"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Reporting"}]},{"type":"paragraph","content":[{"type":"text","text":"Weekly CSV export."}]}]}}}"#,
+        ),
+        (
+            "HTTP/1.1 201 Created",
+            r#"{"id":"11","key":"RW-2001","self":"x"}"#,
+        ),
+        ("HTTP/1.1 201 Created", ""),
+        (
+            "HTTP/1.1 201 Created",
+            r#"{"id":"12","key":"RW-2002","self":"x"}"#,
+        ),
+        ("HTTP/1.1 201 Created", ""),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "\n[checklist]\nfield = \"customfield_10300\"\n",
+    );
+
+    let output = run_jit_with_stdin(
+        ["--config-file", config.path_str(), "split", "RW-1931"],
+        "1,3\ny\n",
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stderr(&output).contains(
+        "Checklist:\n  1. Add API\n  2. Add UI\nDescription sections:\n  3. Reporting\n"
+    ));
+    assert_eq!(
+        stdout(&output),
+        "RW-2001: Add API\nRW-2002: Reporting\nSplit from RW-1931 and added to Sprint 42\n"
+    );
+    let captured = collect_requests(&requests, 6);
+    server.join();
+    let create: Value = serde_json::from_str(request_body(&captured[3])).expect("create body");
+    assert_eq!(create["fields"]["project"]["key"], "RW");
+    assert_eq!(create["fields"]["issuetype"]["name"], "Story");
+    assert_eq!(create["fields"]["summary"], "Reporting");
+    assert!(request_body(&captured[3]).contains("Weekly CSV export."));
+    let link: Value = serde_json::from_str(request_body(&captured[2])).expect("link body");
+    assert_eq!(link["type"]["name"], "Issue split");
+    assert_eq!(link["inwardIssue"]["key"], "RW-1931");
+    assert_eq!(link["outwardIssue"]["key"], "RW-2001");
+    assert!(captured[5].starts_with("POST /rest/agile/1.0/sprint/7/issue HTTP/1.1"));
+    assert!(request_body(&captured[5]).contains(r#"["RW-2001","RW-2002"]"#));
+}
+
+#[test]
+fn stats_prints_trend_table_and_top_lists() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Bug","issuetype":{"name":"Bug"},"created":"2020-01-01T09:00:00.000+0000"}}],"isLast":true}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "stats",
+        "--project",
+        "RW",
+        "--since",
+        "14d",
+        "--chart",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Project stats for RW (last 14 days, 1 issues)"));
+    assert!(stdout.contains("│ Period starting │ Created │ Resolved │ Open bugs │"));
+    assert!(stdout.contains("Open bugs  ██"));
+    assert!(stdout.contains("Top assignees"));
+
+    let captured = collect_requests(&requests, 1);
+    let body: Value = search_params(&captured[0]);
+    assert!(
+        body["jql"]
+            .as_str()
+            .expect("jql should be a string")
+            .contains("created >= -14d OR resolved >= -14d")
+    );
+
+    server.join();
+}
+
+#[test]
+fn stats_exports_json_and_csv_for_dashboards() {
+    for flag in ["--json", "--csv"] {
+        let (server, requests) = spawn_sequence_server(vec![(
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Bug","issuetype":{"name":"Bug"},"created":"2020-01-01T09:00:00.000+0000","assignee":{"displayName":"Ada"}}}],"isLast":true}"#,
+        )]);
+        let config = TempConfig::new(&server.base_url);
+
+        let output = run_jit([
+            "--config-file",
+            config.path_str(),
+            "stats",
+            "--project",
+            "RW",
+            "--since",
+            "2w",
+            flag,
+        ]);
+
+        assert!(output.status.success(), "stderr: {}", stderr(&output));
+        let stdout = stdout(&output);
+        if flag == "--json" {
+            let payload: Value = serde_json::from_str(stdout.trim()).expect("stdout is json");
+            assert_eq!(payload["project"], "RW");
+            assert_eq!(payload["buckets"].as_array().map(Vec::len), Some(2));
+            assert_eq!(payload["buckets"][1]["openBugs"], 1);
+        } else {
+            let lines: Vec<&str> = stdout.lines().collect();
+            assert_eq!(lines[0], "period_start,created,resolved,open_bugs");
+            assert_eq!(lines.len(), 3);
+            assert!(lines[2].ends_with(",0,0,1"));
+        }
+
+        collect_requests(&requests, 1);
+        server.join();
+    }
+}
+
+#[test]
+fn stats_warns_when_limit_leaves_issues_out() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Bug","issuetype":{"name":"Bug"},"created":"2020-01-01T09:00:00.000+0000"}}],"nextPageToken":"next"}"#,
+        ),
+        ("HTTP/1.1 200 OK", r#"{"count":40}"#),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "stats",
+        "--project",
+        "RW",
+        "--limit",
+        "1",
+        "--json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(
+        stderr(&output).contains("only 1 of 40 matching issues were fetched"),
+        "stderr: {}",
+        stderr(&output)
+    );
+    let captured = collect_requests(&requests, 2);
+    server.join();
+    let search: Value = search_params(&captured[0]);
+    assert!(
+        search["jql"]
+            .as_str()
+            .is_some_and(|jql| jql.ends_with("ORDER BY created ASC"))
+    );
+    assert!(captured[1].starts_with("POST /rest/api/3/search/approximate-count "));
+    let count: Value =
+        serde_json::from_str(request_body(&captured[1])).expect("count body should be json");
+    assert!(
+        !count["jql"]
+            .as_str()
+            .is_some_and(|jql| jql.contains("ORDER BY"))
+    );
+}
+
+#[test]
+fn snapshot_records_counts_that_stats_trend_reads_back() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"One","status":{"name":"To Do"}}},{"id":"2","key":"RW-2","fields":{"summary":"Two","status":{"name":"In Progress"},"assignee":{"displayName":"Ada"}}}],"isLast":true}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+    let data_dir = config.data_dir();
+    let envs = [("JIT_DATA_DIR", data_dir.as_str())];
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "snapshot",
+            "--project",
+            "RW",
+        ],
+        &envs,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Recorded 2 open issues for RW"));
+    collect_requests(&requests, 1);
+    server.join();
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "stats",
+            "--project",
+            "RW",
+            "--trend",
+            "--csv",
+        ],
+        &envs,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "Date,Open,In Progress,To Do");
+    assert!(lines[1].ends_with(",2,1,1"));
+}
+
+#[test]
+fn snapshot_records_the_full_count_when_limit_truncates() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"One","status":{"name":"To Do"}}}],"nextPageToken":"next"}"#,
+        ),
+        ("HTTP/1.1 200 OK", r#"{"count":7}"#),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let data_dir = config.data_dir();
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "snapshot",
+            "--project",
+            "RW",
+            "--limit",
+            "1",
+            "--output",
+            "json",
+        ],
+        &[("JIT_DATA_DIR", data_dir.as_str())],
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stderr(&output).contains("only 1 of 7 matching issues were fetched"));
+    let snapshot: Value = serde_json::from_str(&stdout(&output)).expect("snapshot json");
+    assert_eq!(snapshot["total"], 7);
+    collect_requests(&requests, 2);
+    server.join();
+}
+
+#[test]
+fn heatmap_renders_status_by_age_grid() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Forgotten bug","status":{"name":"To Do"},"created":"2020-01-01T09:00:00.000+0000"}}],"isLast":true}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "heatmap",
+        "--project",
+        "RW",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Status    <1w   1-4w   1-3m   3-6m  6-12m    >1y"));
+    assert!(stdout.contains("To Do       ·      ·      ·      ·      ·      1"));
+    assert!(stdout.contains("RW-1  Forgotten bug"));
+
+    collect_requests(&requests, 1);
+    server.join();
+}
+
+#[test]
+fn output_flag_renders_sprint_tickets_as_csv_and_ndjson() {
+    let response = r#"{"issues":[{"id":"10001","key":"RW-123","fields":{"summary":"Support, then ship","status":{"name":"In Progress"}}}]}"#;
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 200 OK", response),
+        ("HTTP/1.1 200 OK", response),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let csv = run_jit(["--config-file", config.path_str(), "--output", "csv"]);
+    assert!(csv.status.success(), "stderr: {}", stderr(&csv));
+    assert_eq!(
+        stdout(&csv),
+        "Key,Summary,Status\nRW-123,\"Support, then ship\",In Progress\n"
+    );
+
+    let ndjson = run_jit(["--config-file", config.path_str(), "--output", "ndjson"]);
+    assert!(ndjson.status.success(), "stderr: {}", stderr(&ndjson));
+    let record: Value =
+        serde_json::from_str(stdout(&ndjson).trim()).expect("ndjson line should be valid JSON");
+    assert_eq!(record["ticket"], "RW-123");
+    assert_eq!(record["status"], "In Progress");
+
+    collect_requests(&requests, 2);
+    server.join();
+}
+
+#[test]
+fn output_format_from_config_applies_to_ticket_query() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"10001","key":"RW-123","fields":{"summary":"Implement backlog creation"}}"#,
+    )]);
+    let config = TempConfig::with_extra(&server.base_url, "\n[output]\nformat = \"yaml\"\n");
+
+    let output = run_jit(["--config-file", config.path_str(), "RW-123"]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("ticket: RW-123"));
+    assert!(stdout.contains("summary: Implement backlog creation"));
+
+    collect_requests(&requests, 1);
+    server.join();
+}
+
+#[test]
+fn missing_ticket_exits_with_not_found_code_and_json_error() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 404 Not Found",
+        r#"{"errorMessages":["Issue does not exist or you do not have permission to see it."],"errors":{}}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit(["--config-file", config.path_str(), "--json", "RW-404"]);
+
+    assert_eq!(output.status.code(), Some(66));
+    assert!(stdout(&output).is_empty());
+    let error: Value =
+        serde_json::from_str(stderr(&output).trim()).expect("stderr should be a JSON error");
+    assert_eq!(error["error"]["kind"], "not_found");
+    assert!(
+        error["error"]["message"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("404")
+    );
+    assert_eq!(
+        error["error"]["hint"],
+        "Check the key, and that your Jira account can see the project."
+    );
+
+    collect_requests(&requests, 1);
+    server.join();
+}
+
+#[test]
+fn rejected_token_is_traced_to_where_it_came_from() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 401 Unauthorized",
+            r#"{"errorMessages":["Unauthorized"]}"#,
+        ),
+        (
+            "HTTP/1.1 401 Unauthorized",
+            r#"{"errorMessages":["Unauthorized"]}"#,
+        ),
+        (
+            "HTTP/1.1 403 Forbidden",
+            r#"{"errorMessages":["You do not have permission to view this issue."]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit(["--config-file", config.path_str(), "RW-123"]);
+    assert_eq!(output.status.code(), Some(77));
+    let stderr_text = stderr(&output);
+    assert!(
+        stderr_text.contains(
+            "Jira rejected the token for user@example.com; it has most likely expired or been revoked.\nRun `jit auth login` to save a new one."
+        ),
+        "stderr: {}",
+        stderr_text
+    );
+    assert!(!stderr_text.contains("Hint:"));
+
+    let output = run_jit_with_env(
+        ["--config-file", config.path_str(), "RW-123"],
+        &[("JIT_JIRA_API_TOKEN", "old-token")],
+    );
+    assert!(stderr(&output).contains(
+        "Replace it in the JIT_JIRA_API_TOKEN environment variable and run the command again."
+    ));
+
+    let output = run_jit(["--config-file", config.path_str(), "--json", "RW-123"]);
+    assert_eq!(output.status.code(), Some(77));
+    let error: Value = serde_json::from_str(stderr(&output).trim()).expect("json error");
+    assert_eq!(error["error"]["kind"], "forbidden");
+
+    collect_requests(&requests, 3);
+    server.join();
+}
+
+#[test]
+fn all_profiles_merges_tickets_from_every_instance() {
+    let (work, work_requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"In Progress"}}}],"isLast":true}"#,
+    )]);
+    let (client, client_requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"issues":[{"id":"2","key":"CL-7","fields":{"summary":"Export invoices","status":{"name":"To Do"}}}],"isLast":true}"#,
+    )]);
+    let config = TempConfig::with_extra(
+        &work.base_url,
+        &format!(
+            "\n[profiles.client.jira]\nbase_url = \"{}\"\n\n[profiles.old.jira]\nbase_url = \"http://127.0.0.1:9\"\n",
+            client.base_url
+        ),
+    );
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "--all-profiles",
+        "--json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let tickets: Value = serde_json::from_str(&stdout(&output)).expect("json output");
+    assert_eq!(
+        tickets,
+        serde_json::json!([
+            {"instance": "default", "ticket": "RW-1", "summary": "Fix login", "status": "In Progress"},
+            {"instance": "client", "ticket": "CL-7", "summary": "Export invoices", "status": "To Do"},
+        ])
+    );
+    assert!(
+        stderr(&output).starts_with("old: "),
+        "stderr: {}",
+        stderr(&output)
+    );
+
+    let captured = collect_requests(&client_requests, 1);
+    assert!(captured[0].starts_with("GET /rest/api/3/search/jql?"));
+    assert!(
+        search_params(&captured[0])["jql"]
+            .as_str()
+            .is_some_and(|jql| jql.contains("assignee = currentUser() AND sprint in openSprints()"))
+    );
+    collect_requests(&work_requests, 1);
+    work.join();
+    client.join();
+}
+
+#[test]
+fn fallback_profiles_finds_a_ticket_on_another_site() {
+    let (work, work_requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 404 Not Found",
+        r#"{"errorMessages":["Issue does not exist or you do not have permission to see it."],"errors":{}}"#,
+    )]);
+    let client_issue = r#"{"id":"2","key":"CL-7","fields":{"summary":"Export invoices"}}"#;
+    let (client, client_requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 200 OK", client_issue),
+        ("HTTP/1.1 200 OK", client_issue),
+    ]);
+    let config = TempConfig::with_extra(
+        &work.base_url,
+        &format!(
+            "fallback_profiles = true\n\n[profiles.client.jira]\nbase_url = \"{}\"\n",
+            client.base_url
+        ),
+    );
+
+    let output = run_jit(["--config-file", config.path_str(), "--json", "CL-7"]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let issue: Value = serde_json::from_str(&stdout(&output)).expect("json output");
+    assert_eq!(issue["ticket"], "CL-7");
+    assert_eq!(
+        stderr(&output).trim(),
+        format!(
+            "CL-7 is not on {}; showing it from profile client ({}).",
+            work.base_url, client.base_url
+        )
+    );
+
+    collect_requests(&work_requests, 1);
+    let captured = collect_requests(&client_requests, 2);
+    assert!(captured[1].starts_with("GET /rest/api/3/issue/CL-7"));
+    work.join();
+    client.join();
+}
+
+#[test]
+fn dash_reads_ticket_keys_from_stdin_and_reports_bad_lines() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"AB-1","fields":{"summary":"First","status":{"name":"To Do"}}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"2","key":"AB-2","fields":{"summary":"Second","status":{"name":"Done"}}}"#,
+        ),
+        (
+            "HTTP/1.1 404 Not Found",
+            r#"{"errorMessages":["Issue does not exist or you do not have permission to see it."],"errors":{}}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let input = format!(
+        "AB-1\n\nnot a ticket\n{}/browse/AB-2\nAB-3\n",
+        server.base_url
+    );
+
+    let output = run_jit_with_stdin(["--config-file", config.path_str(), "--text", "-"], &input);
+
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "AB-1: First\nAB-2: Second\n");
+    let errors = stderr(&output);
+    assert!(
+        errors.contains("line 3: 'not a ticket' is not a ticket key or URL"),
+        "stderr: {}",
+        errors
+    );
+    assert!(errors.contains("AB-3: "), "stderr: {}", errors);
+    assert!(
+        errors.contains("2 of 4 lines could not be resolved"),
+        "stderr: {}",
+        errors
+    );
+
+    let captured = collect_requests(&requests, 3);
+    assert!(captured[1].starts_with("GET /rest/api/3/issue/AB-2"));
+    server.join();
+}
+
+#[test]
+fn read_only_profile_refuses_commands_that_change_jira() {
+    let config = TempConfig::with_extra(
+        "http://127.0.0.1:9",
+        "\n[profiles.prod.jira]\nbase_url = \"https://prod.example.com\"\nread_only = true\n",
+    );
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "--profile",
+        "prod",
+        "done",
+        "RW-1",
+    ]);
+    assert_eq!(output.status.code(), Some(78));
+    assert!(
+        stderr(&output).contains(
+            "jit done would change Jira, but https://prod.example.com is configured read-only (jira.read_only = true)."
+        ),
+        "stderr: {}",
+        stderr(&output)
+    );
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "move",
+            "RW-1",
+            "Done",
+            "--output",
+            "json",
+        ],
+        &[("JIT_JIRA_READ_ONLY", "true")],
+    );
+    assert_eq!(output.status.code(), Some(78));
+    let error: Value = serde_json::from_str(stderr(&output).trim()).expect("json error");
+    assert_eq!(error["error"]["kind"], "config");
+}
+
+#[test]
+fn html_instead_of_json_exits_with_parse_code_and_a_hint() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        "<html><body>Sign in to continue</body></html>",
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit(["--config-file", config.path_str(), "RW-123"]);
+
+    assert_eq!(output.status.code(), Some(76));
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("Error: Failed to parse JIRA API response"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(stderr.contains(
+        "Hint: Check that jira.base_url points at your Jira site rather than a login page or proxy."
+    ));
+
+    collect_requests(&requests, 1);
+    server.join();
+}
+
+#[test]
+fn missing_config_exits_with_config_code() {
+    let config = TempConfig::empty();
+
+    let output = run_jit(["--config-file", config.path_str(), "RW-123"]);
+
+    assert_eq!(output.status.code(), Some(78));
+    assert!(stderr(&output).contains("jit init --config-file"));
+}
+
+#[test]
+fn rejected_edit_lists_jira_error_messages() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 400 Bad Request",
+        r#"{"errorMessages":[],"errors":{"summary":"You must specify a summary of the issue."}}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "edit",
+        "RW-123",
+        "--summary",
+        " ",
+    ]);
+
+    assert_eq!(output.status.code(), Some(65));
+    let stderr = stderr(&output);
+    assert!(stderr.contains("JIRA API request failed with status: 400 Bad Request"));
+    assert!(stderr.contains("  • summary: You must specify a summary of the issue."));
+    assert!(!stderr.contains("errorMessages"));
+
+    collect_requests(&requests, 1);
+    server.join();
+}
+
+#[test]
+fn stats_flag_reports_api_calls_on_stderr() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 404 Not Found",
+        r#"{"errorMessages":["Issue does not exist"]}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit(["--config-file", config.path_str(), "--stats", "RW-404"]);
+
+    let stderr = stderr(&output);
+    assert!(stderr.contains("API calls:  1"));
+    assert!(stderr.contains("Cache hits: 0"));
+    assert!(stderr.contains("404 GET /rest/api/3/issue/RW-404"));
+
+    collect_requests(&requests, 1);
+    server.join();
+}
+
+#[test]
+fn unchanged_responses_are_revalidated_with_etags() {
+    let issue =
+        r#"{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"To Do"}}}"#;
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 200 OK\r\netag: \"v1\"", issue),
+        ("HTTP/1.1 304 Not Modified\r\netag: \"v1\"", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let data_dir = config.data_dir();
+    let env = [("JIT_DATA_DIR", data_dir.as_str())];
+
+    let first = run_jit_with_env(["--config-file", config.path_str(), "RW-1"], &env);
+    let second = run_jit_with_env(
+        ["--config-file", config.path_str(), "--stats", "RW-1"],
+        &env,
+    );
+
+    assert!(first.status.success(), "stderr: {}", stderr(&first));
+    assert!(second.status.success(), "stderr: {}", stderr(&second));
+    assert_eq!(stdout(&second), stdout(&first));
+    assert!(stdout(&second).contains("Fix login"));
+    let stats = stderr(&second);
+    assert!(stats.contains("Cache hits: 1"), "stderr: {stats}");
+    assert!(stats.contains("304 GET /rest/api/3/issue/RW-1"));
+    let requests = collect_requests(&requests, 2);
+    server.join();
+    assert!(!requests[0].to_ascii_lowercase().contains("if-none-match"));
+    assert!(
+        requests[1]
+            .to_ascii_lowercase()
+            .contains("if-none-match: \"v1\"")
+    );
+}
+
+#[test]
+fn cached_responses_are_kept_private_to_the_account() {
+    let issue =
+        r#"{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"To Do"}}}"#;
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 200 OK\r\netag: \"v1\"", issue),
+        ("HTTP/1.1 200 OK\r\netag: \"v1\"", issue),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let data_dir = config.data_dir();
+
+    let first = run_jit_with_env(
+        ["--config-file", config.path_str(), "RW-1"],
+        &[("JIT_DATA_DIR", data_dir.as_str())],
+    );
+    let second = run_jit_with_env(
+        ["--config-file", config.path_str(), "RW-1"],
+        &[
+            ("JIT_DATA_DIR", data_dir.as_str()),
+            ("JIT_JIRA_API_TOKEN", "someone-elses-token"),
+        ],
+    );
+
+    assert!(first.status.success(), "stderr: {}", stderr(&first));
+    assert!(second.status.success(), "stderr: {}", stderr(&second));
+    let requests = collect_requests(&requests, 2);
+    server.join();
+    assert!(!requests[1].to_ascii_lowercase().contains("if-none-match"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let entries = fs::read_dir(PathBuf::from(&data_dir).join("http-cache"))
+            .expect("http-cache should exist")
+            .map(|entry| entry.expect("cache entry").metadata().expect("metadata"))
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 2);
+        for metadata in entries {
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+    }
+}
+
+#[test]
+fn api_requests_advertise_compressed_responses() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 404 Not Found",
+        r#"{"errorMessages":["Issue does not exist"]}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    run_jit(["--config-file", config.path_str(), "RW-404"]);
+
+    let requests = collect_requests(&requests, 1);
+    let accept_encoding = requests[0]
+        .lines()
+        .find_map(|line| {
+            line.to_ascii_lowercase()
+                .strip_prefix("accept-encoding:")
+                .map(|value| value.trim().to_string())
+        })
+        .expect("request should send accept-encoding");
+    assert!(accept_encoding.contains("gzip"));
+    assert!(accept_encoding.contains("br"));
+    server.join();
+}
+
+#[test]
+fn http_config_section_applies_to_api_client() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"10001","key":"RW-123","fields":{"summary":"Implement backlog creation"}}"#,
+    )]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "\n[http]\nversion = \"http1\"\npool_max_idle_per_host = 2\ntcp_keepalive_secs = 30\n",
+    );
+
+    let output = run_jit(["--config-file", config.path_str(), "--text", "RW-123"]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("RW-123: Implement backlog creation"));
+
+    collect_requests(&requests, 1);
+    server.join();
+}
+
+#[test]
+fn sprint_lookup_reports_missing_agile_api_as_unsupported() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"accountId":"account-id-999","displayName":"Cesar Ferreira"}"#,
+        ),
+        ("HTTP/1.1 404 Not Found", r#"{"errorMessages":[]}"#),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"version":"9.12.0","deploymentType":"Server"}"#,
+        ),
+        ("HTTP/1.1 404 Not Found", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "create",
+            "--project",
+            "RW",
+            "--summary",
+            "Implement backlog creation",
+            "--current-sprint",
+            "--board",
+            "42",
+        ],
+        &[("JIT_DATA_DIR", &config.data_dir())],
+    );
+
+    assert_eq!(output.status.code(), Some(69));
+    assert!(stderr(&output).contains(
+        "Boards and sprints (Jira Software) are not supported by your instance (Jira Server 9.12.0, REST API v2)."
+    ));
+
+    let captured = collect_requests(&requests, 4);
+    assert!(captured[2].starts_with("GET /rest/api/2/serverInfo HTTP/1.1"));
+    assert!(captured[3].starts_with("GET /rest/agile/1.0/board?maxResults=1 HTTP/1.1"));
+    server.join();
+
+    let cache = fs::read_to_string(PathBuf::from(config.data_dir()).join("capabilities.json"))
+        .expect("capabilities should be cached");
+    assert!(cache.contains("\"agile\": false"));
+}
+
+#[test]
+fn share_redact_prints_sanitized_markdown() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"10001","key":"RW-123","fields":{"summary":"Crash reported by jane@example.com","status":{"name":"To Do"},"assignee":{"displayName":"Jane Doe"},"description":{"type":"doc","content":[{"type":"paragraph","content":[{"type":"text","text":"Jane Doe saw build 4711 fail"}]}]}}}"#,
+    )]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "\n[share]\nredact_patterns = [\"build \\\\d+\"]\n",
+    );
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "share",
+        "RW-123",
+        "--redact",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.starts_with("# RW-123: Crash reported by [email]\n"));
+    assert!(stdout.contains("- **Assignee:** Person 1"));
+    assert!(stdout.contains("Person 1 saw [redacted] fail"));
+    assert!(!stdout.contains("Jane"));
+
+    collect_requests(&requests, 1);
+    server.join();
+}
+
+#[test]
+fn summarize_sends_ticket_to_configured_llm() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"10001","key":"RW-123","fields":{"summary":"Checkout times out","assignee":{"displayName":"Jane Doe"}}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"choices":[{"message":{"role":"assistant","content":"Checkout is slow.\n\n### Next actions\n- Profile the payment call"}}]}"#,
+        ),
+    ]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        &format!(
+            "\n[llm]\nbase_url = \"{}/v1\"\nmodel = \"gpt-test\"\napi_key = \"llm-key\"\n",
+            server.base_url
+        ),
+    );
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "summarize",
+        "RW-123",
+        "--redact",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("- Profile the payment call"));
+
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[1].starts_with("POST /v1/chat/completions HTTP/1.1"));
+    assert!(captured[1].contains("Bearer llm-key"));
+    let payload: Value = serde_json::from_str(request_body(&captured[1])).expect("json body");
+    assert_eq!(payload["model"], "gpt-test");
+    let prompt = payload["messages"][1]["content"]
+        .as_str()
+        .unwrap_or_default();
+    assert!(prompt.contains("# RW-123: Checkout times out"));
+    assert!(prompt.contains("Person 1"));
+    assert!(!prompt.contains("Jane"));
+    server.join();
+}
+
+#[test]
+fn summarize_requires_llm_config() {
+    let config = TempConfig::new("http://127.0.0.1:9");
+
+    let output = run_jit(["--config-file", config.path_str(), "summarize", "RW-123"]);
+
+    assert_eq!(output.status.code(), Some(78));
+    assert!(stderr(&output).contains("No LLM configured"));
+}
+
+#[test]
+fn create_draft_uses_llm_proposal_after_editing() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"choices":[{"message":{"content":"{\"summary\":\"Checkout times out\",\"description\":\"Users report timeouts on checkout.\",\"acceptance_criteria\":[\"Checkout completes within 2 seconds\"]}"}}]}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"accountId":"account-id-999","displayName":"Cesar Ferreira"}"#,
+        ),
+        ("HTTP/1.1 201 Created", r#"{"id":"10001","key":"RW-123"}"#),
+    ]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        &format!(
+            "\n[llm]\nbase_url = \"{}/v1\"\nmodel = \"gpt-test\"\n",
+            server.base_url
+        ),
+    );
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "create",
+            "--project",
+            "RW",
+            "--draft",
+            "users report timeouts on checkout",
+            "--json",
+        ],
+        &[("VISUAL", "sed -i -e 1s/Checkout/Edited:\\ checkout/")],
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value =
+        serde_json::from_str(stdout(&output).trim()).expect("create output should be json");
+    assert_eq!(payload["summary"], "Edited: checkout times out");
+
+    let captured = collect_requests(&requests, 3);
+    let prompt: Value = serde_json::from_str(request_body(&captured[0])).expect("json body");
+    assert!(
+        prompt["messages"][1]["content"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("users report timeouts on checkout")
+    );
+    let created: Value = serde_json::from_str(request_body(&captured[2])).expect("json body");
+    assert_eq!(created["fields"]["summary"], "Edited: checkout times out");
+    let description = created["fields"]["description"].to_string();
+    assert!(description.contains("Checkout completes within 2 seconds"));
+    assert!(!description.contains("Lines starting with"));
+    server.join();
+}
+
+#[test]
+fn create_story_template_writes_acceptance_criteria_field() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 201 Created",
+        r#"{"id":"10001","key":"RW-124"}"#,
+    )]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "\n[acceptance_criteria]\nfield = \"customfield_10060\"\nformat = \"text\"\n",
+    );
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "create",
+        "--project",
+        "RW",
+        "--summary",
+        "Export invoices",
+        "--template",
+        "story",
+        "--criterion",
+        "Exports a CSV",
+        "--criterion",
+        "Includes paid invoices only",
+        "--assignee",
+        "unassigned",
+        "--json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let captured = collect_requests(&requests, 1);
+    let created: Value = serde_json::from_str(request_body(&captured[0])).expect("json body");
+    assert_eq!(created["fields"]["issuetype"]["name"], "Story");
+    assert_eq!(
+        created["fields"]["customfield_10060"],
+        "- [ ] Exports a CSV\n- [ ] Includes paid invoices only"
+    );
+    assert!(
+        created["fields"]["description"]
+            .to_string()
+            .contains("As a <type of user>")
+    );
+    server.join();
+}
+
+#[test]
+fn create_bug_interactive_builds_a_structured_description() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 201 Created",
+        r#"{"id":"10001","key":"RW-125"}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit_with_stdin(
+        [
+            "--config-file",
+            config.path_str(),
+            "create",
+            "--project",
+            "RW",
+            "--type",
+            "Bug",
+            "-i",
+            "--assignee",
+            "unassigned",
+            "--json",
+        ],
+        "Login fails on Safari\n1. Open the login page\n2. Sign in\n\nThe dashboard opens\n\nA blank page\n\n\n",
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stderr(&output).contains("Steps to reproduce (end with an empty line):"));
+    let created: Value = serde_json::from_str(&stdout(&output)).expect("json output");
+    assert_eq!(created["ticket"], "RW-125");
+    let captured = collect_requests(&requests, 1);
+    let body: Value = serde_json::from_str(request_body(&captured[0])).expect("json body");
+    assert_eq!(body["fields"]["summary"], "Login fails on Safari");
+    let content = &body["fields"]["description"]["content"];
+    let headings: Vec<&str> = content
+        .as_array()
+        .expect("description content")
+        .iter()
+        .filter(|node| node["type"] == "heading")
+        .map(|node| node["content"][0]["text"].as_str().unwrap_or_default())
+        .collect();
+    assert_eq!(
+        headings,
+        ["Steps to reproduce", "Expected behavior", "Actual behavior"]
+    );
+    assert_eq!(content[1]["type"], "orderedList");
+    assert_eq!(
+        content[1]["content"][1]["content"][0]["content"][0]["text"],
+        "Sign in"
+    );
+    server.join();
+}
+
+#[test]
+fn create_capture_env_appends_os_version_and_commit() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 201 Created",
+        r#"{"id":"10001","key":"RW-126"}"#,
+    )]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "\n[create]\nversion_command = \"echo 2.4.1; echo build 99\"\n",
+    );
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "create",
+        "--project",
+        "RW",
+        "--type",
+        "Bug",
+        "--summary",
+        "Crash on export",
+        "--description",
+        "Export crashes.",
+        "--capture-env",
+        "--assignee",
+        "unassigned",
+        "--json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let captured = collect_requests(&requests, 1);
+    let body: Value = serde_json::from_str(request_body(&captured[0])).expect("json body");
+    let content = &body["fields"]["description"]["content"];
+    assert_eq!(content[0]["content"][0]["text"], "Export crashes.");
+    assert_eq!(content[1]["content"][0]["text"], "Captured environment");
+    let items: Vec<&str> = content[2]["content"]
+        .as_array()
+        .expect("environment list")
+        .iter()
+        .map(|item| {
+            item["content"][0]["content"][0]["text"]
+                .as_str()
+                .unwrap_or_default()
+        })
+        .collect();
+    assert!(items[0].starts_with("OS: "), "items: {:?}", items);
+    assert_eq!(items[1], "App version: 2.4.1");
+    server.join();
+}
+
+#[test]
+fn create_requires_summary_without_draft() {
+    let config = TempConfig::new("http://127.0.0.1:9");
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "create",
+        "--project",
+        "RW",
+    ]);
+
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--summary"));
+}
+
+#[test]
+fn comment_snippet_posts_rendered_template() {
+    let (server, requests) =
+        spawn_sequence_server(vec![("HTTP/1.1 201 Created", r#"{"id":"10500"}"#)]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "\n[snippets]\ndeployed = \"Deployed to {env} in build {build}\"\n",
+    );
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "comment",
+        "RW-123",
+        "--snippet",
+        "deployed",
+        "--var",
+        "env=staging",
+        "--var",
+        "build=1042",
+        "--output",
+        "json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value =
+        serde_json::from_str(stdout(&output).trim()).expect("comment output should be json");
+    assert_eq!(payload["comment"], "10500");
+    assert_eq!(payload["body"], "Deployed to staging in build 1042");
+
+    let captured = collect_requests(&requests, 1);
+    assert!(captured[0].starts_with("POST /rest/api/3/issue/RW-123/comment HTTP/1.1"));
+    assert!(request_body(&captured[0]).contains("Deployed to staging in build 1042"));
+    server.join();
+}
+
+#[test]
+fn process_commits_applies_smart_commit_directives() {
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 201 Created", r#"{"id":"10500"}"#),
+        ("HTTP/1.1 201 Created", r#"{"id":"20000"}"#),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let repo = PathBuf::from(config.data_dir()).join("repo");
+    fs::create_dir_all(&repo).expect("create repo dir");
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .expect("git should run");
+        assert!(status.status.success(), "git {:?} failed", args);
+    };
+    git(&["init", "--quiet"]);
+    git(&[
+        "commit",
+        "--quiet",
+        "--allow-empty",
+        "-m",
+        "RW-123 #comment Fixed the crash #time 2h",
+    ]);
+    git(&[
+        "commit",
+        "--quiet",
+        "--allow-empty",
+        "-m",
+        "Refactor RW-124 helpers",
+    ]);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "process-commits",
+        "HEAD",
+        "--repo",
+        repo.to_str().expect("utf-8 path"),
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("RW-123: comment Fixed the crash (applied)"));
+    assert!(stdout.contains("RW-123: time 2h (applied)"));
+    assert!(stdout.contains("RW-124: reference (skipped)"));
+
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[0].starts_with("POST /rest/api/3/issue/RW-123/comment HTTP/1.1"));
+    assert!(captured[1].starts_with("POST /rest/api/3/issue/RW-123/worklog HTTP/1.1"));
+    let worklog: Value = serde_json::from_str(request_body(&captured[1])).expect("json body");
+    assert_eq!(worklog["timeSpent"], "2h");
+    server.join();
+}
+
+#[test]
+fn verify_release_reports_tickets_and_commits_that_are_not_ready() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"Done","statusCategory":{"key":"done"}},"fixVersions":[{"name":"2.5.0"}]}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"2","key":"RW-2","fields":{"summary":"Add signup","status":{"name":"In Review","statusCategory":{"key":"indeterminate"}},"fixVersions":[]}}"#,
+        ),
+        (
+            "HTTP/1.1 404 Not Found",
+            r#"{"errorMessages":["Issue does not exist or you do not have permission to see it."]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let repo = PathBuf::from(config.data_dir()).join("repo");
+    fs::create_dir_all(&repo).expect("create repo dir");
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .expect("git should run");
+        assert!(status.status.success(), "git {:?} failed", args);
+    };
+    git(&["init", "--quiet"]);
+    git(&["commit", "--quiet", "--allow-empty", "-m", "Release 2.4.0"]);
+    git(&["tag", "v2.4.0"]);
+    for message in [
+        "RW-1 Fix login",
+        "RW-2: Add signup with UTF-8 names",
+        "Bump dependencies",
+        "RW-9 Tidy up",
+    ] {
+        git(&["commit", "--quiet", "--allow-empty", "-m", message]);
+    }
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "verify-release",
+        "v2.4.0..HEAD",
+        "--fix-version",
+        "2.5.0",
+        "--project",
+        "RW",
+        "--repo",
+        repo.to_str().expect("utf-8 path"),
+        "--output",
+        "json",
+    ]);
+
+    assert_eq!(output.status.code(), Some(1), "stderr: {}", stderr(&output));
+    let json: Value = serde_json::from_str(&stdout(&output)).expect("report json");
+    assert_eq!(json["commits"], 4);
+    assert_eq!(json["tickets"], serde_json::json!(["RW-1", "RW-2", "RW-9"]));
+    assert_eq!(json["passed"], false);
+    assert_eq!(json["not_done"][0]["ticket"], "RW-2");
+    assert_eq!(json["not_done"][0]["status"], "In Review");
+    assert_eq!(json["missing_fix_version"][0]["ticket"], "RW-2");
+    assert_eq!(json["not_done"].as_array().map(Vec::len), Some(1));
+    assert_eq!(json["not_found"], serde_json::json!(["RW-9"]));
+    assert_eq!(
+        json["unreferenced_commits"][0]["subject"],
+        "Bump dependencies"
+    );
+    assert!(stderr(&output).contains("4 problem(s) found in v2.4.0..HEAD"));
+
+    let requests = collect_requests(&requests, 3);
+    server.join();
+    assert!(
+        requests[0].starts_with("GET /rest/api/3/issue/RW-1?fields=fixVersions,status,summary ")
+    );
+    assert!(requests[2].starts_with("GET /rest/api/3/issue/RW-9?"));
+}
+
+#[test]
+fn verify_release_does_not_take_standards_for_tickets() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"1","key":"RW-1","fields":{"summary":"Store dates","status":{"name":"Done","statusCategory":{"key":"done"}},"fixVersions":[{"name":"2.5.0"}]}}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+    let repo = PathBuf::from(config.data_dir()).join("repo");
+    fs::create_dir_all(&repo).expect("create repo dir");
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .expect("git should run");
+        assert!(status.status.success(), "git {:?} failed", args);
+    };
+    git(&["init", "--quiet"]);
+    git(&["commit", "--quiet", "--allow-empty", "-m", "Release 2.4.0"]);
+    git(&["tag", "v2.4.0"]);
+    for message in ["RW-1 Store dates as ISO-8601", "Accept UTF-8 file names"] {
+        git(&["commit", "--quiet", "--allow-empty", "-m", message]);
+    }
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "verify-release",
+        "v2.4.0..HEAD",
+        "--repo",
+        repo.to_str().expect("utf-8 path"),
+        "--output",
+        "json",
+    ]);
+
+    assert_eq!(output.status.code(), Some(1), "stderr: {}", stderr(&output));
+    let json: Value = serde_json::from_str(&stdout(&output)).expect("report json");
+    assert_eq!(json["tickets"], serde_json::json!(["RW-1"]));
+    assert_eq!(json["not_found"], serde_json::json!([]));
+    assert_eq!(
+        json["unreferenced_commits"][0]["subject"],
+        "Accept UTF-8 file names"
+    );
+
+    let requests = collect_requests(&requests, 1);
+    server.join();
+    assert!(requests[0].starts_with("GET /rest/api/3/issue/RW-1?"));
+}
+
+#[test]
+fn train_reports_release_readiness_as_json() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"10001","key":"RW-1","fields":{"summary":"Ship it","status":{"name":"Done","statusCategory":{"key":"done"}},"components":[{"name":"Payments"}]}}]}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"detail":[{"pullRequests":[{"id":"1","name":"Ship it","status":"OPEN","url":"https://github.com/org/api/pull/1"}]}]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "train",
+        "--fix-version",
+        "2.5.0",
+        "--output",
+        "json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value =
+        serde_json::from_str(stdout(&output).trim()).expect("train output should be json");
+    assert_eq!(payload["ready"], false);
+    assert_eq!(payload["groups"][0]["name"], "Payments");
+    assert_eq!(payload["groups"][0]["tickets"][0]["ready"], false);
+
+    let captured = collect_requests(&requests, 2);
+    let search: Value = search_params(&captured[0]);
+    assert_eq!(search["jql"], "fixVersion = \"2.5.0\" ORDER BY key ASC");
+    server.join();
+}
+
+#[test]
+fn deployed_annotates_every_ticket_in_fix_version() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"10001","key":"RW-1","fields":{"summary":"One"}}]}"#,
+        ),
+        ("HTTP/1.1 201 Created", r#"{"id":"10500"}"#),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "\n[deploy]\nnote = \"{ticket} shipped to {env} in {version}\"\nenvironment_field = \"customfield_12000\"\nenvironment_field_type = \"multi-select\"\n",
+    );
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "deployed",
+        "--fix-version",
+        "2.5.0",
+        "--env",
+        "production",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("RW-1: deployed to production (annotated)"));
+
+    let captured = collect_requests(&requests, 3);
+    assert!(captured[1].starts_with("POST /rest/api/3/issue/RW-1/comment HTTP/1.1"));
+    assert!(request_body(&captured[1]).contains("RW-1 shipped to production in 2.5.0"));
+    assert!(captured[2].starts_with("PUT /rest/api/3/issue/RW-1 HTTP/1.1"));
+    let update: Value = serde_json::from_str(request_body(&captured[2])).expect("json body");
+    assert_eq!(
+        update["update"]["customfield_12000"][0]["add"]["value"],
+        "production"
+    );
+    server.join();
+}
+
+#[test]
+fn trigger_posts_issues_and_data_to_automation_webhook() {
+    let (server, requests) = spawn_sequence_server(vec![("HTTP/1.1 200 OK", "")]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        &format!(
+            "\n[webhooks.rebuild]\nurl = \"{}/automation/webhooks/abc\"\ntoken = \"hook-secret\"\n",
+            server.base_url
+        ),
+    );
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "trigger",
+        "rebuild",
+        "--ticket",
+        "RW-123",
+        "--data",
+        "env=staging",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Triggered rebuild for RW-123"));
+
+    let captured = collect_requests(&requests, 1);
+    assert!(captured[0].starts_with("POST /automation/webhooks/abc HTTP/1.1"));
+    assert!(
+        captured[0]
+            .to_ascii_lowercase()
+            .contains("x-automation-webhook-token: hook-secret")
+    );
+    assert!(!captured[0].contains("Basic "));
+    let payload: Value = serde_json::from_str(request_body(&captured[0])).expect("json body");
+    assert_eq!(payload["issues"][0], "RW-123");
+    assert_eq!(payload["data"]["env"], "staging");
+    server.join();
+}
+
+#[test]
+fn watch_runs_hook_when_field_changes_to_target_value() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Checkout fails","priority":{"name":"Medium"}}}]}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Checkout fails","priority":{"name":"Highest"}}}]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let data_dir = config.data_dir();
+    let fired = format!("{}/fired.txt", data_dir);
+    let mut contents = fs::read_to_string(&config.path).expect("temp config should be readable");
+    contents.push_str(&format!(
+        "\n[[watch]]\nname = \"urgent\"\nfield = \"priority\"\nequals = \"highest\"\nrun = 'echo \"$JIT_TICKET $JIT_PREVIOUS->$JIT_VALUE\" >> {}'\n",
+        fired
+    ));
+    fs::write(&config.path, contents).expect("temp config should be written");
+    let envs = [("JIT_DATA_DIR", data_dir.as_str())];
+
+    let baseline = run_jit_with_env(
+        ["--config-file", config.path_str(), "watch", "--once"],
+        &envs,
+    );
+    assert!(baseline.status.success(), "stderr: {}", stderr(&baseline));
+    assert!(fs::metadata(&fired).is_err());
+
+    let output = run_jit_with_env(
+        ["--config-file", config.path_str(), "watch", "--once"],
+        &envs,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("urgent: RW-1 priority is now Highest"));
+    assert_eq!(
+        fs::read_to_string(&fired).expect("hook should have run"),
+        "RW-1 Medium->Highest\n"
+    );
+
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[1].starts_with("GET /rest/api/3/search/jql?"));
+    assert!(
+        search_params(&captured[1])["jql"]
+            .as_str()
+            .is_some_and(|jql| jql.contains("assignee = currentUser()"))
+    );
+    server.join();
+}
+
+#[test]
+fn comment_reads_stdin_and_prints_comment_url() {
+    let (server, requests) =
+        spawn_sequence_server(vec![("HTTP/1.1 201 Created", r#"{"id":"10500"}"#)]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit_with_stdin(
+        ["--config-file", config.path_str(), "comment", "RW-123", "-"],
+        "Release notes:\n- faster sync\n- fewer retries\n",
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains(&format!(
+        "URL:      {}/browse/RW-123?focusedCommentId=10500",
+        server.base_url
+    )));
+
+    let captured = collect_requests(&requests, 1);
+    let payload: Value = serde_json::from_str(request_body(&captured[0])).expect("json body");
+    assert_eq!(payload["body"]["content"][1]["type"], "bulletList");
+    assert_eq!(
+        payload["body"]["content"][1]["content"][0]["content"][0]["content"][0]["text"],
+        "faster sync"
+    );
+    server.join();
+}
+
+#[test]
+fn list_filters_by_assignee_name_through_user_search() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"[{"accountId":"account-jane","displayName":"Jane Doe"}]"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-7","fields":{"summary":"Fix login","status":{"name":"In Progress"}}}]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "--assignee",
+        "@Jane Doe",
+        "--text",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("RW-7"));
+
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[0].starts_with("GET /rest/api/3/user/search?query=Jane+Doe HTTP/1.1"));
+    let search: Value = search_params(&captured[1]);
+    assert_eq!(
+        search["jql"],
+        "(sprint in openSprints()) AND assignee in (\"account-jane\") ORDER BY updated DESC"
+    );
+    server.join();
+}
+
+#[test]
+fn comments_lists_newest_comments_oldest_first_as_json() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"total":3,"comments":[
+            {"id":"3","author":{"displayName":"Linus"},"created":"2026-04-12T09:00:00.000+0000","body":{"type":"doc","version":1,"content":[{"type":"paragraph","content":[{"type":"text","text":"Newest"}]}]}},
+            {"id":"2","author":{"displayName":"Grace"},"created":"2026-04-10T09:00:00.000+0000","body":{"type":"doc","version":1,"content":[{"type":"paragraph","content":[{"type":"text","text":"Middle"}]}]}}
+        ]}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "comments",
+        "RW-123",
+        "--limit",
+        "2",
+        "--json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value =
+        serde_json::from_str(stdout(&output).trim()).expect("comments output should be json");
+    assert_eq!(payload["total"], 3);
+    assert_eq!(payload["comments"][0]["author"], "Grace");
+    assert_eq!(payload["comments"][1]["body"], "Newest");
+    assert_eq!(payload["comments"][1]["id"], "3");
+
+    let captured = collect_requests(&requests, 1);
+    assert!(captured[0].starts_with(
+        "GET /rest/api/3/issue/RW-123/comment?orderBy=-created&startAt=0&maxResults=2 HTTP/1.1"
+    ));
+    server.join();
+}
+
+#[test]
+fn team_reports_workload_for_named_roster() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"issues":[
+            {"id":"1","key":"RW-1","fields":{"summary":"","assignee":{"displayName":"Ada","accountId":"account-1"},"status":{"name":"In Review","statusCategory":{"key":"indeterminate"}}}},
+            {"id":"2","key":"RW-2","fields":{"summary":"","assignee":{"displayName":"Ada","accountId":"account-1"},"status":{"name":"To Do","statusCategory":{"key":"new"}}}}
+        ]}"#,
+    )]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "\n[teams.platform]\nmembers = [\"account-1\", \"account-2\"]\n",
+    );
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "team",
+        "platform",
+        "--output",
+        "json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value =
+        serde_json::from_str(stdout(&output).trim()).expect("team output should be json");
+    assert_eq!(payload["team"], "platform");
+    assert_eq!(payload["members"][0]["name"], "Ada");
+    assert_eq!(payload["members"][0]["in_progress"], 1);
+    assert_eq!(payload["members"][0]["total"], 2);
+    assert_eq!(payload["members"][1]["total"], 0);
+
+    let captured = collect_requests(&requests, 1);
+    let search: Value = search_params(&captured[0]);
+    assert_eq!(
+        search["jql"],
+        "assignee in (\"account-1\", \"account-2\") AND statusCategory != Done"
+    );
+    server.join();
+}
+
+#[test]
+fn move_transitions_ticket_to_partially_named_status() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"transitions":[{"id":"11","name":"Start review","to":{"name":"In Review"}},{"id":"31","name":"Done","to":{"name":"Done"}}]}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "move",
+        "RW-123",
+        "review",
+        "--output",
+        "text",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), "RW-123: moved to In Review");
+
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[0].starts_with("GET /rest/api/3/issue/RW-123/transitions HTTP/1.1"));
+    assert!(captured[1].starts_with("POST /rest/api/3/issue/RW-123/transitions HTTP/1.1"));
+    let payload: Value = serde_json::from_str(request_body(&captured[1])).expect("json body");
+    assert_eq!(payload["transition"]["id"], "11");
+    server.join();
+}
+
+#[test]
+fn deps_groups_external_links_by_project_and_assignee() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Checkout","issuelinks":[
+                {"type":{"name":"Blocks","inward":"is blocked by","outward":"blocks"},"inwardIssue":{"id":"9","key":"OPS-12","fields":{"summary":"Provision DB","status":{"name":"Open","statusCategory":{"key":"new"}}}}},
+                {"type":{"name":"Blocks","inward":"is blocked by","outward":"blocks"},"inwardIssue":{"id":"2","key":"RW-2","fields":{"summary":"Internal","status":{"name":"Open"}}}}
+            ]}}]}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"9","key":"OPS-12","fields":{"summary":"Provision DB","assignee":{"displayName":"Ops Olivia"}}}]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "deps",
+        "--project",
+        "RW",
+        "--external-only",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "OPS (1)\n  Ops Olivia\n    OPS-12 [Open] Provision DB\n      RW-1 is blocked by OPS-12\n"
+    );
+
+    let captured = collect_requests(&requests, 2);
+    let search: Value = search_params(&captured[1]);
+    assert_eq!(search["jql"], "key in (OPS-12)");
+    server.join();
+}
+
+#[test]
+fn assign_resolves_display_name_and_puts_assignee() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"[{"accountId":"account-jane","displayName":"Jane Doe"}]"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "assign",
+        "RW-123",
+        "Jane Doe",
+        "--output",
+        "text",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), "RW-123: assigned to Jane Doe");
+
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[0].starts_with("GET /rest/api/3/user/search?query=Jane+Doe HTTP/1.1"));
+    assert!(captured[1].starts_with("PUT /rest/api/3/issue/RW-123/assignee HTTP/1.1"));
+    let payload: Value = serde_json::from_str(request_body(&captured[1])).expect("json body");
+    assert_eq!(payload["accountId"], "account-jane");
+    server.join();
+}
+
+#[test]
+fn assign_unassign_clears_assignee_without_lookup() {
+    let (server, requests) = spawn_sequence_server(vec![("HTTP/1.1 204 No Content", "")]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "assign",
+        "RW-123",
+        "--unassign",
+        "--output",
+        "json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let captured = collect_requests(&requests, 1);
+    assert!(captured[0].starts_with("PUT /rest/api/3/issue/RW-123/assignee HTTP/1.1"));
+    let payload: Value = serde_json::from_str(request_body(&captured[0])).expect("json body");
+    assert_eq!(payload["accountId"], Value::Null);
+    server.join();
+}
+
+#[test]
+fn search_follows_pages_and_renders_the_ticket_list() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"First","status":{"name":"In Review"}}}],"nextPageToken":"page-2"}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"2","key":"RW-2","fields":{"summary":"Second","status":{"name":"In Review"}}}],"isLast":true}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "search",
+        "project = RW AND status = \"In Review\" ORDER BY updated DESC",
+        "--mine",
+        "--output",
+        "json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let issues: Value = serde_json::from_str(&stdout(&output)).expect("json output");
+    assert_eq!(
+        issues,
+        serde_json::json!([
+            { "ticket": "RW-1", "summary": "First", "status": "In Review" },
+            { "ticket": "RW-2", "summary": "Second", "status": "In Review" },
+        ])
+    );
+
+    let captured = collect_requests(&requests, 2);
+    let first: Value = search_params(&captured[0]);
+    assert_eq!(
+        first["jql"],
+        "(project = RW AND status = \"In Review\") AND assignee = currentUser() ORDER BY updated DESC"
+    );
+    let second: Value = search_params(&captured[1]);
+    assert_eq!(second["nextPageToken"], "page-2");
+    server.join();
+}
+
+#[test]
+fn limit_zero_fetches_every_page_and_prints_rows_per_page() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"First","status":{"name":"To Do"}}}],"nextPageToken":"page-2"}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"2","key":"RW-2","fields":{"summary":"Second","status":{"name":"Done"}}}]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "--output",
+        "ndjson",
+        "--limit",
+        "0",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        concat!(
+            r#"{"status":"To Do","summary":"First","ticket":"RW-1"}"#,
+            "\n",
+            r#"{"status":"Done","summary":"Second","ticket":"RW-2"}"#,
+            "\n",
+        )
+    );
+
+    let captured = collect_requests(&requests, 2);
+    let first: Value = search_params(&captured[0]);
+    assert_eq!(first["maxResults"], 100);
+    let second: Value = search_params(&captured[1]);
+    assert_eq!(second["nextPageToken"], "page-2");
+    server.join();
+}
+
+#[test]
+fn limit_zero_streams_one_table_across_pages() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Login","status":{"name":"Done","statusCategory":{"key":"done"}},"customfield_10020":[{"name":"Sprint 42","state":"active"}],"customfield_10016":3.0}}],"nextPageToken":"page-2"}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"2","key":"RW-2","fields":{"summary":"Signup flow","status":{"name":"To Do","statusCategory":{"key":"new"}},"customfield_10016":5.0}}],"isLast":true}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit(["--config-file", config.path_str(), "--limit", "0"]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "Current Sprint: Sprint 42\n\n\
+         ┌──────┬─────────┬────────┬────────┐\n\
+         │ Key  │ Summary │ Status │ Points │\n\
+         ├──────┼─────────┼────────┼────────┤\n\
+         │ RW-1 │ Login   │ Done   │ 3      │\n\
+         ├──────┼─────────┼────────┼────────┤\n\
+         │ RW-2 │ Signup… │ To Do  │ 5      │\n\
+         └──────┴─────────┴────────┴────────┘\n\
+         \n\
+         3 of 8 points done\n"
+    );
+    let captured = collect_requests(&requests, 2);
+    let second: Value = search_params(&captured[1]);
+    assert_eq!(second["nextPageToken"], "page-2");
+    server.join();
+}
+
+#[test]
+fn search_ndjson_prints_one_object_per_issue_across_pages() {
+    let (server, _requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"First","status":{"name":"To Do"},"assignee":{"displayName":"Ana Lima"}}}],"nextPageToken":"page-2"}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"2","key":"RW-2","fields":{"summary":"Second","status":{"name":"Done"}}}],"isLast":true}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "search",
+        "project = RW",
+        "--limit",
+        "0",
+        "--ndjson",
+        "--columns",
+        "key,assignee",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        concat!(
+            r#"{"assignee":"Ana Lima","ticket":"RW-1"}"#,
+            "\n",
+            r#"{"assignee":"","ticket":"RW-2"}"#,
+            "\n",
+        )
+    );
+    server.join();
+}
+
+#[test]
+fn markdown_renders_the_ticket_with_its_description() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"In Review"},"issuetype":{"name":"Bug"},"assignee":{"displayName":"Ana Lima"},"description":{"type":"doc","version":1,"content":[{"type":"heading","attrs":{"level":3},"content":[{"type":"text","text":"Steps"}]},{"type":"orderedList","content":[{"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Open "},{"type":"text","text":"login","marks":[{"type":"strong"}]}]}]}]}]}}}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit(["--config-file", config.path_str(), "RW-1", "--markdown"]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            concat!(
+                "# [RW-1]({}/browse/RW-1): Fix login\n",
+                "\n",
+                "| Field | Value |\n",
+                "| --- | --- |\n",
+                "| Type | Bug |\n",
+                "| Status | In Review |\n",
+                "| Assignee | Ana Lima |\n",
+                "\n",
+                "## Description\n",
+                "\n",
+                "### Steps\n",
+                "\n",
+                "1. Open **login**\n",
+            ),
+            server.base_url
+        )
+    );
+    let captured = collect_requests(&requests, 1);
+    assert!(
+        captured[0].contains("description"),
+        "request: {}",
+        captured[0]
+    );
+    server.join();
+}
+
+#[test]
+fn search_table_has_no_sprint_heading() {
+    let (server, _requests) =
+        spawn_sequence_server(vec![("HTTP/1.1 200 OK", r#"{"issues":[],"isLast":true}"#)]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit(["--config-file", config.path_str(), "search", "project = RW"]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output), "No issues found.\n");
+    server.join();
+}
+
+#[test]
+fn search_csv_exports_the_requested_columns_quoted() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Fix \"login\", again","status":{"name":"To Do"},"assignee":{"displayName":"Ana Lima"}}},{"id":"2","key":"RW-2","fields":{"summary":"Docs","status":{"name":"Done"}}}],"isLast":true}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "search",
+        "project = RW",
+        "--csv",
+        "--columns",
+        "key,summary,assignee",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "Key,Summary,Assignee\nRW-1,\"Fix \"\"login\"\", again\",Ana Lima\nRW-2,Docs,\n"
+    );
+    let requests = collect_requests(&requests, 1);
+    server.join();
+    let body: Value = search_params(&requests[0]);
+    assert_eq!(
+        body["fields"],
+        serde_json::json!(["summary", "status", "assignee"])
+    );
+}
+
+#[test]
+fn format_prints_each_ticket_through_inline_and_named_templates() {
+    let page = r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Fix <login> & logout","status":{"name":"To Do"},"assignee":{"displayName":"Ana Lima"}}},{"id":"2","key":"RW-2","fields":{"summary":"Docs","status":{"name":"Done"}}}],"isLast":true}"#;
+    let (server, requests) =
+        spawn_sequence_server(vec![("HTTP/1.1 200 OK", page), ("HTTP/1.1 200 OK", page)]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "\n[templates]\nwho = \"{{key}} {{#if assignee}}@{{assignee}}{{else}}unassigned{{/if}}\"\n",
+    );
+
+    let inline = run_jit([
+        "--config-file",
+        config.path_str(),
+        "search",
+        "project = RW",
+        "--format",
+        "{{key}} [{{status}}] {{summary}}",
+    ]);
+    let named = run_jit([
+        "--config-file",
+        config.path_str(),
+        "search",
+        "project = RW",
+        "--format",
+        "who",
+    ]);
+
+    assert!(inline.status.success(), "stderr: {}", stderr(&inline));
+    assert_eq!(
+        stdout(&inline),
+        "RW-1 [To Do] Fix <login> & logout\nRW-2 [Done] Docs\n"
+    );
+    assert!(named.status.success(), "stderr: {}", stderr(&named));
+    assert_eq!(stdout(&named), "RW-1 @Ana Lima\nRW-2 unassigned\n");
+    let requests = collect_requests(&requests, 2);
+    server.join();
+    let body: Value = search_params(&requests[0]);
+    assert!(
+        body["fields"]
+            .as_array()
+            .expect("fields should be a list")
+            .contains(&Value::from("assignee"))
+    );
+}
+
+#[test]
+fn format_rejects_unknown_template_names() {
+    let config = TempConfig::new("http://127.0.0.1:9");
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "search",
+        "project = RW",
+        "--format",
+        "short",
+    ]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("No template named 'short' in the [templates] config section"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn count_prints_only_the_number_of_matching_tickets() {
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 200 OK", r#"{"count":3}"#),
+        ("HTTP/1.1 200 OK", r#"{"count":12}"#),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let search = run_jit([
+        "--config-file",
+        config.path_str(),
+        "search",
+        "project = RW AND priority = Blocker",
+        "--count",
+    ]);
+    let mine = run_jit(["--config-file", config.path_str(), "--count", "--json"]);
+
+    assert!(search.status.success(), "stderr: {}", stderr(&search));
+    assert_eq!(stdout(&search), "3\n");
+    assert!(mine.status.success(), "stderr: {}", stderr(&mine));
+    let json: Value = serde_json::from_str(&stdout(&mine)).expect("count json");
+    assert_eq!(json, serde_json::json!({"count": 12}));
+    let requests = collect_requests(&requests, 2);
+    server.join();
+    assert!(requests[0].starts_with("POST /rest/api/3/search/approximate-count "));
+    let body: Value = serde_json::from_str(request_body(&requests[0])).expect("count body");
+    assert_eq!(body["jql"], "project = RW AND priority = Blocker");
+    let body: Value = serde_json::from_str(request_body(&requests[1])).expect("count body");
+    assert_eq!(
+        body["jql"],
+        "assignee = currentUser() AND sprint in openSprints() ORDER BY updated DESC"
+    );
+}
+
+#[test]
+fn site_fields_are_discovered_once_per_site_and_cached() {
+    let search = r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"To Do"},"customfield_10104":[{"id":7,"name":"Sprint 7","state":"active"}],"customfield_10028":5.0}}],"isLast":true}"#;
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"[{"id":"summary","name":"Summary","custom":false},{"id":"customfield_10104","name":"Sprint","custom":true,"schema":{"type":"array","items":"json","custom":"com.pyxis.greenhopper.jira:gh-sprint"}},{"id":"customfield_10028","name":"Story Points","custom":true,"schema":{"type":"number"}}]"#,
+        ),
+        ("HTTP/1.1 200 OK", search),
+        ("HTTP/1.1 200 OK", search),
+    ]);
+    let config = TempConfig::empty();
+    fs::write(
+        &config.path,
+        format!(
+            "[jira]\nbase_url = \"{}\"\napi_token = \"token-123\"\nuser_email = \"user@example.com\"\n",
+            server.base_url
+        ),
+    )
+    .expect("config should be written");
+    let data_dir = config.data_dir();
+    let env = [("JIT_DATA_DIR", data_dir.as_str())];
+
+    let first = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "--json",
+            "--columns",
+            "key,sprint,points",
+        ],
+        &env,
+    );
+    let second = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "--stats",
+            "--json",
+            "--columns",
+            "key,sprint,points",
+        ],
+        &env,
+    );
+
+    assert!(first.status.success(), "stderr: {}", stderr(&first));
+    let json: Value = serde_json::from_str(&stdout(&first)).expect("tickets json");
+    assert_eq!(json[0]["sprint"], "Sprint 7");
+    assert_eq!(json[0]["points"], "5");
+    assert_eq!(stdout(&second), stdout(&first));
+    assert!(
+        stderr(&second).contains("Cache hits: 1"),
+        "stderr: {}",
+        stderr(&second)
+    );
+    let requests = collect_requests(&requests, 3);
+    server.join();
+    assert!(requests[0].starts_with("GET /rest/api/3/field HTTP/1.1"));
+    for request in &requests[1..] {
+        let body: Value = search_params(request);
+        assert_eq!(
+            body["fields"],
+            serde_json::json!([
+                "summary",
+                "status",
+                "customfield_10104",
+                "customfield_10028"
+            ])
+        );
+    }
+    let cache = fs::read_to_string(config.dir.join("data").join("fields.json")).expect("cache");
+    assert!(
+        cache.contains("customfield_10104") && cache.contains("customfield_10028"),
+        "cache: {}",
+        cache
+    );
+}
+
+#[test]
+fn gate_fails_when_more_issues_match_than_allowed() {
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 200 OK", r#"{"count":0}"#),
+        ("HTTP/1.1 200 OK", r#"{"count":3}"#),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"In Progress"}}},{"id":"2","key":"RW-2","fields":{"summary":"Ship export","status":{"name":"To Do"}}}],"isLast":true}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let jql = "fixVersion = 2.5 AND status != Done";
+
+    let passing = run_jit(["--config-file", config.path_str(), "gate", "--jql", jql]);
+    let failing = run_jit([
+        "--config-file",
+        config.path_str(),
+        "gate",
+        "--jql",
+        jql,
+        "--max",
+        "1",
+        "--show",
+        "2",
+    ]);
+
+    assert!(passing.status.success(), "stderr: {}", stderr(&passing));
+    assert_eq!(
+        stdout(&passing),
+        "Gate passed: 0 issue(s) match, 0 allowed.\n"
+    );
+    assert_eq!(failing.status.code(), Some(1));
+    assert_eq!(
+        stdout(&failing),
+        "Gate failed: 3 issue(s) match, 1 allowed.\n  RW-1  Fix login  (In Progress)\n  RW-2  Ship export  (To Do)\n  ... and 1 more\n"
+    );
+    assert!(
+        stderr(&failing).contains("3 issue(s) match the gate query, more than the 1 allowed"),
+        "stderr: {}",
+        stderr(&failing)
+    );
+    let requests = collect_requests(&requests, 3);
+    server.join();
+    assert!(requests[1].starts_with("POST /rest/api/3/search/approximate-count "));
+    let body: Value = search_params(&requests[2]);
+    assert_eq!(body["jql"], jql);
+    assert_eq!(body["maxResults"], 2);
+}
+
+#[test]
+fn fields_lists_site_fields_filtered_by_name_or_custom() {
+    let fields = r#"[{"id":"summary","name":"Summary","custom":false,"schema":{"type":"string","system":"summary"}},{"id":"customfield_10016","name":"Story point estimate","custom":true,"schema":{"type":"number","custom":"com.atlassian.jira.plugin.system.customfieldtypes:float","customId":10016}},{"id":"customfield_10020","name":"Sprint","custom":true,"schema":{"type":"array","items":"json","custom":"com.pyxis.greenhopper.jira:gh-sprint","customId":10020}},{"id":"issuekey","name":"Key","custom":false}]"#;
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 200 OK", fields),
+        ("HTTP/1.1 200 OK", fields),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let matching = run_jit(["--config-file", config.path_str(), "fields", "POINT"]);
+    let custom = run_jit([
+        "--config-file",
+        config.path_str(),
+        "fields",
+        "--custom",
+        "--output",
+        "json",
+    ]);
+
+    assert!(matching.status.success(), "stderr: {}", stderr(&matching));
+    assert_eq!(
+        stdout(&matching),
+        "customfield_10016  Story point estimate  (custom, number)\n"
+    );
+    assert!(custom.status.success(), "stderr: {}", stderr(&custom));
+    let json: Value = serde_json::from_str(&stdout(&custom)).expect("fields json");
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {"id": "customfield_10020", "name": "Sprint", "custom": true, "type": "array<json>"},
+            {"id": "customfield_10016", "name": "Story point estimate", "custom": true, "type": "number"},
+        ])
+    );
+    let requests = collect_requests(&requests, 2);
+    server.join();
+    assert!(requests[0].starts_with("GET /rest/api/3/field HTTP/1.1"));
+}
+
+#[test]
+fn fields_are_requested_and_shown_for_a_ticket_and_a_search() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"To Do"},"labels":["auth","web"],"components":[{"name":"Web"}],"customfield_10016":5.0}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"To Do"},"components":[{"name":"Web"},{"name":"API"}],"customfield_10016":5.0}}],"isLast":true}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let ticket = run_jit([
+        "--config-file",
+        config.path_str(),
+        "RW-1",
+        "--fields",
+        "labels,components,customfield_10016",
+        "--json",
+    ]);
+    let search = run_jit([
+        "--config-file",
+        config.path_str(),
+        "search",
+        "project = RW",
+        "--fields",
+        "components,customfield_10016",
+        "--csv",
+    ]);
+
+    assert!(ticket.status.success(), "stderr: {}", stderr(&ticket));
+    let json: Value = serde_json::from_str(&stdout(&ticket)).expect("ticket json");
+    assert_eq!(
+        json["fields"],
+        serde_json::json!({"labels": "auth, web", "components": "Web", "customfield_10016": "5.0"})
+    );
+    assert!(search.status.success(), "stderr: {}", stderr(&search));
+    assert_eq!(
+        stdout(&search),
+        "Key,Summary,Status,components,customfield_10016\nRW-1,Fix login,To Do,\"Web, API\",5.0\n"
+    );
+    let requests = collect_requests(&requests, 2);
+    server.join();
+    let request_line = requests[0].lines().next().unwrap_or_default();
+    assert!(
+        request_line.contains("components")
+            && request_line.contains("customfield_10016")
+            && request_line.contains("labels"),
+        "request: {}",
+        request_line
+    );
+    let body: Value = search_params(&requests[1]);
+    assert_eq!(
+        body["fields"],
+        serde_json::json!(["summary", "status", "components", "customfield_10016"])
+    );
+}
+
+#[test]
+fn export_resumes_from_checkpoint_and_writes_json_array() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"2","key":"OLD-2","fields":{"summary":"Second","attachment":[{"id":"7","filename":"log.txt","size":12,"mimeType":"text/plain","content":"https://example.test/att/7"}]}}]}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"comments":[{"id":"c1","body":"Hi"}],"total":1}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let out = config.dir.join("export.json");
+    fs::write(
+        config.dir.join("export.json.partial"),
+        "{\"key\":\"OLD-1\",\"issue\":{\"key\":\"OLD-1\"},\"comments\":[]}\n",
+    )
+    .expect("write partial export");
+    fs::write(
+        config.dir.join("export.json.checkpoint"),
+        r#"{"jql":"project = OLD","exported":1,"next_page_token":"page-2"}"#,
+    )
+    .expect("write checkpoint");
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "export",
+        "--jql",
+        "project = OLD",
+        "--out",
+        out.to_str().expect("utf-8 path"),
+        "--with-comments",
+        "--with-attachments-manifest",
+        "--output",
+        "text",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stderr(&output).contains("Resuming export after 1 issue(s)"));
+    assert!(stdout(&output).starts_with("Exported 2 issue(s) to "));
+
+    let captured = collect_requests(&requests, 2);
+    let search: Value = search_params(&captured[0]);
+    assert_eq!(search["nextPageToken"], "page-2");
+    assert!(captured[1].starts_with("GET /rest/api/3/issue/OLD-2/comment?orderBy=created"));
+
+    let exported: Value =
+        serde_json::from_str(&fs::read_to_string(&out).expect("read export")).expect("json");
+    assert_eq!(exported[0]["key"], "OLD-1");
+    assert_eq!(exported[1]["comments"][0]["id"], "c1");
+    assert_eq!(exported[1]["attachments"][0]["filename"], "log.txt");
+    assert_eq!(
+        exported[1]["attachments"][0]["url"],
+        "https://example.test/att/7"
+    );
+    assert!(!config.dir.join("export.json.checkpoint").exists());
+    assert!(!config.dir.join("export.json.partial").exists());
+    server.join();
+}
+
+#[test]
+fn open_print_emits_browse_url_without_calling_jira() {
+    let config = TempConfig::new("https://example.atlassian.net");
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "open",
+        "https://example.atlassian.net/browse/RW-1931",
+        "--print",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "https://example.atlassian.net/browse/RW-1931\n"
+    );
+}
+
+#[test]
+fn open_detects_ticket_from_current_branch() {
+    let config = TempConfig::new("https://example.atlassian.net");
+    let repo = config.dir.join("repo");
+    let init = Command::new("git")
+        .args(["init", "-q", "-b", "rw-42-fix-login"])
+        .arg(&repo)
+        .output()
+        .expect("git should run");
+    assert!(init.status.success(), "git init failed");
+    let git_dir = repo.join(".git");
+
+    let output = run_jit_with_env(
+        ["--config-file", config.path_str(), "open", "--print"],
+        &[("GIT_DIR", git_dir.to_str().expect("utf-8 path"))],
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "https://example.atlassian.net/browse/RW-42\n"
+    );
+}
+
+#[test]
+fn branches_stale_deletes_branches_of_done_tickets() {
+    let done = r#"{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"Done","statusCategory":{"key":"done"}}}}"#;
+    let open = r#"{"id":"2","key":"RW-2","fields":{"summary":"Add signup","status":{"name":"In Progress","statusCategory":{"key":"indeterminate"}}}}"#;
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 200 OK", done),
+        ("HTTP/1.1 200 OK", open),
+        ("HTTP/1.1 200 OK", done),
+        ("HTTP/1.1 200 OK", open),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let repo = config.dir.join("repo");
+    let git = |args: &[&str]| {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .expect("git should run");
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    fs::create_dir_all(&repo).expect("create repo dir");
+    git(&["init", "-q", "-b", "main"]);
+    git(&["commit", "-q", "--allow-empty", "-m", "Initial"]);
+    git(&["branch", "rw-1-fix-login"]);
+    git(&["branch", "rw-2-add-signup"]);
+    git(&["branch", "spike"]);
+    let git_dir = repo.join(".git");
+    let env = [("GIT_DIR", git_dir.to_str().expect("utf-8 path"))];
+
+    let all = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "branches",
+            "--output",
+            "json",
+        ],
+        &env,
+    );
+    let stale = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "branches",
+            "--stale",
+            "--yes",
+            "--output",
+            "json",
+        ],
+        &env,
+    );
+
+    assert!(all.status.success(), "stderr: {}", stderr(&all));
+    let json: Value = serde_json::from_str(&stdout(&all)).expect("branches json");
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {"branch": "rw-1-fix-login", "ticket": "RW-1", "status": "Done", "done": true, "deleted": false},
+            {"branch": "rw-2-add-signup", "ticket": "RW-2", "status": "In Progress", "done": false, "deleted": false},
+        ])
+    );
+    assert!(stale.status.success(), "stderr: {}", stderr(&stale));
+    let json: Value = serde_json::from_str(&stdout(&stale)).expect("stale json");
+    assert_eq!(json[0]["branch"], "rw-1-fix-login");
+    assert_eq!(json[0]["deleted"], true);
+    assert_eq!(json.as_array().map(Vec::len), Some(1));
+    assert_eq!(
+        git(&["for-each-ref", "--format=%(refname:short)", "refs/heads"]),
+        "main\nrw-2-add-signup\nspike\n"
+    );
+    let requests = collect_requests(&requests, 4);
+    server.join();
+    assert!(requests[0].starts_with("GET /rest/api/3/issue/RW-1?fields=status,summary "));
+}
+
+#[test]
+fn worktree_checks_out_ticket_branch_and_prunes_done_tickets() {
+    let open = r#"{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"In Progress","statusCategory":{"key":"indeterminate"}}}}"#;
+    let done = r#"{"id":"1","key":"RW-1","fields":{"summary":"Fix login","status":{"name":"Done","statusCategory":{"key":"done"}}}}"#;
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 200 OK", open),
+        ("HTTP/1.1 200 OK", open),
+        ("HTTP/1.1 200 OK", done),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let repo = config.dir.join("repo");
+    let git = |args: &[&str]| {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .expect("git should run");
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    fs::create_dir_all(&repo).expect("create repo dir");
+    git(&["init", "-q", "-b", "main"]);
+    git(&["commit", "-q", "--allow-empty", "-m", "Initial"]);
+    let run_in_repo = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_jit"))
+            .current_dir(&repo)
+            .args(["--config-file", config.path_str()])
+            .args(args)
+            .env("NO_COLOR", "1")
+            .env("JIT_DATA_DIR", shared_data_dir())
+            .output()
+            .expect("jit command should run")
+    };
+
+    let added = run_in_repo(&["worktree", "RW-1", "--output", "json"]);
+    let again = run_in_repo(&["worktree", "RW-1", "--output", "json"]);
+    let pruned = run_in_repo(&["worktree", "--prune", "--yes", "--output", "json"]);
+
+    let worktree = config.dir.join("repo-rw-1");
+    assert!(added.status.success(), "stderr: {}", stderr(&added));
+    let json: Value = serde_json::from_str(&stdout(&added)).expect("worktree json");
+    assert_eq!(json["branch"], "rw-1-fix-login");
+    assert_eq!(json["added"], true);
+    assert_eq!(json["created_branch"], true);
+    assert_eq!(json["path"], worktree.to_str().expect("utf-8 path"));
+    assert!(again.status.success(), "stderr: {}", stderr(&again));
+    let json: Value = serde_json::from_str(&stdout(&again)).expect("existing worktree json");
+    assert_eq!(json["added"], false);
+    assert!(pruned.status.success(), "stderr: {}", stderr(&pruned));
+    let json: Value = serde_json::from_str(&stdout(&pruned)).expect("prune json");
+    assert_eq!(json[0]["ticket"], "RW-1");
+    assert_eq!(json[0]["removed"], true);
+    assert!(!worktree.exists());
+    assert!(git(&["branch", "--list", "rw-1-fix-login"]).contains("rw-1-fix-login"));
+    let requests = collect_requests(&requests, 3);
+    server.join();
+    assert!(requests[2].starts_with("GET /rest/api/3/issue/RW-1?fields=status,summary "));
+}
+
+#[test]
+fn export_downloads_attachments_with_checksum_manifest() {
+    let (files, file_requests) = spawn_sequence_server(vec![("HTTP/1.1 200 OK", "hello")]);
+    let search_body: &'static str = Box::leak(
+        format!(
+            r#"{{"issues":[{{"id":"1","key":"OLD-1","fields":{{"summary":"First","attachment":[{{"id":"7","filename":"log.txt","size":5,"content":"{}/attachment/7"}}]}}}}]}}"#,
+            files.base_url
+        )
+        .into_boxed_str(),
+    );
+    let (server, _requests) = spawn_sequence_server(vec![("HTTP/1.1 200 OK", search_body)]);
+    let config = TempConfig::new(&server.base_url);
+    let out = config.dir.join("export.ndjson");
+    let archive = config.dir.join("archive");
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "export",
+        "--jql",
+        "project = OLD",
+        "--out",
+        out.to_str().expect("utf-8 path"),
+        "--format",
+        "ndjson",
+        "--download-attachments",
+        archive.to_str().expect("utf-8 path"),
+        "--output",
+        "json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value = serde_json::from_str(&stdout(&output)).expect("json output");
+    assert_eq!(payload["attachments"]["downloaded"], 1);
+
+    let captured = collect_requests(&file_requests, 1);
+    assert!(captured[0].starts_with("GET /attachment/7 HTTP/1.1"));
+    assert_eq!(
+        fs::read_to_string(archive.join("OLD-1").join("7-log.txt")).expect("attachment"),
+        "hello"
+    );
+    let manifest: Value =
+        serde_json::from_str(&fs::read_to_string(archive.join("manifest.json")).expect("manifest"))
+            .expect("manifest json");
+    assert_eq!(
+        manifest["7"]["sha256"],
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    );
+    assert_eq!(fs::read_to_string(&out).expect("export").lines().count(), 1);
+    files.join();
+    server.join();
+}
+
+#[test]
+fn branch_creates_and_checks_out_ticket_branch_from_template() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash","issuetype":{"name":"Bug"}}}"#,
+    )]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "[git]\nbranch_template = \"{type}/{key}-{summary}\"\n",
+    );
+    let repo = config.dir.join("repo");
+    let init = Command::new("git")
+        .args(["init", "-q", "-b", "main"])
+        .arg(&repo)
+        .output()
+        .expect("git should run");
+    assert!(init.status.success(), "git init failed");
+    let git_dir = repo.join(".git");
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "branch",
+            "RW-1931",
+            "--output",
+            "text",
+        ],
+        &[
+            ("GIT_DIR", git_dir.to_str().expect("utf-8 path")),
+            ("GIT_WORK_TREE", repo.to_str().expect("utf-8 path")),
+        ],
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), "bug/rw-1931-fix-login-crash");
+    let head = fs::read_to_string(git_dir.join("HEAD")).expect("read HEAD");
+    assert_eq!(head.trim(), "ref: refs/heads/bug/rw-1931-fix-login-crash");
+    let captured = collect_requests(&requests, 1);
+    assert!(
+        captured[0].starts_with("GET /rest/api/3/issue/RW-1931?fields=issuetype,summary HTTP/1.1")
+    );
+    server.join();
+}
+
+#[test]
+fn plan_applies_moves_and_estimates_in_a_batch() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"values":[{"id":43,"name":"Sprint 43","startDate":"2026-11-02T09:00:00.000Z"},{"id":44,"name":"Sprint 44"}],"isLast":true}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"2","key":"RW-2","fields":{"summary":"Already planned","customfield_10016":3}}],"total":1}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"Backlog item"}}],"total":1}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit_with_stdin(
+        ["--config-file", config.path_str(), "plan", "--board", "12"],
+        "m 1\ne 1 5\na\ny\n",
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Sprint 43 (1 issues, 3 pts)"), "{}", stdout);
+    assert!(stdout.contains("RW-1 -> Sprint 43"), "{}", stdout);
+    assert!(stdout.contains("RW-1 estimate -> 5"), "{}", stdout);
+
+    let captured = collect_requests(&requests, 5);
+    assert!(captured[0].starts_with("GET /rest/agile/1.0/board/12/sprint?state=future"));
+    assert!(captured[1].starts_with("GET /rest/agile/1.0/sprint/43/issue?fields=summary"));
+    assert!(captured[2].starts_with("GET /rest/agile/1.0/board/12/backlog?fields=summary"));
+    assert!(captured[3].starts_with("POST /rest/agile/1.0/sprint/43/issue HTTP/1.1"));
+    let moved: Value = serde_json::from_str(request_body(&captured[3])).expect("json body");
+    assert_eq!(moved["issues"], serde_json::json!(["RW-1"]));
+    assert!(captured[4].starts_with("PUT /rest/api/3/issue/RW-1 HTTP/1.1"));
+    let estimate: Value = serde_json::from_str(request_body(&captured[4])).expect("json body");
+    assert_eq!(estimate["fields"]["customfield_10016"], 5.0);
+    server.join();
+}
+
+#[test]
+fn move_takes_ticket_from_current_branch_when_only_status_given() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"transitions":[{"id":"11","name":"Start review","to":{"name":"In Review"}}]}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let repo = config.dir.join("repo");
+    let init = Command::new("git")
+        .args(["init", "-q", "-b", "feature/RW-1931-foo"])
+        .arg(&repo)
+        .output()
+        .expect("git should run");
+    assert!(init.status.success(), "git init failed");
+    let git_dir = repo.join(".git");
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "move",
+            "In Review",
+            "--output",
+            "text",
+        ],
+        &[("GIT_DIR", git_dir.to_str().expect("utf-8 path"))],
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), "RW-1931: moved to In Review");
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[0].starts_with("GET /rest/api/3/issue/RW-1931/transitions HTTP/1.1"));
+    server.join();
+}
+
+#[test]
+fn quick_creates_assigned_issue_in_active_sprint_of_default_project() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"accountId":"account-id-999","displayName":"Cesar Ferreira"}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"values":[{"id":7,"name":"RW board"}],"isLast":true,"maxResults":50,"startAt":0}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"values":[{"id":300,"name":"Sprint 9","startDate":"2026-04-01T09:00:00+00:00"}],"isLast":true,"maxResults":50,"startAt":0}"#,
+        ),
+        ("HTTP/1.1 201 Created", r#"{"id":"10001","key":"RW-124"}"#),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::with_extra(&server.base_url, "project = \"RW\"\n");
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "quick",
+        "Fix cache invalidation bug",
+        "--type",
+        "Bug",
+        "--output",
+        "json",
+    ]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value = serde_json::from_str(stdout(&output).trim()).expect("json output");
+    assert_eq!(payload["ticket"], "RW-124");
+    assert_eq!(payload["sprint"], "Sprint 9");
+    assert_eq!(payload["assignee"], "Cesar Ferreira");
+
+    let captured = collect_requests(&requests, 5);
+    assert!(captured[1].starts_with("GET /rest/agile/1.0/board?projectKeyOrId=RW&type=scrum"));
+    let created: Value = serde_json::from_str(request_body(&captured[3])).expect("json body");
+    assert_eq!(created["fields"]["project"]["key"], "RW");
+    assert_eq!(created["fields"]["issuetype"]["name"], "Bug");
+    assert_eq!(created["fields"]["assignee"]["id"], "account-id-999");
+    assert!(captured[4].starts_with("POST /rest/agile/1.0/sprint/300/issue HTTP/1.1"));
+    server.join();
+}
+
+#[test]
+fn start_assigns_moves_and_branches_with_default_steps() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash","status":{"name":"To Do"}}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"accountId":"account-id-999","displayName":"Cesar Ferreira"}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"transitions":[{"id":"21","name":"Start progress","to":{"name":"In Progress"}}]}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash","status":{"name":"In Progress"}}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"accountId":"account-id-999","displayName":"Cesar Ferreira"}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+    let repo = config.dir.join("repo");
+    let init = Command::new("git")
+        .args(["init", "-q", "-b", "main"])
+        .arg(&repo)
+        .output()
+        .expect("git should run");
+    assert!(init.status.success(), "git init failed");
+    let git_dir = repo.join(".git");
+    let start = || {
+        run_jit_with_env(
+            [
+                "--config-file",
+                config.path_str(),
+                "start",
+                "RW-1931",
+                "--output",
+                "text",
+            ],
+            &[
+                ("GIT_DIR", git_dir.to_str().expect("utf-8 path")),
+                ("GIT_WORK_TREE", repo.to_str().expect("utf-8 path")),
+            ],
+        )
+    };
+
+    let output = start();
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "RW-1931: assigned to Cesar Ferreira\nRW-1931: moved to In Progress\nCreated branch rw-1931-fix-login-crash\n"
+    );
+    let captured = collect_requests(&requests, 5);
+    assert!(
+        captured[0]
+            .starts_with("GET /rest/api/3/issue/RW-1931?fields=issuetype,status,summary HTTP/1.1")
+    );
+    assert!(captured[2].starts_with("PUT /rest/api/3/issue/RW-1931/assignee HTTP/1.1"));
+    assert!(captured[4].starts_with("POST /rest/api/3/issue/RW-1931/transitions HTTP/1.1"));
+    let head = fs::read_to_string(git_dir.join("HEAD")).expect("read HEAD");
+    assert_eq!(head.trim(), "ref: refs/heads/rw-1931-fix-login-crash");
+
+    // Starting it again leaves the status alone instead of failing to move it.
+    let again = start();
+    assert!(again.status.success(), "stderr: {}", stderr(&again));
+    assert!(
+        stdout(&again)
+            .starts_with("RW-1931: assigned to Cesar Ferreira\nRW-1931: moved to In Progress\n")
+    );
+    let captured = collect_requests(&requests, 3);
+    assert!(captured[2].starts_with("PUT /rest/api/3/issue/RW-1931/assignee HTTP/1.1"));
+    server.join();
+}
+
+#[test]
+fn hook_install_prefixes_commit_messages_and_uninstalls() {
+    let config = TempConfig::with_extra(
+        "http://127.0.0.1:9",
+        "[git]\ncommit_template = \"{KEY}: \"\n",
+    );
+    let repo = config.dir.join("repo");
+    let init = Command::new("git")
+        .args(["init", "-q", "-b", "feature/RW-1931-login"])
+        .arg(&repo)
+        .output()
+        .expect("git should run");
+    assert!(init.status.success(), "git init failed");
+    let git_dir = repo.join(".git");
+    let env = [("GIT_DIR", git_dir.to_str().expect("utf-8 path"))];
+    let hook = git_dir.join("hooks").join("prepare-commit-msg");
+
+    let output = run_jit_with_env(["hook", "install"], &env);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let script = fs::read_to_string(&hook).expect("hook written");
+    assert!(script.contains("jit hook prepare-commit-msg \"$@\""));
+
+    let message = config.dir.join("COMMIT_EDITMSG");
+    fs::write(&message, "Fix login crash\n").expect("write message");
+    let message_arg = message.to_str().expect("utf-8 path");
+    for _ in 0..2 {
+        let output = run_jit_with_env(
+            [
+                "--config-file",
+                config.path_str(),
+                "hook",
+                "prepare-commit-msg",
+                message_arg,
+                "message",
+            ],
+            &env,
+        );
+        assert!(output.status.success(), "stderr: {}", stderr(&output));
+    }
+    assert_eq!(
+        fs::read_to_string(&message).expect("read message"),
+        "RW-1931: Fix login crash\n"
+    );
+
+    let output = run_jit_with_env(["hook", "uninstall"], &env);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(!hook.exists());
+
+    fs::write(&hook, "#!/bin/sh\nexit 0\n").expect("write foreign hook");
+    let output = run_jit_with_env(["hook", "install"], &env);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--force"));
+}
+
+#[test]
+fn remind_shows_due_reminders_on_the_next_run() {
+    let config = TempConfig::new("http://127.0.0.1:9");
+    let data_dir = config.dir.join("data");
+    let env = [("JIT_DATA_DIR", data_dir.to_str().expect("utf-8 path"))];
+
+    for (note, due_in) in [("check rollout", "2d"), ("ping infra", "0s")] {
+        let output = run_jit_with_env(
+            [
+                "remind", "RW-1931", note, "--in", due_in, "--output", "json",
+            ],
+            &env,
+        );
+        assert!(output.status.success(), "stderr: {}", stderr(&output));
+    }
+
+    let output = run_jit_with_env(["remind", "--list", "--output", "json"], &env);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stderr(&output).contains("Reminder: RW-1931: ping infra"));
+    let pending: Value = serde_json::from_str(stdout(&output).trim()).expect("json output");
+    let pending = pending.as_array().expect("reminder list");
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0]["note"], "check rollout");
+    assert_eq!(pending[0]["id"], 1);
+
+    let output = run_jit_with_env(["remind", "--cancel", "1", "--output", "json"], &env);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(!stderr(&output).contains("Reminder:"));
+    let output = run_jit_with_env(["remind"], &env);
+    assert_eq!(stdout(&output), "No pending reminders.\n");
+}
+
+#[test]
+fn focus_is_shown_by_current() {
+    let issue = r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash","status":{"name":"In Progress"}}}"#;
+    let (server, requests) =
+        spawn_sequence_server(vec![("HTTP/1.1 200 OK", issue), ("HTTP/1.1 200 OK", issue)]);
+    let config = TempConfig::new(&server.base_url);
+    let data_dir = config.dir.join("data");
+    let env = [("JIT_DATA_DIR", data_dir.to_str().expect("utf-8 path"))];
+
+    let output = run_jit_with_env(
+        ["--config-file", config.path_str(), "focus", "rw-1931"],
+        &env,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "current",
+            "--output",
+            "text",
+        ],
+        &env,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output), "RW-1931: Fix login crash [In Progress]\n");
+    let captured = collect_requests(&requests, 2);
+    assert!(
+        captured[0].starts_with("GET /rest/api/3/issue/rw-1931?fields=status,summary HTTP/1.1")
+    );
+    assert!(
+        captured[1].starts_with("GET /rest/api/3/issue/RW-1931?fields=status,summary HTTP/1.1")
+    );
+    server.join();
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "focus",
+            "--clear",
+            "--output",
+            "json",
+        ],
+        &env,
+    );
+    let payload: Value = serde_json::from_str(stdout(&output).trim()).expect("json output");
+    assert_eq!(payload["cleared"], "RW-1931");
+}
+
+#[test]
+fn start_warns_when_over_the_wip_limit() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash","status":{"name":"To Do"}}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"2","key":"RW-7","fields":{"summary":"Cache"}},{"id":"3","key":"RW-8","fields":{"summary":"Docs"}}],"isLast":true}"#,
+        ),
+        ("HTTP/1.1 201 Created", r#"{"id":"100"}"#),
+    ]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "[start]\nsteps = [\"comment:Picking this up\"]\nwip_limit = 2\n",
+    );
+
+    let output = run_jit(["--config-file", config.path_str(), "start", "RW-1931"]);
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(
+        stderr(&output).contains(
+            "Warning: you already have 2 ticket(s) in progress (WIP limit 2): RW-7, RW-8"
+        )
+    );
+    let captured = collect_requests(&requests, 3);
+    let search: Value = search_params(&captured[1]);
+    assert!(
+        search["jql"]
+            .as_str()
+            .expect("jql")
+            .contains("statusCategory = \"In Progress\" AND key != RW-1931")
+    );
+    assert!(captured[2].starts_with("POST /rest/api/3/issue/RW-1931/comment HTTP/1.1"));
+    server.join();
+}
+
+#[test]
+fn config_set_get_and_list_with_env_overrides() {
+    let config = TempConfig::new("https://old.atlassian.net");
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "config",
+        "set",
+        "jira.base_url",
+        "https://new.atlassian.net",
+    ]);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "config",
+        "set",
+        "start.wip_limit",
+        "3",
+    ]);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let contents = fs::read_to_string(&config.path).expect("config should be written");
+    assert!(contents.contains("base_url = \"https://new.atlassian.net\""));
+    assert!(contents.contains("[start]\nwip_limit = 3\n"));
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "config",
+        "set",
+        "start.wip_limit",
+        "lots",
+    ]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Not saving start.wip_limit = lots"));
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "config",
+            "get",
+            "jira.base_url",
+        ],
+        &[("JIT_JIRA_BASE_URL", "https://env.atlassian.net")],
+    );
+    assert_eq!(stdout(&output), "https://env.atlassian.net\n");
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "config",
+            "list",
+            "--output",
+            "json",
+        ],
+        &[("JIT_JIRA_USER_EMAIL", "env@example.com")],
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let entries: Value = serde_json::from_str(stdout(&output).trim()).expect("json output");
+    let entry = |key: &str| {
+        entries
+            .as_array()
+            .expect("entries")
+            .iter()
+            .find(|entry| entry["key"] == key)
+            .cloned()
+            .expect("entry present")
+    };
+    assert_eq!(entry("jira.user_email")["value"], "env@example.com");
+    assert_eq!(entry("jira.user_email")["source"], "env");
+    assert_eq!(entry("jira.base_url")["source"], "file");
+    assert!(
+        !entry("jira.api_token")["value"]
+            .as_str()
+            .unwrap()
+            .contains("token")
+    );
+}
+
+#[test]
+fn statusline_and_prompt_print_the_branch_ticket_from_the_status_cache() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash","status":{"name":"In Review"}}}"#,
+    )]);
+    let config = TempConfig::with_extra(
+        &server.base_url,
+        "\n[profiles.other.jira]\nbase_url = \"http://127.0.0.1:9\"\n",
+    );
+    let repo = config.dir.join("repo");
+    let init = Command::new("git")
+        .args(["init", "-q", "-b", "feature/RW-1931-login"])
+        .arg(&repo)
+        .output()
+        .expect("git should run");
+    assert!(init.status.success(), "git init failed");
+    let git_dir = repo.join(".git");
+    let data_dir = config.dir.join("data");
+    let env = [
+        ("GIT_DIR", git_dir.to_str().expect("utf-8 path")),
+        ("JIT_DATA_DIR", data_dir.to_str().expect("utf-8 path")),
+    ];
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "statusline",
+            "--refresh",
+            "RW-1931",
+        ],
+        &env,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let captured = collect_requests(&requests, 1);
+    assert!(
+        captured[0].starts_with("GET /rest/api/3/issue/RW-1931?fields=status,summary HTTP/1.1")
+    );
+    server.join();
+
+    let output = run_jit_with_env(["--config-file", config.path_str(), "statusline"], &env);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output), "RW-1931 [In Review]\n");
+
+    // Another site's RW-1931 is a different ticket.
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "--profile",
+            "other",
+            "statusline",
+        ],
+        &env,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output), "RW-1931\n");
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "prompt",
+            "--format",
+            "powerlevel10k",
+        ],
+        &env,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output), "RW-1931 [In Review]\n");
+}
+
+#[test]
+fn profiles_are_chosen_by_flag_env_or_pasted_url() {
+    let issue = r#"{"id":"10001","key":"CL-7","fields":{"summary":"Client onboarding"}}"#;
+    let (server, requests) = spawn_sequence_server(vec![
+        ("HTTP/1.1 200 OK", issue),
+        ("HTTP/1.1 200 OK", issue),
+        ("HTTP/1.1 200 OK", issue),
+    ]);
+    let config = TempConfig::with_extra(
+        "http://127.0.0.1:9",
+        &format!(
+            "\n[profiles.client.jira]\nbase_url = \"{}\"\napi_token = \"client-token\"\n",
+            server.base_url
+        ),
+    );
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "--profile",
+        "client",
+        "--text",
+        "CL-7",
+    ]);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), "CL-7: Client onboarding");
+
+    let output = run_jit_with_env(
+        ["--config-file", config.path_str(), "--text", "CL-7"],
+        &[("JIT_PROFILE", "client")],
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let url = format!("{}/browse/CL-7", server.base_url);
+    let output = run_jit(["--config-file", config.path_str(), "--text", &url]);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), "CL-7: Client onboarding");
+
+    let captured = collect_requests(&requests, 3);
+    assert!(
+        captured
+            .iter()
+            .all(|request| request.starts_with("GET /rest/api/3/issue/CL-7?fields=summary"))
+    );
+    server.join();
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "--profile",
+        "home",
+        "CL-7",
+    ]);
+    assert_eq!(output.status.code(), Some(78));
+    assert!(stderr(&output).contains("Configured profiles: client"));
+}
+
+#[test]
+fn porcelain_output_has_a_versioned_tab_separated_layout() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash","status":{"name":"In Progress","statusCategory":{"key":"indeterminate"}},"issuetype":{"name":"Bug"},"assignee":{"displayName":"Ana Lima"},"labels":["auth","mobile"]}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash","status":{"name":"In Progress","statusCategory":{"key":"indeterminate"}}}},{"id":"2","key":"RW-7","fields":{"summary":"Cache\ttokens"}}],"isLast":true}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit(["--config-file", config.path_str(), "RW-1931", "--porcelain"]);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "# jit porcelain v1\n\
+             issue\tRW-1931\tIn Progress\tindeterminate\tFix login crash\n\
+             field\ttype\tBug\n\
+             field\tpriority\t\n\
+             field\tassignee\tAna Lima\n\
+             field\treporter\t\n\
+             field\tcreated\t\n\
+             field\tupdated\t\n\
+             field\tdue\t\n\
+             field\tlabels\tauth,mobile\n\
+             field\turl\t{}/browse/RW-1931\n",
+            server.base_url
+        )
+    );
+
+    let output = run_jit(["--config-file", config.path_str(), "--porcelain=v1"]);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "# jit porcelain v1\n\
+         issue\tRW-1931\tIn Progress\tindeterminate\tFix login crash\n\
+         issue\tRW-7\t\t\tCache\\ttokens\n"
+    );
+    collect_requests(&requests, 2);
+    server.join();
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "focus",
+        "--clear",
+        "--porcelain",
+    ]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--porcelain is supported by"));
+}
+
+#[test]
+fn token_missing_from_config_and_keyring_points_to_auth_login() {
+    let config = TempConfig::empty();
+    fs::write(
+        &config.path,
+        "[jira]\nbase_url = \"https://example.atlassian.net\"\nuser_email = \"user@example.com\"\n",
+    )
+    .expect("config should be written");
+
+    let output = run_jit_with_env(
+        ["--config-file", config.path_str(), "RW-1"],
+        &[("JIT_AUTH_NO_KEYRING", "1")],
+    );
+    assert_eq!(output.status.code(), Some(78));
+    assert!(
+        stderr(&output).contains("no token in the OS keyring for user@example.com"),
+        "stderr: {}",
+        stderr(&output)
+    );
+    assert!(stderr(&output).contains("jit auth login"));
+
+    let (server, _requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"accountId":"account-id-123","displayName":"Cesar Ferreira"}"#,
+    )]);
+    let output = run_jit_with_stdin(
+        [
+            "--config-file",
+            config.path_str(),
+            "auth",
+            "login",
+            "--plaintext",
+        ],
+        &format!("y\n{}\nuser@example.com\ntoken-123\n", server.base_url),
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).contains("Token:    (OS keyring)"));
+    let contents = fs::read_to_string(&config.path).expect("config should be written");
+    assert!(contents.contains("api_token = \"token-123\""));
+    server.join();
+}
+
+#[test]
+fn serve_answers_json_rpc_requests_over_stdio() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash","status":{"name":"To Do"}}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"transitions":[{"id":"21","name":"Start work","to":{"name":"In Progress"}}]}"#,
+        ),
+        ("HTTP/1.1 204 No Content", ""),
+        ("HTTP/1.1 201 Created", r#"{"id":"100"}"#),
+        (
+            "HTTP/1.1 404 Not Found",
+            r#"{"errorMessages":["Issue does not exist or you do not have permission to see it."]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit_with_stdin(
+        ["--config-file", config.path_str(), "serve", "--stdio"],
+        concat!(
+            "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"getIssue\",\"params\":{\"key\":\"RW-1931\"}}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"transitionIssue\",\"params\":{\"key\":\"RW-1931\",\"status\":\"in progress\"}}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":3,\"method\":\"addComment\",\"params\":{\"key\":\"RW-1931\",\"body\":\"On it\"}}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":4,\"method\":\"getIssue\",\"params\":{\"key\":\"RW-9\"}}\n",
+        ),
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let responses: Vec<Value> = stdout(&output)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("one JSON response per line"))
+        .collect();
+    assert_eq!(responses.len(), 4);
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["result"]["ticket"], "RW-1931");
+    assert_eq!(responses[0]["result"]["summary"], "Fix login crash");
+    assert_eq!(
+        responses[1]["result"],
+        serde_json::json!({"ticket": "RW-1931", "transition": "Start work", "status": "In Progress"})
+    );
+    assert_eq!(responses[2]["result"]["id"], "100");
+    assert_eq!(responses[3]["error"]["code"], -32000);
+    assert_eq!(responses[3]["error"]["data"]["kind"], "not_found");
+
+    let captured = collect_requests(&requests, 5);
+    assert!(captured[0].starts_with("GET /rest/api/3/issue/RW-1931?fields="));
+    assert!(captured[2].starts_with("POST /rest/api/3/issue/RW-1931/transitions"));
+    assert!(request_body(&captured[2]).contains("\"21\""));
+    assert!(captured[3].starts_with("POST /rest/api/3/issue/RW-1931/comment"));
+    server.join();
+}
+
+#[test]
+fn serve_search_limit_zero_fetches_every_page_like_the_cli() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"1","key":"RW-1","fields":{"summary":"First","status":{"name":"To Do"}}}],"nextPageToken":"page-2"}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"issues":[{"id":"2","key":"RW-2","fields":{"summary":"Second","status":{"name":"Done"}}}]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit_with_stdin(
+        ["--config-file", config.path_str(), "serve", "--stdio"],
+        "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"searchIssues\",\"params\":{\"jql\":\"project = RW\",\"limit\":0}}\n",
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let response: Value = serde_json::from_str(stdout(&output).trim()).expect("json response");
+    assert_eq!(response["result"][0]["ticket"], "RW-1");
+    assert_eq!(response["result"][1]["ticket"], "RW-2");
+    let captured = collect_requests(&requests, 2);
+    assert_eq!(search_params(&captured[0])["maxResults"], 100);
+    assert_eq!(search_params(&captured[1])["nextPageToken"], "page-2");
+    server.join();
+}
+
+#[test]
+fn personal_access_token_uses_bearer_auth_and_falls_back_to_api_v2() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 404 Not Found",
+            r#"{"message":"null for uri: /rest/api/3/issue/RW-1","status-code":404}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"version":"9.12.0","deploymentType":"DataCenter"}"#,
+        ),
+        ("HTTP/1.1 200 OK", r#"{"values":[]}"#),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"RW-1","fields":{"summary":"Fix login crash","status":{"name":"To Do"},"description":"Steps to reproduce"}}"#,
+        ),
+        ("HTTP/1.1 201 Created", r#"{"id":"100"}"#),
+    ]);
+    let config = TempConfig::empty();
+    fs::write(
+        &config.path,
+        format!(
+            "{STORY_POINTS_FIELD_LINE}[jira]\nbase_url = \"{}\"\napi_token = \"pat-123\"\nauth = \"pat\"\n{SPRINT_FIELD_LINE}",
+            server.base_url
+        ),
+    )
+    .expect("config should be written");
+    let data_dir = config.data_dir();
+    let env = [("JIT_DATA_DIR", data_dir.as_str())];
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "--show",
+            "--json",
+            "RW-1",
+        ],
+        &env,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value = serde_json::from_str(stdout(&output).trim()).expect("json output");
+    assert_eq!(payload["description"], "Steps to reproduce");
+
+    // The detected v2-only instance is remembered, so later runs go straight to v2.
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "comment",
+            "RW-1",
+            "On it",
+        ],
+        &env,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let captured = collect_requests(&requests, 5);
+    assert!(captured[0].starts_with("GET /rest/api/3/issue/RW-1?"));
+    assert!(captured[0].contains("authorization: Bearer pat-123"));
+    assert!(captured[1].starts_with("GET /rest/api/2/serverInfo"));
+    assert!(captured[3].starts_with("GET /rest/api/2/issue/RW-1?"));
+    assert!(captured[4].starts_with("POST /rest/api/2/issue/RW-1/comment"));
+    assert_eq!(request_body(&captured[4]), r#"{"body":"On it"}"#);
+    server.join();
+}
+
+#[test]
+fn vscode_links_point_file_references_at_the_repository() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash","description":"Panics in src/app.rs:12 and lib/missing.rs:3"}}"#,
+    )]);
+    let config = TempConfig::new(&server.base_url);
+    let repo = config.dir.join("repo");
+    let init = Command::new("git")
+        .args(["init", "-q"])
+        .arg(&repo)
+        .output()
+        .expect("git should run");
+    assert!(init.status.success(), "git init failed");
+    fs::create_dir_all(repo.join("src")).expect("create src");
+    fs::write(repo.join("src").join("app.rs"), "fn main() {}\n").expect("write file");
+    let git_dir = repo.join(".git");
+    let env = [
+        ("GIT_DIR", git_dir.to_str().expect("utf-8 path")),
+        ("GIT_WORK_TREE", repo.to_str().expect("utf-8 path")),
+    ];
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "--show",
+            "--vscode-links",
+            "RW-1931",
+        ],
+        &env,
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let app = repo
+        .join("src")
+        .join("app.rs")
+        .canonicalize()
+        .expect("canonical path");
+    assert!(stdout(&output).contains(&format!(
+        "Panics in src/app.rs:12 (vscode://file{}:12) and lib/missing.rs:3\n",
+        app.display()
+    )));
+    collect_requests(&requests, 1);
+    server.join();
+}
+
+#[test]
+fn auth_test_reports_the_user_or_diagnoses_the_failure() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"accountId":"account-id-123","displayName":"Cesar Ferreira"}"#,
+        ),
+        (
+            "HTTP/1.1 401 Unauthorized",
+            r#"{"errorMessages":["Client must be authenticated to access this resource."]}"#,
+        ),
+    ]);
+    let config = TempConfig::new(&server.base_url);
+
+    let output = run_jit([
+        "--config-file",
+        config.path_str(),
+        "--output",
+        "text",
+        "auth",
+        "test",
+    ]);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "Authenticated as Cesar Ferreira (account-id-123) on {}\n",
+            server.base_url
+        )
+    );
+
+    let output = run_jit(["--config-file", config.path_str(), "auth", "test"]);
+    assert_eq!(output.status.code(), Some(77));
+    assert!(stderr(&output).contains(
+        "Jira rejected the API token for user@example.com. It is wrong, expired, or revoked"
+    ));
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[0].starts_with("GET /rest/api/3/myself"));
+    server.join();
+
+    let unreachable = TempConfig::new("http://127.0.0.1:9");
+    let output = run_jit([
+        "--config-file",
+        unreachable.path_str(),
+        "auth",
+        "test",
+        "--output",
+        "json",
+    ]);
+    assert_eq!(output.status.code(), Some(69));
+    let error: Value = serde_json::from_str(stderr(&output).trim()).expect("json error");
+    assert_eq!(error["error"]["kind"], "network");
+    assert!(
+        error["error"]["message"]
+            .as_str()
+            .expect("message")
+            .starts_with("Could not reach http://127.0.0.1:9.")
+    );
+}
+
+#[test]
+fn trace_lists_frames_in_the_repository_and_opens_the_top_one() {
+    let issue = r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash","description":"java.lang.IllegalStateException: boom\n\tat com.acme.auth.Login.check(Login.java:42)\n\tat org.junit.Runner.run(Runner.java:9)","comment":{"comments":[{"body":"Traceback (most recent call last):\n  File \"/srv/app/jobs/run.py\", line 10, in <module>\n  File \"/srv/app/jobs/sync.py\", line 55, in main\nValueError: bad"}]}}}"#;
+    let (server, requests) =
+        spawn_sequence_server(vec![("HTTP/1.1 200 OK", issue), ("HTTP/1.1 200 OK", issue)]);
+    let config = TempConfig::new(&server.base_url);
+    let repo = config.dir.join("repo");
+    let init = Command::new("git")
+        .args(["init", "-q"])
+        .arg(&repo)
+        .output()
+        .expect("git should run");
+    assert!(init.status.success(), "git init failed");
+    let java = repo.join("src/main/java/com/acme/auth");
+    fs::create_dir_all(&java).expect("create java dir");
+    fs::write(java.join("Login.java"), "class Login {}\n").expect("write file");
+    fs::create_dir_all(repo.join("jobs")).expect("create jobs");
+    fs::write(repo.join("jobs").join("sync.py"), "").expect("write file");
+    fs::write(repo.join("jobs").join("run.py"), "").expect("write file");
+    let add = Command::new("git")
+        .arg("-C")
+        .arg(&repo)
+        .args(["add", "."])
+        .output()
+        .expect("git should run");
+    assert!(add.status.success(), "git add failed");
+    let git_dir = repo.join(".git");
+    let env = [
+        ("GIT_DIR", git_dir.to_str().expect("utf-8 path")),
+        ("GIT_WORK_TREE", repo.to_str().expect("utf-8 path")),
+        ("VISUAL", "echo"),
+    ];
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "trace",
+            "RW-1931",
+            "--output",
+            "json",
+        ],
+        &env,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let payload: Value = serde_json::from_str(&stdout(&output)).expect("json output");
+    let locations: Vec<(String, u64, String)> = payload["frames"]
+        .as_array()
+        .expect("frames")
+        .iter()
+        .map(|frame| {
+            (
+                frame["file"].as_str().unwrap_or_default().to_string(),
+                frame["line"].as_u64().unwrap_or_default(),
+                frame["source"].as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        locations,
+        [
+            (
+                "src/main/java/com/acme/auth/Login.java".to_string(),
+                42,
+                "description".to_string()
+            ),
+            ("jobs/sync.py".to_string(), 55, "comment 1".to_string()),
+            ("jobs/run.py".to_string(), 10, "comment 1".to_string()),
+        ]
+    );
+    assert_eq!(payload["unresolved"], 1);
+
+    let output = run_jit_with_env(
+        [
+            "--config-file",
+            config.path_str(),
+            "trace",
+            "RW-1931",
+            "--open",
+        ],
+        &env,
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let opened = stdout(&output);
+    assert!(opened.starts_with("+42 "), "stdout: {}", opened);
+    assert!(
+        opened
+            .trim_end()
+            .ends_with("src/main/java/com/acme/auth/Login.java")
+    );
+    collect_requests(&requests, 2);
+    server.join();
+}
+
+#[test]
+fn config_values_expand_env_vars_and_run_token_commands() {
+    let (server, requests) = spawn_sequence_server(vec![(
+        "HTTP/1.1 200 OK",
+        r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash"}}"#,
+    )]);
+    let config = TempConfig::empty();
+    fs::write(
+        &config.path,
+        format!(
+            "{STORY_POINTS_FIELD_LINE}[jira]\nbase_url = \"${{JIT_E2E_SITE}}\"\nauth = \"pat\"\ntoken_cmd = \"printf 'pat-%s\\\\n' \\\"$JIT_E2E_VAULT\\\"\"\n{SPRINT_FIELD_LINE}"
+        ),
+    )
+    .expect("config should be written");
+
+    let output = run_jit_with_env(
+        ["--config-file", config.path_str(), "--show", "RW-1931"],
+        &[
+            ("JIT_E2E_SITE", server.base_url.as_str()),
+            ("JIT_E2E_VAULT", "from-vault"),
+        ],
+    );
+
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let captured = collect_requests(&requests, 1);
+    assert!(captured[0].contains("authorization: Bearer pat-from-vault"));
+    server.join();
+
+    let output = run_jit_with_env(
+        ["--config-file", config.path_str(), "--show", "RW-1931"],
+        &[("JIT_E2E_VAULT", "from-vault")],
+    );
+    assert_eq!(output.status.code(), Some(78));
+    assert!(
+        stderr(&output).contains(
+            "jira.base_url refers to ${JIT_E2E_SITE}, which is not set in the environment."
+        ),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn auth_source_reads_the_token_from_pass_or_the_environment() {
+    let (server, requests) = spawn_sequence_server(vec![
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash"}}"#,
+        ),
+        (
+            "HTTP/1.1 200 OK",
+            r#"{"id":"1","key":"RW-1931","fields":{"summary":"Fix login crash"}}"#,
+        ),
+    ]);
+    let config = TempConfig::empty();
+    let bin = config.dir.join("bin");
+    fs::create_dir_all(&bin).expect("create bin");
+    let pass = bin.join("pass");
+    fs::write(
+        &pass,
+        "#!/bin/sh\n[ \"$1 $2\" = \"show work/jira\" ] || exit 1\nprintf 'pat-from-pass\\nurl: https://example.com\\n'\n",
+    )
+    .expect("write fake pass");
+    let mut permissions = fs::metadata(&pass).expect("metadata").permissions();
+    std::os::unix::fs::PermissionsExt::set_mode(&mut permissions, 0o755);
+    fs::set_permissions(&pass, permissions).expect("make fake pass executable");
+    let path = format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    fs::write(
+        &config.path,
+        format!(
+            "{STORY_POINTS_FIELD_LINE}[jira]\nbase_url = \"{}\"\nauth = \"pat\"\n{SPRINT_FIELD_LINE}\n[auth]\nsource = \"pass\"\nitem = \"work/jira\"\n",
+            server.base_url
+        ),
+    )
+    .expect("config should be written");
+
+    let output = run_jit_with_env(
+        ["--config-file", config.path_str(), "--show", "RW-1931"],
+        &[("PATH", path.as_str())],
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let output = run_jit_with_env(
+        ["--config-file", config.path_str(), "--show", "RW-1931"],
+        &[
+            ("JIT_AUTH_SOURCE", "env"),
+            ("JIRA_API_TOKEN", "pat-from-env"),
+        ],
+    );
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    let captured = collect_requests(&requests, 2);
+    assert!(captured[0].contains("authorization: Bearer pat-from-pass\r\n"));
+    assert!(captured[1].contains("authorization: Bearer pat-from-env\r\n"));
+    server.join();
+
+    let output = run_jit_with_env(
+        ["--config-file", config.path_str(), "--show", "RW-1931"],
+        &[("JIT_AUTH_ITEM", "personal/jira"), ("PATH", path.as_str())],
+    );
+    assert_eq!(output.status.code(), Some(78));
+    assert!(
+        stderr(&output).contains("`pass show personal/jira` failed"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}